@@ -0,0 +1,5 @@
+CREATE TYPE reject_reason AS ENUM ('amount_too_high', 'prefer_litigation', 'dispute_debt', 'other');
+
+ALTER TABLE settlements
+    ADD COLUMN rejection_reason reject_reason,
+    ADD COLUMN rejection_note TEXT;
@@ -0,0 +1,12 @@
+CREATE TYPE installment_status AS ENUM ('pending', 'paid', 'missed');
+
+CREATE TABLE settlement_installments (
+    id UUID PRIMARY KEY,
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    sequence INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    due_date TIMESTAMPTZ NOT NULL,
+    status installment_status NOT NULL DEFAULT 'pending',
+    paid_at TIMESTAMPTZ,
+    UNIQUE (settlement_id, sequence)
+);
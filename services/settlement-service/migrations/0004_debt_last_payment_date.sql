@@ -0,0 +1 @@
+ALTER TABLE debts ADD COLUMN last_payment_date TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+CREATE TYPE negotiation_party AS ENUM ('creditor', 'debtor');
+CREATE TYPE negotiation_action AS ENUM ('accept', 'counter', 'hold');
+
+CREATE TABLE negotiation_rounds (
+    id UUID PRIMARY KEY,
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    round_number INTEGER NOT NULL,
+    from_party negotiation_party NOT NULL,
+    amount NUMERIC NOT NULL,
+    decision negotiation_action NOT NULL,
+    counter_amount NUMERIC,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (settlement_id, round_number)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE settlements
+    ADD COLUMN accepted_at TIMESTAMPTZ,
+    ADD COLUMN rescinded_at TIMESTAMPTZ;
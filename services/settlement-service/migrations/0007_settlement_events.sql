@@ -0,0 +1,14 @@
+CREATE TYPE settlement_event_type AS ENUM (
+    'created', 'negotiated', 'accepted', 'rejected', 'rescinded', 'submitted', 'completed', 'failed'
+);
+
+CREATE TABLE settlement_events (
+    id BIGSERIAL PRIMARY KEY,
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    event_type settlement_event_type NOT NULL,
+    actor TEXT NOT NULL,
+    metadata JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX settlement_events_settlement_id_idx ON settlement_events (settlement_id, id);
@@ -0,0 +1,6 @@
+-- Ed25519 public key (hex) each user signs settlement acceptances with.
+CREATE TABLE user_signing_keys (
+    user_id UUID PRIMARY KEY,
+    public_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+ALTER TABLE settlements
+    ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0;
+
+ALTER TYPE settlement_event_type ADD VALUE 'retried';
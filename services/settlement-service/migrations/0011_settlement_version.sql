@@ -0,0 +1,3 @@
+-- Optimistic concurrency: every update must name the version it read and bumps it.
+ALTER TABLE settlements
+    ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Which model produced a proposal and a hash of exactly what it was asked.
+ALTER TABLE settlements
+    ADD COLUMN model_version TEXT,
+    ADD COLUMN prompt_hash TEXT;
@@ -0,0 +1,13 @@
+CREATE TYPE debt_verification_status AS ENUM ('unverified', 'requested', 'verified', 'disputed');
+
+ALTER TABLE debts
+    ADD COLUMN verification_status debt_verification_status NOT NULL DEFAULT 'unverified';
+
+-- Every FDCPA §809 validation request and outcome, in order.
+CREATE TABLE debt_verifications (
+    id UUID PRIMARY KEY,
+    debt_id UUID NOT NULL REFERENCES debts(id),
+    status debt_verification_status NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
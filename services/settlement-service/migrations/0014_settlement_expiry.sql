@@ -0,0 +1,21 @@
+ALTER TYPE settlement_status ADD VALUE 'expired';
+ALTER TYPE settlement_event_type ADD VALUE 'expired';
+
+ALTER TABLE settlements
+    ADD COLUMN expires_at TIMESTAMPTZ;
+
+UPDATE settlements SET expires_at = proposed_at + INTERVAL '14 days';
+
+ALTER TABLE settlements
+    ALTER COLUMN expires_at SET NOT NULL;
+
+-- The expiry sweep only ever looks at open proposals.
+CREATE INDEX settlements_open_expires_at_idx
+    ON settlements (expires_at)
+    WHERE status IN ('proposed', 'negotiating');
+
+-- Per-creditor overrides; creditors without a row get SETTLEMENT_EXPIRY_DAYS.
+CREATE TABLE creditor_settings (
+    creditor_id UUID PRIMARY KEY,
+    proposal_expiry_days INTEGER NOT NULL CHECK (proposal_expiry_days > 0)
+);
@@ -0,0 +1,11 @@
+-- Every leverage analysis computed for a proposal, for charting a creditor's
+-- leverage over time.
+CREATE TABLE leverage_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    creditor_id UUID NOT NULL,
+    settlement_id UUID REFERENCES settlements(id),
+    analysis JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX leverage_snapshots_creditor_id_idx ON leverage_snapshots (creditor_id, created_at);
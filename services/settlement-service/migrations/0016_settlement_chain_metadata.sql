@@ -0,0 +1,5 @@
+-- What the payment transaction carries on-chain: the metadata label and the
+-- hash of the settlement terms it commits to.
+ALTER TABLE settlements
+    ADD COLUMN metadata_label BIGINT,
+    ADD COLUMN terms_hash TEXT;
@@ -0,0 +1,5 @@
+-- Settlements are never removed; deleting one only hides it from its user.
+ALTER TABLE settlements
+    ADD COLUMN deleted_at TIMESTAMPTZ;
+
+ALTER TYPE settlement_event_type ADD VALUE 'deleted';
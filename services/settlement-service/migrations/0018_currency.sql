@@ -0,0 +1,6 @@
+-- ISO 4217 code of the currency every amount on the row is in.
+ALTER TABLE debts
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD' CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE settlements
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD' CHECK (currency ~ '^[A-Z]{3}$');
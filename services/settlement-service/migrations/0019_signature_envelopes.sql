@@ -0,0 +1,18 @@
+-- Settlement terms sent to a document-signing provider for the user to sign.
+CREATE TYPE envelope_status AS ENUM ('sent', 'completed', 'declined', 'voided');
+
+CREATE TABLE signature_envelopes (
+    envelope_id TEXT PRIMARY KEY,
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    provider TEXT NOT NULL,
+    -- SHA-256 of the acceptance terms the envelope was created from.
+    terms_hash TEXT NOT NULL,
+    status envelope_status NOT NULL DEFAULT 'sent',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX signature_envelopes_settlement_id_idx ON signature_envelopes (settlement_id);
+
+ALTER TYPE settlement_event_type ADD VALUE 'signature_requested';
+ALTER TYPE settlement_event_type ADD VALUE 'signed';
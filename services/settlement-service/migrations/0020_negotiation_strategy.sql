@@ -0,0 +1,5 @@
+-- How hard the engine pushes when it proposes and counters on a settlement.
+CREATE TYPE negotiation_strategy AS ENUM ('conservative', 'balanced', 'aggressive');
+
+ALTER TABLE settlements
+    ADD COLUMN strategy negotiation_strategy NOT NULL DEFAULT 'balanced';
@@ -0,0 +1,34 @@
+-- Human-readable references like DMC-2024-000123, numbered per calendar year
+-- (UTC) of `proposed_at`. The counter row is bumped in the same statement as
+-- the settlement insert, so concurrent inserts serialize on it and a rolled
+-- back insert gives its number back.
+CREATE TABLE settlement_reference_counters (
+    year INT PRIMARY KEY,
+    last_value BIGINT NOT NULL
+);
+
+ALTER TABLE settlements
+    ADD COLUMN reference_number TEXT;
+
+WITH numbered AS (
+    SELECT id,
+           EXTRACT(YEAR FROM proposed_at AT TIME ZONE 'UTC')::INT AS year,
+           ROW_NUMBER() OVER (
+               PARTITION BY EXTRACT(YEAR FROM proposed_at AT TIME ZONE 'UTC')
+               ORDER BY proposed_at, id
+           ) AS seq
+    FROM settlements
+)
+UPDATE settlements
+SET reference_number = 'DMC-' || numbered.year || '-' || lpad(numbered.seq::TEXT, GREATEST(6, length(numbered.seq::TEXT)), '0')
+FROM numbered
+WHERE settlements.id = numbered.id;
+
+INSERT INTO settlement_reference_counters (year, last_value)
+SELECT EXTRACT(YEAR FROM proposed_at AT TIME ZONE 'UTC')::INT, COUNT(*)
+FROM settlements
+GROUP BY 1;
+
+ALTER TABLE settlements
+    ALTER COLUMN reference_number SET NOT NULL,
+    ADD CONSTRAINT settlements_reference_number_key UNIQUE (reference_number);
@@ -0,0 +1,15 @@
+-- Artifacts backing a violation: call recordings, letter scans and the like.
+-- The files themselves live in object storage under `storage_key`.
+CREATE TYPE evidence_type AS ENUM ('call_recording', 'voicemail', 'letter_scan', 'screenshot', 'other');
+
+CREATE TABLE violation_evidence (
+    id UUID PRIMARY KEY,
+    violation_id UUID NOT NULL REFERENCES violations(id),
+    evidence_type evidence_type NOT NULL,
+    -- Hex SHA-256 of the file, declared when the evidence is attached.
+    sha256 TEXT NOT NULL CHECK (sha256 ~ '^[0-9a-f]{64}$'),
+    content_type TEXT NOT NULL,
+    storage_key TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (violation_id, sha256)
+);
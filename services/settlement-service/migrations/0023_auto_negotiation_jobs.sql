@@ -0,0 +1,26 @@
+-- Auto-negotiation work queued by external triggers such as the SWORD
+-- protocol. A trigger event enqueues at most one job per user and creditor.
+CREATE TYPE auto_negotiation_status AS ENUM ('pending', 'running', 'succeeded', 'failed');
+
+CREATE TABLE auto_negotiation_jobs (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    creditor_id UUID NOT NULL,
+    trigger TEXT NOT NULL,
+    -- The triggering system's id for the event; redeliveries reuse it.
+    event_id TEXT NOT NULL,
+    violations UUID[] NOT NULL,
+    jurisdiction TEXT NOT NULL,
+    strategy negotiation_strategy NOT NULL DEFAULT 'balanced',
+    status auto_negotiation_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    settlement_id UUID REFERENCES settlements(id),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    UNIQUE (user_id, creditor_id, event_id)
+);
+
+CREATE INDEX auto_negotiation_jobs_pending_idx ON auto_negotiation_jobs (created_at)
+    WHERE status IN ('pending', 'running');
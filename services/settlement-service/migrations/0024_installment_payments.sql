@@ -0,0 +1,2 @@
+-- Installment payments are audited; the platform fee accrues with them.
+ALTER TYPE settlement_event_type ADD VALUE 'installment_paid';
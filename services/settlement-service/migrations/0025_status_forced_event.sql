@@ -0,0 +1,2 @@
+-- Support overrides of settlement status are audited.
+ALTER TYPE settlement_event_type ADD VALUE 'status_forced';
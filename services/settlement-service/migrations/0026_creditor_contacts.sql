@@ -0,0 +1,10 @@
+-- How to reach each creditor with proposals and status changes.
+CREATE TYPE contact_channel AS ENUM ('email', 'mail', 'webhook');
+
+CREATE TABLE creditor_contacts (
+    creditor_id UUID PRIMARY KEY,
+    email TEXT,
+    mailing_address TEXT,
+    preferred_channel contact_channel NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
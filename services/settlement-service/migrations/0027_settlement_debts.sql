@@ -0,0 +1,10 @@
+-- Component debts of a bundled settlement, with each debt's balance when the
+-- bundle was proposed. `settlements.debt_id` is the first of them.
+CREATE TABLE settlement_debts (
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    debt_id UUID NOT NULL REFERENCES debts(id),
+    original_amount NUMERIC NOT NULL,
+    PRIMARY KEY (settlement_id, debt_id)
+);
+
+CREATE INDEX settlement_debts_debt_id_idx ON settlement_debts (debt_id);
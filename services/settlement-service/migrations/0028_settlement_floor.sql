@@ -0,0 +1,6 @@
+-- The least a creditor will ever settle for, as a percentage of the balance.
+-- A creditor may now have settings without an expiry override.
+ALTER TABLE creditor_settings
+    ALTER COLUMN proposal_expiry_days DROP NOT NULL,
+    ADD COLUMN settlement_floor_percentage DOUBLE PRECISION
+        CHECK (settlement_floor_percentage > 0 AND settlement_floor_percentage <= 100);
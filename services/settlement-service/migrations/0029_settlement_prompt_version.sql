@@ -0,0 +1,3 @@
+-- Which version of the AI prompt templates a proposal was made with.
+ALTER TABLE settlements
+    ADD COLUMN prompt_version TEXT;
@@ -0,0 +1,4 @@
+-- Completed settlements whose transaction reconciliation could not find
+-- on-chain wait here for support.
+ALTER TYPE settlement_status ADD VALUE 'needs_review';
+ALTER TYPE settlement_event_type ADD VALUE 'flagged_for_review';
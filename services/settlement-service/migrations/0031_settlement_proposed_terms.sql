@@ -0,0 +1,6 @@
+-- The terms as proposed, kept when a settlement is accepted at a different
+-- amount; settled_amount, saved_amount and platform_fee then hold what was
+-- agreed.
+ALTER TABLE settlements
+    ADD COLUMN proposed_amount NUMERIC,
+    ADD COLUMN proposed_platform_fee NUMERIC;
@@ -0,0 +1,5 @@
+-- The law each violation falls under. Everything recorded before this was
+-- FDCPA conduct.
+CREATE TYPE violation_statute AS ENUM ('fdcpa', 'tcpa', 'fcra', 'state_udap');
+
+ALTER TABLE violations ADD COLUMN statute violation_statute NOT NULL DEFAULT 'fdcpa';
@@ -0,0 +1,4 @@
+-- Who made the proposal. Creditors can now offer terms themselves; every
+-- earlier settlement was proposed on the debtor's behalf.
+ALTER TABLE settlements
+    ADD COLUMN proposed_by negotiation_party NOT NULL DEFAULT 'debtor';
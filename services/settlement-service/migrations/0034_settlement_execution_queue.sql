@@ -0,0 +1,6 @@
+-- Executions attempted while the Cardano node's circuit breaker was open wait
+-- here until it closes.
+ALTER TABLE settlements ADD COLUMN execution_queued_at TIMESTAMPTZ;
+CREATE INDEX settlements_execution_queued_at_idx
+    ON settlements (execution_queued_at) WHERE execution_queued_at IS NOT NULL;
+ALTER TYPE settlement_event_type ADD VALUE 'execution_queued';
@@ -0,0 +1,3 @@
+-- A proposal's terms were recomputed after its violations changed; metadata
+-- carries the old and new amounts.
+ALTER TYPE settlement_event_type ADD VALUE 'recomputed';
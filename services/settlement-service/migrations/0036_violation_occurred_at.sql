@@ -0,0 +1,8 @@
+-- When the conduct happened, which limitations periods run from. It can be
+-- well before the violation was recorded; for those recorded before this,
+-- the recording time is the best estimate there is.
+ALTER TABLE violations ADD COLUMN occurred_at TIMESTAMPTZ;
+UPDATE violations SET occurred_at = created_at;
+ALTER TABLE violations
+    ALTER COLUMN occurred_at SET NOT NULL,
+    ALTER COLUMN occurred_at SET DEFAULT now();
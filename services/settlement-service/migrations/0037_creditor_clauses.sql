@@ -0,0 +1,33 @@
+-- Boilerplate clauses a creditor has every settlement agreement carry, in
+-- order. A version is never edited: rewording a clause adds a version and
+-- retires the one it replaces, so an agreement keeps naming what was agreed.
+CREATE TABLE creditor_clauses (
+    creditor_id UUID NOT NULL,
+    clause_key TEXT NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    required BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retired_at TIMESTAMPTZ,
+    PRIMARY KEY (creditor_id, clause_key, version)
+);
+CREATE UNIQUE INDEX creditor_clauses_current_idx
+    ON creditor_clauses (creditor_id, clause_key) WHERE retired_at IS NULL;
+
+-- The clause versions the debtor agreed to when accepting a settlement.
+CREATE TABLE settlement_clauses (
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    creditor_id UUID NOT NULL,
+    clause_key TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    agreed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (settlement_id, clause_key),
+    FOREIGN KEY (creditor_id, clause_key, version) REFERENCES creditor_clauses
+);
+
+-- Hash of those versions, part of the terms hashed into the on-chain
+-- metadata. Settlements accepted without clauses have none.
+ALTER TABLE settlements ADD COLUMN clauses_hash TEXT;
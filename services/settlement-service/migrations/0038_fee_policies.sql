@@ -0,0 +1,13 @@
+-- The fee policy support has granted a user, e.g. a waiver for a legal aid
+-- case. Users without a row pay the standard fee.
+CREATE TABLE user_fee_policies (
+    user_id UUID PRIMARY KEY,
+    policy JSONB NOT NULL,
+    set_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The policy each settlement's platform fee was charged under, kept so an
+-- accepted amount or a recomputation is charged the same way.
+ALTER TABLE settlements ADD COLUMN fee_policy JSONB NOT NULL DEFAULT '{"kind": "standard"}';
+ALTER TYPE settlement_event_type ADD VALUE 'fee_policy_applied';
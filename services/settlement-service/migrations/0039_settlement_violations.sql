@@ -0,0 +1,13 @@
+-- The violations each proposal cited, so the settlements relying on a
+-- violation can be found. `contribution` is the violation's share of the
+-- proposal's leverage score.
+CREATE TYPE violation_use AS ENUM ('counted', 'repeat', 'expired');
+
+CREATE TABLE settlement_violations (
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    violation_id UUID NOT NULL REFERENCES violations(id),
+    violation_use violation_use NOT NULL,
+    contribution DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (settlement_id, violation_id)
+);
+CREATE INDEX settlement_violations_violation_idx ON settlement_violations (violation_id);
@@ -0,0 +1,10 @@
+-- Each deadline reminder sent for a negotiation, by how many hours before
+-- `expires_at` it was due. The key lets only one sweep send it.
+CREATE TABLE settlement_reminders (
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    threshold_hours INTEGER NOT NULL CHECK (threshold_hours > 0),
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (settlement_id, threshold_hours)
+);
+
+ALTER TYPE settlement_event_type ADD VALUE 'deadline_reminder';
@@ -0,0 +1,4 @@
+-- The most the user pre-authorized paying: a creditor counter at or below it
+-- is accepted on their behalf. Without one every counter waits for them.
+ALTER TABLE settlements ADD COLUMN auto_accept_below NUMERIC;
+ALTER TYPE settlement_event_type ADD VALUE 'auto_accepted';
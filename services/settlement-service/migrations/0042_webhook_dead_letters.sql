@@ -0,0 +1,16 @@
+-- Deliveries that failed every attempt, with the body that was sent, so none
+-- is lost while its target is down; an admin replays them once it is back.
+CREATE TABLE webhook_dead_letters (
+    id UUID PRIMARY KEY,
+    delivery_id UUID NOT NULL UNIQUE REFERENCES webhook_deliveries(id),
+    webhook_id UUID NOT NULL REFERENCES webhooks(id),
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    url TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    redelivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX webhook_dead_letters_open_idx ON webhook_dead_letters (created_at) WHERE redelivered_at IS NULL;
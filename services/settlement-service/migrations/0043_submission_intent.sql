@@ -0,0 +1,6 @@
+-- Written just before a payment is submitted and sent along as the node
+-- gateway's idempotency key, so a submission whose hash was never recorded,
+-- say because the service crashed, can be looked up after a restart.
+ALTER TABLE settlements ADD COLUMN submission_intent UUID;
+
+ALTER TYPE settlement_event_type ADD VALUE 'submission_recovered';
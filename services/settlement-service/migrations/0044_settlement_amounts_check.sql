@@ -0,0 +1,9 @@
+-- The engine refuses terms that don't add up; this keeps anything else from
+-- storing them. NOT VALID so that rows written before it, such as one that
+-- saved a negative amount, don't block the migration; once they are
+-- corrected, VALIDATE CONSTRAINT checks the rest of the table.
+ALTER TABLE settlements ADD CONSTRAINT settlements_amounts_add_up CHECK (
+    settled_amount >= 0
+    AND settled_amount <= original_amount
+    AND saved_amount = original_amount - settled_amount
+) NOT VALID;
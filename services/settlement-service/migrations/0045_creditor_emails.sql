@@ -0,0 +1,23 @@
+-- Creditor replies received by email, each once however often the mail
+-- provider delivers it. A reply recorded as `received` was claimed but not
+-- yet acted on; one that couldn't be acted on waits in `needs_review` for
+-- support to read, with the reason.
+CREATE TYPE creditor_email_status AS ENUM ('received', 'processed', 'needs_review');
+CREATE TYPE creditor_reply_intent AS ENUM ('accept', 'reject', 'counter', 'unclear');
+
+CREATE TABLE creditor_emails (
+    id UUID PRIMARY KEY,
+    message_id TEXT NOT NULL UNIQUE,
+    sender TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    settlement_id UUID REFERENCES settlements(id),
+    intent creditor_reply_intent,
+    amount NUMERIC,
+    status creditor_email_status NOT NULL,
+    review_reason TEXT,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ
+);
+
+CREATE INDEX creditor_emails_review_idx ON creditor_emails (received_at) WHERE status = 'needs_review';
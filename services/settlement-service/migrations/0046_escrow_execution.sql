@@ -0,0 +1,16 @@
+-- Escrow execution locks the payment in a script address instead of paying
+-- the creditor outright. The creditor releases it once the account is
+-- closed; after `escrow_refund_after` the debtor may take it back instead.
+CREATE TYPE execution_mode AS ENUM ('direct', 'escrow');
+
+ALTER TABLE creditor_settings ADD COLUMN execution_mode execution_mode;
+
+ALTER TABLE settlements
+    ADD COLUMN execution_mode execution_mode NOT NULL DEFAULT 'direct',
+    ADD COLUMN escrow_refund_after TIMESTAMPTZ;
+
+ALTER TYPE settlement_status ADD VALUE 'escrowed';
+ALTER TYPE settlement_status ADD VALUE 'refunded';
+ALTER TYPE settlement_event_type ADD VALUE 'escrowed';
+ALTER TYPE settlement_event_type ADD VALUE 'escrow_released';
+ALTER TYPE settlement_event_type ADD VALUE 'escrow_refunded';
@@ -0,0 +1,34 @@
+-- A creditor has one active registration per URL, so no status is delivered
+-- to the same URL twice. Registering the URL again adds to its events.
+-- Duplicates already stored fold into the oldest, which takes on all their
+-- events; the rest are deactivated, keeping their delivery history.
+UPDATE webhooks keeper
+SET events = ARRAY(
+    SELECT DISTINCT event
+    FROM webhooks duplicate, unnest(duplicate.events) AS event
+    WHERE duplicate.active
+      AND duplicate.creditor_id = keeper.creditor_id
+      AND duplicate.url = keeper.url
+    ORDER BY event
+)
+WHERE keeper.active
+  AND NOT EXISTS (
+      SELECT 1 FROM webhooks older
+      WHERE older.active
+        AND older.creditor_id = keeper.creditor_id
+        AND older.url = keeper.url
+        AND (older.created_at, older.id) < (keeper.created_at, keeper.id)
+  );
+
+UPDATE webhooks newer
+SET active = FALSE
+WHERE newer.active
+  AND EXISTS (
+      SELECT 1 FROM webhooks older
+      WHERE older.active
+        AND older.creditor_id = newer.creditor_id
+        AND older.url = newer.url
+        AND (older.created_at, older.id) < (newer.created_at, newer.id)
+  );
+
+CREATE UNIQUE INDEX webhooks_creditor_url_idx ON webhooks (creditor_id, url) WHERE active;
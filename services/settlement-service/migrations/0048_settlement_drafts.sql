@@ -0,0 +1,20 @@
+-- A proposal the user is still putting together, saved as they go so it
+-- survives them navigating away. `fields` holds whatever of the proposal
+-- request has been filled in; nothing is checked until the draft is
+-- finalized into a settlement, which `settlement_id` then names.
+CREATE TYPE draft_kind AS ENUM ('settlement', 'bundle');
+
+CREATE TABLE settlement_drafts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    kind draft_kind NOT NULL,
+    fields JSONB NOT NULL DEFAULT '{}',
+    settlement_id UUID REFERENCES settlements(id),
+    version INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX settlement_drafts_user_idx ON settlement_drafts (user_id, updated_at DESC) WHERE settlement_id IS NULL;
+CREATE INDEX settlement_drafts_expires_at_idx ON settlement_drafts (expires_at);
@@ -0,0 +1,38 @@
+-- A debt held jointly, e.g. by spouses, is owed by each of `debtors`;
+-- `user_id`, who brought it to the platform, is always one of them.
+ALTER TABLE debts ADD COLUMN debtors UUID[] NOT NULL DEFAULT '{}';
+UPDATE debts SET debtors = ARRAY[user_id];
+
+-- Each debtor's acceptance of a settlement, and the terms accepted: one
+-- given before the terms changed no longer counts. A settlement of a joint
+-- debt is only accepted, and paid, once every debtor has accepted its terms.
+CREATE TABLE settlement_acceptances (
+    settlement_id UUID NOT NULL REFERENCES settlements(id),
+    debtor_id UUID NOT NULL,
+    settled_amount NUMERIC NOT NULL,
+    platform_fee NUMERIC NOT NULL,
+    clauses_hash TEXT,
+    signed BOOLEAN NOT NULL,
+    envelope_id TEXT REFERENCES signature_envelopes(envelope_id),
+    accepted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (settlement_id, debtor_id)
+);
+
+-- An envelope is one debtor's signature.
+CREATE UNIQUE INDEX settlement_acceptances_envelope_idx ON settlement_acceptances (envelope_id);
+
+-- Settlements accepted so far were accepted by their one debtor, signed as
+-- the latest acceptance event says.
+INSERT INTO settlement_acceptances
+    (settlement_id, debtor_id, settled_amount, platform_fee, clauses_hash, signed, accepted_at)
+SELECT s.id, s.user_id, s.settled_amount, s.platform_fee, s.clauses_hash, COALESCE((
+    SELECT (e.metadata->>'signed')::boolean
+    FROM settlement_events e
+    WHERE e.settlement_id = s.id AND e.event_type = 'accepted'
+    ORDER BY e.id DESC
+    LIMIT 1
+), false), s.accepted_at
+FROM settlements s
+WHERE s.accepted_at IS NOT NULL;
+
+ALTER TYPE settlement_event_type ADD VALUE 'debtor_accepted';
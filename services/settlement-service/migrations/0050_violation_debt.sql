@@ -0,0 +1,13 @@
+-- The debt a violation was recorded against, whose debtor may back it with
+-- evidence. Violations recorded before this take the debt of the earliest
+-- proposal citing them; those no proposal cites have none.
+ALTER TABLE violations ADD COLUMN debt_id UUID REFERENCES debts(id);
+UPDATE violations v SET debt_id = (
+    SELECT s.debt_id
+    FROM settlement_violations sv
+    JOIN settlements s ON s.id = sv.settlement_id
+    JOIN debts d ON d.id = s.debt_id
+    WHERE sv.violation_id = v.id
+    ORDER BY s.proposed_at, s.id
+    LIMIT 1
+);
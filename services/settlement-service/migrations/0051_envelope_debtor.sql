@@ -0,0 +1,5 @@
+-- The debtor an envelope was sent to sign; only they can accept with it.
+-- Envelopes before joint debts were all for the settlement's user.
+ALTER TABLE signature_envelopes ADD COLUMN debtor_id UUID;
+UPDATE signature_envelopes e SET debtor_id = s.user_id FROM settlements s WHERE s.id = e.settlement_id;
+ALTER TABLE signature_envelopes ALTER COLUMN debtor_id SET NOT NULL;
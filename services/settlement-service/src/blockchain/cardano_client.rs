@@ -0,0 +1,501 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::time::{sleep, Instant};
+use tracing::{info, instrument, warn};
+
+use crate::models::Settlement;
+use crate::services::env_or;
+use crate::services::retry::{is_retryable_status, retry_with, RetryPolicy};
+
+use super::{
+    BlockchainClient, ConfirmationStatus, SettlementMetadata, SubmittedTransaction, TxInclusion,
+    SETTLEMENT_METADATA_LABEL,
+};
+
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 20;
+const DEFAULT_CONFIRMATION_TIMEOUT_SECS: u64 = 600;
+const DEFAULT_MIN_CONFIRMATIONS: u32 = 3;
+
+/// A tx that is neither on-chain nor in the mempool for this many consecutive
+/// polls is treated as dropped. One miss alone can just be propagation lag.
+const DROPPED_AFTER_MISSES: u32 = 3;
+
+/// Rough serialized sizes used to estimate a settlement transaction before it
+/// is built: one input with its witness plus body overhead, and one ADA-only
+/// output.
+const TX_BASE_SIZE_BYTES: u64 = 300;
+const TX_OUTPUT_SIZE_BYTES: u64 = 70;
+
+const LOVELACE_PER_ADA: i64 = 1_000_000;
+
+#[derive(Deserialize)]
+struct SubmitResponse {
+    tx_hash: String,
+    contract_address: String,
+}
+
+/// The gateway's answer to an escrow release or refund.
+#[derive(Deserialize)]
+struct SpendResponse {
+    tx_hash: String,
+}
+
+#[derive(Deserialize)]
+struct TxInfo {
+    block_height: u64,
+}
+
+/// One entry of `/txs/{hash}/metadata/cbor`; `metadata` is hex CBOR.
+#[derive(Deserialize)]
+struct TxMetadataCbor {
+    label: String,
+    metadata: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BlockInfo {
+    height: u64,
+}
+
+/// The linear fee parameters: `fee = min_fee_a * size + min_fee_b` lovelace.
+#[derive(Deserialize)]
+struct ProtocolParameters {
+    min_fee_a: u64,
+    min_fee_b: u64,
+}
+
+#[derive(Clone)]
+pub struct CardanoClient {
+    http: reqwest::Client,
+    node_url: String,
+    poll_interval: Duration,
+    confirmation_timeout: Duration,
+    min_confirmations: u32,
+    retry: RetryPolicy,
+}
+
+impl CardanoClient {
+    /// Poll interval, timeout and required depth are read from
+    /// `CARDANO_CONFIRMATION_POLL_INTERVAL_SECS`, `CARDANO_CONFIRMATION_TIMEOUT_SECS`
+    /// and `CARDANO_MIN_CONFIRMATIONS`. Calls are retried by
+    /// `RetryPolicy::CARDANO` unless `with_retry` says otherwise.
+    pub fn new(node_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            node_url: node_url.trim_end_matches('/').to_string(),
+            poll_interval: Duration::from_secs(env_or(
+                "CARDANO_CONFIRMATION_POLL_INTERVAL_SECS",
+                DEFAULT_POLL_INTERVAL_SECS,
+            )),
+            confirmation_timeout: Duration::from_secs(env_or(
+                "CARDANO_CONFIRMATION_TIMEOUT_SECS",
+                DEFAULT_CONFIRMATION_TIMEOUT_SECS,
+            )),
+            min_confirmations: env_or("CARDANO_MIN_CONFIRMATIONS", DEFAULT_MIN_CONFIRMATIONS),
+            retry: RetryPolicy::CARDANO,
+        }
+    }
+    
+    pub fn with_retry(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+    
+    /// Sends what `request` builds, again as the retry policy allows while
+    /// the node can't be reached or answers 5xx or 429. Any other answer is
+    /// the caller's to read.
+    async fn send(
+        &self,
+        call: &str,
+        request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> anyhow::Result<reqwest::Response> {
+        let request = &request;
+        retry_with(&self.retry, &format!("Cardano {} call", call), || async move {
+            let response = request().send().await?;
+            if is_retryable_status(response.status()) {
+                response.error_for_status().map_err(Into::into)
+            } else {
+                Ok(response)
+            }
+        })
+        .await
+    }
+    
+    /// Spends the settlement's escrow lock through the gateway's `action`
+    /// endpoint, `release` or `refund`.
+    async fn spend_escrow(&self, settlement: &Settlement, action: &str) -> anyhow::Result<String> {
+        let payload = escrow_payload(settlement)?;
+        let response = self
+            .send("spend_escrow", || {
+                self.http.post(format!("{}/tx/escrow/{}", self.node_url, action)).json(&payload)
+            })
+            .await?
+            .error_for_status()?
+            .json::<SpendResponse>()
+            .await
+            .with_context(|| format!("invalid escrow {} response from Cardano node", action))?;
+        
+        Ok(response.tx_hash)
+    }
+    
+    async fn get_tx(&self, tx_hash: &str) -> anyhow::Result<Option<TxInfo>> {
+        let response = self
+            .send("get_tx", || self.http.get(format!("{}/txs/{}", self.node_url, tx_hash)))
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        
+        Ok(Some(response.error_for_status()?.json::<TxInfo>().await?))
+    }
+}
+
+#[async_trait]
+impl BlockchainClient for CardanoClient {
+    fn min_confirmations(&self) -> u32 {
+        self.min_confirmations
+    }
+    
+    /// Builds, signs and submits the settlement payment through the node
+    /// gateway, with `SettlementMetadata` attached under
+    /// `SETTLEMENT_METADATA_LABEL`.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn submit(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        let payload = settlement_payload(settlement)?;
+        let response = self
+            .send("submit", || self.http.post(format!("{}/tx/settlement", self.node_url)).json(&payload))
+            .await?
+            .error_for_status()?
+            .json::<SubmitResponse>()
+            .await
+            .context("invalid submit response from Cardano node")?;
+        
+        Ok(SubmittedTransaction {
+            tx_hash: response.tx_hash,
+            contract_address: response.contract_address,
+            metadata_label: SETTLEMENT_METADATA_LABEL,
+            terms_hash: payload["metadata"]["terms_hash"]
+                .as_str()
+                .expect("payload carries the terms hash")
+                .to_string(),
+        })
+    }
+    
+    /// Builds, signs and submits one transaction paying every settlement,
+    /// through the node gateway's batch endpoint. The metadatum under
+    /// `SETTLEMENT_METADATA_LABEL` is the list of each settlement's
+    /// `SettlementMetadata`.
+    #[instrument(skip_all, fields(settlements = settlements.len()))]
+    async fn submit_batch(&self, settlements: &[Settlement]) -> anyhow::Result<Vec<SubmittedTransaction>> {
+        let metadata: Vec<SettlementMetadata> = settlements.iter().map(SettlementMetadata::for_settlement).collect();
+        let payload = json!({
+            "idempotency_key": settlements.first().and_then(|settlement| settlement.submission_intent),
+            "payments": settlements.iter().map(payment_payload).collect::<Vec<_>>(),
+            "metadata": {
+                "label": SETTLEMENT_METADATA_LABEL,
+                "cbor": hex::encode(SettlementMetadata::batch_to_cbor(&metadata)?),
+            },
+        });
+        let response = self
+            .send("submit_batch", || {
+                self.http.post(format!("{}/tx/settlement-batch", self.node_url)).json(&payload)
+            })
+            .await?
+            .error_for_status()?
+            .json::<SubmitResponse>()
+            .await
+            .context("invalid batch submit response from Cardano node")?;
+        
+        Ok(metadata
+            .into_iter()
+            .map(|metadata| SubmittedTransaction {
+                tx_hash: response.tx_hash.clone(),
+                contract_address: response.contract_address.clone(),
+                metadata_label: SETTLEMENT_METADATA_LABEL,
+                terms_hash: metadata.terms_hash,
+            })
+            .collect())
+    }
+    
+    /// Builds, signs and submits a transaction locking the settlement payment
+    /// in the gateway's escrow script, parameterized by the parties and the
+    /// refund deadline, with the same metadata `submit` attaches.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn submit_escrow(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        let mut payload = settlement_payload(settlement)?;
+        payload["refund_after"] = json!(settlement
+            .escrow_refund_after
+            .context("an escrow payment needs a refund deadline")?);
+        let response = self
+            .send("submit_escrow", || self.http.post(format!("{}/tx/escrow", self.node_url)).json(&payload))
+            .await?
+            .error_for_status()?
+            .json::<SubmitResponse>()
+            .await
+            .context("invalid escrow submit response from Cardano node")?;
+        
+        Ok(SubmittedTransaction {
+            tx_hash: response.tx_hash,
+            contract_address: response.contract_address,
+            metadata_label: SETTLEMENT_METADATA_LABEL,
+            terms_hash: payload["metadata"]["terms_hash"]
+                .as_str()
+                .expect("payload carries the terms hash")
+                .to_string(),
+        })
+    }
+    
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn release_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        self.spend_escrow(settlement, "release").await
+    }
+    
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn refund_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        self.spend_escrow(settlement, "refund").await
+    }
+    
+    /// Asks the node gateway what it submitted under the settlement's
+    /// intent; the gateway answers a repeated submission with the same
+    /// transaction, and keeps the answer for lookups.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn find_submission(&self, settlement: &Settlement) -> anyhow::Result<Option<SubmittedTransaction>> {
+        let Some(intent) = settlement.submission_intent else {
+            return Ok(None);
+        };
+        let response = self
+            .send("find_submission", || self.http.get(format!("{}/tx/idempotency/{}", self.node_url, intent)))
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        
+        let response = response
+            .error_for_status()?
+            .json::<SubmitResponse>()
+            .await
+            .context("invalid submission lookup response from Cardano node")?;
+        Ok(Some(SubmittedTransaction {
+            tx_hash: response.tx_hash,
+            contract_address: response.contract_address,
+            metadata_label: SETTLEMENT_METADATA_LABEL,
+            terms_hash: SettlementMetadata::for_settlement(settlement).terms_hash,
+        }))
+    }
+    
+    /// Network fee, in ADA, of the transaction `submit` would build, from the
+    /// current protocol fee parameters. Nothing is submitted.
+    ///
+    /// The size estimate counts the debtor (change), creditor and platform
+    /// outputs, the last only when there is a platform fee, plus the metadata.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn estimate_fee(&self, settlement: &Settlement) -> anyhow::Result<BigDecimal> {
+        let params = self
+            .send("estimate_fee", || self.http.get(format!("{}/epochs/latest/parameters", self.node_url)))
+            .await?
+            .error_for_status()?
+            .json::<ProtocolParameters>()
+            .await
+            .context("invalid protocol parameters from Cardano node")?;
+        
+        let outputs = if settlement.platform_fee > BigDecimal::from(0) { 3 } else { 2 };
+        let metadata_bytes = serde_json::to_vec(&settlement_payload(settlement)?)?.len() as u64;
+        let size = TX_BASE_SIZE_BYTES + outputs * TX_OUTPUT_SIZE_BYTES + metadata_bytes;
+        
+        let lovelace = params.min_fee_a * size + params.min_fee_b;
+        Ok(BigDecimal::from(lovelace) / BigDecimal::from(LOVELACE_PER_ADA))
+    }
+    
+    /// Polls the node until `tx_hash` is buried under `min_confirmations` blocks,
+    /// disappears from both chain and mempool, or the configured timeout elapses.
+    #[instrument(skip(self))]
+    async fn await_confirmation(
+        &self,
+        tx_hash: &str,
+        min_confirmations: u32,
+    ) -> anyhow::Result<ConfirmationStatus> {
+        let deadline = Instant::now() + self.confirmation_timeout;
+        let mut misses = 0;
+        let mut confirmations = 0;
+        
+        loop {
+            match self.get_tx(tx_hash).await? {
+                Some(tx) => {
+                    misses = 0;
+                    let tip = self.tip_height().await?;
+                    confirmations = tip.saturating_sub(tx.block_height).saturating_add(1) as u32;
+                    
+                    if confirmations >= min_confirmations {
+                        info!("Tx {} confirmed at depth {}", tx_hash, confirmations);
+                        return Ok(ConfirmationStatus::Confirmed {
+                            confirmations,
+                            block_height: tx.block_height,
+                        });
+                    }
+                }
+                None if self.in_mempool(tx_hash).await? => misses = 0,
+                None => {
+                    misses += 1;
+                    if misses >= DROPPED_AFTER_MISSES {
+                        warn!("Tx {} dropped: not on-chain or in mempool", tx_hash);
+                        return Ok(ConfirmationStatus::Dropped);
+                    }
+                }
+            }
+            
+            if Instant::now() + self.poll_interval > deadline {
+                warn!("Timed out waiting for tx {} ({} confirmations)", tx_hash, confirmations);
+                return Ok(ConfirmationStatus::TimedOut { confirmations });
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+    
+    /// Depth of `tx_hash` on-chain right now, or `None` if it isn't in a block.
+    #[instrument(level = "debug", skip(self))]
+    async fn confirmations(&self, tx_hash: &str) -> anyhow::Result<Option<u32>> {
+        Ok(self.inclusion(tx_hash).await?.map(|tx| tx.confirmations))
+    }
+    
+    #[instrument(level = "debug", skip(self))]
+    async fn inclusion(&self, tx_hash: &str) -> anyhow::Result<Option<TxInclusion>> {
+        let Some(tx) = self.get_tx(tx_hash).await? else {
+            return Ok(None);
+        };
+        
+        let tip = self.tip_height().await?;
+        Ok(Some(TxInclusion {
+            block_height: tx.block_height,
+            confirmations: tip.saturating_sub(tx.block_height).saturating_add(1) as u32,
+        }))
+    }
+    
+    #[instrument(level = "debug", skip(self))]
+    async fn in_mempool(&self, tx_hash: &str) -> anyhow::Result<bool> {
+        let response = self
+            .send("in_mempool", || self.http.get(format!("{}/mempool/{}", self.node_url, tx_hash)))
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        
+        response.error_for_status()?;
+        Ok(true)
+    }
+    
+    /// Height of the node's current tip. Cheap enough to use as a liveness probe.
+    async fn tip_height(&self) -> anyhow::Result<u64> {
+        let tip = self
+            .send("tip_height", || self.http.get(format!("{}/blocks/latest", self.node_url)))
+            .await?
+            .error_for_status()?
+            .json::<BlockInfo>()
+            .await?;
+        
+        Ok(tip.height)
+    }
+    
+    #[instrument(skip(self))]
+    async fn transaction_metadata(&self, tx_hash: &str, label: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        let response = self
+            .send("transaction_metadata", || {
+                self.http.get(format!("{}/txs/{}/metadata/cbor", self.node_url, tx_hash))
+            })
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        
+        let entries = response.error_for_status()?.json::<Vec<TxMetadataCbor>>().await?;
+        let label = label.to_string();
+        entries
+            .into_iter()
+            .find(|entry| entry.label == label)
+            .and_then(|entry| entry.metadata)
+            .map(|cbor| hex::decode(cbor).context("invalid metadata CBOR from Cardano node"))
+            .transpose()
+    }
+    
+    fn verify_signature(&self, message: &[u8], signature: &str, pub_key: &str) -> bool {
+        super::verify_signature(message, signature, pub_key)
+    }
+}
+
+/// The settlement terms the node gateway builds into the transaction, and the
+/// CBOR metadatum it attaches verbatim.
+fn settlement_payload(settlement: &Settlement) -> anyhow::Result<serde_json::Value> {
+    let metadata = SettlementMetadata::for_settlement(settlement);
+    let mut payload = payment_payload(settlement);
+    payload["idempotency_key"] = json!(settlement.submission_intent);
+    payload["metadata"] = json!({
+        "label": SETTLEMENT_METADATA_LABEL,
+        "terms_hash": metadata.terms_hash,
+        "cbor": hex::encode(metadata.to_cbor()?),
+    });
+    Ok(payload)
+}
+
+/// The escrow lock a release or refund spends.
+fn escrow_payload(settlement: &Settlement) -> anyhow::Result<serde_json::Value> {
+    Ok(json!({
+        "settlement_id": settlement.id,
+        "lock_tx_hash": settlement.transaction_hash.as_deref().context("the escrow was never locked")?,
+        "script_address": settlement.smart_contract_address,
+        "refund_after": settlement.escrow_refund_after,
+    }))
+}
+
+/// Who is paid what for one settlement.
+fn payment_payload(settlement: &Settlement) -> serde_json::Value {
+    json!({
+        "settlement_id": settlement.id,
+        "user_id": settlement.user_id,
+        "debt_id": settlement.debt_id,
+        "settled_amount": settlement.settled_amount,
+        "platform_fee": settlement.platform_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+    
+    use super::*;
+    use crate::blockchain::metadata;
+    use crate::models::SettlementStatus;
+    
+    #[test]
+    fn the_submitted_terms_hash_is_the_published_one() {
+        let mut settlement = Settlement {
+            reference_number: Some("DMC-2026-000042".to_string()),
+            settled_amount: "600.5".parse().unwrap(),
+            saved_amount: "399.5".parse().unwrap(),
+            platform_fee: "86.45".parse().unwrap(),
+            status: SettlementStatus::Accepted,
+            accepted_at: Some(chrono::Utc::now()),
+            version: 1,
+            ..Settlement::fixture()
+        };
+        
+        for clauses_hash in [None, Some("ab12".to_string())] {
+            settlement.clauses_hash = clauses_hash;
+            let published = metadata::canonical_terms(&settlement);
+            let submitted = settlement_payload(&settlement).unwrap();
+            
+            assert_eq!(submitted["metadata"]["terms_hash"], published.terms_hash.as_str());
+            assert_eq!(format!("{:x}", Sha256::digest(published.terms.as_bytes())), published.terms_hash);
+            assert!(published
+                .terms
+                .starts_with(&format!("{}:{}|", published.canonicalization, published.canonicalization_version)));
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::models::Settlement;
+
+use super::{BlockchainClient, ConfirmationStatus, SubmittedTransaction, TxInclusion};
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_OPEN_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub enum CircuitState {
+    /// Calls go through to the node.
+    Closed,
+    /// The node kept failing; calls fail fast without reaching it.
+    Open,
+    /// The open period is over and one call is let through to probe the node.
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn label(self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Returned instead of calling the node while the breaker is open. Callers
+/// find it by downcasting the `anyhow::Error`.
+#[derive(Debug)]
+pub struct CircuitOpen;
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the Cardano node circuit breaker is open")
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// Whether `e` is the breaker refusing a call rather than the node failing one.
+pub fn is_circuit_open(e: &anyhow::Error) -> bool {
+    e.is::<CircuitOpen>()
+}
+
+/// Read at startup from `CARDANO_BREAKER_FAILURE_THRESHOLD` (default 5) and
+/// `CARDANO_BREAKER_OPEN_SECS` (default 30); see `Config`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive failures that open the breaker.
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before probing the node again.
+    pub open_for: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            open_for: Duration::from_secs(DEFAULT_OPEN_SECS),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// Whether the half-open probe is in flight.
+    probing: bool,
+}
+
+/// Wraps a node client so that once `failure_threshold` calls in a row have
+/// failed, calls fail fast with `CircuitOpen` for `open_for` instead of
+/// waiting on a node that is down. After that one call is let through: its
+/// success closes the breaker, its failure opens it again.
+pub struct CircuitBreaker<C> {
+    inner: C,
+    config: BreakerConfig,
+    breaker: Mutex<Breaker>,
+}
+
+impl<C: BlockchainClient> CircuitBreaker<C> {
+    pub fn new(inner: C, config: BreakerConfig) -> Self {
+        Self {
+            inner,
+            config,
+            breaker: Mutex::new(Breaker::default()),
+        }
+    }
+    
+    pub fn state(&self) -> CircuitState {
+        self.state_of(&self.breaker.lock().unwrap())
+    }
+    
+    /// Opens the breaker as if the node had just failed too often.
+    #[cfg(test)]
+    pub fn trip(&self) {
+        self.breaker.lock().unwrap().opened_at = Some(Instant::now());
+    }
+    
+    /// Ends the open period early, leaving the breaker half-open.
+    #[cfg(test)]
+    pub fn end_open_period(&self) {
+        self.breaker.lock().unwrap().opened_at = Instant::now().checked_sub(self.config.open_for);
+    }
+    
+    fn state_of(&self, breaker: &Breaker) -> CircuitState {
+        match breaker.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.config.open_for => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+    
+    async fn call<T, F>(&self, call: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let mut permit = self.admit()?;
+        let result = call.await;
+        permit.record(result.is_ok());
+        result
+    }
+    
+    fn admit(&self) -> Result<Permit<'_, C>, CircuitOpen> {
+        let mut breaker = self.breaker.lock().unwrap();
+        let probe = match self.state_of(&breaker) {
+            CircuitState::Closed => false,
+            CircuitState::Open => return Err(CircuitOpen),
+            CircuitState::HalfOpen if breaker.probing => return Err(CircuitOpen),
+            CircuitState::HalfOpen => {
+                breaker.probing = true;
+                true
+            }
+        };
+        Ok(Permit {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+    
+    fn record(&self, succeeded: bool, probe: bool) {
+        let mut breaker = self.breaker.lock().unwrap();
+        if probe {
+            breaker.probing = false;
+        }
+        if succeeded {
+            if breaker.opened_at.is_some() {
+                info!("Cardano node recovered; closing the circuit breaker");
+            }
+            *breaker = Breaker::default();
+            return;
+        }
+        
+        breaker.consecutive_failures += 1;
+        // A call let through before the breaker opened may fail after it
+        // did; that doesn't restart the open period.
+        let reopen = probe
+            || (breaker.opened_at.is_none() && breaker.consecutive_failures >= self.config.failure_threshold);
+        if reopen {
+            warn!(
+                "Cardano node failed {} calls in a row; opening the circuit breaker for {}s",
+                breaker.consecutive_failures,
+                self.config.open_for.as_secs()
+            );
+            breaker.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Leave to make one call. A probe dropped before it finishes, e.g. because
+/// the caller timed out, lets the next call probe instead.
+struct Permit<'a, C: BlockchainClient> {
+    breaker: &'a CircuitBreaker<C>,
+    probe: bool,
+    recorded: bool,
+}
+
+impl<C: BlockchainClient> Permit<'_, C> {
+    fn record(&mut self, succeeded: bool) {
+        self.recorded = true;
+        self.breaker.record(succeeded, self.probe);
+    }
+}
+
+impl<C: BlockchainClient> Drop for Permit<'_, C> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            self.breaker.breaker.lock().unwrap().probing = false;
+        }
+    }
+}
+
+#[async_trait]
+impl<C: BlockchainClient> BlockchainClient for CircuitBreaker<C> {
+    fn min_confirmations(&self) -> u32 {
+        self.inner.min_confirmations()
+    }
+    
+    async fn submit(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        self.call(self.inner.submit(settlement)).await
+    }
+    
+    async fn submit_batch(&self, settlements: &[Settlement]) -> anyhow::Result<Vec<SubmittedTransaction>> {
+        self.call(self.inner.submit_batch(settlements)).await
+    }
+    
+    async fn find_submission(&self, settlement: &Settlement) -> anyhow::Result<Option<SubmittedTransaction>> {
+        self.call(self.inner.find_submission(settlement)).await
+    }
+    
+    async fn submit_escrow(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        self.call(self.inner.submit_escrow(settlement)).await
+    }
+    
+    async fn release_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        self.call(self.inner.release_escrow(settlement)).await
+    }
+    
+    async fn refund_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        self.call(self.inner.refund_escrow(settlement)).await
+    }
+    
+    async fn estimate_fee(&self, settlement: &Settlement) -> anyhow::Result<BigDecimal> {
+        self.call(self.inner.estimate_fee(settlement)).await
+    }
+    
+    async fn await_confirmation(
+        &self,
+        tx_hash: &str,
+        min_confirmations: u32,
+    ) -> anyhow::Result<ConfirmationStatus> {
+        self.call(self.inner.await_confirmation(tx_hash, min_confirmations)).await
+    }
+    
+    async fn confirmations(&self, tx_hash: &str) -> anyhow::Result<Option<u32>> {
+        self.call(self.inner.confirmations(tx_hash)).await
+    }
+    
+    async fn inclusion(&self, tx_hash: &str) -> anyhow::Result<Option<TxInclusion>> {
+        self.call(self.inner.inclusion(tx_hash)).await
+    }
+    
+    async fn in_mempool(&self, tx_hash: &str) -> anyhow::Result<bool> {
+        self.call(self.inner.in_mempool(tx_hash)).await
+    }
+    
+    async fn tip_height(&self) -> anyhow::Result<u64> {
+        self.call(self.inner.tip_height()).await
+    }
+    
+    async fn transaction_metadata(&self, tx_hash: &str, label: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        self.call(self.inner.transaction_metadata(tx_hash, label)).await
+    }
+    
+    fn verify_signature(&self, message: &[u8], signature: &str, pub_key: &str) -> bool {
+        self.inner.verify_signature(message, signature, pub_key)
+    }
+    
+    fn circuit_state(&self) -> Option<CircuitState> {
+        Some(self.state())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    
+    use super::*;
+    use crate::blockchain::mock::MockBlockchainClient;
+    
+    /// The mock chain, but with a node that can be taken down.
+    struct FlakyNode {
+        chain: MockBlockchainClient,
+        down: AtomicBool,
+        calls: AtomicU32,
+    }
+    
+    impl FlakyNode {
+        fn new() -> Self {
+            Self {
+                chain: MockBlockchainClient::new(1),
+                down: AtomicBool::new(false),
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+    
+    #[async_trait]
+    impl BlockchainClient for FlakyNode {
+        fn min_confirmations(&self) -> u32 {
+            self.chain.min_confirmations()
+        }
+        
+        async fn submit(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+            self.chain.submit(settlement).await
+        }
+        
+        async fn submit_batch(&self, settlements: &[Settlement]) -> anyhow::Result<Vec<SubmittedTransaction>> {
+            self.chain.submit_batch(settlements).await
+        }
+        
+        async fn find_submission(&self, settlement: &Settlement) -> anyhow::Result<Option<SubmittedTransaction>> {
+            self.chain.find_submission(settlement).await
+        }
+        
+        async fn submit_escrow(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+            self.chain.submit_escrow(settlement).await
+        }
+        
+        async fn release_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+            self.chain.release_escrow(settlement).await
+        }
+        
+        async fn refund_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+            self.chain.refund_escrow(settlement).await
+        }
+        
+        async fn estimate_fee(&self, settlement: &Settlement) -> anyhow::Result<BigDecimal> {
+            self.chain.estimate_fee(settlement).await
+        }
+        
+        async fn await_confirmation(&self, tx_hash: &str, min_confirmations: u32) -> anyhow::Result<ConfirmationStatus> {
+            self.chain.await_confirmation(tx_hash, min_confirmations).await
+        }
+        
+        async fn confirmations(&self, tx_hash: &str) -> anyhow::Result<Option<u32>> {
+            self.chain.confirmations(tx_hash).await
+        }
+        
+        async fn in_mempool(&self, tx_hash: &str) -> anyhow::Result<bool> {
+            self.chain.in_mempool(tx_hash).await
+        }
+        
+        async fn tip_height(&self) -> anyhow::Result<u64> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                anyhow::bail!("connection refused");
+            }
+            self.chain.tip_height().await
+        }
+        
+        async fn transaction_metadata(&self, tx_hash: &str, label: u64) -> anyhow::Result<Option<Vec<u8>>> {
+            self.chain.transaction_metadata(tx_hash, label).await
+        }
+        
+        fn verify_signature(&self, message: &[u8], signature: &str, pub_key: &str) -> bool {
+            self.chain.verify_signature(message, signature, pub_key)
+        }
+    }
+    
+    fn breaker(open_for: Duration) -> CircuitBreaker<FlakyNode> {
+        CircuitBreaker::new(
+            FlakyNode::new(),
+            BreakerConfig {
+                failure_threshold: 3,
+                open_for,
+            },
+        )
+    }
+    
+    #[tokio::test]
+    async fn opens_after_the_threshold_and_fails_fast() {
+        let client = breaker(Duration::from_secs(60));
+        client.inner.down.store(true, Ordering::SeqCst);
+        
+        for _ in 0..3 {
+            assert_eq!(client.state(), CircuitState::Closed);
+            let e = client.tip_height().await.unwrap_err();
+            assert!(!is_circuit_open(&e));
+        }
+        assert_eq!(client.state(), CircuitState::Open);
+        
+        let e = client.tip_height().await.unwrap_err();
+        assert!(is_circuit_open(&e));
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 3);
+    }
+    
+    #[tokio::test]
+    async fn a_success_resets_the_failure_count() {
+        let client = breaker(Duration::from_secs(60));
+        for down in [true, true, false, true, true] {
+            client.inner.down.store(down, Ordering::SeqCst);
+            let _ = client.tip_height().await;
+        }
+        
+        assert_eq!(client.state(), CircuitState::Closed);
+    }
+    
+    #[tokio::test]
+    async fn half_open_probes_close_or_reopen_the_breaker() {
+        let client = breaker(Duration::ZERO);
+        client.inner.down.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            let _ = client.tip_height().await;
+        }
+        assert_eq!(client.state(), CircuitState::HalfOpen);
+        
+        // A failed probe opens it again.
+        assert!(!is_circuit_open(&client.tip_height().await.unwrap_err()));
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 4);
+        
+        client.inner.down.store(false, Ordering::SeqCst);
+        client.tip_height().await.unwrap();
+        assert_eq!(client.state(), CircuitState::Closed);
+    }
+    
+    #[tokio::test]
+    async fn only_one_probe_runs_at_a_time() {
+        let client = breaker(Duration::ZERO);
+        client.inner.down.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            let _ = client.tip_height().await;
+        }
+        
+        let probe = client.admit().unwrap();
+        assert!(client.admit().is_err());
+        drop(probe);
+        assert!(client.admit().is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::Context;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use cardano_serialization_lib::metadata::{
+    MetadataList, MetadataMap, TransactionMetadatum, TransactionMetadatumKind,
+};
+use cardano_serialization_lib::utils::Int;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::models::{CanonicalTerms, Settlement};
+
+/// Transaction metadata label settlement payments are recorded under.
+pub const SETTLEMENT_METADATA_LABEL: u64 = 6760;
+
+/// Names the canonical form of the terms; the version follows it.
+pub const TERMS_CANONICALIZATION: &str = "damocles-settlement-terms";
+/// Digest of the canonical terms' UTF-8 bytes, as lowercase hex.
+pub const TERMS_HASH_ALGORITHM: &str = "sha256";
+/// The canonical form new terms are written in.
+const TERMS_VERSION: &str = "v3";
+
+/// What a settlement payment carries on-chain: enough to tie the transaction
+/// to the agreement without publishing the amounts or who the parties are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementMetadata {
+    pub settlement_id: Uuid,
+    pub terms_hash: String,
+    /// Reduction off the original debt in basis points.
+    pub reduction_bps: i32,
+}
+
+impl SettlementMetadata {
+    pub fn for_settlement(settlement: &Settlement) -> Self {
+        let reduction_bps = if settlement.original_amount > BigDecimal::from(0) {
+            (&settlement.saved_amount * BigDecimal::from(10_000) / &settlement.original_amount)
+                .round(0)
+                .to_i32()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        
+        Self {
+            settlement_id: settlement.id,
+            terms_hash: terms_hash(settlement),
+            reduction_bps,
+        }
+    }
+    
+    /// CBOR of the metadatum stored under `SETTLEMENT_METADATA_LABEL`.
+    pub fn to_cbor(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.to_metadatum()?.to_bytes())
+    }
+    
+    /// CBOR of the metadatum a batch payment carries: a list of each
+    /// settlement's metadatum, in order.
+    pub fn batch_to_cbor(batch: &[Self]) -> anyhow::Result<Vec<u8>> {
+        let mut list = MetadataList::new();
+        for metadata in batch {
+            list.add(&metadata.to_metadatum()?);
+        }
+        Ok(TransactionMetadatum::new_list(&list).to_bytes())
+    }
+    
+    fn to_metadatum(&self) -> anyhow::Result<TransactionMetadatum> {
+        let mut map = MetadataMap::new();
+        map.insert_str(
+            "settlement_id",
+            &TransactionMetadatum::new_text(self.settlement_id.to_string())?,
+        )?;
+        map.insert_str("terms_hash", &TransactionMetadatum::new_text(self.terms_hash.clone())?)?;
+        map.insert_str(
+            "reduction_bps",
+            &TransactionMetadatum::new_int(&Int::new_i32(self.reduction_bps)),
+        )?;
+        
+        Ok(TransactionMetadatum::new_map(&map))
+    }
+    
+    /// The metadata `settlement_id` was paid under: the metadatum of a
+    /// single payment, or its entry in a batch payment's list.
+    pub fn for_settlement_in(bytes: &[u8], settlement_id: Uuid) -> anyhow::Result<Option<Self>> {
+        let metadatum = TransactionMetadatum::from_bytes(bytes.to_vec())?;
+        if metadatum.kind() != TransactionMetadatumKind::MetadataList {
+            return Self::from_metadatum(&metadatum).map(Some);
+        }
+        let list = metadatum.as_list()?;
+        for i in 0..list.len() {
+            let metadata = Self::from_metadatum(&list.get(i))?;
+            if metadata.settlement_id == settlement_id {
+                return Ok(Some(metadata));
+            }
+        }
+        Ok(None)
+    }
+    
+    fn from_metadatum(metadatum: &TransactionMetadatum) -> anyhow::Result<Self> {
+        let map = metadatum.as_map()?;
+        let text = |key: &str| -> anyhow::Result<String> {
+            Ok(map.get_str(key).and_then(|value| value.as_text())?)
+        };
+        
+        Ok(Self {
+            settlement_id: Uuid::parse_str(&text("settlement_id")?).context("invalid settlement_id")?,
+            terms_hash: text("terms_hash")?,
+            reduction_bps: map
+                .get_str("reduction_bps")?
+                .as_int()?
+                .as_i32_or_nothing()
+                .context("reduction_bps out of range")?,
+        })
+    }
+}
+
+/// The agreed terms, in the fixed form that is hashed. Under `v3` the
+/// currency is part of the terms and amounts are written at its minor
+/// units, so the hash survives a round trip through the database and tells
+/// 12.345 KWD from 12.349. Terms accepted with creditor clauses end with the
+/// hash of the clause versions agreed.
+///
+/// Settlements paid before `v3` keep the form they were hashed in: `v1`, or
+/// `v2` with clauses, both at cent scale and without the currency.
+pub fn settlement_terms(settlement: &Settlement) -> String {
+    write_terms(settlement, terms_version(settlement))
+}
+
+/// The version of the canonical form `settlement`'s terms are written in:
+/// the legacy one its recorded hash was taken over, if any, else `v3`.
+pub fn terms_version(settlement: &Settlement) -> &'static str {
+    let legacy = match settlement.clauses_hash {
+        Some(_) => "v2",
+        None => "v1",
+    };
+    match &settlement.terms_hash {
+        Some(recorded) if *recorded == digest(&write_terms(settlement, legacy)) => legacy,
+        _ => TERMS_VERSION,
+    }
+}
+
+fn write_terms(settlement: &Settlement, version: &str) -> String {
+    let amounts = if version == TERMS_VERSION {
+        let units = settlement.currency.minor_units();
+        format!(
+            "{}|{}|{}|{}",
+            settlement.currency.code(),
+            settlement.original_amount.with_scale(units),
+            settlement.settled_amount.with_scale(units),
+            settlement.platform_fee.with_scale(units),
+        )
+    } else {
+        format!(
+            "{}|{}|{}",
+            settlement.original_amount.with_scale(2),
+            settlement.settled_amount.with_scale(2),
+            settlement.platform_fee.with_scale(2),
+        )
+    };
+    let terms = format!(
+        "{}:{}|{}|{}|{}|{}",
+        TERMS_CANONICALIZATION, version, settlement.id, settlement.user_id, settlement.debt_id, amounts,
+    );
+    match &settlement.clauses_hash {
+        Some(clauses) => format!("{}|{}", terms, clauses),
+        None => terms,
+    }
+}
+
+pub fn terms_hash(settlement: &Settlement) -> String {
+    digest(&settlement_terms(settlement))
+}
+
+fn digest(terms: &str) -> String {
+    format!("{:x}", Sha256::digest(terms.as_bytes()))
+}
+
+/// The terms as hashed into the payment's metadata, with what a third
+/// party needs to hash them again.
+pub fn canonical_terms(settlement: &Settlement) -> CanonicalTerms {
+    CanonicalTerms {
+        settlement_id: settlement.id,
+        canonicalization: TERMS_CANONICALIZATION.to_string(),
+        canonicalization_version: terms_version(settlement).to_string(),
+        algorithm: TERMS_HASH_ALGORITHM.to_string(),
+        terms: settlement_terms(settlement),
+        terms_hash: terms_hash(settlement),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+    use std::str::FromStr;
+    
+    use super::*;
+    use crate::models::SettlementStatus;
+    use crate::money::Currency;
+    
+    fn settlement(original: &str, settled: &str) -> Settlement {
+        let original = BigDecimal::from_str(original).unwrap();
+        let settled = BigDecimal::from_str(settled).unwrap();
+        Settlement {
+            saved_amount: &original - &settled,
+            original_amount: original,
+            settled_amount: settled,
+            status: SettlementStatus::Accepted,
+            accepted_at: Some(Utc::now()),
+            version: 1,
+            ..Settlement::fixture()
+        }
+    }
+    
+    #[test]
+    fn metadata_round_trips_through_cbor() {
+        let metadata = SettlementMetadata::for_settlement(&settlement("1000", "600"));
+        assert_eq!(metadata.reduction_bps, 4000);
+        
+        let decoded = SettlementMetadata::for_settlement_in(&metadata.to_cbor().unwrap(), metadata.settlement_id);
+        assert_eq!(decoded.unwrap(), Some(metadata));
+    }
+    
+    #[test]
+    fn each_settlement_of_a_batch_finds_its_own_entry() {
+        let batch = [
+            SettlementMetadata::for_settlement(&settlement("1000", "600")),
+            SettlementMetadata::for_settlement(&settlement("500", "400")),
+        ];
+        let cbor = SettlementMetadata::batch_to_cbor(&batch).unwrap();
+        
+        for metadata in &batch {
+            let found = SettlementMetadata::for_settlement_in(&cbor, metadata.settlement_id).unwrap();
+            assert_eq!(found.as_ref(), Some(metadata));
+        }
+        assert_eq!(SettlementMetadata::for_settlement_in(&cbor, Uuid::new_v4()).unwrap(), None);
+    }
+    
+    #[test]
+    fn terms_hash_ignores_amount_scale() {
+        let a = settlement("1000", "600");
+        let b = Settlement {
+            original_amount: BigDecimal::from_str("1000.00").unwrap(),
+            settled_amount: BigDecimal::from_str("600.0").unwrap(),
+            platform_fee: BigDecimal::from_str("86.000").unwrap(),
+            currency: Currency::usd(),
+            ..a.clone()
+        };
+        assert_eq!(terms_hash(&a), terms_hash(&b));
+        
+        let c = Settlement { settled_amount: BigDecimal::from(601), ..a.clone() };
+        assert_ne!(terms_hash(&a), terms_hash(&c));
+    }
+    
+    #[test]
+    fn agreed_clauses_are_part_of_the_terms() {
+        let without = settlement("1000", "600");
+        let with = |hash: &str| Settlement { clauses_hash: Some(hash.to_string()), ..without.clone() };
+        
+        assert!(settlement_terms(&without).starts_with("damocles-settlement-terms:v3|"));
+        assert!(settlement_terms(&with("abc")).ends_with("|abc"));
+        assert_ne!(terms_hash(&with("abc")), terms_hash(&without));
+        assert_ne!(terms_hash(&with("abc")), terms_hash(&with("abd")));
+    }
+    
+    #[test]
+    fn the_currency_and_its_minor_units_are_part_of_the_terms() {
+        let dinars = |settled: &str| Settlement {
+            currency: Currency::try_from("KWD".to_string()).unwrap(),
+            ..settlement("100", settled)
+        };
+        assert_ne!(terms_hash(&dinars("12.345")), terms_hash(&dinars("12.349")));
+        assert!(settlement_terms(&dinars("12.345")).contains("|KWD|100.000|12.345|"));
+        
+        let dollars = settlement("100", "60");
+        let euros = Settlement { currency: Currency::try_from("EUR".to_string()).unwrap(), ..dollars.clone() };
+        assert_ne!(terms_hash(&dollars), terms_hash(&euros));
+    }
+    
+    #[test]
+    fn settlements_paid_before_v3_keep_their_hash() {
+        for clauses_hash in [None, Some("abc".to_string())] {
+            let mut paid = Settlement { clauses_hash, ..settlement("1000", "600") };
+            let legacy = if paid.clauses_hash.is_some() { "v2" } else { "v1" };
+            let hashed = write_terms(&paid, legacy);
+            paid.terms_hash = Some(digest(&hashed));
+            
+            assert_eq!(terms_version(&paid), legacy);
+            assert_eq!(settlement_terms(&paid), hashed);
+            assert_eq!(paid.terms_hash, Some(terms_hash(&paid)));
+        }
+    }
+}
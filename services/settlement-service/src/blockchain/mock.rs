@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::models::Settlement;
+
+use super::{BlockchainClient, ConfirmationStatus, SettlementMetadata, SubmittedTransaction, SETTLEMENT_METADATA_LABEL};
+
+const MOCK_CONTRACT_ADDRESS: &str = "addr_test1mock";
+const MOCK_ESCROW_ADDRESS: &str = "addr_test1mockescrow";
+const MOCK_GENESIS_HEIGHT: u64 = 1_000;
+
+/// In-memory chain for tests. Transactions get a hash derived from the
+/// settlement id and confirm once they have been polled `confirm_after_polls`
+/// times; every poll mines a block.
+pub struct MockBlockchainClient {
+    confirm_after_polls: u32,
+    min_confirmations: u32,
+    fee: BigDecimal,
+    state: Mutex<MockChain>,
+}
+
+#[derive(Default)]
+struct MockChain {
+    height: u64,
+    /// Polls so far per submitted tx.
+    polls: HashMap<String, u32>,
+    /// Metadata CBOR per submitted tx, all under `SETTLEMENT_METADATA_LABEL`.
+    metadata: HashMap<String, Vec<u8>>,
+    submissions: u32,
+    /// Tx submitted under each submission intent.
+    intents: HashMap<Uuid, String>,
+    /// Escrow locks released or refunded.
+    spent: HashSet<String>,
+}
+
+impl MockBlockchainClient {
+    pub fn new(confirm_after_polls: u32) -> Self {
+        Self {
+            confirm_after_polls,
+            min_confirmations: 1,
+            fee: BigDecimal::from(0),
+            state: Mutex::new(MockChain {
+                height: MOCK_GENESIS_HEIGHT,
+                ..MockChain::default()
+            }),
+        }
+    }
+    
+    /// A chain whose transactions stay in the mempool however often they are
+    /// polled, for tests that look at a submission before any watch settles it.
+    pub fn never_confirming() -> Self {
+        Self::new(u32::MAX)
+    }
+    
+    /// How many times `submit`, `submit_escrow` or `submit_batch` has been called.
+    pub fn submissions(&self) -> u32 {
+        self.state.lock().unwrap().submissions
+    }
+    
+    fn pay(
+        &self,
+        settlement: &Settlement,
+        tx_hash: String,
+        contract_address: &str,
+    ) -> anyhow::Result<SubmittedTransaction> {
+        let mut chain = self.state.lock().unwrap();
+        chain.submissions += 1;
+        
+        let metadata = SettlementMetadata::for_settlement(settlement);
+        chain.polls.insert(tx_hash.clone(), 0);
+        chain.metadata.insert(tx_hash.clone(), metadata.to_cbor()?);
+        if let Some(intent) = settlement.submission_intent {
+            chain.intents.insert(intent, tx_hash.clone());
+        }
+        
+        Ok(SubmittedTransaction {
+            tx_hash,
+            contract_address: contract_address.to_string(),
+            metadata_label: SETTLEMENT_METADATA_LABEL,
+            terms_hash: metadata.terms_hash,
+        })
+    }
+    
+    /// Spends the settlement's escrow lock, at most once.
+    fn spend(&self, settlement: &Settlement, action: &str) -> anyhow::Result<String> {
+        let mut chain = self.state.lock().unwrap();
+        let lock = settlement.transaction_hash.clone().context("the escrow was never locked")?;
+        if !chain.spent.insert(lock.clone()) {
+            anyhow::bail!("escrow lock {} was already spent", lock);
+        }
+        
+        let tx_hash = format!("mock{}{}", action, settlement.id.simple());
+        chain.polls.insert(tx_hash.clone(), 0);
+        Ok(tx_hash)
+    }
+}
+
+#[async_trait]
+impl BlockchainClient for MockBlockchainClient {
+    fn min_confirmations(&self) -> u32 {
+        self.min_confirmations
+    }
+    
+    async fn submit(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        self.pay(settlement, format!("mocktx{}", settlement.id.simple()), MOCK_CONTRACT_ADDRESS)
+    }
+    
+    /// One transaction for the whole batch, its hash derived from the first
+    /// settlement's id.
+    async fn submit_batch(&self, settlements: &[Settlement]) -> anyhow::Result<Vec<SubmittedTransaction>> {
+        let mut chain = self.state.lock().unwrap();
+        chain.submissions += 1;
+        
+        let first = settlements.first().context("an empty batch pays nothing")?;
+        let tx_hash = format!("mocktxbatch{}", first.id.simple());
+        let metadata: Vec<SettlementMetadata> = settlements.iter().map(SettlementMetadata::for_settlement).collect();
+        chain.polls.insert(tx_hash.clone(), 0);
+        chain.metadata.insert(tx_hash.clone(), SettlementMetadata::batch_to_cbor(&metadata)?);
+        if let Some(intent) = first.submission_intent {
+            chain.intents.insert(intent, tx_hash.clone());
+        }
+        
+        Ok(metadata
+            .into_iter()
+            .map(|metadata| SubmittedTransaction {
+                tx_hash: tx_hash.clone(),
+                contract_address: MOCK_CONTRACT_ADDRESS.to_string(),
+                metadata_label: SETTLEMENT_METADATA_LABEL,
+                terms_hash: metadata.terms_hash,
+            })
+            .collect())
+    }
+    
+    async fn find_submission(&self, settlement: &Settlement) -> anyhow::Result<Option<SubmittedTransaction>> {
+        let chain = self.state.lock().unwrap();
+        let Some(tx_hash) = settlement.submission_intent.and_then(|intent| chain.intents.get(&intent)) else {
+            return Ok(None);
+        };
+        Ok(Some(SubmittedTransaction {
+            tx_hash: tx_hash.clone(),
+            contract_address: MOCK_CONTRACT_ADDRESS.to_string(),
+            metadata_label: SETTLEMENT_METADATA_LABEL,
+            terms_hash: SettlementMetadata::for_settlement(settlement).terms_hash,
+        }))
+    }
+    
+    async fn submit_escrow(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction> {
+        settlement
+            .escrow_refund_after
+            .context("an escrow payment needs a refund deadline")?;
+        self.pay(settlement, format!("mockescrow{}", settlement.id.simple()), MOCK_ESCROW_ADDRESS)
+    }
+    
+    async fn release_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        self.spend(settlement, "release")
+    }
+    
+    /// Refused before the deadline, as the script would.
+    async fn refund_escrow(&self, settlement: &Settlement) -> anyhow::Result<String> {
+        let refund_after = settlement
+            .escrow_refund_after
+            .context("an escrow payment needs a refund deadline")?;
+        if Utc::now() < refund_after {
+            anyhow::bail!("the escrow script refuses a refund before {}", refund_after);
+        }
+        self.spend(settlement, "refund")
+    }
+    
+    async fn estimate_fee(&self, _settlement: &Settlement) -> anyhow::Result<BigDecimal> {
+        Ok(self.fee.clone())
+    }
+    
+    async fn await_confirmation(
+        &self,
+        tx_hash: &str,
+        min_confirmations: u32,
+    ) -> anyhow::Result<ConfirmationStatus> {
+        loop {
+            if let Some(depth) = self.confirmations(tx_hash).await? {
+                if depth >= min_confirmations {
+                    let height = self.state.lock().unwrap().height;
+                    return Ok(ConfirmationStatus::Confirmed {
+                        confirmations: depth,
+                        block_height: height + 1 - depth as u64,
+                    });
+                }
+            } else if !self.in_mempool(tx_hash).await? {
+                return Ok(ConfirmationStatus::Dropped);
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+    
+    async fn confirmations(&self, tx_hash: &str) -> anyhow::Result<Option<u32>> {
+        let mut chain = self.state.lock().unwrap();
+        chain.height += 1;
+        
+        let Some(polls) = chain.polls.get_mut(tx_hash) else {
+            return Ok(None);
+        };
+        *polls += 1;
+        
+        Ok((*polls >= self.confirm_after_polls).then(|| *polls - self.confirm_after_polls + 1))
+    }
+    
+    async fn in_mempool(&self, tx_hash: &str) -> anyhow::Result<bool> {
+        Ok(self.state.lock().unwrap().polls.contains_key(tx_hash))
+    }
+    
+    async fn tip_height(&self) -> anyhow::Result<u64> {
+        Ok(self.state.lock().unwrap().height)
+    }
+    
+    async fn transaction_metadata(&self, tx_hash: &str, label: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        if label != SETTLEMENT_METADATA_LABEL {
+            return Ok(None);
+        }
+        Ok(self.state.lock().unwrap().metadata.get(tx_hash).cloned())
+    }
+    
+    fn verify_signature(&self, message: &[u8], signature: &str, pub_key: &str) -> bool {
+        super::verify_signature(message, signature, pub_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    
+    use super::*;
+    use crate::models::SettlementStatus;
+    
+    fn settlement() -> Settlement {
+        Settlement {
+            status: SettlementStatus::Accepted,
+            accepted_at: Some(Utc::now()),
+            version: 1,
+            ..Settlement::fixture()
+        }
+    }
+    
+    #[tokio::test]
+    async fn confirms_after_configured_polls() {
+        let chain = MockBlockchainClient::new(3);
+        let tx = chain.submit(&settlement()).await.unwrap();
+        
+        assert_eq!(chain.confirmations(&tx.tx_hash).await.unwrap(), None);
+        assert_eq!(chain.confirmations(&tx.tx_hash).await.unwrap(), None);
+        assert_eq!(chain.confirmations(&tx.tx_hash).await.unwrap(), Some(1));
+        
+        let status = chain.await_confirmation(&tx.tx_hash, 2).await.unwrap();
+        assert!(matches!(status, ConfirmationStatus::Confirmed { confirmations: 2, .. }));
+        assert_eq!(chain.submissions(), 1);
+    }
+    
+    #[tokio::test]
+    async fn unknown_transactions_are_dropped() {
+        let chain = MockBlockchainClient::new(1);
+        
+        let status = chain.await_confirmation("never-submitted", 1).await.unwrap();
+        assert_eq!(status, ConfirmationStatus::Dropped);
+    }
+}
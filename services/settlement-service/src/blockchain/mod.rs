@@ -0,0 +1,116 @@
+pub mod cardano_client;
+pub mod circuit_breaker;
+pub mod metadata;
+#[cfg(test)]
+pub mod mock;
+pub mod signature;
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+
+use crate::models::Settlement;
+
+pub use circuit_breaker::{is_circuit_open, BreakerConfig, CircuitBreaker, CircuitState};
+pub use metadata::{SettlementMetadata, SETTLEMENT_METADATA_LABEL};
+pub use signature::verify_signature;
+
+#[derive(Debug, Clone)]
+pub struct SubmittedTransaction {
+    pub tx_hash: String,
+    pub contract_address: String,
+    pub metadata_label: u64,
+    /// Terms hash committed to in the transaction metadata.
+    pub terms_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConfirmationStatus {
+    Confirmed { confirmations: u32, block_height: u64 },
+    /// The node no longer knows about the tx: it never made it into a block.
+    Dropped,
+    /// Still pending (in the mempool or too shallow) when the timeout elapsed.
+    TimedOut { confirmations: u32 },
+}
+
+/// Where a transaction sits on-chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxInclusion {
+    pub block_height: u64,
+    pub confirmations: u32,
+}
+
+/// A chain settlements can be paid out on. `CardanoClient` is the production
+/// backend; the engine only ever talks to this trait.
+#[async_trait]
+pub trait BlockchainClient: Send + Sync {
+    /// Confirmation depth at which a settlement counts as final.
+    fn min_confirmations(&self) -> u32;
+    
+    async fn submit(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction>;
+    
+    /// Pays every settlement in one transaction, so they all go out or none
+    /// do. Returns one entry per settlement, in order, all with the same
+    /// `tx_hash`.
+    async fn submit_batch(&self, settlements: &[Settlement]) -> anyhow::Result<Vec<SubmittedTransaction>>;
+    
+    /// The transaction the node took under `settlement`'s
+    /// `submission_intent`, alone or in a batch, or `None` if it took none.
+    /// Submissions carry the intent as their idempotency key.
+    async fn find_submission(&self, settlement: &Settlement) -> anyhow::Result<Option<SubmittedTransaction>>;
+    
+    /// Locks the settlement's payment in an escrow script instead of paying
+    /// it out. The script pays the creditor when the platform signs a
+    /// release, or the debtor once `escrow_refund_after` has passed;
+    /// `contract_address` is the script address.
+    async fn submit_escrow(&self, settlement: &Settlement) -> anyhow::Result<SubmittedTransaction>;
+    
+    /// Spends the escrowed payment to the creditor. Returns the new tx hash.
+    async fn release_escrow(&self, settlement: &Settlement) -> anyhow::Result<String>;
+    
+    /// Spends the escrowed payment back to the debtor, which the script only
+    /// allows after `escrow_refund_after`. Returns the new tx hash.
+    async fn refund_escrow(&self, settlement: &Settlement) -> anyhow::Result<String>;
+    
+    /// Network fee of the transaction `submit` would build, without submitting.
+    async fn estimate_fee(&self, settlement: &Settlement) -> anyhow::Result<BigDecimal>;
+    
+    async fn await_confirmation(
+        &self,
+        tx_hash: &str,
+        min_confirmations: u32,
+    ) -> anyhow::Result<ConfirmationStatus>;
+    
+    /// Current depth of `tx_hash`, or `None` if it isn't in a block.
+    async fn confirmations(&self, tx_hash: &str) -> anyhow::Result<Option<u32>>;
+    
+    /// Block and depth of `tx_hash`, or `None` if it isn't in a block.
+    async fn inclusion(&self, tx_hash: &str) -> anyhow::Result<Option<TxInclusion>> {
+        let Some(confirmations) = self.confirmations(tx_hash).await? else {
+            return Ok(None);
+        };
+        let tip = self.tip_height().await?;
+        Ok(Some(TxInclusion {
+            block_height: (tip + 1).saturating_sub(confirmations as u64),
+            confirmations,
+        }))
+    }
+    
+    async fn in_mempool(&self, tx_hash: &str) -> anyhow::Result<bool>;
+    
+    async fn tip_height(&self) -> anyhow::Result<u64>;
+    
+    /// CBOR of the metadatum `tx_hash` carries under `label`, or `None` if the
+    /// transaction or the label isn't there.
+    async fn transaction_metadata(&self, tx_hash: &str, label: u64) -> anyhow::Result<Option<Vec<u8>>>;
+    
+    /// Verifies a user's signature with this chain's key scheme.
+    fn verify_signature(&self, message: &[u8], signature: &str, pub_key: &str) -> bool;
+    
+    /// State of the circuit breaker in front of the node, for clients that
+    /// have one.
+    fn circuit_state(&self) -> Option<CircuitState> {
+        None
+    }
+}
@@ -0,0 +1,19 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// Verifies a hex-encoded Ed25519 `signature` of `message` against a
+/// hex-encoded 32-byte public key, as produced by Cardano wallet keys.
+/// Malformed keys or signatures simply fail verification.
+pub fn verify_signature(message: &[u8], signature: &str, pub_key: &str) -> bool {
+    let Some(key) = decode_fixed::<32>(pub_key).and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok()) else {
+        return false;
+    };
+    let Some(signature) = decode_fixed::<64>(signature).map(|bytes| Signature::from_bytes(&bytes)) else {
+        return false;
+    };
+    
+    key.verify_strict(message, &signature).is_ok()
+}
+
+fn decode_fixed<const N: usize>(value: &str) -> Option<[u8; N]> {
+    hex::decode(value.trim()).ok()?.try_into().ok()
+}
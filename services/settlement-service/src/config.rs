@@ -0,0 +1,484 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bigdecimal::BigDecimal;
+use reqwest::Url;
+
+use crate::blockchain::BreakerConfig;
+use crate::database::PoolConfig;
+use crate::handlers::display::{DisplayPrecision, MAX_DISPLAY_DECIMALS};
+use crate::models::{AiMode, AnchoringStrategy};
+use crate::services::demand_letter::MAX_RESPONSE_DAYS;
+use crate::services::notifications::ReminderHours;
+use crate::services::retry::RetryPolicy;
+use crate::startup::StartupConfig;
+
+/// Names an env-format file whose settings apply wherever the environment
+/// itself leaves them unset.
+const CONFIG_FILE_VAR: &str = "SETTLEMENT_CONFIG_FILE";
+
+const DEFAULT_PORT: u16 = 8003;
+const DEFAULT_HTTP_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+const WHOLE_NUMBER: &str = "a whole number";
+const SECONDS: &str = "a whole number of seconds";
+const MILLISECONDS: &str = "a whole number of milliseconds";
+
+/// Settings the service reads with defaults of their own but that must at
+/// least be well-formed when set, so a typo stops startup instead of
+/// quietly falling back to the default.
+const TIMEOUTS: &[&str] = &[
+    "AI_CONNECT_TIMEOUT_SECS",
+    "AI_CALL_TIMEOUT_SECS",
+    "CARDANO_CONFIRMATION_TIMEOUT_SECS",
+    "CARDANO_CONFIRMATION_POLL_INTERVAL_SECS",
+    "AUTO_NEGOTIATION_JOB_TIMEOUT_SECS",
+    "EVIDENCE_UPLOAD_URL_TTL_SECS",
+    "CARDANO_TX_STATUS_CACHE_SECS",
+    "LEVERAGE_LEADERBOARD_CACHE_SECS",
+    "EVENT_STREAM_KEEPALIVE_SECS",
+    "SETTLEMENT_EXPIRY_SWEEP_INTERVAL_SECS",
+    "AUTO_NEGOTIATION_POLL_INTERVAL_SECS",
+    "RECONCILIATION_INTERVAL_SECS",
+    "SETTLEMENT_EXECUTION_QUEUE_INTERVAL_SECS",
+    "WEBHOOK_INITIAL_BACKOFF_SECS",
+];
+const COUNTS: &[&str] = &["LEVERAGE_BATCH_CONCURRENCY", "WEBHOOK_MAX_ATTEMPTS", "CARDANO_MIN_CONFIRMATIONS"];
+const RATE_LIMITS: &[(&str, &str)] = &[
+    ("RATE_LIMIT_USER_BURST", "RATE_LIMIT_USER_PER_SEC"),
+    ("RATE_LIMIT_CREDITOR_BURST", "RATE_LIMIT_CREDITOR_PER_SEC"),
+];
+const FLAGS: &[&str] = &["SETTLEMENT_REQUIRE_SIGNATURE", "AI_FALLBACK_ENABLED"];
+const SERVICE_URLS: &[&str] = &[
+    "AI_SERVICE_URL",
+    "NOTIFICATION_SERVICE_URL",
+    "EVIDENCE_BUCKET_URL",
+    "DOCUSIGN_BASE_URL",
+    "CARDANO_EXPLORER_TX_URL",
+];
+
+/// A century: far longer than any window the service keeps, and short
+/// enough that adding it to a date can't overflow.
+const MAX_WINDOW_DAYS: u32 = 36_500;
+const MAX_WINDOW_HOURS: u32 = MAX_WINDOW_DAYS * 24;
+
+/// What the service needs before it can bind, read once at startup. Each
+/// subsystem still reads its own tuning knobs, but [`Config::load`] checks
+/// the numeric, boolean and URL ones too, so every impossible setting among
+/// them is reported at once. Files, secrets and tokens are only read, and
+/// complained about, by the subsystems that use them.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database_url: String,
+    pub pool: PoolConfig,
+    pub cardano_node_url: String,
+    pub breaker: BreakerConfig,
+    /// How calls to the AI service and the Cardano node are retried.
+    pub ai_retry: RetryPolicy,
+    pub cardano_retry: RetryPolicy,
+    pub port: u16,
+    /// How long in-flight requests get to finish once shutdown begins.
+    pub shutdown_timeout: Duration,
+    pub startup: StartupConfig,
+    pub display: DisplayPrecision,
+}
+
+/// Every problem found in the configuration, not just the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration: {}", self.0.join("; "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Applies `SETTLEMENT_CONFIG_FILE`, if set, then reads and checks the
+    /// environment.
+    pub fn load() -> Result<Self, ConfigError> {
+        if let Ok(path) = std::env::var(CONFIG_FILE_VAR) {
+            if let Err(e) = dotenv::from_path(&path) {
+                return Err(ConfigError(vec![format!("{} {}: {}", CONFIG_FILE_VAR, path, e)]));
+            }
+        }
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+    
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut settings = Settings { lookup, problems: Vec::new() };
+        
+        let database_url = settings.url("DATABASE_URL", &["postgres", "postgresql"]);
+        let cardano_node_url = settings.url("CARDANO_NODE_URL", &["http", "https"]);
+        let port = settings.parsed("SETTLEMENT_SERVICE_PORT", DEFAULT_PORT, "a port number");
+        settings.require(port != 0, "SETTLEMENT_SERVICE_PORT", "must not be 0");
+        let shutdown_timeout =
+            settings.parsed("HTTP_SHUTDOWN_TIMEOUT_SECS", DEFAULT_HTTP_SHUTDOWN_TIMEOUT_SECS, SECONDS);
+        
+        let defaults = PoolConfig::default();
+        let pool = PoolConfig {
+            max_connections: settings.parsed("DATABASE_MAX_CONNECTIONS", defaults.max_connections, WHOLE_NUMBER),
+            acquire_timeout: Duration::from_secs(
+                settings.parsed("DATABASE_ACQUIRE_TIMEOUT_SECS", defaults.acquire_timeout.as_secs(), SECONDS),
+            ),
+        };
+        settings.require(pool.max_connections > 0, "DATABASE_MAX_CONNECTIONS", "must be at least 1");
+        settings.require(!pool.acquire_timeout.is_zero(), "DATABASE_ACQUIRE_TIMEOUT_SECS", "must be at least 1");
+        
+        let defaults = BreakerConfig::default();
+        let breaker = BreakerConfig {
+            failure_threshold: settings.parsed(
+                "CARDANO_BREAKER_FAILURE_THRESHOLD",
+                defaults.failure_threshold,
+                WHOLE_NUMBER,
+            ),
+            open_for: Duration::from_secs(
+                settings.parsed("CARDANO_BREAKER_OPEN_SECS", defaults.open_for.as_secs(), SECONDS),
+            ),
+        };
+        settings.require(breaker.failure_threshold > 0, "CARDANO_BREAKER_FAILURE_THRESHOLD", "must be at least 1");
+        let ai_retry = settings.retry_policy("AI_RETRY", RetryPolicy::AI);
+        let cardano_retry = settings.retry_policy("CARDANO_RETRY", RetryPolicy::CARDANO);
+        
+        let defaults = StartupConfig::default();
+        let startup = StartupConfig {
+            deadline: Duration::from_secs(
+                settings.parsed("STARTUP_DEPENDENCY_TIMEOUT_SECS", defaults.deadline.as_secs(), SECONDS),
+            ),
+            require_cardano: settings.parsed("STARTUP_REQUIRE_CARDANO", defaults.require_cardano, "true or false"),
+        };
+        settings.require(!startup.deadline.is_zero(), "STARTUP_DEPENDENCY_TIMEOUT_SECS", "must be at least 1");
+        
+        let defaults = DisplayPrecision::default();
+        let display = DisplayPrecision {
+            percentage_decimals: settings.parsed(
+                "DISPLAY_PERCENTAGE_DECIMALS",
+                defaults.percentage_decimals,
+                WHOLE_NUMBER,
+            ),
+            confidence_decimals: settings.parsed(
+                "DISPLAY_CONFIDENCE_DECIMALS",
+                defaults.confidence_decimals,
+                WHOLE_NUMBER,
+            ),
+        };
+        for (key, decimals) in [
+            ("DISPLAY_PERCENTAGE_DECIMALS", display.percentage_decimals),
+            ("DISPLAY_CONFIDENCE_DECIMALS", display.confidence_decimals),
+        ] {
+            let message = format!("must be at most {}", MAX_DISPLAY_DECIMALS);
+            settings.require(decimals <= MAX_DISPLAY_DECIMALS, key, &message);
+        }
+        
+        settings.check_fee_cap();
+        for key in TIMEOUTS {
+            if let Some(secs) = settings.optional::<u64>(key, SECONDS) {
+                settings.require(secs > 0, key, "must be at least 1");
+            }
+        }
+        for key in COUNTS {
+            if let Some(count) = settings.optional::<u32>(key, WHOLE_NUMBER) {
+                settings.require(count > 0, key, "must be at least 1");
+            }
+        }
+        settings.optional::<usize>("AUTO_NEGOTIATION_WORKERS", WHOLE_NUMBER);
+        settings.optional::<u64>("SHUTDOWN_DRAIN_SECS", SECONDS);
+        if let Some(retries) = settings.optional::<i32>("SETTLEMENT_MAX_RETRIES", WHOLE_NUMBER) {
+            settings.require(retries >= 0, "SETTLEMENT_MAX_RETRIES", "must not be negative");
+        }
+        for (burst_key, rate_key) in RATE_LIMITS {
+            if let Some(burst) = settings.optional::<f64>(burst_key, "a number") {
+                settings.require(burst.is_finite() && burst >= 1.0, burst_key, "must be at least 1");
+            }
+            if let Some(rate) = settings.optional::<f64>(rate_key, "a number") {
+                settings.require(rate.is_finite() && rate >= 0.0, rate_key, "must not be negative");
+            }
+        }
+        for key in FLAGS {
+            settings.optional::<bool>(key, "true or false");
+        }
+        settings.optional::<AiMode>("AI_MODE", "Enabled or Disabled");
+        settings.optional::<u64>("SETTLEMENT_SIMULATION_SEED", WHOLE_NUMBER);
+        settings.optional::<ReminderHours>("SETTLEMENT_REMINDER_HOURS", "a comma-separated list of whole hours");
+        settings.optional::<AnchoringStrategy>(
+            "SETTLEMENT_ANCHORING_STRATEGY",
+            "leverage_proportional, fixed_percentage:<percent> or statutory_damages",
+        );
+        if let Some(confidence) = settings.optional::<f64>("EMAIL_REPLY_MIN_CONFIDENCE", "a number") {
+            settings.require(
+                (0.0..=1.0).contains(&confidence),
+                "EMAIL_REPLY_MIN_CONFIDENCE",
+                "must be between 0 and 1",
+            );
+        }
+        for key in ["ESCROW_REFUND_AFTER_HOURS", "SETTLEMENT_DRAFT_EXPIRY_HOURS"] {
+            if let Some(hours) = settings.window(key, "hours", MAX_WINDOW_HOURS) {
+                settings.require(hours > 0, key, "must be at least 1");
+            }
+        }
+        for key in ["SETTLEMENT_RESCISSION_WINDOW_HOURS", "VIOLATION_DEDUP_WINDOW_HOURS"] {
+            settings.window(key, "hours", MAX_WINDOW_HOURS);
+        }
+        if let Some(days) = settings.window("SETTLEMENT_EXPIRY_DAYS", "days", MAX_WINDOW_DAYS) {
+            settings.require(days > 0, "SETTLEMENT_EXPIRY_DAYS", "must be at least 1");
+        }
+        if let Some(days) = settings.optional::<u32>("DEMAND_LETTER_RESPONSE_DAYS", "a whole number of days") {
+            settings.require(
+                (1..=MAX_RESPONSE_DAYS).contains(&days),
+                "DEMAND_LETTER_RESPONSE_DAYS",
+                &format!("must be between 1 and {}", MAX_RESPONSE_DAYS),
+            );
+        }
+        for key in SERVICE_URLS {
+            if settings.get(key).is_some() {
+                settings.url(key, &["http", "https"]);
+            }
+        }
+        
+        if !settings.problems.is_empty() {
+            return Err(ConfigError(settings.problems));
+        }
+        Ok(Self {
+            database_url: database_url.unwrap_or_default(),
+            pool,
+            cardano_node_url: cardano_node_url.unwrap_or_default(),
+            breaker,
+            ai_retry,
+            cardano_retry,
+            port,
+            shutdown_timeout: Duration::from_secs(shutdown_timeout),
+            startup,
+            display,
+        })
+    }
+}
+
+/// Reads settings through `lookup`, noting each problem and carrying on
+/// with a stand-in value so the rest still get checked.
+struct Settings<F> {
+    lookup: F,
+    problems: Vec<String>,
+}
+
+impl<F: Fn(&str) -> Option<String>> Settings<F> {
+    /// The trimmed value of `key`; blank counts as unset.
+    fn get(&self, key: &str) -> Option<String> {
+        (self.lookup)(key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+    
+    fn require(&mut self, ok: bool, key: &str, message: &str) {
+        if !ok {
+            self.problems.push(format!("{} {}", key, message));
+        }
+    }
+    
+    /// `key` parsed, or `default` when it is unset or doesn't parse.
+    fn parsed<T: FromStr>(&mut self, key: &str, default: T, expected: &str) -> T {
+        self.optional(key, expected).unwrap_or(default)
+    }
+    
+    /// `key` parsed, if it is set; `expected` describes a valid value.
+    fn optional<T: FromStr>(&mut self, key: &str, expected: &str) -> Option<T> {
+        let value = self.get(key)?;
+        let parsed = value.parse().ok();
+        if parsed.is_none() {
+            self.problems.push(format!("{} must be {}, not {:?}", key, expected, value));
+        }
+        parsed
+    }
+    
+    /// A whole number of `unit`, if set, that must not exceed `max`.
+    fn window(&mut self, key: &str, unit: &str, max: u32) -> Option<u32> {
+        let value = self.optional::<u32>(key, &format!("a whole number of {}", unit))?;
+        self.require(value <= max, key, &format!("must be at most {}", max));
+        Some(value)
+    }
+    
+    /// A URL that must be set and use one of `schemes`.
+    fn url(&mut self, key: &str, schemes: &[&str]) -> Option<String> {
+        let Some(value) = self.get(key) else {
+            self.problems.push(format!("{} must be set", key));
+            return None;
+        };
+        match Url::parse(&value) {
+            Ok(url) if schemes.contains(&url.scheme()) => Some(value),
+            Ok(url) => {
+                self.problems.push(format!(
+                    "{} must be a {} URL, not {}",
+                    key,
+                    schemes.join(" or "),
+                    url.scheme()
+                ));
+                None
+            }
+            Err(e) => {
+                self.problems.push(format!("{} is not a URL: {}", key, e));
+                None
+            }
+        }
+    }
+    
+    /// A retry policy from `<prefix>_MAX_ATTEMPTS`, `<prefix>_BASE_DELAY_MS`,
+    /// `<prefix>_MAX_DELAY_MS` and `<prefix>_JITTER`, each `defaults` unless set.
+    fn retry_policy(&mut self, prefix: &str, defaults: RetryPolicy) -> RetryPolicy {
+        let attempts_key = format!("{}_MAX_ATTEMPTS", prefix);
+        let base_key = format!("{}_BASE_DELAY_MS", prefix);
+        let max_key = format!("{}_MAX_DELAY_MS", prefix);
+        let mut millis = |key: &str, default: Duration| {
+            Duration::from_millis(self.parsed(key, default.as_millis() as u64, MILLISECONDS))
+        };
+        let base_delay = millis(&base_key, defaults.base_delay);
+        let max_delay = millis(&max_key, defaults.max_delay);
+        let policy = RetryPolicy {
+            max_attempts: self.parsed(&attempts_key, defaults.max_attempts, WHOLE_NUMBER),
+            base_delay,
+            max_delay,
+            jitter: self.parsed(&format!("{}_JITTER", prefix), defaults.jitter, "true or false"),
+        };
+        self.require(policy.max_attempts > 0, &attempts_key, "must be at least 1");
+        self.require(policy.max_delay >= policy.base_delay, &max_key, &format!("must be at least {}", base_key));
+        policy
+    }
+    
+    /// The fee cap may be an amount or a share of the settled amount; a
+    /// negative amount or a share outside 0-100% can never be charged.
+    fn check_fee_cap(&mut self) {
+        let zero = BigDecimal::from(0);
+        if let Some(amount) = self.optional::<BigDecimal>("PLATFORM_FEE_CAP_AMOUNT", "a decimal amount") {
+            self.require(amount >= zero, "PLATFORM_FEE_CAP_AMOUNT", "must not be negative");
+        }
+        if let Some(percent) = self.optional::<BigDecimal>("PLATFORM_FEE_CAP_PERCENT_OF_SETTLED", "a percentage") {
+            self.require(
+                percent >= zero && percent <= BigDecimal::from(100),
+                "PLATFORM_FEE_CAP_PERCENT_OF_SETTLED",
+                "must be between 0 and 100",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    
+    use super::*;
+    
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Config::from_lookup(|key| vars.get(key).cloned())
+    }
+    
+    const REQUIRED: &[(&str, &str)] = &[
+        ("DATABASE_URL", "postgres://localhost/settlements"),
+        ("CARDANO_NODE_URL", "http://localhost:3100"),
+    ];
+    
+    #[test]
+    fn defaults_fill_in_everything_but_the_urls() {
+        let config = load(REQUIRED).unwrap();
+        
+        assert_eq!(config.port, DEFAULT_PORT);
+        assert_eq!(config.pool.max_connections, PoolConfig::default().max_connections);
+        assert_eq!(config.breaker, BreakerConfig::default());
+        assert_eq!(config.cardano_retry, RetryPolicy::CARDANO);
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(30));
+        assert_eq!(config.startup, StartupConfig::default());
+        assert_eq!(config.display, DisplayPrecision::default());
+    }
+    
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let problems = load(&[
+            ("DATABASE_URL", "mysql://localhost/settlements"),
+            ("SETTLEMENT_SERVICE_PORT", "80800"),
+            ("DATABASE_MAX_CONNECTIONS", "0"),
+            ("AI_RETRY_MAX_ATTEMPTS", "0"),
+            ("CARDANO_RETRY_BASE_DELAY_MS", "500"),
+            ("CARDANO_RETRY_MAX_DELAY_MS", "100"),
+            ("PLATFORM_FEE_CAP_AMOUNT", "-5"),
+            ("PLATFORM_FEE_CAP_PERCENT_OF_SETTLED", "120"),
+            ("AI_CALL_TIMEOUT_SECS", "soon"),
+            ("AI_FALLBACK_ENABLED", "yes"),
+            ("AI_MODE", "off"),
+            ("AI_SERVICE_URL", "not a url"),
+            ("SETTLEMENT_REMINDER_HOURS", "72,0"),
+            ("SETTLEMENT_ANCHORING_STRATEGY", "fixed_percentage:140"),
+            ("EMAIL_REPLY_MIN_CONFIDENCE", "70"),
+            ("ESCROW_REFUND_AFTER_HOURS", "0"),
+            ("SETTLEMENT_DRAFT_EXPIRY_HOURS", "a week"),
+            ("DEMAND_LETTER_RESPONSE_DAYS", "365"),
+            ("DISPLAY_CONFIDENCE_DECIMALS", "16"),
+            ("RECONCILIATION_INTERVAL_SECS", "0"),
+            ("WEBHOOK_MAX_ATTEMPTS", "-1"),
+            ("SETTLEMENT_MAX_RETRIES", "-3"),
+            ("RATE_LIMIT_USER_BURST", "0.5"),
+            ("RATE_LIMIT_CREDITOR_PER_SEC", "-1"),
+            ("SETTLEMENT_RESCISSION_WINDOW_HOURS", "72h"),
+            ("SETTLEMENT_EXPIRY_DAYS", "999999999"),
+        ])
+        .unwrap_err()
+        .0;
+        
+        assert_eq!(
+            problems,
+            [
+                "DATABASE_URL must be a postgres or postgresql URL, not mysql",
+                "CARDANO_NODE_URL must be set",
+                "SETTLEMENT_SERVICE_PORT must be a port number, not \"80800\"",
+                "DATABASE_MAX_CONNECTIONS must be at least 1",
+                "AI_RETRY_MAX_ATTEMPTS must be at least 1",
+                "CARDANO_RETRY_MAX_DELAY_MS must be at least CARDANO_RETRY_BASE_DELAY_MS",
+                "DISPLAY_CONFIDENCE_DECIMALS must be at most 15",
+                "PLATFORM_FEE_CAP_AMOUNT must not be negative",
+                "PLATFORM_FEE_CAP_PERCENT_OF_SETTLED must be between 0 and 100",
+                "AI_CALL_TIMEOUT_SECS must be a whole number of seconds, not \"soon\"",
+                "RECONCILIATION_INTERVAL_SECS must be at least 1",
+                "WEBHOOK_MAX_ATTEMPTS must be a whole number, not \"-1\"",
+                "SETTLEMENT_MAX_RETRIES must not be negative",
+                "RATE_LIMIT_USER_BURST must be at least 1",
+                "RATE_LIMIT_CREDITOR_PER_SEC must not be negative",
+                "AI_FALLBACK_ENABLED must be true or false, not \"yes\"",
+                "AI_MODE must be Enabled or Disabled, not \"off\"",
+                "SETTLEMENT_REMINDER_HOURS must be a comma-separated list of whole hours, not \"72,0\"",
+                "SETTLEMENT_ANCHORING_STRATEGY must be leverage_proportional, fixed_percentage:<percent> or \
+                 statutory_damages, not \"fixed_percentage:140\"",
+                "EMAIL_REPLY_MIN_CONFIDENCE must be between 0 and 1",
+                "ESCROW_REFUND_AFTER_HOURS must be at least 1",
+                "SETTLEMENT_DRAFT_EXPIRY_HOURS must be a whole number of hours, not \"a week\"",
+                "SETTLEMENT_RESCISSION_WINDOW_HOURS must be a whole number of hours, not \"72h\"",
+                "SETTLEMENT_EXPIRY_DAYS must be at most 36500",
+                "DEMAND_LETTER_RESPONSE_DAYS must be between 1 and 90",
+                "AI_SERVICE_URL is not a URL: relative URL without a base",
+            ]
+        );
+    }
+    
+    #[test]
+    fn set_values_override_the_defaults() {
+        let mut vars = REQUIRED.to_vec();
+        vars.extend([
+            ("SETTLEMENT_SERVICE_PORT", " 9000 "),
+            ("CARDANO_BREAKER_OPEN_SECS", "5"),
+            ("HTTP_SHUTDOWN_TIMEOUT_SECS", ""),
+            ("STARTUP_DEPENDENCY_TIMEOUT_SECS", "120"),
+            ("STARTUP_REQUIRE_CARDANO", "false"),
+            ("DISPLAY_PERCENTAGE_DECIMALS", "1"),
+        ]);
+        let config = load(&vars).unwrap();
+        
+        assert_eq!(config.port, 9000);
+        assert_eq!(
+            config.startup,
+            StartupConfig { deadline: Duration::from_secs(120), require_cardano: false }
+        );
+        assert_eq!(config.breaker.open_for, Duration::from_secs(5));
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(30));
+        assert_eq!(config.display.percentage_decimals, 1);
+        assert_eq!(config.display.confidence_decimals, 2);
+    }
+}
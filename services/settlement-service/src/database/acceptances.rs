@@ -0,0 +1,75 @@
+use uuid::Uuid;
+
+use crate::models::SettlementAcceptance;
+use super::Database;
+
+impl Database {
+    /// Everyone who owes the settlement's debts, each once, in no
+    /// particular order.
+    pub async fn get_settlement_debtors(&self, settlement_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT DISTINCT debtor
+            FROM debts, unnest(debts.debtors || debts.user_id) AS debtor
+            WHERE debts.id IN (
+                SELECT debt_id FROM settlements WHERE id = $1
+                UNION
+                SELECT debt_id FROM settlement_debts WHERE settlement_id = $1
+            )
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    pub async fn get_settlement_acceptances(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<Vec<SettlementAcceptance>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementAcceptance>(
+            "SELECT * FROM settlement_acceptances WHERE settlement_id = $1 ORDER BY accepted_at",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Records the debtor's acceptance, replacing any they gave before.
+    pub async fn record_acceptance(&self, acceptance: &SettlementAcceptance) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO settlement_acceptances
+                (settlement_id, debtor_id, settled_amount, platform_fee, clauses_hash, signed, envelope_id, accepted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (settlement_id, debtor_id) DO UPDATE
+            SET settled_amount = EXCLUDED.settled_amount,
+                platform_fee = EXCLUDED.platform_fee,
+                clauses_hash = EXCLUDED.clauses_hash,
+                signed = EXCLUDED.signed,
+                envelope_id = EXCLUDED.envelope_id,
+                accepted_at = EXCLUDED.accepted_at
+            "#,
+        )
+        .bind(acceptance.settlement_id)
+        .bind(acceptance.debtor_id)
+        .bind(&acceptance.settled_amount)
+        .bind(&acceptance.platform_fee)
+        .bind(&acceptance.clauses_hash)
+        .bind(acceptance.signed)
+        .bind(&acceptance.envelope_id)
+        .bind(acceptance.accepted_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    
+    /// Forgets every acceptance of the settlement, once it is rescinded.
+    pub async fn clear_acceptances(&self, settlement_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM settlement_acceptances WHERE settlement_id = $1")
+            .bind(settlement_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{AutoNegotiationJob, AutoNegotiationStatus};
+use super::Database;
+
+impl Database {
+    /// Enqueues `job` unless one already exists for its user, creditor and
+    /// event, in which case that one is returned with `false`.
+    pub async fn enqueue_auto_negotiation_job(
+        &self,
+        job: &AutoNegotiationJob,
+    ) -> Result<(AutoNegotiationJob, bool), sqlx::Error> {
+        let inserted = sqlx::query_as::<_, AutoNegotiationJob>(
+            r#"
+            INSERT INTO auto_negotiation_jobs
+                (id, user_id, creditor_id, trigger, event_id, violations, jurisdiction, strategy, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (user_id, creditor_id, event_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.user_id)
+        .bind(job.creditor_id)
+        .bind(&job.trigger)
+        .bind(&job.event_id)
+        .bind(&job.violations)
+        .bind(&job.jurisdiction)
+        .bind(job.strategy)
+        .bind(job.status)
+        .bind(job.created_at)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        if let Some(inserted) = inserted {
+            return Ok((inserted, true));
+        }
+        
+        let existing = sqlx::query_as::<_, AutoNegotiationJob>(
+            "SELECT * FROM auto_negotiation_jobs WHERE user_id = $1 AND creditor_id = $2 AND event_id = $3",
+        )
+        .bind(job.user_id)
+        .bind(job.creditor_id)
+        .bind(&job.event_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((existing, false))
+    }
+    
+    /// Claims the oldest pending job for a worker, moving it to `running`.
+    /// A job left `running` since before `stale_before` belonged to a worker
+    /// that died and is claimed again. Concurrent workers never get the same job.
+    pub async fn claim_auto_negotiation_job(
+        &self,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<AutoNegotiationJob>, sqlx::Error> {
+        sqlx::query_as::<_, AutoNegotiationJob>(
+            r#"
+            UPDATE auto_negotiation_jobs
+            SET status = 'running', attempts = attempts + 1, started_at = NOW()
+            WHERE id = (
+                SELECT id FROM auto_negotiation_jobs
+                WHERE status = 'pending' OR (status = 'running' AND started_at < $1)
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(stale_before)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Returns a claimed job to the queue without counting the attempt.
+    pub async fn release_auto_negotiation_job(&self, id: Uuid) -> Result<AutoNegotiationJob, sqlx::Error> {
+        sqlx::query_as::<_, AutoNegotiationJob>(
+            r#"
+            UPDATE auto_negotiation_jobs
+            SET status = 'pending', attempts = attempts - 1, started_at = NULL
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn finish_auto_negotiation_job(
+        &self,
+        id: Uuid,
+        status: AutoNegotiationStatus,
+        settlement_id: Option<Uuid>,
+        last_error: Option<&str>,
+    ) -> Result<AutoNegotiationJob, sqlx::Error> {
+        sqlx::query_as::<_, AutoNegotiationJob>(
+            r#"
+            UPDATE auto_negotiation_jobs
+            SET status = $2, settlement_id = $3, last_error = $4, finished_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(settlement_id)
+        .bind(last_error)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,88 @@
+use bigdecimal::BigDecimal;
+use uuid::Uuid;
+
+use crate::models::{CreditorEmail, CreditorEmailStatus, ReplyIntent};
+use super::Database;
+
+impl Database {
+    /// Records `email` as received unless a message with its id already was,
+    /// in which case that one is returned with `false`.
+    pub async fn claim_creditor_email(&self, email: &CreditorEmail) -> Result<(CreditorEmail, bool), sqlx::Error> {
+        let inserted = sqlx::query_as::<_, CreditorEmail>(
+            r#"
+            INSERT INTO creditor_emails (id, message_id, sender, subject, body, status, received_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (message_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(email.id)
+        .bind(&email.message_id)
+        .bind(&email.sender)
+        .bind(&email.subject)
+        .bind(&email.body)
+        .bind(email.status)
+        .bind(email.received_at)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        if let Some(inserted) = inserted {
+            return Ok((inserted, true));
+        }
+        
+        let existing = sqlx::query_as::<_, CreditorEmail>("SELECT * FROM creditor_emails WHERE message_id = $1")
+            .bind(&email.message_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok((existing, false))
+    }
+    
+    /// Records what was done about a received email.
+    pub async fn record_creditor_email_outcome(
+        &self,
+        id: Uuid,
+        settlement_id: Option<Uuid>,
+        intent: Option<ReplyIntent>,
+        amount: Option<&BigDecimal>,
+        status: CreditorEmailStatus,
+        review_reason: Option<&str>,
+    ) -> Result<CreditorEmail, sqlx::Error> {
+        sqlx::query_as::<_, CreditorEmail>(
+            r#"
+            UPDATE creditor_emails
+            SET settlement_id = $2, intent = $3, amount = $4, status = $5, review_reason = $6,
+                processed_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(settlement_id)
+        .bind(intent)
+        .bind(amount)
+        .bind(status)
+        .bind(review_reason)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// Emails in `status`, oldest first, so the longest waiting are read first.
+    pub async fn get_creditor_emails(
+        &self,
+        status: CreditorEmailStatus,
+        limit: i64,
+    ) -> Result<Vec<CreditorEmail>, sqlx::Error> {
+        sqlx::query_as::<_, CreditorEmail>(
+            r#"
+            SELECT * FROM creditor_emails
+            WHERE status = $1
+            ORDER BY received_at, id
+            LIMIT $2
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,306 @@
+use uuid::Uuid;
+
+use crate::models::{AgreementClause, ClauseTemplate, CreditorContact, CreditorProfile, ExecutionMode};
+use super::settlements::advisory_key;
+use super::Database;
+
+impl Database {
+    /// The creditor's override of the proposal expiry window, if it has one.
+    pub async fn get_proposal_expiry_days(&self, creditor_id: Uuid) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<i32>>("SELECT proposal_expiry_days FROM creditor_settings WHERE creditor_id = $1")
+            .bind(creditor_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(Option::flatten)
+    }
+    
+    /// The least the creditor will settle for, as a percentage of the balance.
+    pub async fn get_settlement_floor_percentage(&self, creditor_id: Uuid) -> Result<Option<f64>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<f64>>(
+            "SELECT settlement_floor_percentage FROM creditor_settings WHERE creditor_id = $1",
+        )
+        .bind(creditor_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Option::flatten)
+    }
+    
+    /// Sets or, with `None`, clears the creditor's settlement floor.
+    pub async fn set_settlement_floor_percentage(
+        &self,
+        creditor_id: Uuid,
+        percentage: Option<f64>,
+    ) -> Result<Option<f64>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<f64>>(
+            r#"
+            INSERT INTO creditor_settings (creditor_id, settlement_floor_percentage)
+            VALUES ($1, $2)
+            ON CONFLICT (creditor_id) DO UPDATE
+            SET settlement_floor_percentage = EXCLUDED.settlement_floor_percentage
+            RETURNING settlement_floor_percentage
+            "#,
+        )
+        .bind(creditor_id)
+        .bind(percentage)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// How the creditor wants its settlements paid, if it has said.
+    pub async fn get_execution_mode(&self, creditor_id: Uuid) -> Result<Option<ExecutionMode>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<ExecutionMode>>(
+            "SELECT execution_mode FROM creditor_settings WHERE creditor_id = $1",
+        )
+        .bind(creditor_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Option::flatten)
+    }
+    
+    pub async fn set_execution_mode(
+        &self,
+        creditor_id: Uuid,
+        mode: ExecutionMode,
+    ) -> Result<ExecutionMode, sqlx::Error> {
+        sqlx::query_scalar::<_, ExecutionMode>(
+            r#"
+            INSERT INTO creditor_settings (creditor_id, execution_mode)
+            VALUES ($1, $2)
+            ON CONFLICT (creditor_id) DO UPDATE
+            SET execution_mode = EXCLUDED.execution_mode
+            RETURNING execution_mode
+            "#,
+        )
+        .bind(creditor_id)
+        .bind(mode)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// A settlement counts as accepted once it has left negotiation by
+    /// acceptance, however it ended afterwards.
+    pub async fn get_creditor_profile(&self, creditor_id: Uuid) -> Result<CreditorProfile, sqlx::Error> {
+        sqlx::query_as::<_, CreditorProfile>(
+            r#"
+            WITH creditor_settlements AS (
+                SELECT s.* FROM settlements s
+                JOIN debts d ON d.id = s.debt_id
+                WHERE d.creditor_id = $1
+            ),
+            outcomes AS (
+                SELECT
+                    COUNT(*) AS total,
+                    COUNT(*) FILTER (
+                        WHERE status IN ('accepted', 'completed', 'failed', 'escrowed', 'refunded')
+                    ) AS accepted,
+                    COUNT(*) FILTER (WHERE status NOT IN ('proposed', 'negotiating')) AS decided,
+                    COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                    COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                    AVG(saved_amount * 100 / NULLIF(original_amount, 0))
+                        FILTER (WHERE status = 'completed') AS average_reduction
+                FROM creditor_settlements
+            )
+            SELECT
+                $1 AS creditor_id,
+                (SELECT COUNT(*) FROM violations WHERE creditor_id = $1) AS total_violations,
+                (SELECT AVG((analysis->>'total_leverage_score')::float8)
+                 FROM leverage_snapshots WHERE creditor_id = $1) AS average_leverage_score,
+                total AS total_settlements,
+                decided AS decided_settlements,
+                accepted::float8 / NULLIF(decided, 0) AS acceptance_rate,
+                average_reduction::float8 AS average_reduction_percentage,
+                completed AS completed_settlements,
+                failed AS failed_settlements
+            FROM outcomes
+            "#,
+        )
+        .bind(creditor_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn upsert_creditor_contact(&self, contact: &CreditorContact) -> Result<CreditorContact, sqlx::Error> {
+        sqlx::query_as::<_, CreditorContact>(
+            r#"
+            INSERT INTO creditor_contacts (creditor_id, email, mailing_address, preferred_channel, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (creditor_id) DO UPDATE
+            SET email = EXCLUDED.email,
+                mailing_address = EXCLUDED.mailing_address,
+                preferred_channel = EXCLUDED.preferred_channel,
+                updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(contact.creditor_id)
+        .bind(&contact.email)
+        .bind(&contact.mailing_address)
+        .bind(contact.preferred_channel)
+        .bind(contact.updated_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn get_creditor_contact(&self, creditor_id: Uuid) -> Result<Option<CreditorContact>, sqlx::Error> {
+        sqlx::query_as::<_, CreditorContact>("SELECT * FROM creditor_contacts WHERE creditor_id = $1")
+            .bind(creditor_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// The creditor's clauses in force, in agreement order.
+    pub async fn get_creditor_clauses(&self, creditor_id: Uuid) -> Result<Vec<AgreementClause>, sqlx::Error> {
+        sqlx::query_as::<_, AgreementClause>(
+            "SELECT * FROM creditor_clauses WHERE creditor_id = $1 AND retired_at IS NULL ORDER BY position",
+        )
+        .bind(creditor_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// The clauses in force for the creditor the settlement's debt is owed
+    /// to, in agreement order.
+    pub async fn get_settlement_creditor_clauses(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<Vec<AgreementClause>, sqlx::Error> {
+        sqlx::query_as::<_, AgreementClause>(
+            r#"
+            SELECT c.* FROM creditor_clauses c
+            JOIN debts d ON d.creditor_id = c.creditor_id
+            JOIN settlements s ON s.debt_id = d.id
+            WHERE s.id = $1 AND c.retired_at IS NULL
+            ORDER BY c.position
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Puts `clauses` in force for the creditor, in order. A clause reworded
+    /// gets the next version of its key and the old version is retired, as
+    /// is every clause left out; the rest keep their version.
+    pub async fn replace_creditor_clauses(
+        &self,
+        creditor_id: Uuid,
+        clauses: &[ClauseTemplate],
+    ) -> Result<Vec<AgreementClause>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // Serializes replacements of the same creditor's clauses.
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(advisory_key(creditor_id))
+            .execute(&mut *tx)
+            .await?;
+        let current = sqlx::query_as::<_, AgreementClause>(
+            "SELECT * FROM creditor_clauses WHERE creditor_id = $1 AND retired_at IS NULL",
+        )
+        .bind(creditor_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        for clause in &current {
+            let kept = clauses
+                .iter()
+                .any(|c| c.key == clause.key && c.title == clause.title && c.body == clause.body);
+            if !kept {
+                sqlx::query(
+                    r#"
+                    UPDATE creditor_clauses SET retired_at = NOW()
+                    WHERE creditor_id = $1 AND clause_key = $2 AND version = $3
+                    "#,
+                )
+                .bind(creditor_id)
+                .bind(&clause.key)
+                .bind(clause.version)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        for (position, clause) in clauses.iter().enumerate() {
+            let unchanged = current
+                .iter()
+                .find(|c| c.key == clause.key && c.title == clause.title && c.body == clause.body);
+            if let Some(unchanged) = unchanged {
+                sqlx::query(
+                    r#"
+                    UPDATE creditor_clauses SET position = $4, required = $5
+                    WHERE creditor_id = $1 AND clause_key = $2 AND version = $3
+                    "#,
+                )
+                .bind(creditor_id)
+                .bind(&clause.key)
+                .bind(unchanged.version)
+                .bind(position as i32)
+                .bind(clause.required)
+                .execute(&mut *tx)
+                .await?;
+            } else {
+                // A key brought back after being dropped carries on from its
+                // last version.
+                sqlx::query(
+                    r#"
+                    INSERT INTO creditor_clauses (creditor_id, clause_key, version, position, title, body, required)
+                    SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4, $5, $6
+                    FROM creditor_clauses WHERE creditor_id = $1 AND clause_key = $2
+                    "#,
+                )
+                .bind(creditor_id)
+                .bind(&clause.key)
+                .bind(position as i32)
+                .bind(&clause.title)
+                .bind(&clause.body)
+                .bind(clause.required)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        
+        let replaced = sqlx::query_as::<_, AgreementClause>(
+            "SELECT * FROM creditor_clauses WHERE creditor_id = $1 AND retired_at IS NULL ORDER BY position",
+        )
+        .bind(creditor_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(replaced)
+    }
+    
+    /// The clause versions agreed to when the settlement was accepted, in
+    /// agreement order, including any retired since.
+    pub async fn get_agreed_clauses(&self, settlement_id: Uuid) -> Result<Vec<AgreementClause>, sqlx::Error> {
+        sqlx::query_as::<_, AgreementClause>(
+            r#"
+            SELECT c.* FROM settlement_clauses a
+            JOIN creditor_clauses c
+                ON c.creditor_id = a.creditor_id AND c.clause_key = a.clause_key AND c.version = a.version
+            WHERE a.settlement_id = $1
+            ORDER BY a.position
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// The required clauses in force for the settlement's creditor that the
+    /// settlement was accepted without any version of.
+    pub async fn get_missing_required_clauses(&self, settlement_id: Uuid) -> Result<Vec<AgreementClause>, sqlx::Error> {
+        sqlx::query_as::<_, AgreementClause>(
+            r#"
+            SELECT c.* FROM creditor_clauses c
+            JOIN debts d ON d.creditor_id = c.creditor_id
+            JOIN settlements s ON s.debt_id = d.id
+            WHERE s.id = $1 AND c.retired_at IS NULL AND c.required
+            AND NOT EXISTS (
+                SELECT 1 FROM settlement_clauses a
+                WHERE a.settlement_id = $1 AND a.clause_key = c.clause_key
+            )
+            ORDER BY c.position
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,63 @@
+use uuid::Uuid;
+
+use crate::models::{Debt, DebtVerification};
+use super::Database;
+
+impl Database {
+    pub async fn get_debt(&self, id: Uuid) -> Result<Option<Debt>, sqlx::Error> {
+        sqlx::query_as::<_, Debt>("SELECT * FROM debts WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Most recent active debt the user owes to the creditor, used when a
+    /// proposal is requested without an explicit `debt_id`.
+    pub async fn find_active_debt(&self, user_id: Uuid, creditor_id: Uuid) -> Result<Option<Debt>, sqlx::Error> {
+        sqlx::query_as::<_, Debt>(
+            r#"
+            SELECT * FROM debts
+            WHERE user_id = $1 AND creditor_id = $2 AND status = 'active'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(creditor_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Appends to the debt's validation history and moves the debt to the
+    /// recorded status, atomically.
+    pub async fn record_debt_verification(&self, verification: &DebtVerification) -> Result<Option<Debt>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        
+        let debt = sqlx::query_as::<_, Debt>(
+            "UPDATE debts SET verification_status = $2 WHERE id = $1 RETURNING *",
+        )
+        .bind(verification.debt_id)
+        .bind(verification.status)
+        .fetch_optional(&mut *tx)
+        .await?;
+        
+        if debt.is_some() {
+            sqlx::query(
+                r#"
+                INSERT INTO debt_verifications (id, debt_id, status, note, created_at)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(verification.id)
+            .bind(verification.debt_id)
+            .bind(verification.status)
+            .bind(&verification.note)
+            .bind(verification.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        Ok(debt)
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::SettlementDraft;
+use super::settlements::advisory_key;
+use super::Database;
+
+impl Database {
+    pub async fn insert_draft(&self, draft: &SettlementDraft) -> Result<SettlementDraft, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDraft>(
+            r#"
+            INSERT INTO settlement_drafts (id, user_id, kind, fields, version, expires_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(draft.id)
+        .bind(draft.user_id)
+        .bind(draft.kind)
+        .bind(&draft.fields)
+        .bind(draft.version)
+        .bind(draft.expires_at)
+        .bind(draft.created_at)
+        .bind(draft.updated_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// The draft, unless it has expired.
+    pub async fn get_draft(&self, id: Uuid) -> Result<Option<SettlementDraft>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDraft>("SELECT * FROM settlement_drafts WHERE id = $1 AND expires_at > NOW()")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// The user's drafts not yet finalized or expired, last saved first.
+    pub async fn get_user_drafts(&self, user_id: Uuid) -> Result<Vec<SettlementDraft>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDraft>(
+            r#"
+            SELECT * FROM settlement_drafts
+            WHERE user_id = $1 AND settlement_id IS NULL AND expires_at > NOW()
+            ORDER BY updated_at DESC, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Saves `set` over the draft's fields and drops those `cleared`. `None`
+    /// if the draft has expired or been finalized.
+    pub async fn update_draft_fields(
+        &self,
+        id: Uuid,
+        set: &Map<String, Value>,
+        cleared: &[String],
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<SettlementDraft>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDraft>(
+            r#"
+            UPDATE settlement_drafts
+            SET fields = (fields || $2) - $3::text[],
+                version = version + 1,
+                expires_at = $4,
+                updated_at = NOW()
+            WHERE id = $1 AND settlement_id IS NULL AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(Value::Object(set.clone()))
+        .bind(cleared)
+        .bind(expires_at)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Records the settlement the draft became, if it is still the version
+    /// finalized and not finalized already.
+    pub async fn finish_draft(
+        &self,
+        id: Uuid,
+        version: i32,
+        settlement_id: Uuid,
+    ) -> Result<Option<SettlementDraft>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDraft>(
+            r#"
+            UPDATE settlement_drafts
+            SET settlement_id = $3, updated_at = NOW()
+            WHERE id = $1 AND version = $2 AND settlement_id IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(settlement_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Takes the draft's advisory lock, held until the returned transaction
+    /// ends, so a save can't land while the draft is being finalized.
+    pub async fn lock_draft(&self, id: Uuid) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(advisory_key(id))
+            .execute(&mut *tx)
+            .await?;
+        Ok(tx)
+    }
+    
+    /// Deletes drafts that expired before `now` and returns how many.
+    pub async fn delete_expired_drafts(&self, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let deleted = sqlx::query("DELETE FROM settlement_drafts WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(deleted.rows_affected())
+    }
+}
@@ -0,0 +1,74 @@
+use uuid::Uuid;
+
+use crate::models::{AuditEvent, AuditEventType};
+use super::Database;
+
+impl Database {
+    pub async fn insert_settlement_event(
+        &self,
+        settlement_id: Uuid,
+        event_type: AuditEventType,
+        actor: &str,
+        metadata: &serde_json::Value,
+    ) -> Result<AuditEvent, sqlx::Error> {
+        sqlx::query_as::<_, AuditEvent>(
+            r#"
+            INSERT INTO settlement_events (settlement_id, event_type, actor, metadata)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(event_type)
+        .bind(actor)
+        .bind(metadata)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn get_settlement_events(&self, settlement_id: Uuid) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        sqlx::query_as::<_, AuditEvent>(
+            "SELECT * FROM settlement_events WHERE settlement_id = $1 ORDER BY id",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Up to `limit` of the settlement's events after `after`, oldest first.
+    pub async fn get_settlement_events_after(
+        &self,
+        settlement_id: Uuid,
+        after: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        sqlx::query_as::<_, AuditEvent>(
+            "SELECT * FROM settlement_events WHERE settlement_id = $1 AND id > $2 ORDER BY id LIMIT $3",
+        )
+        .bind(settlement_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// The settlement's latest `limit` events, oldest first.
+    pub async fn get_recent_settlement_events(
+        &self,
+        settlement_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        sqlx::query_as::<_, AuditEvent>(
+            r#"
+            SELECT * FROM (
+                SELECT * FROM settlement_events WHERE settlement_id = $1 ORDER BY id DESC LIMIT $2
+            ) recent
+            ORDER BY id
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,72 @@
+use uuid::Uuid;
+
+use super::Database;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub response: Option<serde_json::Value>,
+}
+
+impl Database {
+    /// Atomically claims an idempotency key. Returns `true` when this caller now
+    /// owns the key, either because it was new or because the previous claim is
+    /// older than 24 hours. Concurrent callers with the same key race on the
+    /// primary key, so exactly one of them wins.
+    pub async fn claim_idempotency_key(&self, key: &str, request_hash: &str) -> Result<bool, sqlx::Error> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key, request_hash, created_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (key) DO UPDATE
+                SET request_hash = EXCLUDED.request_hash,
+                    settlement_id = NULL,
+                    response = NULL,
+                    created_at = NOW()
+                WHERE idempotency_keys.created_at < NOW() - INTERVAL '24 hours'
+            RETURNING key
+            "#,
+        )
+        .bind(key)
+        .bind(request_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(claimed.is_some())
+    }
+    
+    pub async fn get_idempotency_record(&self, key: &str) -> Result<Option<IdempotencyRecord>, sqlx::Error> {
+        sqlx::query_as::<_, IdempotencyRecord>(
+            "SELECT request_hash, response FROM idempotency_keys WHERE key = $1",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    pub async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        settlement_id: Uuid,
+        response: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE idempotency_keys SET settlement_id = $2, response = $3 WHERE key = $1")
+            .bind(key)
+            .bind(settlement_id)
+            .bind(response)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Drops a claim whose request failed so the client can retry with the same key.
+    pub async fn release_idempotency_key(&self, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND settlement_id IS NULL")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use uuid::Uuid;
+
+use crate::models::Installment;
+use super::Database;
+
+impl Database {
+    /// Inserts a full schedule in one transaction. The `(settlement_id, sequence)`
+    /// unique constraint makes a second plan for the same settlement fail as a
+    /// unique violation rather than interleaving two schedules.
+    pub async fn insert_installments(&self, installments: &[Installment]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        
+        for installment in installments {
+            sqlx::query(
+                r#"
+                INSERT INTO settlement_installments
+                    (id, settlement_id, sequence, amount, due_date, status, paid_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(installment.id)
+            .bind(installment.settlement_id)
+            .bind(installment.sequence)
+            .bind(&installment.amount)
+            .bind(installment.due_date)
+            .bind(installment.status)
+            .bind(installment.paid_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await
+    }
+    
+    pub async fn get_installments(&self, settlement_id: Uuid) -> Result<Vec<Installment>, sqlx::Error> {
+        sqlx::query_as::<_, Installment>(
+            "SELECT * FROM settlement_installments WHERE settlement_id = $1 ORDER BY sequence",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Marks installment `sequence` paid. Returns `None` if there is no such
+    /// installment or it was already paid, so a payment is never recorded twice.
+    pub async fn record_installment_payment(
+        &self,
+        settlement_id: Uuid,
+        sequence: i32,
+    ) -> Result<Option<Installment>, sqlx::Error> {
+        sqlx::query_as::<_, Installment>(
+            r#"
+            UPDATE settlement_installments
+            SET status = 'paid', paid_at = NOW()
+            WHERE settlement_id = $1 AND sequence = $2 AND status <> 'paid'
+            RETURNING *
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(sequence)
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
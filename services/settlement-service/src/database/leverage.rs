@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::models::{LeverageAnalysis, LeverageSnapshot};
+use super::Database;
+
+impl Database {
+    pub async fn insert_leverage_snapshot(
+        &self,
+        creditor_id: Uuid,
+        settlement_id: Option<Uuid>,
+        analysis: &LeverageAnalysis,
+    ) -> Result<LeverageSnapshot, sqlx::Error> {
+        sqlx::query_as::<_, LeverageSnapshot>(
+            r#"
+            INSERT INTO leverage_snapshots (creditor_id, settlement_id, analysis)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(creditor_id)
+        .bind(settlement_id)
+        .bind(Json(analysis))
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// The creditor's snapshots, oldest first, optionally only those taken at
+    /// or after `since`.
+    pub async fn get_leverage_snapshots(
+        &self,
+        creditor_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<LeverageSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, LeverageSnapshot>(
+            r#"
+            SELECT * FROM leverage_snapshots
+            WHERE creditor_id = $1
+              AND ($2::timestamptz IS NULL OR created_at >= $2)
+            ORDER BY created_at, id
+            LIMIT $3
+            "#,
+        )
+        .bind(creditor_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// The latest snapshot per creditor across the user's live settlements.
+    pub async fn get_user_leverage_snapshots(&self, user_id: Uuid) -> Result<Vec<LeverageSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, LeverageSnapshot>(
+            r#"
+            SELECT DISTINCT ON (l.creditor_id) l.*
+            FROM leverage_snapshots l
+            JOIN settlements s ON s.id = l.settlement_id
+            WHERE s.user_id = $1 AND s.deleted_at IS NULL
+            ORDER BY l.creditor_id, l.created_at DESC, l.id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// The analysis most recently snapshotted for `settlement_id`.
+    pub async fn get_settlement_leverage_snapshot(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<Option<LeverageSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, LeverageSnapshot>(
+            r#"
+            SELECT * FROM leverage_snapshots
+            WHERE settlement_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
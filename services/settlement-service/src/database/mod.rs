@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+mod settlements;
+mod installments;
+mod debts;
+mod violations;
+mod idempotency;
+mod negotiations;
+mod events;
+mod users;
+mod webhooks;
+mod creditors;
+mod leverage;
+mod signatures;
+mod auto_negotiation;
+mod reconciliation;
+mod creditor_emails;
+mod drafts;
+mod acceptances;
+
+const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 5;
+
+/// How many connections the pool may open and how long a query waits for
+/// one before failing with `sqlx::Error::PoolTimedOut`, which callers see as
+/// a 503 to retry rather than a request hanging until it times out. Read at
+/// startup from `DATABASE_MAX_CONNECTIONS` (default 10) and
+/// `DATABASE_ACQUIRE_TIMEOUT_SECS` (default 5); see `Config`.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub acquire_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            acquire_timeout: Duration::from_secs(DEFAULT_ACQUIRE_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// Connections in the pool right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    pub in_use: u32,
+    pub idle: u32,
+    pub max: u32,
+}
+
+#[derive(Clone)]
+pub struct Database {
+    pool: PgPool,
+}
+
+impl Database {
+    pub async fn connect_with(database_url: &str, config: PoolConfig) -> anyhow::Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .connect(database_url)
+            .await?;
+        
+        Ok(Self { pool })
+    }
+    
+    pub fn pool_status(&self) -> PoolStatus {
+        let idle = self.pool.num_idle() as u32;
+        PoolStatus {
+            in_use: self.pool.size().saturating_sub(idle),
+            idle,
+            max: self.pool.options().get_max_connections(),
+        }
+    }
+    
+    /// For tests that set up rows no query method writes.
+    #[cfg(test)]
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+    
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use uuid::Uuid;
+
+use crate::models::NegotiationRound;
+use super::Database;
+
+impl Database {
+    /// Appends a round, numbering it after the existing ones. Two concurrent
+    /// counters race on the `(settlement_id, round_number)` unique key, so one
+    /// of them fails instead of both claiming the same round.
+    pub async fn insert_negotiation_round(&self, round: &NegotiationRound) -> Result<NegotiationRound, sqlx::Error> {
+        sqlx::query_as::<_, NegotiationRound>(
+            r#"
+            INSERT INTO negotiation_rounds
+                (id, settlement_id, round_number, from_party, amount, decision, counter_amount, created_at)
+            SELECT $1, $2, COALESCE(MAX(round_number), 0) + 1, $3, $4, $5, $6, $7
+            FROM negotiation_rounds
+            WHERE settlement_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(round.id)
+        .bind(round.settlement_id)
+        .bind(round.from_party)
+        .bind(&round.amount)
+        .bind(round.decision)
+        .bind(&round.counter_amount)
+        .bind(round.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn get_negotiation_rounds(&self, settlement_id: Uuid) -> Result<Vec<NegotiationRound>, sqlx::Error> {
+        sqlx::query_as::<_, NegotiationRound>(
+            "SELECT * FROM negotiation_rounds WHERE settlement_id = $1 ORDER BY round_number",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
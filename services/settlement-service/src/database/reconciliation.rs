@@ -0,0 +1,50 @@
+use uuid::Uuid;
+
+use crate::models::{Discrepancy, Settlement};
+use super::Database;
+
+impl Database {
+    /// Completed settlements with a recorded transaction, in id order after
+    /// `after`, so a pass can page through all of them.
+    pub async fn get_completed_settlements_with_transactions(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            SELECT * FROM settlements
+            WHERE status = 'completed'
+              AND transaction_hash IS NOT NULL
+              AND ($1::UUID IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Settlements in `needs_review`, each with the latest flag that put it
+    /// there, oldest flag first.
+    pub async fn get_discrepancies(&self) -> Result<Vec<Discrepancy>, sqlx::Error> {
+        sqlx::query_as::<_, Discrepancy>(
+            r#"
+            SELECT s.id AS settlement_id, s.reference_number, e.metadata AS flag, e.created_at AS flagged_at
+            FROM settlements s
+            JOIN LATERAL (
+                SELECT metadata, created_at FROM settlement_events
+                WHERE settlement_id = s.id AND event_type = 'flagged_for_review'
+                ORDER BY id DESC
+                LIMIT 1
+            ) e ON TRUE
+            WHERE s.status = 'needs_review'
+            ORDER BY e.created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
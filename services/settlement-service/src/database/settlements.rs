@@ -0,0 +1,803 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{PgExecutor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::{
+    AgreementClause, DueReminder, RejectReason, SavingsSummary, Settlement, SettlementDebt, SettlementStatus,
+};
+use crate::money::Currency;
+use super::Database;
+
+impl Database {
+    /// Soft-deleted settlements are treated as absent; see
+    /// `get_settlement_including_deleted`.
+    pub async fn get_settlement(&self, id: Uuid) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    pub async fn get_settlement_including_deleted(&self, id: Uuid) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Soft-deleted settlements are treated as absent.
+    pub async fn get_settlement_by_reference(&self, reference_number: &str) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            "SELECT * FROM settlements WHERE reference_number = $1 AND deleted_at IS NULL",
+        )
+        .bind(reference_number)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Inserts the settlement with the next reference number of its
+    /// `proposed_at` year. Taking the number and inserting are one statement:
+    /// the counter row stays locked until it commits, and a failed insert
+    /// rolls the counter back, so numbers are neither shared nor skipped.
+    pub async fn insert_settlement(&self, settlement: &Settlement) -> Result<Settlement, sqlx::Error> {
+        insert_settlement(&self.pool, settlement).await
+    }
+    
+    /// Inserts a bundled settlement together with its component debts, in
+    /// one transaction.
+    pub async fn insert_bundled_settlement(
+        &self,
+        settlement: &Settlement,
+        debts: &[SettlementDebt],
+    ) -> Result<Settlement, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = insert_settlement(&mut *tx, settlement).await?;
+        
+        for debt in debts {
+            sqlx::query(
+                "INSERT INTO settlement_debts (settlement_id, debt_id, original_amount) VALUES ($1, $2, $3)",
+            )
+            .bind(inserted.id)
+            .bind(debt.debt_id)
+            .bind(&debt.original_amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        Ok(inserted)
+    }
+    
+    /// Component debts of a bundled settlement; empty for any other.
+    pub async fn get_settlement_debts(&self, settlement_id: Uuid) -> Result<Vec<SettlementDebt>, sqlx::Error> {
+        sqlx::query_as::<_, SettlementDebt>(
+            "SELECT * FROM settlement_debts WHERE settlement_id = $1 ORDER BY debt_id",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Marks every debt the settlement pays off as settled: its own and, for
+    /// a bundle, each component.
+    pub async fn settle_debts(&self, settlement_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE debts SET status = 'settled'
+            WHERE id IN (SELECT debt_id FROM settlements WHERE id = $1)
+               OR id IN (SELECT debt_id FROM settlement_debts WHERE settlement_id = $1)
+            "#,
+        )
+        .bind(settlement_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+    
+    /// Keyset page of a user's settlements, newest first. `after` is the
+    /// `(proposed_at, id)` of the last row of the previous page.
+    pub async fn list_user_settlements(
+        &self,
+        user_id: Uuid,
+        status: Option<SettlementStatus>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64,
+        include_deleted: bool,
+    ) -> Result<Vec<Settlement>, sqlx::Error> {
+        let (after_proposed_at, after_id) = after.unzip();
+        
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            SELECT * FROM settlements
+            WHERE user_id = $1
+              AND ($2::settlement_status IS NULL OR status = $2)
+              AND ($3::timestamptz IS NULL OR (proposed_at, id) < ($3, $4))
+              AND ($6 OR deleted_at IS NULL)
+            ORDER BY proposed_at DESC, id DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(user_id)
+        .bind(status)
+        .bind(after_proposed_at)
+        .bind(after_id)
+        .bind(limit)
+        .bind(include_deleted)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Open proposals on the debt, alone or in a bundle, oldest first.
+    pub async fn get_open_debt_settlements(&self, debt_id: Uuid) -> Result<Vec<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            SELECT * FROM settlements
+            WHERE status IN ('proposed', 'negotiating') AND deleted_at IS NULL
+              AND (debt_id = $1 OR id IN (SELECT settlement_id FROM settlement_debts WHERE debt_id = $1))
+            ORDER BY proposed_at, id
+            "#,
+        )
+        .bind(debt_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Open proposals whose expiry has passed, oldest first.
+    pub async fn get_expired_settlements(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            SELECT * FROM settlements
+            WHERE status IN ('proposed', 'negotiating') AND expires_at <= $1
+            ORDER BY expires_at
+            LIMIT $2
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Negotiations open at `now` whose most urgent threshold in
+    /// `threshold_hours` has been reached, and has no reminder yet, soonest
+    /// to expire first. A threshold the negotiation was already inside when
+    /// proposed never comes due.
+    pub async fn get_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        threshold_hours: &[i32],
+        limit: i64,
+    ) -> Result<Vec<DueReminder>, sqlx::Error> {
+        sqlx::query_as::<_, DueReminder>(
+            r#"
+            SELECT s.*, due.threshold_hours
+            FROM settlements s
+            CROSS JOIN LATERAL (
+                SELECT MIN(hours) AS threshold_hours
+                FROM UNNEST($2::INTEGER[]) AS hours
+                WHERE s.expires_at - $1 <= hours * INTERVAL '1 hour'
+                  AND s.expires_at - s.proposed_at > hours * INTERVAL '1 hour'
+            ) due
+            WHERE s.status = 'negotiating' AND s.deleted_at IS NULL AND s.expires_at > $1
+              AND due.threshold_hours IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM settlement_reminders r
+                  WHERE r.settlement_id = s.id AND r.threshold_hours = due.threshold_hours
+              )
+            ORDER BY s.expires_at
+            LIMIT $3
+            "#,
+        )
+        .bind(now)
+        .bind(threshold_hours)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Claims the settlement's reminder for `threshold_hours`; `false` if
+    /// it was already sent, so only one sweep sends it.
+    pub async fn claim_reminder(&self, settlement_id: Uuid, threshold_hours: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO settlement_reminders (settlement_id, threshold_hours)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(threshold_hours)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+    
+    // Every update below is optimistic: it only applies while the row is
+    // still at `version`, bumps it, and returns `None` if someone else got
+    // there first instead of silently overwriting their change.
+    
+    pub async fn transition_settlement_status(
+        &self,
+        id: Uuid,
+        version: i32,
+        from: SettlementStatus,
+        to: SettlementStatus,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET status = $4,
+                version = version + 1,
+                accepted_at = CASE WHEN $4 = 'accepted'::settlement_status THEN NOW() ELSE accepted_at END,
+                completed_at = CASE WHEN $4 = 'completed'::settlement_status THEN NOW() ELSE completed_at END
+            WHERE id = $1 AND version = $2 AND status = $3
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(from)
+        .bind(to)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Keeps the proposed terms and replaces them with the accepted ones.
+    pub async fn record_accepted_terms(
+        &self,
+        id: Uuid,
+        version: i32,
+        settled_amount: &BigDecimal,
+        saved_amount: &BigDecimal,
+        platform_fee: &BigDecimal,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET proposed_amount = settled_amount, proposed_platform_fee = platform_fee,
+                settled_amount = $3, saved_amount = $4, platform_fee = $5, version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(settled_amount)
+        .bind(saved_amount)
+        .bind(platform_fee)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    pub async fn set_auto_accept_below(
+        &self,
+        id: Uuid,
+        version: i32,
+        auto_accept_below: Option<&BigDecimal>,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET auto_accept_below = $3, version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(auto_accept_below)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Records the creditor clauses agreed to on acceptance, replacing any
+    /// agreed before a rescission, along with their hash.
+    pub async fn record_agreed_clauses(
+        &self,
+        id: Uuid,
+        version: i32,
+        clauses: &[AgreementClause],
+        clauses_hash: Option<&str>,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(settlement) = sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements SET clauses_hash = $3, version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(clauses_hash)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        
+        sqlx::query("DELETE FROM settlement_clauses WHERE settlement_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for (position, clause) in clauses.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO settlement_clauses (settlement_id, creditor_id, clause_key, version, position)
+                SELECT s.id, d.creditor_id, $2, $3, $4
+                FROM settlements s JOIN debts d ON d.id = s.debt_id
+                WHERE s.id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(&clause.key)
+            .bind(clause.version)
+            .bind(position as i32)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(Some(settlement))
+    }
+    
+    /// Replaces an open proposal's terms with recomputed ones. Refuses, like
+    /// a lost race, once the proposal is no longer open.
+    pub async fn record_recomputed_terms(
+        &self,
+        id: Uuid,
+        version: i32,
+        terms: &Settlement,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET settled_amount = $3, saved_amount = $4, platform_fee = $5,
+                model_version = $6, prompt_hash = $7, prompt_version = $8, version = version + 1
+            WHERE id = $1 AND version = $2 AND status IN ('proposed', 'negotiating')
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(&terms.settled_amount)
+        .bind(&terms.saved_amount)
+        .bind(&terms.platform_fee)
+        .bind(&terms.model_version)
+        .bind(&terms.prompt_hash)
+        .bind(&terms.prompt_version)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Returns an accepted, unsubmitted settlement to `Proposed`, putting back
+    /// the proposed terms a new acceptance starts from and dropping it from
+    /// the execution queue.
+    pub async fn record_rescission(&self, id: Uuid, version: i32) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET status = 'proposed', rescinded_at = NOW(), accepted_at = NULL,
+                settled_amount = COALESCE(proposed_amount, settled_amount),
+                saved_amount = original_amount - COALESCE(proposed_amount, settled_amount),
+                platform_fee = COALESCE(proposed_platform_fee, platform_fee),
+                proposed_amount = NULL, proposed_platform_fee = NULL,
+                execution_queued_at = NULL, escrow_refund_after = NULL,
+                version = version + 1
+            WHERE id = $1 AND version = $2 AND status = 'accepted'
+              AND transaction_hash IS NULL AND submission_intent IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    pub async fn record_rejection(
+        &self,
+        id: Uuid,
+        version: i32,
+        reason: RejectReason,
+        note: Option<&str>,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET rejection_reason = $3, rejection_note = $4, version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(reason)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Claims one retry of a failed settlement: bumps `retry_count` and clears
+    /// the dead transaction, but only while the row is still `Failed` and under
+    /// `max_retries`, so concurrent retries can't both resubmit.
+    pub async fn begin_settlement_retry(
+        &self,
+        id: Uuid,
+        version: i32,
+        max_retries: i32,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET retry_count = retry_count + 1,
+                transaction_hash = NULL,
+                smart_contract_address = NULL,
+                submission_intent = NULL,
+                version = version + 1
+            WHERE id = $1 AND version = $2 AND status = 'failed' AND retry_count < $3
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(max_retries)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    pub async fn get_savings_summary(
+        &self,
+        user_id: Uuid,
+        currency: &Currency,
+    ) -> Result<SavingsSummary, sqlx::Error> {
+        sqlx::query_as::<_, SavingsSummary>(
+            r#"
+            SELECT
+                $1 AS user_id,
+                $2 AS currency,
+                COALESCE(SUM(original_amount), 0) AS total_original,
+                COALESCE(SUM(settled_amount), 0) AS total_settled,
+                COALESCE(SUM(saved_amount), 0) AS total_saved,
+                COALESCE(SUM(platform_fee), 0) AS total_fees,
+                COUNT(*) AS settlement_count,
+                (SUM(saved_amount) * 100 / NULLIF(SUM(original_amount), 0))::float8
+                    AS average_reduction_percentage
+            FROM settlements
+            WHERE user_id = $1 AND currency = $2 AND status = 'completed' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(currency)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// Takes the settlement's advisory lock, held until the returned
+    /// transaction ends, so work on one settlement serializes across
+    /// instances. Nothing needs to be written through the transaction.
+    pub async fn lock_settlement(&self, id: Uuid) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(advisory_key(id))
+            .execute(&mut *tx)
+            .await?;
+        Ok(tx)
+    }
+    
+    /// Takes the advisory locks of several settlements at once, in key order
+    /// so two batches sharing settlements cannot deadlock, all held until the
+    /// returned transaction ends.
+    pub async fn lock_settlements(&self, ids: &[Uuid]) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let mut keys: Vec<i64> = ids.iter().copied().map(advisory_key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        
+        let mut tx = self.pool.begin().await?;
+        for key in keys {
+            sqlx::query("SELECT pg_advisory_xact_lock($1)")
+                .bind(key)
+                .execute(&mut *tx)
+                .await?;
+        }
+        Ok(tx)
+    }
+    
+    /// Records the on-chain transaction for an accepted settlement. Completion is
+    /// a separate status transition.
+    pub async fn record_settlement_transaction(
+        &self,
+        id: Uuid,
+        version: i32,
+        transaction_hash: &str,
+        contract_address: &str,
+        metadata_label: i64,
+        terms_hash: &str,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        record_transaction(&self.pool, id, version, transaction_hash, contract_address, metadata_label, terms_hash).await
+    }
+    
+    /// Records the one transaction a batch payment went out in on every
+    /// settlement it pays, each with its own terms hash, in one transaction:
+    /// either all of them show it or, when any has changed since it was
+    /// read, none do and this returns `None`.
+    pub async fn record_batch_transaction(
+        &self,
+        settlements: &[(&Settlement, &str)],
+        transaction_hash: &str,
+        contract_address: &str,
+        metadata_label: i64,
+    ) -> Result<Option<Vec<Settlement>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut recorded = Vec::with_capacity(settlements.len());
+        for &(settlement, terms_hash) in settlements {
+            let Some(settlement) = record_transaction(
+                &mut *tx,
+                settlement.id,
+                settlement.version,
+                transaction_hash,
+                contract_address,
+                metadata_label,
+                terms_hash,
+            )
+            .await?
+            else {
+                return Ok(None);
+            };
+            recorded.push(settlement);
+        }
+        tx.commit().await?;
+        Ok(Some(recorded))
+    }
+    
+    /// Records the idempotency key the payment is about to be submitted
+    /// under, or clears it once no submission under it went through. Unlike
+    /// the updates around it this leaves `version` alone: it is written under
+    /// the settlement's lock, and the execution goes on with what it read.
+    pub async fn set_submission_intent(
+        &self,
+        id: Uuid,
+        version: i32,
+        intent: Option<Uuid>,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        set_submission_intent(&self.pool, id, version, intent).await
+    }
+    
+    /// Fixes when an escrow payment may be refunded, before it is locked.
+    pub async fn set_escrow_refund_after(
+        &self,
+        id: Uuid,
+        version: i32,
+        refund_after: DateTime<Utc>,
+    ) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET escrow_refund_after = $3, version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(refund_after)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Records one intent on every settlement a batch payment is about to
+    /// pay, in one transaction, or `None` when any has changed since it was
+    /// read.
+    pub async fn set_batch_submission_intent(
+        &self,
+        settlements: &[Settlement],
+        intent: Uuid,
+    ) -> Result<Option<Vec<Settlement>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut recorded = Vec::with_capacity(settlements.len());
+        for settlement in settlements {
+            let Some(settlement) = set_submission_intent(&mut *tx, settlement.id, settlement.version, Some(intent)).await?
+            else {
+                return Ok(None);
+            };
+            recorded.push(settlement);
+        }
+        tx.commit().await?;
+        Ok(Some(recorded))
+    }
+    
+    /// Accepted settlements whose submission began but never had its
+    /// transaction recorded, oldest first.
+    pub async fn get_interrupted_submissions(&self) -> Result<Vec<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            SELECT * FROM settlements
+            WHERE status = 'accepted' AND transaction_hash IS NULL
+              AND submission_intent IS NOT NULL AND deleted_at IS NULL
+            ORDER BY accepted_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Queues the execution of an accepted settlement until the Cardano node
+    /// is reachable again. Queuing one already queued keeps its place.
+    pub async fn queue_settlement_execution(&self, id: Uuid, version: i32) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET execution_queued_at = COALESCE(execution_queued_at, NOW()), version = version + 1
+            WHERE id = $1 AND version = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Takes the settlement off the execution queue, or returns `None` if
+    /// another instance already did.
+    pub async fn dequeue_settlement_execution(&self, id: Uuid) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET execution_queued_at = NULL, version = version + 1
+            WHERE id = $1 AND execution_queued_at IS NOT NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// Ids of queued executions, longest waiting first.
+    pub async fn get_queued_executions(&self, limit: i64) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT id FROM settlements
+            WHERE execution_queued_at IS NOT NULL AND deleted_at IS NULL
+            ORDER BY execution_queued_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    pub async fn soft_delete_settlement(&self, id: Uuid, version: i32) -> Result<Option<Settlement>, sqlx::Error> {
+        sqlx::query_as::<_, Settlement>(
+            r#"
+            UPDATE settlements
+            SET deleted_at = NOW(), version = version + 1
+            WHERE id = $1 AND version = $2 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
+
+/// Folds the id into the single bigint advisory locks are keyed by. Two
+/// settlements sharing a key only serialize needlessly.
+pub(super) fn advisory_key(id: Uuid) -> i64 {
+    let bytes = id.as_bytes();
+    let high = i64::from_be_bytes(bytes[..8].try_into().expect("a UUID is 16 bytes"));
+    let low = i64::from_be_bytes(bytes[8..].try_into().expect("a UUID is 16 bytes"));
+    high ^ low
+}
+
+async fn insert_settlement<'e, E>(executor: E, settlement: &Settlement) -> Result<Settlement, sqlx::Error>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_as::<_, Settlement>(
+        r#"
+        WITH next AS (
+            INSERT INTO settlement_reference_counters (year, last_value)
+            VALUES (EXTRACT(YEAR FROM $9::TIMESTAMPTZ AT TIME ZONE 'UTC')::INT, 1)
+            ON CONFLICT (year) DO UPDATE
+                SET last_value = settlement_reference_counters.last_value + 1
+            RETURNING year, last_value
+        )
+        INSERT INTO settlements
+            (id, user_id, debt_id, original_amount, settled_amount, saved_amount,
+             platform_fee, status, proposed_at, expires_at, model_version, prompt_hash,
+             prompt_version, currency, strategy, proposed_by, fee_policy, auto_accept_below, execution_mode,
+             reference_number)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+               'DMC-' || year || '-' || lpad(last_value::TEXT, GREATEST(6, length(last_value::TEXT)), '0')
+        FROM next
+        RETURNING *
+        "#,
+    )
+    .bind(settlement.id)
+    .bind(settlement.user_id)
+    .bind(settlement.debt_id)
+    .bind(&settlement.original_amount)
+    .bind(&settlement.settled_amount)
+    .bind(&settlement.saved_amount)
+    .bind(&settlement.platform_fee)
+    .bind(settlement.status)
+    .bind(settlement.proposed_at)
+    .bind(settlement.expires_at)
+    .bind(&settlement.model_version)
+    .bind(&settlement.prompt_hash)
+    .bind(&settlement.prompt_version)
+    .bind(&settlement.currency)
+    .bind(settlement.strategy)
+    .bind(settlement.proposed_by)
+    .bind(Json(&settlement.fee_policy))
+    .bind(&settlement.auto_accept_below)
+    .bind(settlement.execution_mode)
+    .fetch_one(executor)
+    .await
+}
+
+async fn record_transaction<'e, E>(
+    executor: E,
+    id: Uuid,
+    version: i32,
+    transaction_hash: &str,
+    contract_address: &str,
+    metadata_label: i64,
+    terms_hash: &str,
+) -> Result<Option<Settlement>, sqlx::Error>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_as::<_, Settlement>(
+        r#"
+        UPDATE settlements
+        SET transaction_hash = $3, smart_contract_address = $4,
+            metadata_label = $5, terms_hash = $6, execution_queued_at = NULL,
+            version = version + 1
+        WHERE id = $1 AND version = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(version)
+    .bind(transaction_hash)
+    .bind(contract_address)
+    .bind(metadata_label)
+    .bind(terms_hash)
+    .fetch_optional(executor)
+    .await
+}
+
+async fn set_submission_intent<'e, E>(
+    executor: E,
+    id: Uuid,
+    version: i32,
+    intent: Option<Uuid>,
+) -> Result<Option<Settlement>, sqlx::Error>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_as::<_, Settlement>(
+        r#"
+        UPDATE settlements
+        SET submission_intent = $3
+        WHERE id = $1 AND version = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(version)
+    .bind(intent)
+    .fetch_optional(executor)
+    .await
+}
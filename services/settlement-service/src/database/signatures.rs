@@ -0,0 +1,59 @@
+use crate::models::{EnvelopeStatus, SignatureEnvelope};
+use super::Database;
+
+impl Database {
+    pub async fn insert_signature_envelope(
+        &self,
+        envelope: &SignatureEnvelope,
+    ) -> Result<SignatureEnvelope, sqlx::Error> {
+        sqlx::query_as::<_, SignatureEnvelope>(
+            r#"
+            INSERT INTO signature_envelopes
+                (envelope_id, settlement_id, debtor_id, provider, terms_hash, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(&envelope.envelope_id)
+        .bind(envelope.settlement_id)
+        .bind(envelope.debtor_id)
+        .bind(&envelope.provider)
+        .bind(&envelope.terms_hash)
+        .bind(envelope.status)
+        .bind(envelope.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn get_signature_envelope(
+        &self,
+        envelope_id: &str,
+    ) -> Result<Option<SignatureEnvelope>, sqlx::Error> {
+        sqlx::query_as::<_, SignatureEnvelope>("SELECT * FROM signature_envelopes WHERE envelope_id = $1")
+            .bind(envelope_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Moves a `sent` envelope to `status`. Returns `None` if it had already
+    /// left `sent`, so a redelivered callback changes nothing.
+    pub async fn close_signature_envelope(
+        &self,
+        envelope_id: &str,
+        status: EnvelopeStatus,
+    ) -> Result<Option<SignatureEnvelope>, sqlx::Error> {
+        sqlx::query_as::<_, SignatureEnvelope>(
+            r#"
+            UPDATE signature_envelopes
+            SET status = $2,
+                completed_at = CASE WHEN $2 = 'completed'::envelope_status THEN NOW() END
+            WHERE envelope_id = $1 AND status = 'sent'
+            RETURNING *
+            "#,
+        )
+        .bind(envelope_id)
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
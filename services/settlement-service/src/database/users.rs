@@ -0,0 +1,44 @@
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::models::{FeePolicy, UserFeePolicy};
+
+use super::Database;
+
+impl Database {
+    pub async fn get_user_public_key(&self, user_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>("SELECT public_key FROM user_signing_keys WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    pub async fn get_user_fee_policy(&self, user_id: Uuid) -> Result<Option<UserFeePolicy>, sqlx::Error> {
+        sqlx::query_as::<_, UserFeePolicy>("SELECT * FROM user_fee_policies WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    pub async fn upsert_user_fee_policy(
+        &self,
+        user_id: Uuid,
+        policy: &FeePolicy,
+        set_by: &str,
+    ) -> Result<UserFeePolicy, sqlx::Error> {
+        sqlx::query_as::<_, UserFeePolicy>(
+            r#"
+            INSERT INTO user_fee_policies (user_id, policy, set_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET policy = EXCLUDED.policy, set_by = EXCLUDED.set_by, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(Json(policy))
+        .bind(set_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
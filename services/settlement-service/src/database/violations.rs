@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::models::{CitedViolation, EvidenceRef, Violation, ViolationSettlement};
+use super::Database;
+
+impl Database {
+    /// Loads the given violations, ignoring any that were not committed by
+    /// `creditor_id` so a proposal can't borrow leverage from another creditor.
+    pub async fn get_creditor_violations(
+        &self,
+        creditor_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Violation>, sqlx::Error> {
+        let mut violations = sqlx::query_as::<_, Violation>(
+            r#"
+            SELECT id, creditor_id, type, statute, severity, confidence, legal_reference,
+                   estimated_damage, occurred_at, created_at
+            FROM violations
+            WHERE creditor_id = $1 AND id = ANY($2)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(creditor_id)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        self.load_evidence(&mut violations).await?;
+        Ok(violations)
+    }
+    
+    /// Loads violations for many requests at once; callers filter by creditor.
+    pub async fn get_violations(&self, ids: &[Uuid]) -> Result<Vec<Violation>, sqlx::Error> {
+        let mut violations = sqlx::query_as::<_, Violation>(
+            r#"
+            SELECT id, creditor_id, type, statute, severity, confidence, legal_reference,
+                   estimated_damage, occurred_at, created_at
+            FROM violations
+            WHERE id = ANY($1)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        self.load_evidence(&mut violations).await?;
+        Ok(violations)
+    }
+    
+    /// Fills in each violation's `evidence`, oldest first.
+    async fn load_evidence(&self, violations: &mut [Violation]) -> Result<(), sqlx::Error> {
+        let ids: Vec<Uuid> = violations.iter().map(|v| v.id).collect();
+        let evidence = sqlx::query_as::<_, EvidenceRef>(
+            r#"
+            SELECT * FROM violation_evidence
+            WHERE violation_id = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut by_violation: HashMap<Uuid, Vec<EvidenceRef>> = HashMap::new();
+        for item in evidence {
+            by_violation.entry(item.violation_id).or_default().push(item);
+        }
+        for violation in violations {
+            violation.evidence = by_violation.remove(&violation.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+    
+    pub async fn insert_violation_evidence(&self, evidence: &EvidenceRef) -> Result<EvidenceRef, sqlx::Error> {
+        sqlx::query_as::<_, EvidenceRef>(
+            r#"
+            INSERT INTO violation_evidence
+                (id, violation_id, evidence_type, sha256, content_type, storage_key, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(evidence.id)
+        .bind(evidence.violation_id)
+        .bind(evidence.evidence_type)
+        .bind(&evidence.sha256)
+        .bind(&evidence.content_type)
+        .bind(&evidence.storage_key)
+        .bind(evidence.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// The debt a violation was recorded against, if any.
+    pub async fn get_violation_debt_id(&self, violation_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT debt_id FROM violations WHERE id = $1")
+            .bind(violation_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(Option::flatten)
+    }
+    
+    /// Inserts violations imported for `debt_id` and the evidence declared
+    /// for them in one transaction, so an import is recorded whole or not at
+    /// all.
+    pub async fn insert_violations(
+        &self,
+        debt_id: Uuid,
+        violations: &[Violation],
+        evidence: &[EvidenceRef],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for violation in violations {
+            sqlx::query(
+                r#"
+                INSERT INTO violations
+                    (id, creditor_id, debt_id, type, statute, severity, confidence, legal_reference,
+                     estimated_damage, occurred_at, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+            )
+            .bind(violation.id)
+            .bind(violation.creditor_id)
+            .bind(debt_id)
+            .bind(&violation.violation_type)
+            .bind(violation.statute)
+            .bind(&violation.severity)
+            .bind(violation.confidence)
+            .bind(&violation.legal_reference)
+            .bind(violation.estimated_damage)
+            .bind(violation.occurred_at)
+            .bind(violation.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for item in evidence {
+            sqlx::query(
+                r#"
+                INSERT INTO violation_evidence
+                    (id, violation_id, evidence_type, sha256, content_type, storage_key, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(item.id)
+            .bind(item.violation_id)
+            .bind(item.evidence_type)
+            .bind(&item.sha256)
+            .bind(&item.content_type)
+            .bind(&item.storage_key)
+            .bind(item.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+    
+    /// The violations the settlement's terms cite, however they figured.
+    pub async fn get_settlement_violation_ids(&self, settlement_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT violation_id FROM settlement_violations WHERE settlement_id = $1 ORDER BY violation_id",
+        )
+        .bind(settlement_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Records the violations the settlement's terms rest on, replacing any
+    /// recorded before. Those not committed by `creditor_id` are skipped, as
+    /// the leverage analysis skipped them.
+    pub async fn replace_settlement_violations(
+        &self,
+        settlement_id: Uuid,
+        creditor_id: Uuid,
+        cited: &[CitedViolation],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM settlement_violations WHERE settlement_id = $1")
+            .bind(settlement_id)
+            .execute(&mut *tx)
+            .await?;
+        
+        for violation in cited {
+            sqlx::query(
+                r#"
+                INSERT INTO settlement_violations (settlement_id, violation_id, violation_use, contribution)
+                SELECT $1, id, $3, $4 FROM violations WHERE id = $2 AND creditor_id = $5
+                "#,
+            )
+            .bind(settlement_id)
+            .bind(violation.violation_id)
+            .bind(violation.violation_use)
+            .bind(violation.contribution)
+            .bind(creditor_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await
+    }
+    
+    /// The settlements whose terms cite the violation, newest first.
+    pub async fn get_violation_settlements(&self, violation_id: Uuid) -> Result<Vec<ViolationSettlement>, sqlx::Error> {
+        sqlx::query_as::<_, ViolationSettlement>(
+            r#"
+            SELECT s.*, sv.violation_use, sv.contribution
+            FROM settlement_violations sv
+            JOIN settlements s ON s.id = sv.settlement_id
+            WHERE sv.violation_id = $1 AND s.deleted_at IS NULL
+            ORDER BY s.proposed_at DESC, s.id
+            "#,
+        )
+        .bind(violation_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
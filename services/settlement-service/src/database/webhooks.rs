@@ -0,0 +1,236 @@
+use uuid::Uuid;
+
+use crate::models::{DeliveryStatus, SettlementStatus, Webhook, WebhookDeadLetter, WebhookDelivery};
+use super::Database;
+
+impl Database {
+    /// Stores `webhook`, unless the creditor already has an active one for
+    /// the URL: that one takes on `webhook`'s events instead, and is returned
+    /// with its own id and secret.
+    pub async fn insert_webhook(&self, webhook: &Webhook) -> Result<Webhook, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (id, creditor_id, url, secret, events, active, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (creditor_id, url) WHERE active DO UPDATE
+            SET events = ARRAY(
+                SELECT DISTINCT event FROM unnest(webhooks.events || EXCLUDED.events) AS event ORDER BY event
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(webhook.id)
+        .bind(webhook.creditor_id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(webhook.active)
+        .bind(webhook.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    pub async fn get_webhook(&self, id: Uuid) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    pub async fn get_active_webhook(&self, creditor_id: Uuid, url: &str) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE creditor_id = $1 AND url = $2 AND active")
+            .bind(creditor_id)
+            .bind(url)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Active webhooks of `creditor_id`, oldest first.
+    pub async fn get_creditor_webhooks(&self, creditor_id: Uuid) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE creditor_id = $1 AND active ORDER BY created_at, id",
+        )
+        .bind(creditor_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Stops deliveries to the webhook. The row stays for the deliveries
+    /// and dead letters that reference it; `None` if there is no such webhook.
+    pub async fn deactivate_webhook(&self, id: Uuid) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>("UPDATE webhooks SET active = FALSE WHERE id = $1 RETURNING *")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Active webhooks of `creditor_id` subscribed to `status`.
+    pub async fn get_subscribed_webhooks(
+        &self,
+        creditor_id: Uuid,
+        status: SettlementStatus,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE creditor_id = $1 AND active AND $2 = ANY(events)",
+        )
+        .bind(creditor_id)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    pub async fn insert_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            INSERT INTO webhook_deliveries
+                (id, webhook_id, settlement_id, settlement_status, status, attempts, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.settlement_id)
+        .bind(delivery.settlement_status)
+        .bind(delivery.status)
+        .bind(delivery.attempts)
+        .bind(delivery.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// Records the outcome of a delivery attempt; `delivered_at` is set once
+    /// it succeeds.
+    pub async fn record_webhook_attempt(
+        &self,
+        delivery_id: Uuid,
+        status: DeliveryStatus,
+        attempts: i32,
+        last_error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = $3,
+                last_error = $4,
+                delivered_at = CASE WHEN $2 = 'delivered'::webhook_delivery_status THEN NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(status)
+        .bind(attempts)
+        .bind(last_error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Fails the delivery after its last attempt and keeps it as `letter`,
+    /// together, so a failed delivery always has its dead letter.
+    pub async fn dead_letter_webhook_delivery(&self, letter: &WebhookDeadLetter) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'failed', attempts = $2, last_error = $3 WHERE id = $1",
+        )
+        .bind(letter.delivery_id)
+        .bind(letter.attempts)
+        .bind(&letter.last_error)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_dead_letters
+                (id, delivery_id, webhook_id, settlement_id, url, payload, attempts, last_error, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(letter.id)
+        .bind(letter.delivery_id)
+        .bind(letter.webhook_id)
+        .bind(letter.settlement_id)
+        .bind(&letter.url)
+        .bind(&letter.payload)
+        .bind(letter.attempts)
+        .bind(&letter.last_error)
+        .bind(letter.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+    
+    pub async fn get_webhook_dead_letter(&self, id: Uuid) -> Result<Option<WebhookDeadLetter>, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDeadLetter>("SELECT * FROM webhook_dead_letters WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// Dead letters still awaiting redelivery, or all of them, newest first.
+    pub async fn get_webhook_dead_letters(
+        &self,
+        include_redelivered: bool,
+        limit: i64,
+    ) -> Result<Vec<WebhookDeadLetter>, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDeadLetter>(
+            r#"
+            SELECT * FROM webhook_dead_letters
+            WHERE $1 OR redelivered_at IS NULL
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(include_redelivered)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// Records a manual redelivery of an open dead letter. One that succeeds
+    /// closes it and marks its delivery delivered; `None` if it was already
+    /// closed.
+    pub async fn record_dead_letter_redelivery(
+        &self,
+        id: Uuid,
+        error: Option<&str>,
+    ) -> Result<Option<WebhookDeadLetter>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(letter) = sqlx::query_as::<_, WebhookDeadLetter>(
+            r#"
+            UPDATE webhook_dead_letters
+            SET attempts = attempts + 1,
+                last_error = COALESCE($2, last_error),
+                redelivered_at = CASE WHEN $2 IS NULL THEN NOW() END
+            WHERE id = $1 AND redelivered_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        
+        if letter.redelivered_at.is_some() {
+            sqlx::query(
+                r#"
+                UPDATE webhook_deliveries
+                SET status = 'delivered', attempts = $2, delivered_at = $3
+                WHERE id = $1
+                "#,
+            )
+            .bind(letter.delivery_id)
+            .bind(letter.attempts)
+            .bind(letter.redelivered_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        
+        Ok(Some(letter))
+    }
+}
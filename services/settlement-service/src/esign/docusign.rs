@@ -0,0 +1,243 @@
+use std::env;
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::models::{EnvelopeStatus, Settlement, SignatureRequest};
+
+use super::{CreatedEnvelope, EnvelopeEvent, SignatureProvider};
+
+const DEFAULT_BASE_URL: &str = "https://demo.docusign.net/restapi";
+
+/// DocuSign Connect signs each callback body with HMAC-SHA256, base64, in
+/// this header (one per configured key; we use the first).
+const CONNECT_SIGNATURE_HEADER: &str = "X-DocuSign-Signature-1";
+
+#[derive(Deserialize)]
+struct EnvelopeSummary {
+    #[serde(rename = "envelopeId")]
+    envelope_id: String,
+}
+
+#[derive(Deserialize)]
+struct RecipientView {
+    url: String,
+}
+
+/// A DocuSign Connect JSON (SIM) notification.
+#[derive(Deserialize)]
+struct ConnectEvent {
+    event: String,
+    data: ConnectEventData,
+}
+
+#[derive(Deserialize)]
+struct ConnectEventData {
+    #[serde(rename = "envelopeId")]
+    envelope_id: String,
+}
+
+#[derive(Clone)]
+pub struct DocuSignProvider {
+    http: reqwest::Client,
+    base_url: String,
+    account_id: String,
+    access_token: String,
+    connect_hmac_key: String,
+}
+
+impl DocuSignProvider {
+    /// Reads `DOCUSIGN_BASE_URL` (default the demo environment),
+    /// `DOCUSIGN_ACCOUNT_ID`, `DOCUSIGN_ACCESS_TOKEN` and the Connect key
+    /// `DOCUSIGN_CONNECT_HMAC_KEY`. Until they are set, envelopes can't be
+    /// created and every callback is refused.
+    pub fn from_env() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: env::var("DOCUSIGN_BASE_URL")
+                .unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            account_id: env::var("DOCUSIGN_ACCOUNT_ID").unwrap_or_default(),
+            access_token: env::var("DOCUSIGN_ACCESS_TOKEN").unwrap_or_default(),
+            connect_hmac_key: env::var("DOCUSIGN_CONNECT_HMAC_KEY").unwrap_or_default(),
+        }
+    }
+    
+    fn account_url(&self) -> String {
+        format!("{}/v2.1/accounts/{}", self.base_url, self.account_id)
+    }
+}
+
+#[async_trait]
+impl SignatureProvider for DocuSignProvider {
+    fn name(&self) -> &'static str {
+        "docusign"
+    }
+    
+    /// Creates and sends an envelope holding the terms as a text document,
+    /// with the user as an embedded signer (`clientUserId` is their id), then
+    /// asks for their recipient view.
+    async fn create_envelope(
+        &self,
+        settlement: &Settlement,
+        terms: &str,
+        signer: &SignatureRequest,
+    ) -> anyhow::Result<CreatedEnvelope> {
+        if self.account_id.is_empty() || self.access_token.is_empty() {
+            bail!("DocuSign is not configured");
+        }
+        let client_user_id = settlement.user_id.to_string();
+        
+        let envelope = self
+            .http
+            .post(format!("{}/envelopes", self.account_url()))
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+                "emailSubject": format!("Settlement {} for your signature", settlement.id),
+                "documents": [{
+                    "documentId": "1",
+                    "name": format!("Settlement {}", settlement.id),
+                    "fileExtension": "txt",
+                    "documentBase64": BASE64.encode(terms),
+                }],
+                "recipients": {
+                    "signers": [{
+                        "recipientId": "1",
+                        "name": signer.signer_name,
+                        "email": signer.signer_email,
+                        "clientUserId": client_user_id,
+                    }],
+                },
+                "status": "sent",
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<EnvelopeSummary>()
+            .await
+            .context("invalid envelope response from DocuSign")?;
+        
+        let view = self
+            .http
+            .post(format!(
+                "{}/envelopes/{}/views/recipient",
+                self.account_url(),
+                envelope.envelope_id
+            ))
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+                "returnUrl": signer.return_url,
+                "authenticationMethod": "none",
+                "userName": signer.signer_name,
+                "email": signer.signer_email,
+                "clientUserId": client_user_id,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RecipientView>()
+            .await
+            .context("invalid recipient view response from DocuSign")?;
+        
+        Ok(CreatedEnvelope {
+            envelope_id: envelope.envelope_id,
+            signing_url: view.url,
+        })
+    }
+    
+    fn callback_signature_header(&self) -> &'static str {
+        CONNECT_SIGNATURE_HEADER
+    }
+    
+    fn verify_callback(&self, body: &[u8], signature: &str) -> bool {
+        if self.connect_hmac_key.is_empty() {
+            return false;
+        }
+        let Ok(signature) = BASE64.decode(signature.trim()) else {
+            return false;
+        };
+        
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.connect_hmac_key.as_bytes())
+            .expect("HMAC accepts any key length");
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    }
+    
+    fn parse_callback(&self, body: &[u8]) -> anyhow::Result<Option<EnvelopeEvent>> {
+        let event: ConnectEvent =
+            serde_json::from_slice(body).context("invalid DocuSign Connect notification")?;
+        
+        let status = match event.event.as_str() {
+            "envelope-completed" => EnvelopeStatus::Completed,
+            "envelope-declined" => EnvelopeStatus::Declined,
+            "envelope-voided" => EnvelopeStatus::Voided,
+            _ => return Ok(None),
+        };
+        
+        Ok(Some(EnvelopeEvent {
+            envelope_id: event.data.envelope_id,
+            status,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn provider() -> DocuSignProvider {
+        DocuSignProvider {
+            http: reqwest::Client::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            account_id: String::new(),
+            access_token: String::new(),
+            connect_hmac_key: "connect-key".to_string(),
+        }
+    }
+    
+    fn sign(key: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(body);
+        BASE64.encode(mac.finalize().into_bytes())
+    }
+    
+    #[test]
+    fn callbacks_verify_only_under_the_connect_key() {
+        let body = br#"{"event":"envelope-completed","data":{"envelopeId":"abc"}}"#;
+        let provider = provider();
+        
+        assert!(provider.verify_callback(body, &sign("connect-key", body)));
+        assert!(!provider.verify_callback(body, &sign("other-key", body)));
+        assert!(!provider.verify_callback(b"{}", &sign("connect-key", body)));
+        assert!(!provider.verify_callback(body, "not base64!"));
+    }
+    
+    #[test]
+    fn only_terminal_envelope_events_are_tracked() {
+        let provider = provider();
+        
+        assert_eq!(
+            provider
+                .parse_callback(br#"{"event":"envelope-completed","data":{"envelopeId":"abc"}}"#)
+                .unwrap(),
+            Some(EnvelopeEvent {
+                envelope_id: "abc".to_string(),
+                status: EnvelopeStatus::Completed,
+            })
+        );
+        assert_eq!(
+            provider
+                .parse_callback(br#"{"event":"envelope-delivered","data":{"envelopeId":"abc"}}"#)
+                .unwrap(),
+            None
+        );
+        assert!(provider.parse_callback(b"not json").is_err());
+    }
+}
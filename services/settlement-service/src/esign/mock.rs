@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::models::{EnvelopeStatus, Settlement, SignatureRequest};
+
+use super::{CreatedEnvelope, EnvelopeEvent, SignatureProvider};
+
+/// The only callback signature the mock accepts.
+pub const MOCK_CALLBACK_SIGNATURE: &str = "mock-signature";
+
+/// Provider for tests: envelopes are named after the settlement and callbacks
+/// are plain `{"envelope_id", "status"}` JSON.
+pub struct MockSignatureProvider;
+
+#[derive(Deserialize)]
+struct MockCallback {
+    envelope_id: String,
+    status: EnvelopeStatus,
+}
+
+#[async_trait]
+impl SignatureProvider for MockSignatureProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+    
+    async fn create_envelope(
+        &self,
+        settlement: &Settlement,
+        _terms: &str,
+        signer: &SignatureRequest,
+    ) -> anyhow::Result<CreatedEnvelope> {
+        Ok(CreatedEnvelope {
+            envelope_id: format!("envelope-{}", settlement.id),
+            signing_url: format!("{}?envelope={}", signer.return_url, settlement.id),
+        })
+    }
+    
+    fn callback_signature_header(&self) -> &'static str {
+        "X-Mock-Signature"
+    }
+    
+    fn verify_callback(&self, _body: &[u8], signature: &str) -> bool {
+        signature == MOCK_CALLBACK_SIGNATURE
+    }
+    
+    fn parse_callback(&self, body: &[u8]) -> anyhow::Result<Option<EnvelopeEvent>> {
+        let callback: MockCallback = serde_json::from_slice(body)?;
+        Ok(Some(EnvelopeEvent {
+            envelope_id: callback.envelope_id,
+            status: callback.status,
+        }))
+    }
+}
@@ -0,0 +1,48 @@
+pub mod docusign;
+#[cfg(test)]
+pub mod mock;
+
+use async_trait::async_trait;
+
+use crate::models::{EnvelopeStatus, Settlement, SignatureRequest};
+
+#[derive(Debug, Clone)]
+pub struct CreatedEnvelope {
+    pub envelope_id: String,
+    pub signing_url: String,
+}
+
+/// A status change the provider reported for one of our envelopes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeEvent {
+    pub envelope_id: String,
+    pub status: EnvelopeStatus,
+}
+
+/// A document-signing service users can accept settlements through instead
+/// of signing the terms with their own key. `DocuSignProvider` is the
+/// production backend; the engine only ever talks to this trait.
+#[async_trait]
+pub trait SignatureProvider: Send + Sync {
+    /// Recorded with every envelope, e.g. `docusign`.
+    fn name(&self) -> &'static str;
+    
+    /// Sends `terms` out for the user to sign and opens an embedded signing
+    /// session for them.
+    async fn create_envelope(
+        &self,
+        settlement: &Settlement,
+        terms: &str,
+        signer: &SignatureRequest,
+    ) -> anyhow::Result<CreatedEnvelope>;
+    
+    /// Header the provider signs its callbacks in.
+    fn callback_signature_header(&self) -> &'static str;
+    
+    /// Whether `signature` is the provider's signature over `body`.
+    fn verify_callback(&self, body: &[u8], signature: &str) -> bool;
+    
+    /// The envelope status change a verified callback reports, or `None` for
+    /// events we don't track (delivered, viewed, ...).
+    fn parse_callback(&self, body: &[u8]) -> anyhow::Result<Option<EnvelopeEvent>>;
+}
@@ -0,0 +1,196 @@
+use actix_web::{get, post, put, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::admin_auth::AdminActor;
+use crate::models::{FeePolicy, ForceStatusRequest, ListCreditorEmailsQuery, ListDeadLettersQuery};
+use crate::services::settlement_engine::SettlementEngine;
+
+use super::json::Json;
+use super::query::Query;
+use super::ApiError;
+
+/// Support override of a settlement's status. Bypasses the lifecycle graph
+/// but not the on-chain record unless `force_unsafe` is set; the admin and
+/// their reason are recorded on the settlement's audit trail.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    request_body = ForceStatusRequest,
+    responses(
+        (status = 200, description = "The settlement in its forced status", body = Settlement),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The move contradicts the on-chain record, or the settlement is already in that status", body = ErrorBody),
+        (status = 422, description = "Missing reason", body = ErrorBody),
+    )
+)]
+#[post("/settlements/{id}/force-status")]
+pub async fn force_settlement_status(
+    engine: web::Data<SettlementEngine>,
+    admin: web::ReqData<AdminActor>,
+    path: web::Path<Uuid>,
+    request: Json<ForceStatusRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine
+        .force_status(path.into_inner(), &request, &admin.0)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Settlements reconciliation moved to `NeedsReview` because their
+/// transaction is not on-chain, and how this instance's latest pass went.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    responses(
+        (status = 200, description = "Open discrepancies", body = ReconciliationReport),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+    )
+)]
+#[get("/reconciliation/report")]
+pub async fn reconciliation_report(engine: web::Data<SettlementEngine>) -> Result<HttpResponse, ApiError> {
+    let report = engine.reconciliation_report().await?;
+    
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Waives or reduces the platform fee on the user's new proposals, e.g. for
+/// a legal aid case; `standard` goes back to the full fee. Proposals already
+/// made keep the policy they were made under.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "User id"),
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    request_body = FeePolicy,
+    responses(
+        (status = 200, description = "The user's fee policy", body = UserFeePolicy),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+        (status = 422, description = "Reduced rate outside 0-1", body = ErrorBody),
+    )
+)]
+#[put("/users/{id}/fee-policy")]
+pub async fn set_user_fee_policy(
+    engine: web::Data<SettlementEngine>,
+    admin: web::ReqData<AdminActor>,
+    path: web::Path<Uuid>,
+    policy: Json<FeePolicy>,
+) -> Result<HttpResponse, ApiError> {
+    policy.validate().map_err(ApiError::InvalidFields)?;
+    let granted = engine
+        .set_user_fee_policy(path.into_inner(), &policy, &admin.0)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(granted))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "User id"),
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    responses(
+        (status = 200, description = "The user's fee policy", body = UserFeePolicy),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+        (status = 404, description = "No policy set; the user pays the standard fee", body = ErrorBody),
+    )
+)]
+#[get("/users/{id}/fee-policy")]
+pub async fn get_user_fee_policy(
+    engine: web::Data<SettlementEngine>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let policy = engine.get_user_fee_policy(path.into_inner()).await?;
+    
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+/// Webhook deliveries that failed every attempt, newest first; only those
+/// not yet redelivered unless `include_redelivered` is set.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ListDeadLettersQuery,
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    responses(
+        (status = 200, description = "Dead letters", body = Vec<WebhookDeadLetter>),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+    )
+)]
+#[get("/webhooks/dead-letters")]
+pub async fn list_webhook_dead_letters(
+    engine: web::Data<SettlementEngine>,
+    query: Query<ListDeadLettersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let letters = engine.webhook_dead_letters(&query).await?;
+    
+    Ok(HttpResponse::Ok().json(letters))
+}
+
+/// Creditor emails that couldn't be acted on, oldest first, each with the
+/// reason; emails in another `status` when one is given.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ListCreditorEmailsQuery,
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    responses(
+        (status = 200, description = "Creditor emails", body = Vec<CreditorEmail>),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+    )
+)]
+#[get("/creditor-emails")]
+pub async fn list_creditor_emails(
+    engine: web::Data<SettlementEngine>,
+    query: Query<ListCreditorEmailsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let emails = engine.creditor_emails(&query).await?;
+    
+    Ok(HttpResponse::Ok().json(emails))
+}
+
+/// Posts a dead letter's payload to its webhook once more. The dead letter
+/// comes back closed if the target took it, or still open with the new
+/// `last_error` if not.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Dead letter id"),
+        ("Authorization" = String, Header, description = "`Bearer <admin token>`"),
+    ),
+    responses(
+        (status = 200, description = "The dead letter after the attempt", body = WebhookDeadLetter),
+        (status = 401, description = "Missing or unknown admin token", body = ErrorBody),
+        (status = 404, description = "Dead letter not found", body = ErrorBody),
+        (status = 409, description = "Already redelivered", body = ErrorBody),
+    )
+)]
+#[post("/webhooks/dead-letters/{id}/redeliver")]
+pub async fn redeliver_webhook_dead_letter(
+    engine: web::Data<SettlementEngine>,
+    admin: web::ReqData<AdminActor>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let letter = engine
+        .redeliver_webhook_dead_letter(path.into_inner(), &admin.0)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(letter))
+}
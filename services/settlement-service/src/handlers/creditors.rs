@@ -0,0 +1,180 @@
+use actix_web::{get, put, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::{CreditorContactRequest, CreditorExecutionMode, CreditorTermsRequest, SettlementFloor};
+use crate::services::settlement_engine::{authorize_creditor_access, SettlementEngine};
+
+use super::json::Json;
+use super::ApiError;
+
+/// Sets how the creditor is reached with proposals and status changes,
+/// replacing any contact already on file.
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    request_body = CreditorContactRequest,
+    responses(
+        (status = 200, description = "The contact now on file", body = CreditorContact),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Invalid contact", body = ErrorBody),
+    )
+)]
+#[put("/{id}/contact")]
+pub async fn set_creditor_contact(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<CreditorContactRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let creditor_id = path.into_inner();
+    authorize_creditor_access(creditor_id, &caller)?;
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let contact = engine.set_creditor_contact(creditor_id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(contact))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    responses(
+        (status = 200, description = "The creditor's contact", body = CreditorContact),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 404, description = "No contact on file", body = ErrorBody),
+    )
+)]
+#[get("/{id}/contact")]
+pub async fn get_creditor_contact(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let creditor_id = path.into_inner();
+    authorize_creditor_access(creditor_id, &caller)?;
+    let contact = engine.get_creditor_contact(creditor_id).await?;
+    
+    Ok(HttpResponse::Ok().json(contact))
+}
+
+/// Sets the least the creditor will settle for. Proposals are raised to the
+/// floor when the leverage alone would go lower.
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    request_body = SettlementFloor,
+    responses(
+        (status = 200, description = "The floor now in force", body = SettlementFloor),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Invalid percentage", body = ErrorBody),
+    )
+)]
+#[put("/{id}/settlement-floor")]
+pub async fn set_settlement_floor(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<SettlementFloor>,
+) -> Result<HttpResponse, ApiError> {
+    let creditor_id = path.into_inner();
+    authorize_creditor_access(creditor_id, &caller)?;
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let floor = engine.set_settlement_floor(creditor_id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(floor))
+}
+
+/// Sets how the creditor's settlements are paid from now on: outright, or
+/// held in escrow until the creditor releases them. Settlements already
+/// proposed keep the mode they were proposed with.
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    request_body = CreditorExecutionMode,
+    responses(
+        (status = 200, description = "The mode now in force", body = CreditorExecutionMode),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Unknown mode", body = ErrorBody),
+    )
+)]
+#[put("/{id}/execution-mode")]
+pub async fn set_execution_mode(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<CreditorExecutionMode>,
+) -> Result<HttpResponse, ApiError> {
+    let creditor_id = path.into_inner();
+    authorize_creditor_access(creditor_id, &caller)?;
+    let mode = engine.set_execution_mode(creditor_id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(mode))
+}
+
+/// Replaces the clauses the creditor's settlement agreements carry, in the
+/// order given. A clause whose title or body changed gets a new version;
+/// settlements already accepted keep the versions they agreed to.
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    request_body = CreditorTermsRequest,
+    responses(
+        (status = 200, description = "The clauses now in force", body = CreditorTerms),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Invalid clauses", body = ErrorBody),
+    )
+)]
+#[put("/{id}/terms")]
+pub async fn set_creditor_terms(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<CreditorTermsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let creditor_id = path.into_inner();
+    authorize_creditor_access(creditor_id, &caller)?;
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let terms = engine.set_creditor_terms(creditor_id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(terms))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/creditors",
+    tag = "creditors",
+    params(
+        ("id" = Uuid, Path, description = "Creditor id"),
+    ),
+    responses(
+        (status = 200, description = "The clauses in force, possibly none", body = CreditorTerms),
+    )
+)]
+#[get("/{id}/terms")]
+pub async fn get_creditor_terms(
+    engine: web::Data<SettlementEngine>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let terms = engine.get_creditor_terms(path.into_inner()).await?;
+    
+    Ok(HttpResponse::Ok().json(terms))
+}
@@ -0,0 +1,137 @@
+use actix_web::{post, web, HttpResponse};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::{BulkViolationImport, DebtVerificationRequest, FieldError, ImportedViolation, RecomputedProposal};
+use crate::services::settlement_engine::SettlementEngine;
+
+use super::display::{DisplayPrecision, ForDisplay};
+use super::json::{Json, BULK_LIMIT};
+use super::ApiError;
+
+/// `{ "ok": { "violation_id", "evidence" } }` for a row imported, or
+/// `{ "error": { "errors" } }` with every problem found in it.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowResult {
+    Ok(ImportedViolation),
+    Error { errors: Vec<FieldError> },
+}
+
+/// `{ "ok": recomputed proposal }`, or
+/// `{ "error": { "settlement_id", "code", "message" } }`.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecomputeResult {
+    Ok(Box<RecomputedProposal>),
+    Error {
+        settlement_id: Uuid,
+        #[schema(value_type = String)]
+        code: &'static str,
+        message: String,
+    },
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ViolationImportResults {
+    pub imported: usize,
+    pub rejected: usize,
+    /// One per row, in request order.
+    pub results: Vec<ImportRowResult>,
+    /// One per open settlement on the debt, when a recompute was asked for
+    /// and anything was imported.
+    pub recomputed: Vec<RecomputeResult>,
+}
+
+/// Records that validation was requested for the debt, or what came back.
+#[utoipa::path(
+    context_path = "/api/v1/debts",
+    tag = "debts",
+    params(
+        ("id" = Uuid, Path, description = "Debt id"),
+    ),
+    request_body = DebtVerificationRequest,
+    responses(
+        (status = 200, description = "The debt with its new verification status", body = Debt),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "Only a debtor may request validation, and only the creditor record its outcome", body = ErrorBody),
+        (status = 404, description = "Debt not found", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("/{id}/verification")]
+pub async fn record_debt_verification(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<DebtVerificationRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let debt = engine.record_debt_verification(path.into_inner(), &request, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(debt))
+}
+
+/// Imports many violations against the debt's creditor at once, at most
+/// 500. Rows that pass their checks are recorded together; the rest are
+/// reported row by row.
+#[utoipa::path(
+    context_path = "/api/v1/debts",
+    tag = "debts",
+    params(
+        ("id" = Uuid, Path, description = "Debt id"),
+    ),
+    request_body = BulkViolationImport,
+    responses(
+        (status = 200, description = "Per-row results, and any settlements recomputed", body = ViolationImportResults),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for the debt's user", body = ErrorBody),
+        (status = 404, description = "Debt not found", body = ErrorBody),
+        (status = 413, description = "Request body over 1 MiB", body = ErrorBody),
+        (status = 422, description = "No rows, too many, or an invalid recompute", body = ErrorBody),
+    )
+)]
+#[post("/{id}/violations/bulk")]
+pub async fn import_violations(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<BulkViolationImport, BULK_LIMIT>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let import = engine.import_violations(path.into_inner(), &request, &caller).await?;
+    
+    let results: Vec<ImportRowResult> = import
+        .rows
+        .into_iter()
+        .map(|row| match row {
+            Ok(imported) => ImportRowResult::Ok(imported),
+            Err(errors) => ImportRowResult::Error { errors },
+        })
+        .collect();
+    let recomputed = import
+        .recomputed
+        .into_iter()
+        .map(|(settlement_id, result)| match result {
+            Ok(recomputed) => RecomputeResult::Ok(Box::new(recomputed.for_display(&display))),
+            Err(e) => {
+                let e = ApiError::from(e);
+                RecomputeResult::Error {
+                    settlement_id,
+                    code: e.code(),
+                    message: e.client_message().to_string(),
+                }
+            }
+        })
+        .collect();
+    let imported = results.iter().filter(|row| matches!(row, ImportRowResult::Ok(_))).count();
+    
+    Ok(HttpResponse::Ok().json(ViolationImportResults {
+        imported,
+        rejected: results.len() - imported,
+        results,
+        recomputed,
+    }))
+}
@@ -0,0 +1,220 @@
+use serde_json::Value;
+use sqlx::types::Json;
+
+use crate::models::{
+    CreditorProfile, LeaderboardEntry, LeverageAnalysis, LeverageLeaderboard, LeverageSnapshot, OptimalSettlement,
+    RecomputedProposal, SavingsSummary, SettlementProposal, WhatIfProjection,
+};
+
+/// Most decimal places a setting may ask for; an f64 holds no more.
+pub const MAX_DISPLAY_DECIMALS: u32 = 15;
+
+/// Decimal places reduction percentages and confidences are rounded to in
+/// responses. Only handlers round, on the way out: the engine, the AI
+/// service and the database keep every digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayPrecision {
+    pub percentage_decimals: u32,
+    pub confidence_decimals: u32,
+}
+
+impl Default for DisplayPrecision {
+    fn default() -> Self {
+        Self {
+            percentage_decimals: 2,
+            confidence_decimals: 2,
+        }
+    }
+}
+
+impl DisplayPrecision {
+    pub fn percentage(&self, value: f64) -> f64 {
+        round(value, self.percentage_decimals)
+    }
+    
+    pub fn confidence(&self, value: f64) -> f64 {
+        round(value, self.confidence_decimals)
+    }
+    
+    /// An idempotent replay is the proposal as first sent, stored before it
+    /// was rounded.
+    pub fn replayed_proposal(&self, mut response: Value) -> Value {
+        if let Some(value) = response.pointer_mut("/confidence_score") {
+            round_number(value, |number| self.confidence(number));
+        }
+        if let Some(value) = response.pointer_mut("/leverage_analysis/estimated_reduction_percentage") {
+            round_number(value, |number| self.percentage(number));
+        }
+        response
+    }
+}
+
+fn round(value: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (value * scale).round() / scale
+}
+
+fn round_number(value: &mut Value, rounded: impl Fn(f64) -> f64) {
+    if let Some(number) = value.as_f64() {
+        *value = Value::from(rounded(number));
+    }
+}
+
+/// A response body as the API shows it.
+pub trait ForDisplay {
+    fn for_display(self, precision: &DisplayPrecision) -> Self;
+}
+
+impl<T: ForDisplay> ForDisplay for Vec<T> {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        self.into_iter().map(|item| item.for_display(precision)).collect()
+    }
+}
+
+impl ForDisplay for LeverageAnalysis {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            estimated_reduction_percentage: precision.percentage(self.estimated_reduction_percentage),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for SettlementProposal {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            confidence_score: self.confidence_score.map(|value| precision.confidence(value)),
+            leverage_analysis: self.leverage_analysis.for_display(precision),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for RecomputedProposal {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            leverage_analysis: self.leverage_analysis.for_display(precision),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for OptimalSettlement {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            reduction_percentage: precision.percentage(self.reduction_percentage),
+            confidence: self.confidence.map(|value| precision.confidence(value)),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for WhatIfProjection {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            settlement: self.settlement.for_display(precision),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for LeverageSnapshot {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            analysis: Json(self.analysis.0.for_display(precision)),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for LeaderboardEntry {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            estimated_reduction_percentage: precision.percentage(self.estimated_reduction_percentage),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for LeverageLeaderboard {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            creditors: self.creditors.for_display(precision),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for SavingsSummary {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            average_reduction_percentage: self.average_reduction_percentage.map(|value| precision.percentage(value)),
+            ..self
+        }
+    }
+}
+
+impl ForDisplay for CreditorProfile {
+    fn for_display(self, precision: &DisplayPrecision) -> Self {
+        Self {
+            average_reduction_percentage: self.average_reduction_percentage.map(|value| precision.percentage(value)),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    
+    use super::*;
+    use crate::models::{LeverageExplanation, SolStatus};
+    
+    fn analysis() -> LeverageAnalysis {
+        LeverageAnalysis {
+            violation_count: 2,
+            total_leverage_score: 31.456,
+            estimated_reduction_percentage: 15.728391,
+            legal_strength: "moderate".to_string(),
+            key_violations: Vec::new(),
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: SolStatus::WithinPeriod,
+            explanation: LeverageExplanation::default(),
+            exposure_by_statute: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn only_the_response_is_rounded() {
+        let internal = analysis();
+        let shown = internal.clone().for_display(&DisplayPrecision::default());
+        
+        assert_eq!(shown.estimated_reduction_percentage, 15.73);
+        assert_eq!(internal.estimated_reduction_percentage, 15.728391);
+        // What the engine sends the AI service and stores keeps every digit.
+        assert_eq!(serde_json::to_value(&internal).unwrap()["estimated_reduction_percentage"], 15.728391);
+        assert_eq!(serde_json::to_value(&shown).unwrap()["estimated_reduction_percentage"], 15.73);
+        
+        let precise = DisplayPrecision {
+            percentage_decimals: 4,
+            confidence_decimals: 0,
+        };
+        assert_eq!(internal.for_display(&precise).estimated_reduction_percentage, 15.7284);
+        assert_eq!(precise.confidence(0.6832), 1.0);
+    }
+    
+    #[test]
+    fn replayed_proposals_are_rounded_like_fresh_ones() {
+        let replayed = DisplayPrecision::default().replayed_proposal(json!({
+            "confidence_score": 0.68321,
+            "leverage_analysis": { "estimated_reduction_percentage": 15.728391, "total_leverage_score": 31.456 },
+            "reasoning": [],
+        }));
+        
+        assert_eq!(replayed["confidence_score"], 0.68);
+        assert_eq!(replayed["leverage_analysis"]["estimated_reduction_percentage"], 15.73);
+        assert_eq!(replayed["leverage_analysis"]["total_leverage_score"], 31.456);
+    }
+}
@@ -0,0 +1,171 @@
+use actix_web::{get, patch, post, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::{
+    BundleSettlementRequest, CreateDraftRequest, CreateSettlementRequest, DraftKind, ListDraftsQuery,
+    UpdateDraftRequest,
+};
+use crate::services::settlement_engine::{authorize_fee_policy, authorize_user_access, SettlementEngine};
+
+use super::display::{DisplayPrecision, ForDisplay};
+use super::json::{self, Json, BULK_LIMIT};
+use super::query::Query;
+use super::ApiError;
+
+/// Starts a draft. Its fields may be incomplete or wrong; they are only
+/// checked when the draft is finalized.
+#[utoipa::path(
+    context_path = "/api/v1/drafts",
+    tag = "drafts",
+    request_body = CreateDraftRequest,
+    responses(
+        (status = 201, description = "Draft saved", body = SettlementDraft),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 413, description = "Request body over 1 MiB", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("")]
+pub async fn create_draft(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    request: Json<CreateDraftRequest, BULK_LIMIT>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.user_id, &caller)?;
+    let draft = engine.create_draft(&request).await?;
+    
+    Ok(HttpResponse::Created().json(draft))
+}
+
+/// The user's unfinished drafts, last saved first.
+#[utoipa::path(
+    context_path = "/api/v1/drafts",
+    tag = "drafts",
+    params(ListDraftsQuery),
+    responses(
+        (status = 200, description = "Drafts", body = Vec<SettlementDraft>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 422, description = "Invalid query", body = ErrorBody),
+    )
+)]
+#[get("")]
+pub async fn list_drafts(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    query: Query<ListDraftsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_user_access(query.user_id, &caller)?;
+    let drafts = engine.list_drafts(&query).await?;
+    
+    Ok(HttpResponse::Ok().json(drafts))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/drafts",
+    tag = "drafts",
+    params(
+        ("id" = Uuid, Path, description = "Draft id"),
+    ),
+    responses(
+        (status = 200, description = "The draft as last saved", body = SettlementDraft),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The draft is another user's", body = ErrorBody),
+        (status = 404, description = "Draft not found, or expired", body = ErrorBody),
+    )
+)]
+#[get("/{id}")]
+pub async fn get_draft(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let draft = engine.get_draft(path.into_inner()).await?;
+    authorize_user_access(draft.user_id, &caller)?;
+    
+    Ok(HttpResponse::Ok().json(draft))
+}
+
+/// Saves changes to the draft and keeps it from expiring for another
+/// `SETTLEMENT_DRAFT_EXPIRY_HOURS`.
+#[utoipa::path(
+    context_path = "/api/v1/drafts",
+    tag = "drafts",
+    params(
+        ("id" = Uuid, Path, description = "Draft id"),
+    ),
+    request_body = UpdateDraftRequest,
+    responses(
+        (status = 200, description = "The draft as saved", body = SettlementDraft),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The draft is another user's", body = ErrorBody),
+        (status = 404, description = "Draft not found, or expired", body = ErrorBody),
+        (status = 409, description = "The draft was already finalized", body = ErrorBody),
+        (status = 413, description = "Request body over 1 MiB", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[patch("/{id}")]
+pub async fn update_draft(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<UpdateDraftRequest, BULK_LIMIT>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let draft = engine.get_draft(path.into_inner()).await?;
+    authorize_user_access(draft.user_id, &caller)?;
+    let draft = engine.update_draft(draft.id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(draft))
+}
+
+/// Proposes the settlement the draft describes. The draft is checked as
+/// the same request posted to `/settlements` (or `/settlements/bundle`)
+/// would be, and fails with the same errors.
+#[utoipa::path(
+    context_path = "/api/v1/drafts",
+    tag = "drafts",
+    params(
+        ("id" = Uuid, Path, description = "Draft id"),
+    ),
+    responses(
+        (status = 201, description = "Proposal created", body = SettlementProposal),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The draft is another user's, or asks for a fee policy only support may grant", body = ErrorBody),
+        (status = 404, description = "Draft, debt or creditor not found", body = ErrorBody),
+        (status = 409, description = "The draft was already finalized, or saved again meanwhile", body = ErrorBody),
+        (status = 422, description = "The draft is not yet a valid proposal", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 504, description = "The AI service timed out", body = ErrorBody),
+    )
+)]
+#[post("/{id}/finalize")]
+pub async fn finalize_draft(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let draft = engine.get_draft(path.into_inner()).await?;
+    authorize_user_access(draft.user_id, &caller)?;
+    
+    let proposal = match draft.kind {
+        DraftKind::Settlement => {
+            let request: CreateSettlementRequest = json::parse(&draft.request_body())?;
+            request.validate().map_err(ApiError::InvalidFields)?;
+            authorize_fee_policy(request.fee_policy.as_ref(), &caller)?;
+            engine.finalize_settlement_draft(&draft, &request).await?
+        }
+        DraftKind::Bundle => {
+            let request: BundleSettlementRequest = json::parse(&draft.request_body())?;
+            request.validate().map_err(ApiError::InvalidFields)?;
+            engine.finalize_bundle_draft(&draft, &request).await?
+        }
+    };
+    
+    Ok(HttpResponse::Created().json(proposal.for_display(&display)))
+}
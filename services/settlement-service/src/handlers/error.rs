@@ -0,0 +1,209 @@
+use std::fmt;
+
+use actix_web::{http::{header, StatusCode}, HttpResponse, ResponseError};
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blockchain::is_circuit_open;
+use crate::middleware::request_id;
+use crate::models::FieldError;
+use crate::services::settlement_engine::SettlementError;
+
+/// Error returned by every API handler. Serializes as
+/// `{ "error": { "code", "message", "request_id" } }`, plus `fields` for
+/// `InvalidFields`.
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Unauthorized(String),
+    /// 403 for an authenticated caller who is not a party to what they asked
+    /// for.
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    /// 410 for something that existed but can no longer be acted on.
+    Gone(String),
+    /// 409 that is safe to retry after reloading; sent with `Retry-After`.
+    ConcurrentModification(String),
+    Validation(String),
+    /// 422 for a request body with specific bad fields, listed in `fields`.
+    InvalidFields(Vec<FieldError>),
+    /// 413 for a request body over the route's limit.
+    PayloadTooLarge(String),
+    /// 415 for a request body that isn't JSON.
+    UnsupportedMediaType(String),
+    RateLimited { retry_after_secs: u64 },
+    Upstream(String),
+    /// 504: an upstream the request depends on didn't answer in time.
+    UpstreamTimeout(String),
+    /// 503 while a resource we depend on, such as the database pool, is
+    /// saturated; sent with `Retry-After`.
+    Unavailable(String),
+    Internal(String),
+}
+
+/// The body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: ErrorDetail,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// Stable machine-readable code, e.g. `not_found` or `validation_error`.
+    #[schema(value_type = String)]
+    pub code: &'static str,
+    pub message: String,
+    pub request_id: Option<Uuid>,
+    /// Only present for a request body with specific bad fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldError>>,
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Gone(_) => "gone",
+            ApiError::ConcurrentModification(_) => "concurrent_modification",
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => "validation_error",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::Upstream(_) => "upstream_error",
+            ApiError::UpstreamTimeout(_) => "upstream_timeout",
+            ApiError::Unavailable(_) => "service_unavailable",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+    
+    fn message(&self) -> &str {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::Forbidden(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::Gone(msg)
+            | ApiError::ConcurrentModification(msg)
+            | ApiError::Validation(msg)
+            | ApiError::Upstream(msg)
+            | ApiError::UpstreamTimeout(msg)
+            | ApiError::Unavailable(msg)
+            | ApiError::PayloadTooLarge(msg)
+            | ApiError::UnsupportedMediaType(msg)
+            | ApiError::Internal(msg) => msg,
+            ApiError::InvalidFields(_) => "request has invalid fields",
+            ApiError::RateLimited { .. } => "rate limit exceeded",
+        }
+    }
+    
+    /// The message safe to show a client. Internal details go to the log,
+    /// never to the client.
+    pub fn client_message(&self) -> &str {
+        match self {
+            ApiError::Internal(detail) => {
+                error!(request_id = ?request_id::current(), "Internal error: {}", detail);
+                "internal server error"
+            }
+            _ => self.message(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) | ApiError::ConcurrentModification(_) => StatusCode::CONFLICT,
+            ApiError::Gone(_) => StatusCode::GONE,
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::UpstreamTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+    
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        match self {
+            ApiError::ConcurrentModification(_) | ApiError::Unavailable(_) => {
+                response.insert_header((header::RETRY_AFTER, "1"));
+            }
+            ApiError::RateLimited { retry_after_secs } => {
+                response.insert_header((header::RETRY_AFTER, retry_after_secs.to_string()));
+            }
+            _ => {}
+        }
+        
+        let fields = match self {
+            ApiError::InvalidFields(fields) => Some(fields.clone()),
+            _ => None,
+        };
+        
+        response.json(ErrorBody {
+            error: ErrorDetail {
+                code: self.code(),
+                message: self.client_message().to_string(),
+                request_id: request_id::current(),
+                fields,
+            },
+        })
+    }
+}
+
+impl From<SettlementError> for ApiError {
+    fn from(e: SettlementError) -> Self {
+        match e {
+            SettlementError::NotFound(..) | SettlementError::UnknownReference(_) => {
+                ApiError::NotFound(e.to_string())
+            }
+            SettlementError::SignatureRequired(_) => ApiError::BadRequest(e.to_string()),
+            SettlementError::Forbidden(_) => ApiError::Forbidden(e.to_string()),
+            SettlementError::InvalidSignature(_) | SettlementError::InvalidTriggerSignature(_) => {
+                ApiError::Unauthorized(e.to_string())
+            }
+            SettlementError::InvalidStatus { .. }
+            | SettlementError::InvalidTransition { .. }
+            | SettlementError::Conflict(_) => ApiError::Conflict(e.to_string()),
+            SettlementError::ConcurrentModification(_) => ApiError::ConcurrentModification(e.to_string()),
+            SettlementError::Expired(_) => ApiError::Gone(e.to_string()),
+            SettlementError::Validation(_) | SettlementError::IdempotencyMismatch(_) => {
+                ApiError::Validation(e.to_string())
+            }
+            SettlementError::Blockchain(ref inner) if is_circuit_open(inner) => {
+                ApiError::Unavailable("the Cardano node is unavailable; retry shortly".to_string())
+            }
+            SettlementError::Ai(_)
+            | SettlementError::Blockchain(_)
+            | SettlementError::SignatureProvider(_) => ApiError::Upstream(e.to_string()),
+            SettlementError::AiTimeout(_) => ApiError::UpstreamTimeout(e.to_string()),
+            SettlementError::Database(sqlx::Error::PoolTimedOut) => {
+                ApiError::Unavailable("the database is at capacity; retry shortly".to_string())
+            }
+            SettlementError::Agreement(_)
+            | SettlementError::DemandLetter(_)
+            | SettlementError::EvidenceStore(_)
+            | SettlementError::Database(_) => ApiError::Internal(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use actix_web::{get, web, HttpResponse};
+
+use crate::models::Overall;
+use crate::services::settlement_engine::SettlementEngine;
+
+/// Readiness probe: 200 while every critical dependency is up (even if the
+/// report is `degraded`), 503 otherwise.
+#[utoipa::path(
+    context_path = "/api/v1",
+    tag = "health",
+    responses(
+        (status = 200, description = "Every critical dependency is up", body = HealthReport),
+        (status = 503, description = "A critical dependency is down", body = HealthReport),
+    )
+)]
+#[get("/health")]
+pub async fn health_check(engine: web::Data<SettlementEngine>) -> HttpResponse {
+    let report = engine.health_report().await;
+    
+    match report.status {
+        Overall::Healthy | Overall::Degraded => HttpResponse::Ok().json(report),
+        Overall::Unhealthy => HttpResponse::ServiceUnavailable().json(report),
+    }
+}
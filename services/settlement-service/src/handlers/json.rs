@@ -0,0 +1,261 @@
+use std::ops::{Deref, DerefMut};
+
+use actix_web::dev::Payload;
+use actix_web::http::header;
+use actix_web::web::BytesMut;
+use actix_web::{mime, FromRequest, HttpMessage, HttpRequest};
+use futures::future::LocalBoxFuture;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+
+use super::ApiError;
+
+/// Body limit for ordinary requests.
+pub const DEFAULT_LIMIT: usize = 64 * 1024;
+
+/// Body limit for routes carrying many items at once, such as bundles and
+/// leverage batches.
+pub const BULK_LIMIT: usize = 1024 * 1024;
+
+/// A JSON request body of at most `LIMIT` bytes. Unlike `web::Json`, every
+/// failure is one of our own errors: 413 naming the limit for an oversized
+/// body, 415 for one that isn't JSON, and 422 with serde's message and the
+/// path of the offending field for one that doesn't deserialize.
+#[derive(Debug)]
+pub struct Json<T, const LIMIT: usize = DEFAULT_LIMIT>(pub T);
+
+impl<T, const LIMIT: usize> Json<T, LIMIT> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, const LIMIT: usize> Deref for Json<T, LIMIT> {
+    type Target = T;
+    
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, const LIMIT: usize> DerefMut for Json<T, LIMIT> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static, const LIMIT: usize> FromRequest for Json<T, LIMIT> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let is_json = matches!(
+            req.mime_type(),
+            Ok(Some(mime)) if mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON)
+        );
+        let declared_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        let mut payload = payload.take();
+        
+        Box::pin(async move {
+            if !is_json {
+                return Err(ApiError::UnsupportedMediaType("Content-Type must be application/json".to_string()).into());
+            }
+            if declared_length.is_some_and(|length| length > LIMIT) {
+                return Err(too_large(LIMIT).into());
+            }
+            
+            let mut body = BytesMut::new();
+            while let Some(chunk) = payload.next().await {
+                let chunk = chunk.map_err(|e| ApiError::BadRequest(format!("reading request body: {}", e)))?;
+                if body.len() + chunk.len() > LIMIT {
+                    return Err(too_large(LIMIT).into());
+                }
+                body.extend_from_slice(&chunk);
+            }
+            
+            parse(&body).map(Json).map_err(Into::into)
+        })
+    }
+}
+
+/// `body` deserialized, failing as a request body that doesn't would. For
+/// bodies kept to be posted later, such as a draft's.
+pub fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body).map_err(|e| body_error(body, &e))
+}
+
+fn too_large(limit: usize) -> ApiError {
+    ApiError::PayloadTooLarge(format!("request body exceeds the {} byte limit", limit))
+}
+
+fn body_error(body: &[u8], e: &serde_json::Error) -> ApiError {
+    let path = path_at(body, offset(body, e.line(), e.column()));
+    if path.is_empty() {
+        ApiError::Validation(format!("invalid request body: {}", e))
+    } else {
+        ApiError::Validation(format!("invalid request body at `{}`: {}", path, e))
+    }
+}
+
+/// Byte offset of serde's 1-based line and column.
+fn offset(body: &[u8], line: usize, column: usize) -> usize {
+    let line_start = match line {
+        0 | 1 => 0,
+        line => body
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+            .nth(line - 2)
+            .map_or(body.len(), |(i, _)| i + 1),
+    };
+    (line_start + column).min(body.len())
+}
+
+enum Frame {
+    Object { key: Option<String>, awaiting_key: bool },
+    Array { index: usize },
+}
+
+/// Path, like `debts[2].amount`, of the value being read at `offset`; empty
+/// at the top level. Only what precedes `offset` is looked at, so this works
+/// on bodies that are not valid JSON.
+fn path_at(body: &[u8], offset: usize) -> String {
+    let body = &body[..offset];
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut i = 0;
+    while i < body.len() {
+        match body[i] {
+            b'{' => stack.push(Frame::Object { key: None, awaiting_key: true }),
+            b'[' => stack.push(Frame::Array { index: 0 }),
+            b'}' | b']' => {
+                stack.pop();
+            }
+            b',' => match stack.last_mut() {
+                Some(Frame::Object { key, awaiting_key }) => {
+                    *key = None;
+                    *awaiting_key = true;
+                }
+                Some(Frame::Array { index }) => *index += 1,
+                None => {}
+            },
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < body.len() && body[i] != b'"' {
+                    i += if body[i] == b'\\' { 2 } else { 1 };
+                }
+                if let Some(Frame::Object { key, awaiting_key }) = stack.last_mut() {
+                    if *awaiting_key {
+                        let raw = &body[start..(i + 1).min(body.len())];
+                        *key = Some(
+                            serde_json::from_slice(raw)
+                                .unwrap_or_else(|_| String::from_utf8_lossy(&raw[1..]).into_owned()),
+                        );
+                        *awaiting_key = false;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    
+    let mut path = String::new();
+    for frame in &stack {
+        match frame {
+            Frame::Object { key: Some(key), .. } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Frame::Object { key: None, .. } => {}
+            Frame::Array { index } => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use serde::Deserialize;
+    use serde_json::Value;
+    
+    use super::*;
+    
+    #[derive(Deserialize)]
+    struct Item {
+        #[allow(dead_code)]
+        amount: u32,
+    }
+    
+    #[derive(Deserialize)]
+    struct Items {
+        items: Vec<Item>,
+    }
+    
+    async fn count(body: Json<Items, 64>) -> HttpResponse {
+        HttpResponse::Ok().body(body.items.len().to_string())
+    }
+    
+    async fn post(body: &str, content_type: &str) -> (u16, Value) {
+        let app = init_service(App::new().route("/", web::post().to(count))).await;
+        let request = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, content_type))
+            .set_payload(body.to_string())
+            .to_request();
+        let response = call_service(&app, request).await;
+        let status = response.status().as_u16();
+        let body = read_body(response).await;
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+    
+    fn message(body: &Value) -> &str {
+        body["error"]["message"].as_str().unwrap()
+    }
+    
+    #[actix_web::test]
+    async fn malformed_bodies_name_the_offending_field() {
+        let (status, body) = post(r#"{"items": [{"amount": 1}, {"amount": "two"}]}"#, "application/json").await;
+        assert_eq!(status, 422);
+        assert_eq!(body["error"]["code"], "validation_error");
+        assert!(message(&body).contains("at `items[1].amount`"), "{}", message(&body));
+        assert!(message(&body).contains("invalid type"), "{}", message(&body));
+        
+        let (status, body) = post(r#"{"items": [{"amount": 1},"#, "application/json").await;
+        assert_eq!(status, 422);
+        assert!(message(&body).contains("EOF"), "{}", message(&body));
+        
+        let (status, body) = post("{}", "application/json").await;
+        assert_eq!(status, 422);
+        assert!(message(&body).contains("missing field `items`"), "{}", message(&body));
+        
+        let (status, _) = post(r#"{"items": []}"#, "text/plain").await;
+        assert_eq!(status, 415);
+    }
+    
+    #[actix_web::test]
+    async fn oversized_bodies_are_refused_with_the_limit() {
+        let items = [r#"{"amount": 1}"#; 10].join(",");
+        let (status, body) = post(&format!(r#"{{"items": [{}]}}"#, items), "application/json").await;
+        
+        assert_eq!(status, 413);
+        assert_eq!(body["error"]["code"], "payload_too_large");
+        assert!(message(&body).contains("64 byte limit"), "{}", message(&body));
+        assert_eq!(post(r#"{"items": [{"amount": 1}]}"#, "application/json").await.0, 200);
+    }
+    
+    #[test]
+    fn paths_follow_nesting() {
+        let body = br#"{"a": {"b": [1, {"c": "#;
+        assert_eq!(path_at(body, body.len()), "a.b[1].c");
+        assert_eq!(path_at(br#"{"a": 1, "#, 9), "");
+    }
+}
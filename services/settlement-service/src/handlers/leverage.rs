@@ -0,0 +1,164 @@
+use actix_web::{get, post, web, HttpResponse};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::{BatchLeverageRequest, LeaderboardQuery, LeverageAnalysis, LeverageHistoryQuery, LeverageRequest};
+use crate::services::settlement_engine::{authorize_user_access, SettlementEngine};
+
+use super::display::{DisplayPrecision, ForDisplay};
+use super::json::{Json, BULK_LIMIT};
+use super::query::Query;
+use super::ApiError;
+
+/// One entry per batch item: `{ "ok": analysis }` or
+/// `{ "error": { "code", "message" } }`.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemResult {
+    Ok(LeverageAnalysis),
+    Error {
+        #[schema(value_type = String)]
+        code: &'static str,
+        message: String,
+    },
+}
+
+/// Results in the order of the request's items.
+#[derive(Serialize, ToSchema)]
+pub struct BatchLeverageResults {
+    pub results: Vec<BatchItemResult>,
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/leverage",
+    tag = "leverage",
+    request_body = LeverageRequest,
+    responses(
+        (status = 200, description = "Leverage analysis", body = LeverageAnalysis),
+        (status = 404, description = "Creditor or violation not found", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/score")]
+pub async fn calculate_leverage_score(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    request: Json<LeverageRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let analysis = engine.calculate_leverage(&request).await?;
+    
+    Ok(HttpResponse::Ok().json(analysis.for_display(&display)))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/leverage",
+    tag = "leverage",
+    request_body = BatchLeverageRequest,
+    responses(
+        (status = 200, description = "One result per item, in request order", body = BatchLeverageResults),
+        (status = 413, description = "Request body over 1 MiB", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("/batch")]
+pub async fn batch_leverage_scores(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    request: Json<BatchLeverageRequest, BULK_LIMIT>,
+) -> Result<HttpResponse, ApiError> {
+    let results: Vec<BatchItemResult> = engine
+        .calculate_leverage_batch(&request.items)
+        .await?
+        .into_iter()
+        .map(|result| match result {
+            Ok(analysis) => BatchItemResult::Ok(analysis.for_display(&display)),
+            Err(e) => {
+                let e = ApiError::from(e);
+                BatchItemResult::Error {
+                    code: e.code(),
+                    message: e.client_message().to_string(),
+                }
+            }
+        })
+        .collect();
+    
+    Ok(HttpResponse::Ok().json(BatchLeverageResults { results }))
+}
+
+/// The user's creditors, most leverage first, each scored as of the latest
+/// proposal or recompute for one of the user's settlements with it. The
+/// ranking may be up to `LEVERAGE_LEADERBOARD_CACHE_SECS` old; `cached_at`
+/// says when it was computed.
+#[utoipa::path(
+    context_path = "/api/v1/leverage",
+    tag = "leverage",
+    params(
+        LeaderboardQuery,
+    ),
+    responses(
+        (status = 200, description = "The user's creditors ranked by leverage", body = LeverageLeaderboard),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 422, description = "Invalid query parameters", body = ErrorBody),
+    )
+)]
+#[get("/leaderboard")]
+pub async fn leverage_leaderboard(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    query: Query<LeaderboardQuery>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_user_access(query.user_id, &caller)?;
+    let leaderboard = engine.leverage_leaderboard(query.user_id).await?;
+    
+    Ok(HttpResponse::Ok().json(leaderboard.for_display(&display)))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/leverage",
+    tag = "leverage",
+    params(
+        ("creditor_id" = Uuid, Path, description = "Creditor id"),
+        LeverageHistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Snapshots, oldest first", body = Vec<LeverageSnapshot>),
+    )
+)]
+#[get("/{creditor_id}/history")]
+pub async fn get_leverage_history(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    path: web::Path<Uuid>,
+    query: Query<LeverageHistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let snapshots = engine.get_leverage_history(path.into_inner(), query.since).await?;
+    
+    Ok(HttpResponse::Ok().json(snapshots.for_display(&display)))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/leverage",
+    tag = "leverage",
+    params(
+        ("creditor_id" = Uuid, Path, description = "Creditor id"),
+    ),
+    responses(
+        (status = 200, description = "The creditor's track record", body = CreditorProfile),
+        (status = 404, description = "Creditor not found", body = ErrorBody),
+    )
+)]
+#[get("/{creditor_id}/profile")]
+pub async fn get_creditor_profile(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let profile = engine.get_creditor_profile(path.into_inner()).await?;
+    
+    Ok(HttpResponse::Ok().json(profile.for_display(&display)))
+}
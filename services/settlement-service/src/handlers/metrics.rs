@@ -0,0 +1,15 @@
+use actix_web::{get, web, HttpResponse};
+
+use crate::services::metrics::Metrics;
+use crate::services::settlement_engine::SettlementEngine;
+
+#[get("/metrics")]
+pub async fn metrics(metrics: web::Data<Metrics>, engine: web::Data<SettlementEngine>) -> HttpResponse {
+    metrics.observe_db_pool(engine.db_pool_status());
+    metrics.observe_cardano_circuit(engine.cardano_circuit_state());
+    metrics.observe_event_streams(engine.open_event_streams());
+    
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics.render())
+}
@@ -0,0 +1,18 @@
+pub mod admin;
+pub mod creditors;
+pub mod debts;
+pub mod display;
+pub mod drafts;
+pub mod error;
+pub mod health;
+pub mod json;
+pub mod leverage;
+pub mod metrics;
+pub mod query;
+pub mod settlements;
+pub mod triggers;
+pub mod users;
+pub mod violations;
+pub mod webhooks;
+
+pub use error::ApiError;
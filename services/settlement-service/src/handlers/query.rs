@@ -0,0 +1,146 @@
+use std::ops::{Deref, DerefMut};
+
+use actix_web::dev::Payload;
+use actix_web::error::QueryPayloadError;
+use actix_web::{web, FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+use serde::de::DeserializeOwned;
+
+use super::ApiError;
+
+/// Query string parameters. Unlike `web::Query`, a query string that doesn't
+/// deserialize is a 422 of our own, naming the parameter at fault where it
+/// can be told; query structs deny unknown fields, so a misspelled parameter
+/// is refused rather than ignored.
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+    
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Query<T> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(parse(req.query_string()).map(Query).map_err(Into::into))
+    }
+}
+
+fn parse<T: DeserializeOwned>(query: &str) -> Result<T, ApiError> {
+    let error = match deserialize::<T>(query) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    // Unknown and missing fields are named already; a value that doesn't
+    // parse isn't, so find the parameter whose removal makes the error go.
+    let parameter = if error.starts_with("unknown field") || error.starts_with("missing field") {
+        None
+    } else {
+        culprit::<T>(query, &error)
+    };
+    
+    Err(match parameter {
+        Some(name) => ApiError::Validation(format!("invalid query parameter `{}`: {}", name, error)),
+        None => ApiError::Validation(format!("invalid query string: {}", error)),
+    })
+}
+
+fn deserialize<T: DeserializeOwned>(query: &str) -> Result<T, String> {
+    web::Query::<T>::from_query(query)
+        .map(web::Query::into_inner)
+        .map_err(|e| match e {
+            QueryPayloadError::Deserialize(e) => e.to_string(),
+            e => e.to_string(),
+        })
+}
+
+/// Name of the first parameter without which the query no longer fails
+/// with `error`.
+fn culprit<T: DeserializeOwned>(query: &str, error: &str) -> Option<String> {
+    let parameters: Vec<&str> = query.split('&').filter(|parameter| !parameter.is_empty()).collect();
+    (0..parameters.len()).find_map(|i| {
+        let rest = parameters
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, parameter)| *parameter)
+            .collect::<Vec<_>>()
+            .join("&");
+        match deserialize::<T>(&rest) {
+            Err(e) if e == error => None,
+            _ => web::Query::<Vec<(String, String)>>::from_query(parameters[i])
+                .ok()
+                .and_then(|pairs| pairs.into_inner().into_iter().next())
+                .map(|(name, _)| name),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{App, HttpResponse};
+    use serde_json::Value;
+    use uuid::Uuid;
+    
+    use super::*;
+    use crate::models::ListSettlementsQuery;
+    
+    async fn list(query: Query<ListSettlementsQuery>) -> HttpResponse {
+        HttpResponse::Ok().body(query.limit.unwrap_or_default().to_string())
+    }
+    
+    async fn get(query: &str) -> (u16, Value) {
+        let app = init_service(App::new().route("/", web::get().to(list))).await;
+        let response = call_service(&app, TestRequest::get().uri(&format!("/?{}", query)).to_request()).await;
+        let status = response.status().as_u16();
+        let body = read_body(response).await;
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+    
+    fn message(body: &Value) -> &str {
+        body["error"]["message"].as_str().unwrap()
+    }
+    
+    #[actix_web::test]
+    async fn malformed_and_unknown_parameters_are_refused() {
+        let user = format!("user_id={}", Uuid::new_v4());
+        
+        let (status, body) = get(&format!("{}&limit=abc", user)).await;
+        assert_eq!(status, 422);
+        assert_eq!(body["error"]["code"], "validation_error");
+        assert!(message(&body).starts_with("invalid query parameter `limit`:"), "{}", message(&body));
+        
+        let (status, body) = get(&format!("{}&unknown=1", user)).await;
+        assert_eq!(status, 422);
+        assert!(message(&body).contains("unknown field `unknown`"), "{}", message(&body));
+        
+        let (status, body) = get(&format!("{}&statuss=Accepted", user)).await;
+        assert_eq!(status, 422);
+        assert!(message(&body).contains("unknown field `statuss`, expected one of"), "{}", message(&body));
+        
+        let (status, body) = get("limit=5").await;
+        assert_eq!(status, 422);
+        assert!(message(&body).contains("missing field `user_id`"), "{}", message(&body));
+        
+        assert_eq!(get(&format!("{}&limit=5&status=Accepted", user)).await.0, 200);
+    }
+}
@@ -0,0 +1,1237 @@
+use std::convert::Infallible;
+
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+use uuid::Uuid;
+
+use crate::blockchain::metadata;
+use crate::middleware::caller_auth::Caller;
+use crate::middleware::creditor_auth::CreditorActor;
+use crate::models::{
+    AcceptSettlementRequest, AcceptancePredictionRequest, AutoNegotiateRequest, BundleSettlementRequest,
+    CounterOffer, CreateSettlementRequest, CreditorProposalRequest, DemandLetterQuery, ExecuteBatchRequest,
+    FieldError, GetSettlementQuery, InstallmentPlan, ListSettlementsQuery, RecomputeSettlementRequest,
+    RejectSettlementRequest, SignatureRequest, WhatIfRequest,
+};
+use crate::services::event_stream::StreamedEvent;
+use crate::services::settlement_engine::{
+    authorize_counter_party, authorize_fee_policy, authorize_include_deleted, authorize_user_access, IdempotentProposal,
+    SettlementEngine,
+};
+
+use super::display::{DisplayPrecision, ForDisplay};
+use super::json::{Json, BULK_LIMIT};
+use super::query::Query;
+use super::ApiError;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes retries safe; replays the first response for 24 hours"),
+    ),
+    request_body = CreateSettlementRequest,
+    responses(
+        (status = 201, description = "Proposal created", body = SettlementProposal),
+        (status = 200, description = "Replay of an earlier request with the same idempotency key", body = SettlementProposal),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user, or asked for a fee policy only support may grant", body = ErrorBody),
+        (status = 404, description = "Debt or creditor not found", body = ErrorBody),
+        (status = 409, description = "The idempotency key is still in use by another request", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 504, description = "The AI service timed out", body = ErrorBody),
+    )
+)]
+#[post("")]
+pub async fn create_settlement_proposal(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    http_request: HttpRequest,
+    request: Json<CreateSettlementRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.user_id, &caller)?;
+    authorize_fee_policy(request.fee_policy.as_ref(), &caller)?;
+    
+    let idempotency_key = match http_request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.trim().is_empty() => Some(key.trim().to_string()),
+            _ => {
+                return Err(ApiError::Validation(
+                    "Idempotency-Key header must be a non-empty ASCII string".to_string(),
+                ))
+            }
+        },
+        None => None,
+    };
+    
+    let Some(key) = idempotency_key else {
+        let proposal = engine.create_settlement_proposal(&request).await?;
+        return Ok(HttpResponse::Created().json(proposal.for_display(&display)));
+    };
+    
+    match engine.create_settlement_proposal_idempotent(&key, &request).await? {
+        IdempotentProposal::Created(proposal) => Ok(HttpResponse::Created().json(proposal.for_display(&display))),
+        IdempotentProposal::Replayed(response) => Ok(HttpResponse::Ok().json(display.replayed_proposal(response))),
+    }
+}
+
+/// Proposes one settlement for several debts the user owes the same creditor.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    request_body = BundleSettlementRequest,
+    responses(
+        (status = 201, description = "Bundled proposal created", body = SettlementProposal),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 404, description = "A debt was not found", body = ErrorBody),
+        (status = 413, description = "Request body over 1 MiB", body = ErrorBody),
+        (status = 422, description = "Invalid request, or debts that cannot be bundled", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 504, description = "The AI service timed out", body = ErrorBody),
+    )
+)]
+#[post("/bundle")]
+pub async fn create_bundled_settlement(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    request: Json<BundleSettlementRequest, BULK_LIMIT>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.user_id, &caller)?;
+    let proposal = engine.create_bundled_settlement(&request).await?;
+    
+    Ok(HttpResponse::Created().json(proposal.for_display(&display)))
+}
+
+/// Terms a creditor offers the user, who answers them through the usual
+/// accept, reject and counter routes. The debt must be one the user owes
+/// the authenticated creditor.
+#[utoipa::path(
+    context_path = "/api/v1/settlements/creditor-proposed",
+    tag = "settlements",
+    params(
+        ("Authorization" = String, Header, description = "`Bearer <creditor token>`"),
+    ),
+    request_body = CreditorProposalRequest,
+    responses(
+        (status = 201, description = "Proposal created; the user is notified", body = SettlementProposal),
+        (status = 401, description = "Missing or unknown creditor token", body = ErrorBody),
+        (status = 404, description = "Debt not found", body = ErrorBody),
+        (status = 422, description = "Invalid request, or a debt the user does not owe this creditor", body = ErrorBody),
+    )
+)]
+#[post("")]
+pub async fn create_creditor_proposal(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    creditor: web::ReqData<CreditorActor>,
+    request: Json<CreditorProposalRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let proposal = engine.create_creditor_proposal(creditor.0, &request).await?;
+    
+    Ok(HttpResponse::Created().json(proposal.for_display(&display)))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ListSettlementsQuery,
+    ),
+    responses(
+        (status = 200, description = "A page of the user's settlements", body = PaginatedSettlements),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user, or asked for deleted settlements without being support", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[get("")]
+pub async fn list_settlements(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    query: Query<ListSettlementsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_user_access(query.user_id, &caller)?;
+    authorize_include_deleted(query.include_deleted, &caller)?;
+    let page = engine.list_settlements(&query).await?;
+    
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        GetSettlementQuery,
+    ),
+    responses(
+        (status = 200, description = "The settlement", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement, or asked for a deleted one without being support", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}")]
+pub async fn get_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    query: Query<GetSettlementQuery>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_include_deleted(query.include_deleted, &caller)?;
+    let settlement = engine.get_settlement(path.into_inner(), query.include_deleted).await?;
+    engine.authorize_settlement_access(&settlement, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("reference" = String, Path, description = "Reference number, `DMC-<year>-<sequence>`"),
+    ),
+    responses(
+        (status = 200, description = "The settlement", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/by-ref/{reference}")]
+pub async fn get_settlement_by_reference(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.get_settlement_by_reference(&path).await?;
+    engine.authorize_settlement_access(&settlement, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Soft delete: the settlement disappears from the user's view but is kept.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The deleted settlement", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "Only proposed, rejected or expired settlements can be deleted", body = ErrorBody),
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.delete_settlement(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = AcceptSettlementRequest,
+    responses(
+        (status = 200, description = "The accepted settlement, or on a joint debt still waiting on other debtors, the settlement unchanged", body = Settlement),
+        (status = 400, description = "Acceptance requires a signature", body = ErrorBody),
+        (status = 401, description = "Missing or invalid bearer token, or the signature does not match the settlement terms", body = ErrorBody),
+        (status = 403, description = "The caller is not a debtor of the settlement, or is its creditor", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this, or an installment plan fixes its amount", body = ErrorBody),
+        (status = 410, description = "The proposal has expired", body = ErrorBody),
+        (status = 422, description = "Invalid request, e.g. an accepted_amount above the original amount", body = ErrorBody),
+    )
+)]
+#[post("/{id}/accept")]
+pub async fn accept_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<AcceptSettlementRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if request.settlement_id != path.into_inner() {
+        return Err(ApiError::Validation(
+            "settlement_id in body does not match path".to_string(),
+        ));
+    }
+    
+    engine.authorize_settlement(request.settlement_id, &caller).await?;
+    let mut request = request.into_inner();
+    match &caller {
+        // A debtor accepts as themselves.
+        Caller::User(user_id) => {
+            authorize_user_access(request.debtor_id.unwrap_or(*user_id), &caller)?;
+            request.debtor_id = Some(*user_id);
+        }
+        // Support may record a debtor's acceptance; the creditor can't give one.
+        Caller::Creditor(_) => {
+            return Err(ApiError::Forbidden(
+                "a creditor may not accept on a debtor's behalf".to_string(),
+            ))
+        }
+        Caller::Admin(_) => {}
+    }
+    let settlement = engine.accept_settlement(&request).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Which of the settlement's debtors have accepted its terms as they stand.
+/// A debt held jointly is only accepted, and paid, once they all have.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "Each debtor's acceptance, the settlement's user first", body = SettlementAcceptances),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/acceptances")]
+pub async fn get_settlement_acceptances(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.authorize_settlement(path.into_inner(), &caller).await?;
+    let acceptances = engine.settlement_acceptances(&settlement).await?;
+    
+    Ok(HttpResponse::Ok().json(acceptances))
+}
+
+/// Starts an e-signature of the settlement terms; accept with the returned
+/// envelope id once the provider reports it signed.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = SignatureRequest,
+    responses(
+        (status = 201, description = "Envelope created", body = SignatureRequested),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a debtor of the settlement, or is its creditor", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/{id}/signature")]
+pub async fn request_signature(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<SignatureRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let mut request = request.into_inner();
+    match &caller {
+        // A debtor signs as themselves.
+        Caller::User(user_id) => {
+            authorize_user_access(request.debtor_id.unwrap_or(*user_id), &caller)?;
+            request.debtor_id = Some(*user_id);
+        }
+        Caller::Creditor(_) => {
+            return Err(ApiError::Forbidden(
+                "a creditor may not sign on a debtor's behalf".to_string(),
+            ))
+        }
+        Caller::Admin(_) => {}
+    }
+    let requested = engine.request_signature(settlement_id, &request).await?;
+    
+    Ok(HttpResponse::Created().json(requested))
+}
+
+/// Envelope status callbacks from the signature provider. The raw body is
+/// what the provider signed, so it is taken as bytes.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body(content = String, content_type = "application/json", description = "Envelope event, exactly as the provider signed it"),
+    responses(
+        (status = 200, description = "Event recorded"),
+        (status = 401, description = "The callback signature does not verify", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[post("/{id}/signature-callback")]
+pub async fn signature_callback(
+    engine: web::Data<SettlementEngine>,
+    path: web::Path<Uuid>,
+    http_request: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let signature = http_request
+        .headers()
+        .get(engine.signature_callback_header())
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    engine
+        .handle_signature_callback(path.into_inner(), &body, signature)
+        .await?;
+    
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The settlement, proposed again", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not accepted or the rescission window has passed", body = ErrorBody),
+    )
+)]
+#[post("/{id}/rescind")]
+pub async fn rescind_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.rescind_settlement(settlement_id, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = RejectSettlementRequest,
+    responses(
+        (status = 200, description = "The rejected settlement", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("/{id}/reject")]
+pub async fn reject_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<RejectSettlementRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    let request = request.into_inner();
+    
+    if request.settlement_id != settlement_id {
+        return Err(ApiError::Validation(
+            "settlement_id in body does not match path".to_string(),
+        ));
+    }
+    
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine
+        .reject_settlement(settlement_id, request.reason_code, request.note.as_deref())
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The settlement, submitted on-chain; once submitted, repeats return the same transaction", body = Settlement),
+        (status = 202, description = "The Cardano node is unavailable; the settlement is queued and submitted once it is back", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/{id}/execute")]
+pub async fn execute_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.execute_settlement(settlement_id).await?;
+    
+    if settlement.execution_queued_at.is_some() {
+        return Ok(HttpResponse::Accepted().json(settlement));
+    }
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Pays several accepted settlements owed to the same creditor in one
+/// Cardano transaction, all or nothing.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    request_body = ExecuteBatchRequest,
+    responses(
+        (status = 200, description = "Every settlement, submitted together in one transaction", body = BatchExecution),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to every settlement", body = ErrorBody),
+        (status = 404, description = "A settlement was not found", body = ErrorBody),
+        (status = 409, description = "A settlement is not accepted or was already submitted; nothing was submitted", body = ErrorBody),
+        (status = 422, description = "Invalid request, or settlements owed to different creditors", body = ErrorBody),
+        (status = 502, description = "The Cardano node refused the transaction; nothing was submitted", body = ErrorBody),
+        (status = 503, description = "The Cardano node is unavailable; nothing was submitted or queued", body = ErrorBody),
+    )
+)]
+#[post("/execute-batch")]
+pub async fn execute_batch(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    request: Json<ExecuteBatchRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let batch = engine.execute_batch(&request.settlement_ids, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The settlement, resubmitted", body = Settlement),
+        (status = 202, description = "The Cardano node is unavailable; the resubmission is queued", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/{id}/retry")]
+pub async fn retry_settlement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.retry_settlement(settlement_id).await?;
+    
+    if settlement.execution_queued_at.is_some() {
+        return Ok(HttpResponse::Accepted().json(settlement));
+    }
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Pays an escrowed settlement's payment out to the creditor, completing
+/// the settlement. Only the creditor holding the debt, or support, may
+/// release it.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The settlement, completed by the release", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not the settlement's creditor", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not escrowed", body = ErrorBody),
+        (status = 502, description = "The Cardano node refused the release", body = ErrorBody),
+    )
+)]
+#[post("/{id}/release")]
+pub async fn release_escrow(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.release_escrow(settlement_id, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+/// Returns an escrowed payment the creditor hasn't released to the debtor.
+/// Refunds open `ESCROW_REFUND_AFTER_HOURS` (30 days by default) after the
+/// payment was locked, at the settlement's `escrow_refund_after`; until a
+/// refund is asked for, the creditor can still release it.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The settlement, refunded", body = Settlement),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not escrowed, or its refund deadline hasn't passed", body = ErrorBody),
+        (status = 502, description = "The Cardano node refused the refund", body = ErrorBody),
+    )
+)]
+#[post("/{id}/refund")]
+pub async fn refund_escrow(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let settlement = engine.refund_escrow(settlement_id, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(settlement))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "Expected cost of executing now", body = FeeEstimate),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[get("/{id}/estimate")]
+pub async fn estimate_settlement_fee(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let estimate = engine.estimate_settlement_fee(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// Projects the settlement for a scenario with and without hypothetical
+/// extra violations. Nothing is stored.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    request_body = WhatIfRequest,
+    responses(
+        (status = 200, description = "The documented violations alone, then each hypothetical one added in turn", body = [WhatIfProjection]),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 404, description = "Debt not found", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/what-if")]
+pub async fn what_if(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    request: Json<WhatIfRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.base.user_id, &caller)?;
+    
+    let projections = engine.what_if(&request).await?;
+    
+    Ok(HttpResponse::Ok().json(projections.for_display(&display)))
+}
+
+/// Predicts how likely the creditor is to accept an amount, from the
+/// leverage and the creditor's record. Nothing is stored.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    request_body = AcceptancePredictionRequest,
+    responses(
+        (status = 200, description = "The acceptance probability, a suggested amount and what drove them", body = AcceptancePrediction),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 504, description = "The AI service timed out", body = ErrorBody),
+    )
+)]
+#[post("/predict-acceptance")]
+pub async fn predict_acceptance(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    request: Json<AcceptancePredictionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.user_id, &caller)?;
+    let prediction = engine.predict_acceptance(&request).await?;
+    
+    Ok(HttpResponse::Ok().json(prediction))
+}
+
+/// Recomputes a proposal whose leverage went stale as violations were
+/// documented after it was made, and replaces its terms.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = RecomputeSettlementRequest,
+    responses(
+        (status = 200, description = "The recomputed proposal and how its terms changed", body = RecomputedProposal),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is no longer open, or its terms were set by the creditor or split into installments", body = ErrorBody),
+        (status = 410, description = "The proposal has expired", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/{id}/recompute")]
+pub async fn recompute_settlement(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<RecomputeSettlementRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let recomputed = engine.recompute_settlement(settlement_id, &request).await?;
+    
+    Ok(HttpResponse::Ok().json(recomputed.for_display(&display)))
+}
+
+/// Proposes a settlement on the user's behalf against their active debt
+/// with the creditor. Triggers from other services go through the job queue
+/// instead; this runs the negotiation in the request.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    request_body = AutoNegotiateRequest,
+    responses(
+        (status = 201, description = "Proposal created", body = SettlementProposal),
+        (status = 200, description = "Dry run: the proposal that would be made", body = SettlementProposal),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+        (status = 404, description = "The user has no active debt with the creditor", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 504, description = "The AI service timed out", body = ErrorBody),
+    )
+)]
+#[post("/auto-negotiate")]
+pub async fn auto_negotiate(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    request: Json<AutoNegotiateRequest>,
+) -> Result<HttpResponse, ApiError> {
+    request.validate().map_err(ApiError::InvalidFields)?;
+    authorize_user_access(request.user_id, &caller)?;
+    
+    let proposal = engine.auto_negotiate(&request).await?.for_display(&display);
+    if request.dry_run {
+        return Ok(HttpResponse::Ok().json(proposal));
+    }
+    Ok(HttpResponse::Created().json(proposal))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = InstallmentPlan,
+    responses(
+        (status = 201, description = "The installment schedule", body = InstallmentSchedule),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("/{id}/installments")]
+pub async fn create_installment_plan(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    plan: Json<InstallmentPlan>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    let plan = plan.into_inner();
+    
+    if plan.settlement_id != settlement_id {
+        return Err(ApiError::Validation(
+            "settlement_id in body does not match path".to_string(),
+        ));
+    }
+    
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let schedule = engine.create_installment_plan(&plan).await?;
+    
+    Ok(HttpResponse::Created().json(schedule))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The installment schedule and the fee accrued so far", body = InstallmentSchedule),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/installments")]
+pub async fn get_installments(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let schedule = engine.get_installments(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(schedule))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The debts the settlement pays off and each one's share", body = SettlementDebts),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/debts")]
+pub async fn get_settlement_debts(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let debts = engine.get_settlement_debts(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(debts))
+}
+
+/// Records that installment `n` was paid. The platform fee accrues with
+/// each payment rather than being owed upfront.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        ("n" = i32, Path, description = "Installment sequence number, from 1"),
+    ),
+    responses(
+        (status = 200, description = "The installment schedule and the fee accrued so far", body = InstallmentSchedule),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement or installment not found", body = ErrorBody),
+        (status = 409, description = "The installment is already paid or the settlement is not accepted", body = ErrorBody),
+    )
+)]
+#[post("/{id}/installments/{n}/pay")]
+pub async fn pay_installment(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<(Uuid, i32)>,
+) -> Result<HttpResponse, ApiError> {
+    let (settlement_id, sequence) = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let schedule = engine.pay_installment(settlement_id, sequence, &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(schedule))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    request_body = CounterOffer,
+    responses(
+        (status = 200, description = "The recorded round and the recommended response", body = CounterOfferResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement, or countered as the other party", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is not in a status that allows this", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[post("/{id}/counter")]
+pub async fn counter_offer(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    offer: Json<CounterOffer>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    let settlement = engine.authorize_settlement(settlement_id, &caller).await?;
+    // A counter is the caller's, whatever party the body names: one posing
+    // as the creditor could be auto-accepted.
+    authorize_counter_party(offer.from, &caller)?;
+    // Only the user may pre-authorize what is accepted in their name.
+    if offer.auto_accept_below.is_some() {
+        authorize_user_access(settlement.user_id, &caller)?;
+    }
+    let response = engine.counter_offer(settlement_id, &offer).await?;
+    
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "Negotiation rounds, oldest first", body = Vec<NegotiationRound>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/rounds")]
+pub async fn get_negotiation_rounds(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let rounds = engine.get_negotiation_rounds(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(rounds))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "Audit trail, oldest first", body = Vec<AuditEvent>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/events")]
+pub async fn get_settlement_events(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let events = engine.get_settlement_events(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(events))
+}
+
+/// The audit trail as Server-Sent Events: a replay of recent events, or of
+/// those after `Last-Event-ID` on reconnect, then each event as it is
+/// recorded. Every event's SSE id is its audit event id.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        ("Last-Event-ID" = Option<i64>, Header, description = "Resume after this event instead of replaying the most recent ones"),
+    ),
+    responses(
+        (status = 200, description = "Event stream; each `data` line is an AuditEvent", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 422, description = "Last-Event-ID is not an event id", body = ErrorBody),
+    )
+)]
+#[get("/{id}/stream")]
+pub async fn stream_settlement_events(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    http_request: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    let last_event_id = match http_request.headers().get(LAST_EVENT_ID_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .filter(|id| *id >= 0)
+                .ok_or_else(|| {
+                    ApiError::InvalidFields(vec![FieldError::new(LAST_EVENT_ID_HEADER, "must be an event id")])
+                })?,
+        ),
+        None => None,
+    };
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let events = engine.settlement_event_stream(settlement_id, last_event_id).await?;
+    
+    let body = events.map(|event| {
+        let frame = match event {
+            StreamedEvent::Event(event) => match serde_json::to_string(&event) {
+                Ok(data) => format!("id: {}\ndata: {}\n\n", event.id, data),
+                Err(_) => String::new(),
+            },
+            StreamedEvent::KeepAlive => ": keep-alive\n\n".to_string(),
+        };
+        Ok::<_, Infallible>(web::Bytes::from(frame))
+    });
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(body))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "Whether the on-chain metadata matches the stored terms", body = MetadataVerification),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement has not been submitted on-chain", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+    )
+)]
+#[get("/{id}/verify")]
+pub async fn verify_settlement_metadata(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let verification = engine.verify_settlement_metadata(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok().json(verification))
+}
+
+/// The settlement's terms in the canonical form hashed into its payment's
+/// metadata, so anyone can recompute the hash. Terms are final once
+/// accepted; before then the hash follows the current terms.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The canonical terms, their hash and how it is computed", body = CanonicalTerms),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/terms-hash")]
+pub async fn get_terms_hash(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.authorize_settlement(path.into_inner(), &caller).await?;
+    
+    Ok(HttpResponse::Ok().json(metadata::canonical_terms(&settlement)))
+}
+
+/// The settlement's payment as the Cardano node sees it, with a block
+/// explorer link. Node answers are reused for a few seconds, so polling is
+/// cheap.
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The transaction's status; `not_submitted` before execution", body = TransactionStatus),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 502, description = "Upstream service error", body = ErrorBody),
+        (status = 503, description = "The Cardano node is unavailable", body = ErrorBody),
+    )
+)]
+#[get("/{id}/transaction")]
+pub async fn get_transaction_status(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.authorize_settlement(path.into_inner(), &caller).await?;
+    let status = engine.transaction_status(&settlement).await?;
+    
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+    ),
+    responses(
+        (status = 200, description = "The agreement document", content_type = "application/pdf", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/agreement.pdf")]
+pub async fn get_settlement_agreement(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement_id = path.into_inner();
+    engine.authorize_settlement(settlement_id, &caller).await?;
+    let pdf = engine.settlement_agreement_pdf(settlement_id).await?;
+    
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!("inline; filename=\"settlement-{}-agreement.pdf\"", settlement_id),
+        ))
+        .body(pdf.as_ref().clone()))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        DemandLetterQuery,
+    ),
+    responses(
+        (status = 200, description = "The demand letter", content_type = "text/plain", body = String),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is no longer open, or cites no violations", body = ErrorBody),
+        (status = 422, description = "Invalid response_days", body = ErrorBody),
+    )
+)]
+#[get("/{id}/demand-letter")]
+pub async fn get_demand_letter(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    query: Query<DemandLetterQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.authorize_settlement(path.into_inner(), &caller).await?;
+    let letter = engine.demand_letter(&settlement, query.response_days).await?;
+    
+    Ok(HttpResponse::Ok().content_type("text/plain; charset=utf-8").body(letter))
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/settlements",
+    tag = "settlements",
+    params(
+        ("id" = Uuid, Path, description = "Settlement id"),
+        DemandLetterQuery,
+    ),
+    responses(
+        (status = 200, description = "The demand letter", content_type = "application/pdf", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller is not a party to the settlement", body = ErrorBody),
+        (status = 404, description = "Settlement not found", body = ErrorBody),
+        (status = 409, description = "The settlement is no longer open, or cites no violations", body = ErrorBody),
+        (status = 422, description = "Invalid response_days", body = ErrorBody),
+    )
+)]
+#[get("/{id}/demand-letter.pdf")]
+pub async fn get_demand_letter_pdf(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    query: Query<DemandLetterQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let settlement = engine.authorize_settlement(path.into_inner(), &caller).await?;
+    let pdf = engine.demand_letter_pdf(&settlement, query.response_days).await?;
+    
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!("inline; filename=\"settlement-{}-demand-letter.pdf\"", settlement.id),
+        ))
+        .body(pdf))
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use actix_web::{post, web, HttpRequest, HttpResponse};
+
+use crate::models::{InboundEmail, SwordEvent};
+use crate::services::settlement_engine::SettlementEngine;
+use crate::services::triggers::{EMAIL_SIGNATURE_HEADER, SWORD_SIGNATURE_HEADER};
+
+use super::ApiError;
+
+/// Receives a SWORD protocol event and queues auto-negotiation for the user
+/// and creditor it concerns. Events are signed like our own webhooks; each is
+/// queued once, however often it is delivered.
+#[utoipa::path(
+    context_path = "/api/v1/triggers",
+    tag = "triggers",
+    params(
+        ("X-Sword-Signature" = String, Header, description = "`sha256=<hex HMAC-SHA256 of the body>` under the shared trigger secret"),
+    ),
+    request_body(content = SwordEvent, content_type = "application/json"),
+    responses(
+        (status = 202, description = "Auto-negotiation queued", body = EnqueuedJob),
+        (status = 200, description = "The event was received before; the job it queued", body = EnqueuedJob),
+        (status = 401, description = "The event signature does not verify", body = ErrorBody),
+        (status = 422, description = "Invalid event", body = ErrorBody),
+    )
+)]
+#[post("/sword")]
+pub async fn sword_trigger(
+    engine: web::Data<SettlementEngine>,
+    http_request: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    engine.verify_sword_event(&body, signature(&http_request, SWORD_SIGNATURE_HEADER))?;
+    
+    let event: SwordEvent = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::Validation(format!("invalid SWORD event: {}", e)))?;
+    event.validate().map_err(ApiError::InvalidFields)?;
+    
+    let enqueued = engine.enqueue_sword_event(&event).await?;
+    if enqueued.created {
+        Ok(HttpResponse::Accepted().json(enqueued))
+    } else {
+        Ok(HttpResponse::Ok().json(enqueued))
+    }
+}
+
+/// Receives a creditor's reply to a proposal from the inbound mail provider.
+/// The settlement is the one whose reference number the subject carries; an
+/// acceptance or counter the AI service reads in the body becomes the
+/// creditor's negotiation round, and a refusal rejects the settlement.
+/// Emails that can't be acted on are kept for review. Each message is acted
+/// on once, however often it is delivered.
+#[utoipa::path(
+    context_path = "/api/v1/triggers",
+    tag = "triggers",
+    params(
+        ("X-Email-Signature" = String, Header, description = "`sha256=<hex HMAC-SHA256 of the body>` under the shared email secret"),
+    ),
+    request_body(content = InboundEmail, content_type = "application/json"),
+    responses(
+        (status = 202, description = "The email, acted on or kept for review", body = ReceivedEmail),
+        (status = 200, description = "The email was received before; what was done about it", body = ReceivedEmail),
+        (status = 401, description = "The email signature does not verify", body = ErrorBody),
+        (status = 422, description = "Invalid email", body = ErrorBody),
+    )
+)]
+#[post("/email")]
+pub async fn email_trigger(
+    engine: web::Data<SettlementEngine>,
+    http_request: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    engine.verify_creditor_email(&body, signature(&http_request, EMAIL_SIGNATURE_HEADER))?;
+    
+    let email: InboundEmail = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::Validation(format!("invalid email: {}", e)))?;
+    email.validate().map_err(ApiError::InvalidFields)?;
+    
+    let received = engine.receive_creditor_email(&email).await?;
+    if received.created {
+        Ok(HttpResponse::Accepted().json(received))
+    } else {
+        Ok(HttpResponse::Ok().json(received))
+    }
+}
+
+fn signature<'a>(http_request: &'a HttpRequest, header: &str) -> &'a str {
+    http_request
+        .headers()
+        .get(header)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,42 @@
+use actix_web::{get, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::SavingsSummaryQuery;
+use crate::services::settlement_engine::{authorize_user_access, SettlementEngine};
+
+use super::display::{DisplayPrecision, ForDisplay};
+use super::query::Query;
+use super::ApiError;
+
+/// Totals over the user's completed settlements in one currency, with the
+/// reduction weighted by debt size.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    tag = "users",
+    params(
+        ("id" = Uuid, Path, description = "User id"),
+        SavingsSummaryQuery,
+    ),
+    responses(
+        (status = 200, description = "The user's savings; zeros before any settlement completes", body = SavingsSummary),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this user", body = ErrorBody),
+    )
+)]
+#[get("/{id}/savings-summary")]
+pub async fn savings_summary(
+    engine: web::Data<SettlementEngine>,
+    display: web::Data<DisplayPrecision>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    query: Query<SavingsSummaryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = path.into_inner();
+    authorize_user_access(user_id, &caller)?;
+    let summary = engine
+        .savings_summary(user_id, query.into_inner().currency)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(summary.for_display(&display)))
+}
@@ -0,0 +1,67 @@
+use actix_web::{get, post, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::AttachEvidenceRequest;
+use crate::services::settlement_engine::SettlementEngine;
+
+use super::json::Json;
+use super::ApiError;
+
+/// Attaches evidence metadata to a violation. The response carries a
+/// presigned URL the file itself is uploaded to.
+#[utoipa::path(
+    context_path = "/api/v1/violations",
+    tag = "violations",
+    params(
+        ("id" = Uuid, Path, description = "Violation id"),
+    ),
+    request_body = AttachEvidenceRequest,
+    responses(
+        (status = 201, description = "Evidence recorded; upload the file to `upload_url`", body = EvidenceUpload),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller does not owe the violation's debt", body = ErrorBody),
+        (status = 404, description = "Violation not found", body = ErrorBody),
+        (status = 409, description = "The violation already has evidence with this hash", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("/{id}/evidence")]
+pub async fn attach_evidence(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+    request: Json<AttachEvidenceRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let upload = engine.attach_evidence(path.into_inner(), &request, &caller).await?;
+    
+    Ok(HttpResponse::Created().json(upload))
+}
+
+/// The settlements whose terms cite the violation, each with how the
+/// violation figured in its leverage and the points it contributed.
+#[utoipa::path(
+    context_path = "/api/v1/violations",
+    tag = "violations",
+    params(
+        ("id" = Uuid, Path, description = "Violation id"),
+    ),
+    responses(
+        (status = 200, description = "Settlements citing the violation, newest first; a user sees only their own", body = Vec<ViolationSettlement>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The violation was committed by another creditor", body = ErrorBody),
+        (status = 404, description = "Violation not found", body = ErrorBody),
+    )
+)]
+#[get("/{id}/settlements")]
+pub async fn get_violation_settlements(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settlements = engine
+        .get_violation_settlements(path.into_inner(), &caller)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(settlements))
+}
@@ -0,0 +1,92 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use uuid::Uuid;
+
+use crate::middleware::caller_auth::Caller;
+use crate::models::{ListWebhooksQuery, RegisterWebhookRequest};
+use crate::services::settlement_engine::{authorize_creditor_access, SettlementEngine};
+
+use super::json::Json;
+use super::query::Query;
+use super::ApiError;
+
+/// Registering a URL the creditor already has a webhook for doesn't add
+/// another: that webhook is returned, subscribed to the new events too, and
+/// the URL isn't challenged again.
+#[utoipa::path(
+    context_path = "/api/v1/webhooks",
+    tag = "webhooks",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered; the secret is only returned here", body = RegisteredWebhook),
+        (status = 200, description = "The creditor's existing webhook for the URL", body = RegisteredWebhook),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Invalid request", body = ErrorBody),
+    )
+)]
+#[post("")]
+pub async fn register_webhook(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    request: Json<RegisterWebhookRequest>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_creditor_access(request.creditor_id, &caller)?;
+    let webhook = engine.register_webhook(&request).await?;
+    
+    Ok(if webhook.created {
+        HttpResponse::Created().json(webhook)
+    } else {
+        HttpResponse::Ok().json(webhook)
+    })
+}
+
+/// The creditor's active webhooks, oldest first.
+#[utoipa::path(
+    context_path = "/api/v1/webhooks",
+    tag = "webhooks",
+    params(ListWebhooksQuery),
+    responses(
+        (status = 200, description = "Webhooks", body = Vec<Webhook>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The caller may not act for this creditor", body = ErrorBody),
+        (status = 422, description = "Invalid query", body = ErrorBody),
+    )
+)]
+#[get("")]
+pub async fn list_webhooks(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    query: Query<ListWebhooksQuery>,
+) -> Result<HttpResponse, ApiError> {
+    authorize_creditor_access(query.creditor_id, &caller)?;
+    let webhooks = engine.list_webhooks(&query).await?;
+    
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+/// Stops deliveries to the webhook. Its delivery history is kept.
+#[utoipa::path(
+    context_path = "/api/v1/webhooks",
+    tag = "webhooks",
+    params(
+        ("id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 200, description = "The deleted webhook", body = Webhook),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorBody),
+        (status = 403, description = "The webhook is another creditor's", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete_webhook(
+    engine: web::Data<SettlementEngine>,
+    caller: Caller,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let webhook = engine.get_webhook(path.into_inner()).await?;
+    authorize_creditor_access(webhook.creditor_id, &caller)?;
+    let webhook = engine.delete_webhook(webhook.id).await?;
+    
+    Ok(HttpResponse::Ok().json(webhook))
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Message catalogs compiled into the binary, by locale. `en-US` is complete
+/// and every other catalog may leave keys out.
+const CATALOGS: &[(&str, &str)] = &[
+    (DEFAULT_LOCALE, include_str!("../templates/messages/en-US.json")),
+    ("es-US", include_str!("../templates/messages/es-US.json")),
+];
+
+const DEFAULT_LOCALE: &str = "en-US";
+
+/// A BCP 47 language tag limited to a language and an optional region, e.g.
+/// `en-US` or `es`. Messages are rendered from the catalog of the exact tag,
+/// then of any locale with the same language, then of `en-US`, so an
+/// unsupported locale still gets English rather than an error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schema(value_type = String, example = "en-US")]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn en_us() -> Self {
+        Locale(DEFAULT_LOCALE.to_string())
+    }
+    
+    pub fn tag(&self) -> &str {
+        &self.0
+    }
+    
+    fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+    
+    /// Renders the message `key` with `{name}` placeholders filled from
+    /// `values`. A key no catalog has renders as the key itself.
+    pub fn message(&self, key: &str, values: &[(&str, String)]) -> String {
+        let catalogs = catalogs();
+        let template = catalogs
+            .get(self.tag())
+            .and_then(|catalog| catalog.get(key))
+            .or_else(|| {
+                CATALOGS
+                    .iter()
+                    .filter(|(tag, _)| tag.split('-').next() == Some(self.language()))
+                    .find_map(|(tag, _)| catalogs[tag].get(key))
+            })
+            .or_else(|| catalogs[DEFAULT_LOCALE].get(key));
+        
+        match template {
+            Some(template) => render(template, values),
+            None => key.to_string(),
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::en_us()
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+    
+    /// Accepts any letter case and normalizes it, so `en-us` is `en-US`.
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("{:?} is not a language tag like \"en-US\"", tag);
+        let (language, region) = match tag.split_once('-') {
+            Some((language, region)) => (language, Some(region)),
+            None => (tag.as_str(), None),
+        };
+        
+        let language_ok = (2..=3).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_alphabetic());
+        let region_ok = region.is_none_or(|region| {
+            (region.len() == 2 && region.bytes().all(|b| b.is_ascii_alphabetic()))
+                || (region.len() == 3 && region.bytes().all(|b| b.is_ascii_digit()))
+        });
+        if !language_ok || !region_ok {
+            return Err(invalid());
+        }
+        
+        Ok(Locale(match region {
+            Some(region) => format!("{}-{}", language.to_ascii_lowercase(), region.to_ascii_uppercase()),
+            None => language.to_ascii_lowercase(),
+        }))
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.0
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(tag, raw)| {
+                let catalog = serde_json::from_str(raw)
+                    .unwrap_or_else(|e| panic!("built-in message catalog {} is invalid: {}", tag, e));
+                (*tag, catalog)
+            })
+            .collect()
+    })
+}
+
+/// Fills `{name}` placeholders; `{{` and `}}` stand for literal braces, as in
+/// the prompt templates. A placeholder without a value renders empty.
+fn render(template: &str, values: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                if let Some((_, value)) = values.iter().find(|(key, _)| *key == name) {
+                    rendered.push_str(value);
+                }
+            }
+            c => rendered.push(c),
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    
+    use super::*;
+    
+    fn placeholders(template: &str) -> BTreeSet<String> {
+        template
+            .replace("{{", "")
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name.to_string()))
+            .collect()
+    }
+    
+    #[test]
+    fn every_catalog_translates_only_english_keys_and_placeholders() {
+        let catalogs = catalogs();
+        let english = &catalogs[DEFAULT_LOCALE];
+        
+        for (tag, catalog) in catalogs {
+            for (key, template) in catalog {
+                let Some(original) = english.get(key) else {
+                    panic!("{} has key {} that en-US lacks", tag, key);
+                };
+                assert!(
+                    placeholders(template).is_subset(&placeholders(original)),
+                    "{} {} uses placeholders en-US does not: {}",
+                    tag,
+                    key,
+                    template
+                );
+            }
+        }
+    }
+    
+    #[test]
+    fn messages_fall_back_to_the_language_then_english() {
+        let values = [("violation_count", "3".to_string())];
+        let english = Locale::en_us().message("action.send_now", &values);
+        let spanish = Locale::try_from("es-US".to_string()).unwrap().message("action.send_now", &values);
+        
+        assert!(english.contains("3 documented violations"), "{}", english);
+        assert_ne!(spanish, english);
+        assert!(spanish.contains('3'), "{}", spanish);
+        assert_eq!(Locale::try_from("es-MX".to_string()).unwrap().message("action.send_now", &values), spanish);
+        assert_eq!(Locale::try_from("fr-FR".to_string()).unwrap().message("action.send_now", &values), english);
+        assert_eq!(Locale::en_us().message("no.such.key", &values), "no.such.key");
+    }
+    
+    #[test]
+    fn tags_are_normalized_and_validated() {
+        assert_eq!(Locale::try_from("ES-us".to_string()).unwrap().tag(), "es-US");
+        assert_eq!(Locale::try_from("es-419".to_string()).unwrap().tag(), "es-419");
+        assert_eq!(Locale::default().tag(), "en-US");
+        for bad in ["", "e", "english", "en_US", "en-U", "en-USA", "12-US"] {
+            assert!(Locale::try_from(bad.to_string()).is_err(), "{}", bad);
+        }
+    }
+    
+    #[test]
+    fn placeholders_render_and_braces_escape() {
+        let rendered = render("{a} of {b} {{literal}} {missing}.", &[("a", "1".to_string()), ("b", "2".to_string())]);
+        
+        assert_eq!(rendered, "1 of 2 {literal} .");
+    }
+}
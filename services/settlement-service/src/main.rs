@@ -1,75 +1,278 @@
-use actix_web::{web, App, HttpServer, Result, middleware::Logger};
-use tracing::{info, error};
-use std::env;
+use actix_web::{web, App, HttpServer, Result, middleware::Logger, dev::Service};
+use tracing::{info, error, warn};
+use std::sync::Arc;
 use dotenv::dotenv;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
+mod config;
 mod models;
 mod handlers;
 mod services;
 mod database;
 mod blockchain;
-mod ai;
+mod middleware;
+mod money;
+mod i18n;
+mod esign;
+mod openapi;
+mod startup;
 
-use database::Database;
+use config::{Config, ConfigError};
 use services::settlement_engine::SettlementEngine;
 use services::ai_client::AiClient;
+use services::prompts::PromptTemplates;
+use services::demand_letter::DemandLetterTemplate;
+use services::leverage::LeverageEngine;
+use services::metrics::Metrics;
+use middleware::admin_auth::{AdminAuth, AdminTokens};
+use middleware::caller_auth::{CallerAuth, UserTokens};
+use middleware::creditor_auth::{CreditorAuth, CreditorTokens};
+use middleware::rate_limit::{RateLimit, RateLimiter};
 use blockchain::cardano_client::CardanoClient;
+use blockchain::CircuitBreaker;
+use esign::docusign::DocuSignProvider;
 
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
     
-    tracing_subscriber::init();
+    tracing_subscriber::fmt::init();
     
-    let database_url = env::var("DATABASE_URL")
-        .expect("DATABASE_URL must be set");
+    // Every problem at once, before anything connects or binds.
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(ConfigError(problems)) => {
+            for problem in &problems {
+                error!("Configuration: {}", problem);
+            }
+            error!("Refusing to start with {} configuration problems", problems.len());
+            std::process::exit(1);
+        }
+    };
     
-    let cardano_node_url = env::var("CARDANO_NODE_URL")
-        .expect("CARDANO_NODE_URL must be set");
-    
-    let port = env::var("SETTLEMENT_SERVICE_PORT")
-        .unwrap_or_else(|_| "8003".to_string())
-        .parse::<u16>()
-        .expect("Invalid port number");
-    
-    // Initialize services
-    let db = Database::connect(&database_url).await?;
-    let ai_client = AiClient::new();
-    let blockchain_client = CardanoClient::new(&cardano_node_url);
+    // Initialize services. Nothing binds until the dependencies answer, so
+    // the first requests don't fail on a cold start.
+    let cardano_client = CardanoClient::new(&config.cardano_node_url).with_retry(config.cardano_retry);
+    let dependencies =
+        startup::await_dependencies(config.startup, &config.database_url, config.pool, &cardano_client);
+    let db = match dependencies.await {
+        Ok(db) => db,
+        Err(e) => {
+            error!("Refusing to start: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let ai_client = AiClient::new(PromptTemplates::from_env()?).with_retry(config.ai_retry);
+    if let Some(simulation) = ai_client.simulation() {
+        info!("Simulation mode: AI answers are derived from seed {}", simulation.seed);
+    }
+    let blockchain_client = Arc::new(CircuitBreaker::new(cardano_client, config.breaker));
+    let signature_provider = Arc::new(DocuSignProvider::from_env());
+    let leverage_engine = LeverageEngine::from_env()?;
+    let metrics = Metrics::new();
+    let rate_limiter = RateLimiter::from_env();
+    let admin_tokens = AdminTokens::from_env();
+    let creditor_tokens = CreditorTokens::from_env();
+    let caller_auth = CallerAuth::new(UserTokens::from_env(), creditor_tokens.clone(), admin_tokens.clone());
     
     let settlement_engine = SettlementEngine::new(
         db.clone(),
         ai_client,
         blockchain_client,
-    );
+        signature_provider,
+        leverage_engine,
+        metrics.clone(),
+    )
+    .with_demand_letters(DemandLetterTemplate::from_env()?);
+    
+    settlement_engine.spawn_expiry_sweeper();
+    settlement_engine.spawn_auto_negotiation_workers();
+    settlement_engine.spawn_reconciler();
+    // Before the queue starts, so what a crash left half-submitted is
+    // settled before anything is submitted again.
+    match settlement_engine.recover_submissions().await {
+        Ok(0) => {}
+        Ok(recovered) => info!("Recovered {} interrupted submissions", recovered),
+        Err(e) => warn!("Recovering interrupted submissions failed: {}", e),
+    }
+    settlement_engine.spawn_execution_queue();
+    
+    let api_doc = openapi::ApiDoc::openapi();
     
-    info!("Starting Settlement Service on port {}", port);
+    info!("Starting Settlement Service on port {}", config.port);
     
-    HttpServer::new(move || {
+    let engine = settlement_engine.clone();
+    let server = HttpServer::new(move || {
+        let request_metrics = metrics.clone();
+        
         App::new()
             .app_data(web::Data::new(settlement_engine.clone()))
+            .app_data(web::Data::new(metrics.clone()))
+            .app_data(web::Data::new(config.display))
+            .app_data(web::Data::new(caller_auth.clone()))
             .wrap(Logger::default())
+            .wrap_fn(middleware::request_id::request_id)
+            .wrap_fn(move |req, srv| {
+                let in_flight = request_metrics.track_in_flight();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await;
+                    drop(in_flight);
+                    response
+                }
+            })
+            .service(handlers::metrics::metrics)
+            .service(SwaggerUi::new("/swagger/{_:.*}").url("/api-docs/openapi.json", api_doc.clone()))
             .service(
                 web::scope("/api/v1")
                     .service(handlers::health::health_check)
                     .service(
                         web::scope("/settlements")
+                            .wrap(RateLimit(rate_limiter.clone()))
                             .service(handlers::settlements::create_settlement_proposal)
+                            .service(handlers::settlements::create_bundled_settlement)
+                            .service(handlers::settlements::list_settlements)
+                            .service(handlers::settlements::get_settlement_by_reference)
+                            .service(handlers::settlements::execute_batch)
+                            .service(
+                                web::scope("/creditor-proposed")
+                                    .wrap(CreditorAuth(creditor_tokens.clone()))
+                                    .service(handlers::settlements::create_creditor_proposal)
+                            )
                             .service(handlers::settlements::get_settlement)
+                            .service(handlers::settlements::delete_settlement)
                             .service(handlers::settlements::accept_settlement)
+                            .service(handlers::settlements::get_settlement_acceptances)
+                            .service(handlers::settlements::request_signature)
+                            .service(handlers::settlements::signature_callback)
+                            .service(handlers::settlements::reject_settlement)
+                            .service(handlers::settlements::rescind_settlement)
                             .service(handlers::settlements::execute_settlement)
+                            .service(handlers::settlements::retry_settlement)
+                            .service(handlers::settlements::release_escrow)
+                            .service(handlers::settlements::refund_escrow)
+                            .service(handlers::settlements::estimate_settlement_fee)
+                            .service(handlers::settlements::what_if)
+                            .service(handlers::settlements::predict_acceptance)
+                            .service(handlers::settlements::recompute_settlement)
                             .service(handlers::settlements::auto_negotiate)
+                            .service(handlers::settlements::create_installment_plan)
+                            .service(handlers::settlements::get_installments)
+                            .service(handlers::settlements::get_settlement_debts)
+                            .service(handlers::settlements::pay_installment)
+                            .service(handlers::settlements::counter_offer)
+                            .service(handlers::settlements::get_negotiation_rounds)
+                            .service(handlers::settlements::get_settlement_events)
+                            .service(handlers::settlements::stream_settlement_events)
+                            .service(handlers::settlements::verify_settlement_metadata)
+                            .service(handlers::settlements::get_terms_hash)
+                            .service(handlers::settlements::get_transaction_status)
+                            .service(handlers::settlements::get_settlement_agreement)
+                            .service(handlers::settlements::get_demand_letter)
+                            .service(handlers::settlements::get_demand_letter_pdf)
+                    )
+                    .service(
+                        web::scope("/creditors")
+                            .service(handlers::creditors::set_creditor_contact)
+                            .service(handlers::creditors::get_creditor_contact)
+                            .service(handlers::creditors::set_settlement_floor)
+                            .service(handlers::creditors::set_execution_mode)
+                            .service(handlers::creditors::set_creditor_terms)
+                            .service(handlers::creditors::get_creditor_terms)
+                    )
+                    .service(
+                        web::scope("/users")
+                            .service(handlers::users::savings_summary)
+                    )
+                    .service(
+                        web::scope("/debts")
+                            .service(handlers::debts::record_debt_verification)
+                            .service(handlers::debts::import_violations)
+                    )
+                    .service(
+                        web::scope("/violations")
+                            .service(handlers::violations::attach_evidence)
+                            .service(handlers::violations::get_violation_settlements)
+                    )
+                    .service(
+                        web::scope("/triggers")
+                            .service(handlers::triggers::sword_trigger)
+                            .service(handlers::triggers::email_trigger)
+                    )
+                    .service(
+                        web::scope("/drafts")
+                            .service(handlers::drafts::create_draft)
+                            .service(handlers::drafts::list_drafts)
+                            .service(handlers::drafts::get_draft)
+                            .service(handlers::drafts::update_draft)
+                            .service(handlers::drafts::finalize_draft)
+                    )
+                    .service(
+                        web::scope("/webhooks")
+                            .service(handlers::webhooks::register_webhook)
+                            .service(handlers::webhooks::list_webhooks)
+                            .service(handlers::webhooks::delete_webhook)
+                    )
+                    .service(
+                        web::scope("/admin")
+                            .wrap(AdminAuth(admin_tokens.clone()))
+                            .service(handlers::admin::force_settlement_status)
+                            .service(handlers::admin::reconciliation_report)
+                            .service(handlers::admin::set_user_fee_policy)
+                            .service(handlers::admin::get_user_fee_policy)
+                            .service(handlers::admin::list_webhook_dead_letters)
+                            .service(handlers::admin::redeliver_webhook_dead_letter)
+                            .service(handlers::admin::list_creditor_emails)
                     )
                     .service(
                         web::scope("/leverage")
+                            .wrap(RateLimit(rate_limiter.clone()))
                             .service(handlers::leverage::calculate_leverage_score)
-                            .service(handlers::leverage::get_creditor_leverage)
+                            .service(handlers::leverage::batch_leverage_scores)
+                            .service(handlers::leverage::leverage_leaderboard)
+                            .service(handlers::leverage::get_leverage_history)
+                            .service(handlers::leverage::get_creditor_profile)
                     )
             )
     })
-    .bind(("0.0.0.0", port))?
-    .run()
-    .await?;
+    .disable_signals()
+    .shutdown_timeout(config.shutdown_timeout.as_secs())
+    .bind(("0.0.0.0", config.port))?
+    .run();
+    
+    // Stop the background workers as soon as the signal arrives, then let
+    // in-flight requests finish before draining what they started.
+    let server_handle = server.handle();
+    let signal_engine = engine.clone();
+    actix_web::rt::spawn(async move {
+        shutdown_signal().await;
+        info!("Shutdown signal received; no longer accepting requests");
+        signal_engine.begin_shutdown();
+        server_handle.stop(true).await;
+    });
+    
+    server.await?;
+    engine.drain().await;
     
     Ok(())
+}
+
+async fn shutdown_signal() {
+    let interrupt = tokio::signal::ctrl_c();
+    
+    #[cfg(unix)]
+    {
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler");
+        tokio::select! {
+            _ = interrupt => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    
+    #[cfg(not(unix))]
+    {
+        let _ = interrupt.await;
+    }
 }
\ No newline at end of file
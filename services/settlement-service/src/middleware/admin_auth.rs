@@ -0,0 +1,137 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::{Error, HttpMessage};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::handlers::ApiError;
+
+/// The support staff member a request was authenticated as, recorded as the
+/// actor of whatever they change. Available to handlers as `ReqData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminActor(pub String);
+
+/// Bearer tokens that grant admin access, each tied to the name it acts as.
+/// Only token digests are kept.
+#[derive(Clone, Default)]
+pub struct AdminTokens {
+    tokens: Arc<Vec<([u8; 32], String)>>,
+}
+
+impl AdminTokens {
+    /// Reads `ADMIN_API_TOKENS`, a comma-separated list of `name:token`
+    /// pairs. Without it every admin request is refused.
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("ADMIN_API_TOKENS").unwrap_or_default())
+    }
+    
+    fn parse(raw: &str) -> Self {
+        let tokens = raw
+            .split(',')
+            .filter_map(|entry| entry.trim().split_once(':'))
+            .filter(|(name, token)| !name.trim().is_empty() && !token.trim().is_empty())
+            .map(|(name, token)| (digest(token.trim()), name.trim().to_string()))
+            .collect();
+        Self { tokens: Arc::new(tokens) }
+    }
+    
+    /// Comparing digests keeps the comparison time independent of how much
+    /// of a guessed token is right.
+    pub fn authenticate(&self, token: &str) -> Option<AdminActor> {
+        let presented = digest(token);
+        self.tokens
+            .iter()
+            .find(|(known, _)| *known == presented)
+            .map(|(_, name)| AdminActor(format!("admin:{}", name)))
+    }
+}
+
+fn digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Middleware that admits only requests with `Authorization: Bearer <token>`
+/// for a configured admin token, answering 401 otherwise.
+pub struct AdminAuth(pub AdminTokens);
+
+impl<S, B> Transform<S, ServiceRequest> for AdminAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AdminAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminAuthMiddleware {
+            service: Rc::new(service),
+            tokens: self.0.clone(),
+        }))
+    }
+}
+
+pub struct AdminAuthMiddleware<S> {
+    service: Rc<S>,
+    tokens: AdminTokens,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let actor = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.tokens.authenticate(token.trim()));
+        
+        let Some(actor) = actor else {
+            warn!("Refused admin request to {}: missing or unknown token", req.path());
+            return Box::pin(async {
+                Err(ApiError::Unauthorized("a valid admin token is required".to_string()).into())
+            });
+        };
+        
+        req.extensions_mut().insert(actor);
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn tokens_authenticate_as_their_name() {
+        let tokens = AdminTokens::parse("alice:s3cret, bob:other ,broken, :nameless, empty:");
+        
+        assert_eq!(tokens.authenticate("s3cret"), Some(AdminActor("admin:alice".to_string())));
+        assert_eq!(tokens.authenticate("other"), Some(AdminActor("admin:bob".to_string())));
+        assert_eq!(tokens.tokens.len(), 2);
+        assert!(tokens.authenticate("s3cre").is_none());
+        assert!(tokens.authenticate("").is_none());
+    }
+    
+    #[test]
+    fn nothing_authenticates_without_configured_tokens() {
+        assert!(AdminTokens::parse("").authenticate("anything").is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+use actix_web::dev::Payload;
+use actix_web::http::header;
+use actix_web::{web, FromRequest, HttpRequest};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::handlers::ApiError;
+
+use super::admin_auth::AdminTokens;
+use super::creditor_auth::CreditorTokens;
+
+/// Who a request to the settlement routes was authenticated as. Taken by a
+/// handler as an argument, it refuses the request with 401 unless a valid
+/// bearer token came with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Caller {
+    /// A debtor, by the access token the user service issued them.
+    User(Uuid),
+    Creditor(Uuid),
+    /// Support staff, named as in [`AdminActor`](super::admin_auth::AdminActor).
+    Admin(String),
+}
+
+/// Verifies the HS256 access tokens the user service issues, whose
+/// `userId` claim names the user.
+#[derive(Clone, Default)]
+pub struct UserTokens {
+    secret: Option<Arc<[u8]>>,
+}
+
+#[derive(Deserialize)]
+struct TokenHeader {
+    alg: String,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    #[serde(rename = "userId")]
+    user_id: Uuid,
+    exp: i64,
+}
+
+impl UserTokens {
+    /// Reads `JWT_ACCESS_SECRET`, the secret the user service signs access
+    /// tokens with. Without it every user token is refused.
+    pub fn from_env() -> Self {
+        Self::new(&std::env::var("JWT_ACCESS_SECRET").unwrap_or_default())
+    }
+    
+    fn new(secret: &str) -> Self {
+        let secret = secret.trim();
+        Self {
+            secret: (!secret.is_empty()).then(|| Arc::from(secret.as_bytes())),
+        }
+    }
+    
+    /// The user `token` was issued to, if it is signed with our secret and
+    /// unexpired at `now` (seconds since the epoch).
+    fn authenticate(&self, token: &str, now: i64) -> Option<Uuid> {
+        let secret = self.secret.as_ref()?;
+        let (signed, signature) = token.rsplit_once('.')?;
+        let (header, claims) = signed.split_once('.')?;
+        
+        // Only HS256: a token must not get to pick `none` or another
+        // algorithm for itself.
+        let header: TokenHeader = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+        if header.alg != "HS256" {
+            return None;
+        }
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).ok()?;
+        mac.update(signed.as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(signature).ok()?).ok()?;
+        
+        let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()?;
+        (claims.exp > now).then_some(claims.user_id)
+    }
+}
+
+/// Every kind of token the settlement routes accept. Registered as app data
+/// so [`Caller`] can be extracted.
+#[derive(Clone)]
+pub struct CallerAuth {
+    users: UserTokens,
+    creditors: CreditorTokens,
+    admins: AdminTokens,
+}
+
+impl CallerAuth {
+    pub fn new(users: UserTokens, creditors: CreditorTokens, admins: AdminTokens) -> Self {
+        Self { users, creditors, admins }
+    }
+    
+    fn authenticate(&self, token: &str) -> Option<Caller> {
+        if let Some(admin) = self.admins.authenticate(token) {
+            return Some(Caller::Admin(admin.0));
+        }
+        if let Some(creditor) = self.creditors.authenticate(token) {
+            return Some(Caller::Creditor(creditor.0));
+        }
+        self.users
+            .authenticate(token, chrono::Utc::now().timestamp())
+            .map(Caller::User)
+    }
+}
+
+impl FromRequest for Caller {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let Some(auth) = req.app_data::<web::Data<CallerAuth>>() else {
+            return ready(Err(ApiError::Internal("caller authentication is not configured".to_string())));
+        };
+        let caller = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| auth.authenticate(token.trim()));
+        
+        ready(caller.ok_or_else(|| {
+            warn!("Refused request to {}: missing or unknown token", req.path());
+            ApiError::Unauthorized("a valid bearer token is required".to_string())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{App, HttpResponse};
+    
+    use super::*;
+    
+    const SECRET: &str = "access-secret";
+    const NOW: i64 = 1_700_000_000;
+    
+    fn sign(header: &str, claims: &str, secret: &str) -> String {
+        let signed = format!("{}.{}", URL_SAFE_NO_PAD.encode(header), URL_SAFE_NO_PAD.encode(claims));
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(signed.as_bytes());
+        format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+    
+    fn token(user_id: Uuid, exp: i64) -> String {
+        let claims = format!(r#"{{"userId":"{}","email":"user@example.com","exp":{}}}"#, user_id, exp);
+        sign(r#"{"alg":"HS256","typ":"JWT"}"#, &claims, SECRET)
+    }
+    
+    #[test]
+    fn access_tokens_authenticate_as_their_user() {
+        let tokens = UserTokens::new(SECRET);
+        let user_id = Uuid::new_v4();
+        
+        assert_eq!(tokens.authenticate(&token(user_id, NOW + 60), NOW), Some(user_id));
+        assert!(UserTokens::new("").authenticate(&token(user_id, NOW + 60), NOW).is_none());
+    }
+    
+    #[test]
+    fn expired_forged_and_unsigned_tokens_are_refused() {
+        let tokens = UserTokens::new(SECRET);
+        let user_id = Uuid::new_v4();
+        let claims = format!(r#"{{"userId":"{}","exp":{}}}"#, user_id, NOW + 60);
+        
+        assert!(tokens.authenticate(&token(user_id, NOW), NOW).is_none());
+        assert!(tokens.authenticate(&sign(r#"{"alg":"HS256"}"#, &claims, "guessed"), NOW).is_none());
+        assert!(tokens.authenticate(&sign(r#"{"alg":"HS512"}"#, &claims, SECRET), NOW).is_none());
+        let unsigned = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(&claims)
+        );
+        assert!(tokens.authenticate(&unsigned, NOW).is_none());
+        assert!(tokens.authenticate("not-a-token", NOW).is_none());
+    }
+    
+    #[actix_web::test]
+    async fn routes_taking_a_caller_refuse_requests_without_a_valid_token() {
+        let auth = CallerAuth::new(UserTokens::new(SECRET), CreditorTokens::default(), AdminTokens::default());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .route("/", web::get().to(|caller: Caller| async move { HttpResponse::Ok().json(format!("{:?}", caller)) })),
+        )
+        .await;
+        let status = |request: TestRequest| {
+            let app = &app;
+            async move { call_service(app, request.to_request()).await.status().as_u16() }
+        };
+        let valid = token(Uuid::new_v4(), chrono::Utc::now().timestamp() + 60);
+        
+        assert_eq!(status(TestRequest::get().uri("/")).await, 401);
+        assert_eq!(
+            status(TestRequest::get().uri("/").insert_header((header::AUTHORIZATION, "Bearer forged"))).await,
+            401
+        );
+        assert_eq!(
+            status(TestRequest::get().uri("/").insert_header((header::AUTHORIZATION, format!("Bearer {}", valid)))).await,
+            200
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::{Error, HttpMessage};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::handlers::ApiError;
+
+/// The creditor a request was authenticated as. Available to handlers as
+/// `ReqData`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditorActor(pub Uuid);
+
+/// Bearer tokens that let a creditor act on its own debts, each tied to the
+/// creditor it acts as. Only token digests are kept.
+#[derive(Clone, Default)]
+pub struct CreditorTokens {
+    tokens: Arc<Vec<([u8; 32], Uuid)>>,
+}
+
+impl CreditorTokens {
+    /// Reads `CREDITOR_API_TOKENS`, a comma-separated list of
+    /// `creditor_id:token` pairs. Without it every creditor request is
+    /// refused.
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("CREDITOR_API_TOKENS").unwrap_or_default())
+    }
+    
+    fn parse(raw: &str) -> Self {
+        let tokens = raw
+            .split(',')
+            .filter_map(|entry| entry.trim().split_once(':'))
+            .filter(|(_, token)| !token.trim().is_empty())
+            .filter_map(|(creditor_id, token)| {
+                let creditor_id = creditor_id.trim().parse::<Uuid>().ok()?;
+                Some((digest(token.trim()), creditor_id))
+            })
+            .collect();
+        Self { tokens: Arc::new(tokens) }
+    }
+    
+    pub fn authenticate(&self, token: &str) -> Option<CreditorActor> {
+        let presented = digest(token);
+        self.tokens
+            .iter()
+            .find(|(known, _)| *known == presented)
+            .map(|(_, creditor_id)| CreditorActor(*creditor_id))
+    }
+}
+
+fn digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Middleware that admits only requests with `Authorization: Bearer <token>`
+/// for a configured creditor token, answering 401 otherwise.
+pub struct CreditorAuth(pub CreditorTokens);
+
+impl<S, B> Transform<S, ServiceRequest> for CreditorAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = CreditorAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CreditorAuthMiddleware {
+            service: Rc::new(service),
+            tokens: self.0.clone(),
+        }))
+    }
+}
+
+pub struct CreditorAuthMiddleware<S> {
+    service: Rc<S>,
+    tokens: CreditorTokens,
+}
+
+impl<S, B> Service<ServiceRequest> for CreditorAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let actor = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.tokens.authenticate(token.trim()));
+        
+        let Some(actor) = actor else {
+            warn!("Refused creditor request to {}: missing or unknown token", req.path());
+            return Box::pin(async {
+                Err(ApiError::Unauthorized("a valid creditor token is required".to_string()).into())
+            });
+        };
+        
+        req.extensions_mut().insert(actor);
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn tokens_authenticate_as_their_creditor() {
+        let creditor_id = Uuid::new_v4();
+        let tokens = CreditorTokens::parse(&format!("{}:s3cret, not-a-uuid:other, {}:", creditor_id, Uuid::new_v4()));
+        
+        assert_eq!(tokens.authenticate("s3cret"), Some(CreditorActor(creditor_id)));
+        assert_eq!(tokens.tokens.len(), 1);
+        assert!(tokens.authenticate("other").is_none());
+        assert!(tokens.authenticate("").is_none());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod request_id;
+pub mod rate_limit;
+pub mod admin_auth;
+pub mod creditor_auth;
+pub mod caller_auth;
\ No newline at end of file
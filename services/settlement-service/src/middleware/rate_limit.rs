@@ -0,0 +1,297 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::error::PayloadError;
+use actix_web::Error;
+use dashmap::DashMap;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::handlers::ApiError;
+use crate::services::env_or;
+
+const DEFAULT_USER_BURST: f64 = 30.0;
+const DEFAULT_USER_PER_SEC: f64 = 1.0;
+const DEFAULT_CREDITOR_BURST: f64 = 120.0;
+const DEFAULT_CREDITOR_PER_SEC: f64 = 5.0;
+
+/// The longest `Retry-After` given; a bucket refilling slower than this is
+/// asked about again.
+const MAX_WAIT_SECS: f64 = 60.0;
+
+/// Once the map holds this many buckets, refilled ones are dropped; a full
+/// bucket is indistinguishable from a fresh one.
+const PRUNE_ABOVE_BUCKETS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    User(Uuid),
+    Creditor(Uuid),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    burst: f64,
+    per_sec: f64,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token buckets per user and per creditor, shared by every worker.
+#[derive(Clone)]
+pub struct RateLimiter {
+    buckets: Arc<DashMap<Key, Bucket>>,
+    user: Limit,
+    creditor: Limit,
+}
+
+impl RateLimiter {
+    /// Limits are read from `RATE_LIMIT_USER_BURST`, `RATE_LIMIT_USER_PER_SEC`,
+    /// `RATE_LIMIT_CREDITOR_BURST` and `RATE_LIMIT_CREDITOR_PER_SEC`.
+    pub fn from_env() -> Self {
+        Self {
+            buckets: Arc::new(DashMap::new()),
+            user: Limit {
+                burst: env_or("RATE_LIMIT_USER_BURST", DEFAULT_USER_BURST),
+                per_sec: env_or("RATE_LIMIT_USER_PER_SEC", DEFAULT_USER_PER_SEC),
+            },
+            creditor: Limit {
+                burst: env_or("RATE_LIMIT_CREDITOR_BURST", DEFAULT_CREDITOR_BURST),
+                per_sec: env_or("RATE_LIMIT_CREDITOR_PER_SEC", DEFAULT_CREDITOR_PER_SEC),
+            },
+        }
+    }
+    
+    fn limit(&self, key: Key) -> Limit {
+        match key {
+            Key::User(_) => self.user,
+            Key::Creditor(_) => self.creditor,
+        }
+    }
+    
+    /// Takes a token from every bucket in `keys`, or none of them. On refusal
+    /// returns how long until the emptiest bucket has a token again.
+    fn acquire(&self, keys: &[Key], now: Instant) -> Result<(), Duration> {
+        let mut wait = Duration::ZERO;
+        for key in keys {
+            let limit = self.limit(*key);
+            let mut bucket = self.buckets.entry(*key).or_insert(Bucket {
+                tokens: limit.burst,
+                refilled_at: now,
+            });
+            let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * limit.per_sec).min(limit.burst);
+            bucket.refilled_at = now;
+            
+            if bucket.tokens < 1.0 {
+                let secs = if limit.per_sec > 0.0 { (1.0 - bucket.tokens) / limit.per_sec } else { MAX_WAIT_SECS };
+                wait = wait.max(Duration::from_secs_f64(secs.min(MAX_WAIT_SECS)));
+            }
+        }
+        if wait > Duration::ZERO {
+            return Err(wait);
+        }
+        
+        for key in keys {
+            if let Some(mut bucket) = self.buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        self.prune(now);
+        Ok(())
+    }
+    
+    fn prune(&self, now: Instant) {
+        if self.buckets.len() <= PRUNE_ABOVE_BUCKETS {
+            return;
+        }
+        self.buckets.retain(|key, bucket| {
+            let limit = self.limit(*key);
+            let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens + elapsed * limit.per_sec < limit.burst
+        });
+    }
+}
+
+/// Middleware that charges each request to the `user_id` and `creditor_id`
+/// found in its query string or JSON body, answering 429 with `Retry-After`
+/// when either bucket is empty. Requests naming neither pass through.
+pub struct RateLimit(pub RateLimiter);
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            limiter: self.0.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    limiter: RateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limiter = self.limiter.clone();
+        
+        Box::pin(async move {
+            let mut keys = keys_from_query(req.query_string());
+            
+            if is_json(&req) {
+                let body = req.extract::<Bytes>().await?;
+                for key in keys_from_body(&body) {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+                req.set_payload(replay(body));
+            }
+            
+            if let Err(wait) = limiter.acquire(&keys, Instant::now()) {
+                return Err(ApiError::RateLimited {
+                    retry_after_secs: wait.as_secs_f64().ceil().max(1.0) as u64,
+                }
+                .into());
+            }
+            
+            service.call(req).await
+        })
+    }
+}
+
+fn is_json(req: &ServiceRequest) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+fn keys_from_query(query: &str) -> Vec<Key> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter_map(|(name, value)| key(name, value))
+        .collect()
+}
+
+/// Only top-level fields count; nested ids (e.g. batch items) are charged to
+/// the caller named at the top, if any.
+fn keys_from_body(body: &[u8]) -> Vec<Key> {
+    let Ok(Value::Object(fields)) = serde_json::from_slice::<Value>(body) else {
+        return Vec::new();
+    };
+    
+    fields
+        .iter()
+        .filter_map(|(name, value)| key(name, value.as_str()?))
+        .collect()
+}
+
+fn key(name: &str, value: &str) -> Option<Key> {
+    let id = Uuid::parse_str(value).ok()?;
+    match name {
+        "user_id" => Some(Key::User(id)),
+        "creditor_id" => Some(Key::Creditor(id)),
+        _ => None,
+    }
+}
+
+/// Hands a body we already read back to the handler's extractors.
+fn replay(body: Bytes) -> Payload {
+    let stream = futures::stream::once(async move { Ok::<_, PayloadError>(body) });
+    Payload::Stream { payload: Box::pin(stream) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn limiter(burst: f64, per_sec: f64) -> RateLimiter {
+        RateLimiter {
+            buckets: Arc::new(DashMap::new()),
+            user: Limit { burst, per_sec },
+            creditor: Limit { burst, per_sec },
+        }
+    }
+    
+    #[test]
+    fn burst_is_allowed_then_refused_until_refill() {
+        let limiter = limiter(2.0, 1.0);
+        let user = [Key::User(Uuid::new_v4())];
+        let now = Instant::now();
+        
+        assert!(limiter.acquire(&user, now).is_ok());
+        assert!(limiter.acquire(&user, now).is_ok());
+        let wait = limiter.acquire(&user, now).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+        
+        assert!(limiter.acquire(&user, now + Duration::from_secs(1)).is_ok());
+    }
+    
+    #[test]
+    fn refusal_by_one_bucket_charges_neither() {
+        let limiter = limiter(1.0, 1.0);
+        let user = Key::User(Uuid::new_v4());
+        let creditor = Key::Creditor(Uuid::new_v4());
+        let now = Instant::now();
+        
+        assert!(limiter.acquire(&[creditor], now).is_ok());
+        assert!(limiter.acquire(&[user, creditor], now).is_err());
+        assert!(limiter.acquire(&[user], now).is_ok());
+    }
+    
+    #[test]
+    fn slow_refills_wait_at_most_a_minute() {
+        let limiter = limiter(1.0, 1e-300);
+        let user = [Key::User(Uuid::new_v4())];
+        let now = Instant::now();
+        
+        assert!(limiter.acquire(&user, now).is_ok());
+        assert_eq!(limiter.acquire(&user, now).unwrap_err(), Duration::from_secs(60));
+    }
+    
+    #[test]
+    fn ids_come_from_query_and_top_level_body_fields() {
+        let user = Uuid::new_v4();
+        let creditor = Uuid::new_v4();
+        
+        assert_eq!(keys_from_query(&format!("user_id={}&limit=10", user)), vec![Key::User(user)]);
+        assert_eq!(
+            keys_from_body(format!(r#"{{"creditor_id":"{}","items":[{{"user_id":"{}"}}]}}"#, creditor, user).as_bytes()),
+            vec![Key::Creditor(creditor)]
+        );
+        assert!(keys_from_body(b"not json").is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use std::future::Future;
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{Error, HttpMessage};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: Uuid;
+}
+
+/// The id of the request being served on this task, if any.
+pub fn current() -> Option<Uuid> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+/// `wrap_fn` middleware: tags each request with an id (reusing a valid incoming
+/// `X-Request-Id`), exposes it to handlers, error responses and log lines, and
+/// echoes it back in the response headers.
+pub fn request_id<S, B>(
+    req: ServiceRequest,
+    srv: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .unwrap_or_else(Uuid::new_v4);
+    
+    req.extensions_mut().insert(id);
+    // Everything the request does, spawned work included, runs inside this
+    // span, so every log line for it carries the id.
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.path()
+    );
+    let response = REQUEST_ID.scope(id, srv.call(req).instrument(span));
+    
+    async move {
+        let mut response = response.await?;
+        response.headers_mut().insert(
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            HeaderValue::from_str(&id.to_string()).expect("a UUID is a valid header value"),
+        );
+        Ok(response)
+    }
+}
@@ -0,0 +1,64 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Settlement;
+
+/// One debtor's acceptance of a settlement, with the terms they accepted.
+#[derive(Debug, Clone, FromRow)]
+pub struct SettlementAcceptance {
+    pub settlement_id: Uuid,
+    pub debtor_id: Uuid,
+    pub settled_amount: BigDecimal,
+    pub platform_fee: BigDecimal,
+    pub clauses_hash: Option<String>,
+    pub signed: bool,
+    pub envelope_id: Option<String>,
+    pub accepted_at: DateTime<Utc>,
+}
+
+impl SettlementAcceptance {
+    /// Whether this is an acceptance of `terms` with the clauses hashed as
+    /// `clauses_hash`.
+    pub fn covers(&self, terms: &Settlement, clauses_hash: Option<&str>) -> bool {
+        self.settled_amount == terms.settled_amount
+            && self.platform_fee == terms.platform_fee
+            && self.clauses_hash.as_deref() == clauses_hash
+    }
+}
+
+/// Where one debtor stands on a settlement's terms.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DebtorAcceptance {
+    pub debtor_id: Uuid,
+    /// When they accepted the terms as they stand; none if they haven't, or
+    /// accepted terms that have changed since.
+    pub accepted_at: Option<DateTime<Utc>>,
+    /// Whether they signed, by key or envelope.
+    pub signed: bool,
+}
+
+/// Who has and hasn't accepted a settlement.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementAcceptances {
+    pub settlement_id: Uuid,
+    /// Every debtor of the debt, the settlement's user first.
+    pub debtors: Vec<DebtorAcceptance>,
+    /// Whether they all have; a settlement of a joint debt is neither
+    /// accepted nor paid until then.
+    pub complete: bool,
+}
+
+impl SettlementAcceptances {
+    /// The debtors yet to accept.
+    pub fn pending(&self) -> Vec<Uuid> {
+        self.debtors
+            .iter()
+            .filter(|debtor| debtor.accepted_at.is_none())
+            .map(|debtor| debtor.debtor_id)
+            .collect()
+    }
+}
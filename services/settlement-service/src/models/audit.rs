@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "settlement_event_type", rename_all = "snake_case")]
+pub enum AuditEventType {
+    Created,
+    Negotiated,
+    Accepted,
+    Rejected,
+    Rescinded,
+    /// Transaction submitted to Cardano; metadata carries the tx hash and
+    /// contract address.
+    Submitted,
+    Completed,
+    Failed,
+    /// A failed settlement was resubmitted or found confirmed after all.
+    Retried,
+    /// Nobody acted on the proposal before `expires_at`.
+    Expired,
+    /// Hidden from the user's view; the settlement itself is kept.
+    Deleted,
+    /// Terms sent to the signature provider; metadata carries the envelope id.
+    SignatureRequested,
+    /// The user completed the provider's signing envelope.
+    Signed,
+    /// An installment was paid; metadata carries the fee accrued so far.
+    InstallmentPaid,
+    /// Support forced the status outside the lifecycle; metadata carries the
+    /// reason and both statuses.
+    StatusForced,
+    /// Reconciliation found the chain contradicts the recorded status;
+    /// metadata is a `ReviewFlag`.
+    FlaggedForReview,
+    /// Execution was put off because the Cardano node's circuit breaker was
+    /// open; the settlement is submitted once it closes.
+    ExecutionQueued,
+    /// The proposal was recomputed with the current violations; metadata
+    /// carries its terms before and after.
+    Recomputed,
+    /// The proposal's platform fee was waived or reduced; metadata carries
+    /// the policy and the standard fee it was reduced from.
+    FeePolicyApplied,
+    /// A negotiation came within a reminder threshold of expiring and both
+    /// parties were reminded; metadata carries the threshold and deadline.
+    DeadlineReminder,
+    /// A creditor counter within the user's `auto_accept_below` was accepted
+    /// on their behalf; metadata carries the round, amount and threshold.
+    AutoAccepted,
+    /// A submission interrupted before its transaction was recorded was
+    /// resolved at startup; metadata carries the intent and the transaction
+    /// the node took under it, or none.
+    SubmissionRecovered,
+    /// An escrow settlement's payment confirmed in the script address;
+    /// metadata carries the lock tx and when a refund becomes possible.
+    Escrowed,
+    /// The creditor released the escrowed payment to themselves; metadata
+    /// carries the release tx.
+    EscrowReleased,
+    /// The escrowed payment went back to the debtor after the refund
+    /// deadline; metadata carries the refund tx.
+    EscrowRefunded,
+    /// One debtor of a joint debt accepted; metadata carries who, and the
+    /// debtors still to accept before the settlement is accepted.
+    DebtorAccepted,
+}
+
+/// One row of a settlement's compliance trail. `actor` is `user:<id>`,
+/// `creditor`, `debtor` or `system`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub settlement_id: Uuid,
+    pub event_type: AuditEventType,
+    pub actor: String,
+    #[schema(value_type = Object)]
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
\ No newline at end of file
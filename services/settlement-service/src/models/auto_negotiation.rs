@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use super::{AutoNegotiateRequest, FieldError, NegotiationStrategy};
+
+/// Trigger recorded on jobs enqueued by SWORD protocol events.
+pub const SWORD_TRIGGER: &str = "sword_protocol";
+
+/// A SWORD protocol event: documented violations against a creditor that
+/// warrant negotiating the user's debt with them.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SwordEvent {
+    /// Unique per event; a redelivered event carries the same id.
+    pub event_id: String,
+    pub user_id: Uuid,
+    pub creditor_id: Uuid,
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub strategy: NegotiationStrategy,
+}
+
+impl SwordEvent {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.event_id.trim().is_empty() {
+            errors.push(FieldError::new("event_id", "must not be empty"));
+        }
+        if self.user_id.is_nil() {
+            errors.push(FieldError::new("user_id", "must not be the nil UUID"));
+        }
+        if self.creditor_id.is_nil() {
+            errors.push(FieldError::new("creditor_id", "must not be the nil UUID"));
+        }
+        if self.violations.is_empty() {
+            errors.push(FieldError::new("violations", "must not be empty"));
+        }
+        if self.jurisdiction.trim().is_empty() {
+            errors.push(FieldError::new("jurisdiction", "must not be empty"));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "auto_negotiation_status", rename_all = "snake_case")]
+pub enum AutoNegotiationStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One queued auto-negotiation and, once a worker has run it, its outcome.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AutoNegotiationJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub creditor_id: Uuid,
+    pub trigger: String,
+    pub event_id: String,
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    pub strategy: NegotiationStrategy,
+    pub status: AutoNegotiationStatus,
+    pub attempts: i32,
+    /// The proposal the job made, once it succeeded.
+    pub settlement_id: Option<Uuid>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl AutoNegotiationJob {
+    pub fn for_sword_event(event: &SwordEvent, now: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: event.user_id,
+            creditor_id: event.creditor_id,
+            trigger: SWORD_TRIGGER.to_string(),
+            event_id: event.event_id.trim().to_string(),
+            violations: event.violations.clone(),
+            jurisdiction: event.jurisdiction.clone(),
+            strategy: event.strategy,
+            status: AutoNegotiationStatus::Pending,
+            attempts: 0,
+            settlement_id: None,
+            last_error: None,
+            created_at: now,
+            started_at: None,
+            finished_at: None,
+        }
+    }
+    
+    pub fn request(&self) -> AutoNegotiateRequest {
+        AutoNegotiateRequest {
+            user_id: self.user_id,
+            creditor_id: self.creditor_id,
+            trigger: self.trigger.clone(),
+            violations: self.violations.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            strategy: self.strategy,
+            locale: Default::default(),
+            dry_run: false,
+            auto_accept_below: None,
+            ai_mode: None,
+        }
+    }
+}
+
+/// Response to a trigger: the job it enqueued, or the one an earlier delivery
+/// of the same event already did.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EnqueuedJob {
+    #[serde(flatten)]
+    pub job: AutoNegotiationJob,
+    /// False when the event had been received before.
+    pub created: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn event() -> SwordEvent {
+        SwordEvent {
+            event_id: "sword-evt-1".to_string(),
+            user_id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            violations: vec![Uuid::new_v4()],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+        }
+    }
+    
+    #[test]
+    fn events_need_an_id_ids_and_violations() {
+        assert_eq!(event().validate(), Ok(()));
+        
+        let event = SwordEvent {
+            event_id: " ".to_string(),
+            creditor_id: Uuid::nil(),
+            violations: vec![],
+            ..event()
+        };
+        let fields: Vec<_> = event.validate().unwrap_err().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["event_id", "creditor_id", "violations"]);
+    }
+}
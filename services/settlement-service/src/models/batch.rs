@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{FieldError, Settlement};
+
+/// Most settlements one batch payment may cover.
+pub const MAX_BATCH_SETTLEMENTS: usize = 20;
+
+/// Accepted settlements, all owed to the same creditor, to pay together.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExecuteBatchRequest {
+    pub settlement_ids: Vec<Uuid>,
+}
+
+impl ExecuteBatchRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.settlement_ids.len() < 2 {
+            errors.push(FieldError::new("settlement_ids", "must name at least two settlements"));
+        }
+        if self.settlement_ids.len() > MAX_BATCH_SETTLEMENTS {
+            errors.push(FieldError::new(
+                "settlement_ids",
+                format!("must name at most {} settlements", MAX_BATCH_SETTLEMENTS),
+            ));
+        }
+        if let Some(index) = self.settlement_ids.iter().position(Uuid::is_nil) {
+            errors.push(FieldError::new(
+                "settlement_ids",
+                format!("entry {} must not be the nil UUID", index),
+            ));
+        }
+        if let Some(index) =
+            (1..self.settlement_ids.len()).find(|&i| self.settlement_ids[..i].contains(&self.settlement_ids[i]))
+        {
+            errors.push(FieldError::new(
+                "settlement_ids",
+                format!("entry {} repeats an earlier settlement", index),
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A batch paid in one Cardano transaction. Either every settlement named
+/// went out in it or none did: a batch with a settlement that is not
+/// `Accepted`, was already submitted, or is owed to another creditor is
+/// refused before anything is submitted, and one the node refuses or cannot
+/// be reached for leaves every settlement as it was. Nothing is queued while
+/// the node is unavailable. Once submitted, each settlement is watched and
+/// completes on its own, so a settlement with installments still unpaid stays
+/// `Accepted` while the rest complete.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchExecution {
+    pub transaction_hash: String,
+    pub creditor_id: Uuid,
+    /// The settlements in the order they were named, each now recording the
+    /// transaction.
+    pub settlements: Vec<Settlement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn batches_need_two_distinct_settlements() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(ExecuteBatchRequest { settlement_ids: vec![a, b] }.validate(), Ok(()));
+        
+        for settlement_ids in [vec![a], vec![a, b, a], vec![a, Uuid::nil()], vec![Uuid::new_v4(); 21]] {
+            let fields: Vec<_> = ExecuteBatchRequest { settlement_ids }
+                .validate()
+                .unwrap_err()
+                .into_iter()
+                .map(|e| e.field)
+                .collect();
+            assert!(fields.iter().all(|&field| field == "settlement_ids"), "{:?}", fields);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use bigdecimal::BigDecimal;
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+use crate::money::Currency;
+use super::{AiMode, CreateSettlementRequest, FieldError, NegotiationStrategy};
+
+/// Most debts one bundled settlement may cover.
+pub const MAX_BUNDLED_DEBTS: usize = 20;
+
+/// One settlement covering several of the user's debts with the same creditor.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BundleSettlementRequest {
+    pub user_id: Uuid,
+    pub creditor_id: Uuid,
+    pub debt_ids: Vec<Uuid>,
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub strategy: NegotiationStrategy,
+    /// Language the reasoning and recommended action are written in;
+    /// `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+    /// See [`CreateSettlementRequest::ai_mode`].
+    #[serde(default)]
+    pub ai_mode: Option<AiMode>,
+}
+
+impl BundleSettlementRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = match self.settlement_request().validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().filter(|e| e.field != "debt_id").collect(),
+        };
+        
+        if self.debt_ids.len() < 2 {
+            errors.push(FieldError::new("debt_ids", "must name at least two debts"));
+        }
+        if self.debt_ids.len() > MAX_BUNDLED_DEBTS {
+            errors.push(FieldError::new(
+                "debt_ids",
+                format!("must name at most {} debts", MAX_BUNDLED_DEBTS),
+            ));
+        }
+        if let Some(index) = self.debt_ids.iter().position(Uuid::is_nil) {
+            errors.push(FieldError::new(
+                "debt_ids",
+                format!("entry {} must not be the nil UUID", index),
+            ));
+        }
+        if let Some(index) = (1..self.debt_ids.len()).find(|&i| self.debt_ids[..i].contains(&self.debt_ids[i])) {
+            errors.push(FieldError::new(
+                "debt_ids",
+                format!("entry {} repeats an earlier debt", index),
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// The proposal request for the bundle as a whole, filed under its first debt.
+    pub fn settlement_request(&self) -> CreateSettlementRequest {
+        CreateSettlementRequest {
+            user_id: self.user_id,
+            creditor_id: self.creditor_id,
+            debt_id: self.debt_ids.first().copied(),
+            violations: self.violations.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            strategy: self.strategy,
+            locale: self.locale.clone(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: self.ai_mode,
+        }
+    }
+}
+
+/// A component debt of a bundled settlement as stored.
+#[derive(Debug, Clone, FromRow)]
+pub struct SettlementDebt {
+    pub debt_id: Uuid,
+    /// The debt's balance when the bundle was proposed.
+    pub original_amount: BigDecimal,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BundledDebt {
+    pub debt_id: Uuid,
+    #[schema(value_type = String)]
+    pub original_amount: BigDecimal,
+    /// This debt's share of the settled amount, in proportion to its balance.
+    #[schema(value_type = String)]
+    pub settled_amount: BigDecimal,
+}
+
+/// The debts a settlement pays off and how its settled amount divides among
+/// them. A settlement that is not a bundle has exactly one.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettlementDebts {
+    pub settlement_id: Uuid,
+    pub currency: Currency,
+    pub debts: Vec<BundledDebt>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn request(debt_ids: Vec<Uuid>) -> BundleSettlementRequest {
+        BundleSettlementRequest {
+            user_id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            debt_ids,
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+            locale: Default::default(),
+            ai_mode: None,
+        }
+    }
+    
+    #[test]
+    fn bundles_need_two_distinct_debts() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(request(vec![a, b]).validate(), Ok(()));
+        
+        for debt_ids in [vec![a], vec![a, b, a], vec![a, Uuid::nil()]] {
+            let fields: Vec<_> = request(debt_ids).validate().unwrap_err().into_iter().map(|e| e.field).collect();
+            assert_eq!(fields, ["debt_ids"]);
+        }
+    }
+}
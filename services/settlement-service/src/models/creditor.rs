@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use super::{ExecutionMode, FieldError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "contact_channel", rename_all = "snake_case")]
+pub enum ContactChannel {
+    Email,
+    Mail,
+    /// The creditor's registered webhooks.
+    Webhook,
+}
+
+/// Where proposals and status changes for a creditor are delivered.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CreditorContact {
+    pub creditor_id: Uuid,
+    pub email: Option<String>,
+    pub mailing_address: Option<String>,
+    pub preferred_channel: ContactChannel,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreditorContactRequest {
+    pub email: Option<String>,
+    pub mailing_address: Option<String>,
+    pub preferred_channel: ContactChannel,
+}
+
+impl CreditorContactRequest {
+    /// The preferred channel must have an address to deliver to; webhooks
+    /// are registered separately.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        match self.email.as_deref() {
+            Some(email) if !is_valid_email(email.trim()) => {
+                errors.push(FieldError::new("email", "must be a valid email address"));
+            }
+            None if self.preferred_channel == ContactChannel::Email => {
+                errors.push(FieldError::new("email", "is required when the preferred channel is email"));
+            }
+            _ => {}
+        }
+        match self.mailing_address.as_deref() {
+            Some(address) if address.trim().is_empty() => {
+                errors.push(FieldError::new("mailing_address", "must not be empty"));
+            }
+            None if self.preferred_channel == ContactChannel::Mail => {
+                errors.push(FieldError::new("mailing_address", "is required when the preferred channel is mail"));
+            }
+            _ => {}
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    pub fn contact(&self, creditor_id: Uuid, now: DateTime<Utc>) -> CreditorContact {
+        CreditorContact {
+            creditor_id,
+            email: self.email.as_deref().map(|email| email.trim().to_string()),
+            mailing_address: self.mailing_address.as_deref().map(|address| address.trim().to_string()),
+            preferred_channel: self.preferred_channel,
+            updated_at: now,
+        }
+    }
+}
+
+/// The least a creditor will settle for, as a percentage of the balance owed.
+/// Proposals never go below it; `null` removes it.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SettlementFloor {
+    pub settlement_floor_percentage: Option<f64>,
+}
+
+impl SettlementFloor {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        match self.settlement_floor_percentage {
+            Some(percentage) if !(percentage > 0.0 && percentage <= 100.0) => Err(vec![FieldError::new(
+                "settlement_floor_percentage",
+                "must be greater than 0 and at most 100",
+            )]),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How the creditor's settlements are paid from now on; see
+/// [`ExecutionMode`]. Settlements already proposed keep the mode they were
+/// proposed with.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreditorExecutionMode {
+    pub execution_mode: ExecutionMode,
+}
+
+/// Most clauses a creditor's terms may hold.
+pub const MAX_CREDITOR_CLAUSES: usize = 30;
+
+/// Longest clause key; keys name clauses in agreements and audit events.
+pub const MAX_CLAUSE_KEY_LENGTH: usize = 64;
+
+/// One boilerplate clause of a creditor's agreements. `body` is a template
+/// filled in with the same values as the agreement around it, such as
+/// `{{ creditor_id }}` or `{{ settled_amount }}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AgreementClause {
+    #[sqlx(rename = "clause_key")]
+    pub key: String,
+    /// Starts at 1 and goes up whenever the title or body is reworded.
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    /// A settlement is not executed until its debtor has agreed to some
+    /// version of every required clause.
+    pub required: bool,
+}
+
+/// The clauses every settlement agreement with a creditor carries, in the
+/// order they appear.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreditorTerms {
+    pub creditor_id: Uuid,
+    pub clauses: Vec<AgreementClause>,
+}
+
+/// SHA-256 over the key, version and wording of each clause in order, or
+/// none without clauses. Recorded on acceptance, it brings the clauses into
+/// the terms that are signed and hashed on-chain.
+pub fn clauses_hash(clauses: &[AgreementClause]) -> Option<String> {
+    if clauses.is_empty() {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    for clause in clauses {
+        // Length-prefixed, so no clause's text can pass for another's.
+        for field in [clause.key.as_str(), &clause.version.to_string(), &clause.title, &clause.body] {
+            hasher.update(format!("{}:{}|", field.len(), field));
+        }
+    }
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// A clause as the creditor words it; the version is assigned on save.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ClauseTemplate {
+    /// Lowercase letters, digits and `-`, e.g. `release-of-claims`.
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub required: bool,
+}
+
+/// Replaces a creditor's clauses. Clauses left out are retired; one whose
+/// title or body changed gets a new version, while reordering or changing
+/// `required` keeps the version.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreditorTermsRequest {
+    pub clauses: Vec<ClauseTemplate>,
+}
+
+impl CreditorTermsRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.clauses.len() > MAX_CREDITOR_CLAUSES {
+            errors.push(FieldError::new(
+                "clauses",
+                format!("must hold at most {} clauses", MAX_CREDITOR_CLAUSES),
+            ));
+        }
+        let templates = minijinja::Environment::new();
+        for (index, clause) in self.clauses.iter().enumerate() {
+            if !is_valid_clause_key(&clause.key) {
+                errors.push(FieldError::new(
+                    "clauses",
+                    format!(
+                        "entry {} key must be 1-{} lowercase letters, digits or '-'",
+                        index, MAX_CLAUSE_KEY_LENGTH
+                    ),
+                ));
+            } else if self.clauses[..index].iter().any(|earlier| earlier.key == clause.key) {
+                errors.push(FieldError::new("clauses", format!("entry {} repeats key {}", index, clause.key)));
+            }
+            if clause.title.trim().is_empty() {
+                errors.push(FieldError::new("clauses", format!("entry {} title must not be empty", index)));
+            }
+            if clause.body.trim().is_empty() {
+                errors.push(FieldError::new("clauses", format!("entry {} body must not be empty", index)));
+            } else if let Err(e) = templates.template_from_str(&clause.body) {
+                errors.push(FieldError::new(
+                    "clauses",
+                    format!("entry {} body is not a valid template: {}", index, e),
+                ));
+            }
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn is_valid_clause_key(key: &str) -> bool {
+    (1..=MAX_CLAUSE_KEY_LENGTH).contains(&key.len())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// A deliberately loose check: one `@`, something before it and a dotted
+/// domain after it, no whitespace. Deliverability is the mail server's call.
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn request(email: Option<&str>, preferred_channel: ContactChannel) -> CreditorContactRequest {
+        CreditorContactRequest {
+            email: email.map(str::to_string),
+            mailing_address: None,
+            preferred_channel,
+        }
+    }
+    
+    #[test]
+    fn emails_need_a_local_part_and_a_dotted_domain() {
+        for email in ["disputes@creditor.com", "a.b+settlements@mail.creditor.co.uk"] {
+            assert!(is_valid_email(email), "{}", email);
+        }
+        for email in [
+            "",
+            "creditor.com",
+            "@creditor.com",
+            "disputes@",
+            "disputes@creditor",
+            "a b@creditor.com",
+            "a@b@creditor.com",
+            "a@creditor..com",
+        ] {
+            assert!(!is_valid_email(email), "{}", email);
+        }
+    }
+    
+    #[test]
+    fn the_preferred_channel_needs_an_address() {
+        assert_eq!(request(Some("disputes@creditor.com"), ContactChannel::Email).validate(), Ok(()));
+        assert_eq!(request(None, ContactChannel::Webhook).validate(), Ok(()));
+        
+        let fields: Vec<_> = request(None, ContactChannel::Email)
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["email"]);
+        let fields: Vec<_> = request(Some("not-an-email"), ContactChannel::Mail)
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["email", "mailing_address"]);
+    }
+    
+    #[test]
+    fn clauses_need_distinct_keys_and_template_bodies() {
+        let clause = |key: &str, body: &str| ClauseTemplate {
+            key: key.to_string(),
+            title: "Release of Claims".to_string(),
+            body: body.to_string(),
+            required: true,
+        };
+        let valid = CreditorTermsRequest {
+            clauses: vec![
+                clause("release-of-claims", "The Creditor releases debt {{ debt_id }}."),
+                clause("confidentiality", "The parties keep these terms confidential."),
+            ],
+        };
+        assert_eq!(valid.validate(), Ok(()));
+        
+        let messages: Vec<_> = CreditorTermsRequest {
+            clauses: vec![
+                clause("release-of-claims", "Releases {{ debt_id"),
+                clause("release-of-claims", "Again."),
+                clause("Release Of Claims", " "),
+            ],
+        }
+        .validate()
+        .unwrap_err()
+        .into_iter()
+        .map(|e| e.message)
+        .collect();
+        assert_eq!(messages.len(), 4, "{:?}", messages);
+        assert!(messages[0].starts_with("entry 0 body is not a valid template"), "{:?}", messages);
+        assert_eq!(messages[1], "entry 1 repeats key release-of-claims");
+        assert!(messages[2].starts_with("entry 2 key must be"), "{:?}", messages);
+        assert_eq!(messages[3], "entry 2 body must not be empty");
+    }
+    
+    #[test]
+    fn the_clauses_hash_changes_with_any_wording_or_version() {
+        let clause = AgreementClause {
+            key: "confidentiality".to_string(),
+            version: 1,
+            title: "Confidentiality".to_string(),
+            body: "The parties keep these terms confidential.".to_string(),
+            required: false,
+        };
+        let hash = |clause: AgreementClause| clauses_hash(&[clause]).unwrap();
+        
+        assert_eq!(clauses_hash(&[]), None);
+        assert_eq!(hash(clause.clone()), hash(AgreementClause { required: true, ..clause.clone() }));
+        assert_ne!(hash(clause.clone()), hash(AgreementClause { version: 2, ..clause.clone() }));
+        assert_ne!(hash(clause.clone()), hash(AgreementClause { body: "Kept quiet.".to_string(), ..clause.clone() }));
+    }
+}
@@ -0,0 +1,206 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::money::amount;
+
+use super::FieldError;
+
+/// Trigger named when an inbound email's signature doesn't verify.
+pub const EMAIL_TRIGGER: &str = "creditor_email";
+
+/// A creditor's email as the inbound mail provider posts it.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InboundEmail {
+    /// The message's `Message-ID`; a redelivered email carries the same one.
+    pub message_id: String,
+    /// `From` address, with or without a display name.
+    pub from: String,
+    /// Names the settlement replied about by its reference number.
+    pub subject: String,
+    /// Plain-text body.
+    #[serde(default)]
+    pub text: String,
+}
+
+impl InboundEmail {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.message_id.trim().is_empty() {
+            errors.push(FieldError::new("message_id", "must not be empty"));
+        }
+        if self.sender_address().is_none() {
+            errors.push(FieldError::new("from", "must be an email address"));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// The bare address `from` names, lower-cased: `billing@example.com`
+    /// for `Billing <Billing@Example.com>`.
+    pub fn sender_address(&self) -> Option<String> {
+        let from = self.from.trim();
+        let address = match (from.rfind('<'), from.ends_with('>')) {
+            (Some(start), true) => &from[start + 1..from.len() - 1],
+            _ => from,
+        };
+        let address = address.trim();
+        match address.split_once('@') {
+            Some((local, domain)) if !local.is_empty() && domain.contains('.') && !address.contains(' ') => {
+                Some(address.to_ascii_lowercase())
+            }
+            _ => None,
+        }
+    }
+    
+    /// The first settlement reference number in the subject, as in
+    /// `Re: Settlement offer DMC-2024-000123`.
+    pub fn reference_number(&self) -> Option<String> {
+        self.subject
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .map(|word| word.trim_matches('-').to_ascii_uppercase())
+            .find(|word| is_reference_number(word))
+    }
+}
+
+/// `DMC-<year>-<sequence of six or more digits>`.
+fn is_reference_number(word: &str) -> bool {
+    let mut parts = word.split('-');
+    matches!(
+        (parts.next(), parts.next(), parts.next(), parts.next()),
+        (Some("DMC"), Some(year), Some(sequence), None)
+            if year.len() == 4
+                && sequence.len() >= 6
+                && year.bytes().chain(sequence.bytes()).all(|b| b.is_ascii_digit())
+    )
+}
+
+/// What a creditor's reply says, as the AI service reads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "creditor_reply_intent", rename_all = "snake_case")]
+pub enum ReplyIntent {
+    /// Takes the proposal as it stands.
+    Accept,
+    /// Turns it down outright.
+    Reject,
+    /// Offers to settle for a different amount.
+    Counter,
+    Unclear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditorReply {
+    pub intent: ReplyIntent,
+    /// The amount countered with.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    pub amount: Option<BigDecimal>,
+    /// How sure the reading is, between 0 and 1.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "creditor_email_status", rename_all = "snake_case")]
+pub enum CreditorEmailStatus {
+    /// Claimed by a delivery that hasn't finished acting on it.
+    Received,
+    Processed,
+    /// Couldn't be acted on; `review_reason` says why.
+    NeedsReview,
+}
+
+/// A creditor's email and what was done about it.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct CreditorEmail {
+    pub id: Uuid,
+    pub message_id: String,
+    pub sender: String,
+    pub subject: String,
+    pub body: String,
+    /// The settlement the subject referenced, once it was found.
+    pub settlement_id: Option<Uuid>,
+    pub intent: Option<ReplyIntent>,
+    /// The amount a counter named.
+    #[schema(value_type = Option<String>)]
+    pub amount: Option<BigDecimal>,
+    pub status: CreditorEmailStatus,
+    pub review_reason: Option<String>,
+    pub received_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+impl CreditorEmail {
+    /// `email` as received, before anything is done about it.
+    pub fn received(email: &InboundEmail, received_at: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            message_id: email.message_id.trim().to_string(),
+            sender: email.sender_address().unwrap_or_else(|| email.from.clone()),
+            subject: email.subject.clone(),
+            body: email.text.clone(),
+            settlement_id: None,
+            intent: None,
+            amount: None,
+            status: CreditorEmailStatus::Received,
+            review_reason: None,
+            received_at,
+            processed_at: None,
+        }
+    }
+}
+
+/// Response to an inbound email: what was done about it, now or when an
+/// earlier delivery of the same message was received.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReceivedEmail {
+    #[serde(flatten)]
+    pub email: CreditorEmail,
+    /// False when the message had been received before.
+    pub created: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct ListCreditorEmailsQuery {
+    /// `NeedsReview` unless given.
+    pub status: Option<CreditorEmailStatus>,
+    pub limit: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn email(from: &str, subject: &str) -> InboundEmail {
+        InboundEmail {
+            message_id: "<reply-1@example.com>".to_string(),
+            from: from.to_string(),
+            subject: subject.to_string(),
+            text: String::new(),
+        }
+    }
+    
+    #[test]
+    fn replies_name_their_sender_and_settlement() {
+        let reply = email("Acme Billing <Billing@Acme.example>", "RE: Settlement offer (dmc-2024-000123)");
+        assert_eq!(reply.sender_address().as_deref(), Some("billing@acme.example"));
+        assert_eq!(reply.reference_number().as_deref(), Some("DMC-2024-000123"));
+        
+        let bare = email("billing@acme.example", "Fwd: DMC-2024-1234567 and DMC-2024-000124");
+        assert_eq!(bare.sender_address().as_deref(), Some("billing@acme.example"));
+        assert_eq!(bare.reference_number().as_deref(), Some("DMC-2024-1234567"));
+        
+        assert_eq!(email("Acme <billing>", "DMC-24-000123").sender_address(), None);
+        assert_eq!(email("a@b.example", "Your offer DMC-2024-123").reference_number(), None);
+        assert_eq!(email("a@b.example", "Your offer").reference_number(), None);
+        assert!(email("not an address", "").validate().is_err());
+    }
+}
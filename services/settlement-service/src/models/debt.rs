@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use bigdecimal::BigDecimal;
+use utoipa::ToSchema;
+
+use crate::money::{amount, Currency};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Debt {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Everyone who owes the debt, `user_id` among them. A debt held jointly
+    /// has more than one, and each must accept a settlement of it.
+    #[serde(default)]
+    pub debtors: Vec<Uuid>,
+    pub creditor_id: Uuid,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub original_amount: BigDecimal,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub current_amount: BigDecimal,
+    pub currency: Currency,
+    pub status: String, // "active", "negotiating", "settled", "recovered"
+    pub last_payment_date: Option<DateTime<Utc>>,
+    pub verification_status: VerificationStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where the debt stands on FDCPA §809 validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "debt_verification_status", rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Unverified,
+    /// Validation requested; the collector must pause until it responds.
+    Requested,
+    Verified,
+    Disputed,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DebtVerificationRequest {
+    pub status: VerificationStatus,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DebtVerification {
+    pub id: Uuid,
+    pub debt_id: Uuid,
+    pub status: VerificationStatus,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Whether the debt is still within the statute of limitations for collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum SolStatus {
+    WithinPeriod,
+    TimeBarred,
+    /// No last-payment date on record, or no SOL rule for the jurisdiction.
+    Unknown,
+}
\ No newline at end of file
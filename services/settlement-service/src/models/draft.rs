@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::FieldError;
+
+/// Which proposal a draft becomes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "draft_kind", rename_all = "snake_case")]
+pub enum DraftKind {
+    /// A `CreateSettlementRequest`.
+    #[default]
+    Settlement,
+    /// A `BundleSettlementRequest`.
+    Bundle,
+}
+
+/// A proposal request saved part-way through. Nothing in it is checked, and
+/// nothing is charged or submitted, until it is finalized.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SettlementDraft {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: DraftKind,
+    /// The request's fields filled in so far, `user_id` aside.
+    #[schema(value_type = Object)]
+    pub fields: Value,
+    /// The settlement the draft was finalized into.
+    pub settlement_id: Option<Uuid>,
+    /// Bumped by every save.
+    pub version: i32,
+    /// Pushed back by every save; an expired draft is gone.
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SettlementDraft {
+    /// The request the draft amounts to, as the body it would be posted as.
+    pub fn request_body(&self) -> Vec<u8> {
+        let mut request = match &self.fields {
+            Value::Object(fields) => fields.clone(),
+            _ => Map::new(),
+        };
+        request.insert("user_id".to_string(), Value::String(self.user_id.to_string()));
+        serde_json::to_vec(&request).expect("a JSON object serializes")
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateDraftRequest {
+    pub user_id: Uuid,
+    #[serde(default)]
+    pub kind: DraftKind,
+    /// Any of the request's fields, in any state.
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub fields: Map<String, Value>,
+}
+
+impl CreateDraftRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.user_id.is_nil() {
+            errors.push(FieldError::new("user_id", "must not be the nil UUID"));
+        }
+        errors.extend(fields_problem(&self.fields));
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A save: the fields given replace the draft's, and a field given as
+/// `null` is cleared. Fields not given are left as they are.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDraftRequest {
+    #[schema(value_type = Object)]
+    pub fields: Map<String, Value>,
+}
+
+impl UpdateDraftRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        match fields_problem(&self.fields) {
+            Some(error) => Err(vec![error]),
+            None => Ok(()),
+        }
+    }
+    
+    /// The fields set, and the names of those cleared.
+    pub fn changes(&self) -> (Map<String, Value>, Vec<String>) {
+        let (cleared, set): (Map<String, Value>, Map<String, Value>) =
+            self.fields.clone().into_iter().partition(|(_, value)| value.is_null());
+        (set, cleared.into_iter().map(|(name, _)| name).collect())
+    }
+}
+
+/// The draft belongs to one user; its fields can't name another.
+fn fields_problem(fields: &Map<String, Value>) -> Option<FieldError> {
+    fields
+        .contains_key("user_id")
+        .then(|| FieldError::new("fields.user_id", "is the draft's own user_id"))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct ListDraftsQuery {
+    pub user_id: Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    
+    use super::*;
+    
+    #[test]
+    fn saves_set_and_clear_fields() {
+        let save: UpdateDraftRequest = serde_json::from_value(json!({
+            "fields": { "jurisdiction": "CA", "debt_id": null, "violations": [] },
+        }))
+        .unwrap();
+        let (set, cleared) = save.changes();
+        
+        assert_eq!(Value::Object(set), json!({ "jurisdiction": "CA", "violations": [] }));
+        assert_eq!(cleared, ["debt_id"]);
+        assert!(save.validate().is_ok());
+        
+        let other_user: UpdateDraftRequest = serde_json::from_value(json!({
+            "fields": { "user_id": Uuid::new_v4() },
+        }))
+        .unwrap();
+        assert_eq!(other_user.validate().unwrap_err()[0].field, "fields.user_id");
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::blockchain::CircuitState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Overall {
+    Healthy,
+    /// A non-critical dependency is down; the service still works.
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DependencyCheck {
+    #[schema(value_type = String)]
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub critical: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// State of the circuit breaker in front of the dependency, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<CircuitState>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthReport {
+    pub status: Overall,
+    pub checks: Vec<DependencyCheck>,
+}
+
+impl HealthReport {
+    pub fn from_checks(checks: Vec<DependencyCheck>) -> Self {
+        let down = |critical: bool| {
+            checks
+                .iter()
+                .any(|c| c.critical == critical && c.status == CheckStatus::Down)
+        };
+        
+        let status = if down(true) {
+            Overall::Unhealthy
+        } else if down(false) {
+            Overall::Degraded
+        } else {
+            Overall::Healthy
+        };
+        
+        Self { status, checks }
+    }
+}
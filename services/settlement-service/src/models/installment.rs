@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use bigdecimal::BigDecimal;
+use utoipa::ToSchema;
+
+use crate::money::{amount, Currency};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Cadence {
+    Weekly,
+    Biweekly,
+    Monthly,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InstallmentPlan {
+    pub settlement_id: Uuid,
+    pub num_installments: u32,
+    pub cadence: Cadence,
+    pub first_due: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Installment {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub sequence: i32,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    pub due_date: DateTime<Utc>,
+    pub status: InstallmentStatus,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+/// A settlement's installments and the platform fee they have earned. The
+/// fee accrues in proportion to the settled amount paid so far, so a user is
+/// only charged on savings actually realized.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InstallmentSchedule {
+    pub settlement_id: Uuid,
+    pub installments: Vec<Installment>,
+    /// The settlement's platform fee, owed once every installment is paid.
+    #[schema(value_type = String)]
+    pub total_fee: BigDecimal,
+    /// The share of `total_fee` the paid installments have accrued.
+    #[schema(value_type = String)]
+    pub accrued_fee: BigDecimal,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "installment_status", rename_all = "snake_case")]
+pub enum InstallmentStatus {
+    Pending,
+    Paid,
+    Missed,
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx::types::Json;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{AiMode, LeverageAnalysis, Statute};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LeverageRequest {
+    pub creditor_id: Uuid,
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    /// Counts only the violations under these statutes, to model a narrower
+    /// complaint; the rest are reported as `excluded_violations`. Every
+    /// statute counts when absent or empty.
+    #[serde(default)]
+    pub statutes: Option<Vec<Statute>>,
+    /// `Disabled` returns the rules' analysis without the AI service's
+    /// refinement; the service's `AI_MODE` applies when absent.
+    #[serde(default)]
+    pub ai_mode: Option<AiMode>,
+}
+
+impl LeverageRequest {
+    pub fn counts_statute(&self, statute: Statute) -> bool {
+        match self.statutes.as_deref() {
+            Some(statutes) if !statutes.is_empty() => statutes.contains(&statute),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchLeverageRequest {
+    pub items: Vec<LeverageRequest>,
+}
+
+/// A leverage analysis as it stood when a proposal was made.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct LeverageSnapshot {
+    pub id: i64,
+    pub creditor_id: Uuid,
+    pub settlement_id: Option<Uuid>,
+    #[schema(value_type = LeverageAnalysis)]
+    pub analysis: Json<LeverageAnalysis>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How a creditor has fared across every user. Rates and averages are
+/// `None` until there is something to average.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct CreditorProfile {
+    pub creditor_id: Uuid,
+    pub total_violations: i64,
+    pub average_leverage_score: Option<f64>,
+    pub total_settlements: i64,
+    /// Proposals no longer open, whether accepted or not.
+    pub decided_settlements: i64,
+    /// Share of the decided proposals that the creditor accepted.
+    pub acceptance_rate: Option<f64>,
+    /// Mean saving on completed settlements, as a percentage of the debt.
+    pub average_reduction_percentage: Option<f64>,
+    pub completed_settlements: i64,
+    pub failed_settlements: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct LeverageHistoryQuery {
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct LeaderboardQuery {
+    pub user_id: Uuid,
+}
+
+/// Where the user stands with one creditor, as of the latest leverage
+/// snapshot taken for one of their settlements with it.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LeaderboardEntry {
+    pub creditor_id: Uuid,
+    pub total_leverage_score: f64,
+    pub violation_count: i32,
+    pub estimated_reduction_percentage: f64,
+    pub legal_strength: String,
+    /// The settlement the snapshot was taken for.
+    pub settlement_id: Option<Uuid>,
+    pub scored_at: DateTime<Utc>,
+}
+
+/// The user's creditors, the one they have the most leverage against first.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LeverageLeaderboard {
+    pub user_id: Uuid,
+    pub creditors: Vec<LeaderboardEntry>,
+    /// When the ranking was computed; it may be served from cache since.
+    pub cached_at: DateTime<Utc>,
+}
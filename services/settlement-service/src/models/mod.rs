@@ -1,7 +1,37 @@
 pub mod settlement;
 pub mod violation;
 pub mod debt;
+pub mod installment;
+pub mod negotiation;
+pub mod health;
+pub mod leverage;
+pub mod audit;
+pub mod webhook;
+pub mod signature;
+pub mod auto_negotiation;
+pub mod creditor;
+pub mod bundle;
+pub mod reconciliation;
+pub mod batch;
+pub mod creditor_email;
+pub mod draft;
+pub mod acceptance;
 
 pub use settlement::*;
 pub use violation::*;
-pub use debt::*;
\ No newline at end of file
+pub use debt::*;
+pub use installment::*;
+pub use negotiation::*;
+pub use health::*;
+pub use leverage::*;
+pub use audit::*;
+pub use webhook::*;
+pub use signature::*;
+pub use auto_negotiation::*;
+pub use creditor::*;
+pub use bundle::*;
+pub use reconciliation::*;
+pub use batch::*;
+pub use creditor_email::*;
+pub use draft::*;
+pub use acceptance::*;
\ No newline at end of file
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+use crate::money::{amount, Currency};
+
+use super::{FieldError, LeverageAnalysis, ProposalSource, Settlement};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "negotiation_party", rename_all = "snake_case")]
+pub enum Party {
+    Creditor,
+    #[default]
+    Debtor,
+}
+
+/// How hard to push, chosen per settlement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "negotiation_strategy", rename_all = "snake_case")]
+pub enum NegotiationStrategy {
+    /// A modest reduction the creditor is likely to accept quickly.
+    Conservative,
+    #[default]
+    Balanced,
+    /// Anchors low and leans on every documented violation.
+    Aggressive,
+}
+
+impl NegotiationStrategy {
+    /// Target reduction as a percentage of the one the leverage supports.
+    pub fn target_reduction_scale(self) -> i64 {
+        match self {
+            NegotiationStrategy::Conservative => 60,
+            NegotiationStrategy::Balanced => 100,
+            NegotiationStrategy::Aggressive => 140,
+        }
+    }
+    
+    /// How many key violations a proposal cites; `None` cites all of them.
+    pub fn violations_cited(self) -> Option<usize> {
+        match self {
+            NegotiationStrategy::Conservative => Some(1),
+            NegotiationStrategy::Balanced => Some(3),
+            NegotiationStrategy::Aggressive => None,
+        }
+    }
+    
+    /// Percentage of the gap to a creditor's counter-offer conceded per round.
+    pub fn concession_percent(self) -> i64 {
+        match self {
+            NegotiationStrategy::Conservative => 75,
+            NegotiationStrategy::Balanced => 50,
+            NegotiationStrategy::Aggressive => 25,
+        }
+    }
+}
+
+/// Whether the AI service may be asked, for users whose contracts keep AI
+/// out of the loop. With `Disabled` the rules alone score the leverage and
+/// set the amount. `AI_MODE` sets it for the service; a request may turn AI
+/// off for itself, but not back on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum AiMode {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
+impl FromStr for AiMode {
+    type Err = String;
+    
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "enabled" => Ok(AiMode::Enabled),
+            "disabled" => Ok(AiMode::Disabled),
+            _ => Err(format!("{:?} is not an AI mode", raw.trim())),
+        }
+    }
+}
+
+/// Where a proposal opens, before the creditor's floor is applied.
+/// `SETTLEMENT_ANCHORING_STRATEGY` sets it for every proposal, as
+/// `leverage_proportional`, `fixed_percentage:<percent>` or
+/// `statutory_damages`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AnchoringStrategy {
+    /// The amount the AI service or the rules fallback proposes: a
+    /// reduction in proportion to the leverage, scaled by the negotiation
+    /// strategy.
+    #[default]
+    LeverageProportional,
+    /// A fixed share of the current balance; `40.0` opens at 40% of it.
+    FixedPercentage(f64),
+    /// The current balance less the statutory damages the creditor could
+    /// owe for each documented violation, summed; never below zero.
+    StatutoryDamages,
+}
+
+impl fmt::Display for AnchoringStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnchoringStrategy::LeverageProportional => write!(f, "leverage_proportional"),
+            AnchoringStrategy::FixedPercentage(percentage) => write!(f, "fixed_percentage:{}", percentage),
+            AnchoringStrategy::StatutoryDamages => write!(f, "statutory_damages"),
+        }
+    }
+}
+
+impl FromStr for AnchoringStrategy {
+    type Err = String;
+    
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.trim().split_once(':') {
+            None if raw.trim() == "leverage_proportional" => Ok(AnchoringStrategy::LeverageProportional),
+            None if raw.trim() == "statutory_damages" => Ok(AnchoringStrategy::StatutoryDamages),
+            Some(("fixed_percentage", percentage)) => match percentage.trim().parse::<f64>() {
+                Ok(percentage) if percentage > 0.0 && percentage <= 100.0 => {
+                    Ok(AnchoringStrategy::FixedPercentage(percentage))
+                }
+                _ => Err(format!("{:?} is not a percentage above 0 and at most 100", percentage.trim())),
+            },
+            _ => Err(format!("{:?} is not an anchoring strategy", raw.trim())),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CounterOffer {
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    pub from: Party,
+    /// Currency `amount` is in; absent means the settlement's.
+    #[serde(default)]
+    pub currency: Option<Currency>,
+    /// Language the decision's reasoning is written in; `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+    /// From the debtor only: replaces the settlement's
+    /// [`auto_accept_below`](crate::models::Settlement::auto_accept_below)
+    /// threshold for the creditor's counters from here on.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub auto_accept_below: Option<BigDecimal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "negotiation_action", rename_all = "snake_case")]
+pub enum NegotiationAction {
+    Accept,
+    Counter,
+    Hold,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NegotiationRound {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub round_number: i32,
+    pub from_party: Party,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    pub decision: NegotiationAction,
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub counter_amount: Option<BigDecimal>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What the engine recommends doing with a counter-offer.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NegotiationDecision {
+    pub action: NegotiationAction,
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub counter_amount: Option<BigDecimal>,
+    pub reasoning: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CounterOfferResponse {
+    pub decision: NegotiationDecision,
+    pub round: NegotiationRound,
+    /// The settlement, accepted and submitted for payment on the user's
+    /// behalf, when the counter was within their `auto_accept_below`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_accepted: Option<Settlement>,
+}
+
+/// An amount to try on a creditor before negotiating for real.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AcceptancePredictionRequest {
+    /// The user weighing the amount; only they or support may ask.
+    pub user_id: Uuid,
+    pub creditor_id: Uuid,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub proposed_amount: BigDecimal,
+    /// The balance `proposed_amount` would settle.
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub original_amount: BigDecimal,
+    #[serde(default)]
+    pub currency: Currency,
+    /// As returned when scoring leverage or proposing a settlement.
+    pub leverage_analysis: LeverageAnalysis,
+    /// Language the reasoning is written in; `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl AcceptancePredictionRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        let zero = BigDecimal::from(0);
+        
+        if self.user_id.is_nil() {
+            errors.push(FieldError::new("user_id", "must not be the nil UUID"));
+        }
+        if self.creditor_id.is_nil() {
+            errors.push(FieldError::new("creditor_id", "must not be the nil UUID"));
+        }
+        if self.original_amount <= zero {
+            errors.push(FieldError::new("original_amount", "must be positive"));
+        }
+        if self.proposed_amount <= zero {
+            errors.push(FieldError::new("proposed_amount", "must be positive"));
+        } else if self.proposed_amount > self.original_amount {
+            errors.push(FieldError::new("proposed_amount", "must not exceed original_amount"));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// How much of the balance `proposed_amount` asks the creditor to forgo.
+    pub fn requested_reduction_percentage(&self) -> f64 {
+        if self.original_amount <= BigDecimal::from(0) {
+            return 0.0;
+        }
+        ((&self.original_amount - &self.proposed_amount) * BigDecimal::from(100) / &self.original_amount)
+            .to_f64()
+            .unwrap_or(0.0)
+    }
+}
+
+/// The AI service's view of whether the creditor accepts, from the leverage
+/// alone; the engine weighs in the creditor's record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptanceAssessment {
+    pub acceptance_probability: f64,
+    /// The reduction, as a percentage of the balance, the creditor is most
+    /// likely to agree to.
+    pub acceptable_reduction_percentage: f64,
+    pub reasoning: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AcceptancePrediction {
+    /// Between 0 and 1.
+    pub acceptance_probability: f64,
+    /// The amount the creditor is most likely to accept, which may be below
+    /// the one proposed.
+    #[schema(value_type = String)]
+    pub suggested_amount: BigDecimal,
+    pub reasoning: Vec<String>,
+    /// `RulesFallback` when the AI service couldn't be asked.
+    pub source: ProposalSource,
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use super::SettlementStatus;
+
+/// What the chain says about a settlement's transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ChainState {
+    /// In a block at least the configured confirmation depth deep.
+    Confirmed,
+    /// In the mempool or a block too shallow to count yet.
+    Pending,
+    /// Neither in a block nor in the mempool.
+    Dropped,
+}
+
+/// Why reconciliation moved a settlement to `NeedsReview`; recorded as the
+/// metadata of its `FlaggedForReview` event.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewFlag {
+    /// What the database said before the settlement was flagged.
+    pub recorded_status: SettlementStatus,
+    pub chain_state: ChainState,
+    pub tx_hash: String,
+}
+
+/// A settlement waiting in `NeedsReview` and what flagged it.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Discrepancy {
+    pub settlement_id: Uuid,
+    pub reference_number: Option<String>,
+    #[sqlx(json)]
+    #[serde(flatten)]
+    pub flag: ReviewFlag,
+    pub flagged_at: DateTime<Utc>,
+}
+
+/// One pass of the reconciliation job.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReconciliationRun {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Settlements whose transaction was looked up on-chain.
+    pub checked: usize,
+    /// Settlements moved to `NeedsReview` by this pass.
+    pub flagged: usize,
+    /// Settlements that could not be checked, e.g. because the node was down.
+    pub errors: usize,
+}
+
+/// Response of `GET /admin/reconciliation/report`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReconciliationReport {
+    pub generated_at: DateTime<Utc>,
+    /// This instance's latest pass; none until it has run once since starting.
+    pub last_run: Option<ReconciliationRun>,
+    /// Every settlement still in `NeedsReview`, oldest flag first.
+    pub discrepancies: Vec<Discrepancy>,
+}
@@ -3,24 +3,184 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use bigdecimal::BigDecimal;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+use crate::i18n::Locale;
+use crate::money::{amount, Currency};
+
+use super::{AiMode, NegotiationStrategy, Party, SolStatus, Statute};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Settlement {
     pub id: Uuid,
+    /// `DMC-<year>-<sequence>`, assigned when the settlement is stored, so
+    /// `None` only on previews.
+    pub reference_number: Option<String>,
     pub user_id: Uuid,
     pub debt_id: Uuid,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
     pub original_amount: BigDecimal,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
     pub settled_amount: BigDecimal,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
     pub saved_amount: BigDecimal,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
     pub platform_fee: BigDecimal,
+    /// The proposed `settled_amount`, kept when the settlement is accepted at
+    /// a different amount; `settled_amount`, `saved_amount` and
+    /// `platform_fee` then hold the accepted terms.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub proposed_amount: Option<BigDecimal>,
+    /// The proposed `platform_fee`, kept alongside `proposed_amount`.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub proposed_platform_fee: Option<BigDecimal>,
+    /// Currency of every amount above; always the debt's.
+    pub currency: Currency,
     pub status: SettlementStatus,
     pub smart_contract_address: Option<String>,
     pub transaction_hash: Option<String>,
     pub proposed_at: DateTime<Utc>,
+    /// An open proposal nobody has acted on by now is moved to `Expired`.
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub rescinded_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    pub rejection_reason: Option<RejectReason>,
+    pub rejection_note: Option<String>,
+    /// Resubmissions after a failed on-chain payment.
+    pub retry_count: i32,
+    /// Bumped by every update; writers must present the version they read.
+    pub version: i32,
+    /// Model that produced the proposal, `rules-fallback` when the AI was
+    /// down and `creditor-offer` when the creditor set the amount.
+    pub model_version: Option<String>,
+    /// SHA-256 of the request the proposal was computed from.
+    pub prompt_hash: Option<String>,
+    /// Version of the prompt templates the AI was asked with; none for
+    /// rules-based proposals.
+    pub prompt_version: Option<String>,
+    /// Strategy the proposal was made with; counters follow it too.
+    pub strategy: NegotiationStrategy,
+    /// Who offered these terms; the other party is the one asked to answer.
+    #[serde(default)]
+    pub proposed_by: Party,
+    /// Label the payment's on-chain metadata was recorded under.
+    pub metadata_label: Option<i64>,
+    /// Hash of the terms the payment committed to on-chain.
+    pub terms_hash: Option<String>,
+    /// Set when the user removes the settlement; the row itself is kept.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Set while execution waits for the Cardano node's circuit breaker to
+    /// close; the settlement stays `Accepted` until it is submitted.
+    #[serde(default)]
+    pub execution_queued_at: Option<DateTime<Utc>>,
+    /// Hash of the creditor clauses the debtor agreed to on acceptance; see
+    /// [`clauses_hash`](super::clauses_hash).
+    #[serde(default)]
+    pub clauses_hash: Option<String>,
+    /// How the platform fee was charged; see [`FeePolicy`].
+    #[serde(default)]
+    #[sqlx(json)]
+    pub fee_policy: FeePolicy,
+    /// A creditor counter at or below this is accepted on the user's
+    /// behalf; see [`CreateSettlementRequest::auto_accept_below`].
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub auto_accept_below: Option<BigDecimal>,
+    /// Idempotency key the payment is submitted under, written just before
+    /// submission so one interrupted before its hash was recorded can be
+    /// found again.
+    #[serde(default)]
+    pub submission_intent: Option<Uuid>,
+    /// Copied from the creditor's settings when the settlement is proposed.
+    #[serde(default)]
+    pub execution_mode: ExecutionMode,
+    /// When an escrowed payment may be refunded to the debtor if the
+    /// creditor hasn't released it. Set, and committed to the lock, just
+    /// before an escrow payment is submitted.
+    #[serde(default)]
+    pub escrow_refund_after: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl Settlement {
+    /// A proposal made just now and not yet stored: 600 USD for a debt of
+    /// 1000. Tests change what they're about with struct update syntax.
+    pub fn fixture() -> Self {
+        let now = Utc::now();
+        Settlement {
+            id: Uuid::new_v4(),
+            reference_number: None,
+            user_id: Uuid::new_v4(),
+            debt_id: Uuid::new_v4(),
+            original_amount: BigDecimal::from(1000),
+            settled_amount: BigDecimal::from(600),
+            saved_amount: BigDecimal::from(400),
+            platform_fee: BigDecimal::from(86),
+            proposed_amount: None,
+            proposed_platform_fee: None,
+            currency: Currency::usd(),
+            status: SettlementStatus::Proposed,
+            smart_contract_address: None,
+            transaction_hash: None,
+            proposed_at: now,
+            expires_at: now + chrono::Duration::days(14),
+            accepted_at: None,
+            rescinded_at: None,
+            completed_at: None,
+            rejection_reason: None,
+            rejection_note: None,
+            retry_count: 0,
+            version: 0,
+            model_version: None,
+            prompt_hash: None,
+            prompt_version: None,
+            strategy: Default::default(),
+            proposed_by: Default::default(),
+            metadata_label: None,
+            terms_hash: None,
+            deleted_at: None,
+            execution_queued_at: None,
+            clauses_hash: None,
+            fee_policy: FeePolicy::Standard,
+            auto_accept_below: None,
+            submission_intent: None,
+            execution_mode: Default::default(),
+            escrow_refund_after: None,
+        }
+    }
+}
+
+/// How an accepted settlement's payment is made.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "execution_mode", rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// Paid to the creditor outright; the settlement completes once the
+    /// payment confirms.
+    #[default]
+    Direct,
+    /// Locked in a script address until the creditor releases it, which
+    /// completes the settlement, or until the refund deadline passes, after
+    /// which the debtor may take it back.
+    Escrow,
+}
+
+/// A negotiation that has come within `threshold_hours` of expiring without
+/// a reminder for that threshold being sent.
+#[derive(Debug, Clone, FromRow)]
+pub struct DueReminder {
+    #[sqlx(flatten)]
+    pub settlement: Settlement,
+    pub threshold_hours: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "settlement_status", rename_all = "snake_case")]
 pub enum SettlementStatus {
     Proposed,
@@ -29,50 +189,1128 @@ pub enum SettlementStatus {
     Rejected,
     Completed,
     Failed,
+    Expired,
+    /// Recorded as completed, but reconciliation found the transaction is
+    /// not on-chain. Only support can move it on, by forcing a status.
+    NeedsReview,
+    /// An escrow payment is locked in its script address, waiting for the
+    /// creditor to release it.
+    Escrowed,
+    /// The escrowed payment went back to the debtor unreleased.
+    Refunded,
+}
+
+impl SettlementStatus {
+    /// Whether the user may delete a settlement in this status: drafts and dead
+    /// ends only, never anything money has moved or may still move on.
+    pub fn is_deletable(&self) -> bool {
+        matches!(
+            self,
+            SettlementStatus::Proposed | SettlementStatus::Rejected | SettlementStatus::Expired
+        )
+    }
+    
+    /// Still waiting on either party, and so subject to expiry.
+    pub fn is_open(&self) -> bool {
+        matches!(self, SettlementStatus::Proposed | SettlementStatus::Negotiating)
+    }
+    
+    /// Whether the settlement lifecycle allows moving from `self` to `next`.
+    /// `Rejected`, `Expired`, `NeedsReview` and `Refunded` are terminal and have
+    /// no outgoing edges; `Completed` only leads to `NeedsReview`, when
+    /// reconciliation finds its transaction dropped. `Accepted -> Proposed` is
+    /// the cooling-off rescission; `Failed -> Accepted` is a retry and
+    /// `Failed -> Completed` a failed tx that confirmed after all, or
+    /// `Failed -> Escrowed` for an escrow lock. `Escrowed` ends in `Completed`
+    /// when released or `Refunded`.
+    pub fn can_transition_to(&self, next: &SettlementStatus) -> bool {
+        use SettlementStatus::*;
+        
+        matches!(
+            (self, next),
+            (Proposed, Negotiating | Accepted | Rejected | Expired)
+                | (Negotiating, Accepted | Rejected | Expired)
+                | (Accepted, Proposed | Completed | Failed | Escrowed)
+                | (Failed, Accepted | Completed | Escrowed)
+                | (Completed, NeedsReview)
+                | (Escrowed, Completed | Refunded)
+        )
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for SettlementStatus {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_settlement_status")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "reject_reason", rename_all = "snake_case")]
+pub enum RejectReason {
+    AmountTooHigh,
+    PreferLitigation,
+    DisputeDebt,
+    Other,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateSettlementRequest {
     pub user_id: Uuid,
     pub creditor_id: Uuid,
     pub debt_id: Option<Uuid>,
     pub violations: Vec<Uuid>,
+    pub jurisdiction: String, // two-letter state code, e.g. "CA"
+    #[serde(default)]
+    pub strategy: NegotiationStrategy,
+    /// Language the reasoning and recommended action are written in;
+    /// `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Overrides the user's fee policy for this proposal. Only support may
+    /// ask for anything but the standard fee.
+    #[serde(default)]
+    pub fee_policy: Option<FeePolicy>,
+    /// The most the user will pay without being asked again: a creditor
+    /// counter at or below it is accepted, and executed, on their behalf.
+    /// Counters above it wait for the user.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub auto_accept_below: Option<BigDecimal>,
+    /// `Disabled` keeps the AI service out of this proposal; the service's
+    /// `AI_MODE` applies when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_mode: Option<AiMode>,
+}
+
+impl CreateSettlementRequest {
+    /// Checks the request's shape before it reaches the engine, reporting
+    /// every problem rather than just the first.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.user_id.is_nil() {
+            errors.push(FieldError::new("user_id", "must not be the nil UUID"));
+        }
+        if self.creditor_id.is_nil() {
+            errors.push(FieldError::new("creditor_id", "must not be the nil UUID"));
+        }
+        if !self.user_id.is_nil() && self.creditor_id == self.user_id {
+            errors.push(FieldError::new("creditor_id", "must differ from user_id"));
+        }
+        match self.debt_id {
+            Some(debt_id) if debt_id.is_nil() => {
+                errors.push(FieldError::new("debt_id", "must not be the nil UUID"));
+            }
+            None if self.violations.is_empty() => {
+                errors.push(FieldError::new(
+                    "violations",
+                    "must not be empty when debt_id is omitted",
+                ));
+            }
+            _ => {}
+        }
+        if let Some(index) = self.violations.iter().position(Uuid::is_nil) {
+            errors.push(FieldError::new(
+                "violations",
+                format!("entry {} must not be the nil UUID", index),
+            ));
+        }
+        if let Some(problem) = self.fee_policy.as_ref().and_then(FeePolicy::problem) {
+            errors.push(FieldError::new("fee_policy.rate", problem));
+        }
+        if self.auto_accept_below.as_ref().is_some_and(|amount| *amount <= BigDecimal::from(0)) {
+            errors.push(FieldError::new("auto_accept_below", "must be positive"));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Terms a creditor offers the user, made under the creditor's own
+/// credentials, so it names no creditor.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreditorProposalRequest {
+    pub user_id: Uuid,
+    pub debt_id: Uuid,
+    /// What the creditor will accept, in the debt's currency.
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    /// The violations the user holds against the creditor, so the user can
+    /// see what their leverage is worth next to the offer.
+    #[serde(default)]
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    /// Language the user reads the reasoning, recommended action and
+    /// notification in; `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl CreditorProposalRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.user_id.is_nil() {
+            errors.push(FieldError::new("user_id", "must not be the nil UUID"));
+        }
+        if self.debt_id.is_nil() {
+            errors.push(FieldError::new("debt_id", "must not be the nil UUID"));
+        }
+        if self.amount <= BigDecimal::from(0) {
+            errors.push(FieldError::new("amount", "must be positive"));
+        }
+        if let Some(index) = self.violations.iter().position(Uuid::is_nil) {
+            errors.push(FieldError::new(
+                "violations",
+                format!("entry {} must not be the nil UUID", index),
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// The proposal request the user would make for the same debt, which the
+    /// leverage analysis is run for.
+    pub fn settlement_request(&self, creditor_id: Uuid) -> CreateSettlementRequest {
+        CreateSettlementRequest {
+            user_id: self.user_id,
+            creditor_id,
+            debt_id: Some(self.debt_id),
+            violations: self.violations.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            strategy: NegotiationStrategy::default(),
+            locale: self.locale.clone(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: None,
+        }
+    }
+}
+
+/// Most hypothetical violations one what-if request may project.
+pub const MAX_WHAT_IF_VIOLATIONS: usize = 10;
+
+/// A proposal scenario plus violations that haven't happened yet, to see what
+/// waiting for them would be worth.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WhatIfRequest {
+    #[serde(flatten)]
+    pub base: CreateSettlementRequest,
+    /// Projected cumulatively, in order.
+    pub hypothetical_violations: Vec<HypotheticalViolation>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct HypotheticalViolation {
+    /// As on a documented violation, e.g. `FalseRepresentation`.
+    pub violation_type: String,
+    #[serde(default)]
+    pub statute: Statute,
+}
+
+impl WhatIfRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = self.base.validate().err().unwrap_or_default();
+        
+        if self.hypothetical_violations.len() > MAX_WHAT_IF_VIOLATIONS {
+            errors.push(FieldError::new(
+                "hypothetical_violations",
+                format!("must not have more than {} entries", MAX_WHAT_IF_VIOLATIONS),
+            ));
+        }
+        if let Some(index) = self
+            .hypothetical_violations
+            .iter()
+            .position(|violation| violation.violation_type.trim().is_empty())
+        {
+            errors.push(FieldError::new(
+                "hypothetical_violations",
+                format!("entry {} must have a violation_type", index),
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// The settlement projected with the documented violations plus some of the
+/// hypothetical ones.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WhatIfProjection {
+    /// The hypothetical violation this projection adds to the one before it;
+    /// `None` for the first, which has the documented violations only.
+    pub added_violation: Option<String>,
+    pub total_leverage_score: f64,
+    pub settlement: OptimalSettlement,
+    pub source: ProposalSource,
+    /// How much lower `settlement.amount` is than in the projection before;
+    /// zero for the first.
+    #[schema(value_type = String)]
+    pub marginal_reduction: BigDecimal,
+}
+
+/// The violations a proposal that has gone stale is recomputed with.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecomputeSettlementRequest {
+    /// Every violation the proposal should now count, not just those
+    /// documented since it was made.
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl RecomputeSettlementRequest {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.jurisdiction.trim().is_empty() {
+            errors.push(FieldError::new("jurisdiction", "must not be empty"));
+        }
+        if let Some(index) = self.violations.iter().position(Uuid::is_nil) {
+            errors.push(FieldError::new(
+                "violations",
+                format!("entry {} must not be the nil UUID", index),
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// The proposal request `settlement` would be made with today, under the
+    /// strategy it was first proposed with.
+    pub fn settlement_request(&self, settlement: &Settlement, creditor_id: Uuid) -> CreateSettlementRequest {
+        CreateSettlementRequest {
+            user_id: settlement.user_id,
+            creditor_id,
+            debt_id: Some(settlement.debt_id),
+            violations: self.violations.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            strategy: settlement.strategy,
+            locale: self.locale.clone(),
+            fee_policy: None,
+            auto_accept_below: settlement.auto_accept_below.clone(),
+            ai_mode: None,
+        }
+    }
+}
+
+/// A proposal recomputed with the current violations, and how its terms
+/// moved.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecomputedProposal {
+    pub settlement: Settlement,
+    pub fee_breakdown: FeeBreakdown,
+    pub leverage_analysis: LeverageAnalysis,
+    pub reasoning: Vec<String>,
+    pub source: ProposalSource,
+    pub changes: ProposalChanges,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProposalChanges {
+    pub settled_amount: AmountChange,
+    pub saved_amount: AmountChange,
+    pub platform_fee: AmountChange,
+    /// `None` when no leverage analysis was kept for the old proposal.
+    pub previous_leverage_score: Option<f64>,
+    pub leverage_score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct AmountChange {
+    #[schema(value_type = String)]
+    pub before: BigDecimal,
+    #[schema(value_type = String)]
+    pub after: BigDecimal,
+    /// `after - before`, so negative when the amount fell.
+    #[schema(value_type = String)]
+    pub difference: BigDecimal,
+}
+
+impl AmountChange {
+    pub fn new(before: &BigDecimal, after: &BigDecimal) -> Self {
+        Self {
+            before: before.clone(),
+            after: after.clone(),
+            difference: after - before,
+        }
+    }
+}
+
+/// One problem with one field of a request body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    #[schema(value_type = String)]
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self { field, message: message.into() }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AcceptSettlementRequest {
     pub settlement_id: Uuid,
     pub user_signature: Option<String>,
+    /// A completed e-signature envelope, in place of `user_signature`.
+    #[serde(default)]
+    pub envelope_id: Option<String>,
+    /// The debtor accepting, when the debt is held jointly; the settlement's
+    /// user unless given. A debtor can only accept for themselves.
+    #[serde(default)]
+    pub debtor_id: Option<Uuid>,
+    /// The amount actually agreed, when it differs from the proposed
+    /// `settled_amount`; savings and fees are recomputed from it. At most the
+    /// original amount. A signature must cover the terms at this amount, and
+    /// every debtor of a joint debt must accept at the same one.
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub accepted_amount: Option<BigDecimal>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RejectSettlementRequest {
+    pub settlement_id: Uuid,
+    pub reason_code: RejectReason,
+    pub note: Option<String>,
+}
+
+/// Support override of a settlement's status, outside the lifecycle graph.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForceStatusRequest {
+    pub status: SettlementStatus,
+    /// Why the override was needed; kept on the audit trail.
+    pub reason: String,
+    /// Also skip the checks against the on-chain record, e.g. completing a
+    /// settlement that has no confirmed transaction.
+    #[serde(default)]
+    pub force_unsafe: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct ListSettlementsQuery {
+    pub user_id: Uuid,
+    pub status: Option<SettlementStatus>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+    /// Admin override: lists deleted settlements too. Refused for anyone
+    /// else.
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct GetSettlementQuery {
+    /// Admin override, as on `ListSettlementsQuery`.
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginatedSettlements {
+    pub items: Vec<Settlement>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct SavingsSummaryQuery {
+    /// Amounts in different currencies don't add up, so a summary covers
+    /// one; USD by default.
+    pub currency: Option<Currency>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct DemandLetterQuery {
+    /// Days the creditor is given to answer, up to 90; 30 unless the
+    /// service is configured otherwise.
+    pub response_days: Option<u32>,
+}
+
+/// What a user has saved across their completed settlements in one currency.
+/// Settlements that failed, were rescinded or are still open don't count.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SavingsSummary {
+    pub user_id: Uuid,
+    pub currency: Currency,
+    #[schema(value_type = String)]
+    pub total_original: BigDecimal,
+    #[schema(value_type = String)]
+    pub total_settled: BigDecimal,
+    #[schema(value_type = String)]
+    pub total_saved: BigDecimal,
+    #[schema(value_type = String)]
+    pub total_fees: BigDecimal,
+    pub settlement_count: i64,
+    /// `total_saved` as a percentage of `total_original`, so each settlement
+    /// weighs in by the size of its debt; `None` without settlements.
+    pub average_reduction_percentage: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeBreakdown {
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub base_fee: BigDecimal,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub success_fee: BigDecimal,
+    pub success_fee_rate: f64,
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub blockchain_cost: BigDecimal,
+    /// Sum of the components before the platform fee cap.
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub uncapped_total: BigDecimal,
+    /// Taken off `uncapped_total` when the cap applies, zero otherwise.
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub cap_discount: BigDecimal,
+    pub cap_applied: bool,
+    /// The policy the fee was charged under.
+    #[serde(default)]
+    pub fee_policy: FeePolicy,
+    /// What the standard policy would charge, cap included, so a waived or
+    /// reduced fee still shows what was let go of.
+    #[serde(default, deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub standard_total: BigDecimal,
+    /// Taken off `standard_total` by a waived or reduced fee policy.
+    #[serde(default, deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
+    pub policy_discount: BigDecimal,
+    #[serde(default)]
+    pub currency: Currency,
+}
+
+impl FeeBreakdown {
+    /// The platform fee charged: the components less any cap and policy
+    /// discounts.
+    pub fn total(&self) -> BigDecimal {
+        &self.base_fee + &self.success_fee + &self.blockchain_cost - &self.cap_discount - &self.policy_discount
+    }
+}
+
+/// Ceiling on the platform fee; whichever configured limit is lower wins.
+#[derive(Debug, Clone, Default)]
+pub struct FeeCap {
+    pub max_amount: Option<BigDecimal>,
+    /// Percentage of the settled amount, e.g. `15` for 15%.
+    pub max_percent_of_settled: Option<BigDecimal>,
+}
+
+/// How much of the platform fee a settlement is charged. Legal aid cases
+/// have it waived or reduced; the standard fee is still worked out and
+/// shown next to what is charged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FeePolicy {
+    #[default]
+    Standard,
+    /// Nothing is charged.
+    Waived,
+    /// `rate` of the standard fee is charged, e.g. `0.5` for half.
+    Reduced {
+        #[serde(deserialize_with = "amount::deserialize")]
+        #[schema(value_type = String)]
+        rate: BigDecimal,
+    },
+}
+
+impl FeePolicy {
+    /// Whether the policy lets the user off any of the standard fee, which
+    /// only support may grant.
+    pub fn is_concession(&self) -> bool {
+        *self != FeePolicy::Standard
+    }
+    
+    /// Why the policy can't be charged under, if it can't.
+    pub fn problem(&self) -> Option<&'static str> {
+        match self {
+            FeePolicy::Reduced { rate } if *rate <= BigDecimal::from(0) || *rate >= BigDecimal::from(1) => {
+                Some("must be between 0 and 1, exclusive")
+            }
+            _ => None,
+        }
+    }
+    
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        match self.problem() {
+            Some(problem) => Err(vec![FieldError::new("rate", problem)]),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The fee policy a user's proposals get unless a request names another,
+/// and the admin who set it.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct UserFeePolicy {
+    pub user_id: Uuid,
+    #[sqlx(json)]
+    pub policy: FeePolicy,
+    pub set_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Whether a settlement's on-chain metadata still matches its stored terms.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MetadataVerification {
+    pub settlement_id: Uuid,
+    pub transaction_hash: String,
+    pub metadata_label: i64,
+    /// Recomputed from the settlement as stored now.
+    pub expected_terms_hash: String,
+    pub recorded_terms_hash: Option<String>,
+    /// `None` if the transaction carries no metadata under the label.
+    pub on_chain_terms_hash: Option<String>,
+    pub verified: bool,
+}
+
+/// A settlement's terms in the canonical form hashed into its on-chain
+/// metadata. Anyone holding the terms can check the hash: it is the
+/// `algorithm` digest of `terms`' UTF-8 bytes, in lowercase hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CanonicalTerms {
+    pub settlement_id: Uuid,
+    /// `damocles-settlement-terms`; `terms` starts with it and the version.
+    pub canonicalization: String,
+    /// `v3`; settlements paid before it keep `v1`, or `v2` for terms
+    /// accepted with creditor clauses.
+    pub canonicalization_version: String,
+    pub algorithm: String,
+    /// `<canonicalization>:v3|id|user_id|debt_id|currency|original|settled|fee[|clauses_hash]`,
+    /// amounts at the currency's minor units. `v1` and `v2` have no
+    /// currency and amounts at two decimal places; `v2` adds the clauses hash.
+    pub terms: String,
+    pub terms_hash: String,
+}
+
+/// Where a settlement's payment stands on-chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionState {
+    /// Nothing has been submitted on-chain yet.
+    NotSubmitted,
+    /// Waiting in the node's mempool for a block.
+    Pending,
+    /// In a block, but not yet deep enough to be final.
+    Confirming,
+    Confirmed,
+    /// Neither in a block nor in the mempool: dropped, or not yet seen by
+    /// the node.
+    NotFound,
+}
+
+/// A settlement's payment transaction as the Cardano node sees it, for users
+/// tracking it on-chain themselves.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TransactionStatus {
+    pub settlement_id: Uuid,
+    pub tx_hash: Option<String>,
+    /// `None` while the transaction is not in a block.
+    pub confirmations: Option<u32>,
+    pub block_height: Option<u64>,
+    /// Depth at which the payment counts as final.
+    pub required_confirmations: u32,
+    pub status: TransactionState,
+    /// The transaction on a public block explorer.
+    pub explorer_url: Option<String>,
+}
+
+/// What executing a settlement is expected to cost right now.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeeEstimate {
+    #[schema(value_type = String)]
+    pub estimated_network_fee: BigDecimal,
+    /// Settled amount plus platform fee plus the network fee.
+    #[schema(value_type = String)]
+    pub estimated_total: BigDecimal,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SettlementProposal {
     pub settlement: Settlement,
+    pub fee_breakdown: FeeBreakdown,
     pub leverage_analysis: LeverageAnalysis,
     pub recommended_action: String,
-    pub confidence_score: f64,
+    /// The model's confidence in the amount; absent when AI was disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_score: Option<f64>,
+    pub reasoning: Vec<String>,
+    pub source: ProposalSource,
+    pub model_version: String,
+    pub prompt_hash: String,
+    /// False for previews, whose settlement was never stored.
+    pub persisted: bool,
+    /// The creditor's settlement floor raised the amount; the leverage
+    /// analysis still reports what the violations alone are worth.
+    pub floor_applied: bool,
+    /// Problems that do not stop the proposal but need attention, such as a
+    /// creditor it cannot be delivered to.
+    pub warnings: Vec<String>,
+    /// Present only when the service runs in simulation mode, so the
+    /// amounts came from the seed rather than the AI service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<Simulation>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Simulation {
+    pub seed: u64,
+}
+
+/// Where the proposed amount came from, so callers can flag degraded proposals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ProposalSource {
+    Ai,
+    RulesFallback,
+    /// AI was disabled for the request; the rules set the amount.
+    RulesOnly,
+    /// The creditor named the amount; the leverage analysis is for comparison.
+    Creditor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LeverageAnalysis {
     pub violation_count: i32,
     pub total_leverage_score: f64,
     pub estimated_reduction_percentage: f64,
     pub legal_strength: String, // "weak", "moderate", "strong", "very_strong"
     pub key_violations: Vec<String>,
+    /// Violations with no evidence attached. They still count, but without
+    /// the corroboration bonus, and are the first a creditor will dispute.
+    #[serde(default)]
+    pub unsubstantiated_violations: Vec<Uuid>,
+    /// Violations left out because the limitations period for their statute
+    /// has run; a creditor can no longer be sued over them.
+    #[serde(default)]
+    pub expired_violations: Vec<Uuid>,
+    /// Violations left out because the request only counted other
+    /// statutes; see [`LeverageRequest::statutes`](super::LeverageRequest::statutes).
+    #[serde(default)]
+    pub excluded_violations: Vec<Uuid>,
+    pub statute_of_limitations: SolStatus,
+    /// How `total_leverage_score` was arrived at.
+    #[serde(default)]
+    pub explanation: LeverageExplanation,
+    /// The score and damages split by the law each violation falls under,
+    /// one entry per statute with violations.
+    #[serde(default)]
+    pub exposure_by_statute: Vec<StatuteExposure>,
 }
 
-#[derive(Debug, Serialize)]
+/// What the violations under one statute are worth. The leverage scores of
+/// all statutes sum to `total_leverage_score`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatuteExposure {
+    pub statute: Statute,
+    pub violation_count: i32,
+    /// For FDCPA this includes any state uplift on the same conduct.
+    pub leverage_score: f64,
+    /// Sum of the violations' estimated actual damages.
+    pub actual_damages: f64,
+    /// Range of statutory damages the statute allows for these violations,
+    /// in USD, should liability be established.
+    pub statutory_damages_min: f64,
+    pub statutory_damages_max: f64,
+}
+
+/// Slack allowed between the summed contributions and the score they explain,
+/// for floating-point rounding.
+pub const LEVERAGE_RECONCILIATION_TOLERANCE: f64 = 1e-6;
+
+/// The factors behind a leverage score; their contributions sum to it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LeverageExplanation {
+    pub factors: Vec<Factor>,
+}
+
+impl LeverageExplanation {
+    pub fn total(&self) -> f64 {
+        self.factors.iter().map(|factor| factor.contribution).sum()
+    }
+    
+    /// Whether the contributions add up to `score`, within
+    /// `LEVERAGE_RECONCILIATION_TOLERANCE`.
+    pub fn reconciles_to(&self, score: f64) -> bool {
+        (self.total() - score).abs() <= LEVERAGE_RECONCILIATION_TOLERANCE
+    }
+}
+
+/// One term of a leverage score: `contribution` is `input * weight`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Factor {
+    /// A violation type, or what adjusted the score, e.g. a state statute.
+    pub name: String,
+    pub input: f64,
+    pub weight: f64,
+    pub contribution: f64,
+    /// The violation the term scores, repeats folded into it included; none
+    /// for adjustments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violation_id: Option<Uuid>,
+}
+
+impl Factor {
+    pub fn new(name: impl Into<String>, input: f64, weight: f64) -> Self {
+        Self {
+            name: name.into(),
+            input,
+            weight,
+            contribution: input * weight,
+            violation_id: None,
+        }
+    }
+    
+    pub fn for_violation(self, violation_id: Uuid) -> Self {
+        Self {
+            violation_id: Some(violation_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct OptimalSettlement {
+    #[serde(deserialize_with = "amount::deserialize")]
+    #[schema(value_type = String)]
     pub amount: BigDecimal,
     pub reduction_percentage: f64,
-    pub confidence: f64,
+    /// Absent when AI was disabled: the rules give no confidence of their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
     pub reasoning: Vec<String>,
+    #[serde(default)]
+    pub model_version: String,
+    /// Filled in by `AiClient` from the request it sent, not by the service.
+    #[serde(default)]
+    pub prompt_hash: String,
+    /// Filled in by `AiClient`: the prompt templates it rendered.
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+    /// Currency `amount` is in; absent means the debt's.
+    #[serde(default)]
+    pub currency: Option<Currency>,
+    /// Set by the engine when `amount` was raised to the creditor's settlement
+    /// floor; the leverage alone supported less.
+    #[serde(default)]
+    pub floor_applied: bool,
 }
 
-#[derive(Debug, Deserialize)]
+/// The auto-negotiation trigger that acts on the AI service's advice.
+pub const AI_RECOMMENDATION_TRIGGER: &str = "ai_recommendation";
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AutoNegotiateRequest {
     pub user_id: Uuid,
     pub creditor_id: Uuid,
     pub trigger: String, // "sword_protocol", "manual", "ai_recommendation"
-}
\ No newline at end of file
+    /// The leverage to negotiate with; the debt is the user's active one
+    /// with the creditor.
+    pub violations: Vec<Uuid>,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub strategy: NegotiationStrategy,
+    /// Language the reasoning and recommended action are written in;
+    /// `en-US` by default.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Compute and return the proposal without storing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// See [`CreateSettlementRequest::auto_accept_below`].
+    #[serde(default, deserialize_with = "amount::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub auto_accept_below: Option<BigDecimal>,
+    /// See [`CreateSettlementRequest::ai_mode`].
+    #[serde(default)]
+    pub ai_mode: Option<AiMode>,
+}
+
+impl AutoNegotiateRequest {
+    /// Checks the proposal request it makes, and that an AI recommendation
+    /// isn't acted on with AI disabled.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = self.settlement_request().validate().err().unwrap_or_default();
+        
+        if self.trigger == AI_RECOMMENDATION_TRIGGER && self.ai_mode == Some(AiMode::Disabled) {
+            errors.push(FieldError::new(
+                "ai_mode",
+                "must not be Disabled for an ai_recommendation trigger",
+            ));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// The proposal request auto-negotiation makes on the user's behalf.
+    pub fn settlement_request(&self) -> CreateSettlementRequest {
+        CreateSettlementRequest {
+            user_id: self.user_id,
+            creditor_id: self.creditor_id,
+            debt_id: None,
+            violations: self.violations.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            strategy: self.strategy,
+            locale: self.locale.clone(),
+            fee_policy: None,
+            auto_accept_below: self.auto_accept_below.clone(),
+            ai_mode: self.ai_mode,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+    
+    use super::SettlementStatus::{self, *};
+    use super::{
+        AutoNegotiateRequest, CreateSettlementRequest, FeePolicy, HypotheticalViolation, WhatIfRequest,
+        MAX_WHAT_IF_VIOLATIONS,
+    };
+    
+    const ALL: [SettlementStatus; 10] =
+        [Proposed, Negotiating, Accepted, Rejected, Completed, Failed, Expired, NeedsReview, Escrowed, Refunded];
+    
+    const LEGAL: [(SettlementStatus, SettlementStatus); 17] = [
+        (Proposed, Negotiating),
+        (Proposed, Accepted),
+        (Proposed, Rejected),
+        (Proposed, Expired),
+        (Negotiating, Accepted),
+        (Negotiating, Rejected),
+        (Negotiating, Expired),
+        (Accepted, Proposed),
+        (Accepted, Completed),
+        (Accepted, Failed),
+        (Accepted, Escrowed),
+        (Failed, Accepted),
+        (Failed, Completed),
+        (Failed, Escrowed),
+        (Completed, NeedsReview),
+        (Escrowed, Completed),
+        (Escrowed, Refunded),
+    ];
+    
+    #[test]
+    fn legal_edges_are_allowed() {
+        for (from, to) in LEGAL {
+            assert!(from.can_transition_to(&to), "{:?} -> {:?} should be allowed", from, to);
+        }
+    }
+    
+    #[test]
+    fn every_other_edge_is_rejected() {
+        for from in ALL {
+            for to in ALL {
+                if LEGAL.contains(&(from, to)) {
+                    continue;
+                }
+                assert!(!from.can_transition_to(&to), "{:?} -> {:?} should be illegal", from, to);
+            }
+        }
+    }
+    
+    #[test]
+    fn proposed_cannot_skip_to_completed_or_failed() {
+        assert!(!Proposed.can_transition_to(&Completed));
+        assert!(!Proposed.can_transition_to(&Failed));
+        assert!(!Negotiating.can_transition_to(&Completed));
+    }
+    
+    #[test]
+    fn terminal_states_have_no_outgoing_edges() {
+        for from in [Rejected, Expired, NeedsReview, Refunded] {
+            assert!(ALL.iter().all(|to| !from.can_transition_to(to)), "{:?} is terminal", from);
+        }
+    }
+    
+    #[test]
+    fn self_transitions_are_not_status_changes() {
+        for status in ALL {
+            assert!(!status.can_transition_to(&status));
+        }
+    }
+    
+    fn request() -> CreateSettlementRequest {
+        CreateSettlementRequest {
+            user_id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            debt_id: Some(Uuid::new_v4()),
+            violations: vec![Uuid::new_v4()],
+            jurisdiction: "CA".to_string(),
+            strategy: Default::default(),
+            locale: Default::default(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: None,
+        }
+    }
+    
+    fn invalid_fields(request: &CreateSettlementRequest) -> Vec<(&'static str, String)> {
+        request
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| (e.field, e.message))
+            .collect()
+    }
+    
+    #[test]
+    fn a_debt_or_violations_alone_is_enough() {
+        assert_eq!(CreateSettlementRequest { violations: vec![], ..request() }.validate(), Ok(()));
+        assert_eq!(CreateSettlementRequest { debt_id: None, ..request() }.validate(), Ok(()));
+    }
+    
+    #[test]
+    fn neither_debt_nor_violations_is_rejected() {
+        let request = CreateSettlementRequest { debt_id: None, violations: vec![], ..request() };
+        assert_eq!(
+            invalid_fields(&request),
+            [("violations", "must not be empty when debt_id is omitted".to_string())]
+        );
+    }
+    
+    #[test]
+    fn nil_ids_are_rejected() {
+        let nil = Uuid::nil();
+        for (request, field) in [
+            (CreateSettlementRequest { user_id: nil, ..request() }, "user_id"),
+            (CreateSettlementRequest { creditor_id: nil, ..request() }, "creditor_id"),
+            (CreateSettlementRequest { debt_id: Some(nil), ..request() }, "debt_id"),
+        ] {
+            assert_eq!(invalid_fields(&request), [(field, "must not be the nil UUID".to_string())]);
+        }
+        
+        let request = CreateSettlementRequest { violations: vec![Uuid::new_v4(), nil], ..request() };
+        assert_eq!(
+            invalid_fields(&request),
+            [("violations", "entry 1 must not be the nil UUID".to_string())]
+        );
+    }
+    
+    #[test]
+    fn creditor_must_differ_from_user() {
+        let base = request();
+        let request = CreateSettlementRequest { creditor_id: base.user_id, ..base };
+        assert_eq!(invalid_fields(&request), [("creditor_id", "must differ from user_id".to_string())]);
+    }
+    
+    #[test]
+    fn every_problem_is_reported() {
+        let request = CreateSettlementRequest {
+            user_id: Uuid::nil(),
+            creditor_id: Uuid::nil(),
+            debt_id: None,
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            strategy: Default::default(),
+            locale: Default::default(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: None,
+        };
+        let fields: Vec<_> = invalid_fields(&request).into_iter().map(|(field, _)| field).collect();
+        assert_eq!(fields, ["user_id", "creditor_id", "violations"]);
+    }
+    
+    #[test]
+    fn what_if_bounds_its_hypothetical_violations() {
+        let hypothetical = |violation_type: &str| HypotheticalViolation {
+            violation_type: violation_type.to_string(),
+            statute: Default::default(),
+        };
+        let within = WhatIfRequest {
+            base: request(),
+            hypothetical_violations: vec![hypothetical("FalseRepresentation"); MAX_WHAT_IF_VIOLATIONS],
+        };
+        assert!(within.validate().is_ok());
+        
+        let request = WhatIfRequest {
+            base: CreateSettlementRequest { user_id: Uuid::nil(), ..request() },
+            hypothetical_violations: vec![hypothetical(" "); MAX_WHAT_IF_VIOLATIONS + 1],
+        };
+        let messages: Vec<_> = request.validate().unwrap_err().into_iter().map(|e| e.message).collect();
+        assert_eq!(
+            messages,
+            [
+                "must not be the nil UUID".to_string(),
+                format!("must not have more than {} entries", MAX_WHAT_IF_VIOLATIONS),
+                "entry 0 must have a violation_type".to_string(),
+            ]
+        );
+    }
+    
+    #[test]
+    fn a_reduced_fee_policy_charges_a_share_of_the_fee() {
+        let policy: FeePolicy = serde_json::from_str(r#"{"kind": "reduced", "rate": "0.25"}"#).unwrap();
+        assert_eq!(policy, FeePolicy::Reduced { rate: "0.25".parse().unwrap() });
+        assert!(policy.validate().is_ok());
+        assert_eq!(serde_json::to_value(FeePolicy::Waived).unwrap(), serde_json::json!({ "kind": "waived" }));
+        
+        for rate in ["0", "1", "1.5"] {
+            let request = CreateSettlementRequest {
+                fee_policy: Some(FeePolicy::Reduced { rate: rate.parse().unwrap() }),
+                ..request()
+            };
+            assert_eq!(invalid_fields(&request)[0].0, "fee_policy.rate", "{}", rate);
+        }
+    }
+    
+    #[test]
+    fn ai_recommendations_cannot_be_acted_on_without_ai() {
+        let request = |trigger: &str, ai_mode: &str| -> AutoNegotiateRequest {
+            serde_json::from_value(serde_json::json!({
+                "user_id": Uuid::new_v4(),
+                "creditor_id": Uuid::new_v4(),
+                "trigger": trigger,
+                "violations": [Uuid::new_v4()],
+                "jurisdiction": "CA",
+                "ai_mode": ai_mode,
+            }))
+            .unwrap()
+        };
+        
+        assert!(request("manual", "Disabled").validate().is_ok());
+        assert!(request("ai_recommendation", "Enabled").validate().is_ok());
+        let errors = request("ai_recommendation", "Disabled").validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "ai_mode");
+    }
+}
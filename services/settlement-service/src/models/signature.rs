@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "envelope_status", rename_all = "snake_case")]
+pub enum EnvelopeStatus {
+    Sent,
+    Completed,
+    Declined,
+    Voided,
+}
+
+/// Settlement terms sent to a document-signing provider.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SignatureEnvelope {
+    pub envelope_id: String,
+    pub settlement_id: Uuid,
+    /// The debtor signing; only they can accept with the envelope.
+    pub debtor_id: Uuid,
+    pub provider: String,
+    /// SHA-256 of the acceptance terms the envelope was created from; a
+    /// completed envelope only accepts the settlement while they still match.
+    pub terms_hash: String,
+    pub status: EnvelopeStatus,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SignatureRequest {
+    pub signer_name: String,
+    pub signer_email: String,
+    /// Where the provider sends the user once they have signed.
+    pub return_url: String,
+    /// The debtor signing, when the debt is held jointly; the settlement's
+    /// user unless given. A debtor can only sign for themselves.
+    #[serde(default)]
+    pub debtor_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignatureRequested {
+    #[serde(flatten)]
+    pub envelope: SignatureEnvelope,
+    /// Embedded signing session; short-lived, so it is never stored.
+    pub signing_url: String,
+}
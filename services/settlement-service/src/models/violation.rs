@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+
+use super::{FieldError, Settlement};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Violation {
+    pub id: Uuid,
+    pub creditor_id: Uuid,
+    #[sqlx(rename = "type")]
+    pub violation_type: String,
+    #[serde(default)]
+    pub statute: Statute,
+    pub severity: String, // "low", "medium", "high", "critical"
+    pub confidence: f64,
+    pub legal_reference: String,
+    pub estimated_damage: f64,
+    /// When the conduct happened; limitations periods run from here.
+    pub occurred_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Near-identical violations folded into this one by deduplication.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub repeat_count: u32,
+    /// Artifacts backing the violation, including those of folded repeats.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub evidence: Vec<EvidenceRef>,
+}
+
+/// The law a violation falls under, which decides how it is scored and what
+/// damages it can carry.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::Type, ToSchema,
+)]
+#[sqlx(type_name = "violation_statute", rename_all = "snake_case")]
+pub enum Statute {
+    /// Fair Debt Collection Practices Act.
+    #[default]
+    #[serde(rename = "FDCPA")]
+    Fdcpa,
+    /// Telephone Consumer Protection Act: robocalls and autodialed texts.
+    #[serde(rename = "TCPA")]
+    Tcpa,
+    /// Fair Credit Reporting Act: what was reported to the credit bureaus.
+    #[serde(rename = "FCRA")]
+    Fcra,
+    /// A state law against unfair and deceptive acts and practices.
+    #[serde(rename = "StateUDAP")]
+    StateUdap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "evidence_type", rename_all = "snake_case")]
+pub enum EvidenceType {
+    CallRecording,
+    Voicemail,
+    LetterScan,
+    Screenshot,
+    Other,
+}
+
+/// A stored artifact backing a violation. The file itself is in object
+/// storage under `storage_key`; `sha256` is what the uploader declared.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EvidenceRef {
+    pub id: Uuid,
+    pub violation_id: Uuid,
+    pub evidence_type: EvidenceType,
+    pub sha256: String,
+    pub content_type: String,
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AttachEvidenceRequest {
+    pub evidence_type: EvidenceType,
+    /// Hex SHA-256 of the file about to be uploaded.
+    pub sha256: String,
+    /// MIME type the file will be uploaded with, e.g. `audio/mpeg`.
+    pub content_type: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EvidenceUpload {
+    #[serde(flatten)]
+    pub evidence: EvidenceRef,
+    /// Presigned `PUT` for the file; send it with the declared `content_type`.
+    pub upload_url: String,
+    pub upload_expires_at: DateTime<Utc>,
+}
+
+/// Most rows one bulk import may carry.
+pub const MAX_VIOLATION_IMPORT_ROWS: usize = 500;
+
+/// Violations recorded against a debt's creditor in one go, as when moving
+/// them over from a spreadsheet. Rows are checked one at a time, so a row
+/// that doesn't parse is reported on its own rather than refusing the rest.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkViolationImport {
+    #[schema(value_type = Vec<ViolationRecord>)]
+    pub violations: Vec<serde_json::Value>,
+    /// Recomputes the debt's open settlements with the imported violations
+    /// added to those they cite.
+    #[serde(default)]
+    pub recompute: Option<ImportRecompute>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportRecompute {
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl BulkViolationImport {
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.violations.is_empty() {
+            errors.push(FieldError::new("violations", "must not be empty"));
+        }
+        if self.violations.len() > MAX_VIOLATION_IMPORT_ROWS {
+            errors.push(FieldError::new(
+                "violations",
+                format!("must not have more than {} entries", MAX_VIOLATION_IMPORT_ROWS),
+            ));
+        }
+        if let Some(recompute) = &self.recompute {
+            if recompute.jurisdiction.trim().is_empty() {
+                errors.push(FieldError::new("recompute.jurisdiction", "must not be empty"));
+            }
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// One row of a bulk import.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ViolationRecord {
+    pub violation_type: String,
+    #[serde(default)]
+    pub statute: Statute,
+    /// `low`, `medium`, `high` or `critical`.
+    pub severity: String,
+    /// Between 0 and 1.
+    pub confidence: f64,
+    pub legal_reference: String,
+    pub estimated_damage: f64,
+    pub occurred_at: DateTime<Utc>,
+    /// Files backing the violation, each uploaded to the URL its row returns.
+    #[serde(default)]
+    pub evidence: Vec<AttachEvidenceRequest>,
+}
+
+impl ViolationRecord {
+    /// Checks everything but the evidence, which is checked as it would be
+    /// attached one file at a time.
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if self.violation_type.trim().is_empty() {
+            errors.push(FieldError::new("violation_type", "must not be empty"));
+        }
+        if !["low", "medium", "high", "critical"].contains(&self.severity.as_str()) {
+            errors.push(FieldError::new("severity", "must be low, medium, high or critical"));
+        }
+        if !(0.0..=1.0).contains(&self.confidence) {
+            errors.push(FieldError::new("confidence", "must be between 0 and 1"));
+        }
+        if self.legal_reference.trim().is_empty() {
+            errors.push(FieldError::new("legal_reference", "must not be empty"));
+        }
+        if !self.estimated_damage.is_finite() || self.estimated_damage < 0.0 {
+            errors.push(FieldError::new("estimated_damage", "must not be negative"));
+        }
+        if self.occurred_at > now {
+            errors.push(FieldError::new("occurred_at", "must not be in the future"));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A row imported, with where to upload the files it declared, in order.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportedViolation {
+    pub violation_id: Uuid,
+    pub evidence: Vec<EvidenceUpload>,
+}
+
+/// How a violation a proposal cited figured in its leverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "violation_use", rename_all = "snake_case")]
+pub enum ViolationUse {
+    /// Scored in its own right.
+    Counted,
+    /// Folded into an earlier violation of the same type as one of its
+    /// repeats; that violation's contribution includes it.
+    Repeat,
+    /// Left out because its statute's limitations period had run.
+    Expired,
+}
+
+/// A violation a settlement's proposal cited, and what it contributed to the
+/// proposal's leverage score.
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow, ToSchema)]
+pub struct CitedViolation {
+    pub violation_id: Uuid,
+    pub violation_use: ViolationUse,
+    pub contribution: f64,
+}
+
+/// A settlement that relied on a violation.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ViolationSettlement {
+    #[sqlx(flatten)]
+    pub settlement: Settlement,
+    pub violation_use: ViolationUse,
+    pub contribution: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use serde_json::json;
+    
+    use super::*;
+    
+    fn record(row: serde_json::Value) -> ViolationRecord {
+        serde_json::from_value(row).unwrap()
+    }
+    
+    #[test]
+    fn import_rows_are_checked_one_at_a_time() {
+        let now = Utc::now();
+        let good = json!({
+            "violation_type": "FalseRepresentation",
+            "severity": "high",
+            "confidence": 0.9,
+            "legal_reference": "15 U.S.C. 1692e",
+            "estimated_damage": 500.0,
+            "occurred_at": now - Duration::days(30),
+        });
+        assert_eq!(record(good.clone()).validate(now), Ok(()));
+        
+        let mut bad = good.clone();
+        bad["severity"] = json!("urgent");
+        bad["confidence"] = json!(1.5);
+        bad["occurred_at"] = json!(now + Duration::days(1));
+        let fields: Vec<_> = record(bad).validate(now).unwrap_err().iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["severity", "confidence", "occurred_at"]);
+        
+        let empty = BulkViolationImport {
+            violations: Vec::new(),
+            recompute: Some(ImportRecompute {
+                jurisdiction: " ".to_string(),
+                locale: Locale::default(),
+            }),
+        };
+        let fields: Vec<_> = empty.validate().unwrap_err().iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["violations", "recompute.jurisdiction"]);
+        let too_many = BulkViolationImport {
+            violations: vec![good; MAX_VIOLATION_IMPORT_ROWS + 1],
+            recompute: None,
+        };
+        assert!(too_many.validate().is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use super::SettlementStatus;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub creditor_id: Uuid,
+    pub url: String,
+    /// HMAC key for the signature header; only ever returned when the
+    /// webhook is first registered.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<SettlementStatus>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    pub creditor_id: Uuid,
+    pub url: String,
+    pub events: Vec<SettlementStatus>,
+}
+
+/// Response to a registration: the new webhook, or the creditor's existing
+/// one for the URL, now subscribed to the requested events as well.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    /// Only for a new webhook; registering again doesn't return it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// False when the creditor had already registered the URL.
+    pub created: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct ListWebhooksQuery {
+    pub creditor_id: Uuid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub settlement_id: Uuid,
+    pub settlement_status: SettlementStatus,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A delivery that failed every attempt, kept with what was sent so an
+/// admin can replay it once the target is back.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDeadLetter {
+    pub id: Uuid,
+    pub delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub settlement_id: Uuid,
+    /// Where the delivery was posted.
+    pub url: String,
+    /// The body as first sent; a redelivery sends it again, freshly signed.
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    /// Every attempt so far, manual redeliveries included.
+    pub attempts: i32,
+    pub last_error: String,
+    pub created_at: DateTime<Utc>,
+    /// When a redelivery succeeded; until then the dead letter is open.
+    pub redelivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct ListDeadLettersQuery {
+    /// Also list dead letters already redelivered.
+    #[serde(default)]
+    pub include_redelivered: bool,
+    pub limit: Option<u32>,
+}
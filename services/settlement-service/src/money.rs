@@ -0,0 +1,408 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most decimal places an amount may have; no currency comes close, so more
+/// means garbage, not precision.
+pub const MAX_AMOUNT_SCALE: usize = 12;
+
+/// Most digits an amount may have before the decimal point, a quintillion
+/// less one.
+pub const MAX_AMOUNT_DIGITS: usize = 18;
+
+/// An ISO 4217 currency code, e.g. `USD`. Amounts carry no currency of their
+/// own; each settlement and debt records the one its amounts are in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+#[sqlx(transparent)]
+#[schema(value_type = String, example = "USD")]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn usd() -> Self {
+        Currency("USD".to_string())
+    }
+    
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+    
+    /// Decimal places of the currency's minor unit (ISO 4217 "exponent").
+    pub fn minor_units(&self) -> i64 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+            | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            "CLF" | "UYW" => 4,
+            _ => 2,
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::usd()
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+    
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        if code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase()) {
+            Ok(Currency(code))
+        } else {
+            Err(format!("{:?} is not an ISO 4217 currency code", code))
+        }
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.0
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Why a string is not an amount. Each variant carries the offending input,
+/// cut short when it is long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    /// Not digits with an optional fractional part, e.g. `0.1.2` or `.5`.
+    Malformed(String),
+    /// Exponent notation, e.g. `1e3`, which hides how large an amount is.
+    ScientificNotation(String),
+    Negative(String),
+    /// More than [`MAX_AMOUNT_SCALE`] decimal places.
+    TooPrecise(String),
+    /// More than [`MAX_AMOUNT_DIGITS`] digits before the decimal point.
+    TooLarge(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::Malformed(input) => write!(f, "{:?} is not a decimal amount", input),
+            MoneyError::ScientificNotation(input) => {
+                write!(f, "{:?} uses scientific notation; write the amount out in full", input)
+            }
+            MoneyError::Negative(input) => write!(f, "{:?} is negative", input),
+            MoneyError::TooPrecise(input) => {
+                write!(f, "{:?} has more than {} decimal places", input, MAX_AMOUNT_SCALE)
+            }
+            MoneyError::TooLarge(input) => {
+                write!(f, "{:?} has more than {} digits before the decimal point", input, MAX_AMOUNT_DIGITS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+/// Parses an amount written as a plain, non-negative decimal: digits, then
+/// optionally a point and more digits, with nothing around them. Anything
+/// else is refused rather than read some other way, so what is stored is
+/// exactly what was sent.
+pub fn parse_amount(input: &str) -> Result<BigDecimal, MoneyError> {
+    let shown = || shorten(input);
+    if input.contains(['e', 'E']) {
+        return Err(MoneyError::ScientificNotation(shown()));
+    }
+    let unsigned = input.strip_prefix('-').unwrap_or(input);
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !digits(whole) || !fraction.is_none_or(digits) {
+        return Err(MoneyError::Malformed(shown()));
+    }
+    if input.starts_with('-') {
+        return Err(MoneyError::Negative(shown()));
+    }
+    if fraction.map_or(0, str::len) > MAX_AMOUNT_SCALE {
+        return Err(MoneyError::TooPrecise(shown()));
+    }
+    if whole.trim_start_matches('0').len() > MAX_AMOUNT_DIGITS {
+        return Err(MoneyError::TooLarge(shown()));
+    }
+    
+    BigDecimal::from_str(input).map_err(|_| MoneyError::Malformed(shown()))
+}
+
+/// The first 32 characters of `input`, enough to recognise it in an error.
+fn shorten(input: &str) -> String {
+    match input.char_indices().nth(32) {
+        Some((end, _)) => format!("{}...", &input[..end]),
+        None => input.to_string(),
+    }
+}
+
+/// `deserialize_with` functions that read amounts through [`parse_amount`].
+/// Amounts are sent as strings; a JSON integer is exact and accepted too,
+/// but a JSON float may have lost precision before it arrived and is not.
+pub mod amount {
+    use std::fmt;
+    
+    use bigdecimal::BigDecimal;
+    use serde::de::{self, Deserializer, Visitor};
+    use serde::Deserialize;
+    
+    use super::{parse_amount, MoneyError};
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigDecimal, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+    
+    /// For optional amounts; pair it with `#[serde(default)]` so a missing
+    /// field is still `None`.
+    pub fn deserialize_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<BigDecimal>, D::Error> {
+        Ok(Option::<Amount>::deserialize(deserializer)?.map(|amount| amount.0))
+    }
+    
+    struct Amount(BigDecimal);
+    
+    impl<'de> Deserialize<'de> for Amount {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserialize(deserializer).map(Amount)
+        }
+    }
+    
+    struct AmountVisitor;
+    
+    impl Visitor<'_> for AmountVisitor {
+        type Value = BigDecimal;
+        
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal amount as a string")
+        }
+        
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<BigDecimal, E> {
+            parse_amount(value).map_err(E::custom)
+        }
+        
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<BigDecimal, E> {
+            Ok(BigDecimal::from(value))
+        }
+        
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<BigDecimal, E> {
+            if value < 0 {
+                return Err(E::custom(MoneyError::Negative(value.to_string())));
+            }
+            Ok(BigDecimal::from(value))
+        }
+        
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<BigDecimal, E> {
+            Err(E::custom(format!(
+                "the amount {} must be sent as a string to keep its precision",
+                value
+            )))
+        }
+    }
+}
+
+/// Rounds to the currency's minor unit with banker's rounding; see
+/// `round_half_even`.
+pub fn round_to_minor_units(amount: BigDecimal, currency: &Currency) -> BigDecimal {
+    round_half_even(amount, currency.minor_units())
+}
+
+/// Rounds a non-negative amount up to the currency's minor unit, for minimums
+/// that rounding must never take an amount below.
+pub fn round_up_to_minor_units(amount: BigDecimal, currency: &Currency) -> BigDecimal {
+    let truncated = amount.with_scale(currency.minor_units());
+    if truncated < amount {
+        truncated + BigDecimal::new(1.into(), currency.minor_units())
+    } else {
+        truncated
+    }
+}
+
+/// Rounds to `scale` decimal places with banker's rounding (round half to
+/// even), so repeated rounding of half-unit amounts doesn't drift in one
+/// direction.
+fn round_half_even(amount: BigDecimal, scale: i64) -> BigDecimal {
+    let unit = BigDecimal::new(1.into(), scale);
+    let half_unit = BigDecimal::new(5.into(), scale + 1);
+    
+    // `with_scale` truncates toward zero; the remainder decides the last unit.
+    let truncated = amount.with_scale(scale);
+    let remainder = (&amount - &truncated).abs();
+    
+    let round_away = if remainder > half_unit {
+        true
+    } else if remainder == half_unit {
+        !((&truncated / &unit) % BigDecimal::from(2)).is_zero()
+    } else {
+        false
+    };
+    
+    match (round_away, amount.is_negative()) {
+        (false, _) => truncated,
+        (true, false) => truncated + unit,
+        (true, true) => truncated - unit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    
+    use super::*;
+    
+    fn cents(value: &str) -> String {
+        round_to_minor_units(BigDecimal::from_str(value).unwrap(), &Currency::usd()).to_string()
+    }
+    
+    #[test]
+    fn rounds_half_cents_to_even() {
+        assert_eq!(cents("0.125"), "0.12");
+        assert_eq!(cents("0.135"), "0.14");
+        assert_eq!(cents("0.145"), "0.14");
+        assert_eq!(cents("2.675"), "2.68");
+        assert_eq!(cents("1.005"), "1.00");
+    }
+    
+    #[test]
+    fn rounds_negative_half_cents_to_even() {
+        assert_eq!(cents("-0.125"), "-0.12");
+        assert_eq!(cents("-0.135"), "-0.14");
+    }
+    
+    #[test]
+    fn anything_past_half_rounds_away_from_zero() {
+        assert_eq!(cents("0.1250001"), "0.13");
+        assert_eq!(cents("1234.56789"), "1234.57");
+        assert_eq!(cents("-1234.56789"), "-1234.57");
+    }
+    
+    #[test]
+    fn anything_below_half_rounds_toward_zero() {
+        assert_eq!(cents("0.1249999"), "0.12");
+        assert_eq!(cents("0.004"), "0.00");
+    }
+    
+    #[test]
+    fn whole_and_cent_amounts_are_unchanged_but_get_two_decimals() {
+        assert_eq!(cents("100"), "100.00");
+        assert_eq!(cents("99.9"), "99.90");
+        assert_eq!(cents("42.42"), "42.42");
+    }
+    
+    fn in_currency(value: &str, code: &str) -> String {
+        let currency = Currency::try_from(code.to_string()).unwrap();
+        round_to_minor_units(BigDecimal::from_str(value).unwrap(), &currency).to_string()
+    }
+    
+    #[test]
+    fn rounding_up_never_lands_below_the_amount() {
+        let up = |value: &str| round_up_to_minor_units(BigDecimal::from_str(value).unwrap(), &Currency::usd()).to_string();
+        
+        assert_eq!(up("600.001"), "600.01");
+        assert_eq!(up("600.00"), "600.00");
+        assert_eq!(up("600"), "600.00");
+    }
+    
+    #[test]
+    fn rounding_follows_the_currency_minor_unit() {
+        assert_eq!(in_currency("1234.5", "JPY"), "1234");
+        assert_eq!(in_currency("1235.5", "JPY"), "1236");
+        assert_eq!(in_currency("1.0005", "KWD"), "1.000");
+        assert_eq!(in_currency("1.0015", "KWD"), "1.002");
+        assert_eq!(in_currency("2.675", "EUR"), "2.68");
+    }
+    
+    #[test]
+    fn plain_decimals_parse_exactly() {
+        assert_eq!(parse_amount("600").unwrap(), BigDecimal::from(600));
+        assert_eq!(parse_amount("600.10").unwrap().to_string(), "600.10");
+        assert_eq!(parse_amount("0.000000000001").unwrap().to_string(), "0.000000000001");
+        assert_eq!(parse_amount("000123.5").unwrap().to_string(), "123.5");
+        assert!(parse_amount("999999999999999999.99").is_ok());
+    }
+    
+    #[test]
+    fn malformed_amounts_are_refused() {
+        for input in ["", "0.1.2", ".5", "5.", "1,000", " 600", "600 ", "+5", "--5", "0x10", "NaN", "inf", "١٢", "5-"] {
+            assert_eq!(parse_amount(input), Err(MoneyError::Malformed(input.to_string())), "{:?}", input);
+        }
+        for input in ["1e3", "1E-2", "-1e3"] {
+            assert!(matches!(parse_amount(input), Err(MoneyError::ScientificNotation(_))), "{:?}", input);
+        }
+        assert_eq!(parse_amount("-5.00"), Err(MoneyError::Negative("-5.00".to_string())));
+        assert!(matches!(parse_amount("0.0000000000001"), Err(MoneyError::TooPrecise(_))));
+        assert!(matches!(parse_amount(&"9".repeat(19)), Err(MoneyError::TooLarge(_))));
+        
+        let huge = "1".repeat(10_000);
+        assert_eq!(
+            parse_amount(&huge).unwrap_err().to_string(),
+            format!("\"{}...\" has more than 18 digits before the decimal point", "1".repeat(32))
+        );
+    }
+    
+    #[derive(Debug, serde::Deserialize)]
+    struct Amounts {
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: BigDecimal,
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        optional: Option<BigDecimal>,
+    }
+    
+    #[test]
+    fn amounts_deserialize_from_strings_and_integers_only() {
+        let parsed: Amounts = serde_json::from_str(r#"{"amount": "600.10", "optional": 5}"#).unwrap();
+        assert_eq!(parsed.amount.to_string(), "600.10");
+        assert_eq!(parsed.optional, Some(BigDecimal::from(5)));
+        let parsed: Amounts = serde_json::from_str(r#"{"amount": 600, "optional": null}"#).unwrap();
+        assert_eq!(parsed.optional, None);
+        assert_eq!(serde_json::from_str::<Amounts>(r#"{"amount": 600}"#).unwrap().optional, None);
+        
+        for body in [
+            r#"{"amount": "1e3"}"#,
+            r#"{"amount": 600.1}"#,
+            r#"{"amount": -5}"#,
+            r#"{"amount": "0.1.2"}"#,
+            r#"{"amount": true}"#,
+            r#"{"amount": "600", "optional": "-1"}"#,
+        ] {
+            assert!(serde_json::from_str::<Amounts>(body).is_err(), "{}", body);
+        }
+    }
+    
+    /// Random strings over the characters amounts are made of, and a few
+    /// they are not: nothing may panic, and whatever parses must print back
+    /// as the same number.
+    #[test]
+    fn random_inputs_never_panic_and_round_trip() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+        
+        const ALPHABET: &[char] = &['0', '1', '5', '9', '.', '-', '+', 'e', 'E', ' ', ',', '_', 'x', '\u{661}'];
+        let mut rng = StdRng::seed_from_u64(75);
+        for _ in 0..20_000 {
+            let len = rng.gen_range(0..40);
+            let input: String = (0..len).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())]).collect();
+            if let Ok(amount) = parse_amount(&input) {
+                assert_eq!(parse_amount(&amount.to_string()), Ok(amount.clone()), "{:?}", input);
+                assert!(!amount.is_negative(), "{:?}", input);
+            }
+        }
+    }
+    
+    #[test]
+    fn currency_codes_must_be_three_uppercase_letters() {
+        assert!(Currency::try_from("USD".to_string()).is_ok());
+        assert!(Currency::try_from("usd".to_string()).is_err());
+        assert!(Currency::try_from("US".to_string()).is_err());
+        assert!(serde_json::from_str::<Currency>("\"EURO\"").is_err());
+        assert_eq!(serde_json::to_string(&Currency::usd()).unwrap(), "\"USD\"");
+    }
+}
@@ -0,0 +1,330 @@
+use utoipa::OpenApi;
+
+use crate::blockchain::CircuitState;
+use crate::handlers::{
+    self,
+    debts::{ImportRowResult, RecomputeResult, ViolationImportResults},
+    error::{ErrorBody, ErrorDetail},
+    leverage::{BatchItemResult, BatchLeverageResults},
+};
+use crate::i18n::Locale;
+use crate::models::*;
+use crate::money::Currency;
+
+/// The OpenAPI 3 description of the `/api/v1` endpoints, served at
+/// `/api-docs/openapi.json` and browsable through Swagger UI at `/swagger/`.
+/// A handler or model added to the API must be listed here as well.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "DAMOCLES Settlement Service",
+        description = "Leverage-backed debt settlement: proposals, negotiation, e-signature and Cardano execution."
+    ),
+    paths(
+        handlers::health::health_check,
+        handlers::settlements::create_settlement_proposal,
+        handlers::settlements::create_bundled_settlement,
+        handlers::settlements::create_creditor_proposal,
+        handlers::settlements::list_settlements,
+        handlers::settlements::get_settlement,
+        handlers::settlements::get_settlement_by_reference,
+        handlers::settlements::delete_settlement,
+        handlers::settlements::accept_settlement,
+        handlers::settlements::get_settlement_acceptances,
+        handlers::settlements::request_signature,
+        handlers::settlements::signature_callback,
+        handlers::settlements::reject_settlement,
+        handlers::settlements::rescind_settlement,
+        handlers::settlements::execute_settlement,
+        handlers::settlements::execute_batch,
+        handlers::settlements::retry_settlement,
+        handlers::settlements::release_escrow,
+        handlers::settlements::refund_escrow,
+        handlers::settlements::estimate_settlement_fee,
+        handlers::settlements::what_if,
+        handlers::settlements::predict_acceptance,
+        handlers::settlements::recompute_settlement,
+        handlers::settlements::auto_negotiate,
+        handlers::settlements::create_installment_plan,
+        handlers::settlements::get_installments,
+        handlers::settlements::get_settlement_debts,
+        handlers::settlements::pay_installment,
+        handlers::settlements::counter_offer,
+        handlers::settlements::get_negotiation_rounds,
+        handlers::settlements::get_settlement_events,
+        handlers::settlements::stream_settlement_events,
+        handlers::settlements::verify_settlement_metadata,
+        handlers::settlements::get_terms_hash,
+        handlers::settlements::get_transaction_status,
+        handlers::settlements::get_settlement_agreement,
+        handlers::settlements::get_demand_letter,
+        handlers::settlements::get_demand_letter_pdf,
+        handlers::creditors::set_creditor_contact,
+        handlers::creditors::get_creditor_contact,
+        handlers::creditors::set_settlement_floor,
+        handlers::creditors::set_execution_mode,
+        handlers::creditors::set_creditor_terms,
+        handlers::creditors::get_creditor_terms,
+        handlers::users::savings_summary,
+        handlers::debts::record_debt_verification,
+        handlers::debts::import_violations,
+        handlers::violations::attach_evidence,
+        handlers::violations::get_violation_settlements,
+        handlers::triggers::sword_trigger,
+        handlers::triggers::email_trigger,
+        handlers::drafts::create_draft,
+        handlers::drafts::list_drafts,
+        handlers::drafts::get_draft,
+        handlers::drafts::update_draft,
+        handlers::drafts::finalize_draft,
+        handlers::webhooks::register_webhook,
+        handlers::webhooks::list_webhooks,
+        handlers::webhooks::delete_webhook,
+        handlers::admin::force_settlement_status,
+        handlers::admin::reconciliation_report,
+        handlers::admin::set_user_fee_policy,
+        handlers::admin::get_user_fee_policy,
+        handlers::admin::list_webhook_dead_letters,
+        handlers::admin::redeliver_webhook_dead_letter,
+        handlers::admin::list_creditor_emails,
+        handlers::leverage::calculate_leverage_score,
+        handlers::leverage::batch_leverage_scores,
+        handlers::leverage::leverage_leaderboard,
+        handlers::leverage::get_leverage_history,
+        handlers::leverage::get_creditor_profile,
+    ),
+    components(schemas(
+        ErrorBody,
+        ErrorDetail,
+        FieldError,
+        Currency,
+        Locale,
+        Settlement,
+        SettlementStatus,
+        ExecutionMode,
+        RejectReason,
+        NegotiationStrategy,
+        CreateSettlementRequest,
+        CreditorProposalRequest,
+        BundleSettlementRequest,
+        BundledDebt,
+        SettlementDebts,
+        AutoNegotiateRequest,
+        WhatIfRequest,
+        HypotheticalViolation,
+        WhatIfProjection,
+        AcceptancePredictionRequest,
+        AcceptancePrediction,
+        RecomputeSettlementRequest,
+        RecomputedProposal,
+        ProposalChanges,
+        AmountChange,
+        OptimalSettlement,
+        SettlementProposal,
+        ProposalSource,
+        AiMode,
+        Simulation,
+        FeeBreakdown,
+        FeePolicy,
+        UserFeePolicy,
+        LeverageAnalysis,
+        LeverageExplanation,
+        Factor,
+        StatuteExposure,
+        Statute,
+        SolStatus,
+        PaginatedSettlements,
+        SavingsSummary,
+        AcceptSettlementRequest,
+        SettlementAcceptances,
+        DebtorAcceptance,
+        RejectSettlementRequest,
+        ForceStatusRequest,
+        ReconciliationReport,
+        ReconciliationRun,
+        Discrepancy,
+        ReviewFlag,
+        ChainState,
+        SignatureRequest,
+        SignatureRequested,
+        SignatureEnvelope,
+        EnvelopeStatus,
+        FeeEstimate,
+        MetadataVerification,
+        CanonicalTerms,
+        ExecuteBatchRequest,
+        BatchExecution,
+        TransactionStatus,
+        TransactionState,
+        InstallmentPlan,
+        Installment,
+        InstallmentSchedule,
+        InstallmentStatus,
+        Cadence,
+        CounterOffer,
+        CounterOfferResponse,
+        NegotiationDecision,
+        NegotiationAction,
+        NegotiationRound,
+        Party,
+        AuditEvent,
+        AuditEventType,
+        CreditorContact,
+        CreditorContactRequest,
+        ContactChannel,
+        SettlementFloor,
+        CreditorExecutionMode,
+        CreditorTerms,
+        CreditorTermsRequest,
+        AgreementClause,
+        ClauseTemplate,
+        Debt,
+        DebtVerificationRequest,
+        VerificationStatus,
+        AttachEvidenceRequest,
+        EvidenceUpload,
+        BulkViolationImport,
+        ViolationRecord,
+        ImportRecompute,
+        ImportedViolation,
+        ViolationImportResults,
+        ImportRowResult,
+        RecomputeResult,
+        EvidenceRef,
+        EvidenceType,
+        ViolationSettlement,
+        ViolationUse,
+        SwordEvent,
+        EnqueuedJob,
+        AutoNegotiationJob,
+        AutoNegotiationStatus,
+        InboundEmail,
+        ReceivedEmail,
+        CreditorEmail,
+        CreditorEmailStatus,
+        ReplyIntent,
+        CreateDraftRequest,
+        UpdateDraftRequest,
+        SettlementDraft,
+        DraftKind,
+        RegisterWebhookRequest,
+        RegisteredWebhook,
+        Webhook,
+        WebhookDeadLetter,
+        LeverageRequest,
+        BatchLeverageRequest,
+        BatchLeverageResults,
+        BatchItemResult,
+        LeverageSnapshot,
+        LeverageLeaderboard,
+        LeaderboardEntry,
+        CreditorProfile,
+        HealthReport,
+        DependencyCheck,
+        Overall,
+        CheckStatus,
+        CircuitState,
+    )),
+    tags(
+        (name = "settlements", description = "Settlement proposals, negotiation, acceptance and on-chain execution, for the parties to each settlement behind a bearer token"),
+        (name = "leverage", description = "Leverage scoring from documented creditor violations"),
+        (name = "creditors", description = "Creditor contact details, settlement terms and how settlements are paid"),
+        (name = "users", description = "What users have saved across their settlements"),
+        (name = "debts", description = "Debt validation under FDCPA §809, and bulk violation imports against a debt"),
+        (name = "violations", description = "Evidence backing documented creditor violations, and the settlements citing them"),
+        (name = "triggers", description = "Events from other DAMOCLES services that start auto-negotiation, and creditors' email replies"),
+        (name = "drafts", description = "Proposals saved part-way through, to be finished and finalized later"),
+        (name = "webhooks", description = "Creditor notifications of settlement status changes"),
+        (name = "admin", description = "Support overrides, behind an admin bearer token"),
+        (name = "health", description = "Readiness"),
+    )
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    
+    use super::*;
+    
+    fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    found.push(reference);
+                }
+                map.values().for_each(|v| refs(v, found));
+            }
+            Value::Array(items) => items.iter().for_each(|v| refs(v, found)),
+            _ => {}
+        }
+    }
+    
+    #[test]
+    fn every_referenced_schema_is_registered() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = &spec["components"]["schemas"];
+        
+        let mut found = Vec::new();
+        refs(&spec, &mut found);
+        assert!(!found.is_empty());
+        for reference in found {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            assert!(schemas.get(name).is_some(), "{} is referenced but not registered", reference);
+        }
+    }
+    
+    #[test]
+    fn routes_are_documented_under_their_scopes() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = &spec["paths"];
+        
+        for (path, method) in [
+            ("/api/v1/settlements", "post"),
+            ("/api/v1/settlements/{id}", "get"),
+            ("/api/v1/settlements/by-ref/{reference}", "get"),
+            ("/api/v1/settlements/{id}/accept", "post"),
+            ("/api/v1/settlements/{id}/acceptances", "get"),
+            ("/api/v1/settlements/{id}/agreement.pdf", "get"),
+            ("/api/v1/settlements/{id}/demand-letter", "get"),
+            ("/api/v1/settlements/{id}/demand-letter.pdf", "get"),
+            ("/api/v1/settlements/{id}/transaction", "get"),
+            ("/api/v1/settlements/{id}/stream", "get"),
+            ("/api/v1/settlements/{id}/terms-hash", "get"),
+            ("/api/v1/settlements/what-if", "post"),
+            ("/api/v1/settlements/bundle", "post"),
+            ("/api/v1/settlements/execute-batch", "post"),
+            ("/api/v1/settlements/{id}/release", "post"),
+            ("/api/v1/settlements/{id}/refund", "post"),
+            ("/api/v1/settlements/{id}/installments/{n}/pay", "post"),
+            ("/api/v1/leverage/score", "post"),
+            ("/api/v1/leverage/leaderboard", "get"),
+            ("/api/v1/creditors/{id}/contact", "put"),
+            ("/api/v1/creditors/{id}/terms", "put"),
+            ("/api/v1/creditors/{id}/execution-mode", "put"),
+            ("/api/v1/debts/{id}/verification", "post"),
+            ("/api/v1/debts/{id}/violations/bulk", "post"),
+            ("/api/v1/violations/{id}/evidence", "post"),
+            ("/api/v1/violations/{id}/settlements", "get"),
+            ("/api/v1/triggers/sword", "post"),
+            ("/api/v1/triggers/email", "post"),
+            ("/api/v1/drafts", "post"),
+            ("/api/v1/drafts", "get"),
+            ("/api/v1/drafts/{id}", "get"),
+            ("/api/v1/drafts/{id}", "patch"),
+            ("/api/v1/drafts/{id}/finalize", "post"),
+            ("/api/v1/webhooks", "post"),
+            ("/api/v1/webhooks", "get"),
+            ("/api/v1/webhooks/{id}", "delete"),
+            ("/api/v1/admin/settlements/{id}/force-status", "post"),
+            ("/api/v1/admin/reconciliation/report", "get"),
+            ("/api/v1/admin/users/{id}/fee-policy", "put"),
+            ("/api/v1/admin/webhooks/dead-letters", "get"),
+            ("/api/v1/admin/webhooks/dead-letters/{id}/redeliver", "post"),
+            ("/api/v1/admin/creditor-emails", "get"),
+            ("/api/v1/health", "get"),
+        ] {
+            assert!(paths[path].get(method).is_some(), "{} {} is not documented", method, path);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use minijinja::Environment;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::{AgreementClause, FeeBreakdown, FeePolicy, LeverageAnalysis, Settlement};
+use crate::services::pdf;
+
+const TEMPLATE: &str = include_str!("../../templates/settlement_agreement.txt");
+
+/// Settlements whose rendered agreement is kept; past this the cache starts
+/// over rather than tracking recency.
+const MAX_CACHED_AGREEMENTS: usize = 1_000;
+
+/// Everything the agreement states besides the settlement row itself.
+pub struct AgreementTerms<'a> {
+    pub settlement: &'a Settlement,
+    pub creditor_id: Uuid,
+    pub fees: &'a FeeBreakdown,
+    /// The analysis the proposal was made with, if one was snapshotted.
+    pub leverage: Option<&'a LeverageAnalysis>,
+    /// The creditor's clauses: the versions agreed once the settlement is
+    /// accepted, the ones in force until then.
+    pub clauses: &'a [AgreementClause],
+}
+
+/// Fills the agreement template. Lines starting `# ` and `## ` are the title
+/// and section headings; [`pdf::render_text`] lays the rest out as body text.
+/// Each clause body is filled with the same values as the template first.
+pub fn agreement_text(terms: &AgreementTerms) -> anyhow::Result<String> {
+    let settlement = terms.settlement;
+    let minor_units = settlement.currency.minor_units();
+    let amount = |value: &bigdecimal::BigDecimal| value.with_scale(minor_units).to_string();
+    
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.add_template("settlement_agreement", TEMPLATE)?;
+    
+    let mut context = json!({
+        "reference_number": settlement.reference_number.as_deref().unwrap_or("not yet assigned"),
+        "settlement_id": settlement.id,
+        "proposed_at": settlement.proposed_at.format("%Y-%m-%d").to_string(),
+        "user_id": settlement.user_id,
+        "creditor_id": terms.creditor_id,
+        "debt_id": settlement.debt_id,
+        "currency": settlement.currency,
+        "original_amount": amount(&settlement.original_amount),
+        "settled_amount": amount(&settlement.settled_amount),
+        "saved_amount": amount(&settlement.saved_amount),
+        "platform_fee": amount(&settlement.platform_fee),
+        "contract_address": settlement.smart_contract_address,
+        "transaction_hash": settlement.transaction_hash,
+        "leverage": terms.leverage.map(|leverage| json!({
+            "violation_count": leverage.violation_count,
+            "legal_strength": leverage.legal_strength,
+            "estimated_reduction_percentage": format!("{:.1}", leverage.estimated_reduction_percentage),
+            "key_violations": leverage.key_violations,
+        })),
+        "fees": {
+            "base_fee": amount(&terms.fees.base_fee),
+            "success_fee": amount(&terms.fees.success_fee),
+            "success_fee_percent": format!("{:.0}", terms.fees.success_fee_rate * 100.0),
+            "blockchain_cost": amount(&terms.fees.blockchain_cost),
+            "cap_applied": terms.fees.cap_applied,
+            "cap_discount": amount(&terms.fees.cap_discount),
+            "policy": match &terms.fees.fee_policy {
+                FeePolicy::Standard => "standard",
+                FeePolicy::Waived => "waived",
+                FeePolicy::Reduced { .. } => "reduced",
+            },
+            "policy_percent": match &terms.fees.fee_policy {
+                FeePolicy::Reduced { rate } => (rate * bigdecimal::BigDecimal::from(100)).normalized().to_string(),
+                _ => String::new(),
+            },
+            "policy_discount": amount(&terms.fees.policy_discount),
+        },
+    });
+    let clauses = terms
+        .clauses
+        .iter()
+        .map(|clause| {
+            Ok(json!({
+                "key": clause.key,
+                "version": clause.version,
+                "title": clause.title,
+                "body": env.render_str(&clause.body, &context)?,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    context["clauses"] = json!(clauses);
+    
+    let text = env.get_template("settlement_agreement")?.render(&context)?;
+    
+    Ok(text)
+}
+
+/// Renders the agreement to a PDF.
+pub fn render_agreement(terms: &AgreementTerms) -> anyhow::Result<Vec<u8>> {
+    let text = agreement_text(terms)?;
+    let title = format!(
+        "Settlement agreement {}",
+        terms.settlement.reference_number.as_deref().unwrap_or_default()
+    );
+    
+    pdf::render_text(title, &text)
+}
+
+/// Rendered agreements, one per settlement, tagged with the settlement
+/// version and the hash of the clauses they were rendered from. Any update
+/// bumps the version and rewording a clause changes the hash, so a stale
+/// document is never served.
+#[derive(Clone, Default)]
+pub struct AgreementCache {
+    entries: Arc<DashMap<Uuid, CachedAgreement>>,
+}
+
+struct CachedAgreement {
+    version: i32,
+    clauses_hash: Option<String>,
+    pdf: Arc<Vec<u8>>,
+}
+
+impl AgreementCache {
+    pub fn get(&self, settlement_id: Uuid, version: i32, clauses_hash: Option<&str>) -> Option<Arc<Vec<u8>>> {
+        let entry = self.entries.get(&settlement_id)?;
+        (entry.version == version && entry.clauses_hash.as_deref() == clauses_hash).then(|| entry.pdf.clone())
+    }
+    
+    /// Caches `pdf` as the agreement of `version` with the clauses hashing
+    /// to `clauses_hash`, unless a newer version is already cached.
+    pub fn insert(
+        &self,
+        settlement_id: Uuid,
+        version: i32,
+        clauses_hash: Option<String>,
+        pdf: Vec<u8>,
+    ) -> Arc<Vec<u8>> {
+        if self.entries.len() >= MAX_CACHED_AGREEMENTS && !self.entries.contains_key(&settlement_id) {
+            self.entries.clear();
+        }
+        
+        let pdf = Arc::new(pdf);
+        self.entries
+            .entry(settlement_id)
+            .and_modify(|entry| {
+                if version >= entry.version {
+                    *entry = CachedAgreement { version, clauses_hash: clauses_hash.clone(), pdf: pdf.clone() };
+                }
+            })
+            .or_insert_with(|| CachedAgreement { version, clauses_hash, pdf: pdf.clone() });
+        pdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+    
+    use super::*;
+    use crate::models::{SettlementStatus, SolStatus};
+    use crate::money::Currency;
+    
+    fn settlement() -> Settlement {
+        let now = Utc::now();
+        Settlement {
+            reference_number: Some("DMC-2024-000123".to_string()),
+            status: SettlementStatus::Accepted,
+            smart_contract_address: Some("addr_test1contract".to_string()),
+            expires_at: now,
+            accepted_at: Some(now),
+            version: 1,
+            ..Settlement::fixture()
+        }
+    }
+    
+    fn fees() -> FeeBreakdown {
+        FeeBreakdown {
+            base_fee: BigDecimal::from(6),
+            success_fee: BigDecimal::from(80),
+            success_fee_rate: 0.2,
+            blockchain_cost: BigDecimal::from(0),
+            uncapped_total: BigDecimal::from(86),
+            cap_discount: BigDecimal::from(0),
+            cap_applied: false,
+            fee_policy: FeePolicy::Standard,
+            standard_total: BigDecimal::from(86),
+            policy_discount: BigDecimal::from(0),
+            currency: Currency::usd(),
+        }
+    }
+    
+    #[test]
+    fn agreement_states_the_reference_parties_and_amounts() {
+        let settlement = settlement();
+        let leverage = LeverageAnalysis {
+            violation_count: 2,
+            total_leverage_score: 3.5,
+            estimated_reduction_percentage: 40.0,
+            legal_strength: "strong".to_string(),
+            key_violations: vec!["FalseRepresentation".to_string()],
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: SolStatus::WithinPeriod,
+            explanation: Default::default(),
+            exposure_by_statute: Vec::new(),
+        };
+        let creditor_id = Uuid::new_v4();
+        let text = agreement_text(&AgreementTerms {
+            settlement: &settlement,
+            creditor_id,
+            fees: &fees(),
+            leverage: Some(&leverage),
+            clauses: &[],
+        })
+        .unwrap();
+        
+        for expected in [
+            "Reference: DMC-2024-000123".to_string(),
+            format!("Creditor: {}", creditor_id),
+            "accept 600.00 USD".to_string(),
+            "settlement contract at addr_test1contract".to_string(),
+            "- FalseRepresentation".to_string(),
+            "Platform fee: 86.00 USD".to_string(),
+            "## Signatures".to_string(),
+        ] {
+            assert!(text.contains(&expected), "missing {:?} in:\n{}", expected, text);
+        }
+        assert!(!text.contains("Transaction:"));
+    }
+    
+    #[test]
+    fn agreement_renders_to_a_pdf() {
+        let settlement = settlement();
+        let pdf = render_agreement(&AgreementTerms {
+            settlement: &settlement,
+            creditor_id: Uuid::new_v4(),
+            fees: &fees(),
+            leverage: None,
+            clauses: &[],
+        })
+        .unwrap();
+        
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+    
+    #[test]
+    fn cache_serves_only_the_version_it_rendered() {
+        let cache = AgreementCache::default();
+        let id = Uuid::new_v4();
+        
+        cache.insert(id, 2, None, b"v2".to_vec());
+        cache.insert(id, 1, None, b"v1".to_vec());
+        
+        assert_eq!(cache.get(id, 2, None).as_deref(), Some(&b"v2".to_vec()));
+        assert!(cache.get(id, 1, None).is_none());
+        assert!(cache.get(id, 3, None).is_none());
+        
+        cache.insert(id, 2, Some("reworded".to_string()), b"v2 reworded".to_vec());
+        assert!(cache.get(id, 2, None).is_none());
+        assert_eq!(cache.get(id, 2, Some("reworded")).as_deref(), Some(&b"v2 reworded".to_vec()));
+    }
+    
+    #[test]
+    fn clauses_are_filled_in_and_listed_in_order() {
+        let settlement = settlement();
+        let clause = |key: &str, title: &str, body: &str| AgreementClause {
+            key: key.to_string(),
+            version: 2,
+            title: title.to_string(),
+            body: body.to_string(),
+            required: true,
+        };
+        let clauses = [
+            clause(
+                "release-of-claims",
+                "Release of Claims",
+                "On payment of {{ settled_amount }} {{ currency }} the Creditor releases all claims on debt {{ debt_id }}.",
+            ),
+            clause("confidentiality", "Confidentiality", "The parties keep these terms confidential."),
+        ];
+        let text = agreement_text(&AgreementTerms {
+            settlement: &settlement,
+            creditor_id: Uuid::new_v4(),
+            fees: &fees(),
+            leverage: None,
+            clauses: &clauses,
+        })
+        .unwrap();
+        
+        let release = text.find("1. Release of Claims (clause release-of-claims, version 2)").expect(&text);
+        let confidentiality = text.find("2. Confidentiality (clause confidentiality, version 2)").expect(&text);
+        assert!(release < confidentiality);
+        assert!(text.contains(&format!("releases all claims on debt {}.", settlement.debt_id)));
+        assert!(text.contains("On payment of 600.00 USD"));
+    }
+}
@@ -0,0 +1,613 @@
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::i18n::Locale;
+use crate::models::{
+    AcceptanceAssessment, AcceptancePredictionRequest, CounterOffer, CreditorReply, Debt, InboundEmail,
+    LeverageAnalysis, NegotiationAction, NegotiationDecision, NegotiationRound, NegotiationStrategy,
+    OptimalSettlement, Party, Settlement, Violation,
+};
+use crate::money::round_to_minor_units;
+use crate::services::env_or;
+use crate::services::prompts::PromptTemplates;
+use crate::services::retry::{retry_with, RetryPolicy};
+use crate::services::simulation::SimulationConfig;
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 2;
+
+/// Budget for one attempt at an AI call, connecting included.
+const DEFAULT_CALL_TIMEOUT_SECS: u64 = 5;
+
+/// `model_version` recorded for proposals made by `rules_fallback`.
+pub const FALLBACK_MODEL_VERSION: &str = "rules-fallback";
+
+/// `model_version` recorded for proposals made by `rules_only`.
+pub const RULES_ONLY_MODEL_VERSION: &str = "rules-only";
+
+/// Confidence reported for rules-based proposals; deliberately low so nobody
+/// mistakes the degraded path for a model recommendation.
+const FALLBACK_CONFIDENCE: f64 = 0.3;
+
+/// FDCPA statutory damages cap per action (15 U.S.C. §1692k(a)(2)(A)).
+const FDCPA_STATUTORY_CAP: i64 = 1000;
+
+/// After this many rounds without agreement the fallback stops countering.
+const FALLBACK_MAX_COUNTER_ROUNDS: usize = 6;
+
+/// Reduction percentage by number of documented violations, used when the AI
+/// service is unavailable. Conservative on purpose.
+const FALLBACK_REDUCTION_TABLE: [(i32, i64); 5] = [
+    (0, 5),
+    (1, 10),
+    (2, 15),
+    (4, 20),
+    (6, 25),
+];
+
+/// Reduction points beyond what the leverage supports at which the fallback
+/// puts acceptance at about one in four; asking for exactly what it supports
+/// is an even chance.
+const FALLBACK_ACCEPTANCE_SPREAD: f64 = 10.0;
+
+/// An AI call ran past its budget. Travels inside `anyhow::Error`; see
+/// `is_timeout`.
+#[derive(Debug)]
+pub struct AiTimeout {
+    pub call: &'static str,
+    pub budget: Duration,
+}
+
+impl fmt::Display for AiTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AI {} call timed out after {:?}", self.call, self.budget)
+    }
+}
+
+impl std::error::Error for AiTimeout {}
+
+pub fn is_timeout(e: &anyhow::Error) -> bool {
+    e.is::<AiTimeout>()
+}
+
+#[derive(Clone)]
+pub struct AiClient {
+    http: reqwest::Client,
+    base_url: String,
+    call_timeout: Duration,
+    retry: RetryPolicy,
+    prompts: PromptTemplates,
+    /// When set, no call reaches the AI service; see `SimulationConfig`.
+    simulation: Option<SimulationConfig>,
+}
+
+impl AiClient {
+    /// Reads `AI_SERVICE_URL`, `AI_CONNECT_TIMEOUT_SECS` (default 2), the
+    /// per-attempt budget `AI_CALL_TIMEOUT_SECS` (default 5) and
+    /// `SETTLEMENT_SIMULATION_SEED`, which switches to simulation mode.
+    /// Calls are retried by `RetryPolicy::AI` unless `with_retry` says
+    /// otherwise.
+    pub fn new(prompts: PromptTemplates) -> Self {
+        let base_url = env::var("AI_SERVICE_URL")
+            .unwrap_or_else(|_| "http://localhost:8004".to_string());
+        
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(env_or(
+                "AI_CONNECT_TIMEOUT_SECS",
+                DEFAULT_CONNECT_TIMEOUT_SECS,
+            )))
+            .build()
+            .expect("valid AI HTTP client configuration");
+        
+        Self {
+            http,
+            base_url,
+            call_timeout: Duration::from_secs(env_or("AI_CALL_TIMEOUT_SECS", DEFAULT_CALL_TIMEOUT_SECS)),
+            retry: RetryPolicy::AI,
+            prompts,
+            simulation: SimulationConfig::from_env(),
+        }
+    }
+    
+    /// A client that answers every call from `simulation`.
+    #[cfg(test)]
+    pub fn simulated(prompts: PromptTemplates, simulation: SimulationConfig) -> Self {
+        Self {
+            simulation: Some(simulation),
+            ..Self::new(prompts)
+        }
+    }
+    
+    /// A client that calls the AI service at `base_url`.
+    #[cfg(test)]
+    pub fn at(prompts: PromptTemplates, base_url: String) -> Self {
+        Self {
+            base_url,
+            simulation: None,
+            ..Self::new(prompts)
+        }
+    }
+    
+    pub fn with_retry(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+    
+    pub fn simulation(&self) -> Option<SimulationConfig> {
+        self.simulation
+    }
+    
+    /// Runs `request`, failing with `AiTimeout` if it takes longer than the
+    /// per-attempt budget.
+    async fn within_budget<T>(
+        &self,
+        call: &'static str,
+        request: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        tokio::time::timeout(self.call_timeout, request)
+            .await
+            .map_err(|_| AiTimeout { call, budget: self.call_timeout })?
+    }
+    
+    /// Posts `body` to the AI service's `path` and reads the answer, each
+    /// attempt within the budget, retrying as the policy allows.
+    async fn post<T: DeserializeOwned>(&self, call: &'static str, path: &str, body: &Value) -> anyhow::Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let url = &url;
+        retry_with(&self.retry, &format!("AI {} call", call), || {
+            self.within_budget(call, async move {
+                let response = self.http.post(url).json(body).send().await?.error_for_status()?;
+                Ok(response.json::<T>().await?)
+            })
+        })
+        .await
+    }
+    
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        if self.simulation.is_some() {
+            return Ok(());
+        }
+        self.http
+            .get(format!("{}/health", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        Ok(())
+    }
+    
+    /// Lets the AI service refine a rules-based leverage analysis, e.g. by
+    /// weighing the specific violations against the creditor's history.
+    #[instrument(skip_all, fields(creditor_id = %creditor_id, violations = violations.len()))]
+    pub async fn score_leverage(
+        &self,
+        creditor_id: Uuid,
+        violations: &[Violation],
+        baseline: &LeverageAnalysis,
+    ) -> anyhow::Result<LeverageAnalysis> {
+        if let Some(simulation) = &self.simulation {
+            return Ok(simulation.score_leverage(baseline));
+        }
+        let body = json!({
+            "creditor_id": creditor_id,
+            "violations": violations,
+            "baseline": baseline,
+        });
+        self.post("leverage_score", "/api/v1/leverage/score", &body).await
+    }
+    
+    /// Asks the AI service for the settlement amount most likely to be accepted
+    /// given the debt, the leverage we hold over the creditor and how hard
+    /// `strategy` says to push. The rendered proposal prompt goes with it, and
+    /// `locale` says what language to write the reasoning in.
+    #[instrument(skip_all, fields(debt_id = %debt.id, ?strategy))]
+    pub async fn calculate_optimal_settlement(
+        &self,
+        debt: &Debt,
+        leverage: &LeverageAnalysis,
+        strategy: NegotiationStrategy,
+        locale: &Locale,
+    ) -> anyhow::Result<OptimalSettlement> {
+        if let Some(simulation) = &self.simulation {
+            return Ok(simulation.optimal_settlement(debt, leverage, strategy, locale));
+        }
+        let mut body = optimal_settlement_request(debt, leverage, strategy, locale);
+        body["prompt"] = json!(self.prompts.proposal.render(&[
+            ("violation_count", leverage.violation_count.to_string()),
+            ("original_amount", debt.original_amount.to_string()),
+            ("current_amount", debt.current_amount.to_string()),
+            ("currency", debt.currency.to_string()),
+            ("legal_strength", leverage.legal_strength.clone()),
+            ("key_violations", leverage.key_violations.join(", ")),
+            ("estimated_reduction_percentage", format!("{:.1}", leverage.estimated_reduction_percentage)),
+            ("strategy", format!("{:?}", strategy).to_lowercase()),
+            (
+                "target_reduction_percentage",
+                format!("{:.1}", target_reduction_percentage(leverage, strategy)),
+            ),
+        ]));
+        body["prompt_version"] = json!(self.prompts.version);
+        let mut optimal: OptimalSettlement = self
+            .post("optimal_settlement", "/api/v1/settlements/optimal", &body)
+            .await?;
+        if optimal.model_version.trim().is_empty() {
+            optimal.model_version = "unknown".to_string();
+        }
+        optimal.prompt_hash = prompt_hash(&body);
+        optimal.prompt_version = Some(self.prompts.version.clone());
+        Ok(optimal)
+    }
+    
+    /// Asks the AI service whether to accept, counter or hold on a counter-offer,
+    /// given the proposal and every earlier round. The reasoning comes back in
+    /// the offer's locale.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id, round = history.len() + 1))]
+    pub async fn evaluate_counter_offer(
+        &self,
+        settlement: &Settlement,
+        offer: &CounterOffer,
+        history: &[NegotiationRound],
+    ) -> anyhow::Result<NegotiationDecision> {
+        if let Some(simulation) = &self.simulation {
+            return Ok(simulation.evaluate_counter_offer(settlement, offer, history));
+        }
+        let prompt = self.prompts.counter_offer.render(&[
+            ("original_amount", settlement.original_amount.to_string()),
+            ("proposed_amount", settlement.settled_amount.to_string()),
+            ("offer_amount", offer.amount.to_string()),
+            ("currency", settlement.currency.to_string()),
+            ("offer_from", format!("{:?}", offer.from).to_lowercase()),
+            ("round", (history.len() + 1).to_string()),
+            ("strategy", format!("{:?}", settlement.strategy).to_lowercase()),
+            ("concession_percent", settlement.strategy.concession_percent().to_string()),
+        ]);
+        let body = json!({
+            "settlement_id": settlement.id,
+            "original_amount": settlement.original_amount,
+            "proposed_amount": settlement.settled_amount,
+            "offer_amount": offer.amount,
+            "offer_from": offer.from,
+            "currency": settlement.currency,
+            "strategy": settlement.strategy,
+            "concession_percent": settlement.strategy.concession_percent(),
+            "history": history,
+            "prompt": prompt,
+            "prompt_version": self.prompts.version,
+            "locale": offer.locale,
+        });
+        self.post("counter_offer", "/api/v1/settlements/counter", &body).await
+    }
+    
+    /// Asks the AI service how likely the creditor is to accept the proposed
+    /// amount given the leverage. The creditor's own record is left to the
+    /// caller to weigh in.
+    #[instrument(skip_all, fields(creditor_id = %request.creditor_id))]
+    pub async fn predict_acceptance(
+        &self,
+        request: &AcceptancePredictionRequest,
+    ) -> anyhow::Result<AcceptanceAssessment> {
+        if let Some(simulation) = &self.simulation {
+            return Ok(simulation.predict_acceptance(request));
+        }
+        let body = json!({
+            "creditor_id": request.creditor_id,
+            "proposed_amount": request.proposed_amount,
+            "original_amount": request.original_amount,
+            "currency": request.currency,
+            "requested_reduction_percentage": request.requested_reduction_percentage(),
+            "leverage": request.leverage_analysis,
+            "locale": request.locale,
+        });
+        self.post("acceptance_prediction", "/api/v1/settlements/acceptance", &body).await
+    }
+    
+    /// Asks the AI service whether a creditor's email about `settlement`
+    /// accepts its proposal, rejects it or counters it, and with what amount.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    pub async fn parse_creditor_reply(
+        &self,
+        email: &InboundEmail,
+        settlement: &Settlement,
+    ) -> anyhow::Result<CreditorReply> {
+        if let Some(simulation) = &self.simulation {
+            return Ok(simulation.parse_creditor_reply());
+        }
+        let body = json!({
+            "settlement_id": settlement.id,
+            "subject": email.subject,
+            "text": email.text,
+            "proposed_amount": settlement.settled_amount,
+            "original_amount": settlement.original_amount,
+            "currency": settlement.currency,
+        });
+        self.post("creditor_reply", "/api/v1/emails/parse", &body).await
+    }
+}
+
+/// How likely the creditor is to accept when the AI service can't say: an
+/// even chance when the amount asks for the reduction the leverage supports,
+/// falling off the further it asks beyond that.
+pub fn acceptance_fallback(request: &AcceptancePredictionRequest) -> AcceptanceAssessment {
+    let locale = &request.locale;
+    let requested = request.requested_reduction_percentage();
+    let supported = request.leverage_analysis.estimated_reduction_percentage;
+    // Logistic in the gap; ln 3 puts one spread past the support at 25%.
+    let acceptance_probability =
+        1.0 / (1.0 + (3f64.ln() * (requested - supported) / FALLBACK_ACCEPTANCE_SPREAD).exp());
+    
+    AcceptanceAssessment {
+        acceptance_probability,
+        acceptable_reduction_percentage: supported,
+        reasoning: vec![
+            locale.message("fallback.prediction", &[]),
+            locale.message(
+                "prediction.gap",
+                &[
+                    ("requested_percentage", format!("{:.1}", requested)),
+                    ("supported_percentage", format!("{:.1}", supported)),
+                ],
+            ),
+        ],
+    }
+}
+
+/// What to answer on a counter-offer when the AI service can't.
+///
+/// Accept creditor offers at or below our proposal, otherwise concede the
+/// strategy's share of the gap until the round budget runs out. A debtor's own
+/// counter just waits for the creditor.
+pub fn counter_fallback(
+    settlement: &Settlement,
+    offer: &CounterOffer,
+    history: &[NegotiationRound],
+) -> NegotiationDecision {
+    let locale = &offer.locale;
+    let degraded = locale.message("fallback.decision", &[]);
+    
+    if offer.from == Party::Debtor {
+        return NegotiationDecision {
+            action: NegotiationAction::Hold,
+            counter_amount: None,
+            reasoning: vec![degraded, locale.message("fallback.awaiting_creditor", &[])],
+        };
+    }
+    
+    if offer.amount <= settlement.settled_amount {
+        return NegotiationDecision {
+            action: NegotiationAction::Accept,
+            counter_amount: None,
+            reasoning: vec![degraded, locale.message("fallback.offer_within_proposal", &[])],
+        };
+    }
+    
+    if history.len() >= FALLBACK_MAX_COUNTER_ROUNDS {
+        return NegotiationDecision {
+            action: NegotiationAction::Hold,
+            counter_amount: None,
+            reasoning: vec![
+                degraded,
+                locale.message("fallback.holding", &[("rounds", history.len().to_string())]),
+            ],
+        };
+    }
+    
+    let concession = settlement.strategy.concession_percent();
+    let counter = round_to_minor_units(
+        &settlement.settled_amount
+            + (&offer.amount - &settlement.settled_amount) * BigDecimal::from(concession) / BigDecimal::from(100),
+        &settlement.currency,
+    );
+    NegotiationDecision {
+        action: NegotiationAction::Counter,
+        counter_amount: Some(counter.clone()),
+        reasoning: vec![
+            degraded,
+            locale.message(
+                "fallback.countering",
+                &[
+                    ("counter_amount", counter.to_string()),
+                    ("concession_percentage", concession.to_string()),
+                    ("strategy", format!("{:?}", settlement.strategy)),
+                ],
+            ),
+        ],
+    }
+}
+
+/// The proposal to make with AI disabled: the fallback's amount, recorded as
+/// the rules' own choice rather than a degraded one, and without a
+/// confidence, which only the model gives.
+pub fn rules_only(
+    debt: &Debt,
+    leverage: &LeverageAnalysis,
+    strategy: NegotiationStrategy,
+    locale: &Locale,
+) -> OptimalSettlement {
+    let mut optimal = rules_fallback(debt, leverage, strategy, locale);
+    optimal.model_version = RULES_ONLY_MODEL_VERSION.to_string();
+    optimal.confidence = None;
+    optimal.reasoning[0] = locale.message("rules_only.proposal", &[]);
+    optimal
+}
+
+fn optimal_settlement_request(
+    debt: &Debt,
+    leverage: &LeverageAnalysis,
+    strategy: NegotiationStrategy,
+    locale: &Locale,
+) -> serde_json::Value {
+    json!({
+        "debt_id": debt.id,
+        "creditor_id": debt.creditor_id,
+        "original_amount": debt.original_amount,
+        "current_amount": debt.current_amount,
+        "currency": debt.currency,
+        "leverage": leverage,
+        "strategy": strategy,
+        "target_reduction_percentage": target_reduction_percentage(leverage, strategy),
+        "locale": locale,
+    })
+}
+
+/// The reduction `strategy` aims for given what the leverage supports.
+pub fn target_reduction_percentage(leverage: &LeverageAnalysis, strategy: NegotiationStrategy) -> f64 {
+    leverage.estimated_reduction_percentage * strategy.target_reduction_scale() as f64 / 100.0
+}
+
+/// Hash of the exact request body, so a recommendation can be reproduced by
+/// replaying the same input against the same model version.
+fn prompt_hash(body: &serde_json::Value) -> String {
+    let bytes = serde_json::to_vec(body).expect("AI request serializes");
+    format!("{:x}", Sha256::digest(&bytes))
+}
+
+/// The proposal to make when the AI service can't.
+///
+/// Reduction is the table percentage of the current balance, scaled by the
+/// strategy, but never more than the creditor's statutory exposure (the FDCPA
+/// cap per documented violation, with a floor of one).
+pub fn rules_fallback(
+    debt: &Debt,
+    leverage: &LeverageAnalysis,
+    strategy: NegotiationStrategy,
+    locale: &Locale,
+) -> OptimalSettlement {
+    let percentage = FALLBACK_REDUCTION_TABLE
+        .iter()
+        .rev()
+        .find(|(min_violations, _)| leverage.violation_count >= *min_violations)
+        .map(|(_, percentage)| *percentage)
+        .unwrap_or(0)
+        * strategy.target_reduction_scale()
+        / 100;
+    
+    let by_percentage = &debt.current_amount * BigDecimal::from(percentage) / BigDecimal::from(100);
+    let statutory_exposure =
+        BigDecimal::from(FDCPA_STATUTORY_CAP * leverage.violation_count.max(1) as i64);
+    let reduction = by_percentage
+        .min(statutory_exposure)
+        .with_scale(debt.currency.minor_units());
+    
+    let amount = &debt.current_amount - &reduction;
+    let reduction_percentage = if debt.current_amount > BigDecimal::from(0) {
+        (&reduction * BigDecimal::from(100) / &debt.current_amount)
+            .to_f64()
+            .unwrap_or(0.0)
+    } else {
+        0.0
+    };
+    
+    OptimalSettlement {
+        amount,
+        reduction_percentage,
+        confidence: Some(FALLBACK_CONFIDENCE),
+        model_version: FALLBACK_MODEL_VERSION.to_string(),
+        prompt_hash: prompt_hash(&optimal_settlement_request(debt, leverage, strategy, locale)),
+        prompt_version: None,
+        currency: Some(debt.currency.clone()),
+        floor_applied: false,
+        reasoning: vec![
+            locale.message("fallback.proposal", &[]),
+            locale.message(
+                "fallback.proposal_basis",
+                &[
+                    ("violation_count", leverage.violation_count.to_string()),
+                    ("reduction_percentage", percentage.to_string()),
+                    ("strategy", format!("{:?}", strategy)),
+                    (
+                        "statutory_exposure",
+                        (FDCPA_STATUTORY_CAP * leverage.violation_count.max(1) as i64).to_string(),
+                    ),
+                ],
+            ),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn a_call_past_its_budget_times_out() {
+        // Accepts connections and never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        
+        let client = AiClient {
+            http: reqwest::Client::new(),
+            base_url: format!("http://{}", addr),
+            call_timeout: Duration::from_millis(50),
+            retry: RetryPolicy {
+                max_attempts: 1,
+                ..RetryPolicy::AI
+            },
+            prompts: PromptTemplates::default(),
+            simulation: None,
+        };
+        let baseline = LeverageAnalysis {
+            violation_count: 0,
+            total_leverage_score: 0.0,
+            estimated_reduction_percentage: 0.0,
+            legal_strength: "weak".to_string(),
+            key_violations: Vec::new(),
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: crate::models::SolStatus::Unknown,
+            explanation: Default::default(),
+            exposure_by_statute: Vec::new(),
+        };
+        
+        let e = client.score_leverage(Uuid::new_v4(), &[], &baseline).await.unwrap_err();
+        assert!(is_timeout(&e), "{}", e);
+    }
+    
+    #[test]
+    fn aggressive_fallback_anchors_lower_than_conservative() {
+        let debt = Debt {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            debtors: Vec::new(),
+            creditor_id: Uuid::new_v4(),
+            original_amount: BigDecimal::from(10_000),
+            current_amount: BigDecimal::from(10_000),
+            currency: Default::default(),
+            status: "active".to_string(),
+            last_payment_date: None,
+            verification_status: crate::models::VerificationStatus::Unverified,
+            created_at: chrono::Utc::now(),
+        };
+        let leverage = LeverageAnalysis {
+            violation_count: 4,
+            total_leverage_score: 0.0,
+            estimated_reduction_percentage: 20.0,
+            legal_strength: "strong".to_string(),
+            key_violations: Vec::new(),
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: crate::models::SolStatus::WithinPeriod,
+            explanation: Default::default(),
+            exposure_by_statute: Vec::new(),
+        };
+        let amount = |strategy| rules_fallback(&debt, &leverage, strategy, &Locale::default()).amount;
+        
+        assert_eq!(amount(NegotiationStrategy::Balanced), BigDecimal::from(8_000));
+        assert!(amount(NegotiationStrategy::Aggressive) < amount(NegotiationStrategy::Balanced));
+        assert!(amount(NegotiationStrategy::Conservative) > amount(NegotiationStrategy::Balanced));
+    }
+}
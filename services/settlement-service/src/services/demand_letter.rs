@@ -0,0 +1,276 @@
+use std::env;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDate;
+use minijinja::Environment;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::{LeverageAnalysis, Settlement, Statute, Violation};
+use crate::services::pdf;
+
+const DEFAULT_TEMPLATE: &str = include_str!("../../templates/demand_letter.txt");
+
+/// Days the creditor is given to answer when the request doesn't say.
+pub const DEFAULT_RESPONSE_DAYS: u32 = 30;
+/// Most days a letter may give; a demand with no near deadline isn't one.
+pub const MAX_RESPONSE_DAYS: u32 = 90;
+
+/// What a demand letter states.
+pub struct DemandLetterTerms<'a> {
+    pub settlement: &'a Settlement,
+    pub creditor_id: Uuid,
+    /// The analysis the proposal was made with.
+    pub leverage: &'a LeverageAnalysis,
+    /// The violations the analysis counted, in the order they happened.
+    pub violations: &'a [Violation],
+    pub date: NaiveDate,
+    pub respond_by: NaiveDate,
+}
+
+/// The demand letter template: the built-in one unless
+/// `DEMAND_LETTER_TEMPLATE` names a file to use instead. Like the agreement,
+/// it is a minijinja template whose `# ` and `## ` lines are the title and
+/// section headings.
+#[derive(Debug, Clone)]
+pub struct DemandLetterTemplate {
+    source: Arc<str>,
+}
+
+impl Default for DemandLetterTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_TEMPLATE).expect("built-in demand letter template is valid")
+    }
+}
+
+impl DemandLetterTemplate {
+    /// A template that doesn't parse is an error, so the service refuses to
+    /// start rather than fail every letter.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let Ok(path) = env::var("DEMAND_LETTER_TEMPLATE") else {
+            return Ok(Self::default());
+        };
+        let source =
+            std::fs::read_to_string(&path).with_context(|| format!("reading demand letter template {}", path))?;
+        
+        Self::parse(&source).with_context(|| format!("loading demand letter template {}", path))
+    }
+    
+    fn parse(source: &str) -> anyhow::Result<Self> {
+        environment().template_from_str(source)?;
+        Ok(Self { source: source.into() })
+    }
+    
+    pub fn text(&self, terms: &DemandLetterTerms) -> anyhow::Result<String> {
+        let settlement = terms.settlement;
+        let minor_units = settlement.currency.minor_units();
+        let amount = |value: &BigDecimal| value.with_scale(minor_units).to_string();
+        let usd = |value: f64| format!("{:.2}", value);
+        let exposure = &terms.leverage.exposure_by_statute;
+        let reduction = if settlement.original_amount > BigDecimal::from(0) {
+            (&settlement.saved_amount / &settlement.original_amount * BigDecimal::from(100))
+                .to_f64()
+                .unwrap_or_default()
+        } else {
+            0.0
+        };
+        
+        let context = json!({
+            "date": terms.date.to_string(),
+            "respond_by": terms.respond_by.to_string(),
+            "reference_number": settlement.reference_number.as_deref().unwrap_or("not yet assigned"),
+            "settlement_id": settlement.id,
+            "user_id": settlement.user_id,
+            "creditor_id": terms.creditor_id,
+            "debt_id": settlement.debt_id,
+            "currency": settlement.currency,
+            "original_amount": amount(&settlement.original_amount),
+            "demand_amount": amount(&settlement.settled_amount),
+            "reduction_percentage": format!("{:.1}", reduction),
+            "legal_strength": terms.leverage.legal_strength.replace('_', " "),
+            "violations": terms.violations.iter().map(|violation| json!({
+                "occurred_on": violation.occurred_at.date_naive().to_string(),
+                "type": violation.violation_type,
+                "statute": violation.statute,
+                "statute_name": statute_name(violation.statute),
+                "legal_reference": violation.legal_reference,
+                "severity": violation.severity,
+            })).collect::<Vec<_>>(),
+            "exposure": exposure.iter().map(|exposure| json!({
+                "statute": exposure.statute,
+                "statute_name": statute_name(exposure.statute),
+                "violation_count": exposure.violation_count,
+                "leverage_score": exposure.leverage_score,
+                "actual_damages": usd(exposure.actual_damages),
+                "statutory_damages_min": usd(exposure.statutory_damages_min),
+                "statutory_damages_max": usd(exposure.statutory_damages_max),
+            })).collect::<Vec<_>>(),
+            "statutory_damages_min": usd(exposure.iter().map(|exposure| exposure.statutory_damages_min).sum()),
+            "statutory_damages_max": usd(exposure.iter().map(|exposure| exposure.statutory_damages_max).sum()),
+            "actual_damages": usd(exposure.iter().map(|exposure| exposure.actual_damages).sum()),
+            "estimated_reduction_percentage": format!("{:.1}", terms.leverage.estimated_reduction_percentage),
+        });
+        
+        Ok(environment().render_str(&self.source, context)?)
+    }
+    
+    pub fn pdf(&self, terms: &DemandLetterTerms) -> anyhow::Result<Vec<u8>> {
+        let text = self.text(terms)?;
+        let title = format!(
+            "Demand letter {}",
+            terms.settlement.reference_number.as_deref().unwrap_or_default()
+        );
+        
+        pdf::render_text(title, &text)
+    }
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env
+}
+
+fn statute_name(statute: Statute) -> &'static str {
+    match statute {
+        Statute::Fdcpa => "the Fair Debt Collection Practices Act",
+        Statute::Tcpa => "the Telephone Consumer Protection Act",
+        Statute::Fcra => "the Fair Credit Reporting Act",
+        Statute::StateUdap => "state law against unfair and deceptive practices",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    
+    use super::*;
+    use crate::models::{SolStatus, StatuteExposure};
+    
+    fn settlement() -> Settlement {
+        Settlement {
+            reference_number: Some("DMC-2024-000123".to_string()),
+            version: 1,
+            ..Settlement::fixture()
+        }
+    }
+    
+    fn violation(violation_type: &str, statute: Statute, legal_reference: &str, day: u32) -> Violation {
+        let occurred_at = Utc.with_ymd_and_hms(2024, 3, day, 15, 0, 0).unwrap();
+        Violation {
+            id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            violation_type: violation_type.to_string(),
+            statute,
+            severity: "high".to_string(),
+            confidence: 0.9,
+            legal_reference: legal_reference.to_string(),
+            estimated_damage: 500.0,
+            occurred_at,
+            created_at: occurred_at,
+            repeat_count: 0,
+            evidence: Vec::new(),
+        }
+    }
+    
+    fn leverage() -> LeverageAnalysis {
+        LeverageAnalysis {
+            violation_count: 2,
+            total_leverage_score: 42.0,
+            estimated_reduction_percentage: 40.0,
+            legal_strength: "very_strong".to_string(),
+            key_violations: vec!["FalseRepresentation".to_string(), "Robocall".to_string()],
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: SolStatus::WithinPeriod,
+            explanation: Default::default(),
+            exposure_by_statute: vec![
+                StatuteExposure {
+                    statute: Statute::Fdcpa,
+                    violation_count: 1,
+                    leverage_score: 30.0,
+                    actual_damages: 500.0,
+                    statutory_damages_min: 0.0,
+                    statutory_damages_max: 1000.0,
+                },
+                StatuteExposure {
+                    statute: Statute::Tcpa,
+                    violation_count: 1,
+                    leverage_score: 12.0,
+                    actual_damages: 500.0,
+                    statutory_damages_min: 500.0,
+                    statutory_damages_max: 1500.0,
+                },
+            ],
+        }
+    }
+    
+    fn terms<'a>(
+        settlement: &'a Settlement,
+        leverage: &'a LeverageAnalysis,
+        violations: &'a [Violation],
+    ) -> DemandLetterTerms<'a> {
+        DemandLetterTerms {
+            settlement,
+            creditor_id: Uuid::nil(),
+            leverage,
+            violations,
+            date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            respond_by: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+        }
+    }
+    
+    #[test]
+    fn letter_cites_the_violations_exposure_and_demand() {
+        let settlement = settlement();
+        let leverage = leverage();
+        let violations = [
+            violation("FalseRepresentation", Statute::Fdcpa, "15 U.S.C. § 1692e", 2),
+            violation("Robocall", Statute::Tcpa, "47 U.S.C. § 227(b)", 9),
+        ];
+        let text = DemandLetterTemplate::default().text(&terms(&settlement, &leverage, &violations)).unwrap();
+        
+        for expected in [
+            "Reference: DMC-2024-000123",
+            "- 2024-03-02: FalseRepresentation, contrary to the Fair Debt Collection Practices Act (15 U.S.C. § 1692e).",
+            "- 2024-03-09: Robocall, contrary to the Telephone Consumer Protection Act (47 U.S.C. § 227(b)).",
+            "- the Telephone Consumer Protection Act: 1 violation, carrying statutory damages of 500.00 to 1500.00 USD",
+            "between 500.00 and 2500.00 USD",
+            "a very strong case",
+            "for 600.00 USD, 40.0% less than its balance of 1000.00 USD",
+            "answer in writing by 2024-07-01",
+        ] {
+            assert!(text.contains(expected), "missing {:?} in:\n{}", expected, text);
+        }
+        assert!(text.starts_with("# Demand for Settlement\n"));
+    }
+    
+    #[test]
+    fn templates_can_be_replaced_but_must_parse() {
+        let settlement = settlement();
+        let leverage = leverage();
+        let template = DemandLetterTemplate::parse("Pay {{ demand_amount }} {{ currency }} by {{ respond_by }}.")
+            .unwrap();
+        
+        assert_eq!(
+            template.text(&terms(&settlement, &leverage, &[])).unwrap(),
+            "Pay 600.00 USD by 2024-07-01."
+        );
+        assert!(DemandLetterTemplate::parse("{% for violation in %}").is_err());
+        assert!(DemandLetterTemplate::parse("{{ demand_amount }").is_err());
+    }
+    
+    #[test]
+    fn letter_renders_to_a_pdf() {
+        let settlement = settlement();
+        let leverage = leverage();
+        let violations = [violation("FalseRepresentation", Statute::Fdcpa, "15 U.S.C. § 1692e", 2)];
+        let pdf = DemandLetterTemplate::default().pdf(&terms(&settlement, &leverage, &violations)).unwrap();
+        
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use futures::Stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::models::AuditEvent;
+use crate::services::shutdown::Shutdown;
+
+/// Events replayed to a stream that connects without a `Last-Event-ID`.
+pub const STREAM_REPLAY_EVENTS: i64 = 50;
+
+/// Events read from the database at a time while a stream catches up.
+const STREAM_PAGE_SIZE: i64 = 100;
+
+/// Wakeups a slow stream may miss before it is told it lagged; it then
+/// reads the database anyway, so nothing is lost.
+const WAKEUP_CAPACITY: usize = 16;
+
+/// What a settlement's event stream yields.
+#[derive(Debug, Clone)]
+pub enum StreamedEvent {
+    Event(AuditEvent),
+    /// Nothing happened for a while. Writing something lets the server
+    /// notice a client that went away.
+    KeepAlive,
+}
+
+/// Wakes this instance's event streams when their settlement gets an event.
+/// A settlement only has a channel while a stream is subscribed to it.
+#[derive(Clone, Default)]
+pub struct EventHub {
+    channels: Arc<DashMap<Uuid, broadcast::Sender<i64>>>,
+}
+
+impl EventHub {
+    pub fn publish(&self, event: &AuditEvent) {
+        if let Some(channel) = self.channels.get(&event.settlement_id) {
+            // Nobody listening is fine; the last subscriber is on its way out.
+            let _ = channel.send(event.id);
+        }
+    }
+    
+    pub fn subscribe(&self, settlement_id: Uuid) -> Subscription {
+        let receiver = self
+            .channels
+            .entry(settlement_id)
+            .or_insert_with(|| broadcast::channel(WAKEUP_CAPACITY).0)
+            .subscribe();
+        Subscription {
+            hub: self.clone(),
+            settlement_id,
+            receiver: Some(receiver),
+        }
+    }
+    
+    /// Subscriptions not yet dropped, across every settlement.
+    pub fn subscriptions(&self) -> usize {
+        self.channels.iter().map(|channel| channel.receiver_count()).sum()
+    }
+}
+
+/// One stream's interest in a settlement's events. Dropping the last one
+/// removes the settlement's channel.
+pub struct Subscription {
+    hub: EventHub,
+    settlement_id: Uuid,
+    receiver: Option<broadcast::Receiver<i64>>,
+}
+
+impl Subscription {
+    /// Waits up to `timeout` for the settlement to get an event; `false`
+    /// when none arrived in time.
+    async fn wait(&mut self, timeout: Duration) -> bool {
+        let Some(receiver) = self.receiver.as_mut() else {
+            return false;
+        };
+        match tokio::time::timeout(timeout, receiver.recv()).await {
+            Ok(Ok(_) | Err(RecvError::Lagged(_))) => true,
+            // The hub keeps the sender while anyone subscribes, so this
+            // doesn't happen; waiting out the timeout keeps it from spinning.
+            Ok(Err(RecvError::Closed)) => {
+                tokio::time::sleep(timeout).await;
+                false
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        drop(self.receiver.take());
+        self.hub
+            .channels
+            .remove_if(&self.settlement_id, |_, channel| channel.receiver_count() == 0);
+    }
+}
+
+/// Where a stream has got to.
+struct Cursor {
+    db: Database,
+    subscription: Subscription,
+    shutdown: Shutdown,
+    settlement_id: Uuid,
+    last_event_id: i64,
+    pending: VecDeque<AuditEvent>,
+    /// Whether the last read found every event there was to read.
+    caught_up: bool,
+    keep_alive: Duration,
+}
+
+/// The settlement's events after `last_event_id`, or its most recent
+/// [`STREAM_REPLAY_EVENTS`] without one, then each new event as it is
+/// recorded. Events are read back from the database in id order, so none is
+/// skipped or sent twice, and those recorded by other instances arrive
+/// within `keep_alive`. The stream ends when shutdown begins or the
+/// database can't be read; either way the client reconnects with the id of
+/// the last event it got.
+pub async fn settlement_events(
+    db: Database,
+    hub: &EventHub,
+    shutdown: Shutdown,
+    settlement_id: Uuid,
+    last_event_id: Option<i64>,
+    keep_alive: Duration,
+) -> Result<impl Stream<Item = StreamedEvent>, sqlx::Error> {
+    // Subscribed before reading, so an event recorded in between still
+    // wakes the stream.
+    let subscription = hub.subscribe(settlement_id);
+    let replay = match last_event_id {
+        Some(after) => db.get_settlement_events_after(settlement_id, after, STREAM_PAGE_SIZE).await?,
+        None => db.get_recent_settlement_events(settlement_id, STREAM_REPLAY_EVENTS).await?,
+    };
+    let cursor = Cursor {
+        db,
+        subscription,
+        shutdown,
+        settlement_id,
+        last_event_id: last_event_id.unwrap_or(0),
+        caught_up: last_event_id.is_none() || (replay.len() as i64) < STREAM_PAGE_SIZE,
+        pending: replay.into(),
+        keep_alive,
+    };
+    
+    Ok(futures::stream::unfold(cursor, |mut cursor| async move {
+        loop {
+            if let Some(event) = cursor.pending.pop_front() {
+                cursor.last_event_id = event.id;
+                return Some((StreamedEvent::Event(event), cursor));
+            }
+            if cursor.shutdown.is_draining() {
+                return None;
+            }
+            
+            let woken = if cursor.caught_up {
+                tokio::select! {
+                    woken = cursor.subscription.wait(cursor.keep_alive) => woken,
+                    _ = cursor.shutdown.draining() => return None,
+                }
+            } else {
+                true
+            };
+            match cursor
+                .db
+                .get_settlement_events_after(cursor.settlement_id, cursor.last_event_id, STREAM_PAGE_SIZE)
+                .await
+            {
+                Ok(events) if events.is_empty() && !woken => return Some((StreamedEvent::KeepAlive, cursor)),
+                Ok(events) => {
+                    cursor.caught_up = (events.len() as i64) < STREAM_PAGE_SIZE;
+                    cursor.pending.extend(events);
+                }
+                Err(e) => {
+                    warn!("Ending the event stream of settlement {}: {}", cursor.settlement_id, e);
+                    return None;
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn the_last_subscription_dropped_removes_the_channel() {
+        let hub = EventHub::default();
+        let settlement_id = Uuid::new_v4();
+        
+        let first = hub.subscribe(settlement_id);
+        let mut second = hub.subscribe(settlement_id);
+        hub.subscribe(Uuid::new_v4());
+        assert_eq!(hub.subscriptions(), 2);
+        assert_eq!(hub.channels.len(), 1);
+        
+        drop(first);
+        hub.publish(&AuditEvent {
+            id: 7,
+            settlement_id,
+            event_type: crate::models::AuditEventType::Accepted,
+            actor: "system".to_string(),
+            metadata: serde_json::Value::Null,
+            created_at: chrono::Utc::now(),
+        });
+        assert!(second.wait(Duration::from_secs(1)).await);
+        assert!(!second.wait(Duration::from_millis(10)).await);
+        
+        drop(second);
+        assert_eq!(hub.subscriptions(), 0);
+        assert!(hub.channels.is_empty());
+    }
+}
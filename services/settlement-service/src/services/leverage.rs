@@ -0,0 +1,517 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::{DateTime, Months, Utc};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::models::{
+    Debt, Factor, LeverageAnalysis, LeverageExplanation, SolStatus, Statute, StatuteExposure, Violation,
+};
+
+/// Leverage points for a violation type missing from the weight table.
+const POINTS_PER_VIOLATION: f64 = 10.0;
+
+/// Each repeat folded into a deduplicated violation adds this share of its
+/// weight, up to the cap, so a pattern counts for more than a single incident
+/// without inflating leverage linearly.
+const REPEAT_BONUS_PER_OCCURRENCE: f64 = 0.1;
+const MAX_REPEAT_BONUS: f64 = 0.5;
+
+/// A violation with at least one evidence artifact behind it counts for this
+/// share more than one resting on the user's word alone.
+const CORROBORATION_BONUS: f64 = 0.25;
+
+/// Leverage points by violation type, roughly tracking how much exposure each
+/// kind of conduct carries under its statute. `LEVERAGE_SEVERITY_WEIGHTS_PATH` points at a
+/// JSON object of `{ "<type>": points }` that overrides or extends these.
+const DEFAULT_SEVERITY_WEIGHTS: [(&str, f64); 15] = [
+    ("false_threat_of_arrest", 30.0),
+    ("threat_of_violence", 30.0),
+    ("false_threat_of_legal_action", 25.0),
+    ("misrepresented_amount", 20.0),
+    ("third_party_disclosure", 20.0),
+    ("contact_after_cease_request", 15.0),
+    ("harassment", 15.0),
+    ("calls_outside_permitted_hours", 10.0),
+    ("failure_to_validate_debt", 10.0),
+    ("missing_mini_miranda", 5.0),
+    ("wrong_number_call", 3.0),
+    ("inaccurate_credit_reporting", 20.0),
+    ("failure_to_investigate_dispute", 20.0),
+    ("robocall_without_consent", 10.0),
+    ("autodialed_text_without_consent", 10.0),
+];
+
+/// Months after the conduct within which a suit must be brought, by statute:
+/// one year under FDCPA (15 U.S.C. 1692k(d)), two under FCRA (15 U.S.C.
+/// 1681p, from discovery, which we take to be the conduct itself), four
+/// under TCPA (the federal catch-all, 28 U.S.C. 1658), and three for state
+/// UDAP claims, the most common period among the states.
+/// `LEVERAGE_LIMITATIONS_PATH` points at a JSON object of
+/// `{ "<statute>": months }` that overrides these.
+const DEFAULT_LIMITATIONS_MONTHS: [(Statute, u32); 4] = [
+    (Statute::Fdcpa, 12),
+    (Statute::Fcra, 24),
+    (Statute::Tcpa, 48),
+    (Statute::StateUdap, 36),
+];
+
+/// Reduction we can credibly ask for per leverage point, capped so a pile of
+/// minor violations never implies the debt disappears entirely.
+const REDUCTION_PER_POINT: f64 = 0.5;
+const MAX_REDUCTION_PERCENTAGE: f64 = 70.0;
+
+/// A time-barred debt may be unenforceable, which is worth far more than any
+/// single violation.
+const TIME_BARRED_REDUCTION_BONUS: f64 = 30.0;
+const MAX_TIME_BARRED_REDUCTION_PERCENTAGE: f64 = 90.0;
+
+/// State add-on rules shipped with the service; `LEVERAGE_JURISDICTIONS_PATH`
+/// points at a replacement file.
+const DEFAULT_JURISDICTIONS: &str = include_str!("../../config/jurisdictions.json");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JurisdictionRule {
+    pub statute: String,
+    /// State exposure relative to federal FDCPA exposure for the same conduct.
+    pub multiplier: f64,
+    /// Years after the last payment before collection is time-barred.
+    pub statute_of_limitations_years: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct LeverageEngine {
+    jurisdictions: Arc<HashMap<String, JurisdictionRule>>,
+    severity_weights: Arc<HashMap<String, f64>>,
+    limitations_months: Arc<HashMap<Statute, u32>>,
+}
+
+impl LeverageEngine {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let jurisdictions = match env::var("LEVERAGE_JURISDICTIONS_PATH") {
+            Ok(path) => {
+                let raw = std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading jurisdiction table {}", path))?;
+                parse_jurisdictions(&raw).with_context(|| format!("parsing jurisdiction table {}", path))?
+            }
+            Err(_) => parse_jurisdictions(DEFAULT_JURISDICTIONS)
+                .context("parsing built-in jurisdiction table")?,
+        };
+        
+        let mut severity_weights: HashMap<String, f64> = DEFAULT_SEVERITY_WEIGHTS
+            .iter()
+            .map(|(violation_type, points)| (violation_type.to_string(), *points))
+            .collect();
+        if let Ok(path) = env::var("LEVERAGE_SEVERITY_WEIGHTS_PATH") {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading severity weights {}", path))?;
+            let overrides: HashMap<String, f64> = serde_json::from_str(&raw)
+                .with_context(|| format!("parsing severity weights {}", path))?;
+            severity_weights.extend(
+                overrides
+                    .into_iter()
+                    .map(|(violation_type, points)| (violation_type.to_ascii_lowercase(), points)),
+            );
+        }
+        
+        let mut limitations_months: HashMap<Statute, u32> = DEFAULT_LIMITATIONS_MONTHS.into_iter().collect();
+        if let Ok(path) = env::var("LEVERAGE_LIMITATIONS_PATH") {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading limitations periods {}", path))?;
+            let overrides: HashMap<Statute, u32> = serde_json::from_str(&raw)
+                .with_context(|| format!("parsing limitations periods {}", path))?;
+            limitations_months.extend(overrides);
+        }
+        
+        info!(
+            "Loaded leverage rules for {} jurisdictions and {} violation types",
+            jurisdictions.len(),
+            severity_weights.len()
+        );
+        Ok(Self {
+            jurisdictions: Arc::new(jurisdictions),
+            severity_weights: Arc::new(severity_weights),
+            limitations_months: Arc::new(limitations_months),
+        })
+    }
+    
+    /// Leverage points one violation of `violation_type` is worth.
+    pub fn severity_weight(&self, violation_type: &str) -> f64 {
+        self.severity_weights
+            .get(&violation_type.trim().to_ascii_lowercase())
+            .copied()
+            .unwrap_or(POINTS_PER_VIOLATION)
+    }
+    
+    /// Whether the limitations period for `violation`'s statute has run by
+    /// `now`, so it can no longer be sued over. A period ends on the same
+    /// day of the month it began; at that instant the claim is gone.
+    pub fn claim_expired(&self, violation: &Violation, now: DateTime<Utc>) -> bool {
+        let Some(&months) = self.limitations_months.get(&violation.statute) else {
+            return false;
+        };
+        match violation.occurred_at.checked_add_months(Months::new(months)) {
+            Some(expires) => expires <= now,
+            None => false,
+        }
+    }
+    
+    /// Scores the violations, each weighted by its type's severity and raised
+    /// when evidence corroborates it, under federal law and, where we have
+    /// rules for `jurisdiction`, under state law too; the analysis reflects
+    /// whichever exposure is stronger. Each violation's contribution is listed
+    /// in `key_violations`, and those without evidence in
+    /// `unsubstantiated_violations`. The explanation has one factor per
+    /// violation, its severity weighted by repeats and evidence, plus one for
+    /// the state uplift when that applies.
+    ///
+    /// Each statute brings its own damage model: every TCPA call counts in
+    /// full where other repeats are capped, the state uplift applies to FDCPA
+    /// conduct only, and `exposure_by_statute` gives each statute's share of
+    /// the score with the statutory damages it allows.
+    ///
+    /// Violations whose limitations period has run by `now` carry no
+    /// exposure: they are left out of the score and the count, listed in
+    /// `expired_violations`, and noted once in `key_violations`.
+    pub fn analyze(&self, violations: &[Violation], jurisdiction: &str, now: DateTime<Utc>) -> LeverageAnalysis {
+        let (expired, violations): (Vec<&Violation>, Vec<&Violation>) =
+            violations.iter().partition(|violation| self.claim_expired(violation, now));
+        let violation_count = violations.len() as i32;
+        
+        let mut exposure: BTreeMap<Statute, StatuteExposure> = BTreeMap::new();
+        let mut key_violations = Vec::with_capacity(violations.len() + 1);
+        let mut unsubstantiated_violations = Vec::new();
+        let mut factors = Vec::with_capacity(violations.len() + 1);
+        for violation in violations {
+            let occurrences = violation.repeat_count as f64 + 1.0;
+            let repeat_weight = match violation.statute {
+                Statute::Tcpa => occurrences,
+                _ => 1.0 + (violation.repeat_count as f64 * REPEAT_BONUS_PER_OCCURRENCE).min(MAX_REPEAT_BONUS),
+            };
+            let corroboration_bonus = if violation.evidence.is_empty() {
+                unsubstantiated_violations.push(violation.id);
+                0.0
+            } else {
+                CORROBORATION_BONUS
+            };
+            let factor = Factor::new(
+                violation.violation_type.clone(),
+                self.severity_weight(&violation.violation_type),
+                repeat_weight * (1.0 + corroboration_bonus),
+            )
+            .for_violation(violation.id);
+            let points = factor.contribution;
+            factors.push(factor);
+            
+            let statute = exposure.entry(violation.statute).or_insert_with(|| StatuteExposure {
+                statute: violation.statute,
+                violation_count: 0,
+                leverage_score: 0.0,
+                actual_damages: 0.0,
+                statutory_damages_min: 0.0,
+                statutory_damages_max: 0.0,
+            });
+            statute.violation_count += 1;
+            statute.leverage_score += points;
+            statute.actual_damages += violation.estimated_damage;
+            if let Some((min, max)) = per_occurrence_damages(violation.statute) {
+                statute.statutory_damages_min += min * occurrences;
+                statute.statutory_damages_max += max * occurrences;
+            }
+            
+            key_violations.push(if violation.repeat_count > 0 {
+                format!(
+                    "{} x{} (+{})",
+                    violation.violation_type,
+                    violation.repeat_count + 1,
+                    points
+                )
+            } else {
+                format!("{} (+{})", violation.violation_type, points)
+            });
+        }
+        for statute in exposure.values_mut() {
+            if let Some((min, max)) = per_action_damages(statute.statute) {
+                statute.statutory_damages_min = min;
+                statute.statutory_damages_max = max;
+            }
+        }
+        
+        let jurisdiction = jurisdiction.trim().to_ascii_uppercase();
+        match (self.jurisdictions.get(&jurisdiction), exposure.get_mut(&Statute::Fdcpa)) {
+            (Some(rule), Some(fdcpa)) if rule.multiplier > 1.0 && fdcpa.leverage_score > 0.0 => {
+                key_violations.push(format!(
+                    "{} exposure under {} (x{})",
+                    jurisdiction, rule.statute, rule.multiplier
+                ));
+                let uplift = Factor::new(rule.statute.clone(), fdcpa.leverage_score, rule.multiplier - 1.0);
+                fdcpa.leverage_score += uplift.contribution;
+                factors.push(uplift);
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                key_violations.push(format!(
+                    "warning: no state rules for jurisdiction '{}', federal exposure only",
+                    jurisdiction
+                ));
+            }
+        }
+        if !expired.is_empty() {
+            key_violations.push(format!(
+                "{} violation(s) past their statute's limitations period left out",
+                expired.len()
+            ));
+        }
+        
+        let total_leverage_score: f64 = exposure.values().map(|statute| statute.leverage_score).sum();
+        let estimated_reduction_percentage =
+            (total_leverage_score * REDUCTION_PER_POINT).min(MAX_REDUCTION_PERCENTAGE);
+        let explanation = LeverageExplanation { factors };
+        debug_assert!(explanation.reconciles_to(total_leverage_score));
+        
+        LeverageAnalysis {
+            violation_count,
+            total_leverage_score,
+            estimated_reduction_percentage,
+            legal_strength: legal_strength(total_leverage_score).to_string(),
+            key_violations,
+            unsubstantiated_violations,
+            expired_violations: expired.iter().map(|violation| violation.id).collect(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: SolStatus::Unknown,
+            explanation,
+            exposure_by_statute: exposure.into_values().collect(),
+        }
+    }
+    
+    /// Statute-of-limitations status of `debt` in `jurisdiction` as of `now`.
+    /// Without a last-payment date or a known SOL period this is `Unknown`;
+    /// we never guess.
+    pub fn sol_status(&self, debt: &Debt, jurisdiction: &str, now: DateTime<Utc>) -> SolStatus {
+        let years = self
+            .jurisdictions
+            .get(&jurisdiction.trim().to_ascii_uppercase())
+            .and_then(|rule| rule.statute_of_limitations_years);
+        
+        let (Some(last_payment), Some(years)) = (debt.last_payment_date, years) else {
+            return SolStatus::Unknown;
+        };
+        
+        match last_payment.checked_add_months(Months::new(years * 12)) {
+            Some(expires) if expires <= now => SolStatus::TimeBarred,
+            _ => SolStatus::WithinPeriod,
+        }
+    }
+    
+    /// Folds the SOL status into an analysis. A time-barred debt gets a
+    /// `TimeBarred` key violation and a substantially larger reduction.
+    pub fn apply_statute_of_limitations(&self, analysis: &mut LeverageAnalysis, status: SolStatus) {
+        analysis.statute_of_limitations = status;
+        
+        if status == SolStatus::TimeBarred {
+            analysis.key_violations.insert(0, "TimeBarred".to_string());
+            analysis.estimated_reduction_percentage = (analysis.estimated_reduction_percentage
+                + TIME_BARRED_REDUCTION_BONUS)
+                .min(MAX_TIME_BARRED_REDUCTION_PERCENTAGE);
+            analysis.legal_strength = "very_strong".to_string();
+        }
+    }
+}
+
+/// Statutory damages, in USD, each call or violation can carry on its own:
+/// TCPA allows $500 per call, trebled to $1,500 when willful.
+fn per_occurrence_damages(statute: Statute) -> Option<(f64, f64)> {
+    match statute {
+        Statute::Tcpa => Some((500.0, 1500.0)),
+        _ => None,
+    }
+}
+
+/// Statutory damages, in USD, capped per action however many violations it
+/// covers: up to $1,000 under FDCPA (15 U.S.C. 1692k) and $100 to $1,000 for
+/// willful FCRA noncompliance (15 U.S.C. 1681n). State UDAP damages vary too
+/// much by state to model; only actual damages count there.
+fn per_action_damages(statute: Statute) -> Option<(f64, f64)> {
+    match statute {
+        Statute::Fdcpa => Some((0.0, 1000.0)),
+        Statute::Fcra => Some((100.0, 1000.0)),
+        Statute::Tcpa | Statute::StateUdap => None,
+    }
+}
+
+fn parse_jurisdictions(raw: &str) -> anyhow::Result<HashMap<String, JurisdictionRule>> {
+    let table: HashMap<String, JurisdictionRule> = serde_json::from_str(raw)?;
+    
+    Ok(table
+        .into_iter()
+        .map(|(code, rule)| (code.to_ascii_uppercase(), rule))
+        .collect())
+}
+
+pub fn legal_strength(total_leverage_score: f64) -> &'static str {
+    match total_leverage_score {
+        s if s >= 60.0 => "very_strong",
+        s if s >= 30.0 => "strong",
+        s if s >= 10.0 => "moderate",
+        _ => "weak",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+    
+    use super::*;
+    use crate::models::{EvidenceRef, EvidenceType};
+    
+    fn violation() -> Violation {
+        Violation {
+            id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            violation_type: "harassment".to_string(),
+            statute: Statute::Fdcpa,
+            severity: "medium".to_string(),
+            confidence: 0.9,
+            legal_reference: "15 U.S.C. 1692d".to_string(),
+            estimated_damage: 1000.0,
+            occurred_at: Utc::now(),
+            created_at: Utc::now(),
+            repeat_count: 0,
+            evidence: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn evidence_corroborates_and_its_absence_is_flagged() {
+        let engine = LeverageEngine::from_env().unwrap();
+        let bare = violation();
+        let mut backed = violation();
+        backed.evidence.push(EvidenceRef {
+            id: Uuid::new_v4(),
+            violation_id: backed.id,
+            evidence_type: EvidenceType::CallRecording,
+            sha256: "0".repeat(64),
+            content_type: "audio/mpeg".to_string(),
+            storage_key: format!("violations/{}/recording", backed.id),
+            created_at: Utc::now(),
+        });
+        
+        let unsubstantiated = engine.analyze(std::slice::from_ref(&bare), "CA", Utc::now());
+        let corroborated = engine.analyze(std::slice::from_ref(&backed), "CA", Utc::now());
+        
+        assert!(corroborated.total_leverage_score > unsubstantiated.total_leverage_score);
+        assert_eq!(unsubstantiated.unsubstantiated_violations, [bare.id]);
+        assert!(corroborated.unsubstantiated_violations.is_empty());
+    }
+    
+    #[test]
+    fn explanation_reconciles_to_the_total_score() {
+        let engine = LeverageEngine::from_env().unwrap();
+        let mut repeated = violation();
+        repeated.violation_type = "false_threat_of_arrest".to_string();
+        repeated.repeat_count = 3;
+        let mut corroborated = violation();
+        corroborated.evidence.push(EvidenceRef {
+            id: Uuid::new_v4(),
+            violation_id: corroborated.id,
+            evidence_type: EvidenceType::CallRecording,
+            sha256: "0".repeat(64),
+            content_type: "audio/mpeg".to_string(),
+            storage_key: format!("violations/{}/recording", corroborated.id),
+            created_at: Utc::now(),
+        });
+        let violations = [violation(), repeated, corroborated];
+        
+        for jurisdiction in ["CA", "TX", "ZZ"] {
+            let analysis = engine.analyze(&violations, jurisdiction, Utc::now());
+            let factors = &analysis.explanation.factors;
+            
+            assert!(
+                analysis.explanation.reconciles_to(analysis.total_leverage_score),
+                "{}: {} != {}",
+                jurisdiction,
+                analysis.explanation.total(),
+                analysis.total_leverage_score
+            );
+            assert!(factors.iter().all(|factor| factor.contribution == factor.input * factor.weight));
+            assert_eq!(factors[1].input, 30.0);
+            assert_eq!(factors[2].weight, 1.0 + CORROBORATION_BONUS);
+        }
+        assert_eq!(engine.analyze(&violations, "CA", Utc::now()).explanation.factors.len(), 4);
+        assert_eq!(engine.analyze(&violations, "ZZ", Utc::now()).explanation.factors.len(), 3);
+    }
+    
+    #[test]
+    fn each_statute_applies_its_own_damage_model() {
+        let engine = LeverageEngine::from_env().unwrap();
+        let harassment = violation();
+        let mut robocalls = violation();
+        robocalls.violation_type = "robocall_without_consent".to_string();
+        robocalls.statute = Statute::Tcpa;
+        robocalls.repeat_count = 9;
+        let mut reporting = violation();
+        reporting.violation_type = "inaccurate_credit_reporting".to_string();
+        reporting.statute = Statute::Fcra;
+        
+        let analysis = engine.analyze(&[harassment, robocalls, reporting], "CA", Utc::now());
+        let exposure = |statute| {
+            analysis
+                .exposure_by_statute
+                .iter()
+                .find(|exposure| exposure.statute == statute)
+                .unwrap()
+        };
+        
+        let tcpa = exposure(Statute::Tcpa);
+        assert_eq!(tcpa.leverage_score, 100.0);
+        assert_eq!((tcpa.statutory_damages_min, tcpa.statutory_damages_max), (5000.0, 15000.0));
+        // The Rosenthal Act doubles FDCPA conduct only.
+        assert_eq!(exposure(Statute::Fdcpa).leverage_score, 30.0);
+        assert_eq!(exposure(Statute::Fdcpa).statutory_damages_max, 1000.0);
+        assert_eq!(exposure(Statute::Fcra).leverage_score, 20.0);
+        assert_eq!(exposure(Statute::Fcra).actual_damages, 1000.0);
+        assert_eq!(analysis.exposure_by_statute.len(), 3);
+        assert_eq!(analysis.total_leverage_score, 150.0);
+    }
+    
+    #[test]
+    fn violations_drop_out_when_their_limitations_period_runs() {
+        let engine = LeverageEngine::from_env().unwrap();
+        let now = Utc::now();
+        let mut expired = violation();
+        expired.occurred_at = now.checked_sub_months(Months::new(12)).unwrap();
+        let mut live = violation();
+        live.occurred_at = expired.occurred_at + chrono::Duration::seconds(1);
+        // TCPA allows four years, so the same age leaves it actionable.
+        let mut robocall = violation();
+        robocall.statute = Statute::Tcpa;
+        robocall.occurred_at = expired.occurred_at;
+        
+        assert!(engine.claim_expired(&expired, now));
+        assert!(!engine.claim_expired(&live, now));
+        assert!(!engine.claim_expired(&robocall, now));
+        
+        let analysis = engine.analyze(&[expired.clone(), live.clone(), robocall], "ZZ", now);
+        let alone = engine.analyze(&[live], "ZZ", now);
+        assert_eq!(analysis.expired_violations, [expired.id]);
+        assert_eq!(analysis.violation_count, 2);
+        assert_eq!(analysis.explanation.factors.len(), 2);
+        assert_eq!(
+            exposure_of(&analysis, Statute::Fdcpa).leverage_score,
+            exposure_of(&alone, Statute::Fdcpa).leverage_score
+        );
+        assert!(analysis.key_violations.iter().any(|note| note.starts_with("1 violation(s) past")));
+    }
+    
+    fn exposure_of(analysis: &LeverageAnalysis, statute: Statute) -> &StatuteExposure {
+        analysis
+            .exposure_by_statute
+            .iter()
+            .find(|exposure| exposure.statute == statute)
+            .unwrap()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,206 @@
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramTimer, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::blockchain::CircuitState;
+use crate::database::PoolStatus;
+use crate::models::SettlementStatus;
+
+/// Prometheus series for the settlement service. Cloning is cheap; every
+/// clone records into the same registry.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    settlements: IntCounterVec,
+    ai_latency: HistogramVec,
+    ai_timeouts: IntCounterVec,
+    cardano_submission_latency: Histogram,
+    cardano_submission_failures: IntCounter,
+    reconciliation_mismatches: IntCounter,
+    in_flight_requests: IntGauge,
+    db_connections_in_use: IntGauge,
+    db_connections_idle: IntGauge,
+    db_connections_max: IntGauge,
+    cardano_circuit_state: IntGaugeVec,
+    event_streams: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        
+        let settlements = IntCounterVec::new(
+            Opts::new(
+                "settlements_total",
+                "Settlements entering each status (proposed on creation)",
+            ),
+            &["status"],
+        )
+        .unwrap();
+        let ai_latency = HistogramVec::new(
+            HistogramOpts::new("ai_request_duration_seconds", "AI service call latency"),
+            &["operation"],
+        )
+        .unwrap();
+        let ai_timeouts = IntCounterVec::new(
+            Opts::new("ai_timeouts_total", "AI service calls that exceeded their time budget"),
+            &["operation"],
+        )
+        .unwrap();
+        let cardano_submission_latency = Histogram::with_opts(HistogramOpts::new(
+            "cardano_submission_duration_seconds",
+            "Cardano transaction submission latency",
+        ))
+        .unwrap();
+        let cardano_submission_failures = IntCounter::new(
+            "cardano_submission_failures_total",
+            "Failed Cardano transaction submissions",
+        )
+        .unwrap();
+        let reconciliation_mismatches = IntCounter::new(
+            "settlement_reconciliation_mismatches_total",
+            "Completed settlements reconciliation found without a transaction on-chain",
+        )
+        .unwrap();
+        let in_flight_requests =
+            IntGauge::new("http_requests_in_flight", "HTTP requests currently being served").unwrap();
+        let db_connections_in_use =
+            IntGauge::new("db_pool_connections_in_use", "Database connections checked out of the pool").unwrap();
+        let db_connections_idle =
+            IntGauge::new("db_pool_connections_idle", "Open database connections waiting in the pool").unwrap();
+        let db_connections_max =
+            IntGauge::new("db_pool_max_connections", "Most connections the pool will open").unwrap();
+        let cardano_circuit_state = IntGaugeVec::new(
+            Opts::new(
+                "cardano_circuit_breaker_state",
+                "1 for the state the Cardano node's circuit breaker is in, 0 for the others",
+            ),
+            &["state"],
+        )
+        .unwrap();
+        let event_streams =
+            IntGauge::new("settlement_event_streams_open", "Settlement event streams open on this instance").unwrap();
+        
+        registry.register(Box::new(settlements.clone())).unwrap();
+        registry.register(Box::new(ai_latency.clone())).unwrap();
+        registry.register(Box::new(ai_timeouts.clone())).unwrap();
+        registry.register(Box::new(cardano_submission_latency.clone())).unwrap();
+        registry.register(Box::new(cardano_submission_failures.clone())).unwrap();
+        registry.register(Box::new(reconciliation_mismatches.clone())).unwrap();
+        registry.register(Box::new(in_flight_requests.clone())).unwrap();
+        registry.register(Box::new(db_connections_in_use.clone())).unwrap();
+        registry.register(Box::new(db_connections_idle.clone())).unwrap();
+        registry.register(Box::new(db_connections_max.clone())).unwrap();
+        registry.register(Box::new(cardano_circuit_state.clone())).unwrap();
+        registry.register(Box::new(event_streams.clone())).unwrap();
+        
+        Self {
+            registry,
+            settlements,
+            ai_latency,
+            ai_timeouts,
+            cardano_submission_latency,
+            cardano_submission_failures,
+            reconciliation_mismatches,
+            in_flight_requests,
+            db_connections_in_use,
+            db_connections_idle,
+            db_connections_max,
+            cardano_circuit_state,
+            event_streams,
+        }
+    }
+    
+    pub fn settlement_entered(&self, status: SettlementStatus) {
+        self.settlements.with_label_values(&[status_label(status)]).inc();
+    }
+    
+    /// Observes the AI call's latency when the returned timer is dropped.
+    pub fn ai_timer(&self, operation: &str) -> HistogramTimer {
+        self.ai_latency.with_label_values(&[operation]).start_timer()
+    }
+    
+    pub fn ai_timed_out(&self, operation: &str) {
+        self.ai_timeouts.with_label_values(&[operation]).inc();
+    }
+    
+    pub fn cardano_submission_timer(&self) -> HistogramTimer {
+        self.cardano_submission_latency.start_timer()
+    }
+    
+    pub fn cardano_submission_failed(&self) {
+        self.cardano_submission_failures.inc();
+    }
+    
+    /// Alert on any increase: money the database says moved may not have.
+    pub fn reconciliation_mismatch(&self) {
+        self.reconciliation_mismatches.inc();
+    }
+    
+    /// Counts a request as in flight until the guard is dropped, including when
+    /// the client disconnects and the request future is cancelled.
+    pub fn track_in_flight(&self) -> InFlightGuard {
+        self.in_flight_requests.inc();
+        InFlightGuard(self.in_flight_requests.clone())
+    }
+    
+    /// Pool saturation is sampled rather than counted, so this is called
+    /// just before each scrape.
+    pub fn observe_db_pool(&self, status: PoolStatus) {
+        self.db_connections_in_use.set(status.in_use.into());
+        self.db_connections_idle.set(status.idle.into());
+        self.db_connections_max.set(status.max.into());
+    }
+    
+    /// Sampled before each scrape, like the pool; a client without a breaker
+    /// exports nothing.
+    pub fn observe_cardano_circuit(&self, state: Option<CircuitState>) {
+        let Some(state) = state else {
+            return;
+        };
+        for candidate in [CircuitState::Closed, CircuitState::Open, CircuitState::HalfOpen] {
+            self.cardano_circuit_state
+                .with_label_values(&[candidate.label()])
+                .set((candidate == state).into());
+        }
+    }
+    
+    /// Sampled before each scrape, like the pool, so a stream that was
+    /// dropped without cleaning up would show here.
+    pub fn observe_event_streams(&self, open: usize) {
+        self.event_streams.set(open as i64);
+    }
+    
+    /// Renders every series in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("encoding metrics into a Vec cannot fail");
+        String::from_utf8(buffer).expect("Prometheus text format is UTF-8")
+    }
+}
+
+pub struct InFlightGuard(IntGauge);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+fn status_label(status: SettlementStatus) -> &'static str {
+    match status {
+        SettlementStatus::Proposed => "proposed",
+        SettlementStatus::Negotiating => "negotiating",
+        SettlementStatus::Accepted => "accepted",
+        SettlementStatus::Rejected => "rejected",
+        SettlementStatus::Completed => "completed",
+        SettlementStatus::Failed => "failed",
+        SettlementStatus::Expired => "expired",
+        SettlementStatus::NeedsReview => "needs_review",
+        SettlementStatus::Escrowed => "escrowed",
+        SettlementStatus::Refunded => "refunded",
+    }
+}
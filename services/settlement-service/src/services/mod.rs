@@ -0,0 +1,26 @@
+pub mod settlement_engine;
+pub mod ai_client;
+pub mod agreement;
+pub mod demand_letter;
+pub mod event_stream;
+pub mod evidence;
+pub mod leverage;
+pub mod metrics;
+pub mod notifications;
+pub mod pdf;
+pub mod prompts;
+pub mod retry;
+pub mod shutdown;
+pub mod simulation;
+pub mod triggers;
+pub mod webhooks;
+
+/// Reads `key` from the environment, falling back to `default` when it is
+/// unset or doesn't parse. [`crate::config::Config::load`] has already
+/// refused to start on a setting it checks that doesn't.
+pub fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde_json::json;
+use tracing::{info, warn, Instrument};
+
+use crate::i18n::Locale;
+use crate::models::Settlement;
+use crate::services::shutdown::Shutdown;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many hours before a negotiation expires each of its reminders goes
+/// out, longest first. `SETTLEMENT_REMINDER_HOURS` lists them, e.g. `72,24`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReminderHours(Vec<i32>);
+
+impl ReminderHours {
+    pub fn hours(&self) -> &[i32] {
+        &self.0
+    }
+}
+
+impl Default for ReminderHours {
+    fn default() -> Self {
+        Self(vec![72, 24])
+    }
+}
+
+impl FromStr for ReminderHours {
+    type Err = String;
+    
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let mut hours = raw
+            .split(',')
+            .map(|entry| match entry.trim().parse::<i32>() {
+                Ok(hours) if hours > 0 => Ok(hours),
+                _ => Err(format!("{:?} is not a positive number of hours", entry.trim())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        hours.sort_unstable_by(|a, b| b.cmp(a));
+        hours.dedup();
+        Ok(Self(hours))
+    }
+}
+
+/// Sends users in-app notifications through the notification service.
+/// Creditors hear about settlements through their webhooks; this is how a
+/// debtor hears about terms a creditor offered them.
+#[derive(Clone)]
+pub struct UserNotifier {
+    http: reqwest::Client,
+    /// Base URL of the notification service; without one notifications are
+    /// only logged.
+    base_url: Option<String>,
+    shutdown: Shutdown,
+}
+
+impl UserNotifier {
+    /// Reads `NOTIFICATION_SERVICE_URL`.
+    pub fn from_env(shutdown: Shutdown) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build notification HTTP client");
+        
+        Self {
+            http,
+            base_url: std::env::var("NOTIFICATION_SERVICE_URL")
+                .ok()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            shutdown,
+        }
+    }
+    
+    /// Tells the user, in `locale`, that a creditor has offered them
+    /// `settlement`. Sent once in the background; never blocks or fails the
+    /// caller.
+    pub fn settlement_offered(&self, settlement: &Settlement, locale: &Locale) {
+        let Some(base_url) = &self.base_url else {
+            info!(
+                "Settlement {} was offered to user {}; NOTIFICATION_SERVICE_URL is unset, so they were not notified",
+                settlement.id, settlement.user_id
+            );
+            return;
+        };
+        
+        let body = json!({
+            "userId": settlement.user_id,
+            "type": "settlement_opportunity",
+            "title": locale.message("notification.offer.title", &[]),
+            "message": locale.message(
+                "notification.offer.message",
+                &[
+                    ("original_amount", settlement.original_amount.to_string()),
+                    ("settled_amount", settlement.settled_amount.to_string()),
+                    ("currency", settlement.currency.to_string()),
+                    ("saved_amount", settlement.saved_amount.to_string()),
+                    ("expires_on", settlement.expires_at.format("%Y-%m-%d").to_string()),
+                ],
+            ),
+            "locale": locale,
+            "data": {
+                "settlement_id": settlement.id,
+                "reference_number": settlement.reference_number,
+                "debt_id": settlement.debt_id,
+                "settled_amount": settlement.settled_amount,
+                "expires_at": settlement.expires_at,
+            },
+            "priority": "high",
+        });
+        self.send(base_url, settlement, body);
+    }
+    
+    /// Reminds the user that their negotiation of `settlement` expires within
+    /// `threshold_hours`. Sent like [`Self::settlement_offered`].
+    pub fn deadline_approaching(&self, settlement: &Settlement, threshold_hours: i32, locale: &Locale) {
+        let Some(base_url) = &self.base_url else {
+            info!(
+                "Settlement {} expires within {}h; NOTIFICATION_SERVICE_URL is unset, so user {} was not reminded",
+                settlement.id, threshold_hours, settlement.user_id
+            );
+            return;
+        };
+        
+        let body = json!({
+            "userId": settlement.user_id,
+            "type": "settlement_deadline",
+            "title": locale.message("notification.reminder.title", &[]),
+            "message": locale.message(
+                "notification.reminder.message",
+                &[
+                    ("settled_amount", settlement.settled_amount.to_string()),
+                    ("currency", settlement.currency.to_string()),
+                    ("hours", threshold_hours.to_string()),
+                    ("expires_on", settlement.expires_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+                ],
+            ),
+            "locale": locale,
+            "data": {
+                "settlement_id": settlement.id,
+                "reference_number": settlement.reference_number,
+                "settled_amount": settlement.settled_amount,
+                "expires_at": settlement.expires_at,
+                "threshold_hours": threshold_hours,
+            },
+            "priority": "high",
+        });
+        self.send(base_url, settlement, body);
+    }
+    
+    fn send(&self, base_url: &str, settlement: &Settlement, body: serde_json::Value) {
+        let url = format!("{}/api/notifications/send", base_url);
+        let request = self.http.post(url).json(&body);
+        let settlement_id = settlement.id;
+        let guard = self.shutdown.track();
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                match request.send().await.and_then(|response| response.error_for_status()) {
+                    Ok(_) => info!("Notified the user of settlement {}", settlement_id),
+                    Err(e) => warn!("Notifying the user of settlement {} failed: {}", settlement_id, e),
+                }
+            }
+            .in_current_span(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn reminder_hours_are_positive_and_longest_first() {
+        assert_eq!("24, 72,24".parse::<ReminderHours>().unwrap().hours(), [72, 24]);
+        assert_eq!(ReminderHours::default().hours(), [72, 24]);
+        assert!("72,0".parse::<ReminderHours>().is_err());
+        assert!("72,soon".parse::<ReminderHours>().is_err());
+    }
+}
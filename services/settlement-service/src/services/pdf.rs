@@ -0,0 +1,96 @@
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+/// A4, in millimetres.
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 20.0;
+
+/// Characters per body line; Helvetica at 10pt averages about half an em a
+/// glyph, which fills the text width between the margins.
+const WRAP_COLUMNS: usize = 95;
+
+const TITLE_SIZE: f32 = 16.0;
+const HEADING_SIZE: f32 = 12.0;
+const BODY_SIZE: f32 = 10.0;
+
+/// Lays `text` out as a PDF in the standard Helvetica fonts, so no font
+/// files need to ship with the service. A line starting `# ` is a title and
+/// one starting `## ` a section heading; the rest is body text, wrapped to
+/// the page, with the continuation lines of a `- ` item indented.
+pub fn render_text(title: String, text: &str) -> anyhow::Result<Vec<u8>> {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "text");
+    let mut writer = PageWriter {
+        layer: doc.get_page(page).get_layer(layer),
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+        doc,
+        y: PAGE_HEIGHT_MM - MARGIN_MM,
+    };
+    
+    for line in text.lines() {
+        if let Some(title) = line.strip_prefix("# ") {
+            writer.line(title, TITLE_SIZE, true, 0.0);
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            writer.line(heading, HEADING_SIZE, true, 0.0);
+        } else if line.trim().is_empty() {
+            writer.skip(BODY_SIZE / 2.0);
+        } else {
+            let indent = if line.starts_with("- ") { 4.0 } else { 0.0 };
+            for (i, wrapped) in wrap(line, WRAP_COLUMNS).iter().enumerate() {
+                writer.line(wrapped, BODY_SIZE, false, if i == 0 { 0.0 } else { indent });
+            }
+        }
+    }
+    
+    Ok(writer.doc.save_to_bytes()?)
+}
+
+struct PageWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Baseline of the last line written, from the bottom of the page.
+    y: f32,
+}
+
+impl PageWriter {
+    fn line(&mut self, text: &str, size: f32, bold: bool, indent: f32) {
+        self.skip(size);
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(MARGIN_MM + indent), Mm(self.y), font);
+    }
+    
+    /// Moves down one line of `size` points, starting a new page when the
+    /// bottom margin is reached.
+    fn skip(&mut self, size: f32) {
+        // 1.4x leading; a point is 0.353mm.
+        let height = size * 1.4 * 0.353;
+        if self.y - height < MARGIN_MM {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "text");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT_MM - MARGIN_MM;
+        }
+        self.y -= height;
+    }
+}
+
+/// Greedy word wrap; a word longer than `columns` gets a line to itself.
+fn wrap(line: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    
+    for word in line.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > columns {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
@@ -0,0 +1,235 @@
+use std::env;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+
+const DEFAULT_PROPOSAL: &str = include_str!("../../templates/prompts/proposal.txt");
+const DEFAULT_COUNTER_OFFER: &str = include_str!("../../templates/prompts/counter_offer.txt");
+
+/// Placeholders the proposal prompt may use; the first two are required.
+const PROPOSAL_PLACEHOLDERS: &[&str] = &[
+    "violation_count",
+    "original_amount",
+    "current_amount",
+    "currency",
+    "legal_strength",
+    "key_violations",
+    "estimated_reduction_percentage",
+    "strategy",
+    "target_reduction_percentage",
+];
+const PROPOSAL_REQUIRED: usize = 2;
+
+/// Placeholders the counter-offer prompt may use; the first three are required.
+const COUNTER_OFFER_PLACEHOLDERS: &[&str] = &[
+    "original_amount",
+    "proposed_amount",
+    "offer_amount",
+    "currency",
+    "offer_from",
+    "round",
+    "strategy",
+    "concession_percent",
+];
+const COUNTER_OFFER_REQUIRED: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder(&'static str),
+}
+
+/// A prompt split into literal text and `{name}` placeholders. `{{` and `}}`
+/// stand for literal braces.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    segments: Vec<Segment>,
+}
+
+impl PromptTemplate {
+    /// Fails on unbalanced braces, placeholders not in `known` and any of
+    /// the first `required` of them missing.
+    fn parse(raw: &str, known: &[&'static str], required: usize) -> anyhow::Result<Self> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = raw.chars().peekable();
+        
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!("unclosed placeholder {{{}", name),
+                        }
+                    }
+                    let Some(placeholder) = known.iter().find(|known| **known == name) else {
+                        bail!("unknown placeholder {{{}}}", name);
+                    };
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Placeholder(placeholder));
+                }
+                '}' => bail!("unmatched '}}'; write '}}}}' for a literal brace"),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        
+        let template = Self { segments };
+        for name in &known[..required] {
+            if !template.uses(name) {
+                bail!("required placeholder {{{}}} is missing", name);
+            }
+        }
+        Ok(template)
+    }
+    
+    fn uses(&self, name: &str) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Placeholder(placeholder) if *placeholder == name))
+    }
+    
+    /// Fills every placeholder from `values`; one without a value renders empty.
+    pub fn render(&self, values: &[(&str, String)]) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.as_str(),
+                Segment::Placeholder(name) => values
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.as_str())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// The prompts sent to the AI service, and a version identifying them that
+/// is recorded on every settlement they produce.
+#[derive(Debug, Clone)]
+pub struct PromptTemplates {
+    pub proposal: PromptTemplate,
+    pub counter_offer: PromptTemplate,
+    /// First 12 hex digits of the SHA-256 of both templates, so any edit
+    /// yields a new version.
+    pub version: String,
+}
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        Self::parse(DEFAULT_PROPOSAL, DEFAULT_COUNTER_OFFER).expect("built-in prompt templates are valid")
+    }
+}
+
+impl PromptTemplates {
+    /// Reads `proposal.txt` and `counter_offer.txt` from
+    /// `AI_PROMPT_TEMPLATES_DIR` when it is set, using the built-in template
+    /// for a file that isn't there. A malformed template is an error, so the
+    /// service refuses to start rather than send a broken prompt.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let Ok(dir) = env::var("AI_PROMPT_TEMPLATES_DIR") else {
+            return Ok(Self::default());
+        };
+        let dir = Path::new(&dir);
+        let proposal = read_or(dir, "proposal.txt", DEFAULT_PROPOSAL)?;
+        let counter_offer = read_or(dir, "counter_offer.txt", DEFAULT_COUNTER_OFFER)?;
+        
+        Self::parse(&proposal, &counter_offer)
+            .with_context(|| format!("loading prompt templates from {}", dir.display()))
+    }
+    
+    fn parse(proposal: &str, counter_offer: &str) -> anyhow::Result<Self> {
+        let mut hasher = Sha256::new();
+        hasher.update(proposal.as_bytes());
+        hasher.update([0]);
+        hasher.update(counter_offer.as_bytes());
+        
+        Ok(Self {
+            proposal: PromptTemplate::parse(proposal, PROPOSAL_PLACEHOLDERS, PROPOSAL_REQUIRED)
+                .context("proposal.txt")?,
+            counter_offer: PromptTemplate::parse(counter_offer, COUNTER_OFFER_PLACEHOLDERS, COUNTER_OFFER_REQUIRED)
+                .context("counter_offer.txt")?,
+            version: format!("{:x}", hasher.finalize())[..12].to_string(),
+        })
+    }
+}
+
+fn read_or(dir: &Path, name: &str, default: &str) -> anyhow::Result<String> {
+    let path = dir.join(name);
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => Ok(raw),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(default.to_string()),
+        Err(e) => Err(e).with_context(|| format!("reading prompt template {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn built_in_templates_are_valid() {
+        let templates = PromptTemplates::default();
+        
+        assert_eq!(templates.version.len(), 12);
+        assert!(templates.proposal.uses("violation_count"));
+        assert!(templates.counter_offer.uses("offer_amount"));
+    }
+    
+    #[test]
+    fn placeholders_render_and_braces_escape() {
+        let template = PromptTemplate::parse(
+            "{violation_count} violations on {original_amount} {{literal}}",
+            PROPOSAL_PLACEHOLDERS,
+            PROPOSAL_REQUIRED,
+        )
+        .unwrap();
+        
+        let rendered = template.render(&[
+            ("violation_count", "3".to_string()),
+            ("original_amount", "1000.00".to_string()),
+        ]);
+        assert_eq!(rendered, "3 violations on 1000.00 {literal}");
+    }
+    
+    #[test]
+    fn malformed_templates_are_rejected() {
+        let parse = |raw| {
+            PromptTemplate::parse(raw, PROPOSAL_PLACEHOLDERS, PROPOSAL_REQUIRED)
+                .unwrap_err()
+                .to_string()
+        };
+        
+        assert!(parse("{original_amount} only").contains("{violation_count} is missing"));
+        assert!(parse("{violation_count} {original_amount} {debtor_name}").contains("unknown placeholder {debtor_name}"));
+        assert!(parse("{violation_count} {original_amount} }").contains("unmatched"));
+        assert!(parse("{violation_count} {original_amount").contains("unclosed placeholder"));
+    }
+    
+    #[test]
+    fn editing_a_template_changes_the_version() {
+        let edited = format!("{}\nBe concise.", DEFAULT_PROPOSAL);
+        
+        assert_ne!(
+            PromptTemplates::parse(&edited, DEFAULT_COUNTER_OFFER).unwrap().version,
+            PromptTemplates::default().version
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::future::Future;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::StatusCode;
+use tracing::warn;
+
+use crate::services::ai_client::is_timeout;
+
+/// How a call to an upstream service is retried when it fails in a way that
+/// may pass: a timeout, a connection that didn't go through, or a 5xx or 429
+/// answer. Anything else, such as a 4xx or a response that doesn't parse,
+/// would fail the same way again and is returned at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in all, the first included; 1 never retries.
+    pub max_attempts: u32,
+    /// Pause after the first failure; it doubles after each one after that.
+    pub base_delay: Duration,
+    /// The longest pause, however many attempts have failed.
+    pub max_delay: Duration,
+    /// Whether each pause is picked at random from its upper half, so
+    /// callers that failed together don't all retry together.
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Calls the AI service: the rules fallback stands in for it, so it
+    /// isn't waited on for long.
+    pub const AI: Self = Self {
+        max_attempts: 2,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+        jitter: true,
+    };
+    
+    /// Calls the Cardano node gateway, which dedupes repeated submissions
+    /// by their idempotency key.
+    pub const CARDANO: Self = Self {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(250),
+        max_delay: Duration::from_secs(2),
+        jitter: true,
+    };
+    
+    /// The pause after `failures` failed attempts.
+    pub fn delay(&self, failures: u32) -> Duration {
+        let doubled = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)));
+        let delay = doubled.min(self.max_delay);
+        if self.jitter && !delay.is_zero() {
+            rand::thread_rng().gen_range(delay / 2..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Whether `e` may not happen again: a timeout, a connection that failed,
+/// or an upstream answer of 5xx or 429.
+pub fn is_retryable(e: &anyhow::Error) -> bool {
+    if is_timeout(e) {
+        return true;
+    }
+    match e.downcast_ref::<reqwest::Error>() {
+        Some(e) if e.is_timeout() || e.is_connect() => true,
+        Some(e) => e.status().is_some_and(is_retryable_status),
+        None => false,
+    }
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Calls `op` until it succeeds, fails in a way that isn't retryable, or
+/// has been tried `policy.max_attempts` times, and returns the last error.
+/// `call` names the call in the warnings.
+pub async fn retry_with<T, F, Fut>(policy: &RetryPolicy, call: &str, mut op: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut failures = 0;
+    loop {
+        let e = match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        failures += 1;
+        if failures >= policy.max_attempts || !is_retryable(&e) {
+            return Err(e);
+        }
+        let delay = policy.delay(failures);
+        warn!(
+            "{} failed on attempt {}/{}: {}; retrying in {:?}",
+            call, failures, policy.max_attempts, e, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    
+    use anyhow::anyhow;
+    
+    use super::*;
+    use crate::services::ai_client::AiTimeout;
+    
+    const QUICK: RetryPolicy = RetryPolicy {
+        max_attempts: 4,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        jitter: false,
+    };
+    
+    /// A server that answers every request with `status`, counting them.
+    async fn answering(status: u16) -> (String, std::sync::Arc<AtomicU32>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(AtomicU32::new(0));
+        let counted = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                counted.fetch_add(1, Ordering::SeqCst);
+                let response = format!("HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, requests)
+    }
+    
+    async fn get(url: &str) -> anyhow::Result<()> {
+        reqwest::get(url).await?.error_for_status()?;
+        Ok(())
+    }
+    
+    #[tokio::test]
+    async fn transient_failures_are_retried_until_they_pass() {
+        let calls = AtomicU32::new(0);
+        let answer = retry_with(&QUICK, "flaky", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(AiTimeout { call: "flaky", budget: Duration::ZERO }.into()),
+                _ => Ok(42),
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(answer, 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        
+        let (url, requests) = answering(503).await;
+        let e = retry_with(&QUICK, "unavailable", || get(&url)).await.unwrap_err();
+        assert!(is_retryable(&e), "{}", e);
+        assert_eq!(requests.load(Ordering::SeqCst), QUICK.max_attempts);
+    }
+    
+    #[tokio::test]
+    async fn deterministic_failures_stop_at_once() {
+        let (url, requests) = answering(422).await;
+        let e = retry_with(&QUICK, "invalid", || get(&url)).await.unwrap_err();
+        assert!(!is_retryable(&e), "{}", e);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        
+        let (url, requests) = answering(404).await;
+        retry_with(&QUICK, "missing", || get(&url)).await.unwrap_err();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        
+        let calls = AtomicU32::new(0);
+        let e = retry_with(&QUICK, "malformed", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow!("invalid response from upstream"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(e.to_string(), "invalid response from upstream");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    fn pauses_double_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 6,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: false,
+        };
+        let pauses: Vec<u64> = (1..=4).map(|failures| policy.delay(failures).as_millis() as u64).collect();
+        assert_eq!(pauses, [100, 200, 350, 350]);
+        
+        let jittered = RetryPolicy { jitter: true, ..policy };
+        for failures in 1..=4 {
+            let pause = jittered.delay(failures);
+            assert!(pause >= policy.delay(failures) / 2 && pause <= policy.delay(failures), "{:?}", pause);
+        }
+    }
+}
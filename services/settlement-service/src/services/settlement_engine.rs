@@ -0,0 +1,9443 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
+
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::{DateTime, Duration, Months, Utc};
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::sync::Notify;
+use tracing::{error, info, info_span, instrument, warn, Instrument, Span};
+use uuid::Uuid;
+
+use crate::blockchain::{
+    is_circuit_open, metadata, BlockchainClient, CircuitState, ConfirmationStatus, SettlementMetadata, TxInclusion,
+    SETTLEMENT_METADATA_LABEL,
+};
+use crate::database::{Database, PoolStatus};
+use crate::esign::SignatureProvider;
+use crate::i18n::Locale;
+use crate::middleware::caller_auth::Caller;
+use crate::models::{
+    AcceptSettlementRequest, AcceptanceAssessment, AcceptancePrediction, AcceptancePredictionRequest,
+    AgreementClause, AiMode, AmountChange, AnchoringStrategy, AttachEvidenceRequest, AuditEvent, AuditEventType,
+    AutoNegotiateRequest, AutoNegotiationJob, AutoNegotiationStatus, BatchExecution, BulkViolationImport,
+    BundleSettlementRequest, BundledDebt, Cadence, ChainState, CheckStatus, CitedViolation, CounterOffer,
+    CounterOfferResponse, CreateDraftRequest, CreateSettlementRequest, CreditorContact, CreditorContactRequest,
+    CreditorEmail, CreditorEmailStatus, CreditorExecutionMode, CreditorProfile, CreditorProposalRequest,
+    CreditorTerms, CreditorTermsRequest, Debt, DebtVerification, DebtVerificationRequest, DebtorAcceptance,
+    DependencyCheck, DueReminder, EnqueuedJob, EnvelopeStatus, EvidenceRef, EvidenceUpload, ExecutionMode,
+    Factor, FeeBreakdown, FeeCap, FeeEstimate, FeePolicy, FieldError, ForceStatusRequest, HealthReport,
+    HypotheticalViolation, ImportedViolation, InboundEmail, Installment, InstallmentPlan, InstallmentSchedule,
+    InstallmentStatus, LeaderboardEntry, LeverageAnalysis, LeverageLeaderboard, LeverageRequest,
+    LeverageSnapshot, ListCreditorEmailsQuery, ListDeadLettersQuery, ListDraftsQuery, ListSettlementsQuery,
+    ListWebhooksQuery, MetadataVerification, NegotiationAction, NegotiationDecision, NegotiationRound,
+    NegotiationStrategy, OptimalSettlement, PaginatedSettlements, Party, ProposalChanges, ProposalSource,
+    ReceivedEmail, RecomputeSettlementRequest, RecomputedProposal, ReconciliationReport, ReconciliationRun,
+    RegisterWebhookRequest, RegisteredWebhook, RejectReason, ReplyIntent, ReviewFlag, SavingsSummary,
+    Settlement, SettlementAcceptance, SettlementAcceptances, SettlementDebt, SettlementDebts, SettlementDraft,
+    SettlementFloor, SettlementProposal, SettlementStatus, SignatureEnvelope, SignatureRequest,
+    SignatureRequested, SolStatus, SwordEvent, TransactionState, TransactionStatus, UpdateDraftRequest,
+    UserFeePolicy, VerificationStatus, Violation, ViolationRecord, ViolationSettlement, ViolationUse, Webhook,
+    WebhookDeadLetter, WhatIfProjection, WhatIfRequest, clauses_hash, EMAIL_TRIGGER, SWORD_TRIGGER,
+};
+use crate::money::{round_to_minor_units, round_up_to_minor_units, Currency};
+use crate::services::agreement::{self, AgreementCache, AgreementTerms};
+use crate::services::ai_client::{self, AiClient};
+use crate::services::demand_letter::{self, DemandLetterTemplate, DemandLetterTerms, MAX_RESPONSE_DAYS};
+use crate::services::leverage::LeverageEngine;
+use crate::services::env_or;
+use crate::services::event_stream::{self, EventHub, StreamedEvent};
+use crate::services::evidence::EvidenceStore;
+use crate::services::metrics::Metrics;
+use crate::services::notifications::{ReminderHours, UserNotifier};
+use crate::services::shutdown::Shutdown;
+use crate::services::triggers::SignedTrigger;
+use crate::services::webhooks::WebhookDispatcher;
+
+/// `model_version` recorded for proposals whose amount the creditor set.
+const CREDITOR_OFFER_MODEL_VERSION: &str = "creditor-offer";
+
+const DEFAULT_PAGE_SIZE: u32 = 25;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Upper bound on any single dependency probe in the health report.
+const HEALTH_PROBE_TIMEOUT: StdDuration = StdDuration::from_secs(2);
+
+const DEFAULT_RESCISSION_WINDOW_HOURS: u32 = 72;
+
+const DEFAULT_MAX_SETTLEMENT_RETRIES: i32 = 3;
+
+/// How long a proposal stays open when its creditor has no override.
+const DEFAULT_PROPOSAL_EXPIRY_DAYS: u32 = 14;
+
+const DEFAULT_EXPIRY_SWEEP_INTERVAL_SECS: u64 = 300;
+
+/// Expired proposals handled per query; the sweep keeps going until none are left.
+const EXPIRY_SWEEP_BATCH_SIZE: i64 = 100;
+
+/// Auto-negotiation jobs run at once on each instance.
+const DEFAULT_AUTO_NEGOTIATION_WORKERS: usize = 4;
+
+/// How often idle workers look for jobs enqueued by other instances.
+const DEFAULT_AUTO_NEGOTIATION_POLL_INTERVAL_SECS: u64 = 30;
+
+/// A job running longer than this is abandoned, and one left `running` this
+/// long by a worker that died is picked up again.
+const DEFAULT_AUTO_NEGOTIATION_JOB_TIMEOUT_SECS: u64 = 300;
+
+/// A creditor email read with less confidence than this is left for review.
+const DEFAULT_EMAIL_REPLY_MIN_CONFIDENCE: f64 = 0.7;
+
+/// How long an escrowed payment waits for the creditor's release before the
+/// debtor may have it refunded: 30 days.
+const DEFAULT_ESCROW_REFUND_AFTER_HOURS: u32 = 720;
+
+/// How long a draft is kept after it was last saved: a week.
+const DEFAULT_DRAFT_EXPIRY_HOURS: u32 = 168;
+
+/// Rejection note of a settlement the creditor declined by email.
+const EMAIL_REJECTION_NOTE: &str = "declined by the creditor by email";
+
+/// How often completed settlements are checked against the chain.
+const DEFAULT_RECONCILIATION_INTERVAL_SECS: u64 = 3600;
+
+/// Settlements looked up per page of a reconciliation pass.
+const RECONCILIATION_BATCH_SIZE: i64 = 100;
+
+/// How often executions queued behind the Cardano node's circuit breaker are
+/// tried again.
+const DEFAULT_EXECUTION_QUEUE_INTERVAL_SECS: u64 = 30;
+
+/// Queued executions taken per query of a queue pass.
+const EXECUTION_QUEUE_BATCH_SIZE: i64 = 50;
+
+/// How long a shutdown waits for executions, confirmations and webhook
+/// deliveries before leaving them to be picked up after restart.
+const DEFAULT_SHUTDOWN_DRAIN_SECS: u64 = 30;
+
+/// How long the node's answer about a transaction is reused, so UIs polling
+/// a payment don't reach the node on every request.
+const DEFAULT_TX_STATUS_CACHE_SECS: u64 = 10;
+
+/// How long an idle event stream waits before a keep-alive, which is also
+/// the longest an event recorded by another instance takes to reach it.
+const DEFAULT_EVENT_STREAM_KEEPALIVE_SECS: u64 = 15;
+
+/// Transactions whose status is cached at once; the cache is cleared when
+/// full rather than tracking which entry is oldest.
+const MAX_CACHED_TX_STATUSES: usize = 10_000;
+
+/// How long a user's leverage leaderboard is reused. This instance drops it
+/// as soon as it records a snapshot for the user; other instances' copies
+/// age out.
+const DEFAULT_LEADERBOARD_CACHE_SECS: u64 = 60;
+
+/// Leaderboards cached at once, cleared when full as transactions are.
+const MAX_CACHED_LEADERBOARDS: usize = 10_000;
+
+const DEFAULT_EXPLORER_TX_URL: &str = "https://cardanoscan.io/transaction";
+
+/// Violations of the same type this close together count as one pattern.
+const DEFAULT_VIOLATION_DEDUP_WINDOW_HOURS: u32 = 24;
+
+/// Actor recorded for changes made by the service itself, e.g. the
+/// confirmation watcher.
+const SYSTEM_ACTOR: &str = "system";
+
+const MAX_LEVERAGE_BATCH_SIZE: usize = 100;
+
+/// Settlements on a creditor's record at which its history counts as much
+/// as the AI's assessment in an acceptance prediction.
+const ACCEPTANCE_HISTORY_WEIGHT: f64 = 10.0;
+
+const MAX_LEVERAGE_HISTORY: i64 = 1000;
+
+/// AI scoring calls in flight at once for a single batch request.
+const DEFAULT_LEVERAGE_BATCH_CONCURRENCY: usize = 8;
+
+/// Share of the user's savings the platform keeps as its success fee.
+const SUCCESS_FEE_RATE: &str = "0.20";
+
+/// Processing fee as a share of the settled amount.
+const BASE_FEE_RATE: &str = "0.01";
+
+/// Flat allowance for the Cardano transaction fee, in settlement currency.
+const BLOCKCHAIN_COST: &str = "0.50";
+
+#[derive(Debug)]
+pub enum SettlementError {
+    NotFound(&'static str, Uuid),
+    /// No settlement has this reference number.
+    UnknownReference(String),
+    InvalidStatus {
+        settlement_id: Uuid,
+        status: SettlementStatus,
+        action: &'static str,
+    },
+    InvalidTransition {
+        settlement_id: Uuid,
+        from: SettlementStatus,
+        to: SettlementStatus,
+    },
+    Validation(String),
+    Conflict(String),
+    /// An optimistic update lost a race; the caller can reload and retry.
+    ConcurrentModification(Uuid),
+    IdempotencyMismatch(String),
+    /// The proposal passed its `expires_at` before anyone acted on it.
+    Expired(Uuid),
+    /// Acceptance requires a signature and none was supplied.
+    SignatureRequired(Uuid),
+    /// A signature was supplied but does not verify against the user's key.
+    InvalidSignature(Uuid),
+    /// The caller is not a party to the settlement, or acts for another
+    /// user.
+    Forbidden(String),
+    Ai(anyhow::Error),
+    /// The named AI call ran past its budget and fallback is disabled.
+    AiTimeout(&'static str),
+    Blockchain(anyhow::Error),
+    SignatureProvider(anyhow::Error),
+    /// The agreement document could not be rendered.
+    Agreement(anyhow::Error),
+    /// The demand letter could not be rendered.
+    DemandLetter(anyhow::Error),
+    EvidenceStore(anyhow::Error),
+    /// An inbound trigger from the named source failed authentication.
+    InvalidTriggerSignature(&'static str),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for SettlementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettlementError::NotFound(entity, id) => write!(f, "{} {} not found", entity, id),
+            SettlementError::UnknownReference(reference) => write!(f, "settlement {} not found", reference),
+            SettlementError::InvalidStatus { settlement_id, status, action } => write!(
+                f,
+                "cannot {} settlement {} while it is {:?}",
+                action, settlement_id, status
+            ),
+            SettlementError::InvalidTransition { settlement_id, from, to } => write!(
+                f,
+                "settlement {} cannot move from {:?} to {:?}",
+                settlement_id, from, to
+            ),
+            SettlementError::Validation(msg) => write!(f, "invalid request: {}", msg),
+            SettlementError::Conflict(msg) => write!(f, "conflict: {}", msg),
+            SettlementError::ConcurrentModification(id) => write!(
+                f,
+                "settlement {} was modified concurrently; reload it and retry",
+                id
+            ),
+            SettlementError::IdempotencyMismatch(key) => write!(
+                f,
+                "idempotency key {} was already used with a different request",
+                key
+            ),
+            SettlementError::Expired(id) => write!(f, "settlement {} has expired", id),
+            SettlementError::SignatureRequired(id) => {
+                write!(f, "accepting settlement {} requires a user signature", id)
+            }
+            SettlementError::InvalidSignature(id) => {
+                write!(f, "signature does not match the terms of settlement {}", id)
+            }
+            SettlementError::Forbidden(msg) => write!(f, "forbidden: {}", msg),
+            SettlementError::Ai(e) => write!(f, "AI service error: {}", e),
+            SettlementError::AiTimeout(call) => write!(f, "AI service timed out on {}", call),
+            SettlementError::Blockchain(e) => write!(f, "blockchain error: {}", e),
+            SettlementError::SignatureProvider(e) => write!(f, "signature provider error: {}", e),
+            SettlementError::Agreement(e) => write!(f, "could not render agreement: {}", e),
+            SettlementError::DemandLetter(e) => write!(f, "could not render demand letter: {}", e),
+            SettlementError::EvidenceStore(e) => write!(f, "evidence storage error: {}", e),
+            SettlementError::InvalidTriggerSignature(source) => {
+                write!(f, "{} trigger signature does not verify", source)
+            }
+            SettlementError::Database(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SettlementError {}
+
+impl From<sqlx::Error> for SettlementError {
+    fn from(e: sqlx::Error) -> Self {
+        SettlementError::Database(e)
+    }
+}
+
+pub enum IdempotentProposal {
+    Created(Box<SettlementProposal>),
+    /// The stored response of an earlier request with the same key.
+    Replayed(serde_json::Value),
+}
+
+/// What a bulk violation import did: one result per row, in request order,
+/// then one per open settlement on the debt it recomputed.
+pub struct ViolationImport {
+    pub rows: Vec<Result<ImportedViolation, Vec<FieldError>>>,
+    pub recomputed: Vec<(Uuid, Result<RecomputedProposal, SettlementError>)>,
+}
+
+/// What a creditor email has been read as so far.
+#[derive(Default)]
+struct EmailReading {
+    settlement_id: Option<Uuid>,
+    intent: Option<ReplyIntent>,
+    amount: Option<BigDecimal>,
+}
+
+#[derive(Clone)]
+pub struct SettlementEngine {
+    db: Database,
+    ai_client: AiClient,
+    blockchain_client: Arc<dyn BlockchainClient>,
+    signature_provider: Arc<dyn SignatureProvider>,
+    leverage: LeverageEngine,
+    metrics: Metrics,
+    webhooks: WebhookDispatcher,
+    user_notifier: UserNotifier,
+    rescission_window: Duration,
+    leverage_batch_concurrency: usize,
+    require_acceptance_signature: bool,
+    max_retries: i32,
+    proposal_expiry: Duration,
+    expiry_sweep_interval: StdDuration,
+    reminder_hours: ReminderHours,
+    dedup_window: Duration,
+    fee_cap: FeeCap,
+    anchoring: AnchoringStrategy,
+    ai_fallback_enabled: bool,
+    ai_mode: AiMode,
+    agreements: AgreementCache,
+    demand_letters: DemandLetterTemplate,
+    /// Days a demand letter gives the creditor unless the request says.
+    demand_letter_response_days: u32,
+    evidence_store: EvidenceStore,
+    sword_trigger: SignedTrigger,
+    email_trigger: SignedTrigger,
+    /// Least confidence a creditor email's reading is acted on with.
+    email_reply_min_confidence: f64,
+    /// From the escrow lock to when its payment may be refunded.
+    escrow_refund_window: Duration,
+    /// From a draft's last save to when it is deleted.
+    draft_expiry: Duration,
+    auto_negotiation_workers: usize,
+    auto_negotiation_poll_interval: StdDuration,
+    auto_negotiation_job_timeout: StdDuration,
+    /// Wakes an idle worker when this instance enqueues a job.
+    auto_negotiation_wakeup: Arc<Notify>,
+    shutdown: Shutdown,
+    shutdown_drain_deadline: StdDuration,
+    reconciliation_interval: StdDuration,
+    last_reconciliation: Arc<Mutex<Option<ReconciliationRun>>>,
+    execution_queue_interval: StdDuration,
+    /// Settlements with a confirmation watcher running on this instance.
+    watching: Arc<Mutex<HashSet<Uuid>>>,
+    tx_statuses: Arc<DashMap<String, CachedTxStatus>>,
+    tx_status_ttl: StdDuration,
+    leaderboards: Arc<DashMap<Uuid, CachedLeaderboard>>,
+    leaderboard_ttl: StdDuration,
+    /// Explorer page of a transaction, less the `/<hash>` suffix.
+    explorer_tx_url: String,
+    /// Wakes event streams open on this instance.
+    event_hub: EventHub,
+    event_stream_keep_alive: StdDuration,
+}
+
+/// What the node last said about a transaction, and when.
+#[derive(Clone, Copy)]
+struct CachedTxStatus {
+    fetched_at: Instant,
+    state: TransactionState,
+    inclusion: Option<TxInclusion>,
+}
+
+#[derive(Clone)]
+struct CachedLeaderboard {
+    computed_at: Instant,
+    leaderboard: LeverageLeaderboard,
+}
+
+impl SettlementEngine {
+    pub fn new(
+        db: Database,
+        ai_client: AiClient,
+        blockchain_client: Arc<dyn BlockchainClient>,
+        signature_provider: Arc<dyn SignatureProvider>,
+        leverage: LeverageEngine,
+        metrics: Metrics,
+    ) -> Self {
+        let shutdown = Shutdown::default();
+        Self {
+            webhooks: WebhookDispatcher::new(db.clone(), shutdown.clone()),
+            user_notifier: UserNotifier::from_env(shutdown.clone()),
+            db,
+            ai_client,
+            blockchain_client,
+            signature_provider,
+            leverage,
+            metrics,
+            rescission_window: Duration::hours(
+                env_or("SETTLEMENT_RESCISSION_WINDOW_HOURS", DEFAULT_RESCISSION_WINDOW_HOURS).into(),
+            ),
+            leverage_batch_concurrency: env_or(
+                "LEVERAGE_BATCH_CONCURRENCY",
+                DEFAULT_LEVERAGE_BATCH_CONCURRENCY,
+            )
+            .max(1),
+            require_acceptance_signature: env_or("SETTLEMENT_REQUIRE_SIGNATURE", false),
+            max_retries: env_or("SETTLEMENT_MAX_RETRIES", DEFAULT_MAX_SETTLEMENT_RETRIES),
+            proposal_expiry: Duration::days(env_or("SETTLEMENT_EXPIRY_DAYS", DEFAULT_PROPOSAL_EXPIRY_DAYS).into()),
+            expiry_sweep_interval: StdDuration::from_secs(
+                env_or("SETTLEMENT_EXPIRY_SWEEP_INTERVAL_SECS", DEFAULT_EXPIRY_SWEEP_INTERVAL_SECS).max(1),
+            ),
+            reminder_hours: env_or("SETTLEMENT_REMINDER_HOURS", ReminderHours::default()),
+            dedup_window: Duration::hours(
+                env_or("VIOLATION_DEDUP_WINDOW_HOURS", DEFAULT_VIOLATION_DEDUP_WINDOW_HOURS).into(),
+            ),
+            fee_cap: FeeCap {
+                max_amount: env_decimal("PLATFORM_FEE_CAP_AMOUNT"),
+                max_percent_of_settled: env_decimal("PLATFORM_FEE_CAP_PERCENT_OF_SETTLED"),
+            },
+            anchoring: env_or("SETTLEMENT_ANCHORING_STRATEGY", AnchoringStrategy::default()),
+            ai_fallback_enabled: env_or("AI_FALLBACK_ENABLED", true),
+            ai_mode: env_or("AI_MODE", AiMode::default()),
+            agreements: AgreementCache::default(),
+            demand_letters: DemandLetterTemplate::default(),
+            demand_letter_response_days: env_or("DEMAND_LETTER_RESPONSE_DAYS", demand_letter::DEFAULT_RESPONSE_DAYS),
+            evidence_store: EvidenceStore::from_env(),
+            sword_trigger: SignedTrigger::from_env("SWORD_TRIGGER_SECRET"),
+            email_trigger: SignedTrigger::from_env("EMAIL_TRIGGER_SECRET"),
+            email_reply_min_confidence: env_or("EMAIL_REPLY_MIN_CONFIDENCE", DEFAULT_EMAIL_REPLY_MIN_CONFIDENCE),
+            escrow_refund_window: Duration::hours(
+                env_or("ESCROW_REFUND_AFTER_HOURS", DEFAULT_ESCROW_REFUND_AFTER_HOURS).into(),
+            ),
+            draft_expiry: Duration::hours(env_or("SETTLEMENT_DRAFT_EXPIRY_HOURS", DEFAULT_DRAFT_EXPIRY_HOURS).into()),
+            auto_negotiation_workers: env_or("AUTO_NEGOTIATION_WORKERS", DEFAULT_AUTO_NEGOTIATION_WORKERS),
+            auto_negotiation_poll_interval: StdDuration::from_secs(
+                env_or(
+                    "AUTO_NEGOTIATION_POLL_INTERVAL_SECS",
+                    DEFAULT_AUTO_NEGOTIATION_POLL_INTERVAL_SECS,
+                )
+                .max(1),
+            ),
+            auto_negotiation_job_timeout: StdDuration::from_secs(
+                env_or("AUTO_NEGOTIATION_JOB_TIMEOUT_SECS", DEFAULT_AUTO_NEGOTIATION_JOB_TIMEOUT_SECS).max(1),
+            ),
+            auto_negotiation_wakeup: Arc::new(Notify::new()),
+            shutdown,
+            shutdown_drain_deadline: StdDuration::from_secs(env_or(
+                "SHUTDOWN_DRAIN_SECS",
+                DEFAULT_SHUTDOWN_DRAIN_SECS,
+            )),
+            reconciliation_interval: StdDuration::from_secs(
+                env_or("RECONCILIATION_INTERVAL_SECS", DEFAULT_RECONCILIATION_INTERVAL_SECS).max(1),
+            ),
+            last_reconciliation: Arc::new(Mutex::new(None)),
+            execution_queue_interval: StdDuration::from_secs(
+                env_or("SETTLEMENT_EXECUTION_QUEUE_INTERVAL_SECS", DEFAULT_EXECUTION_QUEUE_INTERVAL_SECS).max(1),
+            ),
+            watching: Arc::new(Mutex::new(HashSet::new())),
+            tx_statuses: Arc::new(DashMap::new()),
+            tx_status_ttl: StdDuration::from_secs(env_or("CARDANO_TX_STATUS_CACHE_SECS", DEFAULT_TX_STATUS_CACHE_SECS)),
+            leaderboards: Arc::new(DashMap::new()),
+            leaderboard_ttl: StdDuration::from_secs(env_or(
+                "LEVERAGE_LEADERBOARD_CACHE_SECS",
+                DEFAULT_LEADERBOARD_CACHE_SECS,
+            )),
+            explorer_tx_url: env_or("CARDANO_EXPLORER_TX_URL", DEFAULT_EXPLORER_TX_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            event_hub: EventHub::default(),
+            event_stream_keep_alive: StdDuration::from_secs(
+                env_or("EVENT_STREAM_KEEPALIVE_SECS", DEFAULT_EVENT_STREAM_KEEPALIVE_SECS).max(1),
+            ),
+        }
+    }
+    
+    /// Writes demand letters from `template` rather than the built-in one.
+    pub fn with_demand_letters(self, template: DemandLetterTemplate) -> Self {
+        Self { demand_letters: template, ..self }
+    }
+    
+    /// Stops the queue workers and the expiry sweeper from picking up more
+    /// work. Executions already under way carry on.
+    pub fn begin_shutdown(&self) {
+        self.shutdown.begin();
+    }
+    
+    /// Waits up to `SHUTDOWN_DRAIN_SECS` (default 30) for in-flight
+    /// executions, confirmation watchers and webhook deliveries. Whatever is
+    /// still running then stops without changing state: a settlement stays
+    /// `Accepted` with its transaction recorded, so executing it again after
+    /// restart resumes the watch, and a webhook delivery stays `Pending`.
+    pub async fn drain(&self) {
+        self.begin_shutdown();
+        info!("Draining {} in-flight operations", self.shutdown.in_flight());
+        match self.shutdown.drain(self.shutdown_drain_deadline).await {
+            0 => info!("Drained all in-flight operations"),
+            abandoned => warn!(
+                "Left {} operations unfinished after {}s; they resume after restart",
+                abandoned,
+                self.shutdown_drain_deadline.as_secs()
+            ),
+        }
+    }
+    
+    /// Probes the database, the Cardano node (tip query only) and the AI
+    /// service concurrently. The AI service is non-critical because proposals
+    /// fall back to rules when it is down. The node's check carries its
+    /// circuit breaker state; while the breaker is open the node is reported
+    /// down without being asked.
+    pub async fn health_report(&self) -> HealthReport {
+        let (database, mut cardano, ai) = futures::join!(
+            probe("database", true, self.db.ping()),
+            probe("cardano_node", true, self.blockchain_client.tip_height()),
+            probe("ai_service", false, self.ai_client.ping()),
+        );
+        cardano.circuit = self.cardano_circuit_state();
+        
+        HealthReport::from_checks(vec![database, cardano, ai])
+    }
+    
+    pub fn db_pool_status(&self) -> PoolStatus {
+        self.db.pool_status()
+    }
+    
+    pub fn cardano_circuit_state(&self) -> Option<CircuitState> {
+        self.blockchain_client.circuit_state()
+    }
+    
+    /// Whether `e` means the Cardano node's circuit breaker is open, either
+    /// because it refused the call or because this failure opened it.
+    fn cardano_unavailable(&self, e: &anyhow::Error) -> bool {
+        is_circuit_open(e) || self.cardano_circuit_state() == Some(CircuitState::Open)
+    }
+    
+    #[instrument(skip_all, fields(settlement_id = tracing::field::Empty, creditor_id = %request.creditor_id))]
+    pub async fn create_settlement_proposal(
+        &self,
+        request: &CreateSettlementRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let proposal = self.preview_settlement_proposal(request).await?;
+        self.persist_proposal(request, proposal, &[]).await
+    }
+    
+    /// Proposes one settlement for several of the user's debts with the same
+    /// creditor. The bundle is negotiated as a single debt owing their
+    /// combined balance, with the leverage of every violation named; it is
+    /// filed under its first debt and its breakdown is kept per debt.
+    #[instrument(skip_all, fields(settlement_id = tracing::field::Empty, creditor_id = %request.creditor_id))]
+    pub async fn create_bundled_settlement(
+        &self,
+        request: &BundleSettlementRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let settlement_request = request.settlement_request();
+        let mut debts = Vec::with_capacity(request.debt_ids.len());
+        for &debt_id in &request.debt_ids {
+            let debt = self
+                .db
+                .get_debt(debt_id)
+                .await?
+                .ok_or(SettlementError::NotFound("debt", debt_id))?;
+            ensure_proposable(&debt, &settlement_request)?;
+            debts.push(debt);
+        }
+        if debts.iter().any(|debt| debt.currency != debts[0].currency) {
+            return Err(SettlementError::Validation(
+                "bundled debts must all be in the same currency".to_string(),
+            ));
+        }
+        
+        let proposal = self.propose_for_debt(&settlement_request, bundle_debt(&debts)).await?;
+        let components: Vec<_> = debts
+            .iter()
+            .map(|debt| SettlementDebt {
+                debt_id: debt.id,
+                original_amount: debt.current_amount.clone(),
+            })
+            .collect();
+        self.persist_proposal(&settlement_request, proposal, &components).await
+    }
+    
+    /// Saves a proposal request the user has only started on. Drafts are
+    /// kept for `SETTLEMENT_DRAFT_EXPIRY_HOURS` (default 168, a week) after
+    /// they were last saved.
+    pub async fn create_draft(&self, request: &CreateDraftRequest) -> Result<SettlementDraft, SettlementError> {
+        let now = Utc::now();
+        let draft = self
+            .db
+            .insert_draft(&SettlementDraft {
+                id: Uuid::new_v4(),
+                user_id: request.user_id,
+                kind: request.kind,
+                fields: serde_json::Value::Object(request.fields.clone()),
+                settlement_id: None,
+                version: 0,
+                expires_at: now + self.draft_expiry,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        info!("Started {:?} draft {} for user {}", draft.kind, draft.id, draft.user_id);
+        Ok(draft)
+    }
+    
+    pub async fn get_draft(&self, draft_id: Uuid) -> Result<SettlementDraft, SettlementError> {
+        self.db
+            .get_draft(draft_id)
+            .await?
+            .ok_or(SettlementError::NotFound("draft", draft_id))
+    }
+    
+    pub async fn list_drafts(&self, query: &ListDraftsQuery) -> Result<Vec<SettlementDraft>, SettlementError> {
+        Ok(self.db.get_user_drafts(query.user_id).await?)
+    }
+    
+    pub async fn update_draft(
+        &self,
+        draft_id: Uuid,
+        request: &UpdateDraftRequest,
+    ) -> Result<SettlementDraft, SettlementError> {
+        let lock = self.db.lock_draft(draft_id).await?;
+        let draft = self.get_draft(draft_id).await?;
+        ensure_draft_open(&draft)?;
+        
+        let (set, cleared) = request.changes();
+        let draft = self
+            .db
+            .update_draft_fields(draft_id, &set, &cleared, Utc::now() + self.draft_expiry)
+            .await?
+            .ok_or(SettlementError::NotFound("draft", draft_id))?;
+        lock.commit().await?;
+        Ok(draft)
+    }
+    
+    /// Proposes the settlement `draft` has become, once the handler has
+    /// checked `request` as it would a proposal posted directly.
+    pub async fn finalize_settlement_draft(
+        &self,
+        draft: &SettlementDraft,
+        request: &CreateSettlementRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        self.finalize_draft(draft, self.create_settlement_proposal(request)).await
+    }
+    
+    pub async fn finalize_bundle_draft(
+        &self,
+        draft: &SettlementDraft,
+        request: &BundleSettlementRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        self.finalize_draft(draft, self.create_bundled_settlement(request)).await
+    }
+    
+    /// Runs `propose` under the draft's lock, as long as the draft is still
+    /// open and `draft` is its latest save, so it becomes one settlement at
+    /// most and that settlement is the version checked.
+    async fn finalize_draft(
+        &self,
+        draft: &SettlementDraft,
+        propose: impl Future<Output = Result<SettlementProposal, SettlementError>>,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let lock = self.db.lock_draft(draft.id).await?;
+        let current = self.get_draft(draft.id).await?;
+        ensure_draft_open(&current)?;
+        if current.version != draft.version {
+            return Err(SettlementError::Conflict(format!(
+                "draft {} was saved again while it was being finalized",
+                draft.id
+            )));
+        }
+        
+        let proposal = propose.await?;
+        self.db
+            .finish_draft(draft.id, draft.version, proposal.settlement.id)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(draft.id))?;
+        lock.commit().await?;
+        info!("Finalized draft {} into settlement {}", draft.id, proposal.settlement.id);
+        Ok(proposal)
+    }
+    
+    /// Stores a computed proposal, with its component debts if it is a bundle.
+    async fn persist_proposal(
+        &self,
+        request: &CreateSettlementRequest,
+        mut proposal: SettlementProposal,
+        components: &[SettlementDebt],
+    ) -> Result<SettlementProposal, SettlementError> {
+        let proposed_by = proposal.settlement.proposed_by;
+        if proposed_by == Party::Debtor && self.db.get_creditor_contact(request.creditor_id).await?.is_none() {
+            proposal.warnings.push(format!(
+                "creditor {} has no contact on file, so this proposal cannot be delivered",
+                request.creditor_id
+            ));
+        }
+        proposal.settlement = if components.is_empty() {
+            self.db.insert_settlement(&proposal.settlement).await?
+        } else {
+            self.db.insert_bundled_settlement(&proposal.settlement, components).await?
+        };
+        proposal.persisted = true;
+        Span::current().record("settlement_id", tracing::field::display(proposal.settlement.id));
+        let settlement = &proposal.settlement;
+        
+        self.snapshot_leverage(settlement, request.creditor_id, &proposal.leverage_analysis).await;
+        self.db
+            .replace_settlement_violations(
+                settlement.id,
+                request.creditor_id,
+                &cited_violations(&request.violations, &proposal.leverage_analysis),
+            )
+            .await?;
+        
+        let mut metadata = json!({
+            "debt_id": settlement.debt_id,
+            "original_amount": settlement.original_amount,
+            "settled_amount": settlement.settled_amount,
+            "source": proposal.source,
+            "ai_mode": match proposal.source {
+                ProposalSource::RulesOnly => AiMode::Disabled,
+                _ => self.ai_mode,
+            },
+            "model_version": proposal.model_version,
+            "prompt_hash": proposal.prompt_hash,
+            "prompt_version": settlement.prompt_version,
+            "proposed_by": proposed_by,
+        });
+        if !components.is_empty() {
+            metadata["bundled_debts"] = json!(components.iter().map(|c| c.debt_id).collect::<Vec<_>>());
+        }
+        let actor = match proposed_by {
+            Party::Debtor => user_actor(settlement.user_id),
+            Party::Creditor => creditor_actor(request.creditor_id),
+        };
+        self.metrics.settlement_entered(SettlementStatus::Proposed);
+        self.record_event(settlement.id, AuditEventType::Created, &actor, metadata)
+            .await?;
+        if settlement.fee_policy.is_concession() {
+            self.record_event(
+                settlement.id,
+                AuditEventType::FeePolicyApplied,
+                &actor,
+                json!({
+                    "fee_policy": settlement.fee_policy,
+                    "granted_by": if request.fee_policy.is_some() { "request" } else { "user" },
+                    "standard_fee": proposal.fee_breakdown.standard_total,
+                    "platform_fee": settlement.platform_fee,
+                }),
+            )
+            .await?;
+        }
+        info!(
+            "Proposed settlement {} for debt {} ({} -> {})",
+            settlement.id, settlement.debt_id, settlement.original_amount, settlement.settled_amount
+        );
+        
+        Ok(proposal)
+    }
+    
+    /// Computes the proposal `create_settlement_proposal` would make, leverage
+    /// analysis included, without persisting it, emitting events or touching
+    /// the chain. The returned settlement has `persisted: false` and its id
+    /// refers to nothing.
+    #[instrument(skip_all, fields(creditor_id = %request.creditor_id))]
+    pub async fn preview_settlement_proposal(
+        &self,
+        request: &CreateSettlementRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let debt = self.resolve_debt(request).await?;
+        self.propose_for_debt(request, debt).await
+    }
+    
+    async fn propose_for_debt(
+        &self,
+        request: &CreateSettlementRequest,
+        debt: Debt,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let ai_mode = self.ai_mode(request.ai_mode)?;
+        let (leverage_analysis, caveats) = self.assess_leverage(request, &debt).await?;
+        let (optimal, source) = self
+            .optimal_settlement(&debt, &leverage_analysis, request.strategy, &request.locale, ai_mode)
+            .await?;
+        
+        let (mut settlement, fee_breakdown) = self
+            .new_settlement(request, &debt, optimal.amount.clone(), Party::Debtor)
+            .await?;
+        settlement.model_version = Some(optimal.model_version.clone());
+        settlement.prompt_hash = Some(optimal.prompt_hash.clone());
+        settlement.prompt_version = optimal.prompt_version.clone();
+        
+        Ok(SettlementProposal {
+            fee_breakdown,
+            recommended_action: recommended_action(&leverage_analysis, &request.locale),
+            confidence_score: optimal.confidence,
+            reasoning: strategy_reasoning(request.strategy, &leverage_analysis, &request.locale)
+                .into_iter()
+                .chain(caveats)
+                .chain(optimal.reasoning)
+                .collect(),
+            source,
+            model_version: optimal.model_version,
+            prompt_hash: optimal.prompt_hash,
+            settlement,
+            persisted: false,
+            floor_applied: optimal.floor_applied,
+            warnings: Vec::new(),
+            simulation: self.ai_client.simulation().map(|simulation| simulation.info()),
+            leverage_analysis,
+        })
+    }
+    
+    /// Records terms a creditor offers the user, who answers them through
+    /// the usual accept, reject and counter routes. The amount is the
+    /// creditor's; the leverage analysis still runs so the user can see how
+    /// the offer compares with what their violations support.
+    #[instrument(skip_all, fields(settlement_id = tracing::field::Empty, creditor_id = %creditor_id))]
+    pub async fn create_creditor_proposal(
+        &self,
+        creditor_id: Uuid,
+        request: &CreditorProposalRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let settlement_request = request.settlement_request(creditor_id);
+        let debt = self.resolve_debt(&settlement_request).await?;
+        if request.amount > debt.current_amount {
+            return Err(SettlementError::Validation(format!(
+                "amount {} exceeds the debt's balance of {}",
+                request.amount, debt.current_amount
+            )));
+        }
+        if round_to_minor_units(request.amount.clone(), &debt.currency) != request.amount {
+            return Err(SettlementError::Validation(format!(
+                "amount {} is finer than {} allows",
+                request.amount, debt.currency
+            )));
+        }
+        
+        let (leverage_analysis, caveats) = self.assess_leverage(&settlement_request, &debt).await?;
+        let settled_amount = request.amount.with_scale(debt.currency.minor_units());
+        let (mut settlement, fee_breakdown) = self
+            .new_settlement(&settlement_request, &debt, settled_amount, Party::Creditor)
+            .await?;
+        let prompt_hash = hash_creditor_proposal(creditor_id, request);
+        settlement.model_version = Some(CREDITOR_OFFER_MODEL_VERSION.to_string());
+        settlement.prompt_hash = Some(prompt_hash.clone());
+        
+        let (recommended_action, comparison) =
+            assess_creditor_offer(&settlement, &leverage_analysis, &request.locale);
+        let proposal = SettlementProposal {
+            fee_breakdown,
+            recommended_action,
+            // The creditor has already agreed to its own terms.
+            confidence_score: Some(1.0),
+            reasoning: std::iter::once(comparison).chain(caveats).collect(),
+            source: ProposalSource::Creditor,
+            model_version: CREDITOR_OFFER_MODEL_VERSION.to_string(),
+            prompt_hash,
+            settlement,
+            persisted: false,
+            floor_applied: false,
+            warnings: Vec::new(),
+            simulation: None,
+            leverage_analysis,
+        };
+        
+        let proposal = self.persist_proposal(&settlement_request, proposal, &[]).await?;
+        self.user_notifier.settlement_offered(&proposal.settlement, &request.locale);
+        Ok(proposal)
+    }
+    
+    /// The leverage the request's violations give against `debt`, and why it
+    /// may count for less than the violations alone suggest.
+    async fn assess_leverage(
+        &self,
+        request: &CreateSettlementRequest,
+        debt: &Debt,
+    ) -> Result<(LeverageAnalysis, Vec<String>), SettlementError> {
+        let violations = self
+            .db
+            .get_creditor_violations(request.creditor_id, &request.violations)
+            .await?;
+        if violations.len() < request.violations.len() {
+            warn!(
+                "Ignoring {} violations not attributable to creditor {}",
+                request.violations.len() - violations.len(),
+                request.creditor_id
+            );
+        }
+        let raw_violation_count = violations.len();
+        let now = Utc::now();
+        let violations = self.dedup_violations(violations, now);
+        
+        let mut leverage_analysis = self.leverage.analyze(&violations, &request.jurisdiction, now);
+        let sol_status = self.leverage.sol_status(debt, &request.jurisdiction, now);
+        self.leverage
+            .apply_statute_of_limitations(&mut leverage_analysis, sol_status);
+        
+        let locale = &request.locale;
+        let caveats = sol_reasoning(sol_status, &request.jurisdiction, locale)
+            .into_iter()
+            .chain(verification_reasoning(debt.verification_status, locale))
+            .chain(self.dedup_reasoning(raw_violation_count, violations.len(), locale))
+            .collect();
+        Ok((leverage_analysis, caveats))
+    }
+    
+    /// An unstored `Proposed` settlement of `debt` at `settled_amount`, with
+    /// its fee. The caller records which model, if any, set the amount.
+    async fn new_settlement(
+        &self,
+        request: &CreateSettlementRequest,
+        debt: &Debt,
+        settled_amount: BigDecimal,
+        proposed_by: Party,
+    ) -> Result<(Settlement, FeeBreakdown), SettlementError> {
+        // Savings absorb any rounding residual so the amounts always add up.
+        let saved_amount = &debt.current_amount - &settled_amount;
+        let fee_policy = match &request.fee_policy {
+            Some(policy) => policy.clone(),
+            None => self
+                .db
+                .get_user_fee_policy(request.user_id)
+                .await?
+                .map(|granted| granted.policy)
+                .unwrap_or_default(),
+        };
+        let fee_breakdown = self.compute_fee(&saved_amount, &settled_amount, &debt.currency, &fee_policy);
+        let platform_fee = fee_breakdown.total();
+        let proposed_at = Utc::now();
+        let expires_at = proposed_at + self.proposal_expiry_for(request.creditor_id).await?;
+        let execution_mode = self.db.get_execution_mode(request.creditor_id).await?.unwrap_or_default();
+        
+        let settlement = Settlement {
+            id: Uuid::new_v4(),
+            reference_number: None,
+            user_id: request.user_id,
+            debt_id: debt.id,
+            original_amount: debt.current_amount.clone(),
+            settled_amount,
+            saved_amount,
+            platform_fee,
+            proposed_amount: None,
+            proposed_platform_fee: None,
+            currency: debt.currency.clone(),
+            status: SettlementStatus::Proposed,
+            smart_contract_address: None,
+            transaction_hash: None,
+            proposed_at,
+            expires_at,
+            accepted_at: None,
+            rescinded_at: None,
+            completed_at: None,
+            rejection_reason: None,
+            rejection_note: None,
+            retry_count: 0,
+            version: 0,
+            model_version: None,
+            prompt_hash: None,
+            prompt_version: None,
+            strategy: request.strategy,
+            proposed_by,
+            metadata_label: None,
+            terms_hash: None,
+            deleted_at: None,
+            execution_queued_at: None,
+            clauses_hash: None,
+            fee_policy,
+            auto_accept_below: request.auto_accept_below.clone(),
+            submission_intent: None,
+            execution_mode,
+            escrow_refund_after: None,
+        };
+        ensure_amounts(&settlement)?;
+        Ok((settlement, fee_breakdown))
+    }
+    
+    /// Projects the settlement `request.base` would get now, then with each
+    /// hypothetical violation added in turn, so the caller can see what each
+    /// one is worth. Nothing is persisted. Hypothetical violations count as
+    /// unsubstantiated and fold into documented ones of the same type as a
+    /// real new violation would.
+    pub async fn what_if(&self, request: &WhatIfRequest) -> Result<Vec<WhatIfProjection>, SettlementError> {
+        let base = &request.base;
+        let ai_mode = self.ai_mode(base.ai_mode)?;
+        let debt = self.resolve_debt(base).await?;
+        let mut violations = self
+            .db
+            .get_creditor_violations(base.creditor_id, &base.violations)
+            .await?;
+        let now = Utc::now();
+        let sol_status = self.leverage.sol_status(&debt, &base.jurisdiction, now);
+        
+        let scenarios = std::iter::once(None).chain(request.hypothetical_violations.iter().map(Some));
+        let mut projections: Vec<WhatIfProjection> = Vec::with_capacity(request.hypothetical_violations.len() + 1);
+        for added in scenarios {
+            if let Some(added) = added {
+                violations.push(hypothetical_violation(base.creditor_id, added));
+            }
+            let mut analysis = self
+                .leverage
+                .analyze(&self.dedup_violations(violations.clone(), now), &base.jurisdiction, now);
+            self.leverage.apply_statute_of_limitations(&mut analysis, sol_status);
+            let (settlement, source) = self
+                .optimal_settlement(&debt, &analysis, base.strategy, &base.locale, ai_mode)
+                .await?;
+            
+            let marginal_reduction = match projections.last() {
+                Some(previous) => &previous.settlement.amount - &settlement.amount,
+                None => BigDecimal::from(0),
+            };
+            projections.push(WhatIfProjection {
+                added_violation: added.map(|violation| violation.violation_type.clone()),
+                total_leverage_score: analysis.total_leverage_score,
+                settlement,
+                source,
+                marginal_reduction,
+            });
+        }
+        
+        Ok(projections)
+    }
+    
+    /// How likely the creditor is to accept `request.proposed_amount`: the
+    /// AI's assessment of the leverage, blended with how often the creditor
+    /// has accepted before and how much it has let go of. Nothing is stored.
+    #[instrument(skip_all, fields(creditor_id = %request.creditor_id))]
+    pub async fn predict_acceptance(
+        &self,
+        request: &AcceptancePredictionRequest,
+    ) -> Result<AcceptancePrediction, SettlementError> {
+        let profile = self.db.get_creditor_profile(request.creditor_id).await?;
+        let (assessment, source) = match self
+            .ai_call("acceptance_prediction", self.ai_client.predict_acceptance(request))
+            .await
+        {
+            Ok(assessment) => (assessment, ProposalSource::Ai),
+            Err(e) => {
+                self.fall_back_from("acceptance_prediction", e)?;
+                (ai_client::acceptance_fallback(request), ProposalSource::RulesFallback)
+            }
+        };
+        
+        Ok(blend_acceptance(request, assessment, &profile, source))
+    }
+    
+    /// Recomputes an open proposal with the violations documented since it
+    /// was made, under its original strategy and balance, and replaces its
+    /// terms. Terms the creditor set, or that an installment plan was split
+    /// from, are left alone.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn recompute_settlement(
+        &self,
+        settlement_id: Uuid,
+        request: &RecomputeSettlementRequest,
+    ) -> Result<RecomputedProposal, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        if !settlement.status.is_open() {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "recompute",
+            });
+        }
+        ensure_not_expired(&settlement)?;
+        if settlement.proposed_by == Party::Creditor {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} carries the creditor's own terms",
+                settlement_id
+            )));
+        }
+        if !self.db.get_installments(settlement_id).await?.is_empty() {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} has an installment plan for the proposed amount",
+                settlement_id
+            )));
+        }
+        
+        let mut debts = Vec::new();
+        for component in self.db.get_settlement_debts(settlement_id).await? {
+            let debt = self
+                .db
+                .get_debt(component.debt_id)
+                .await?
+                .ok_or(SettlementError::NotFound("debt", component.debt_id))?;
+            debts.push(debt);
+        }
+        if debts.is_empty() {
+            let debt = self
+                .db
+                .get_debt(settlement.debt_id)
+                .await?
+                .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+            debts.push(debt);
+        }
+        // Negotiated from the balance the proposal was made against, even if
+        // the debt's has moved since.
+        let debt = Debt {
+            current_amount: settlement.original_amount.clone(),
+            ..bundle_debt(&debts)
+        };
+        let settlement_request = request.settlement_request(&settlement, debt.creditor_id);
+        ensure_proposable(&debt, &settlement_request)?;
+        // A proposal made without AI is recomputed without it too.
+        let ai_mode = match settlement.model_version.as_deref() {
+            Some(ai_client::RULES_ONLY_MODEL_VERSION) => AiMode::Disabled,
+            _ => self.ai_mode(None)?,
+        };
+        
+        let (leverage_analysis, caveats) = self.assess_leverage(&settlement_request, &debt).await?;
+        let (optimal, source) = self
+            .optimal_settlement(&debt, &leverage_analysis, settlement.strategy, &request.locale, ai_mode)
+            .await?;
+        let saved_amount = &settlement.original_amount - &optimal.amount;
+        let fee_breakdown = self.compute_fee(
+            &saved_amount,
+            &optimal.amount,
+            &settlement.currency,
+            &settlement.fee_policy,
+        );
+        let terms = Settlement {
+            settled_amount: optimal.amount.clone(),
+            saved_amount,
+            platform_fee: fee_breakdown.total(),
+            model_version: Some(optimal.model_version.clone()),
+            prompt_hash: Some(optimal.prompt_hash.clone()),
+            prompt_version: optimal.prompt_version.clone(),
+            ..settlement.clone()
+        };
+        ensure_amounts(&terms)?;
+        
+        let previous_leverage_score = self
+            .db
+            .get_settlement_leverage_snapshot(settlement_id)
+            .await?
+            .map(|snapshot| snapshot.analysis.total_leverage_score);
+        let changes = ProposalChanges {
+            settled_amount: AmountChange::new(&settlement.settled_amount, &terms.settled_amount),
+            saved_amount: AmountChange::new(&settlement.saved_amount, &terms.saved_amount),
+            platform_fee: AmountChange::new(&settlement.platform_fee, &terms.platform_fee),
+            previous_leverage_score,
+            leverage_score: leverage_analysis.total_leverage_score,
+        };
+        
+        let updated = self
+            .db
+            .record_recomputed_terms(settlement_id, settlement.version, &terms)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        self.snapshot_leverage(&updated, debt.creditor_id, &leverage_analysis).await;
+        self.db
+            .replace_settlement_violations(
+                settlement_id,
+                debt.creditor_id,
+                &cited_violations(&settlement_request.violations, &leverage_analysis),
+            )
+            .await?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::Recomputed,
+            &user_actor(settlement.user_id),
+            json!({
+                "changes": changes,
+                "violation_count": leverage_analysis.violation_count,
+                "source": source,
+                "ai_mode": ai_mode,
+                "model_version": optimal.model_version,
+                "prompt_hash": optimal.prompt_hash,
+            }),
+        )
+        .await?;
+        info!(
+            "Recomputed settlement {} ({} -> {})",
+            settlement_id, settlement.settled_amount, updated.settled_amount
+        );
+        
+        Ok(RecomputedProposal {
+            settlement: updated,
+            fee_breakdown,
+            reasoning: strategy_reasoning(settlement.strategy, &leverage_analysis, &request.locale)
+                .into_iter()
+                .chain(caveats)
+                .chain(optimal.reasoning)
+                .collect(),
+            source,
+            changes,
+            leverage_analysis,
+        })
+    }
+    
+    pub async fn calculate_leverage(
+        &self,
+        request: &LeverageRequest,
+    ) -> Result<LeverageAnalysis, SettlementError> {
+        let violations = self
+            .db
+            .get_creditor_violations(request.creditor_id, &request.violations)
+            .await?;
+        
+        self.score_leverage(request, violations).await
+    }
+    
+    /// Scores many requests with a single violations query and a bounded
+    /// number of concurrent AI calls. Results are in input order; an item that
+    /// fails does not fail the batch, only a batch-wide problem returns `Err`.
+    pub async fn calculate_leverage_batch(
+        &self,
+        items: &[LeverageRequest],
+    ) -> Result<Vec<Result<LeverageAnalysis, SettlementError>>, SettlementError> {
+        if items.len() > MAX_LEVERAGE_BATCH_SIZE {
+            return Err(SettlementError::Validation(format!(
+                "at most {} items per batch",
+                MAX_LEVERAGE_BATCH_SIZE
+            )));
+        }
+        
+        let mut ids: Vec<Uuid> = items
+            .iter()
+            .flat_map(|item| item.violations.iter().copied())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        let violations = self.db.get_violations(&ids).await?;
+        
+        let results = futures::stream::iter(items.iter().map(|item| {
+            let owned: Vec<Violation> = violations
+                .iter()
+                .filter(|v| v.creditor_id == item.creditor_id && item.violations.contains(&v.id))
+                .cloned()
+                .collect();
+            self.score_leverage(item, owned)
+        }))
+        .buffered(self.leverage_batch_concurrency)
+        .collect()
+        .await;
+        
+        Ok(results)
+    }
+    
+    /// Rules-based analysis refined by the AI service. There is no rules
+    /// fallback here: callers asking for a score want the model's view,
+    /// unless AI is disabled and they get the rules' alone. Violations under
+    /// statutes the request doesn't count are scored as if they weren't
+    /// there, and only listed.
+    async fn score_leverage(
+        &self,
+        request: &LeverageRequest,
+        violations: Vec<Violation>,
+    ) -> Result<LeverageAnalysis, SettlementError> {
+        let ai_mode = self.ai_mode(request.ai_mode)?;
+        let (violations, excluded): (Vec<Violation>, Vec<Violation>) = violations
+            .into_iter()
+            .partition(|violation| request.counts_statute(violation.statute));
+        let now = Utc::now();
+        let violations = self.dedup_violations(violations, now);
+        let baseline = self.leverage.analyze(&violations, &request.jurisdiction, now);
+        // Expired claims are out of the picture, for the model as for us.
+        let violations: Vec<Violation> = violations
+            .into_iter()
+            .filter(|violation| !baseline.expired_violations.contains(&violation.id))
+            .collect();
+        let excluded_violations = excluded.iter().map(|violation| violation.id).collect();
+        if ai_mode == AiMode::Disabled {
+            return Ok(LeverageAnalysis {
+                excluded_violations,
+                ..baseline
+            });
+        }
+        
+        let mut analysis = self
+            .ai_call(
+                "leverage_score",
+                self.ai_client.score_leverage(request.creditor_id, &violations, &baseline),
+            )
+            .await
+            .map_err(|e| ai_error("leverage_score", e))?;
+        
+        // Evidence is ours to judge, whatever the model made of the score.
+        analysis.unsubstantiated_violations = baseline.unsubstantiated_violations;
+        analysis.expired_violations = baseline.expired_violations;
+        analysis.excluded_violations = excluded_violations;
+        // So is the explanation; whatever the model moved the score by shows
+        // up as one factor, keeping the contributions summing to the total.
+        let adjustment = analysis.total_leverage_score - baseline.total_leverage_score;
+        analysis.explanation = baseline.explanation;
+        if adjustment != 0.0 {
+            analysis.explanation.factors.push(Factor::new("ai_refinement", adjustment, 1.0));
+        }
+        Ok(analysis)
+    }
+    
+    /// Records an artifact backing `violation_id` and returns a presigned URL
+    /// to upload the file to. The violation counts as corroborated from now
+    /// on; `sha256` is what the stored file will be checked against. Only the
+    /// debtor of the violation's debt, or support, may back it; a violation
+    /// recorded against no debt is support's alone.
+    pub async fn attach_evidence(
+        &self,
+        violation_id: Uuid,
+        request: &AttachEvidenceRequest,
+        caller: &Caller,
+    ) -> Result<EvidenceUpload, SettlementError> {
+        let now = Utc::now();
+        let evidence = new_evidence(violation_id, request, now).map_err(SettlementError::Validation)?;
+        
+        if self.db.get_violations(&[violation_id]).await?.is_empty() {
+            return Err(SettlementError::NotFound("violation", violation_id));
+        }
+        match self.db.get_violation_debt_id(violation_id).await? {
+            Some(debt_id) => {
+                let debt = self
+                    .db
+                    .get_debt(debt_id)
+                    .await?
+                    .ok_or(SettlementError::NotFound("debt", debt_id))?;
+                authorize_user_access(debt.user_id, caller)?;
+            }
+            None if matches!(caller, Caller::Admin(_)) => {}
+            None => {
+                return Err(SettlementError::Forbidden(format!(
+                    "violation {} is on no debt the caller owes",
+                    violation_id
+                )))
+            }
+        }
+        
+        // Presign first, so a misconfigured store leaves no dangling row.
+        let upload = self
+            .evidence_store
+            .upload_url(&evidence.storage_key, &evidence.content_type, now)
+            .map_err(SettlementError::EvidenceStore)?;
+        
+        let evidence = match self.db.insert_violation_evidence(&evidence).await {
+            Ok(evidence) => evidence,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                return Err(SettlementError::Conflict(format!(
+                    "violation {} already has evidence with this hash",
+                    violation_id
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        
+        info!(
+            "Attached {:?} evidence {} to violation {}",
+            evidence.evidence_type, evidence.id, violation_id
+        );
+        Ok(EvidenceUpload {
+            evidence,
+            upload_url: upload.url,
+            upload_expires_at: upload.expires_at,
+        })
+    }
+    
+    /// The settlements whose terms cite the violation, with what it
+    /// contributed to each. Support and the creditor that committed it see
+    /// them all; a user sees their own.
+    pub async fn get_violation_settlements(
+        &self,
+        violation_id: Uuid,
+        caller: &Caller,
+    ) -> Result<Vec<ViolationSettlement>, SettlementError> {
+        let violation = self
+            .db
+            .get_violations(&[violation_id])
+            .await?
+            .pop()
+            .ok_or(SettlementError::NotFound("violation", violation_id))?;
+        if let Caller::Creditor(creditor_id) = caller {
+            if *creditor_id != violation.creditor_id {
+                return Err(SettlementError::Forbidden(format!(
+                    "violation {} was not committed by creditor {}",
+                    violation_id, creditor_id
+                )));
+            }
+        }
+        
+        let mut settlements = self.db.get_violation_settlements(violation_id).await?;
+        if let Caller::User(user_id) = caller {
+            settlements.retain(|cited| cited.settlement.user_id == *user_id);
+        }
+        Ok(settlements)
+    }
+    
+    /// Records many violations against the debt's creditor at once. Each row
+    /// is checked on its own and those that pass are inserted together, in
+    /// one transaction; a row that fails is reported and leaves the others
+    /// be. With `recompute`, every open settlement on the debt is then
+    /// recomputed with the imported violations added to those it cites; one
+    /// that can't be is reported without undoing the import.
+    #[instrument(skip_all, fields(debt_id = %debt_id, rows = request.violations.len()))]
+    pub async fn import_violations(
+        &self,
+        debt_id: Uuid,
+        request: &BulkViolationImport,
+        caller: &Caller,
+    ) -> Result<ViolationImport, SettlementError> {
+        let debt = self
+            .db
+            .get_debt(debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", debt_id))?;
+        authorize_user_access(debt.user_id, caller)?;
+        
+        let now = Utc::now();
+        let mut violations = Vec::new();
+        let mut evidence = Vec::new();
+        let mut rows = Vec::with_capacity(request.violations.len());
+        for row in &request.violations {
+            let record = match serde_json::from_value::<ViolationRecord>(row.clone()) {
+                Ok(record) => record,
+                Err(e) => {
+                    rows.push(Err(vec![FieldError::new("row", e.to_string())]));
+                    continue;
+                }
+            };
+            let violation_id = Uuid::new_v4();
+            let mut errors = record.validate(now).err().unwrap_or_default();
+            let mut row_evidence: Vec<EvidenceRef> = Vec::new();
+            for (index, declared) in record.evidence.iter().enumerate() {
+                match new_evidence(violation_id, declared, now) {
+                    Ok(item) if row_evidence.iter().any(|other| other.sha256 == item.sha256) => {
+                        errors.push(FieldError::new("evidence", format!("entry {} repeats a hash", index)));
+                    }
+                    Ok(item) => row_evidence.push(item),
+                    Err(e) => errors.push(FieldError::new("evidence", format!("entry {}: {}", index, e))),
+                }
+            }
+            if !errors.is_empty() {
+                rows.push(Err(errors));
+                continue;
+            }
+            
+            // Presigned before anything is stored, as for a single upload.
+            let row_uploads = row_evidence
+                .iter()
+                .map(|item| {
+                    let upload = self
+                        .evidence_store
+                        .upload_url(&item.storage_key, &item.content_type, now)
+                        .map_err(SettlementError::EvidenceStore)?;
+                    Ok(EvidenceUpload {
+                        evidence: item.clone(),
+                        upload_url: upload.url,
+                        upload_expires_at: upload.expires_at,
+                    })
+                })
+                .collect::<Result<Vec<_>, SettlementError>>()?;
+            rows.push(Ok(ImportedViolation {
+                violation_id,
+                evidence: row_uploads,
+            }));
+            evidence.extend(row_evidence);
+            violations.push(Violation {
+                id: violation_id,
+                creditor_id: debt.creditor_id,
+                violation_type: record.violation_type.trim().to_string(),
+                statute: record.statute,
+                severity: record.severity,
+                confidence: record.confidence,
+                legal_reference: record.legal_reference.trim().to_string(),
+                estimated_damage: record.estimated_damage,
+                occurred_at: record.occurred_at,
+                created_at: now,
+                repeat_count: 0,
+                evidence: Vec::new(),
+            });
+        }
+        
+        self.db.insert_violations(debt_id, &violations, &evidence).await?;
+        info!(
+            "Imported {} of {} violations for debt {}",
+            violations.len(),
+            rows.len(),
+            debt_id
+        );
+        
+        let mut recomputed = Vec::new();
+        if let Some(recompute) = request.recompute.as_ref().filter(|_| !violations.is_empty()) {
+            for settlement in self.db.get_open_debt_settlements(debt_id).await? {
+                let mut cited = self.db.get_settlement_violation_ids(settlement.id).await?;
+                cited.extend(violations.iter().map(|violation| violation.id));
+                let request = RecomputeSettlementRequest {
+                    violations: cited,
+                    jurisdiction: recompute.jurisdiction.clone(),
+                    locale: recompute.locale.clone(),
+                };
+                let result = self.recompute_settlement(settlement.id, &request).await;
+                if let Err(e) = &result {
+                    warn!("Could not recompute settlement {} after an import: {}", settlement.id, e);
+                }
+                recomputed.push((settlement.id, result));
+            }
+        }
+        
+        Ok(ViolationImport { rows, recomputed })
+    }
+    
+    /// The debt a proposal request is for: the one named, or else the user's
+    /// active debt with the creditor. It must be theirs and not in dispute.
+    async fn resolve_debt(&self, request: &CreateSettlementRequest) -> Result<Debt, SettlementError> {
+        let debt = match request.debt_id {
+            Some(debt_id) => self
+                .db
+                .get_debt(debt_id)
+                .await?
+                .ok_or(SettlementError::NotFound("debt", debt_id))?,
+            None => self
+                .db
+                .find_active_debt(request.user_id, request.creditor_id)
+                .await?
+                .ok_or(SettlementError::NotFound("active debt for creditor", request.creditor_id))?,
+        };
+        
+        ensure_proposable(&debt, request)?;
+        Ok(debt)
+    }
+    
+    /// The AI's settlement amount for the debt given the leverage, or the
+    /// rules' when AI is disabled or the rules fallback's when the AI is
+    /// unavailable, rounded to the debt's currency, then anchored by
+    /// `SETTLEMENT_ANCHORING_STRATEGY` and never below the creditor's
+    /// settlement floor.
+    async fn optimal_settlement(
+        &self,
+        debt: &Debt,
+        leverage: &LeverageAnalysis,
+        strategy: NegotiationStrategy,
+        locale: &Locale,
+        ai_mode: AiMode,
+    ) -> Result<(OptimalSettlement, ProposalSource), SettlementError> {
+        let answer = match ai_mode {
+            AiMode::Enabled => Some(
+                self.ai_call(
+                    "optimal_settlement",
+                    self.ai_client.calculate_optimal_settlement(debt, leverage, strategy, locale),
+                )
+                .await,
+            ),
+            AiMode::Disabled => None,
+        };
+        let (mut optimal, source) = match answer {
+            None => (
+                ai_client::rules_only(debt, leverage, strategy, locale),
+                ProposalSource::RulesOnly,
+            ),
+            Some(Ok(optimal)) => (optimal, ProposalSource::Ai),
+            Some(Err(e)) => {
+                self.fall_back_from("optimal_settlement", e)?;
+                (
+                    ai_client::rules_fallback(debt, leverage, strategy, locale),
+                    ProposalSource::RulesFallback,
+                )
+            }
+        };
+        ensure_currency(&debt.currency, optimal.currency.as_ref())?;
+        
+        optimal.amount = round_to_minor_units(optimal.amount, &debt.currency);
+        apply_anchoring(&mut optimal, debt, leverage, self.anchoring, locale);
+        if let Some(floor_percentage) = self.db.get_settlement_floor_percentage(debt.creditor_id).await? {
+            apply_settlement_floor(&mut optimal, debt, floor_percentage, locale);
+        }
+        Ok((optimal, source))
+    }
+    
+    /// Times an AI call and counts it in `ai_timeouts_total` if it ran out of
+    /// budget.
+    async fn ai_call<T>(
+        &self,
+        call: &'static str,
+        request: impl std::future::Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let _timer = self.metrics.ai_timer(call);
+        let result = request.await;
+        if matches!(&result, Err(e) if ai_client::is_timeout(e)) {
+            self.metrics.ai_timed_out(call);
+        }
+        result
+    }
+    
+    /// The AI mode a request runs under: the service's `AI_MODE` unless the
+    /// request turns AI off. A request can't turn it back on when the
+    /// service has it off.
+    fn ai_mode(&self, requested: Option<AiMode>) -> Result<AiMode, SettlementError> {
+        if self.ai_mode == AiMode::Disabled && requested == Some(AiMode::Enabled) {
+            return Err(SettlementError::Validation(
+                "ai_mode cannot be Enabled while AI_MODE is Disabled".to_string(),
+            ));
+        }
+        Ok(requested.unwrap_or(self.ai_mode))
+    }
+    
+    /// Decides what a failed AI call means: carry on with the rules fallback,
+    /// or, with `AI_FALLBACK_ENABLED=false`, fail the request.
+    fn fall_back_from(&self, call: &'static str, e: anyhow::Error) -> Result<(), SettlementError> {
+        if !self.ai_fallback_enabled {
+            return Err(ai_error(call, e));
+        }
+        warn!("AI {} call failed, using rules fallback: {}", call, e);
+        Ok(())
+    }
+    
+    /// Collapses violations of the same type that happened within the dedup
+    /// window (`VIOLATION_DEDUP_WINDOW_HOURS`, default 24h) of the first one in
+    /// their run into that first violation, counting the rest in
+    /// `repeat_count`. Violations whose claim has expired by `now` are kept
+    /// apart, each on its own, so a run is all live or all expired. Result is
+    /// ordered by `occurred_at`.
+    pub fn dedup_violations(&self, violations: Vec<Violation>, now: DateTime<Utc>) -> Vec<Violation> {
+        fold_repeats(violations, self.dedup_window, |violation| self.leverage.claim_expired(violation, now))
+    }
+    
+    fn dedup_reasoning(&self, raw: usize, deduped: usize, locale: &Locale) -> Option<String> {
+        (raw > deduped).then(|| {
+            locale.message(
+                "dedup.collapsed",
+                &[
+                    ("collapsed", (raw - deduped).to_string()),
+                    ("window_hours", self.dedup_window.num_hours().to_string()),
+                    ("distinct", deduped.to_string()),
+                ],
+            )
+        })
+    }
+    
+    /// Splits the platform fee into its components, clamps it to the
+    /// configured cap (`PLATFORM_FEE_CAP_AMOUNT`,
+    /// `PLATFORM_FEE_CAP_PERCENT_OF_SETTLED`) and then waives or reduces it
+    /// as `policy` says. The stored `platform_fee` is always
+    /// `FeeBreakdown::total()`, so what we show and what we charge can't
+    /// drift apart. Every component is rounded to `currency`'s minor unit.
+    pub fn compute_fee(
+        &self,
+        saved_amount: &BigDecimal,
+        settled_amount: &BigDecimal,
+        currency: &Currency,
+        policy: &FeePolicy,
+    ) -> FeeBreakdown {
+        let fees = apply_fee_cap(
+            fee_breakdown(saved_amount, settled_amount, currency),
+            &self.fee_cap,
+            settled_amount,
+            currency,
+        );
+        apply_fee_policy(fees, policy, currency)
+    }
+    
+    /// Creates a proposal at most once per idempotency key.
+    ///
+    /// The key is claimed before any work happens, so of two concurrent requests
+    /// only one proceeds; the other sees the claim and either replays the stored
+    /// response or, if the first is still running, gets a conflict. Keys expire
+    /// after 24 hours.
+    pub async fn create_settlement_proposal_idempotent(
+        &self,
+        key: &str,
+        request: &CreateSettlementRequest,
+    ) -> Result<IdempotentProposal, SettlementError> {
+        let request_hash = hash_request(request);
+        
+        if !self.db.claim_idempotency_key(key, &request_hash).await? {
+            let record = self.db.get_idempotency_record(key).await?.ok_or_else(|| {
+                SettlementError::Conflict(format!("idempotency key {} is being reclaimed", key))
+            })?;
+            
+            if record.request_hash != request_hash {
+                return Err(SettlementError::IdempotencyMismatch(key.to_string()));
+            }
+            
+            return match record.response {
+                Some(response) => Ok(IdempotentProposal::Replayed(response)),
+                None => Err(SettlementError::Conflict(format!(
+                    "a request with idempotency key {} is still in progress",
+                    key
+                ))),
+            };
+        }
+        
+        let proposal = match self.create_settlement_proposal(request).await {
+            Ok(proposal) => proposal,
+            Err(e) => {
+                self.db.release_idempotency_key(key).await?;
+                return Err(e);
+            }
+        };
+        
+        let response = serde_json::to_value(&proposal).expect("SettlementProposal serializes");
+        self.db
+            .complete_idempotency_key(key, proposal.settlement.id, &response)
+            .await?;
+        
+        Ok(IdempotentProposal::Created(Box::new(proposal)))
+    }
+    
+    pub async fn list_settlements(
+        &self,
+        query: &ListSettlementsQuery,
+    ) -> Result<PaginatedSettlements, SettlementError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let after = query
+            .cursor
+            .as_deref()
+            .map(decode_cursor)
+            .transpose()?;
+        
+        // Fetch one extra row to learn whether another page exists without a
+        // separate COUNT query.
+        let mut items = self
+            .db
+            .list_user_settlements(
+                query.user_id,
+                query.status,
+                after,
+                limit as i64 + 1,
+                query.include_deleted,
+            )
+            .await?;
+        
+        let next_cursor = if items.len() > limit as usize {
+            items.truncate(limit as usize);
+            items.last().map(encode_cursor)
+        } else {
+            None
+        };
+        
+        Ok(PaginatedSettlements { items, next_cursor })
+    }
+    
+    pub async fn get_settlement(
+        &self,
+        settlement_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Settlement, SettlementError> {
+        let settlement = if include_deleted {
+            self.db.get_settlement_including_deleted(settlement_id).await?
+        } else {
+            self.db.get_settlement(settlement_id).await?
+        };
+        
+        settlement.ok_or(SettlementError::NotFound("settlement", settlement_id))
+    }
+    
+    /// What the user saved across their completed settlements in `currency`,
+    /// USD when absent.
+    pub async fn savings_summary(
+        &self,
+        user_id: Uuid,
+        currency: Option<Currency>,
+    ) -> Result<SavingsSummary, SettlementError> {
+        Ok(self
+            .db
+            .get_savings_summary(user_id, &currency.unwrap_or_default())
+            .await?)
+    }
+    
+    /// Looks a settlement up by its `DMC-<year>-<sequence>` reference;
+    /// case and surrounding whitespace don't matter.
+    pub async fn get_settlement_by_reference(&self, reference_number: &str) -> Result<Settlement, SettlementError> {
+        let reference_number = reference_number.trim().to_ascii_uppercase();
+        
+        self.db
+            .get_settlement_by_reference(&reference_number)
+            .await?
+            .ok_or(SettlementError::UnknownReference(reference_number))
+    }
+    
+    /// Refuses `caller` unless they are a party to `settlement`: a debtor
+    /// of its debt, the creditor holding it, or support staff.
+    pub async fn authorize_settlement_access(
+        &self,
+        settlement: &Settlement,
+        caller: &Caller,
+    ) -> Result<(), SettlementError> {
+        let allowed = match caller {
+            Caller::Admin(_) => true,
+            Caller::User(user_id) => {
+                *user_id == settlement.user_id || self.settlement_debtors(settlement).await?.contains(user_id)
+            }
+            // A bundle's debts are all owed to one creditor, so the first
+            // stands for them all.
+            Caller::Creditor(creditor_id) => self
+                .db
+                .get_debt(settlement.debt_id)
+                .await?
+                .is_some_and(|debt| debt.creditor_id == *creditor_id),
+        };
+        if !allowed {
+            warn!("Refused {:?} access to settlement {}", caller, settlement.id);
+            return Err(SettlementError::Forbidden(format!(
+                "not a party to settlement {}",
+                settlement.id
+            )));
+        }
+        Ok(())
+    }
+    
+    /// The settlement, if `caller` may access it.
+    pub async fn authorize_settlement(
+        &self,
+        settlement_id: Uuid,
+        caller: &Caller,
+    ) -> Result<Settlement, SettlementError> {
+        let settlement = self.get_settlement(settlement_id, false).await?;
+        self.authorize_settlement_access(&settlement, caller).await?;
+        Ok(settlement)
+    }
+    
+    /// The settlement's agreement as a PDF: terms, the creditor's clauses, the
+    /// leverage it was proposed with, the fee breakdown and signature blocks.
+    /// Documents are cached per settlement version and clause wording, so one
+    /// is only re-rendered after either changes.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn settlement_agreement_pdf(&self, settlement_id: Uuid) -> Result<Arc<Vec<u8>>, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        let clauses = self.agreement_clauses(&settlement).await?;
+        let clauses_hash = clauses_hash(&clauses);
+        
+        if let Some(pdf) = self.agreements.get(settlement_id, settlement.version, clauses_hash.as_deref()) {
+            return Ok(pdf);
+        }
+        
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+        let leverage = self.db.get_settlement_leverage_snapshot(settlement_id).await?;
+        let fees = self.compute_fee(
+            &settlement.saved_amount,
+            &settlement.settled_amount,
+            &settlement.currency,
+            &settlement.fee_policy,
+        );
+        
+        let pdf = agreement::render_agreement(&AgreementTerms {
+            settlement: &settlement,
+            creditor_id: debt.creditor_id,
+            fees: &fees,
+            leverage: leverage.as_ref().map(|snapshot| &snapshot.analysis.0),
+            clauses: &clauses,
+        })
+        .map_err(SettlementError::Agreement)?;
+        
+        Ok(self.agreements.insert(settlement_id, settlement.version, clauses_hash, pdf))
+    }
+    
+    /// The demand letter for an open settlement, as text: the violations its
+    /// proposal counted, their exposure by statute, and the proposed amount
+    /// as the demand, all from the leverage snapshot the proposal was made
+    /// with. The creditor is given `response_days` to answer, or the
+    /// configured default.
+    pub async fn demand_letter(
+        &self,
+        settlement: &Settlement,
+        response_days: Option<u32>,
+    ) -> Result<String, SettlementError> {
+        self.with_demand_letter_terms(settlement, response_days, |terms| self.demand_letters.text(terms))
+            .await
+    }
+    
+    /// The demand letter as a PDF.
+    pub async fn demand_letter_pdf(
+        &self,
+        settlement: &Settlement,
+        response_days: Option<u32>,
+    ) -> Result<Vec<u8>, SettlementError> {
+        self.with_demand_letter_terms(settlement, response_days, |terms| self.demand_letters.pdf(terms))
+            .await
+    }
+    
+    async fn with_demand_letter_terms<T>(
+        &self,
+        settlement: &Settlement,
+        response_days: Option<u32>,
+        write: impl FnOnce(&DemandLetterTerms) -> anyhow::Result<T>,
+    ) -> Result<T, SettlementError> {
+        if !settlement.status.is_open() {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id: settlement.id,
+                status: settlement.status,
+                action: "write a demand letter for",
+            });
+        }
+        let response_days = response_days.unwrap_or(self.demand_letter_response_days);
+        if !(1..=MAX_RESPONSE_DAYS).contains(&response_days) {
+            return Err(SettlementError::Validation(format!(
+                "response_days must be between 1 and {}",
+                MAX_RESPONSE_DAYS
+            )));
+        }
+        
+        let leverage = self
+            .db
+            .get_settlement_leverage_snapshot(settlement.id)
+            .await?
+            .ok_or_else(|| {
+                SettlementError::Conflict(format!("settlement {} has no leverage analysis to cite", settlement.id))
+            })?
+            .analysis
+            .0;
+        // The violations the analysis counted: those the proposal cited,
+        // less any it left out as time-barred or under another statute.
+        let left_out: HashSet<Uuid> = leverage
+            .expired_violations
+            .iter()
+            .chain(&leverage.excluded_violations)
+            .copied()
+            .collect();
+        let ids: Vec<Uuid> = self
+            .db
+            .get_settlement_violation_ids(settlement.id)
+            .await?
+            .into_iter()
+            .filter(|id| !left_out.contains(id))
+            .collect();
+        let mut violations = self.db.get_violations(&ids).await?;
+        if violations.is_empty() {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} cites no violations to demand on",
+                settlement.id
+            )));
+        }
+        violations.sort_by_key(|violation| violation.occurred_at);
+        
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+        let date = Utc::now().date_naive();
+        
+        write(&DemandLetterTerms {
+            settlement,
+            creditor_id: debt.creditor_id,
+            leverage: &leverage,
+            violations: &violations,
+            date,
+            respond_by: date + Duration::days(response_days.into()),
+        })
+        .map_err(SettlementError::DemandLetter)
+    }
+    
+    /// The clauses the settlement's agreement carries: the creditor's in
+    /// force while it is open, and afterwards the versions agreed on
+    /// acceptance, if any.
+    async fn agreement_clauses(&self, settlement: &Settlement) -> Result<Vec<AgreementClause>, SettlementError> {
+        if settlement.status.is_open() {
+            Ok(self.db.get_settlement_creditor_clauses(settlement.id).await?)
+        } else {
+            Ok(self.db.get_agreed_clauses(settlement.id).await?)
+        }
+    }
+    
+    /// Hides a draft or dead-end settlement from its user. The row, its events
+    /// and its negotiation history are all kept; every lookup except the
+    /// admin `include_deleted` view treats it as gone.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn delete_settlement(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        if !settlement.status.is_deletable() {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "delete",
+            });
+        }
+        
+        let settlement = self
+            .db
+            .soft_delete_settlement(settlement_id, settlement.version)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::Deleted,
+            &user_actor(settlement.user_id),
+            json!({ "status": settlement.status }),
+        )
+        .await?;
+        
+        info!("Settlement {} deleted by user {}", settlement_id, settlement.user_id);
+        Ok(settlement)
+    }
+    
+    /// Accepts at the proposed amount, or at `accepted_amount` when the
+    /// creditor agreed to a different one; the proposed terms are then kept
+    /// in `proposed_amount` and `proposed_platform_fee`.
+    ///
+    /// On a debt held jointly each debtor accepts in turn, as `debtor_id`.
+    /// The settlement is returned unchanged, still open, until the last of
+    /// them accepts the terms the others did; see
+    /// [`Self::settlement_acceptances`].
+    #[instrument(skip_all, fields(settlement_id = %request.settlement_id))]
+    pub async fn accept_settlement(
+        &self,
+        request: &AcceptSettlementRequest,
+    ) -> Result<Settlement, SettlementError> {
+        // Debtors accepting at once take turns, so one of them is sure to
+        // find the others' acceptances and accept the settlement.
+        let lock = self.db.lock_settlement(request.settlement_id).await?;
+        let accepted = self.accept(request).await;
+        lock.commit().await?;
+        accepted
+    }
+    
+    async fn accept(&self, request: &AcceptSettlementRequest) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(request.settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", request.settlement_id))?;
+        ensure_not_expired(&settlement)?;
+        if !settlement.status.can_transition_to(&SettlementStatus::Accepted) {
+            return Err(SettlementError::InvalidTransition {
+                settlement_id: settlement.id,
+                from: settlement.status,
+                to: SettlementStatus::Accepted,
+            });
+        }
+        let debtor_id = request.debtor_id.unwrap_or(settlement.user_id);
+        let debtors = self.settlement_debtors(&settlement).await?;
+        if !debtors.contains(&debtor_id) {
+            return Err(SettlementError::Validation(format!(
+                "debtor {} does not owe the debt settlement {} settles",
+                debtor_id, settlement.id
+            )));
+        }
+        let terms = match &request.accepted_amount {
+            Some(amount) if *amount != settlement.settled_amount => Some(self.accepted_terms(&settlement, amount).await?),
+            _ => None,
+        };
+        let signed_terms = terms.as_ref().unwrap_or(&settlement);
+        let clauses = self.db.get_settlement_creditor_clauses(settlement.id).await?;
+        let clauses_hash = clauses_hash(&clauses);
+        
+        let signature = request
+            .user_signature
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let envelope_id = request
+            .envelope_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        match (signature, envelope_id) {
+            (Some(_), Some(_)) => {
+                return Err(SettlementError::Validation(
+                    "supply either user_signature or envelope_id, not both".to_string(),
+                ))
+            }
+            (Some(signature), None) => {
+                self.verify_acceptance(signed_terms, debtor_id, clauses_hash.as_deref(), signature)
+                    .await?
+            }
+            (None, Some(envelope_id)) => {
+                self.verify_envelope(signed_terms, debtor_id, clauses_hash.as_deref(), envelope_id)
+                    .await?
+            }
+            // Each debtor of a joint debt signs for themselves, whatever the
+            // setting; an unsigned acceptance would bind the others to
+            // nothing they agreed to.
+            (None, None) if self.require_acceptance_signature || debtors.len() > 1 => {
+                return Err(SettlementError::SignatureRequired(settlement.id))
+            }
+            (None, None) => {}
+        }
+        
+        let signed = signature.is_some() || envelope_id.is_some();
+        let acceptances = self.db.get_settlement_acceptances(settlement.id).await?;
+        self.db
+            .record_acceptance(&SettlementAcceptance {
+                settlement_id: settlement.id,
+                debtor_id,
+                settled_amount: signed_terms.settled_amount.clone(),
+                platform_fee: signed_terms.platform_fee.clone(),
+                clauses_hash: clauses_hash.clone(),
+                signed,
+                envelope_id: envelope_id.map(str::to_string),
+                accepted_at: Utc::now(),
+            })
+            .await?;
+        let pending: Vec<Uuid> = debtors
+            .iter()
+            .copied()
+            .filter(|&debtor| {
+                debtor != debtor_id
+                    && !acceptances.iter().any(|acceptance| {
+                        acceptance.debtor_id == debtor && acceptance.covers(signed_terms, clauses_hash.as_deref())
+                    })
+            })
+            .collect();
+        if !pending.is_empty() {
+            self.record_event(
+                settlement.id,
+                AuditEventType::DebtorAccepted,
+                &user_actor(debtor_id),
+                json!({
+                    "debtor_id": debtor_id,
+                    "settled_amount": signed_terms.settled_amount,
+                    "signed": signed,
+                    "envelope_id": envelope_id,
+                    "pending": pending,
+                }),
+            )
+            .await?;
+            info!(
+                "Debtor {} accepted settlement {}; {} more to accept",
+                debtor_id,
+                settlement.id,
+                pending.len()
+            );
+            return Ok(settlement);
+        }
+        
+        let mut settlement = self.transition(&settlement, SettlementStatus::Accepted).await?;
+        if let Some(terms) = terms {
+            settlement = self
+                .db
+                .record_accepted_terms(
+                    settlement.id,
+                    settlement.version,
+                    &terms.settled_amount,
+                    &terms.saved_amount,
+                    &terms.platform_fee,
+                )
+                .await?
+                .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        }
+        // A settlement rescinded and accepted again drops what it agreed to
+        // before, even if its creditor has no clauses any more.
+        if !clauses.is_empty() || settlement.clauses_hash.is_some() {
+            settlement = self
+                .db
+                .record_agreed_clauses(settlement.id, settlement.version, &clauses, clauses_hash.as_deref())
+                .await?
+                .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        }
+        self.record_event(
+            settlement.id,
+            AuditEventType::Accepted,
+            &user_actor(debtor_id),
+            json!({
+                "debtors": debtors,
+                "settled_amount": settlement.settled_amount,
+                "proposed_amount": settlement.proposed_amount,
+                "platform_fee": settlement.platform_fee,
+                "signed": signed,
+                "envelope_id": envelope_id,
+                "clauses": clauses
+                    .iter()
+                    .map(|clause| json!({ "key": clause.key, "version": clause.version }))
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await?;
+        info!("Settlement {} accepted by user {}", settlement.id, debtor_id);
+        
+        Ok(settlement)
+    }
+    
+    /// Who of the settlement's debtors has accepted its terms as they stand:
+    /// those it is open on, or those it was accepted on.
+    pub async fn settlement_acceptances(
+        &self,
+        settlement: &Settlement,
+    ) -> Result<SettlementAcceptances, SettlementError> {
+        let clauses_hash = if settlement.status.is_open() {
+            clauses_hash(&self.db.get_settlement_creditor_clauses(settlement.id).await?)
+        } else {
+            settlement.clauses_hash.clone()
+        };
+        let acceptances = self.db.get_settlement_acceptances(settlement.id).await?;
+        let debtors: Vec<DebtorAcceptance> = self
+            .settlement_debtors(settlement)
+            .await?
+            .into_iter()
+            .map(|debtor_id| {
+                let acceptance = acceptances.iter().find(|acceptance| {
+                    acceptance.debtor_id == debtor_id && acceptance.covers(settlement, clauses_hash.as_deref())
+                });
+                DebtorAcceptance {
+                    debtor_id,
+                    accepted_at: acceptance.map(|acceptance| acceptance.accepted_at),
+                    signed: acceptance.is_some_and(|acceptance| acceptance.signed),
+                }
+            })
+            .collect();
+        Ok(SettlementAcceptances {
+            settlement_id: settlement.id,
+            complete: debtors.iter().all(|debtor| debtor.accepted_at.is_some()),
+            debtors,
+        })
+    }
+    
+    /// Everyone who owes the settlement's debts, its user first.
+    async fn settlement_debtors(&self, settlement: &Settlement) -> Result<Vec<Uuid>, SettlementError> {
+        let mut debtors = self.db.get_settlement_debtors(settlement.id).await?;
+        debtors.retain(|&debtor| debtor != settlement.user_id);
+        debtors.sort_unstable();
+        debtors.insert(0, settlement.user_id);
+        Ok(debtors)
+    }
+    
+    /// `settlement` renegotiated to `accepted_amount`, with savings and the
+    /// fee recomputed from it. Refused for an amount above the original, or
+    /// once an installment plan has split the proposed amount.
+    async fn accepted_terms(
+        &self,
+        settlement: &Settlement,
+        accepted_amount: &BigDecimal,
+    ) -> Result<Settlement, SettlementError> {
+        if *accepted_amount <= BigDecimal::from(0) {
+            return Err(SettlementError::Validation("accepted_amount must be positive".to_string()));
+        }
+        if *accepted_amount > settlement.original_amount {
+            return Err(SettlementError::Validation(format!(
+                "accepted_amount {} exceeds the original amount {}",
+                accepted_amount, settlement.original_amount
+            )));
+        }
+        if round_to_minor_units(accepted_amount.clone(), &settlement.currency) != *accepted_amount {
+            return Err(SettlementError::Validation(format!(
+                "accepted_amount {} is finer than {} allows",
+                accepted_amount, settlement.currency
+            )));
+        }
+        if !self.db.get_installments(settlement.id).await?.is_empty() {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} has an installment plan for the proposed amount",
+                settlement.id
+            )));
+        }
+        
+        let settled_amount = accepted_amount.with_scale(settlement.currency.minor_units());
+        let saved_amount = &settlement.original_amount - &settled_amount;
+        let platform_fee = self
+            .compute_fee(&saved_amount, &settled_amount, &settlement.currency, &settlement.fee_policy)
+            .total();
+        let terms = Settlement {
+            settled_amount,
+            saved_amount,
+            platform_fee,
+            ..settlement.clone()
+        };
+        ensure_amounts(&terms)?;
+        Ok(terms)
+    }
+    
+    /// Checks `signature` against the user's registered key over the canonical
+    /// terms, which bind the settlement id so it can't be replayed elsewhere,
+    /// and the hash of the creditor's clauses.
+    async fn verify_acceptance(
+        &self,
+        settlement: &Settlement,
+        debtor_id: Uuid,
+        clauses_hash: Option<&str>,
+        signature: &str,
+    ) -> Result<(), SettlementError> {
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+        
+        let Some(public_key) = self.db.get_user_public_key(debtor_id).await? else {
+            warn!("User {} has no signing key registered", debtor_id);
+            return Err(SettlementError::InvalidSignature(settlement.id));
+        };
+        
+        let terms = acceptance_terms(settlement, debt.creditor_id, clauses_hash);
+        if !self
+            .blockchain_client
+            .verify_signature(terms.as_bytes(), signature, &public_key)
+        {
+            return Err(SettlementError::InvalidSignature(settlement.id));
+        }
+        
+        Ok(())
+    }
+    
+    /// Checks that `envelope_id` is a completed envelope of this settlement,
+    /// signed by `debtor_id` over the terms as they stand now.
+    async fn verify_envelope(
+        &self,
+        settlement: &Settlement,
+        debtor_id: Uuid,
+        clauses_hash: Option<&str>,
+        envelope_id: &str,
+    ) -> Result<(), SettlementError> {
+        let envelope = self
+            .db
+            .get_signature_envelope(envelope_id)
+            .await?
+            .filter(|envelope| envelope.settlement_id == settlement.id)
+            .ok_or_else(|| foreign_envelope(envelope_id, settlement.id))?;
+        
+        if envelope.debtor_id != debtor_id {
+            return Err(SettlementError::Validation(format!(
+                "envelope {} was signed by another debtor",
+                envelope_id
+            )));
+        }
+        if envelope.status != EnvelopeStatus::Completed {
+            return Err(SettlementError::Validation(format!(
+                "envelope {} is {:?}, not completed",
+                envelope_id, envelope.status
+            )));
+        }
+        
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+        if envelope.terms_hash != terms_hash(&acceptance_terms(settlement, debt.creditor_id, clauses_hash)) {
+            warn!(
+                "Envelope {} was signed over terms settlement {} no longer has",
+                envelope_id, settlement.id
+            );
+            return Err(SettlementError::InvalidSignature(settlement.id));
+        }
+        
+        Ok(())
+    }
+    
+    /// Sends the settlement's acceptance terms to the signature provider and
+    /// opens a signing session for the debtor. Once the provider reports the
+    /// envelope completed, its id can be used to accept the settlement as
+    /// that debtor.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn request_signature(
+        &self,
+        settlement_id: Uuid,
+        request: &SignatureRequest,
+    ) -> Result<SignatureRequested, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        ensure_not_expired(&settlement)?;
+        
+        if !settlement.status.is_open() {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "request a signature for",
+            });
+        }
+        if [&request.signer_name, &request.signer_email, &request.return_url]
+            .iter()
+            .any(|field| field.trim().is_empty())
+        {
+            return Err(SettlementError::Validation(
+                "signer_name, signer_email and return_url are required".to_string(),
+            ));
+        }
+        let debtor_id = request.debtor_id.unwrap_or(settlement.user_id);
+        if !self.settlement_debtors(&settlement).await?.contains(&debtor_id) {
+            return Err(SettlementError::Validation(format!(
+                "debtor {} does not owe the debt settlement {} settles",
+                debtor_id, settlement.id
+            )));
+        }
+        
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+        let clauses = self.db.get_settlement_creditor_clauses(settlement_id).await?;
+        let terms = acceptance_terms(&settlement, debt.creditor_id, clauses_hash(&clauses).as_deref());
+        
+        let created = self
+            .signature_provider
+            .create_envelope(&settlement, &terms, request)
+            .await
+            .map_err(SettlementError::SignatureProvider)?;
+        let envelope = self
+            .db
+            .insert_signature_envelope(&SignatureEnvelope {
+                envelope_id: created.envelope_id,
+                settlement_id,
+                debtor_id,
+                provider: self.signature_provider.name().to_string(),
+                terms_hash: terms_hash(&terms),
+                status: EnvelopeStatus::Sent,
+                created_at: Utc::now(),
+                completed_at: None,
+            })
+            .await?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::SignatureRequested,
+            &user_actor(debtor_id),
+            json!({
+                "envelope_id": envelope.envelope_id,
+                "provider": envelope.provider,
+            }),
+        )
+        .await?;
+        
+        Ok(SignatureRequested {
+            envelope,
+            signing_url: created.signing_url,
+        })
+    }
+    
+    /// Header the signature provider signs its callbacks in.
+    pub fn signature_callback_header(&self) -> &'static str {
+        self.signature_provider.callback_signature_header()
+    }
+    
+    /// Applies a signature provider callback for one of the settlement's
+    /// envelopes. Callbacks that don't carry the provider's signature are
+    /// refused; redeliveries and events we don't track change nothing.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn handle_signature_callback(
+        &self,
+        settlement_id: Uuid,
+        body: &[u8],
+        signature: &str,
+    ) -> Result<(), SettlementError> {
+        if !self.signature_provider.verify_callback(body, signature) {
+            warn!("Refused signature callback for settlement {}: bad signature", settlement_id);
+            return Err(SettlementError::InvalidSignature(settlement_id));
+        }
+        
+        let Some(event) = self
+            .signature_provider
+            .parse_callback(body)
+            .map_err(|e| SettlementError::Validation(format!("{:#}", e)))?
+        else {
+            return Ok(());
+        };
+        
+        let envelope = self
+            .db
+            .get_signature_envelope(&event.envelope_id)
+            .await?
+            .filter(|envelope| envelope.settlement_id == settlement_id)
+            .ok_or_else(|| foreign_envelope(&event.envelope_id, settlement_id))?;
+        let Some(envelope) = self
+            .db
+            .close_signature_envelope(&envelope.envelope_id, event.status)
+            .await?
+        else {
+            return Ok(());
+        };
+        info!(
+            "Envelope {} for settlement {} is now {:?}",
+            envelope.envelope_id, settlement_id, envelope.status
+        );
+        
+        if envelope.status == EnvelopeStatus::Completed {
+            let settlement = self
+                .db
+                .get_settlement_including_deleted(settlement_id)
+                .await?
+                .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+            self.record_event(
+                settlement_id,
+                AuditEventType::Signed,
+                &user_actor(settlement.user_id),
+                json!({
+                    "envelope_id": envelope.envelope_id,
+                    "provider": envelope.provider,
+                }),
+            )
+            .await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Undoes an acceptance during the cooling-off window (measured from
+    /// `accepted_at`, `SETTLEMENT_RESCISSION_WINDOW_HOURS`, default 72h),
+    /// returning the settlement to `Proposed`. Every debtor accepts it again.
+    ///
+    /// The platform fee is only collected as part of the on-chain payment, so
+    /// refusing rescission once a transaction has been submitted is what
+    /// guarantees a rescinded settlement is never charged. Any confirmation
+    /// watcher still running for the settlement will find it no longer
+    /// `Accepted` and give up. A submission already under way is waited out:
+    /// it may yet take a transaction.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn rescind_settlement(&self, settlement_id: Uuid, caller: &Caller) -> Result<Settlement, SettlementError> {
+        // Executing takes the same lock, so nothing is submitted while this decides.
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let rescinded = self.rescind(settlement_id, caller).await;
+        lock.commit().await?;
+        rescinded
+    }
+    
+    async fn rescind(&self, settlement_id: Uuid, caller: &Caller) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        if settlement.status != SettlementStatus::Accepted {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "rescind",
+            });
+        }
+        
+        let accepted_at = settlement.accepted_at.unwrap_or(settlement.proposed_at);
+        if Utc::now() > accepted_at + self.rescission_window {
+            return Err(SettlementError::Conflict(format!(
+                "the {}h rescission window for settlement {} closed at {}",
+                self.rescission_window.num_hours(),
+                settlement_id,
+                accepted_at + self.rescission_window
+            )));
+        }
+        
+        if settlement.transaction_hash.is_some() || settlement.submission_intent.is_some() {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} has already been submitted on-chain and can no longer be rescinded",
+                settlement_id
+            )));
+        }
+        
+        let settlement = self
+            .db
+            .record_rescission(settlement_id, settlement.version)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        self.db.clear_acceptances(settlement_id).await?;
+        self.metrics.settlement_entered(SettlementStatus::Proposed);
+        self.webhooks.notify(settlement.clone());
+        self.record_event(
+            settlement_id,
+            AuditEventType::Rescinded,
+            &caller_actor(caller),
+            json!({ "accepted_at": accepted_at, "platform_fee_charged": false }),
+        )
+        .await?;
+        
+        info!(
+            "Settlement {} rescinded within cooling-off window; no platform fee charged",
+            settlement_id
+        );
+        
+        Ok(settlement)
+    }
+    
+    /// Rejects an open proposal on the user's behalf.
+    ///
+    /// Rejection is a purely off-chain transition: nothing has been submitted to
+    /// Cardano for a `Proposed`/`Negotiating` settlement, so this path only ever
+    /// touches the database and must never reach `blockchain_client`.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn reject_settlement(
+        &self,
+        settlement_id: Uuid,
+        reason: RejectReason,
+        note: Option<&str>,
+    ) -> Result<Settlement, SettlementError> {
+        self.reject(settlement_id, reason, note, Party::Debtor).await
+    }
+    
+    /// Rejects the settlement on behalf of `by`: the user, or the creditor
+    /// declining by email.
+    async fn reject(
+        &self,
+        settlement_id: Uuid,
+        reason: RejectReason,
+        note: Option<&str>,
+        by: Party,
+    ) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        let actor = match by {
+            Party::Debtor => user_actor(settlement.user_id),
+            Party::Creditor => party_actor(by).to_string(),
+        };
+        let settlement = self.transition(&settlement, SettlementStatus::Rejected).await?;
+        let settlement = self
+            .db
+            .record_rejection(settlement_id, settlement.version, reason, note)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::Rejected,
+            &actor,
+            json!({ "reason": reason, "note": note }),
+        )
+        .await?;
+        
+        info!("Settlement {} rejected by {:?} ({:?})", settlement_id, by, reason);
+        Ok(settlement)
+    }
+    
+    /// Records a counter-offer as a new negotiation round and asks the AI what
+    /// to do about it. The first counter moves a proposal into `Negotiating`.
+    ///
+    /// A creditor counter at or below the settlement's `auto_accept_below`
+    /// is instead accepted on the user's behalf, at the countered amount,
+    /// and executed. Acceptance stands in for the user's, so with
+    /// `SETTLEMENT_REQUIRE_SIGNATURE` on it waits for them like any other.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn counter_offer(
+        &self,
+        settlement_id: Uuid,
+        offer: &CounterOffer,
+    ) -> Result<CounterOfferResponse, SettlementError> {
+        // Counters take turns with each other and with acceptances, so the
+        // threshold a counter is auto-accepted within is the one it was read
+        // against.
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let countered = self.counter(settlement_id, offer).await;
+        lock.commit().await?;
+        let mut response = countered?;
+        
+        // Execution takes the lock itself.
+        if let Some(accepted) = response.auto_accepted.take() {
+            response.auto_accepted = Some(match self.execute_settlement(settlement_id).await {
+                Ok(executed) => executed,
+                Err(e) => {
+                    warn!("Auto-accepted settlement {} was not executed: {}", settlement_id, e);
+                    accepted
+                }
+            });
+        }
+        Ok(response)
+    }
+    
+    async fn counter(
+        &self,
+        settlement_id: Uuid,
+        offer: &CounterOffer,
+    ) -> Result<CounterOfferResponse, SettlementError> {
+        let mut settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        ensure_not_expired(&settlement)?;
+        ensure_currency(&settlement.currency, offer.currency.as_ref())?;
+        
+        if offer.amount <= BigDecimal::from(0) {
+            return Err(SettlementError::Validation(
+                "counter-offer amount must be positive".to_string(),
+            ));
+        }
+        if offer.amount > settlement.original_amount {
+            return Err(SettlementError::Validation(format!(
+                "counter-offer {} exceeds the original debt of {}",
+                offer.amount, settlement.original_amount
+            )));
+        }
+        if let Some(threshold) = &offer.auto_accept_below {
+            if offer.from != Party::Debtor {
+                return Err(SettlementError::Validation(
+                    "only the debtor sets auto_accept_below".to_string(),
+                ));
+            }
+            ensure_auto_accept_below(threshold, &settlement.original_amount, &settlement.currency)?;
+        }
+        
+        match settlement.status {
+            SettlementStatus::Proposed => {
+                settlement = self.transition(&settlement, SettlementStatus::Negotiating).await?;
+            }
+            SettlementStatus::Negotiating => {}
+            status => {
+                return Err(SettlementError::InvalidStatus {
+                    settlement_id,
+                    status,
+                    action: "counter",
+                })
+            }
+        }
+        
+        if let Some(threshold) = &offer.auto_accept_below {
+            settlement = self
+                .db
+                .set_auto_accept_below(settlement_id, settlement.version, Some(threshold))
+                .await?
+                .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        }
+        
+        let within_threshold = settlement.auto_accept_below.clone().filter(|threshold| {
+            offer.from == Party::Creditor && !self.require_acceptance_signature && offer.amount <= *threshold
+        });
+        let history = self.db.get_negotiation_rounds(settlement_id).await?;
+        let mut decision = if let Some(threshold) = &within_threshold {
+            NegotiationDecision {
+                action: NegotiationAction::Accept,
+                counter_amount: None,
+                reasoning: vec![offer.locale.message(
+                    "negotiation.auto_accepted",
+                    &[
+                        ("amount", offer.amount.to_string()),
+                        ("currency", settlement.currency.to_string()),
+                        ("threshold", threshold.to_string()),
+                    ],
+                )],
+            }
+        } else {
+            match self
+                .ai_call(
+                    "counter_offer",
+                    self.ai_client.evaluate_counter_offer(&settlement, offer, &history),
+                )
+                .await
+            {
+                Ok(decision) => decision,
+                Err(e) => {
+                    self.fall_back_from("counter_offer", e)?;
+                    ai_client::counter_fallback(&settlement, offer, &history)
+                }
+            }
+        };
+        decision.counter_amount = decision
+            .counter_amount
+            .map(|amount| round_to_minor_units(amount, &settlement.currency));
+        
+        let round = self
+            .db
+            .insert_negotiation_round(&NegotiationRound {
+                id: Uuid::new_v4(),
+                settlement_id,
+                round_number: 0, // assigned by the insert
+                from_party: offer.from,
+                amount: offer.amount.clone(),
+                decision: decision.action,
+                counter_amount: decision.counter_amount.clone(),
+                created_at: Utc::now(),
+            })
+            .await?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::Negotiated,
+            party_actor(offer.from),
+            json!({
+                "round_number": round.round_number,
+                "amount": round.amount,
+                "decision": round.decision,
+                "counter_amount": round.counter_amount,
+            }),
+        )
+        .await?;
+        
+        info!(
+            "Settlement {} round {}: {:?} offered {}, decision {:?}",
+            settlement_id, round.round_number, offer.from, offer.amount, decision.action
+        );
+        
+        let auto_accepted = if within_threshold.is_some() {
+            self.auto_accept(&settlement, &round).await
+        } else {
+            None
+        };
+        Ok(CounterOfferResponse { decision, round, auto_accepted })
+    }
+    
+    /// Accepts `round`'s creditor counter for the user, who pre-authorized
+    /// it, under the settlement's lock; the caller executes it once that is
+    /// released. Whatever goes wrong is logged and leaves the counter to the
+    /// user.
+    async fn auto_accept(&self, settlement: &Settlement, round: &NegotiationRound) -> Option<Settlement> {
+        let request = AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: Some(round.amount.clone()),
+        };
+        // The user's pre-authorization is unsigned, so it can't accept for
+        // the debtors of a joint debt; they each sign.
+        let accepted = match self.accept(&request).await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!("Could not auto-accept settlement {}: {}", settlement.id, e);
+                return None;
+            }
+        };
+        if let Err(e) = self
+            .record_event(
+                settlement.id,
+                AuditEventType::AutoAccepted,
+                SYSTEM_ACTOR,
+                json!({
+                    "round_number": round.round_number,
+                    "accepted_amount": round.amount,
+                    "auto_accept_below": settlement.auto_accept_below,
+                }),
+            )
+            .await
+        {
+            warn!("Failed to record the auto-acceptance of settlement {}: {}", settlement.id, e);
+        }
+        info!(
+            "Settlement {} auto-accepted at {} within {:?}",
+            settlement.id, round.amount, settlement.auto_accept_below
+        );
+        Some(accepted)
+    }
+    
+    pub async fn get_negotiation_rounds(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<Vec<NegotiationRound>, SettlementError> {
+        if self.db.get_settlement(settlement_id).await?.is_none() {
+            return Err(SettlementError::NotFound("settlement", settlement_id));
+        }
+        
+        Ok(self.db.get_negotiation_rounds(settlement_id).await?)
+    }
+    
+    /// Splits the settled amount into a payment schedule.
+    ///
+    /// Each installment gets the settled amount divided evenly and truncated to
+    /// the currency's minor unit; whatever is left over lands on the final installment so the
+    /// schedule always sums to exactly `settled_amount`.
+    #[instrument(skip_all, fields(settlement_id = %plan.settlement_id))]
+    pub async fn create_installment_plan(
+        &self,
+        plan: &InstallmentPlan,
+    ) -> Result<InstallmentSchedule, SettlementError> {
+        if plan.num_installments == 0 {
+            return Err(SettlementError::Validation(
+                "num_installments must be at least 1".to_string(),
+            ));
+        }
+        
+        let settlement = self
+            .db
+            .get_settlement(plan.settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", plan.settlement_id))?;
+        
+        match settlement.status {
+            SettlementStatus::Proposed | SettlementStatus::Negotiating | SettlementStatus::Accepted => {}
+            status => {
+                return Err(SettlementError::InvalidStatus {
+                    settlement_id: settlement.id,
+                    status,
+                    action: "create an installment plan for",
+                })
+            }
+        }
+        
+        let amounts = split_amount(
+            &settlement.settled_amount,
+            plan.num_installments,
+            &settlement.currency,
+        );
+        let mut installments = Vec::with_capacity(amounts.len());
+        for (index, amount) in amounts.into_iter().enumerate() {
+            let due_date = installment_due_date(plan.first_due, plan.cadence, index as u32)
+                .ok_or_else(|| SettlementError::Validation("installment due date out of range".to_string()))?;
+            
+            installments.push(Installment {
+                id: Uuid::new_v4(),
+                settlement_id: settlement.id,
+                sequence: index as i32 + 1,
+                amount,
+                due_date,
+                status: InstallmentStatus::Pending,
+                paid_at: None,
+            });
+        }
+        
+        match self.db.insert_installments(&installments).await {
+            Ok(()) => {}
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                return Err(SettlementError::Conflict(format!(
+                    "settlement {} already has an installment plan",
+                    settlement.id
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        }
+        
+        info!(
+            "Created {} installment plan for settlement {}",
+            installments.len(),
+            settlement.id
+        );
+        
+        Ok(installment_schedule(&settlement, installments))
+    }
+    
+    pub async fn get_installments(&self, settlement_id: Uuid) -> Result<InstallmentSchedule, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        let installments = self.db.get_installments(settlement_id).await?;
+        Ok(installment_schedule(&settlement, installments))
+    }
+    
+    /// The debts a settlement pays off and each one's share of its settled amount.
+    pub async fn get_settlement_debts(&self, settlement_id: Uuid) -> Result<SettlementDebts, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        let components = self.db.get_settlement_debts(settlement_id).await?;
+        Ok(SettlementDebts {
+            settlement_id,
+            currency: settlement.currency.clone(),
+            debts: debt_breakdown(&settlement, components),
+        })
+    }
+    
+    /// Records payment of installment `sequence` of an accepted settlement
+    /// and returns the schedule with the platform fee accrued so far. Missed
+    /// installments can still be paid; paid ones can't be paid again. The
+    /// last payment completes a settlement whose transaction has confirmed;
+    /// one still confirming completes when it does.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn pay_installment(
+        &self,
+        settlement_id: Uuid,
+        sequence: i32,
+        caller: &Caller,
+    ) -> Result<InstallmentSchedule, SettlementError> {
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let paid = self.pay(settlement_id, sequence, caller).await;
+        lock.commit().await?;
+        paid
+    }
+    
+    async fn pay(
+        &self,
+        settlement_id: Uuid,
+        sequence: i32,
+        caller: &Caller,
+    ) -> Result<InstallmentSchedule, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        if !matches!(settlement.status, SettlementStatus::Accepted | SettlementStatus::Completed) {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "pay an installment of",
+            });
+        }
+        
+        let Some(paid) = self.db.record_installment_payment(settlement_id, sequence).await? else {
+            let installments = self.db.get_installments(settlement_id).await?;
+            if installments.iter().any(|installment| installment.sequence == sequence) {
+                return Err(SettlementError::Conflict(format!(
+                    "installment {} of settlement {} is already paid",
+                    sequence, settlement_id
+                )));
+            }
+            return Err(SettlementError::NotFound("installment of settlement", settlement_id));
+        };
+        
+        let schedule = installment_schedule(&settlement, self.db.get_installments(settlement_id).await?);
+        self.record_event(
+            settlement_id,
+            AuditEventType::InstallmentPaid,
+            &caller_actor(caller),
+            json!({
+                "sequence": paid.sequence,
+                "amount": paid.amount,
+                "accrued_fee": schedule.accrued_fee,
+                "total_fee": schedule.total_fee,
+            }),
+        )
+        .await?;
+        info!(
+            "Installment {} of settlement {} paid; fee accrued {} of {}",
+            sequence, settlement_id, schedule.accrued_fee, schedule.total_fee
+        );
+        
+        let settled = schedule
+            .installments
+            .iter()
+            .all(|installment| installment.status == InstallmentStatus::Paid);
+        if let (true, SettlementStatus::Accepted, Some(tx_hash)) =
+            (settled, settlement.status, &settlement.transaction_hash)
+        {
+            // The payment stands whatever the chain says; a later execute
+            // picks the confirmation back up.
+            match self.blockchain_client.inclusion(tx_hash).await {
+                Ok(Some(inclusion)) if inclusion.confirmations >= self.blockchain_client.min_confirmations() => {
+                    self.settle_confirmed(settlement_id, tx_hash, inclusion).await?;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Could not check tx {} of settlement {}: {}", tx_hash, settlement_id, e),
+            }
+        }
+        
+        Ok(schedule)
+    }
+    
+    /// Quotes the network fee of executing the settlement as it stands now.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn estimate_settlement_fee(&self, settlement_id: Uuid) -> Result<FeeEstimate, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        match settlement.status {
+            SettlementStatus::Proposed | SettlementStatus::Negotiating | SettlementStatus::Accepted => {}
+            status => {
+                return Err(SettlementError::InvalidStatus {
+                    settlement_id,
+                    status,
+                    action: "estimate fees for",
+                })
+            }
+        }
+        
+        let estimated_network_fee = self
+            .blockchain_client
+            .estimate_fee(&settlement)
+            .await
+            .map_err(SettlementError::Blockchain)?;
+        
+        Ok(FeeEstimate {
+            estimated_total: &settlement.settled_amount + &settlement.platform_fee + &estimated_network_fee,
+            estimated_network_fee,
+            currency: settlement.currency.clone(),
+        })
+    }
+    
+    /// Submits an accepted settlement to Cardano.
+    ///
+    /// The settlement stays `Accepted` while the transaction is pending; a
+    /// background watcher moves it to `Completed` once the tx reaches the
+    /// configured depth, or to `Failed` if it is dropped. An escrow
+    /// settlement's payment is locked in a script address instead and moves
+    /// to `Escrowed`, to be released or refunded; see [`Self::release_escrow`]
+    /// and [`Self::refund_escrow`]. One with an
+    /// installment plan only completes once every installment is paid, and
+    /// executing again reuses the recorded transaction rather than resubmitting.
+    /// Submission runs apart from the request, so a client that disconnects
+    /// or a shutdown that closes the connection cannot cut it short. While the
+    /// Cardano node's circuit breaker is open the settlement is queued instead,
+    /// with `execution_queued_at` set, and submitted by the execution queue
+    /// once the breaker closes.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn execute_settlement(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let engine = self.clone();
+        let guard = self.shutdown.track();
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                engine.submit_and_watch(settlement_id).await
+            }
+            .in_current_span(),
+        )
+        .await
+        .expect("settlement execution panicked")
+    }
+    
+    /// Submits the payment at most once: the settlement's advisory lock
+    /// serializes concurrent executes, and one that finds a transaction
+    /// already recorded returns it instead of paying again.
+    async fn submit_and_watch(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let submitted = self.submit_once(settlement_id).await;
+        lock.commit().await?;
+        let settlement = submitted?;
+        
+        // A replay of a submission that hasn't confirmed yet picks its watch
+        // back up, e.g. after a restart.
+        if settlement.status == SettlementStatus::Accepted && settlement.transaction_hash.is_some() {
+            self.spawn_confirmation(settlement.clone());
+        }
+        Ok(settlement)
+    }
+    
+    async fn submit_once(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        // A failed payment is for retry to replace, not to report back.
+        let submitted = settlement.transaction_hash.is_some() && settlement.status != SettlementStatus::Failed;
+        if !submitted && settlement.status != SettlementStatus::Accepted {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "execute",
+            });
+        }
+        if let Some(tx_hash) = settlement.transaction_hash.as_ref().filter(|_| submitted) {
+            info!("Settlement {} was already submitted in tx {}", settlement_id, tx_hash);
+            return Ok(settlement);
+        }
+        
+        self.submit_or_queue(&settlement).await
+    }
+    
+    /// Pays several accepted settlements owed to the same creditor in one
+    /// Cardano transaction, so they go out together or not at all; see
+    /// [`BatchExecution`] for what is guaranteed. The caller must be a party
+    /// to every one of them. All their advisory locks are held throughout,
+    /// so neither an execute nor another batch can pay one of them twice.
+    #[instrument(skip_all, fields(settlements = settlement_ids.len()))]
+    pub async fn execute_batch(&self, settlement_ids: &[Uuid], caller: &Caller) -> Result<BatchExecution, SettlementError> {
+        let engine = self.clone();
+        let guard = self.shutdown.track();
+        let (settlement_ids, caller) = (settlement_ids.to_vec(), caller.clone());
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                let lock = engine.db.lock_settlements(&settlement_ids).await?;
+                let submitted = engine.submit_batch_once(&settlement_ids, &caller).await;
+                lock.commit().await?;
+                let batch = submitted?;
+                
+                for settlement in &batch.settlements {
+                    engine.spawn_confirmation(settlement.clone());
+                }
+                Ok(batch)
+            }
+            .in_current_span(),
+        )
+        .await
+        .expect("batch execution panicked")
+    }
+    
+    async fn submit_batch_once(&self, settlement_ids: &[Uuid], caller: &Caller) -> Result<BatchExecution, SettlementError> {
+        let mut settlements = Vec::with_capacity(settlement_ids.len());
+        let mut creditor_id = None;
+        for &settlement_id in settlement_ids {
+            let settlement = self.authorize_settlement(settlement_id, caller).await?;
+            if settlement.status != SettlementStatus::Accepted {
+                return Err(SettlementError::InvalidStatus {
+                    settlement_id,
+                    status: settlement.status,
+                    action: "execute in a batch",
+                });
+            }
+            if settlement.execution_mode == ExecutionMode::Escrow {
+                return Err(SettlementError::Validation(format!(
+                    "settlement {} is paid through escrow, which a batch can't do",
+                    settlement_id
+                )));
+            }
+            if let Some(tx_hash) = &settlement.transaction_hash {
+                return Err(SettlementError::Conflict(format!(
+                    "settlement {} was already submitted in tx {}",
+                    settlement_id, tx_hash
+                )));
+            }
+            
+            let debt = self
+                .db
+                .get_debt(settlement.debt_id)
+                .await?
+                .ok_or(SettlementError::NotFound("debt", settlement.debt_id))?;
+            if *creditor_id.get_or_insert(debt.creditor_id) != debt.creditor_id {
+                return Err(SettlementError::Validation(format!(
+                    "settlement {} is owed to another creditor than the rest of the batch",
+                    settlement_id
+                )));
+            }
+            self.ensure_required_clauses(&settlement).await?;
+            self.ensure_acceptances(&settlement).await?;
+            settlements.push(settlement);
+        }
+        let creditor_id = creditor_id.ok_or_else(|| SettlementError::Validation("the batch is empty".to_string()))?;
+        
+        // The batch goes out under one intent; one the whole batch already
+        // shares, from an interrupted earlier attempt, is reused.
+        let intent = settlements[0].submission_intent;
+        if intent.is_none() || settlements.iter().any(|settlement| settlement.submission_intent != intent) {
+            settlements = self
+                .db
+                .set_batch_submission_intent(&settlements, Uuid::new_v4())
+                .await?
+                .ok_or_else(|| SettlementError::Conflict("a settlement in the batch changed while it was read".to_string()))?;
+        }
+        
+        let timer = self.metrics.cardano_submission_timer();
+        let submitted = self.blockchain_client.submit_batch(&settlements).await;
+        timer.observe_duration();
+        let submitted = submitted.map_err(|e| {
+            if !is_circuit_open(&e) {
+                self.metrics.cardano_submission_failed();
+            }
+            SettlementError::Blockchain(e)
+        })?;
+        let tx = submitted.first().expect("a batch transaction pays at least one settlement");
+        info!("Batch of {} settlements submitted in tx {}", settlements.len(), tx.tx_hash);
+        
+        // The payment is out; from here on a failure leaves the chain ahead
+        // of the database, which only reconciliation by hand can fix.
+        let terms: Vec<(&Settlement, &str)> = settlements
+            .iter()
+            .zip(&submitted)
+            .map(|(settlement, submitted)| (settlement, submitted.terms_hash.as_str()))
+            .collect();
+        let recorded = self
+            .db
+            .record_batch_transaction(&terms, &tx.tx_hash, &tx.contract_address, tx.metadata_label as i64)
+            .await
+            .inspect_err(|e| error!("Batch tx {} was submitted but not recorded: {}", tx.tx_hash, e))?
+            .ok_or_else(|| {
+                error!("Batch tx {} was submitted but a settlement changed before it was recorded", tx.tx_hash);
+                SettlementError::Conflict(format!(
+                    "batch tx {} was submitted but a settlement changed before it was recorded",
+                    tx.tx_hash
+                ))
+            })?;
+        
+        for (settlement, submitted) in recorded.iter().zip(&submitted) {
+            self.record_event(
+                settlement.id,
+                AuditEventType::Submitted,
+                &user_actor(settlement.user_id),
+                json!({
+                    "tx_hash": submitted.tx_hash,
+                    "contract_address": submitted.contract_address,
+                    "metadata_label": submitted.metadata_label,
+                    "terms_hash": submitted.terms_hash,
+                    "batch": settlement_ids,
+                }),
+            )
+            .await?;
+        }
+        
+        Ok(BatchExecution {
+            transaction_hash: tx.tx_hash.clone(),
+            creditor_id,
+            settlements: recorded,
+        })
+    }
+    
+    /// Checks the metadata on the settlement's payment transaction against a
+    /// hash recomputed from the terms as stored, so tampering on either side
+    /// shows up as a mismatch.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn verify_settlement_metadata(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<MetadataVerification, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        let Some(tx_hash) = settlement.transaction_hash.clone() else {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} has not been submitted on-chain",
+                settlement_id
+            )));
+        };
+        let label = settlement
+            .metadata_label
+            .unwrap_or(SETTLEMENT_METADATA_LABEL as i64);
+        
+        let on_chain_terms_hash = self
+            .blockchain_client
+            .transaction_metadata(&tx_hash, label as u64)
+            .await
+            .map_err(SettlementError::Blockchain)?
+            .map(|cbor| SettlementMetadata::for_settlement_in(&cbor, settlement_id))
+            .transpose()
+            .map_err(SettlementError::Blockchain)?
+            .flatten()
+            .map(|metadata| metadata.terms_hash);
+        
+        let expected_terms_hash = metadata::terms_hash(&settlement);
+        let verified = settlement.terms_hash.as_deref() == Some(expected_terms_hash.as_str())
+            && on_chain_terms_hash.as_deref() == Some(expected_terms_hash.as_str());
+        if !verified {
+            warn!("Settlement {} metadata does not match tx {}", settlement_id, tx_hash);
+        }
+        
+        Ok(MetadataVerification {
+            settlement_id,
+            transaction_hash: tx_hash,
+            metadata_label: label,
+            expected_terms_hash,
+            recorded_terms_hash: settlement.terms_hash,
+            on_chain_terms_hash,
+            verified,
+        })
+    }
+    
+    /// Where the settlement's payment stands on-chain, asked of the node live
+    /// but reused for `CARDANO_TX_STATUS_CACHE_SECS` (default 10) per
+    /// transaction. Before submission there is nothing to ask.
+    pub async fn transaction_status(&self, settlement: &Settlement) -> Result<TransactionStatus, SettlementError> {
+        let required_confirmations = self.blockchain_client.min_confirmations();
+        let Some(tx_hash) = settlement.transaction_hash.clone() else {
+            return Ok(TransactionStatus {
+                settlement_id: settlement.id,
+                tx_hash: None,
+                confirmations: None,
+                block_height: None,
+                required_confirmations,
+                status: TransactionState::NotSubmitted,
+                explorer_url: None,
+            });
+        };
+        
+        let cached = self
+            .tx_statuses
+            .get(&tx_hash)
+            .map(|entry| *entry)
+            .filter(|entry| entry.fetched_at.elapsed() < self.tx_status_ttl);
+        let lookup = match cached {
+            Some(lookup) => lookup,
+            None => {
+                let lookup = self.look_up_transaction(&tx_hash, required_confirmations).await?;
+                if self.tx_statuses.len() >= MAX_CACHED_TX_STATUSES {
+                    self.tx_statuses.clear();
+                }
+                self.tx_statuses.insert(tx_hash.clone(), lookup);
+                lookup
+            }
+        };
+        
+        Ok(TransactionStatus {
+            settlement_id: settlement.id,
+            confirmations: lookup.inclusion.map(|tx| tx.confirmations),
+            block_height: lookup.inclusion.map(|tx| tx.block_height),
+            required_confirmations,
+            status: lookup.state,
+            explorer_url: Some(format!("{}/{}", self.explorer_tx_url, tx_hash)),
+            tx_hash: Some(tx_hash),
+        })
+    }
+    
+    async fn look_up_transaction(
+        &self,
+        tx_hash: &str,
+        required_confirmations: u32,
+    ) -> Result<CachedTxStatus, SettlementError> {
+        let inclusion = self
+            .blockchain_client
+            .inclusion(tx_hash)
+            .await
+            .map_err(SettlementError::Blockchain)?;
+        let state = match inclusion {
+            Some(tx) if tx.confirmations >= required_confirmations => TransactionState::Confirmed,
+            Some(_) => TransactionState::Confirming,
+            None if self
+                .blockchain_client
+                .in_mempool(tx_hash)
+                .await
+                .map_err(SettlementError::Blockchain)? =>
+            {
+                TransactionState::Pending
+            }
+            None => TransactionState::NotFound,
+        };
+        
+        Ok(CachedTxStatus {
+            fetched_at: Instant::now(),
+            state,
+            inclusion,
+        })
+    }
+    
+    /// Moves a settlement to any status on behalf of support, bypassing the
+    /// lifecycle graph; `actor` and the reason go on the audit trail.
+    ///
+    /// Moves the on-chain record contradicts are still refused unless
+    /// `force_unsafe` is set: completing without a confirmed transaction or
+    /// with installments outstanding, failing a settlement that was never
+    /// submitted, escrowing one with no escrow lock and refund deadline, and
+    /// taking a completed settlement anywhere else.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn force_status(
+        &self,
+        settlement_id: Uuid,
+        request: &ForceStatusRequest,
+        actor: &str,
+    ) -> Result<Settlement, SettlementError> {
+        let reason = request.reason.trim();
+        if reason.is_empty() {
+            return Err(SettlementError::Validation("reason must not be empty".to_string()));
+        }
+        
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        let next = request.status;
+        
+        if settlement.status == next {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} is already {:?}",
+                settlement_id, next
+            )));
+        }
+        if !request.force_unsafe {
+            self.check_forced_status(&settlement, next).await?;
+        }
+        
+        let updated = self.apply_status(&settlement, next).await?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::StatusForced,
+            actor,
+            json!({
+                "from": settlement.status,
+                "to": next,
+                "reason": reason,
+                "force_unsafe": request.force_unsafe,
+            }),
+        )
+        .await?;
+        warn!(
+            "Settlement {} forced {:?} -> {:?} by {}: {}",
+            settlement_id, settlement.status, next, actor, reason
+        );
+        
+        Ok(updated)
+    }
+    
+    async fn check_forced_status(
+        &self,
+        settlement: &Settlement,
+        next: SettlementStatus,
+    ) -> Result<(), SettlementError> {
+        let unsafe_move = |why: &str| {
+            Err(SettlementError::Conflict(format!(
+                "cannot force settlement {} from {:?} to {:?}: {}; set force_unsafe to override",
+                settlement.id, settlement.status, next, why
+            )))
+        };
+        
+        if settlement.status == SettlementStatus::Completed {
+            return unsafe_move("its transaction has already confirmed");
+        }
+        
+        match next {
+            SettlementStatus::Completed => {
+                let Some(tx_hash) = settlement.transaction_hash.as_deref() else {
+                    return unsafe_move("it has no transaction");
+                };
+                let confirmations = self
+                    .blockchain_client
+                    .confirmations(tx_hash)
+                    .await
+                    .map_err(SettlementError::Blockchain)?;
+                let confirmed = confirmations
+                    .is_some_and(|depth| depth >= self.blockchain_client.min_confirmations());
+                if !confirmed {
+                    return unsafe_move("its transaction is not confirmed");
+                }
+                let outstanding = self
+                    .db
+                    .get_installments(settlement.id)
+                    .await?
+                    .iter()
+                    .any(|i| i.status != InstallmentStatus::Paid);
+                if outstanding {
+                    return unsafe_move("it has unpaid installments");
+                }
+                Ok(())
+            }
+            SettlementStatus::Failed if settlement.transaction_hash.is_none() => {
+                unsafe_move("it was never submitted")
+            }
+            SettlementStatus::Escrowed
+                if settlement.execution_mode != ExecutionMode::Escrow
+                    || settlement.transaction_hash.is_none()
+                    || settlement.escrow_refund_after.is_none() =>
+            {
+                unsafe_move("it has no escrow lock with a refund deadline")
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// Resubmits a `Failed` settlement, at most `SETTLEMENT_MAX_RETRIES` times.
+    ///
+    /// A dropped tx can still land, so the previous hash is checked first to
+    /// avoid paying twice: if it confirmed, the settlement completes; if it is
+    /// on-chain but shallow or back in the mempool, the settlement returns to
+    /// `Accepted` and is watched again. Only a tx the node has no trace of is
+    /// replaced by a fresh submission, which is queued like an execute while
+    /// the node's circuit breaker is open. The whole retry holds the
+    /// settlement's advisory lock, so it cannot race an execute into a second
+    /// submission.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn retry_settlement(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let retried = self.retry_once(settlement_id).await;
+        lock.commit().await?;
+        retried
+    }
+    
+    async fn retry_once(&self, settlement_id: Uuid) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        
+        if settlement.status != SettlementStatus::Failed {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: "retry",
+            });
+        }
+        
+        let previous_tx_hash = settlement.transaction_hash.clone();
+        if let Some(tx_hash) = previous_tx_hash.clone() {
+            let confirmations = self
+                .blockchain_client
+                .confirmations(&tx_hash)
+                .await
+                .map_err(SettlementError::Blockchain)?;
+            
+            let still_alive = match confirmations {
+                Some(_) => true,
+                None => self
+                    .blockchain_client
+                    .in_mempool(&tx_hash)
+                    .await
+                    .map_err(SettlementError::Blockchain)?,
+            };
+            
+            if still_alive {
+                let confirmed = confirmations
+                    .is_some_and(|depth| depth >= self.blockchain_client.min_confirmations());
+                let outstanding = self
+                    .db
+                    .get_installments(settlement_id)
+                    .await?
+                    .iter()
+                    .any(|i| i.status != InstallmentStatus::Paid);
+                
+                let next = match (confirmed && !outstanding, settlement.execution_mode) {
+                    (true, ExecutionMode::Direct) => SettlementStatus::Completed,
+                    (true, ExecutionMode::Escrow) => SettlementStatus::Escrowed,
+                    (false, _) => SettlementStatus::Accepted,
+                };
+                let settlement = self.transition(&settlement, next).await?;
+                self.record_event(
+                    settlement_id,
+                    AuditEventType::Retried,
+                    &user_actor(settlement.user_id),
+                    json!({ "tx_hash": tx_hash, "resubmitted": false, "confirmations": confirmations }),
+                )
+                .await?;
+                
+                if next == SettlementStatus::Accepted && !confirmed {
+                    self.spawn_confirmation(settlement.clone());
+                }
+                return Ok(settlement);
+            }
+        }
+        
+        if settlement.retry_count >= self.max_retries {
+            return Err(SettlementError::Conflict(format!(
+                "settlement {} has reached its limit of {} retries",
+                settlement_id, self.max_retries
+            )));
+        }
+        
+        let settlement = self
+            .db
+            .begin_settlement_retry(settlement_id, settlement.version, self.max_retries)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement_id))?;
+        
+        let settlement = self.transition(&settlement, SettlementStatus::Accepted).await?;
+        self.record_event(
+            settlement_id,
+            AuditEventType::Retried,
+            &user_actor(settlement.user_id),
+            json!({
+                "previous_tx_hash": previous_tx_hash,
+                "resubmitted": true,
+                "retry_count": settlement.retry_count,
+            }),
+        )
+        .await?;
+        
+        let settlement = match self.submit_or_queue(&settlement).await {
+            Ok(settlement) => settlement,
+            Err(e) => {
+                self.transition(&settlement, SettlementStatus::Failed).await?;
+                return Err(e);
+            }
+        };
+        
+        if settlement.transaction_hash.is_some() {
+            self.spawn_confirmation(settlement.clone());
+        }
+        Ok(settlement)
+    }
+    
+    /// Pays an escrowed payment out to the creditor, completing the
+    /// settlement. Only the creditor, once it is satisfied the debt is
+    /// closed, or support may release it.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn release_escrow(&self, settlement_id: Uuid, caller: &Caller) -> Result<Settlement, SettlementError> {
+        if let Caller::User(_) = caller {
+            return Err(SettlementError::Forbidden(format!(
+                "only the creditor can release the escrow of settlement {}",
+                settlement_id
+            )));
+        }
+        self.close_escrow(settlement_id, caller, SettlementStatus::Completed).await
+    }
+    
+    /// Returns an escrowed payment the creditor never released to the debtor,
+    /// ending the settlement `Refunded`.
+    ///
+    /// A refund is refused until `escrow_refund_after`, which is
+    /// `ESCROW_REFUND_AFTER_HOURS` (default 720, 30 days) after the lock was
+    /// first submitted; the escrow script enforces the same deadline, so the
+    /// chain would refuse an early refund too. Nothing happens by itself at
+    /// the deadline: until someone asks for the refund, the creditor can
+    /// still release the payment.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    pub async fn refund_escrow(&self, settlement_id: Uuid, caller: &Caller) -> Result<Settlement, SettlementError> {
+        self.close_escrow(settlement_id, caller, SettlementStatus::Refunded).await
+    }
+    
+    /// Spends the escrow lock to the party `outcome` pays. The settlement's
+    /// advisory lock is held throughout, so a release and a refund can't both
+    /// go out.
+    async fn close_escrow(
+        &self,
+        settlement_id: Uuid,
+        caller: &Caller,
+        outcome: SettlementStatus,
+    ) -> Result<Settlement, SettlementError> {
+        let lock = self.db.lock_settlement(settlement_id).await?;
+        let closed = self.close_escrow_once(settlement_id, caller, outcome).await;
+        lock.commit().await?;
+        closed
+    }
+    
+    async fn close_escrow_once(
+        &self,
+        settlement_id: Uuid,
+        caller: &Caller,
+        outcome: SettlementStatus,
+    ) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        let release = outcome == SettlementStatus::Completed;
+        if settlement.status != SettlementStatus::Escrowed {
+            return Err(SettlementError::InvalidStatus {
+                settlement_id,
+                status: settlement.status,
+                action: if release { "release" } else { "refund" },
+            });
+        }
+        
+        let spent = if release {
+            self.blockchain_client.release_escrow(&settlement).await
+        } else {
+            // Only a status forced with `force_unsafe` escrows without one.
+            let refund_after = settlement.escrow_refund_after.ok_or_else(|| {
+                SettlementError::Conflict(format!("settlement {} has no escrow refund deadline", settlement_id))
+            })?;
+            if Utc::now() < refund_after {
+                return Err(SettlementError::Conflict(format!(
+                    "the escrow of settlement {} cannot be refunded before {}",
+                    settlement_id,
+                    refund_after.to_rfc3339()
+                )));
+            }
+            self.blockchain_client.refund_escrow(&settlement).await
+        };
+        let tx_hash = spent.map_err(SettlementError::Blockchain)?;
+        let paid = if release { "creditor" } else { "debtor" };
+        info!("Escrow of settlement {} paid to the {} in tx {}", settlement_id, paid, tx_hash);
+        
+        let settlement = self.transition(&settlement, outcome).await?;
+        self.record_event(
+            settlement_id,
+            if release { AuditEventType::EscrowReleased } else { AuditEventType::EscrowRefunded },
+            &caller_actor(caller),
+            json!({ "tx_hash": tx_hash, "lock_tx_hash": settlement.transaction_hash }),
+        )
+        .await?;
+        Ok(settlement)
+    }
+    
+    /// Submits the payment, or queues it when the Cardano node's circuit
+    /// breaker is open so it goes out once the node is back.
+    async fn submit_or_queue(&self, settlement: &Settlement) -> Result<Settlement, SettlementError> {
+        self.ensure_required_clauses(settlement).await?;
+        self.ensure_acceptances(settlement).await?;
+        
+        // Written before anything goes out, so a submission interrupted
+        // before its transaction is recorded can be found again; see
+        // `recover_submissions`. One already written is reused, so the node
+        // answers a resubmission with the payment it already took.
+        let settlement = match settlement.submission_intent {
+            Some(_) => settlement.clone(),
+            None => {
+                let settlement = self
+                    .db
+                    .set_submission_intent(settlement.id, settlement.version, Some(Uuid::new_v4()))
+                    .await?
+                    .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+                // The refund deadline is built into the escrow lock, so it is
+                // fixed with the intent: a resubmission under the same intent
+                // locks under the same deadline.
+                match settlement.execution_mode {
+                    ExecutionMode::Direct => settlement,
+                    ExecutionMode::Escrow => self
+                        .db
+                        .set_escrow_refund_after(
+                            settlement.id,
+                            settlement.version,
+                            Utc::now() + self.escrow_refund_window,
+                        )
+                        .await?
+                        .ok_or(SettlementError::ConcurrentModification(settlement.id))?,
+                }
+            }
+        };
+        match self.submit_transaction(&settlement).await {
+            Err(SettlementError::Blockchain(e)) if self.cardano_unavailable(&e) => {
+                self.queue_execution(&settlement, &e).await
+            }
+            submitted => submitted,
+        }
+    }
+    
+    /// Refuses to pay a settlement until its debtor has agreed to some version
+    /// of every clause its creditor requires, naming the clauses missing.
+    async fn ensure_required_clauses(&self, settlement: &Settlement) -> Result<(), SettlementError> {
+        let missing = self.db.get_missing_required_clauses(settlement.id).await?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let missing: Vec<String> = missing
+            .iter()
+            .map(|clause| format!("{} ({} v{})", clause.title, clause.key, clause.version))
+            .collect();
+        Err(SettlementError::Conflict(format!(
+            "settlement {} cannot be executed until its debtor agrees to the clauses its creditor requires: {}",
+            settlement.id,
+            missing.join(", ")
+        )))
+    }
+    
+    /// Refuses to pay a settlement of a joint debt until every debtor has
+    /// accepted the terms it would be paid on, naming those who haven't. One
+    /// debtor's debt was accepted by its acceptance, however that came about.
+    async fn ensure_acceptances(&self, settlement: &Settlement) -> Result<(), SettlementError> {
+        if self.settlement_debtors(settlement).await?.len() == 1 {
+            return Ok(());
+        }
+        let acceptances = self.settlement_acceptances(settlement).await?;
+        if acceptances.complete {
+            return Ok(());
+        }
+        let pending: Vec<String> = acceptances.pending().iter().map(Uuid::to_string).collect();
+        Err(SettlementError::Conflict(format!(
+            "settlement {} cannot be executed until every debtor accepts it; waiting on {}",
+            settlement.id,
+            pending.join(", ")
+        )))
+    }
+    
+    async fn queue_execution(
+        &self,
+        settlement: &Settlement,
+        reason: &anyhow::Error,
+    ) -> Result<Settlement, SettlementError> {
+        let queued = self
+            .db
+            .queue_settlement_execution(settlement.id, settlement.version)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        self.record_event(
+            settlement.id,
+            AuditEventType::ExecutionQueued,
+            SYSTEM_ACTOR,
+            json!({ "reason": reason.to_string() }),
+        )
+        .await?;
+        warn!("Queued execution of settlement {}: {}", settlement.id, reason);
+        
+        Ok(queued)
+    }
+    
+    /// Submits the payment transaction, or the escrow lock, and records it on
+    /// the settlement.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn submit_transaction(&self, settlement: &Settlement) -> Result<Settlement, SettlementError> {
+        let timer = self.metrics.cardano_submission_timer();
+        let submitted = match settlement.execution_mode {
+            ExecutionMode::Direct => self.blockchain_client.submit(settlement).await,
+            ExecutionMode::Escrow => self.blockchain_client.submit_escrow(settlement).await,
+        };
+        timer.observe_duration();
+        
+        let submitted = submitted.map_err(|e| {
+            if !is_circuit_open(&e) {
+                self.metrics.cardano_submission_failed();
+            }
+            SettlementError::Blockchain(e)
+        })?;
+        info!("Settlement {} submitted in tx {}", settlement.id, submitted.tx_hash);
+        
+        let settlement = self
+            .db
+            .record_settlement_transaction(
+                settlement.id,
+                settlement.version,
+                &submitted.tx_hash,
+                &submitted.contract_address,
+                submitted.metadata_label as i64,
+                &submitted.terms_hash,
+            )
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        self.record_event(
+            settlement.id,
+            AuditEventType::Submitted,
+            &user_actor(settlement.user_id),
+            json!({
+                "tx_hash": submitted.tx_hash,
+                "contract_address": submitted.contract_address,
+                "metadata_label": submitted.metadata_label,
+                "terms_hash": submitted.terms_hash,
+                "execution_mode": settlement.execution_mode,
+            }),
+        )
+        .await?;
+        
+        Ok(settlement)
+    }
+    
+    /// The watcher outlives the request that started it but stays in its span,
+    /// so its log lines still carry the request id. A settlement this
+    /// instance is already watching isn't watched twice.
+    fn spawn_confirmation(&self, settlement: Settlement) {
+        if !self.watching.lock().unwrap().insert(settlement.id) {
+            return;
+        }
+        let engine = self.clone();
+        let guard = self.shutdown.track();
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                let settlement_id = settlement.id;
+                if let Err(e) = engine.confirm_settlement(settlement).await {
+                    error!("Confirming settlement failed: {}", e);
+                }
+                engine.watching.lock().unwrap().remove(&settlement_id);
+            }
+            .in_current_span(),
+        );
+    }
+    
+    /// Waits for the settlement's transaction to confirm and applies the
+    /// outcome: `Completed`, or `Escrowed` for an escrow lock. A timeout, or a
+    /// shutdown past its drain deadline, leaves the settlement `Accepted` so a
+    /// later execute can pick the same transaction back up.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn confirm_settlement(&self, settlement: Settlement) -> Result<Settlement, SettlementError> {
+        let tx_hash = settlement
+            .transaction_hash
+            .clone()
+            .expect("confirm_settlement requires a submitted transaction");
+        
+        let confirmation = self
+            .blockchain_client
+            .await_confirmation(&tx_hash, self.blockchain_client.min_confirmations());
+        let status = tokio::select! {
+            status = confirmation => match status {
+                Ok(status) => status,
+                // The node went away mid-watch; the queue resumes the watch
+                // once it is back.
+                Err(e) if self.cardano_unavailable(&e) => {
+                    let current = self
+                        .db
+                        .get_settlement(settlement.id)
+                        .await?
+                        .ok_or(SettlementError::NotFound("settlement", settlement.id))?;
+                    return self.queue_execution(&current, &e).await;
+                }
+                Err(e) => return Err(SettlementError::Blockchain(e)),
+            },
+            _ = self.shutdown.abandoned() => {
+                warn!(
+                    "Shutting down before settlement {} tx {} confirmed; leaving it accepted",
+                    settlement.id, tx_hash
+                );
+                return Ok(settlement);
+            }
+        };
+        
+        match status {
+            ConfirmationStatus::Confirmed { confirmations, block_height } => {
+                // Paying the last installment may settle it at the same time.
+                let lock = self.db.lock_settlement(settlement.id).await?;
+                let inclusion = TxInclusion { block_height, confirmations };
+                let settled = self.settle_confirmed(settlement.id, &tx_hash, inclusion).await;
+                lock.commit().await?;
+                settled
+            }
+            ConfirmationStatus::Dropped => {
+                warn!("Settlement {} tx {} was dropped", settlement.id, tx_hash);
+                let settlement = self.transition(&settlement, SettlementStatus::Failed).await?;
+                self.record_event(
+                    settlement.id,
+                    AuditEventType::Failed,
+                    SYSTEM_ACTOR,
+                    json!({ "tx_hash": tx_hash, "reason": "transaction dropped" }),
+                )
+                .await?;
+                Ok(settlement)
+            }
+            ConfirmationStatus::TimedOut { confirmations } => {
+                warn!(
+                    "Settlement {} tx {} still pending ({} confirmations); leaving it accepted",
+                    settlement.id, tx_hash, confirmations
+                );
+                Ok(settlement)
+            }
+        }
+    }
+    
+    /// Applies a confirmed payment: `Completed`, or `Escrowed` for an escrow
+    /// lock, unless installments are still outstanding. The caller holds the
+    /// settlement's lock; one already settled is left as it is.
+    async fn settle_confirmed(
+        &self,
+        settlement_id: Uuid,
+        tx_hash: &str,
+        inclusion: TxInclusion,
+    ) -> Result<Settlement, SettlementError> {
+        let settlement = self
+            .db
+            .get_settlement(settlement_id)
+            .await?
+            .ok_or(SettlementError::NotFound("settlement", settlement_id))?;
+        if matches!(settlement.status, SettlementStatus::Completed | SettlementStatus::Escrowed) {
+            return Ok(settlement);
+        }
+        let TxInclusion { block_height, confirmations } = inclusion;
+        
+        let installments = self.db.get_installments(settlement.id).await?;
+        let outstanding = installments
+            .iter()
+            .filter(|i| i.status != InstallmentStatus::Paid)
+            .count();
+        
+        if outstanding > 0 {
+            info!(
+                "Settlement {} has {} unpaid installments; leaving it accepted",
+                settlement.id, outstanding
+            );
+            return Ok(settlement);
+        }
+        
+        if settlement.execution_mode == ExecutionMode::Escrow {
+            let settlement = self.transition(&settlement, SettlementStatus::Escrowed).await?;
+            self.record_event(
+                settlement.id,
+                AuditEventType::Escrowed,
+                SYSTEM_ACTOR,
+                json!({
+                    "tx_hash": tx_hash,
+                    "script_address": settlement.smart_contract_address,
+                    "confirmations": confirmations,
+                    "block_height": block_height,
+                    "refund_after": settlement.escrow_refund_after,
+                }),
+            )
+            .await?;
+            return Ok(settlement);
+        }
+        
+        let settlement = self.transition(&settlement, SettlementStatus::Completed).await?;
+        self.record_event(
+            settlement.id,
+            AuditEventType::Completed,
+            SYSTEM_ACTOR,
+            json!({
+                "tx_hash": tx_hash,
+                "confirmations": confirmations,
+                "block_height": block_height,
+            }),
+        )
+        .await?;
+        Ok(settlement)
+    }
+    
+    /// The creditor's leverage snapshots in the order they were taken, at most
+    /// `MAX_LEVERAGE_HISTORY` of them starting from `since`.
+    pub async fn get_leverage_history(
+        &self,
+        creditor_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<LeverageSnapshot>, SettlementError> {
+        Ok(self
+            .db
+            .get_leverage_snapshots(creditor_id, since, MAX_LEVERAGE_HISTORY)
+            .await?)
+    }
+    
+    /// The user's creditors ranked by leverage, each as of the latest
+    /// snapshot for one of the user's settlements with it. Reused for
+    /// `LEVERAGE_LEADERBOARD_CACHE_SECS` (default 60) unless this instance
+    /// snapshots leverage for the user first.
+    pub async fn leverage_leaderboard(&self, user_id: Uuid) -> Result<LeverageLeaderboard, SettlementError> {
+        if let Some(cached) = self
+            .leaderboards
+            .get(&user_id)
+            .filter(|entry| entry.computed_at.elapsed() < self.leaderboard_ttl)
+        {
+            return Ok(cached.leaderboard.clone());
+        }
+        
+        let mut creditors: Vec<LeaderboardEntry> = self
+            .db
+            .get_user_leverage_snapshots(user_id)
+            .await?
+            .into_iter()
+            .map(|snapshot| LeaderboardEntry {
+                creditor_id: snapshot.creditor_id,
+                total_leverage_score: snapshot.analysis.total_leverage_score,
+                violation_count: snapshot.analysis.violation_count,
+                estimated_reduction_percentage: snapshot.analysis.estimated_reduction_percentage,
+                legal_strength: snapshot.analysis.legal_strength.clone(),
+                settlement_id: snapshot.settlement_id,
+                scored_at: snapshot.created_at,
+            })
+            .collect();
+        creditors.sort_by(|a, b| {
+            b.total_leverage_score
+                .total_cmp(&a.total_leverage_score)
+                .then(a.creditor_id.cmp(&b.creditor_id))
+        });
+        let leaderboard = LeverageLeaderboard {
+            user_id,
+            creditors,
+            cached_at: Utc::now(),
+        };
+        
+        if self.leaderboards.len() >= MAX_CACHED_LEADERBOARDS {
+            self.leaderboards.clear();
+        }
+        self.leaderboards.insert(
+            user_id,
+            CachedLeaderboard {
+                computed_at: Instant::now(),
+                leaderboard: leaderboard.clone(),
+            },
+        );
+        Ok(leaderboard)
+    }
+    
+    /// Records the analysis `settlement` was proposed or recomputed on, which
+    /// changes its user's leaderboard. A failure is only logged.
+    async fn snapshot_leverage(&self, settlement: &Settlement, creditor_id: Uuid, analysis: &LeverageAnalysis) {
+        if let Err(e) = self
+            .db
+            .insert_leverage_snapshot(creditor_id, Some(settlement.id), analysis)
+            .await
+        {
+            warn!("Failed to snapshot leverage for creditor {}: {}", creditor_id, e);
+        }
+        self.leaderboards.remove(&settlement.user_id);
+    }
+    
+    pub async fn get_creditor_profile(&self, creditor_id: Uuid) -> Result<CreditorProfile, SettlementError> {
+        Ok(self.db.get_creditor_profile(creditor_id).await?)
+    }
+    
+    /// Records a §809 validation request (`Requested`) or its outcome
+    /// (`Verified`, `Disputed`) against the debt.
+    pub async fn record_debt_verification(
+        &self,
+        debt_id: Uuid,
+        request: &DebtVerificationRequest,
+        caller: &Caller,
+    ) -> Result<Debt, SettlementError> {
+        if request.status == VerificationStatus::Unverified {
+            return Err(SettlementError::Validation(
+                "a verification record must be a request or an outcome".to_string(),
+            ));
+        }
+        let debt = self
+            .db
+            .get_debt(debt_id)
+            .await?
+            .ok_or(SettlementError::NotFound("debt", debt_id))?;
+        authorize_debt_verification(&debt, request.status, caller)?;
+        
+        let debt = self
+            .db
+            .record_debt_verification(&DebtVerification {
+                id: Uuid::new_v4(),
+                debt_id,
+                status: request.status,
+                note: request.note.clone(),
+                created_at: Utc::now(),
+            })
+            .await?
+            .ok_or(SettlementError::NotFound("debt", debt_id))?;
+        
+        info!("Debt {} verification status is now {:?}", debt_id, debt.verification_status);
+        Ok(debt)
+    }
+    
+    /// Stores how the creditor is reached, replacing any contact already on
+    /// file; its email is also what creditor replies are checked against.
+    pub async fn set_creditor_contact(
+        &self,
+        creditor_id: Uuid,
+        request: &CreditorContactRequest,
+    ) -> Result<CreditorContact, SettlementError> {
+        let contact = self.db.upsert_creditor_contact(&request.contact(creditor_id, Utc::now())).await?;
+        info!("Creditor {} prefers {:?}", creditor_id, contact.preferred_channel);
+        Ok(contact)
+    }
+    
+    pub async fn get_creditor_contact(&self, creditor_id: Uuid) -> Result<CreditorContact, SettlementError> {
+        self.db
+            .get_creditor_contact(creditor_id)
+            .await?
+            .ok_or(SettlementError::NotFound("creditor contact", creditor_id))
+    }
+    
+    pub async fn set_settlement_floor(
+        &self,
+        creditor_id: Uuid,
+        floor: &SettlementFloor,
+    ) -> Result<SettlementFloor, SettlementError> {
+        let settlement_floor_percentage = self
+            .db
+            .set_settlement_floor_percentage(creditor_id, floor.settlement_floor_percentage)
+            .await?;
+        info!("Creditor {} settlement floor is now {:?}%", creditor_id, settlement_floor_percentage);
+        Ok(SettlementFloor { settlement_floor_percentage })
+    }
+    
+    /// Settlements proposed from now on are paid this way; those already
+    /// proposed keep their mode.
+    pub async fn set_execution_mode(
+        &self,
+        creditor_id: Uuid,
+        request: &CreditorExecutionMode,
+    ) -> Result<CreditorExecutionMode, SettlementError> {
+        let execution_mode = self.db.set_execution_mode(creditor_id, request.execution_mode).await?;
+        info!("Creditor {} settlements are now paid {:?}", creditor_id, execution_mode);
+        Ok(CreditorExecutionMode { execution_mode })
+    }
+    
+    /// Replaces the clauses the creditor's agreements carry. Settlements
+    /// already accepted keep the versions they agreed to.
+    pub async fn set_creditor_terms(
+        &self,
+        creditor_id: Uuid,
+        request: &CreditorTermsRequest,
+    ) -> Result<CreditorTerms, SettlementError> {
+        let clauses = self.db.replace_creditor_clauses(creditor_id, &request.clauses).await?;
+        info!("Creditor {} agreements now carry {} clauses", creditor_id, clauses.len());
+        Ok(CreditorTerms { creditor_id, clauses })
+    }
+    
+    pub async fn get_creditor_terms(&self, creditor_id: Uuid) -> Result<CreditorTerms, SettlementError> {
+        let clauses = self.db.get_creditor_clauses(creditor_id).await?;
+        Ok(CreditorTerms { creditor_id, clauses })
+    }
+    
+    /// Sets the fee policy the user's new proposals are made under; those
+    /// already proposed keep theirs. `admin` is recorded as granting it.
+    pub async fn set_user_fee_policy(
+        &self,
+        user_id: Uuid,
+        policy: &FeePolicy,
+        admin: &str,
+    ) -> Result<UserFeePolicy, SettlementError> {
+        if let Some(problem) = policy.problem() {
+            return Err(SettlementError::Validation(format!("fee policy rate {}", problem)));
+        }
+        let granted = self.db.upsert_user_fee_policy(user_id, policy, admin).await?;
+        info!("User {} fee policy is now {:?}, set by {}", user_id, policy, admin);
+        Ok(granted)
+    }
+    
+    pub async fn get_user_fee_policy(&self, user_id: Uuid) -> Result<UserFeePolicy, SettlementError> {
+        self.db
+            .get_user_fee_policy(user_id)
+            .await?
+            .ok_or(SettlementError::NotFound("fee policy", user_id))
+    }
+    
+    /// Registers the creditor's URL for the events requested. A URL the
+    /// creditor has already registered isn't challenged again: its webhook
+    /// takes on any events it lacked and is returned as it is now.
+    pub async fn register_webhook(
+        &self,
+        request: &RegisterWebhookRequest,
+    ) -> Result<RegisteredWebhook, SettlementError> {
+        match reqwest::Url::parse(&request.url) {
+            Ok(url) if matches!(url.scheme(), "https" | "http") => {}
+            _ => {
+                return Err(SettlementError::Validation(
+                    "webhook url must be an absolute http(s) URL".to_string(),
+                ))
+            }
+        }
+        if request.events.is_empty() {
+            return Err(SettlementError::Validation(
+                "subscribe to at least one settlement status".to_string(),
+            ));
+        }
+        
+        let mut events = Vec::with_capacity(request.events.len());
+        for status in &request.events {
+            if !events.contains(status) {
+                events.push(*status);
+            }
+        }
+        
+        let existing = self.db.get_active_webhook(request.creditor_id, &request.url).await?;
+        match &existing {
+            Some(webhook) if events.iter().all(|status| webhook.events.contains(status)) => {
+                return Ok(RegisteredWebhook {
+                    webhook: webhook.clone(),
+                    secret: None,
+                    created: false,
+                });
+            }
+            Some(_) => {}
+            None => self
+                .webhooks
+                .verify_endpoint(&request.url)
+                .await
+                .map_err(|e| SettlementError::Validation(format!("webhook url failed the challenge: {}", e)))?,
+        }
+        
+        let id = Uuid::new_v4();
+        let webhook = self
+            .db
+            .insert_webhook(&Webhook {
+                id,
+                creditor_id: request.creditor_id,
+                url: request.url.clone(),
+                secret: WebhookDispatcher::generate_secret(),
+                events,
+                active: true,
+                created_at: Utc::now(),
+            })
+            .await?;
+        
+        // A registration racing this one may have stored the URL first.
+        let created = webhook.id == id;
+        if created {
+            info!("Registered webhook {} for creditor {}", webhook.id, webhook.creditor_id);
+        } else {
+            info!("Webhook {} of creditor {} now covers {:?}", webhook.id, webhook.creditor_id, webhook.events);
+        }
+        Ok(RegisteredWebhook {
+            secret: created.then(|| webhook.secret.clone()),
+            webhook,
+            created,
+        })
+    }
+    
+    pub async fn list_webhooks(&self, query: &ListWebhooksQuery) -> Result<Vec<Webhook>, SettlementError> {
+        Ok(self.db.get_creditor_webhooks(query.creditor_id).await?)
+    }
+    
+    pub async fn get_webhook(&self, webhook_id: Uuid) -> Result<Webhook, SettlementError> {
+        self.db
+            .get_webhook(webhook_id)
+            .await?
+            .ok_or(SettlementError::NotFound("webhook", webhook_id))
+    }
+    
+    /// Stops deliveries to the webhook; one already deleted is returned as is.
+    pub async fn delete_webhook(&self, webhook_id: Uuid) -> Result<Webhook, SettlementError> {
+        let webhook = self
+            .db
+            .deactivate_webhook(webhook_id)
+            .await?
+            .ok_or(SettlementError::NotFound("webhook", webhook_id))?;
+        info!("Deleted webhook {} of creditor {}", webhook.id, webhook.creditor_id);
+        Ok(webhook)
+    }
+    
+    pub async fn webhook_dead_letters(
+        &self,
+        query: &ListDeadLettersQuery,
+    ) -> Result<Vec<WebhookDeadLetter>, SettlementError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        Ok(self
+            .db
+            .get_webhook_dead_letters(query.include_redelivered, limit as i64)
+            .await?)
+    }
+    
+    /// Replays a dead-lettered delivery once, on `admin`'s say-so. A failed
+    /// replay leaves the dead letter open with the new error; one already
+    /// redelivered is a conflict.
+    pub async fn redeliver_webhook_dead_letter(
+        &self,
+        id: Uuid,
+        admin: &str,
+    ) -> Result<WebhookDeadLetter, SettlementError> {
+        let letter = self
+            .db
+            .get_webhook_dead_letter(id)
+            .await?
+            .ok_or(SettlementError::NotFound("webhook dead letter", id))?;
+        let already_redelivered = || SettlementError::Conflict(format!("webhook dead letter {} was already redelivered", id));
+        if letter.redelivered_at.is_some() {
+            return Err(already_redelivered());
+        }
+        let webhook = self
+            .db
+            .get_webhook(letter.webhook_id)
+            .await?
+            .ok_or(SettlementError::NotFound("webhook", letter.webhook_id))?;
+        
+        info!("Admin {} is redelivering webhook dead letter {}", admin, id);
+        self.webhooks
+            .redeliver(&webhook, &letter)
+            .await?
+            .ok_or_else(already_redelivered)
+    }
+    
+    pub async fn get_settlement_events(&self, settlement_id: Uuid) -> Result<Vec<AuditEvent>, SettlementError> {
+        if self.db.get_settlement(settlement_id).await?.is_none() {
+            return Err(SettlementError::NotFound("settlement", settlement_id));
+        }
+        
+        Ok(self.db.get_settlement_events(settlement_id).await?)
+    }
+    
+    /// The settlement's audit trail as it grows: a replay of the events after
+    /// `last_event_id` (or of the most recent ones), then each event as it is
+    /// recorded, with a keep-alive every `EVENT_STREAM_KEEPALIVE_SECS`
+    /// (default 15) while nothing happens. Dropping the stream ends its
+    /// subscription.
+    pub async fn settlement_event_stream(
+        &self,
+        settlement_id: Uuid,
+        last_event_id: Option<i64>,
+    ) -> Result<impl Stream<Item = StreamedEvent>, SettlementError> {
+        if self.db.get_settlement(settlement_id).await?.is_none() {
+            return Err(SettlementError::NotFound("settlement", settlement_id));
+        }
+        
+        Ok(event_stream::settlement_events(
+            self.db.clone(),
+            &self.event_hub,
+            self.shutdown.clone(),
+            settlement_id,
+            last_event_id,
+            self.event_stream_keep_alive,
+        )
+        .await?)
+    }
+    
+    /// Event streams open on this instance.
+    pub fn open_event_streams(&self) -> usize {
+        self.event_hub.subscriptions()
+    }
+    
+    /// Appends to the settlement's audit trail. A failed write fails the
+    /// operation: compliance needs every state change on record.
+    async fn record_event(
+        &self,
+        settlement_id: Uuid,
+        event_type: AuditEventType,
+        actor: &str,
+        metadata: serde_json::Value,
+    ) -> Result<AuditEvent, SettlementError> {
+        let event = self
+            .db
+            .insert_settlement_event(settlement_id, event_type, actor, &metadata)
+            .await?;
+        self.event_hub.publish(&event);
+        Ok(event)
+    }
+    
+    /// The creditor's expiry window, or `SETTLEMENT_EXPIRY_DAYS` (default 14).
+    async fn proposal_expiry_for(&self, creditor_id: Uuid) -> Result<Duration, SettlementError> {
+        Ok(self
+            .db
+            .get_proposal_expiry_days(creditor_id)
+            .await?
+            .map(|days| Duration::days(days.into()))
+            .unwrap_or(self.proposal_expiry))
+    }
+    
+    /// Proposes a settlement of the user's active debt with the creditor on
+    /// their behalf, from the violations the trigger supplied. A dry run only
+    /// previews the proposal.
+    #[instrument(skip_all, fields(creditor_id = %request.creditor_id, dry_run = request.dry_run))]
+    pub async fn auto_negotiate(
+        &self,
+        request: &AutoNegotiateRequest,
+    ) -> Result<SettlementProposal, SettlementError> {
+        let settlement_request = request.settlement_request();
+        if request.dry_run {
+            return self.preview_settlement_proposal(&settlement_request).await;
+        }
+        
+        let proposal = self.create_settlement_proposal(&settlement_request).await?;
+        info!(
+            "Auto-negotiated settlement {} for user {} with creditor {} ({})",
+            proposal.settlement.id, request.user_id, request.creditor_id, request.trigger
+        );
+        Ok(proposal)
+    }
+    
+    /// Checks the signature a SWORD protocol event was delivered with.
+    pub fn verify_sword_event(&self, body: &[u8], signature: &str) -> Result<(), SettlementError> {
+        if !self.sword_trigger.verify(body, signature) {
+            warn!("Refused SWORD trigger: bad signature");
+            return Err(SettlementError::InvalidTriggerSignature(SWORD_TRIGGER));
+        }
+        Ok(())
+    }
+    
+    /// Queues auto-negotiation for a verified SWORD protocol event. Redelivery
+    /// of an event already queued for the user and creditor returns the job
+    /// it queued rather than negotiating twice.
+    pub async fn enqueue_sword_event(&self, event: &SwordEvent) -> Result<EnqueuedJob, SettlementError> {
+        let (job, created) = self
+            .db
+            .enqueue_auto_negotiation_job(&AutoNegotiationJob::for_sword_event(event, Utc::now()))
+            .await?;
+        
+        if created {
+            info!(
+                "Queued auto-negotiation {} for user {} with creditor {} (SWORD event {})",
+                job.id, job.user_id, job.creditor_id, job.event_id
+            );
+            self.auto_negotiation_wakeup.notify_one();
+        }
+        Ok(EnqueuedJob { job, created })
+    }
+    
+    /// Checks the signature a creditor email was delivered with.
+    pub fn verify_creditor_email(&self, body: &[u8], signature: &str) -> Result<(), SettlementError> {
+        if !self.email_trigger.verify(body, signature) {
+            warn!("Refused creditor email: bad signature");
+            return Err(SettlementError::InvalidTriggerSignature(EMAIL_TRIGGER));
+        }
+        Ok(())
+    }
+    
+    /// Acts on a creditor's reply to a proposal. The subject's reference
+    /// number names the settlement, and the AI service reads the body: an
+    /// acceptance or a counter is recorded as the creditor's negotiation
+    /// round, a refusal rejects the settlement. An email that can't be acted
+    /// on is kept for review with the reason: no known reference, a sender
+    /// other than the creditor's contact address, a reading less sure than
+    /// `EMAIL_REPLY_MIN_CONFIDENCE` (default 0.7), or a negotiation that
+    /// refused it. A redelivered message is returned as first received.
+    pub async fn receive_creditor_email(&self, email: &InboundEmail) -> Result<ReceivedEmail, SettlementError> {
+        let (received, created) = self
+            .db
+            .claim_creditor_email(&CreditorEmail::received(email, Utc::now()))
+            .await?;
+        if !created {
+            return Ok(ReceivedEmail { email: received, created });
+        }
+        
+        let mut reading = EmailReading::default();
+        let (status, review_reason) = match self.act_on_creditor_email(email, &mut reading).await {
+            Ok(()) => {
+                info!(
+                    "Creditor email {} acted on for settlement {:?}: {:?}",
+                    received.id, reading.settlement_id, reading.intent
+                );
+                (CreditorEmailStatus::Processed, None)
+            }
+            Err(reason) => {
+                warn!("Creditor email {} needs review: {}", received.id, reason);
+                (CreditorEmailStatus::NeedsReview, Some(reason))
+            }
+        };
+        let received = self
+            .db
+            .record_creditor_email_outcome(
+                received.id,
+                reading.settlement_id,
+                reading.intent,
+                reading.amount.as_ref(),
+                status,
+                review_reason.as_deref(),
+            )
+            .await?;
+        Ok(ReceivedEmail { email: received, created })
+    }
+    
+    /// Does what `email` asks, noting what it was read as along the way;
+    /// otherwise says why it can't be acted on.
+    async fn act_on_creditor_email(&self, email: &InboundEmail, reading: &mut EmailReading) -> Result<(), String> {
+        let reference = email
+            .reference_number()
+            .ok_or_else(|| "the subject names no settlement reference".to_string())?;
+        let settlement = self.get_settlement_by_reference(&reference).await.map_err(|e| e.to_string())?;
+        reading.settlement_id = Some(settlement.id);
+        
+        let debt = self
+            .db
+            .get_debt(settlement.debt_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("debt {} not found", settlement.debt_id))?;
+        let contact = self
+            .db
+            .get_creditor_contact(debt.creditor_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .and_then(|contact| contact.email);
+        let sender = email.sender_address();
+        match contact {
+            Some(contact) if Some(contact.trim().to_ascii_lowercase()) == sender => {}
+            Some(_) => return Err(format!("{} is not the creditor's contact address", email.from)),
+            None => return Err("the creditor has no contact address to check the sender against".to_string()),
+        }
+        
+        let reply = self
+            .ai_call("creditor_reply", self.ai_client.parse_creditor_reply(email, &settlement))
+            .await
+            .map_err(|e| format!("the reply could not be read: {}", e))?;
+        reading.intent = Some(reply.intent);
+        reading.amount = reply.amount.clone();
+        if reply.intent == ReplyIntent::Unclear || reply.confidence < self.email_reply_min_confidence {
+            return Err(format!("read as {:?} with confidence {}", reply.intent, reply.confidence));
+        }
+        
+        let counter = |amount: BigDecimal| CounterOffer {
+            amount,
+            from: Party::Creditor,
+            currency: None,
+            locale: Locale::default(),
+            auto_accept_below: None,
+        };
+        let acted = match reply.intent {
+            ReplyIntent::Counter => {
+                let amount = reply.amount.ok_or_else(|| "read as a counter without an amount".to_string())?;
+                self.counter_offer(settlement.id, &counter(amount)).await.map(drop)
+            }
+            ReplyIntent::Accept => self
+                .counter_offer(settlement.id, &counter(settlement.settled_amount.clone()))
+                .await
+                .map(drop),
+            ReplyIntent::Reject => self
+                .reject(settlement.id, RejectReason::Other, Some(EMAIL_REJECTION_NOTE), Party::Creditor)
+                .await
+                .map(drop),
+            ReplyIntent::Unclear => unreachable!("unclear replies are left for review"),
+        };
+        acted.map_err(|e| e.to_string())
+    }
+    
+    /// Creditor emails in `status`, those needing review unless given,
+    /// oldest first.
+    pub async fn creditor_emails(
+        &self,
+        query: &ListCreditorEmailsQuery,
+    ) -> Result<Vec<CreditorEmail>, SettlementError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let status = query.status.unwrap_or(CreditorEmailStatus::NeedsReview);
+        Ok(self.db.get_creditor_emails(status, limit as i64).await?)
+    }
+    
+    /// Starts `AUTO_NEGOTIATION_WORKERS` (default 4) workers that run queued
+    /// auto-negotiation jobs. Idle workers wake when this instance queues a
+    /// job, and otherwise every `AUTO_NEGOTIATION_POLL_INTERVAL_SECS` (default
+    /// 30) to pick up jobs queued elsewhere. Safe to run on every instance:
+    /// each job is claimed by exactly one worker. Workers stop claiming jobs
+    /// once shutdown begins.
+    pub fn spawn_auto_negotiation_workers(&self) {
+        for worker in 0..self.auto_negotiation_workers {
+            let engine = self.clone();
+            tokio::spawn(
+                async move {
+                    while !engine.shutdown.is_draining() {
+                        match engine.run_next_auto_negotiation_job().await {
+                            Ok(Some(_)) => continue,
+                            Ok(None) => {}
+                            Err(e) => error!("Auto-negotiation worker {} failed: {}", worker, e),
+                        }
+                        let wakeup = engine.auto_negotiation_wakeup.notified();
+                        tokio::select! {
+                            _ = tokio::time::timeout(engine.auto_negotiation_poll_interval, wakeup) => {}
+                            _ = engine.shutdown.draining() => {}
+                        }
+                    }
+                }
+                .instrument(info_span!("auto_negotiation_worker", worker)),
+            );
+        }
+    }
+    
+    /// Claims the oldest queued job, runs it and records the outcome.
+    /// Returns `None` when there was nothing to run. A job still running when
+    /// a shutdown gives up on it goes back to `pending` for the next worker.
+    #[instrument(skip_all, fields(job_id = tracing::field::Empty))]
+    pub async fn run_next_auto_negotiation_job(&self) -> Result<Option<AutoNegotiationJob>, SettlementError> {
+        let stale_before = Utc::now()
+            - Duration::from_std(self.auto_negotiation_job_timeout).expect("job timeout fits a chrono duration");
+        let Some(job) = self.db.claim_auto_negotiation_job(stale_before).await? else {
+            return Ok(None);
+        };
+        Span::current().record("job_id", tracing::field::display(job.id));
+        let _guard = self.shutdown.track();
+        
+        let request = job.request();
+        let run = tokio::time::timeout(self.auto_negotiation_job_timeout, self.auto_negotiate(&request));
+        let outcome = tokio::select! {
+            outcome = run => match outcome {
+                Ok(outcome) => outcome.map_err(|e| e.to_string()),
+                Err(_) => Err(format!("timed out after {}s", self.auto_negotiation_job_timeout.as_secs())),
+            },
+            _ = self.shutdown.abandoned() => {
+                warn!("Shutting down before auto-negotiation {} finished; requeueing it", job.id);
+                return Ok(Some(self.db.release_auto_negotiation_job(job.id).await?));
+            }
+        };
+        
+        let job = match outcome {
+            Ok(proposal) => {
+                self.db
+                    .finish_auto_negotiation_job(
+                        job.id,
+                        AutoNegotiationStatus::Succeeded,
+                        Some(proposal.settlement.id),
+                        None,
+                    )
+                    .await?
+            }
+            Err(e) => {
+                warn!("Auto-negotiation {} for user {} failed: {}", job.id, job.user_id, e);
+                self.db
+                    .finish_auto_negotiation_job(job.id, AutoNegotiationStatus::Failed, None, Some(&e))
+                    .await?
+            }
+        };
+        Ok(Some(job))
+    }
+    
+    /// Starts the background task that moves open proposals past their
+    /// `expires_at` to `Expired`, every `SETTLEMENT_EXPIRY_SWEEP_INTERVAL_SECS`
+    /// (default 300), and reminds both parties to negotiations about to get
+    /// there, and deletes expired drafts. Safe to run on every instance: a
+    /// proposal two sweeps race for is only expired, and each reminder only
+    /// sent, once. Stops once shutdown begins.
+    pub fn spawn_expiry_sweeper(&self) {
+        let engine = self.clone();
+        tokio::spawn(
+            async move {
+                let mut interval = tokio::time::interval(engine.expiry_sweep_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        _ = engine.shutdown.draining() => break,
+                    }
+                    match engine.expire_settlements().await {
+                        Ok(0) => {}
+                        Ok(expired) => info!("Expired {} settlement proposals", expired),
+                        Err(e) => error!("Settlement expiry sweep failed: {}", e),
+                    }
+                    match engine.send_deadline_reminders().await {
+                        Ok(0) => {}
+                        Ok(sent) => info!("Sent {} negotiation deadline reminders", sent),
+                        Err(e) => error!("Negotiation deadline reminders failed: {}", e),
+                    }
+                    match engine.db.delete_expired_drafts(Utc::now()).await {
+                        Ok(0) => {}
+                        Ok(deleted) => info!("Deleted {} expired settlement drafts", deleted),
+                        Err(e) => error!("Deleting expired settlement drafts failed: {}", e),
+                    }
+                }
+            }
+            .instrument(info_span!("expiry_sweeper")),
+        );
+    }
+    
+    /// Reminds the user and the creditor of each negotiation that has come
+    /// within one of `SETTLEMENT_REMINDER_HOURS` (default 72 and 24) of
+    /// expiring, and returns how many were reminded. A sweep that missed a
+    /// threshold sends only the most urgent one reached; running twice sends
+    /// nothing new.
+    pub async fn send_deadline_reminders(&self) -> Result<usize, SettlementError> {
+        let mut sent = 0;
+        loop {
+            let due = self
+                .db
+                .get_due_reminders(Utc::now(), self.reminder_hours.hours(), EXPIRY_SWEEP_BATCH_SIZE)
+                .await?;
+            
+            for DueReminder { settlement, threshold_hours } in &due {
+                if !self.db.claim_reminder(settlement.id, *threshold_hours).await? {
+                    // Another sweep sent it.
+                    continue;
+                }
+                self.user_notifier
+                    .deadline_approaching(settlement, *threshold_hours, &Locale::default());
+                self.webhooks.deadline_approaching(settlement.clone(), *threshold_hours);
+                self.record_event(
+                    settlement.id,
+                    AuditEventType::DeadlineReminder,
+                    SYSTEM_ACTOR,
+                    json!({
+                        "threshold_hours": threshold_hours,
+                        "expires_at": settlement.expires_at,
+                        "notified": ["user", "creditor"],
+                    }),
+                )
+                .await?;
+                sent += 1;
+            }
+            
+            if (due.len() as i64) < EXPIRY_SWEEP_BATCH_SIZE {
+                return Ok(sent);
+            }
+        }
+    }
+    
+    /// Expires every open proposal past its deadline and returns how many.
+    /// One that is acted on mid-sweep keeps whatever the other writer did.
+    pub async fn expire_settlements(&self) -> Result<usize, SettlementError> {
+        let mut expired = 0;
+        loop {
+            let batch = self
+                .db
+                .get_expired_settlements(Utc::now(), EXPIRY_SWEEP_BATCH_SIZE)
+                .await?;
+            let mut progressed = false;
+            
+            for settlement in &batch {
+                match self.transition(settlement, SettlementStatus::Expired).await {
+                    Ok(settlement) => {
+                        self.record_event(
+                            settlement.id,
+                            AuditEventType::Expired,
+                            SYSTEM_ACTOR,
+                            json!({ "expires_at": settlement.expires_at }),
+                        )
+                        .await?;
+                        expired += 1;
+                        progressed = true;
+                    }
+                    Err(SettlementError::ConcurrentModification(_)) => {
+                        progressed = true;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            
+            if !progressed || (batch.len() as i64) < EXPIRY_SWEEP_BATCH_SIZE {
+                return Ok(expired);
+            }
+        }
+    }
+    
+    /// Starts the background task that checks completed settlements against
+    /// the chain every `RECONCILIATION_INTERVAL_SECS` (default 3600); see
+    /// `reconcile_settlements`. Stops once shutdown begins.
+    pub fn spawn_reconciler(&self) {
+        let engine = self.clone();
+        tokio::spawn(
+            async move {
+                let mut interval = tokio::time::interval(engine.reconciliation_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        _ = engine.shutdown.draining() => break,
+                    }
+                    match engine.reconcile_settlements().await {
+                        Ok(run) if run.flagged > 0 => warn!(
+                            "Reconciliation flagged {} of {} completed settlements for review",
+                            run.flagged, run.checked
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!("Settlement reconciliation failed: {}", e),
+                    }
+                }
+            }
+            .instrument(info_span!("reconciler")),
+        );
+    }
+    
+    /// Starts the background task that runs the execution queue every
+    /// `SETTLEMENT_EXECUTION_QUEUE_INTERVAL_SECS` (default 30); see
+    /// `run_execution_queue`. Stops once shutdown begins.
+    pub fn spawn_execution_queue(&self) {
+        let engine = self.clone();
+        tokio::spawn(
+            async move {
+                let mut interval = tokio::time::interval(engine.execution_queue_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        _ = engine.shutdown.draining() => break,
+                    }
+                    match engine.run_execution_queue().await {
+                        Ok(0) => {}
+                        Ok(executed) => info!("Executed {} queued settlements", executed),
+                        Err(e) => error!("Execution queue pass failed: {}", e),
+                    }
+                }
+            }
+            .instrument(info_span!("execution_queue")),
+        );
+    }
+    
+    /// Executes settlements queued while the Cardano node's circuit breaker
+    /// was open, longest waiting first, and returns how many went through.
+    /// Nothing is tried while the breaker is open; while it is half-open a
+    /// tip query probes the node first. The pass stops as soon as a
+    /// settlement is queued again. One whose execution fails for another
+    /// reason is left off the queue, as if its execute had failed.
+    pub async fn run_execution_queue(&self) -> Result<usize, SettlementError> {
+        let mut executed = 0;
+        loop {
+            match self.cardano_circuit_state() {
+                Some(CircuitState::Open) => return Ok(executed),
+                Some(CircuitState::HalfOpen) if self.blockchain_client.tip_height().await.is_err() => {
+                    return Ok(executed);
+                }
+                _ => {}
+            }
+            
+            let batch = self.db.get_queued_executions(EXECUTION_QUEUE_BATCH_SIZE).await?;
+            for &settlement_id in &batch {
+                if self.db.dequeue_settlement_execution(settlement_id).await?.is_none() {
+                    continue;
+                }
+                let _guard = self.shutdown.track();
+                match self.submit_and_watch(settlement_id).await {
+                    Ok(settlement) if settlement.execution_queued_at.is_some() => return Ok(executed),
+                    Ok(_) => executed += 1,
+                    Err(e) => warn!("Queued execution of settlement {} failed: {}", settlement_id, e),
+                }
+            }
+            
+            if (batch.len() as i64) < EXECUTION_QUEUE_BATCH_SIZE {
+                return Ok(executed);
+            }
+        }
+    }
+    
+    /// Resolves every submission interrupted, as by a crash, between being
+    /// sent and having its transaction recorded, and returns how many it
+    /// resolved. The node is asked what it took under each settlement's
+    /// `submission_intent`: a transaction it took is recorded and watched as
+    /// if the submission had finished; with none, the intent is cleared and
+    /// the settlement can be executed again. One the node can't be asked
+    /// about is left for the next start; executing it meanwhile resubmits
+    /// under the same intent, which the node answers with any payment it
+    /// already took.
+    pub async fn recover_submissions(&self) -> Result<usize, SettlementError> {
+        let interrupted = self.db.get_interrupted_submissions().await?;
+        let mut recovered = 0;
+        for settlement in &interrupted {
+            let lock = self.db.lock_settlement(settlement.id).await?;
+            let resolved = self.recover_submission(settlement.id).await;
+            lock.commit().await?;
+            match resolved {
+                Ok(Some(settlement)) => {
+                    if settlement.transaction_hash.is_some() {
+                        self.spawn_confirmation(settlement);
+                    }
+                    recovered += 1;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Could not recover the submission of settlement {}: {}", settlement.id, e),
+            }
+        }
+        Ok(recovered)
+    }
+    
+    /// `None` when the settlement is no longer interrupted, as when an
+    /// execute got to it first.
+    #[instrument(skip_all, fields(settlement_id = %settlement_id))]
+    async fn recover_submission(&self, settlement_id: Uuid) -> Result<Option<Settlement>, SettlementError> {
+        let Some(settlement) = self.db.get_settlement(settlement_id).await? else {
+            return Ok(None);
+        };
+        let Some(intent) = settlement.submission_intent else {
+            return Ok(None);
+        };
+        if settlement.status != SettlementStatus::Accepted || settlement.transaction_hash.is_some() {
+            return Ok(None);
+        }
+        
+        let found = self
+            .blockchain_client
+            .find_submission(&settlement)
+            .await
+            .map_err(SettlementError::Blockchain)?;
+        let Some(submitted) = found else {
+            let settlement = self
+                .db
+                .set_submission_intent(settlement.id, settlement.version, None)
+                .await?
+                .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+            self.record_event(
+                settlement.id,
+                AuditEventType::SubmissionRecovered,
+                SYSTEM_ACTOR,
+                json!({ "submission_intent": intent, "tx_hash": null }),
+            )
+            .await?;
+            info!("Settlement {} was never submitted; it can be executed again", settlement.id);
+            return Ok(Some(settlement));
+        };
+        
+        let settlement = self
+            .db
+            .record_settlement_transaction(
+                settlement.id,
+                settlement.version,
+                &submitted.tx_hash,
+                &submitted.contract_address,
+                submitted.metadata_label as i64,
+                &submitted.terms_hash,
+            )
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        self.record_event(
+            settlement.id,
+            AuditEventType::SubmissionRecovered,
+            SYSTEM_ACTOR,
+            json!({
+                "submission_intent": intent,
+                "tx_hash": submitted.tx_hash,
+                "contract_address": submitted.contract_address,
+                "metadata_label": submitted.metadata_label,
+                "terms_hash": submitted.terms_hash,
+            }),
+        )
+        .await?;
+        info!("Recovered settlement {} submitted in tx {}", settlement.id, submitted.tx_hash);
+        Ok(Some(settlement))
+    }
+    
+    /// Looks up the transaction of every completed settlement and moves any
+    /// whose transaction is neither in a block nor the mempool to
+    /// `NeedsReview`. A settlement the node can't be asked about is counted
+    /// as an error and checked again next pass.
+    pub async fn reconcile_settlements(&self) -> Result<ReconciliationRun, SettlementError> {
+        let started_at = Utc::now();
+        let (mut checked, mut flagged, mut errors) = (0, 0, 0);
+        let mut after = None;
+        
+        loop {
+            let batch = self
+                .db
+                .get_completed_settlements_with_transactions(after, RECONCILIATION_BATCH_SIZE)
+                .await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after = Some(last.id);
+            
+            for settlement in &batch {
+                checked += 1;
+                match self.reconcile_settlement(settlement).await {
+                    Ok(true) => flagged += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        errors += 1;
+                        warn!("Could not reconcile settlement {}: {}", settlement.id, e);
+                    }
+                }
+            }
+        }
+        
+        let run = ReconciliationRun {
+            started_at,
+            finished_at: Utc::now(),
+            checked,
+            flagged,
+            errors,
+        };
+        *self.last_reconciliation.lock().unwrap() = Some(run.clone());
+        Ok(run)
+    }
+    
+    /// Flags `settlement` if the chain has no trace of its transaction and
+    /// says whether it did. A transaction back in the mempool or in a shallow
+    /// block, as after a rollback, is left to settle.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id))]
+    async fn reconcile_settlement(&self, settlement: &Settlement) -> Result<bool, SettlementError> {
+        let tx_hash = settlement
+            .transaction_hash
+            .clone()
+            .expect("reconciled settlements have a transaction");
+        
+        let chain_state = match self
+            .blockchain_client
+            .confirmations(&tx_hash)
+            .await
+            .map_err(SettlementError::Blockchain)?
+        {
+            Some(depth) if depth >= self.blockchain_client.min_confirmations() => ChainState::Confirmed,
+            Some(_) => ChainState::Pending,
+            None if self.blockchain_client.in_mempool(&tx_hash).await.map_err(SettlementError::Blockchain)? => {
+                ChainState::Pending
+            }
+            None => ChainState::Dropped,
+        };
+        
+        match chain_state {
+            ChainState::Confirmed => return Ok(false),
+            ChainState::Pending => {
+                warn!(
+                    "Completed settlement {} tx {} is not confirmed on-chain yet",
+                    settlement.id, tx_hash
+                );
+                return Ok(false);
+            }
+            ChainState::Dropped => {}
+        }
+        
+        error!(
+            "Completed settlement {} tx {} is not on-chain; flagging it for review",
+            settlement.id, tx_hash
+        );
+        let flag = ReviewFlag {
+            recorded_status: settlement.status,
+            chain_state,
+            tx_hash,
+        };
+        let settlement = self.transition(settlement, SettlementStatus::NeedsReview).await?;
+        self.metrics.reconciliation_mismatch();
+        self.record_event(
+            settlement.id,
+            AuditEventType::FlaggedForReview,
+            SYSTEM_ACTOR,
+            serde_json::to_value(&flag).expect("review flag serializes"),
+        )
+        .await?;
+        Ok(true)
+    }
+    
+    /// Every settlement waiting in `NeedsReview`, with this instance's latest
+    /// reconciliation pass.
+    pub async fn reconciliation_report(&self) -> Result<ReconciliationReport, SettlementError> {
+        let last_run = self.last_reconciliation.lock().unwrap().clone();
+        Ok(ReconciliationReport {
+            generated_at: Utc::now(),
+            last_run,
+            discrepancies: self.db.get_discrepancies().await?,
+        })
+    }
+    
+    /// The only place settlement status is allowed to change, apart from a
+    /// support override through `force_status`. Checks the move against the
+    /// lifecycle graph and applies it against the version we read, so an
+    /// illegal or concurrently-raced transition surfaces as an error instead
+    /// of corrupting the record.
+    #[instrument(skip_all, fields(settlement_id = %settlement.id, from = ?settlement.status, to = ?next))]
+    async fn transition(
+        &self,
+        settlement: &Settlement,
+        next: SettlementStatus,
+    ) -> Result<Settlement, SettlementError> {
+        if !settlement.status.can_transition_to(&next) {
+            return Err(SettlementError::InvalidTransition {
+                settlement_id: settlement.id,
+                from: settlement.status,
+                to: next,
+            });
+        }
+        
+        self.apply_status(settlement, next).await
+    }
+    
+    async fn apply_status(
+        &self,
+        settlement: &Settlement,
+        next: SettlementStatus,
+    ) -> Result<Settlement, SettlementError> {
+        let updated = self
+            .db
+            .transition_settlement_status(settlement.id, settlement.version, settlement.status, next)
+            .await?
+            .ok_or(SettlementError::ConcurrentModification(settlement.id))?;
+        
+        // One payment satisfies every debt the settlement covers.
+        if next == SettlementStatus::Completed {
+            let settled = self.db.settle_debts(settlement.id).await?;
+            info!("Settlement {} settled {} debts", settlement.id, settled);
+        }
+        self.metrics.settlement_entered(next);
+        self.webhooks.notify(updated.clone());
+        info!("Settlement {} moved {:?} -> {:?}", settlement.id, settlement.status, next);
+        Ok(updated)
+    }
+}
+
+async fn probe<T, E, F>(name: &'static str, critical: bool, check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<T, E>>,
+    E: fmt::Display,
+{
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(HEALTH_PROBE_TIMEOUT, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    
+    let error = match outcome {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("timed out after {}ms", HEALTH_PROBE_TIMEOUT.as_millis())),
+    };
+    
+    DependencyCheck {
+        name,
+        status: if error.is_none() { CheckStatus::Up } else { CheckStatus::Down },
+        critical,
+        latency_ms,
+        error,
+        circuit: None,
+    }
+}
+
+/// The exact bytes a user signs to accept a settlement. The currency is
+/// signed with the amounts, which are fixed to its minor units so the same
+/// terms always serialize identically. When the creditor has clauses, the
+/// [`clauses_hash`] of those in force is signed too.
+pub fn acceptance_terms(settlement: &Settlement, creditor_id: Uuid, clauses_hash: Option<&str>) -> String {
+    let units = settlement.currency.minor_units();
+    let terms = format!(
+        "damocles:accept-settlement:v2|id={}|creditor={}|currency={}|original={}|settled={}|fee={}",
+        settlement.id,
+        creditor_id,
+        settlement.currency.code(),
+        settlement.original_amount.with_scale(units),
+        settlement.settled_amount.with_scale(units),
+        settlement.platform_fee.with_scale(units),
+    );
+    match clauses_hash {
+        Some(clauses) => format!("{}|clauses={}", terms, clauses),
+        None => terms,
+    }
+}
+
+/// SHA-256 of the acceptance terms, as recorded on signature envelopes.
+fn terms_hash(terms: &str) -> String {
+    format!("{:x}", Sha256::digest(terms.as_bytes()))
+}
+
+fn foreign_envelope(envelope_id: &str, settlement_id: Uuid) -> SettlementError {
+    SettlementError::Validation(format!(
+        "envelope {} does not belong to settlement {}",
+        envelope_id, settlement_id
+    ))
+}
+
+fn ensure_draft_open(draft: &SettlementDraft) -> Result<(), SettlementError> {
+    match draft.settlement_id {
+        Some(settlement_id) => Err(SettlementError::Conflict(format!(
+            "draft {} was already finalized into settlement {}",
+            draft.id, settlement_id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Acting on an expired proposal is refused even if the sweep hasn't
+/// caught up with it yet.
+fn ensure_not_expired(settlement: &Settlement) -> Result<(), SettlementError> {
+    let lapsed = settlement.status.is_open() && settlement.expires_at <= Utc::now();
+    if settlement.status == SettlementStatus::Expired || lapsed {
+        return Err(SettlementError::Expired(settlement.id));
+    }
+    Ok(())
+}
+
+/// Refuses amounts quoted in a currency other than `expected`; `None` means
+/// the amount didn't say and is taken to be in `expected`.
+fn ensure_currency(expected: &Currency, found: Option<&Currency>) -> Result<(), SettlementError> {
+    match found {
+        Some(found) if found != expected => Err(SettlementError::Validation(format!(
+            "amount is in {} but the debt is in {}",
+            found, expected
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Refuses terms that don't add up: nothing settled below zero or above the
+/// original amount, and savings of exactly what is left. Amounts reach here
+/// from the AI service, creditors and acceptances, and are checked once more
+/// before they are stored.
+fn ensure_amounts(settlement: &Settlement) -> Result<(), SettlementError> {
+    if settlement.settled_amount < BigDecimal::from(0) {
+        return Err(SettlementError::Validation(format!(
+            "settled amount {} is negative",
+            settlement.settled_amount
+        )));
+    }
+    if settlement.settled_amount > settlement.original_amount {
+        return Err(SettlementError::Validation(format!(
+            "settled amount {} exceeds the original amount {}",
+            settlement.settled_amount, settlement.original_amount
+        )));
+    }
+    if settlement.saved_amount != &settlement.original_amount - &settlement.settled_amount {
+        return Err(SettlementError::Validation(format!(
+            "saved amount {} is not the original amount {} less the settled amount {}",
+            settlement.saved_amount, settlement.original_amount, settlement.settled_amount
+        )));
+    }
+    Ok(())
+}
+
+/// Evidence as `request` declares it for `violation_id`, with the hash and
+/// content type checked, or what is wrong with them.
+fn new_evidence(violation_id: Uuid, request: &AttachEvidenceRequest, now: DateTime<Utc>) -> Result<EvidenceRef, String> {
+    let sha256 = request.sha256.trim().to_ascii_lowercase();
+    if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("sha256 must be 64 hex characters".to_string());
+    }
+    let content_type = request.content_type.trim();
+    if !is_mime_type(content_type) {
+        return Err(format!("{:?} is not a MIME type", request.content_type));
+    }
+    
+    let id = Uuid::new_v4();
+    Ok(EvidenceRef {
+        id,
+        violation_id,
+        evidence_type: request.evidence_type,
+        sha256,
+        content_type: content_type.to_string(),
+        storage_key: format!("violations/{}/{}", violation_id, id),
+        created_at: now,
+    })
+}
+
+/// `type/subtype`, optionally followed by `;` parameters.
+fn is_mime_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default().trim();
+    let token = |part: &str| {
+        !part.is_empty()
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&b))
+    };
+    
+    value.len() <= 255
+        && value.bytes().all(|b| b.is_ascii_graphic() || b == b' ')
+        && matches!(essence.split_once('/'), Some((kind, subtype)) if token(kind) && token(subtype))
+}
+
+/// Folds each violation into the first of its type and statute to have
+/// happened at most `window` before it. Runs go by `occurred_at`, not when
+/// the violations were recorded: an import records months of conduct at
+/// once. `expired` violations are left as they are: the limitations check
+/// goes by a run's first date, so every member must share its fate.
+fn fold_repeats(
+    violations: Vec<Violation>,
+    window: Duration,
+    expired: impl Fn(&Violation) -> bool,
+) -> Vec<Violation> {
+    let (mut deduped, mut violations): (Vec<Violation>, Vec<Violation>) = violations.into_iter().partition(expired);
+    violations.sort_by(|a, b| {
+        a.violation_type
+            .cmp(&b.violation_type)
+            .then(a.statute.cmp(&b.statute))
+            .then(a.occurred_at.cmp(&b.occurred_at))
+    });
+    
+    let expired = deduped.len();
+    for violation in violations {
+        match deduped[expired..].last_mut() {
+            Some(run)
+                if run.violation_type == violation.violation_type
+                    && run.statute == violation.statute
+                    && violation.occurred_at - run.occurred_at <= window =>
+            {
+                run.repeat_count += 1 + violation.repeat_count;
+                run.confidence = run.confidence.max(violation.confidence);
+                run.estimated_damage = run.estimated_damage.max(violation.estimated_damage);
+                run.evidence.extend(violation.evidence);
+            }
+            _ => deduped.push(violation),
+        }
+    }
+    
+    deduped.sort_by_key(|v| v.occurred_at);
+    deduped
+}
+
+/// A violation that hasn't happened, for what-if projections. It has no
+/// evidence and its id refers to nothing.
+fn hypothetical_violation(creditor_id: Uuid, hypothetical: &HypotheticalViolation) -> Violation {
+    Violation {
+        id: Uuid::new_v4(),
+        creditor_id,
+        violation_type: hypothetical.violation_type.trim().to_string(),
+        statute: hypothetical.statute,
+        severity: "unknown".to_string(),
+        confidence: 1.0,
+        legal_reference: "hypothetical".to_string(),
+        estimated_damage: 0.0,
+        occurred_at: Utc::now(),
+        created_at: Utc::now(),
+        repeat_count: 0,
+        evidence: Vec::new(),
+    }
+}
+
+/// How each violation the request cited figured in `analysis`, in the order
+/// cited. A violation with no factor of its own was left out as expired or
+/// folded into another as a repeat, and contributed nothing itself.
+fn cited_violations(cited: &[Uuid], analysis: &LeverageAnalysis) -> Vec<CitedViolation> {
+    let mut seen = HashSet::new();
+    cited
+        .iter()
+        .filter(|id| seen.insert(**id))
+        .map(|&violation_id| {
+            let contribution = analysis
+                .explanation
+                .factors
+                .iter()
+                .filter(|factor| factor.violation_id == Some(violation_id))
+                .map(|factor| factor.contribution)
+                .reduce(|total, contribution| total + contribution);
+            let violation_use = match contribution {
+                Some(_) => ViolationUse::Counted,
+                None if analysis.expired_violations.contains(&violation_id) => ViolationUse::Expired,
+                None => ViolationUse::Repeat,
+            };
+            CitedViolation {
+                violation_id,
+                violation_use,
+                contribution: contribution.unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+fn ai_error(call: &'static str, e: anyhow::Error) -> SettlementError {
+    if ai_client::is_timeout(&e) {
+        SettlementError::AiTimeout(call)
+    } else {
+        SettlementError::Ai(e)
+    }
+}
+
+fn user_actor(user_id: Uuid) -> String {
+    format!("user:{}", user_id)
+}
+
+fn creditor_actor(creditor_id: Uuid) -> String {
+    format!("creditor:{}", creditor_id)
+}
+
+fn party_actor(party: Party) -> &'static str {
+    match party {
+        Party::Creditor => "creditor",
+        Party::Debtor => "debtor",
+    }
+}
+
+fn caller_actor(caller: &Caller) -> String {
+    match caller {
+        Caller::User(user_id) => user_actor(*user_id),
+        Caller::Creditor(creditor_id) => creditor_actor(*creditor_id),
+        Caller::Admin(name) => name.clone(),
+    }
+}
+
+fn recommended_action(leverage: &LeverageAnalysis, locale: &Locale) -> String {
+    match leverage.legal_strength.as_str() {
+        "very_strong" | "strong" => locale.message(
+            "action.send_now",
+            &[("violation_count", leverage.violation_count.to_string())],
+        ),
+        "moderate" => locale.message("action.send_and_negotiate", &[]),
+        _ => locale.message("action.document_more", &[]),
+    }
+}
+
+/// What the user should do with a creditor's offer, and why: accept one at
+/// least as generous as the reduction their leverage supports, counter one
+/// that falls short.
+fn assess_creditor_offer(settlement: &Settlement, leverage: &LeverageAnalysis, locale: &Locale) -> (String, String) {
+    let offered = if settlement.original_amount > BigDecimal::from(0) {
+        (&settlement.saved_amount * BigDecimal::from(100) / &settlement.original_amount)
+            .to_f64()
+            .unwrap_or_default()
+    } else {
+        0.0
+    };
+    let supported = leverage.estimated_reduction_percentage;
+    let reasoning = locale.message(
+        "creditor_offer.comparison",
+        &[
+            ("offered_percentage", format!("{:.1}", offered)),
+            ("supported_percentage", format!("{:.1}", supported)),
+        ],
+    );
+    let action = if offered >= supported {
+        locale.message("creditor_offer.accept", &[])
+    } else {
+        locale.message("creditor_offer.counter", &[("shortfall", format!("{:.1}", supported - offered))])
+    };
+    (action, reasoning)
+}
+
+/// What the strategy aims for, followed by the key violations it cites.
+fn strategy_reasoning(strategy: NegotiationStrategy, leverage: &LeverageAnalysis, locale: &Locale) -> Vec<String> {
+    let target = ai_client::target_reduction_percentage(leverage, strategy);
+    let key = match strategy {
+        NegotiationStrategy::Conservative => "strategy.conservative",
+        NegotiationStrategy::Balanced => "strategy.balanced",
+        NegotiationStrategy::Aggressive => "strategy.aggressive",
+    };
+    let headline = locale.message(key, &[("target_percentage", format!("{:.0}", target))]);
+    
+    let cited = strategy.violations_cited().unwrap_or(leverage.key_violations.len());
+    std::iter::once(headline)
+        .chain(
+            leverage
+                .key_violations
+                .iter()
+                .take(cited)
+                .map(|violation| locale.message("strategy.cited_violation", &[("violation", violation.clone())])),
+        )
+        .collect()
+}
+
+fn sol_reasoning(status: SolStatus, jurisdiction: &str, locale: &Locale) -> Option<String> {
+    let key = match status {
+        SolStatus::TimeBarred => "sol.time_barred",
+        SolStatus::Unknown => "sol.unknown",
+        SolStatus::WithinPeriod => return None,
+    };
+    Some(locale.message(key, &[("jurisdiction", jurisdiction.to_string())]))
+}
+
+fn verification_reasoning(status: VerificationStatus, locale: &Locale) -> Option<String> {
+    match status {
+        VerificationStatus::Unverified => Some(locale.message("verification.unverified", &[])),
+        VerificationStatus::Requested => Some(locale.message("verification.requested", &[])),
+        VerificationStatus::Verified | VerificationStatus::Disputed => None,
+    }
+}
+
+fn fee_breakdown(saved_amount: &BigDecimal, settled_amount: &BigDecimal, currency: &Currency) -> FeeBreakdown {
+    let zero = BigDecimal::from(0);
+    let rate = |value: &str| BigDecimal::from_str(value).expect("valid fee rate constant");
+    let success_fee_rate = rate(SUCCESS_FEE_RATE);
+    
+    // A proposal that saves nothing earns no success fee.
+    let saved_amount = saved_amount.max(&zero);
+    
+    let base_fee = round_to_minor_units(settled_amount * rate(BASE_FEE_RATE), currency);
+    let success_fee = round_to_minor_units(saved_amount * &success_fee_rate, currency);
+    let blockchain_cost = round_to_minor_units(rate(BLOCKCHAIN_COST), currency);
+    
+    FeeBreakdown {
+        uncapped_total: &base_fee + &success_fee + &blockchain_cost,
+        base_fee,
+        success_fee,
+        success_fee_rate: success_fee_rate.to_f64().unwrap_or_default(),
+        blockchain_cost,
+        cap_discount: zero.clone(),
+        cap_applied: false,
+        fee_policy: FeePolicy::Standard,
+        standard_total: zero.clone(),
+        policy_discount: zero,
+        currency: currency.clone(),
+    }
+}
+
+fn apply_fee_cap(
+    mut fees: FeeBreakdown,
+    cap: &FeeCap,
+    settled_amount: &BigDecimal,
+    currency: &Currency,
+) -> FeeBreakdown {
+    let by_percentage = cap
+        .max_percent_of_settled
+        .as_ref()
+        .map(|percent| round_to_minor_units(settled_amount * percent / BigDecimal::from(100), currency));
+    let limit = match (cap.max_amount.clone(), by_percentage) {
+        (Some(amount), Some(percentage)) => amount.min(percentage),
+        (Some(limit), None) | (None, Some(limit)) => limit,
+        (None, None) => return fees,
+    };
+    
+    if fees.uncapped_total > limit {
+        fees.cap_discount = &fees.uncapped_total - &limit;
+        fees.cap_applied = true;
+    }
+    fees
+}
+
+/// Takes what `policy` lets off the capped fee, keeping that fee as the
+/// standard total.
+fn apply_fee_policy(mut fees: FeeBreakdown, policy: &FeePolicy, currency: &Currency) -> FeeBreakdown {
+    fees.standard_total = fees.total();
+    let charged = match policy {
+        FeePolicy::Standard => fees.standard_total.clone(),
+        FeePolicy::Waived => BigDecimal::from(0),
+        FeePolicy::Reduced { rate } => round_to_minor_units(&fees.standard_total * rate, currency),
+    };
+    fees.policy_discount = &fees.standard_total - charged;
+    fees.fee_policy = policy.clone();
+    fees
+}
+
+fn env_decimal(key: &str) -> Option<BigDecimal> {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| BigDecimal::from_str(value.trim()).ok())
+}
+
+fn hash_creditor_proposal(creditor_id: Uuid, request: &CreditorProposalRequest) -> String {
+    let body = serde_json::to_vec(&(creditor_id, request)).expect("CreditorProposalRequest serializes");
+    format!("{:x}", Sha256::digest(&body))
+}
+
+fn hash_request(request: &CreateSettlementRequest) -> String {
+    let body = serde_json::to_vec(request).expect("CreateSettlementRequest serializes");
+    format!("{:x}", Sha256::digest(&body))
+}
+
+fn encode_cursor(settlement: &Settlement) -> String {
+    format!(
+        "{}.{}",
+        settlement.proposed_at.timestamp_micros(),
+        settlement.id.simple()
+    )
+}
+
+fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, Uuid), SettlementError> {
+    let invalid = || SettlementError::Validation("invalid cursor".to_string());
+    
+    let (micros, id) = cursor.split_once('.').ok_or_else(invalid)?;
+    let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+    let proposed_at = DateTime::<Utc>::from_timestamp_micros(micros).ok_or_else(invalid)?;
+    let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+    
+    Ok((proposed_at, id))
+}
+
+/// Raises `optimal` to the creditor's floor, `floor_percentage` of the
+/// balance, if the leverage took it lower.
+fn apply_settlement_floor(optimal: &mut OptimalSettlement, debt: &Debt, floor_percentage: f64, locale: &Locale) {
+    let Some(percentage) = BigDecimal::from_f64(floor_percentage) else {
+        return;
+    };
+    let floor = round_up_to_minor_units(
+        &debt.current_amount * percentage / BigDecimal::from(100),
+        &debt.currency,
+    );
+    if optimal.amount >= floor {
+        return;
+    }
+    
+    optimal.reasoning.push(locale.message(
+        "floor.raised",
+        &[
+            ("amount", optimal.amount.to_string()),
+            ("floor_percentage", floor_percentage.to_string()),
+            ("floor", floor.to_string()),
+        ],
+    ));
+    optimal.reduction_percentage = reduction_percentage(&debt.current_amount, &floor);
+    optimal.amount = floor;
+    optimal.floor_applied = true;
+}
+
+/// Opens the proposal where `anchoring` says to and notes the anchor in its
+/// reasoning. The leverage-proportional anchor is the amount proposed.
+fn apply_anchoring(
+    optimal: &mut OptimalSettlement,
+    debt: &Debt,
+    leverage: &LeverageAnalysis,
+    anchoring: AnchoringStrategy,
+    locale: &Locale,
+) {
+    let (key, basis) = match anchoring {
+        AnchoringStrategy::LeverageProportional => ("anchoring.leverage_proportional", None),
+        AnchoringStrategy::FixedPercentage(percentage) => {
+            let share = BigDecimal::from_f64(percentage).unwrap_or_default();
+            optimal.amount = round_to_minor_units(
+                &debt.current_amount * share / BigDecimal::from(100),
+                &debt.currency,
+            );
+            ("anchoring.fixed_percentage", Some(("percentage", percentage.to_string())))
+        }
+        AnchoringStrategy::StatutoryDamages => {
+            let exposure = round_to_minor_units(statutory_exposure(leverage), &debt.currency);
+            optimal.amount = round_to_minor_units(
+                (&debt.current_amount - &exposure).max(BigDecimal::from(0)),
+                &debt.currency,
+            );
+            ("anchoring.statutory_damages", Some(("exposure", exposure.to_string())))
+        }
+    };
+    optimal.reduction_percentage = match anchoring {
+        AnchoringStrategy::LeverageProportional => optimal.reduction_percentage,
+        _ => reduction_percentage(&debt.current_amount, &optimal.amount),
+    };
+    
+    let mut args = vec![
+        ("strategy", anchoring.to_string()),
+        ("anchor", optimal.amount.to_string()),
+    ];
+    args.extend(basis);
+    optimal.reasoning.push(locale.message(key, &args));
+}
+
+/// The statutory damages the creditor could owe for the documented
+/// violations at most, summed across statutes. Statutes that award them
+/// per violation count each one.
+fn statutory_exposure(leverage: &LeverageAnalysis) -> BigDecimal {
+    leverage
+        .exposure_by_statute
+        .iter()
+        .filter_map(|statute| BigDecimal::from_f64(statute.statutory_damages_max))
+        .sum()
+}
+
+/// How far below `balance` an `amount` is, as a percentage of it.
+fn reduction_percentage(balance: &BigDecimal, amount: &BigDecimal) -> f64 {
+    if *balance > BigDecimal::from(0) {
+        ((balance - amount) * BigDecimal::from(100) / balance)
+            .to_f64()
+            .unwrap_or(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// A debt can be proposed against by the user who owes it to the creditor
+/// named, unless it is in dispute.
+fn ensure_proposable(debt: &Debt, request: &CreateSettlementRequest) -> Result<(), SettlementError> {
+    if debt.user_id != request.user_id || debt.creditor_id != request.creditor_id {
+        return Err(SettlementError::Validation(
+            "debt does not belong to this user and creditor".to_string(),
+        ));
+    }
+    if debt.verification_status == VerificationStatus::Disputed {
+        return Err(SettlementError::Validation(format!(
+            "debt {} is disputed; resolve the dispute before proposing a settlement",
+            debt.id
+        )));
+    }
+    if let Some(threshold) = &request.auto_accept_below {
+        ensure_auto_accept_below(threshold, &debt.current_amount, &debt.currency)?;
+    }
+    Ok(())
+}
+
+/// An auto-accept threshold must be an amount the settlement could be
+/// accepted at.
+fn ensure_auto_accept_below(
+    threshold: &BigDecimal,
+    original_amount: &BigDecimal,
+    currency: &Currency,
+) -> Result<(), SettlementError> {
+    if *threshold <= BigDecimal::from(0) || threshold > original_amount {
+        return Err(SettlementError::Validation(format!(
+            "auto_accept_below must be positive and at most the original amount of {}",
+            original_amount
+        )));
+    }
+    if round_to_minor_units(threshold.clone(), currency) != *threshold {
+        return Err(SettlementError::Validation(format!(
+            "auto_accept_below {} is finer than {} allows",
+            threshold, currency
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses `caller` unless they act for `user_id`: the user themselves or
+/// support staff. Creditors propose through their own route.
+pub fn authorize_user_access(user_id: Uuid, caller: &Caller) -> Result<(), SettlementError> {
+    match caller {
+        Caller::Admin(_) => Ok(()),
+        Caller::User(id) if *id == user_id => Ok(()),
+        _ => Err(SettlementError::Forbidden(format!("may not act for user {}", user_id))),
+    }
+}
+
+/// Refuses `caller` unless they act for `creditor_id`: the creditor
+/// themselves or support staff.
+pub fn authorize_creditor_access(creditor_id: Uuid, caller: &Caller) -> Result<(), SettlementError> {
+    match caller {
+        Caller::Admin(_) => Ok(()),
+        Caller::Creditor(id) if *id == creditor_id => Ok(()),
+        _ => Err(SettlementError::Forbidden(format!("may not act for creditor {}", creditor_id))),
+    }
+}
+
+/// A debtor asks for validation; the creditor, or support on its behalf,
+/// answers with the outcome.
+pub fn authorize_debt_verification(
+    debt: &Debt,
+    status: VerificationStatus,
+    caller: &Caller,
+) -> Result<(), SettlementError> {
+    match (status, caller) {
+        (_, Caller::Admin(_)) => Ok(()),
+        (VerificationStatus::Requested, Caller::User(id)) if *id == debt.user_id || debt.debtors.contains(id) => {
+            Ok(())
+        }
+        (VerificationStatus::Verified | VerificationStatus::Disputed, Caller::Creditor(id))
+            if *id == debt.creditor_id =>
+        {
+            Ok(())
+        }
+        _ => Err(SettlementError::Forbidden(format!(
+            "may not record {:?} on debt {}",
+            status, debt.id
+        ))),
+    }
+}
+
+/// Refuses a counter-offer `from` a party other than the caller's. Support
+/// staff may record either party's counter.
+pub fn authorize_counter_party(from: Party, caller: &Caller) -> Result<(), SettlementError> {
+    let party = match caller {
+        Caller::Admin(_) => return Ok(()),
+        Caller::User(_) => Party::Debtor,
+        Caller::Creditor(_) => Party::Creditor,
+    };
+    if from != party {
+        return Err(SettlementError::Forbidden(format!(
+            "the {:?} may not counter as the {:?}",
+            party, from
+        )));
+    }
+    Ok(())
+}
+
+/// Deleted settlements are shown to support staff only.
+pub fn authorize_include_deleted(include_deleted: bool, caller: &Caller) -> Result<(), SettlementError> {
+    if include_deleted && !matches!(caller, Caller::Admin(_)) {
+        return Err(SettlementError::Forbidden(
+            "only support may see deleted settlements".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Waived and reduced fees are granted by support; anyone may ask for the
+/// standard fee.
+pub fn authorize_fee_policy(policy: Option<&FeePolicy>, caller: &Caller) -> Result<(), SettlementError> {
+    if policy.is_some_and(FeePolicy::is_concession) && !matches!(caller, Caller::Admin(_)) {
+        return Err(SettlementError::Forbidden(
+            "only support may waive or reduce the platform fee".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Weighs the creditor's record into the AI's `assessment`, each part of it
+/// by how many settlements back it up: the acceptance rate by the decided
+/// proposals, the reduction by the completed ones.
+fn blend_acceptance(
+    request: &AcceptancePredictionRequest,
+    assessment: AcceptanceAssessment,
+    profile: &CreditorProfile,
+    source: ProposalSource,
+) -> AcceptancePrediction {
+    let locale = &request.locale;
+    let weight = |settlements: i64| settlements as f64 / (settlements as f64 + ACCEPTANCE_HISTORY_WEIGHT);
+    let blend = |assessed: f64, history: Option<f64>, settlements: i64| match history {
+        Some(history) if settlements > 0 => {
+            let weight = weight(settlements);
+            (1.0 - weight) * assessed + weight * history
+        }
+        _ => assessed,
+    };
+    
+    let mut reasoning = assessment.reasoning;
+    let acceptance_probability = blend(
+        assessment.acceptance_probability.clamp(0.0, 1.0),
+        profile.acceptance_rate,
+        profile.decided_settlements,
+    )
+    .clamp(0.0, 1.0);
+    reasoning.push(match profile.acceptance_rate {
+        Some(rate) if profile.decided_settlements > 0 => locale.message(
+            "prediction.history",
+            &[
+                ("acceptance_percentage", format!("{:.0}", rate * 100.0)),
+                ("decided", profile.decided_settlements.to_string()),
+                ("weight_percentage", format!("{:.0}", weight(profile.decided_settlements) * 100.0)),
+            ],
+        ),
+        _ => locale.message("prediction.no_history", &[]),
+    });
+    
+    let reduction = blend(
+        assessment.acceptable_reduction_percentage,
+        profile.average_reduction_percentage,
+        profile.completed_settlements,
+    )
+    .clamp(0.0, 100.0);
+    let suggested_amount = round_to_minor_units(
+        &request.original_amount * BigDecimal::from_f64(100.0 - reduction).unwrap_or_else(|| BigDecimal::from(100))
+            / BigDecimal::from(100),
+        &request.currency,
+    );
+    reasoning.push(locale.message(
+        "prediction.suggested",
+        &[
+            ("suggested_amount", suggested_amount.to_string()),
+            ("currency", request.currency.to_string()),
+            ("suggested_percentage", format!("{:.1}", reduction)),
+        ],
+    ));
+    
+    AcceptancePrediction {
+        acceptance_probability,
+        suggested_amount,
+        reasoning,
+        source,
+    }
+}
+
+/// The single debt a bundle is negotiated as. It is only as verified as its
+/// least verified component, and the most recent payment on any of them
+/// governs the statute of limitations.
+fn bundle_debt(debts: &[Debt]) -> Debt {
+    let first = &debts[0];
+    let verification_status = if debts.iter().all(|d| d.verification_status == VerificationStatus::Verified) {
+        VerificationStatus::Verified
+    } else if debts.iter().any(|d| d.verification_status == VerificationStatus::Requested) {
+        VerificationStatus::Requested
+    } else {
+        VerificationStatus::Unverified
+    };
+    
+    let mut debtors: Vec<Uuid> = Vec::new();
+    for debtor in debts.iter().flat_map(|d| &d.debtors) {
+        if !debtors.contains(debtor) {
+            debtors.push(*debtor);
+        }
+    }
+    
+    Debt {
+        id: first.id,
+        user_id: first.user_id,
+        debtors,
+        creditor_id: first.creditor_id,
+        original_amount: debts.iter().map(|d| d.original_amount.clone()).sum(),
+        current_amount: debts.iter().map(|d| d.current_amount.clone()).sum(),
+        currency: first.currency.clone(),
+        status: first.status.clone(),
+        last_payment_date: debts.iter().filter_map(|d| d.last_payment_date).max(),
+        verification_status,
+        created_at: debts.iter().map(|d| d.created_at).min().unwrap_or(first.created_at),
+    }
+}
+
+/// Divides the settled amount among the component debts in proportion to
+/// their balances; the last absorbs the rounding so the shares add up. A
+/// settlement that is not a bundle is its own single component.
+fn debt_breakdown(settlement: &Settlement, components: Vec<SettlementDebt>) -> Vec<BundledDebt> {
+    if components.is_empty() {
+        return vec![BundledDebt {
+            debt_id: settlement.debt_id,
+            original_amount: settlement.original_amount.clone(),
+            settled_amount: settlement.settled_amount.clone(),
+        }];
+    }
+    
+    let total: BigDecimal = components.iter().map(|c| c.original_amount.clone()).sum();
+    let mut allocated = BigDecimal::from(0);
+    let last = components.len() - 1;
+    components
+        .into_iter()
+        .enumerate()
+        .map(|(index, component)| {
+            let settled_amount = if index == last {
+                &settlement.settled_amount - &allocated
+            } else if total > BigDecimal::from(0) {
+                round_to_minor_units(
+                    &settlement.settled_amount * &component.original_amount / &total,
+                    &settlement.currency,
+                )
+            } else {
+                BigDecimal::from(0)
+            };
+            allocated += &settled_amount;
+            BundledDebt {
+                debt_id: component.debt_id,
+                original_amount: component.original_amount,
+                settled_amount,
+            }
+        })
+        .collect()
+}
+
+fn installment_schedule(settlement: &Settlement, installments: Vec<Installment>) -> InstallmentSchedule {
+    InstallmentSchedule {
+        settlement_id: settlement.id,
+        accrued_fee: accrued_fee(settlement, &installments),
+        total_fee: settlement.platform_fee.clone(),
+        currency: settlement.currency.clone(),
+        installments,
+    }
+}
+
+/// The platform fee earned by the paid installments: the fee's share of the
+/// settled amount paid so far, and exactly the whole fee once it is all paid.
+fn accrued_fee(settlement: &Settlement, installments: &[Installment]) -> BigDecimal {
+    let paid: BigDecimal = installments
+        .iter()
+        .filter(|installment| installment.status == InstallmentStatus::Paid)
+        .map(|installment| installment.amount.clone())
+        .sum();
+    
+    if paid >= settlement.settled_amount {
+        return settlement.platform_fee.clone();
+    }
+    if paid <= BigDecimal::from(0) {
+        return BigDecimal::from(0);
+    }
+    round_to_minor_units(
+        &settlement.platform_fee * paid / &settlement.settled_amount,
+        &settlement.currency,
+    )
+}
+
+fn split_amount(total: &BigDecimal, parts: u32, currency: &Currency) -> Vec<BigDecimal> {
+    let share = (total / BigDecimal::from(parts)).with_scale(currency.minor_units());
+    let mut amounts = vec![share.clone(); parts as usize - 1];
+    amounts.push(total - &share * BigDecimal::from(parts - 1));
+    amounts
+}
+
+fn installment_due_date(first_due: DateTime<Utc>, cadence: Cadence, index: u32) -> Option<DateTime<Utc>> {
+    match cadence {
+        Cadence::Weekly => first_due.checked_add_signed(Duration::weeks(index as i64)),
+        Cadence::Biweekly => first_due.checked_add_signed(Duration::weeks(2 * index as i64)),
+        Cadence::Monthly => first_due.checked_add_months(Months::new(index)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::cardano_client::CardanoClient;
+    use crate::blockchain::mock::MockBlockchainClient;
+    use crate::blockchain::{BreakerConfig, CircuitBreaker};
+    use crate::database::PoolConfig;
+    use crate::esign::mock::{MockSignatureProvider, MOCK_CALLBACK_SIGNATURE};
+    use crate::models::{ClauseTemplate, ContactChannel, DraftKind, EvidenceType, ImportRecompute, Statute, StatuteExposure};
+    use crate::services::prompts::PromptTemplates;
+    use crate::services::simulation::{SimulationConfig, SIMULATION_MODEL_VERSION};
+    
+    fn dec(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+    
+    fn usd() -> Currency {
+        Currency::usd()
+    }
+    
+    #[test]
+    fn fee_components_sum_exactly_to_platform_fee() {
+        let fees = fee_breakdown(&dec("3333.33"), &dec("6666.67"), &usd());
+        
+        assert_eq!(fees.base_fee, dec("66.67"));
+        assert_eq!(fees.success_fee, dec("666.67"));
+        assert_eq!(fees.blockchain_cost, dec("0.50"));
+        assert_eq!(fees.total(), dec("733.84"));
+        assert_eq!(fees.total(), &fees.base_fee + &fees.success_fee + &fees.blockchain_cost);
+    }
+    
+    #[test]
+    fn success_fee_is_a_share_of_savings() {
+        let fees = fee_breakdown(&dec("1000"), &dec("4000"), &usd());
+        
+        assert_eq!(fees.success_fee, dec("200.00"));
+        assert_eq!(fees.success_fee_rate, 0.20);
+    }
+    
+    #[test]
+    fn no_success_fee_without_savings() {
+        let fees = fee_breakdown(&dec("-50"), &dec("1050"), &usd());
+        
+        assert_eq!(fees.success_fee, dec("0"));
+        assert_eq!(fees.total(), &fees.base_fee + &fees.blockchain_cost);
+    }
+    
+    #[test]
+    fn fee_is_clamped_to_the_lower_cap() {
+        let cap = FeeCap {
+            max_amount: Some(dec("5000")),
+            max_percent_of_settled: Some(dec("10")),
+        };
+        let fees = apply_fee_cap(
+            fee_breakdown(&dec("60000"), &dec("40000"), &usd()),
+            &cap,
+            &dec("40000"),
+            &usd(),
+        );
+        
+        assert_eq!(fees.uncapped_total, dec("12400.50"));
+        assert!(fees.cap_applied);
+        assert_eq!(fees.total(), dec("4000.00"));
+        assert_eq!(fees.cap_discount, dec("8400.50"));
+    }
+    
+    #[test]
+    fn waived_and_reduced_fees_keep_the_standard_fee() {
+        let standard = fee_breakdown(&dec("1000"), &dec("4000"), &usd());
+        let charged = |policy: FeePolicy| apply_fee_policy(standard.clone(), &policy, &usd());
+        
+        let waived = charged(FeePolicy::Waived);
+        assert_eq!(waived.total(), dec("0"));
+        assert_eq!(waived.standard_total, standard.total());
+        assert_eq!(waived.policy_discount, standard.total());
+        
+        let reduced = charged(FeePolicy::Reduced { rate: dec("0.333") });
+        assert_eq!(reduced.standard_total, dec("240.50"));
+        assert_eq!(reduced.total(), dec("80.09"));
+        assert_eq!(charged(FeePolicy::Standard).total(), standard.total());
+    }
+    
+    #[test]
+    fn fee_under_the_cap_is_untouched() {
+        let cap = FeeCap {
+            max_amount: Some(dec("5000")),
+            max_percent_of_settled: None,
+        };
+        let fees = apply_fee_cap(
+            fee_breakdown(&dec("1000"), &dec("4000"), &usd()),
+            &cap,
+            &dec("4000"),
+            &usd(),
+        );
+        
+        assert!(!fees.cap_applied);
+        assert_eq!(fees.total(), fees.uncapped_total);
+    }
+    
+    #[test]
+    fn fees_round_to_the_currency_minor_unit() {
+        let jpy = Currency::try_from("JPY".to_string()).unwrap();
+        let fees = fee_breakdown(&dec("33333"), &dec("66667"), &jpy);
+        
+        assert_eq!(fees.base_fee.to_string(), "667");
+        assert_eq!(fees.success_fee.to_string(), "6667");
+        assert_eq!(fees.blockchain_cost.to_string(), "0");
+        assert_eq!(fees.currency, jpy);
+    }
+    
+    #[test]
+    fn amounts_in_another_currency_are_refused() {
+        let eur = Currency::try_from("EUR".to_string()).unwrap();
+        
+        assert!(ensure_currency(&usd(), None).is_ok());
+        assert!(ensure_currency(&usd(), Some(&usd())).is_ok());
+        assert!(matches!(ensure_currency(&usd(), Some(&eur)), Err(SettlementError::Validation(_))));
+    }
+    
+    fn installments(amounts: &[&str], paid: usize) -> Vec<Installment> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| Installment {
+                id: Uuid::new_v4(),
+                settlement_id: Uuid::nil(),
+                sequence: index as i32 + 1,
+                amount: dec(amount),
+                due_date: Utc::now(),
+                status: if index < paid { InstallmentStatus::Paid } else { InstallmentStatus::Pending },
+                paid_at: None,
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn fee_accrues_with_the_share_of_the_settlement_paid() {
+        let settlement = proposed_settlement();
+        let schedule = ["200.00", "200.00", "200.00"];
+        
+        assert_eq!(accrued_fee(&settlement, &installments(&schedule, 0)), dec("0"));
+        assert_eq!(accrued_fee(&settlement, &installments(&schedule, 1)), dec("28.83"));
+        assert_eq!(accrued_fee(&settlement, &installments(&schedule, 2)), dec("57.67"));
+    }
+    
+    #[test]
+    fn the_whole_fee_is_owed_once_every_installment_is_paid() {
+        let settlement = proposed_settlement();
+        
+        let accrued = accrued_fee(&settlement, &installments(&["200.00", "200.00", "200.00"], 3));
+        assert_eq!(accrued, settlement.platform_fee);
+    }
+    
+    #[test]
+    fn bundle_shares_follow_the_balances_and_add_up() {
+        let settlement = Settlement { settled_amount: dec("100.00"), ..proposed_settlement() };
+        let components: Vec<_> = ["300", "300", "300"]
+            .iter()
+            .map(|amount| SettlementDebt {
+                debt_id: Uuid::new_v4(),
+                original_amount: dec(amount),
+            })
+            .collect();
+        
+        let shares: Vec<_> = debt_breakdown(&settlement, components)
+            .into_iter()
+            .map(|debt| debt.settled_amount)
+            .collect();
+        assert_eq!(shares, [dec("33.33"), dec("33.33"), dec("33.34")]);
+    }
+    
+    #[test]
+    fn an_unbundled_settlement_is_its_own_breakdown() {
+        let settlement = proposed_settlement();
+        
+        let breakdown = debt_breakdown(&settlement, vec![]);
+        assert_eq!(breakdown.len(), 1);
+        assert_eq!(breakdown[0].debt_id, settlement.debt_id);
+        assert_eq!(breakdown[0].settled_amount, settlement.settled_amount);
+    }
+    
+    #[test]
+    fn the_floor_raises_only_proposals_below_it() {
+        let debt = Debt {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            debtors: Vec::new(),
+            creditor_id: Uuid::new_v4(),
+            original_amount: dec("1000"),
+            current_amount: dec("1000"),
+            currency: usd(),
+            status: "active".to_string(),
+            last_payment_date: None,
+            verification_status: VerificationStatus::Unverified,
+            created_at: Utc::now(),
+        };
+        let optimal = |amount: &str| OptimalSettlement {
+            amount: dec(amount),
+            reduction_percentage: 40.0,
+            confidence: Some(0.8),
+            reasoning: vec![],
+            model_version: "test".to_string(),
+            prompt_hash: String::new(),
+            prompt_version: None,
+            currency: None,
+            floor_applied: false,
+        };
+        
+        let mut raised = optimal("600.00");
+        apply_settlement_floor(&mut raised, &debt, 75.0, &Locale::default());
+        assert_eq!(raised.amount, dec("750.00"));
+        assert_eq!(raised.reduction_percentage, 25.0);
+        assert!(raised.floor_applied);
+        assert_eq!(raised.reasoning.len(), 1);
+        
+        let mut kept = optimal("800.00");
+        apply_settlement_floor(&mut kept, &debt, 75.0, &Locale::default());
+        assert_eq!(kept.amount, dec("800.00"));
+        assert!(!kept.floor_applied);
+    }
+    
+    #[test]
+    fn terms_must_add_up() {
+        let terms = |settled: &str, saved: &str| Settlement {
+            settled_amount: dec(settled),
+            saved_amount: dec(saved),
+            ..proposed_settlement()
+        };
+        let refusal = |settlement: Settlement| match ensure_amounts(&settlement) {
+            Err(SettlementError::Validation(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        };
+        
+        // Settling for the whole balance saves nothing, and for none saves it all.
+        assert!(ensure_amounts(&terms("1000", "0")).is_ok());
+        assert!(ensure_amounts(&terms("0", "1000")).is_ok());
+        
+        assert_eq!(refusal(terms("1200", "-200")), "settled amount 1200 exceeds the original amount 1000");
+        assert_eq!(refusal(terms("-5", "1005")), "settled amount -5 is negative");
+        assert_eq!(
+            refusal(terms("600", "300")),
+            "saved amount 300 is not the original amount 1000 less the settled amount 600"
+        );
+    }
+    
+    #[test]
+    fn each_anchoring_strategy_opens_from_its_own_basis() {
+        let debt = |balance: &str| Debt {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            debtors: Vec::new(),
+            creditor_id: Uuid::new_v4(),
+            original_amount: dec(balance),
+            current_amount: dec(balance),
+            currency: usd(),
+            status: "active".to_string(),
+            last_payment_date: None,
+            verification_status: VerificationStatus::Unverified,
+            created_at: Utc::now(),
+        };
+        let exposure = |statute: Statute, violation_count: i32, max: f64| StatuteExposure {
+            statute,
+            violation_count,
+            leverage_score: 10.0,
+            actual_damages: 0.0,
+            statutory_damages_min: 0.0,
+            statutory_damages_max: max,
+        };
+        let leverage = LeverageAnalysis {
+            exposure_by_statute: vec![exposure(Statute::Fdcpa, 1, 1000.0), exposure(Statute::Tcpa, 2, 3000.0)],
+            ..prediction_request().leverage_analysis
+        };
+        let anchored = |balance: &str, anchoring: AnchoringStrategy| {
+            let mut optimal = OptimalSettlement {
+                amount: dec("8000.00"),
+                reduction_percentage: 20.0,
+                confidence: Some(0.8),
+                reasoning: vec![],
+                model_version: "test".to_string(),
+                prompt_hash: String::new(),
+                prompt_version: None,
+                currency: None,
+                floor_applied: false,
+            };
+            apply_anchoring(&mut optimal, &debt(balance), &leverage, anchoring, &Locale::default());
+            optimal
+        };
+        
+        let proportional = anchored("10000", AnchoringStrategy::LeverageProportional);
+        let fixed = anchored("10000", AnchoringStrategy::FixedPercentage(55.0));
+        let statutory = anchored("10000", AnchoringStrategy::StatutoryDamages);
+        assert_eq!(
+            [&proportional.amount, &fixed.amount, &statutory.amount],
+            [&dec("8000.00"), &dec("5500.00"), &dec("6000.00")]
+        );
+        assert_eq!(
+            [proportional.reduction_percentage, fixed.reduction_percentage, statutory.reduction_percentage],
+            [20.0, 45.0, 40.0]
+        );
+        assert!(proportional.reasoning[0].contains("8000.00 (leverage_proportional anchoring)"));
+        assert!(fixed.reasoning[0].contains("5500.00 (fixed_percentage:55 anchoring): 55% of the balance"));
+        assert!(statutory.reasoning[0].contains("the balance less 4000.00 of statutory damages"));
+        
+        // Exposure beyond the balance takes the anchor to nothing, not below.
+        let small = anchored("2500", AnchoringStrategy::StatutoryDamages);
+        assert_eq!(small.amount, dec("0"));
+        assert_eq!(small.reduction_percentage, 100.0);
+        
+        for raw in ["leverage_proportional", "fixed_percentage:55", "statutory_damages"] {
+            assert_eq!(raw.parse::<AnchoringStrategy>().unwrap().to_string(), raw);
+        }
+        assert!("fixed_percentage:0".parse::<AnchoringStrategy>().is_err());
+        assert!("fixed".parse::<AnchoringStrategy>().is_err());
+    }
+    
+    async fn test_database() -> Database {
+        Database::connect_with(&std::env::var("DATABASE_URL").unwrap(), PoolConfig::default())
+            .await
+            .unwrap()
+    }
+    
+    fn test_engine(db: Database, blockchain_client: Arc<dyn BlockchainClient>) -> SettlementEngine {
+        SettlementEngine::new(
+            db,
+            AiClient::new(PromptTemplates::default()),
+            blockchain_client,
+            Arc::new(MockSignatureProvider),
+            LeverageEngine::from_env().unwrap(),
+            Metrics::new(),
+        )
+    }
+    
+    fn proposed_settlement() -> Settlement {
+        Settlement {
+            platform_fee: dec("86.50"),
+            expires_at: Utc::now() + Duration::days(DEFAULT_PROPOSAL_EXPIRY_DAYS.into()),
+            ..Settlement::fixture()
+        }
+    }
+    
+    fn prediction_request() -> AcceptancePredictionRequest {
+        AcceptancePredictionRequest {
+            user_id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            proposed_amount: dec("7000"),
+            original_amount: dec("10000"),
+            currency: usd(),
+            leverage_analysis: LeverageAnalysis {
+                violation_count: 2,
+                total_leverage_score: 40.0,
+                estimated_reduction_percentage: 20.0,
+                legal_strength: "moderate".to_string(),
+                key_violations: Vec::new(),
+                unsubstantiated_violations: Vec::new(),
+                expired_violations: Vec::new(),
+                excluded_violations: Vec::new(),
+                statute_of_limitations: SolStatus::WithinPeriod,
+                explanation: Default::default(),
+                exposure_by_statute: Vec::new(),
+            },
+            locale: Locale::default(),
+        }
+    }
+    
+    fn profile(decided: i64, acceptance_rate: Option<f64>, completed: i64, reduction: Option<f64>) -> CreditorProfile {
+        CreditorProfile {
+            creditor_id: Uuid::new_v4(),
+            total_violations: 0,
+            average_leverage_score: None,
+            total_settlements: decided,
+            decided_settlements: decided,
+            acceptance_rate,
+            average_reduction_percentage: reduction,
+            completed_settlements: completed,
+            failed_settlements: 0,
+        }
+    }
+    
+    #[test]
+    fn a_creditor_without_history_leaves_the_assessment_as_is() {
+        let request = prediction_request();
+        let assessment = ai_client::acceptance_fallback(&request);
+        let probability = assessment.acceptance_probability;
+        let prediction =
+            blend_acceptance(&request, assessment, &profile(0, None, 0, None), ProposalSource::RulesFallback);
+        
+        assert_eq!(prediction.acceptance_probability, probability);
+        assert_eq!(prediction.suggested_amount, dec("8000.00"));
+        assert_eq!(prediction.source, ProposalSource::RulesFallback);
+    }
+    
+    #[test]
+    fn the_creditor_record_weighs_in_with_the_number_of_decisions() {
+        let request = prediction_request();
+        let assessment = AcceptanceAssessment {
+            acceptance_probability: 0.2,
+            acceptable_reduction_percentage: 20.0,
+            reasoning: Vec::new(),
+        };
+        let prediction =
+            blend_acceptance(&request, assessment, &profile(10, Some(0.8), 10, Some(40.0)), ProposalSource::Ai);
+        
+        // Ten decisions carry as much weight as the assessment itself.
+        assert!((prediction.acceptance_probability - 0.5).abs() < 1e-9);
+        assert_eq!(prediction.suggested_amount, dec("7000.00"));
+        assert_eq!(prediction.reasoning.len(), 2);
+    }
+    
+    #[test]
+    fn out_of_range_assessments_are_clamped() {
+        let assessment = AcceptanceAssessment {
+            acceptance_probability: 1.7,
+            acceptable_reduction_percentage: 140.0,
+            reasoning: Vec::new(),
+        };
+        let prediction = blend_acceptance(
+            &prediction_request(),
+            assessment,
+            &profile(0, None, 0, None),
+            ProposalSource::Ai,
+        );
+        
+        assert_eq!(prediction.acceptance_probability, 1.0);
+        assert_eq!(prediction.suggested_amount, dec("0.00"));
+    }
+    
+    #[test]
+    fn only_the_user_and_support_staff_act_for_a_user() {
+        let user_id = Uuid::new_v4();
+        
+        assert!(authorize_user_access(user_id, &Caller::User(user_id)).is_ok());
+        assert!(authorize_user_access(user_id, &Caller::Admin("admin:alice".to_string())).is_ok());
+        assert!(matches!(
+            authorize_user_access(user_id, &Caller::User(Uuid::new_v4())),
+            Err(SettlementError::Forbidden(_))
+        ));
+        assert!(matches!(
+            authorize_user_access(user_id, &Caller::Creditor(Uuid::new_v4())),
+            Err(SettlementError::Forbidden(_))
+        ));
+    }
+    
+    #[test]
+    fn only_the_creditor_and_support_staff_act_for_a_creditor() {
+        let creditor_id = Uuid::new_v4();
+        
+        assert!(authorize_creditor_access(creditor_id, &Caller::Creditor(creditor_id)).is_ok());
+        assert!(authorize_creditor_access(creditor_id, &Caller::Admin("admin:alice".to_string())).is_ok());
+        assert!(matches!(
+            authorize_creditor_access(creditor_id, &Caller::Creditor(Uuid::new_v4())),
+            Err(SettlementError::Forbidden(_))
+        ));
+        assert!(matches!(
+            authorize_creditor_access(creditor_id, &Caller::User(creditor_id)),
+            Err(SettlementError::Forbidden(_))
+        ));
+    }
+    
+    #[test]
+    fn debtors_request_validation_and_creditors_answer_it() {
+        let user_id = Uuid::new_v4();
+        let debt = Debt {
+            id: Uuid::new_v4(),
+            user_id,
+            debtors: vec![user_id, Uuid::new_v4()],
+            creditor_id: Uuid::new_v4(),
+            original_amount: dec("1000"),
+            current_amount: dec("1000"),
+            currency: Currency::default(),
+            status: "active".to_string(),
+            last_payment_date: None,
+            verification_status: VerificationStatus::Unverified,
+            created_at: Utc::now(),
+        };
+        let co_debtor = Caller::User(debt.debtors[1]);
+        let creditor = Caller::Creditor(debt.creditor_id);
+        let admin = Caller::Admin("admin:alice".to_string());
+        
+        assert!(authorize_debt_verification(&debt, VerificationStatus::Requested, &co_debtor).is_ok());
+        assert!(authorize_debt_verification(&debt, VerificationStatus::Verified, &creditor).is_ok());
+        assert!(authorize_debt_verification(&debt, VerificationStatus::Disputed, &admin).is_ok());
+        // A debtor can't clear their own debt for proposals, nor a creditor
+        // freeze it.
+        for (status, caller) in [
+            (VerificationStatus::Verified, &co_debtor),
+            (VerificationStatus::Disputed, &Caller::User(user_id)),
+            (VerificationStatus::Requested, &creditor),
+            (VerificationStatus::Verified, &Caller::Creditor(Uuid::new_v4())),
+            (VerificationStatus::Requested, &Caller::User(Uuid::new_v4())),
+        ] {
+            assert!(matches!(
+                authorize_debt_verification(&debt, status, caller),
+                Err(SettlementError::Forbidden(_))
+            ));
+        }
+    }
+    
+    #[test]
+    fn parties_counter_only_as_themselves() {
+        let debtor = Caller::User(Uuid::new_v4());
+        let creditor = Caller::Creditor(Uuid::new_v4());
+        
+        assert!(authorize_counter_party(Party::Debtor, &debtor).is_ok());
+        assert!(authorize_counter_party(Party::Creditor, &creditor).is_ok());
+        assert!(authorize_counter_party(Party::Creditor, &Caller::Admin("admin:alice".to_string())).is_ok());
+        // A debtor posing as the creditor would accept their own counter.
+        assert!(matches!(
+            authorize_counter_party(Party::Creditor, &debtor),
+            Err(SettlementError::Forbidden(_))
+        ));
+        assert!(matches!(
+            authorize_counter_party(Party::Debtor, &creditor),
+            Err(SettlementError::Forbidden(_))
+        ));
+    }
+    
+    #[test]
+    fn only_support_sees_deleted_settlements() {
+        let user = Caller::User(Uuid::new_v4());
+        
+        assert!(authorize_include_deleted(false, &user).is_ok());
+        assert!(authorize_include_deleted(true, &Caller::Admin("admin:alice".to_string())).is_ok());
+        for caller in [user, Caller::Creditor(Uuid::new_v4())] {
+            assert!(matches!(authorize_include_deleted(true, &caller), Err(SettlementError::Forbidden(_))));
+        }
+    }
+    
+    #[test]
+    fn only_support_waives_or_reduces_the_fee() {
+        let user = Caller::User(Uuid::new_v4());
+        let reduced = FeePolicy::Reduced { rate: dec("0.5") };
+        
+        assert!(authorize_fee_policy(None, &user).is_ok());
+        assert!(authorize_fee_policy(Some(&FeePolicy::Standard), &user).is_ok());
+        assert!(authorize_fee_policy(Some(&FeePolicy::Waived), &Caller::Admin("admin:alice".to_string())).is_ok());
+        for policy in [FeePolicy::Waived, reduced] {
+            assert!(matches!(authorize_fee_policy(Some(&policy), &user), Err(SettlementError::Forbidden(_))));
+        }
+    }
+    
+    #[test]
+    fn repeats_fold_by_when_they_happened() {
+        let creditor_id = Uuid::new_v4();
+        let call = |hours_ago: i64| Violation {
+            occurred_at: Utc::now() - Duration::hours(hours_ago),
+            ..hypothetical_violation(
+                creditor_id,
+                &HypotheticalViolation {
+                    violation_type: "HarassmentCalls".to_string(),
+                    statute: Statute::Fdcpa,
+                },
+            )
+        };
+        // Imported together, so recorded at the same moment.
+        let (march, march_again, june) = (call(2900), call(2897), call(700));
+        let june_id = june.id;
+        
+        let deduped = fold_repeats(vec![june, march_again, march.clone()], Duration::hours(24), |_| false);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!((deduped[0].id, deduped[0].repeat_count), (march.id, 1));
+        assert_eq!((deduped[1].id, deduped[1].repeat_count), (june_id, 0));
+    }
+    
+    #[test]
+    fn expired_violations_fold_into_no_run() {
+        let leverage = LeverageEngine::from_env().unwrap();
+        let (creditor_id, now) = (Uuid::new_v4(), Utc::now());
+        let lapsed = now.checked_sub_months(Months::new(12)).unwrap();
+        let call = |occurred_at| Violation {
+            occurred_at,
+            ..hypothetical_violation(
+                creditor_id,
+                &HypotheticalViolation {
+                    violation_type: "HarassmentCalls".to_string(),
+                    statute: Statute::Fdcpa,
+                },
+            )
+        };
+        // One pair straddles the end of the limitations period, the other is
+        // long past it.
+        let straddling = [call(lapsed - Duration::hours(2)), call(lapsed + Duration::hours(2))];
+        let stale = [call(lapsed - Duration::days(30)), call(lapsed - Duration::days(30) + Duration::hours(1))];
+        let violations = straddling.iter().chain(&stale).cloned().collect();
+        
+        let deduped = fold_repeats(violations, Duration::hours(24), |v| leverage.claim_expired(v, now));
+        let analysis = leverage.analyze(&deduped, "ZZ", now);
+        // The live call counts though an expired one came first, and each
+        // expired call is listed as one rather than passed off as a repeat.
+        assert_eq!(analysis.violation_count, 1);
+        assert_eq!(analysis.explanation.factors[0].violation_id, Some(straddling[1].id));
+        let mut expired = analysis.expired_violations.clone();
+        expired.sort();
+        let mut stale_ids = vec![straddling[0].id, stale[0].id, stale[1].id];
+        stale_ids.sort();
+        assert_eq!(expired, stale_ids);
+    }
+    
+    #[test]
+    fn acceptances_sign_the_currency_at_its_minor_units() {
+        let creditor_id = Uuid::new_v4();
+        let dinars = |settled: &str| Settlement {
+            currency: Currency::try_from("KWD".to_string()).unwrap(),
+            settled_amount: dec(settled),
+            ..Settlement::fixture()
+        };
+        let signed = acceptance_terms(&dinars("12.345"), creditor_id, None);
+        
+        assert!(signed.starts_with("damocles:accept-settlement:v2|"));
+        assert!(signed.contains("|currency=KWD|original=1000.000|settled=12.345|"));
+        assert_ne!(signed, acceptance_terms(&dinars("12.349"), creditor_id, None));
+        let dollars = Settlement { currency: Currency::usd(), ..dinars("12.345") };
+        assert_ne!(signed, acceptance_terms(&dollars, creditor_id, None));
+    }
+    
+    #[test]
+    fn cited_violations_say_how_each_figured_in_the_score() {
+        let (counted, repeat, expired) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut analysis = prediction_request().leverage_analysis;
+        analysis.expired_violations = vec![expired];
+        analysis.explanation.factors = vec![
+            Factor::new("FalseRepresentation", 15.0, 1.1).for_violation(counted),
+            Factor::new("CA Rosenthal Act", 16.5, 0.5),
+        ];
+        
+        let cited = cited_violations(&[counted, repeat, expired, counted], &analysis);
+        let uses: Vec<_> = cited.iter().map(|c| (c.violation_id, c.violation_use)).collect();
+        assert_eq!(
+            uses,
+            [
+                (counted, ViolationUse::Counted),
+                (repeat, ViolationUse::Repeat),
+                (expired, ViolationUse::Expired),
+            ]
+        );
+        assert!((cited[0].contribution - 16.5).abs() < 1e-9);
+        assert_eq!(cited[1].contribution, 0.0);
+        assert_eq!(cited[2].contribution, 0.0);
+    }
+    
+    // The tests below run against the Postgres database at `DATABASE_URL`,
+    // which must have the settlement schema and migrations applied.
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_accepts_have_exactly_one_winner() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        
+        let request = AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: None,
+        };
+        let (first, second) = tokio::join!(
+            engine.accept_settlement(&request),
+            engine.accept_settlement(&request),
+        );
+        
+        let results = [first, second];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results.iter().any(|r| matches!(
+            r,
+            Err(SettlementError::ConcurrentModification(_) | SettlementError::InvalidTransition { .. })
+        )));
+        
+        let stored = db.get_settlement(settlement.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, SettlementStatus::Accepted);
+        assert_eq!(stored.version, 1);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn executed_settlement_completes_once_the_chain_confirms() {
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(2));
+        let engine = test_engine(db.clone(), chain.clone());
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        let executed = engine.execute_settlement(settlement.id).await.unwrap();
+        assert!(executed.transaction_hash.is_some());
+        
+        let mut status = executed.status;
+        for _ in 0..50 {
+            status = db.get_settlement(settlement.id).await.unwrap().unwrap().status;
+            if status == SettlementStatus::Completed {
+                break;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+        assert_eq!(status, SettlementStatus::Completed);
+        assert_eq!(chain.submissions(), 1);
+        assert!(engine.verify_settlement_metadata(settlement.id).await.unwrap().verified);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_executes_submit_once() {
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(1_000));
+        let engine = test_engine(db.clone(), chain.clone());
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        
+        // Open a connection per execute up front, so they really overlap.
+        futures::future::join_all((0..5).map(|_| db.get_settlement(settlement.id))).await;
+        let executes = futures::future::join_all((0..5).map(|_| engine.execute_settlement(settlement.id))).await;
+        
+        assert_eq!(chain.submissions(), 1);
+        let tx_hashes: Vec<_> = executes
+            .into_iter()
+            .map(|executed| executed.unwrap().transaction_hash.unwrap())
+            .collect();
+        assert!(tx_hashes.iter().all(|tx_hash| *tx_hash == tx_hashes[0]));
+        
+        // A later execute reports the same transaction rather than paying again.
+        let replayed = engine.execute_settlement(settlement.id).await.unwrap();
+        assert_eq!(replayed.transaction_hash.as_ref(), Some(&tx_hashes[0]));
+        assert_eq!(chain.submissions(), 1);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn lapsed_proposals_are_expired_and_refuse_acceptance() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db
+            .insert_settlement(&Settlement {
+                expires_at: Utc::now() - Duration::minutes(1),
+                ..proposed_settlement()
+            })
+            .await
+            .unwrap();
+        
+        let request = AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: None,
+        };
+        assert!(matches!(
+            engine.accept_settlement(&request).await,
+            Err(SettlementError::Expired(_))
+        ));
+        
+        assert!(engine.expire_settlements().await.unwrap() >= 1);
+        let stored = db.get_settlement(settlement.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, SettlementStatus::Expired);
+        assert!(matches!(
+            engine.accept_settlement(&request).await,
+            Err(SettlementError::Expired(_))
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn deleted_settlements_are_hidden_but_kept() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        
+        let deleted = engine.delete_settlement(settlement.id).await.unwrap();
+        assert!(deleted.deleted_at.is_some());
+        
+        assert!(matches!(
+            engine.get_settlement(settlement.id, false).await,
+            Err(SettlementError::NotFound(..))
+        ));
+        assert_eq!(engine.get_settlement(settlement.id, true).await.unwrap().id, settlement.id);
+        
+        let listed = |include_deleted| ListSettlementsQuery {
+            user_id: settlement.user_id,
+            status: None,
+            limit: None,
+            cursor: None,
+            include_deleted,
+        };
+        assert!(engine.list_settlements(&listed(false)).await.unwrap().items.is_empty());
+        assert_eq!(engine.list_settlements(&listed(true)).await.unwrap().items.len(), 1);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn accepted_settlements_cannot_be_deleted() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        
+        assert!(matches!(
+            engine.delete_settlement(settlement.id).await,
+            Err(SettlementError::InvalidStatus { action: "delete", .. })
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn completed_envelopes_accept_the_settlement() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(settlement.debt_id)
+        .bind(settlement.user_id)
+        .bind(Uuid::new_v4())
+        .bind(&settlement.original_amount)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        
+        let requested = engine
+            .request_signature(
+                settlement.id,
+                &SignatureRequest {
+                    signer_name: "Test User".to_string(),
+                    signer_email: "user@example.com".to_string(),
+                    return_url: "https://app.example.com/signed".to_string(),
+                    debtor_id: None,
+                },
+            )
+            .await
+            .unwrap();
+        let envelope_id = requested.envelope.envelope_id.clone();
+        let accept = AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: Some(envelope_id.clone()),
+            debtor_id: None,
+            accepted_amount: None,
+        };
+        assert!(matches!(
+            engine.accept_settlement(&accept).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        let callback = serde_json::to_vec(&json!({
+            "envelope_id": envelope_id,
+            "status": EnvelopeStatus::Completed,
+        }))
+        .unwrap();
+        assert!(matches!(
+            engine.handle_signature_callback(settlement.id, &callback, "forged").await,
+            Err(SettlementError::InvalidSignature(_))
+        ));
+        engine
+            .handle_signature_callback(settlement.id, &callback, MOCK_CALLBACK_SIGNATURE)
+            .await
+            .unwrap();
+        
+        let accepted = engine.accept_settlement(&accept).await.unwrap();
+        assert_eq!(accepted.status, SettlementStatus::Accepted);
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        assert!(events.iter().any(|event| event.event_type == AuditEventType::Signed));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_inserts_get_distinct_reference_numbers() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        
+        let inserted = futures::future::try_join_all(
+            (0..10).map(|_| async { db.insert_settlement(&proposed_settlement()).await }),
+        )
+        .await
+        .unwrap();
+        let mut references: Vec<_> = inserted
+            .iter()
+            .map(|settlement| settlement.reference_number.clone().unwrap())
+            .collect();
+        references.sort();
+        references.dedup();
+        assert_eq!(references.len(), inserted.len());
+        
+        let year = Utc::now().format("%Y").to_string();
+        assert!(references.iter().all(|reference| reference.starts_with(&format!("DMC-{}-", year))));
+        
+        let found = engine
+            .get_settlement_by_reference(&references[0].to_lowercase())
+            .await
+            .unwrap();
+        assert_eq!(found.reference_number.as_ref(), Some(&references[0]));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn sword_events_queue_one_job_that_records_its_outcome() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let event = SwordEvent {
+            event_id: format!("sword-{}", Uuid::new_v4()),
+            user_id: Uuid::new_v4(),
+            creditor_id: Uuid::new_v4(),
+            violations: vec![Uuid::new_v4()],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+        };
+        
+        let first = engine.enqueue_sword_event(&event).await.unwrap();
+        let redelivered = engine.enqueue_sword_event(&event).await.unwrap();
+        assert!(first.created);
+        assert!(!redelivered.created);
+        assert_eq!(redelivered.job.id, first.job.id);
+        
+        let mut finished = None;
+        while let Some(job) = engine.run_next_auto_negotiation_job().await.unwrap() {
+            if job.id == first.job.id {
+                finished = Some(job);
+            }
+        }
+        // The user has no debt with the creditor, so there is nothing to settle.
+        let finished = finished.unwrap();
+        assert_eq!(finished.status, AutoNegotiationStatus::Failed);
+        assert_eq!(finished.attempts, 1);
+        assert!(finished.last_error.unwrap().contains("not found"));
+        assert!(finished.finished_at.is_some());
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn what_if_projects_each_hypothetical_violation_in_turn() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        
+        let request = WhatIfRequest {
+            base: CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            },
+            hypothetical_violations: ["FalseRepresentation", "HarassmentCalls"]
+                .map(|violation_type| HypotheticalViolation {
+                    violation_type: violation_type.to_string(),
+                    statute: Default::default(),
+                })
+                .to_vec(),
+        };
+        let projections = engine.what_if(&request).await.unwrap();
+        
+        let added: Vec<_> = projections.iter().map(|p| p.added_violation.as_deref()).collect();
+        assert_eq!(added, [None, Some("FalseRepresentation"), Some("HarassmentCalls")]);
+        assert_eq!(projections[0].marginal_reduction, dec("0"));
+        for pair in projections.windows(2) {
+            assert!(pair[1].total_leverage_score > pair[0].total_leverage_score);
+            assert_eq!(pair[1].marginal_reduction, &pair[0].settlement.amount - &pair[1].settlement.amount);
+        }
+        assert!(projections[2].settlement.amount < projections[0].settlement.amount);
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM settlements WHERE debt_id = $1")
+            .bind(debt_id)
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(stored, 0);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn paying_installments_accrues_the_fee() {
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(1));
+        let engine = test_engine(db.clone(), chain.clone());
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let (debtor, support) = (Caller::User(settlement.user_id), Caller::Admin("admin:alice".to_string()));
+        let plan = InstallmentPlan {
+            settlement_id: settlement.id,
+            num_installments: 2,
+            cadence: Cadence::Monthly,
+            first_due: Utc::now(),
+        };
+        engine.create_installment_plan(&plan).await.unwrap();
+        
+        assert!(matches!(
+            engine.pay_installment(settlement.id, 1, &debtor).await,
+            Err(SettlementError::InvalidStatus { .. })
+        ));
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        
+        // Paid out already, with no watch left to complete it.
+        let accepted = db.get_settlement(settlement.id).await.unwrap().unwrap();
+        let submitted = chain.submit(&accepted).await.unwrap();
+        sqlx::query("UPDATE settlements SET transaction_hash = $2 WHERE id = $1")
+            .bind(settlement.id)
+            .bind(&submitted.tx_hash)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        
+        let schedule = engine.pay_installment(settlement.id, 1, &debtor).await.unwrap();
+        assert_eq!(schedule.accrued_fee, dec("43.25"));
+        assert_eq!(schedule.total_fee, dec("86.50"));
+        assert!(matches!(
+            engine.pay_installment(settlement.id, 1, &debtor).await,
+            Err(SettlementError::Conflict(_))
+        ));
+        assert!(matches!(
+            engine.pay_installment(settlement.id, 3, &debtor).await,
+            Err(SettlementError::NotFound(..))
+        ));
+        assert_eq!(db.get_settlement(settlement.id).await.unwrap().unwrap().status, SettlementStatus::Accepted);
+        
+        // The last payment completes it, the transaction having confirmed.
+        let schedule = engine.pay_installment(settlement.id, 2, &support).await.unwrap();
+        assert_eq!(schedule.accrued_fee, schedule.total_fee);
+        assert_eq!(db.get_settlement(settlement.id).await.unwrap().unwrap().status, SettlementStatus::Completed);
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        let payers: Vec<&str> = events
+            .iter()
+            .filter(|event| event.event_type == AuditEventType::InstallmentPaid)
+            .map(|event| event.actor.as_str())
+            .collect();
+        assert_eq!(payers, [user_actor(settlement.user_id).as_str(), "admin:alice"]);
+        assert_eq!(events.last().unwrap().event_type, AuditEventType::Completed);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn forced_status_skips_the_graph_but_not_the_chain() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let force = |status, force_unsafe| ForceStatusRequest {
+            status,
+            reason: "creditor confirmed payment by phone".to_string(),
+            force_unsafe,
+        };
+        
+        assert!(matches!(
+            engine
+                .force_status(
+                    settlement.id,
+                    &ForceStatusRequest { reason: " ".to_string(), ..force(SettlementStatus::Accepted, false) },
+                    "admin:alice",
+                )
+                .await,
+            Err(SettlementError::Validation(_))
+        ));
+        assert!(matches!(
+            engine.force_status(settlement.id, &force(SettlementStatus::Completed, false), "admin:alice").await,
+            Err(SettlementError::Conflict(_))
+        ));
+        
+        // Proposed -> Rejected -> Accepted leaves the lifecycle graph twice.
+        engine
+            .force_status(settlement.id, &force(SettlementStatus::Rejected, false), "admin:alice")
+            .await
+            .unwrap();
+        let accepted = engine
+            .force_status(settlement.id, &force(SettlementStatus::Accepted, false), "admin:alice")
+            .await
+            .unwrap();
+        assert_eq!(accepted.status, SettlementStatus::Accepted);
+        
+        let completed = engine
+            .force_status(settlement.id, &force(SettlementStatus::Completed, true), "admin:alice")
+            .await
+            .unwrap();
+        assert_eq!(completed.status, SettlementStatus::Completed);
+        assert!(completed.completed_at.is_some());
+        
+        let forced: Vec<_> = engine
+            .get_settlement_events(settlement.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|event| event.event_type == AuditEventType::StatusForced)
+            .collect();
+        assert_eq!(forced.len(), 3);
+        assert!(forced.iter().all(|event| event.actor == "admin:alice"));
+        assert_eq!(forced[2].metadata["force_unsafe"], true);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn proposals_warn_when_the_creditor_cannot_be_reached() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("1000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let request = CreateSettlementRequest {
+            user_id,
+            creditor_id,
+            debt_id: Some(debt_id),
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+            locale: Default::default(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: None,
+        };
+        
+        let proposal = engine.create_settlement_proposal(&request).await.unwrap();
+        assert_eq!(proposal.warnings.len(), 1);
+        assert!(matches!(
+            engine.get_creditor_contact(creditor_id).await,
+            Err(SettlementError::NotFound(..))
+        ));
+        
+        let contact = CreditorContactRequest {
+            email: Some(" disputes@creditor.com ".to_string()),
+            mailing_address: None,
+            preferred_channel: ContactChannel::Email,
+        };
+        engine.set_creditor_contact(creditor_id, &contact).await.unwrap();
+        let stored = engine.get_creditor_contact(creditor_id).await.unwrap();
+        assert_eq!(stored.email.as_deref(), Some("disputes@creditor.com"));
+        
+        let proposal = engine.create_settlement_proposal(&request).await.unwrap();
+        assert!(proposal.warnings.is_empty());
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn bundled_settlements_cover_and_settle_every_debt() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let debt_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        for (debt_id, amount) in debt_ids.iter().zip(["3000", "1000"]) {
+            sqlx::query(
+                "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+                 VALUES ($1, $2, $3, $4, $4)",
+            )
+            .bind(debt_id)
+            .bind(user_id)
+            .bind(creditor_id)
+            .bind(dec(amount))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        let request = BundleSettlementRequest {
+            user_id,
+            creditor_id,
+            debt_ids: debt_ids.to_vec(),
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+            locale: Default::default(),
+            ai_mode: None,
+        };
+        
+        let proposal = engine.create_bundled_settlement(&request).await.unwrap();
+        let settlement = proposal.settlement;
+        assert_eq!(settlement.original_amount, dec("4000"));
+        assert_eq!(settlement.debt_id, debt_ids[0]);
+        
+        let breakdown = engine.get_settlement_debts(settlement.id).await.unwrap();
+        assert_eq!(breakdown.debts.len(), 2);
+        let total: BigDecimal = breakdown.debts.iter().map(|debt| debt.settled_amount.clone()).sum();
+        assert_eq!(total, settlement.settled_amount);
+        
+        let forced = ForceStatusRequest {
+            status: SettlementStatus::Completed,
+            reason: "paid off-chain".to_string(),
+            force_unsafe: true,
+        };
+        engine.force_status(settlement.id, &forced, "admin:alice").await.unwrap();
+        for debt_id in debt_ids {
+            assert_eq!(db.get_debt(debt_id).await.unwrap().unwrap().status, "settled");
+        }
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn proposals_respect_the_creditor_floor_but_report_true_leverage() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let request = WhatIfRequest {
+            base: CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            },
+            hypothetical_violations: ["FalseRepresentation", "HarassmentCalls", "ThreatOfArrest"]
+                .map(|violation_type| HypotheticalViolation {
+                    violation_type: violation_type.to_string(),
+                    statute: Default::default(),
+                })
+                .to_vec(),
+        };
+        let unfloored = engine.what_if(&request).await.unwrap();
+        let lowest = unfloored.last().unwrap().settlement.amount.clone();
+        
+        let floor = SettlementFloor { settlement_floor_percentage: Some(99.0) };
+        engine.set_settlement_floor(creditor_id, &floor).await.unwrap();
+        let floored = engine.what_if(&request).await.unwrap();
+        let last = floored.last().unwrap();
+        
+        assert!(lowest < dec("9900.00"));
+        assert_eq!(last.settlement.amount, dec("9900.00"));
+        assert!(last.settlement.floor_applied);
+        assert_eq!(last.total_leverage_score, unfloored.last().unwrap().total_leverage_score);
+        
+        let proposal = engine.preview_settlement_proposal(&request.base).await.unwrap();
+        assert!(proposal.settlement.settled_amount >= dec("9900.00"));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn completed_settlements_missing_on_chain_are_flagged_for_review() {
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(1));
+        let engine = test_engine(db.clone(), chain.clone());
+        let complete = |settlement: Settlement, tx_hash: String| {
+            let db = db.clone();
+            async move {
+                sqlx::query(
+                    "UPDATE settlements SET status = 'completed', transaction_hash = $2, completed_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(settlement.id)
+                .bind(tx_hash)
+                .execute(db.pool())
+                .await
+                .unwrap();
+                db.get_settlement(settlement.id).await.unwrap().unwrap()
+            }
+        };
+        
+        let confirmed = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let tx = chain.submit(&confirmed).await.unwrap();
+        let confirmed = complete(confirmed, tx.tx_hash).await;
+        let ghost = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let ghost = complete(ghost, "ghosttx".to_string()).await;
+        
+        assert!(!engine.reconcile_settlement(&confirmed).await.unwrap());
+        assert!(engine.reconcile_settlement(&ghost).await.unwrap());
+        
+        let flagged = db.get_settlement(ghost.id).await.unwrap().unwrap();
+        assert_eq!(flagged.status, SettlementStatus::NeedsReview);
+        assert_eq!(
+            db.get_settlement(confirmed.id).await.unwrap().unwrap().status,
+            SettlementStatus::Completed
+        );
+        
+        let report = engine.reconciliation_report().await.unwrap();
+        let discrepancy = report
+            .discrepancies
+            .iter()
+            .find(|d| d.settlement_id == ghost.id)
+            .expect("flagged settlement is reported");
+        assert_eq!(discrepancy.flag.recorded_status, SettlementStatus::Completed);
+        assert_eq!(discrepancy.flag.chain_state, ChainState::Dropped);
+        assert_eq!(discrepancy.flag.tx_hash, "ghosttx");
+        assert!(report.discrepancies.iter().all(|d| d.settlement_id != confirmed.id));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn accepting_a_different_amount_recomputes_savings_and_fee() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let accept = |amount: &str| AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: Some(dec(amount)),
+        };
+        
+        for amount in ["1000.01", "0", "700.001"] {
+            assert!(matches!(
+                engine.accept_settlement(&accept(amount)).await,
+                Err(SettlementError::Validation(_))
+            ));
+        }
+        
+        let accepted = engine.accept_settlement(&accept("700")).await.unwrap();
+        assert_eq!(accepted.status, SettlementStatus::Accepted);
+        assert_eq!(accepted.settled_amount, dec("700.00"));
+        assert_eq!(accepted.saved_amount, dec("300.00"));
+        assert_eq!(accepted.platform_fee, dec("67.50"));
+        assert_eq!(accepted.proposed_amount, Some(dec("600")));
+        assert_eq!(accepted.proposed_platform_fee, Some(dec("86.50")));
+        
+        // Rescinding goes back to the proposal as made.
+        let rescinded = engine.rescind_settlement(settlement.id, &Caller::User(settlement.user_id)).await.unwrap();
+        assert_eq!(rescinded.settled_amount, dec("600"));
+        assert_eq!(rescinded.saved_amount, dec("400"));
+        assert_eq!(rescinded.platform_fee, dec("86.50"));
+        assert_eq!(rescinded.proposed_amount, None);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn rescinding_leaves_the_queue_and_waits_out_a_submission() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let debtor = Caller::User(settlement.user_id);
+        let accept = AcceptSettlementRequest {
+            settlement_id: settlement.id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: None,
+        };
+        let accepted = engine.accept_settlement(&accept).await.unwrap();
+        
+        // A submission under way may yet take a transaction.
+        let submitting = db.set_submission_intent(accepted.id, accepted.version, Some(Uuid::new_v4())).await.unwrap().unwrap();
+        assert!(matches!(
+            engine.rescind_settlement(settlement.id, &debtor).await,
+            Err(SettlementError::Conflict(_))
+        ));
+        
+        let cleared = db.set_submission_intent(submitting.id, submitting.version, None).await.unwrap().unwrap();
+        db.queue_settlement_execution(cleared.id, cleared.version).await.unwrap().unwrap();
+        let rescinded = engine.rescind_settlement(settlement.id, &debtor).await.unwrap();
+        assert_eq!(rescinded.status, SettlementStatus::Proposed);
+        assert!(rescinded.rescinded_at.is_some());
+        assert_eq!(rescinded.execution_queued_at, None);
+        assert_eq!(rescinded.escrow_refund_after, None);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn simulated_proposals_are_reproducible() {
+        let db = test_database().await;
+        let engine = SettlementEngine::new(
+            db.clone(),
+            AiClient::simulated(PromptTemplates::default(), SimulationConfig { seed: 7 }),
+            Arc::new(MockBlockchainClient::new(1)),
+            Arc::new(MockSignatureProvider),
+            LeverageEngine::from_env().unwrap(),
+            Metrics::new(),
+        );
+        let mut proposals = Vec::new();
+        for _ in 0..2 {
+            let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+            sqlx::query(
+                "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+                 VALUES ($1, $2, $3, $4, $4)",
+            )
+            .bind(debt_id)
+            .bind(user_id)
+            .bind(creditor_id)
+            .bind(dec("2500"))
+            .execute(db.pool())
+            .await
+            .unwrap();
+            let request = CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            };
+            proposals.push(engine.preview_settlement_proposal(&request).await.unwrap());
+        }
+        
+        let (first, second) = (&proposals[0], &proposals[1]);
+        assert_eq!(first.settlement.settled_amount, second.settlement.settled_amount);
+        assert_eq!(first.confidence_score, second.confidence_score);
+        assert_eq!(first.source, ProposalSource::Ai);
+        assert_eq!(first.model_version, SIMULATION_MODEL_VERSION);
+        assert_eq!(first.simulation.map(|simulation| simulation.seed), Some(7));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn a_saturated_pool_answers_503_to_retry() {
+        use actix_web::ResponseError;
+        
+        use crate::handlers::ApiError;
+        
+        let config = PoolConfig { max_connections: 1, acquire_timeout: std::time::Duration::from_millis(100) };
+        let db = Database::connect_with(&std::env::var("DATABASE_URL").unwrap(), config)
+            .await
+            .unwrap();
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let held = db.pool().acquire().await.unwrap();
+        assert_eq!(engine.db_pool_status(), PoolStatus { in_use: 1, idle: 0, max: 1 });
+        
+        let e = engine.get_settlement(Uuid::new_v4(), false).await.unwrap_err();
+        assert!(matches!(e, SettlementError::Database(sqlx::Error::PoolTimedOut)), "{}", e);
+        let response = ApiError::from(e).error_response();
+        assert_eq!(response.status().as_u16(), 503);
+        assert!(response.headers().contains_key("retry-after"));
+        
+        drop(held);
+        assert!(matches!(
+            engine.get_settlement(Uuid::new_v4(), false).await,
+            Err(SettlementError::NotFound(..))
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn creditor_proposals_are_answered_like_any_other() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let offer = |amount: &str| CreditorProposalRequest {
+            user_id,
+            debt_id,
+            amount: dec(amount),
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            locale: Default::default(),
+        };
+        
+        for amount in ["10000.01", "9000.001"] {
+            assert!(matches!(
+                engine.create_creditor_proposal(creditor_id, &offer(amount)).await,
+                Err(SettlementError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            engine.create_creditor_proposal(Uuid::new_v4(), &offer("9000")).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        let proposal = engine.create_creditor_proposal(creditor_id, &offer("9000")).await.unwrap();
+        let settlement = &proposal.settlement;
+        assert_eq!(settlement.proposed_by, Party::Creditor);
+        assert_eq!(settlement.status, SettlementStatus::Proposed);
+        assert_eq!(settlement.settled_amount, dec("9000.00"));
+        assert_eq!(settlement.saved_amount, dec("1000.00"));
+        assert_eq!(proposal.source, ProposalSource::Creditor);
+        assert!(proposal.reasoning[0].contains("offers a 10.0% reduction"), "{:?}", proposal.reasoning);
+        assert!(proposal.recommended_action.starts_with("Accept"));
+        
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        assert_eq!(events[0].actor, format!("creditor:{}", creditor_id));
+        
+        let accepted = engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(accepted.status, SettlementStatus::Accepted);
+        assert_eq!(accepted.proposed_by, Party::Creditor);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn savings_summary_weights_the_reduction_by_debt_size() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let user_id = Uuid::new_v4();
+        let settlement = |status, original: &str, settled: &str, currency: Currency| {
+            let (original, settled) = (dec(original), dec(settled));
+            Settlement {
+                user_id,
+                status,
+                saved_amount: &original - &settled,
+                original_amount: original,
+                settled_amount: settled,
+                platform_fee: dec("10"),
+                currency,
+                ..proposed_settlement()
+            }
+        };
+        for settlement in [
+            // 50% off a small debt and 10% off a large one.
+            settlement(SettlementStatus::Completed, "1000", "500", Currency::usd()),
+            settlement(SettlementStatus::Completed, "9000", "8100", Currency::usd()),
+            settlement(SettlementStatus::Failed, "5000", "1000", Currency::usd()),
+            settlement(SettlementStatus::Proposed, "5000", "1000", Currency::usd()),
+            settlement(SettlementStatus::Completed, "5000", "1000", Currency::try_from("EUR".to_string()).unwrap()),
+        ] {
+            db.insert_settlement(&settlement).await.unwrap();
+        }
+        
+        let summary = engine.savings_summary(user_id, None).await.unwrap();
+        assert_eq!(summary.settlement_count, 2);
+        assert_eq!(summary.total_original, dec("10000"));
+        assert_eq!(summary.total_settled, dec("8600"));
+        assert_eq!(summary.total_saved, dec("1400"));
+        assert_eq!(summary.total_fees, dec("20"));
+        // 1400 of 10000, not the 30% mean of 50% and 10%.
+        assert!((summary.average_reduction_percentage.unwrap() - 14.0).abs() < 1e-9);
+        
+        let nothing = engine.savings_summary(Uuid::new_v4(), None).await.unwrap();
+        assert_eq!(nothing.settlement_count, 0);
+        assert_eq!(nothing.total_saved, dec("0"));
+        assert_eq!(nothing.average_reduction_percentage, None);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn executions_queue_while_the_node_breaker_is_open() {
+        let db = test_database().await;
+        let chain = Arc::new(CircuitBreaker::new(
+            MockBlockchainClient::new(1_000),
+            BreakerConfig {
+                failure_threshold: 1,
+                open_for: StdDuration::from_secs(60),
+            },
+        ));
+        let engine = test_engine(db.clone(), chain.clone());
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        
+        chain.trip();
+        let queued = engine.execute_settlement(settlement.id).await.unwrap();
+        assert_eq!(queued.status, SettlementStatus::Accepted);
+        assert!(queued.execution_queued_at.is_some());
+        assert!(queued.transaction_hash.is_none());
+        assert_eq!(engine.run_execution_queue().await.unwrap(), 0);
+        
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        assert!(events.iter().any(|e| e.event_type == AuditEventType::ExecutionQueued));
+        
+        chain.end_open_period();
+        assert_eq!(engine.run_execution_queue().await.unwrap(), 1);
+        assert_eq!(chain.state(), CircuitState::Closed);
+        let submitted = db.get_settlement(settlement.id).await.unwrap().unwrap();
+        assert!(submitted.execution_queued_at.is_none());
+        assert!(submitted.transaction_hash.is_some());
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn proposals_are_written_in_the_requested_locale() {
+        let db = test_database().await;
+        let engine = SettlementEngine::new(
+            db.clone(),
+            AiClient::simulated(PromptTemplates::default(), SimulationConfig { seed: 7 }),
+            Arc::new(MockBlockchainClient::new(1)),
+            Arc::new(MockSignatureProvider),
+            LeverageEngine::from_env().unwrap(),
+            Metrics::new(),
+        );
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("2500"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let preview = |locale: &str| {
+            let request = CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::Balanced,
+                locale: Locale::try_from(locale.to_string()).unwrap(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            };
+            let engine = engine.clone();
+            async move { engine.preview_settlement_proposal(&request).await.unwrap() }
+        };
+        
+        let english = preview("en-US").await;
+        let spanish = preview("es-US").await;
+        assert_eq!(english.settlement.settled_amount, spanish.settlement.settled_amount);
+        assert_eq!(english.reasoning.len(), spanish.reasoning.len());
+        assert!(english.reasoning[0].starts_with("Balanced strategy"), "{:?}", english.reasoning);
+        assert!(spanish.reasoning[0].starts_with("Estrategia equilibrada"), "{:?}", spanish.reasoning);
+        assert!(spanish.reasoning.iter().any(|line| line.contains("FDCPA")), "{:?}", spanish.reasoning);
+        let [.., simulated, anchored] = spanish.reasoning.as_slice() else {
+            panic!("{:?}", spanish.reasoning);
+        };
+        assert!(simulated.starts_with("Propuesta simulada"), "{:?}", spanish.reasoning);
+        assert!(anchored.starts_with("Oferta inicial"), "{:?}", spanish.reasoning);
+        assert_ne!(english.recommended_action, spanish.recommended_action);
+        
+        // A locale without a catalog reads English.
+        assert_eq!(preview("fr-FR").await.reasoning, english.reasoning);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn recompute_replaces_stale_terms_with_the_current_violations() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let proposal = engine
+            .create_settlement_proposal(&CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            })
+            .await
+            .unwrap();
+        let settlement_id = proposal.settlement.id;
+        
+        // Documented after the proposal was made.
+        let mut violations = Vec::new();
+        for violation_type in ["FalseRepresentation", "HarassmentCalls"] {
+            let id = Uuid::new_v4();
+            sqlx::query(
+                "INSERT INTO violations (id, creditor_id, type, severity, confidence, legal_reference, estimated_damage)
+                 VALUES ($1, $2, $3, 'high', 0.9, '15 U.S.C. 1692e', 500)",
+            )
+            .bind(id)
+            .bind(creditor_id)
+            .bind(violation_type)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            violations.push(id);
+        }
+        let request = RecomputeSettlementRequest {
+            violations,
+            jurisdiction: "CA".to_string(),
+            locale: Default::default(),
+        };
+        let recomputed = engine.recompute_settlement(settlement_id, &request).await.unwrap();
+        
+        let (before, after) = (&proposal.settlement, &recomputed.settlement);
+        assert!(after.settled_amount < before.settled_amount);
+        assert_eq!(&after.settled_amount + &after.saved_amount, before.original_amount);
+        assert_eq!(after.platform_fee, recomputed.fee_breakdown.total());
+        assert_eq!(after.version, before.version + 1);
+        assert_eq!(after.expires_at, before.expires_at);
+        let changes = &recomputed.changes;
+        assert_eq!(changes.settled_amount, AmountChange::new(&before.settled_amount, &after.settled_amount));
+        assert_eq!(changes.saved_amount.difference, -&changes.settled_amount.difference);
+        assert_eq!(changes.previous_leverage_score, Some(proposal.leverage_analysis.total_leverage_score));
+        assert!(changes.leverage_score > proposal.leverage_analysis.total_leverage_score);
+        let events = engine.get_settlement_events(settlement_id).await.unwrap();
+        let event = events.iter().find(|e| e.event_type == AuditEventType::Recomputed).unwrap();
+        assert_eq!(event.metadata["changes"]["settled_amount"]["after"], json!(changes.settled_amount.after));
+        assert_eq!(event.metadata["violation_count"], json!(2));
+        
+        engine
+            .reject_settlement(settlement_id, RejectReason::AmountTooHigh, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.recompute_settlement(settlement_id, &request).await,
+            Err(SettlementError::InvalidStatus { action: "recompute", .. })
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn only_parties_to_a_settlement_may_access_it() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let creditor_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(settlement.debt_id)
+        .bind(settlement.user_id)
+        .bind(creditor_id)
+        .bind(&settlement.original_amount)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        
+        for caller in [
+            Caller::User(settlement.user_id),
+            Caller::Creditor(creditor_id),
+            Caller::Admin("admin:alice".to_string()),
+        ] {
+            let authorized = engine.authorize_settlement(settlement.id, &caller).await.unwrap();
+            assert_eq!(authorized.id, settlement.id);
+        }
+        for caller in [Caller::User(Uuid::new_v4()), Caller::Creditor(Uuid::new_v4())] {
+            let refused = engine.authorize_settlement(settlement.id, &caller).await.unwrap_err();
+            assert!(matches!(refused, SettlementError::Forbidden(_)), "{}", refused);
+        }
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn transaction_status_follows_the_chain_and_is_cached_briefly() {
+        let chain = Arc::new(MockBlockchainClient::new(1));
+        let engine = test_engine(test_database().await, chain.clone());
+        let mut settlement = proposed_settlement();
+        
+        let unsubmitted = engine.transaction_status(&settlement).await.unwrap();
+        assert_eq!(unsubmitted.status, TransactionState::NotSubmitted);
+        assert!(unsubmitted.explorer_url.is_none());
+        
+        let tx = chain.submit(&settlement).await.unwrap();
+        settlement.transaction_hash = Some(tx.tx_hash.clone());
+        let first = engine.transaction_status(&settlement).await.unwrap();
+        assert_eq!(first.status, TransactionState::Confirmed);
+        assert_eq!(first.confirmations, Some(1));
+        assert_eq!(
+            first.explorer_url.as_deref(),
+            Some(format!("{}/{}", DEFAULT_EXPLORER_TX_URL, tx.tx_hash).as_str())
+        );
+        
+        // Every poll of the mock mines a block, so only a cached answer
+        // comes back at the same depth.
+        let second = engine.transaction_status(&settlement).await.unwrap();
+        assert_eq!(second.confirmations, first.confirmations);
+        assert_eq!(second.block_height, first.block_height);
+        
+        settlement.transaction_hash = Some("never-submitted".to_string());
+        let unknown = engine.transaction_status(&settlement).await.unwrap();
+        assert_eq!(unknown.status, TransactionState::NotFound);
+        assert_eq!(unknown.block_height, None);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn batches_execute_in_one_transaction_or_not_at_all() {
+        let db = test_database().await;
+        let chain = Arc::new(CircuitBreaker::new(
+            MockBlockchainClient::never_confirming(),
+            BreakerConfig {
+                failure_threshold: 1,
+                open_for: StdDuration::from_secs(60),
+            },
+        ));
+        let engine = test_engine(db.clone(), chain.clone());
+        let (user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let accepted = |creditor_id: Uuid| {
+            let (db, engine) = (db.clone(), engine.clone());
+            async move {
+                let settlement = db
+                    .insert_settlement(&Settlement { user_id, ..proposed_settlement() })
+                    .await
+                    .unwrap();
+                sqlx::query(
+                    "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+                     VALUES ($1, $2, $3, $4, $4)",
+                )
+                .bind(settlement.debt_id)
+                .bind(user_id)
+                .bind(creditor_id)
+                .bind(&settlement.original_amount)
+                .execute(db.pool())
+                .await
+                .unwrap();
+                engine
+                    .accept_settlement(&AcceptSettlementRequest {
+                        settlement_id: settlement.id,
+                        user_signature: None,
+                        envelope_id: None,
+                        debtor_id: None,
+                        accepted_amount: None,
+                    })
+                    .await
+                    .unwrap();
+                settlement.id
+            }
+        };
+        let (first, second) = (accepted(creditor_id).await, accepted(creditor_id).await);
+        let elsewhere = accepted(Uuid::new_v4()).await;
+        let caller = Caller::User(user_id);
+        
+        let mixed = engine.execute_batch(&[first, elsewhere], &caller).await.unwrap_err();
+        assert!(matches!(mixed, SettlementError::Validation(_)), "{}", mixed);
+        let stranger = engine
+            .execute_batch(&[first, second], &Caller::User(Uuid::new_v4()))
+            .await
+            .unwrap_err();
+        assert!(matches!(stranger, SettlementError::Forbidden(_)), "{}", stranger);
+        
+        chain.trip();
+        let unavailable = engine.execute_batch(&[first, second], &caller).await.unwrap_err();
+        assert!(matches!(unavailable, SettlementError::Blockchain(_)), "{}", unavailable);
+        for id in [first, second] {
+            let untouched = db.get_settlement(id).await.unwrap().unwrap();
+            assert!(untouched.transaction_hash.is_none());
+            assert!(untouched.execution_queued_at.is_none());
+        }
+        
+        chain.end_open_period();
+        let batch = engine.execute_batch(&[first, second], &caller).await.unwrap();
+        assert_eq!(batch.creditor_id, creditor_id);
+        assert_eq!(batch.settlements.iter().map(|s| s.id).collect::<Vec<_>>(), [first, second]);
+        for settlement in &batch.settlements {
+            assert_eq!(settlement.transaction_hash.as_deref(), Some(batch.transaction_hash.as_str()));
+            assert!(engine.verify_settlement_metadata(settlement.id).await.unwrap().verified);
+        }
+        
+        let again = engine.execute_batch(&[first, second], &caller).await.unwrap_err();
+        assert!(matches!(again, SettlementError::Conflict(_)), "{}", again);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn acceptance_records_the_clause_versions_and_execution_needs_the_required_ones() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1_000)));
+        let creditor_id = Uuid::new_v4();
+        let clause = |key: &str, body: &str, required| ClauseTemplate {
+            key: key.to_string(),
+            title: key.to_string(),
+            body: body.to_string(),
+            required,
+        };
+        let set_terms = |clauses| {
+            let engine = engine.clone();
+            async move {
+                engine
+                    .set_creditor_terms(creditor_id, &CreditorTermsRequest { clauses })
+                    .await
+                    .unwrap()
+                    .clauses
+                    .into_iter()
+                    .map(|clause| (clause.key, clause.version))
+                    .collect::<Vec<_>>()
+            }
+        };
+        let versions = |clauses: &[(&str, i32)]| -> Vec<(String, i32)> {
+            clauses.iter().map(|&(key, version)| (key.to_string(), version)).collect()
+        };
+        
+        set_terms(vec![
+            clause("release-of-claims", "The Creditor releases debt {{ debt_id }}.", true),
+            clause("confidentiality", "The parties keep these terms confidential.", false),
+        ])
+        .await;
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(settlement.debt_id)
+        .bind(settlement.user_id)
+        .bind(creditor_id)
+        .bind(&settlement.original_amount)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let accepted = engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        let agreed = db.get_agreed_clauses(accepted.id).await.unwrap();
+        assert_eq!(
+            agreed.iter().map(|c| (c.key.clone(), c.version)).collect::<Vec<_>>(),
+            versions(&[("release-of-claims", 1), ("confidentiality", 1)])
+        );
+        assert_eq!(accepted.clauses_hash, clauses_hash(&agreed));
+        
+        // Rewording keeps what was agreed; a new required clause holds the
+        // payment back.
+        let reworded = set_terms(vec![
+            clause("release-of-claims", "The Creditor releases every claim.", true),
+            clause("confidentiality", "The parties keep these terms confidential.", false),
+            clause("no-resale", "The debt will not be sold.", true),
+        ])
+        .await;
+        assert_eq!(
+            reworded,
+            versions(&[("release-of-claims", 2), ("confidentiality", 1), ("no-resale", 1)])
+        );
+        assert_eq!(db.get_agreed_clauses(accepted.id).await.unwrap(), agreed);
+        let refused = engine.execute_settlement(accepted.id).await.unwrap_err();
+        let SettlementError::Conflict(message) = &refused else {
+            panic!("{}", refused);
+        };
+        assert!(message.contains("no-resale v1"), "{}", message);
+        assert!(!message.contains("release-of-claims"), "{}", message);
+        
+        set_terms(vec![clause("release-of-claims", "The Creditor releases every claim.", true)]).await;
+        let executed = engine.execute_settlement(accepted.id).await.unwrap();
+        assert_eq!(executed.terms_hash, Some(metadata::terms_hash(&accepted)));
+        assert!(metadata::settlement_terms(&executed).starts_with("damocles-settlement-terms:v3|"));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn event_streams_replay_resume_and_follow_new_events() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let mut recorded = Vec::new();
+        for event_type in [AuditEventType::Created, AuditEventType::Negotiated, AuditEventType::Negotiated] {
+            let event = engine.record_event(settlement.id, event_type, "system", json!({})).await.unwrap();
+            recorded.push(event.id);
+        }
+        async fn next_id(stream: &mut (impl Stream<Item = StreamedEvent> + Unpin)) -> i64 {
+            match tokio::time::timeout(StdDuration::from_secs(5), stream.next()).await {
+                Ok(Some(StreamedEvent::Event(event))) => event.id,
+                other => panic!("expected an event, got {:?}", other),
+            }
+        }
+        
+        assert!(matches!(
+            engine.settlement_event_stream(Uuid::new_v4(), None).await,
+            Err(SettlementError::NotFound(..))
+        ));
+        
+        let mut replayed = Box::pin(engine.settlement_event_stream(settlement.id, None).await.unwrap());
+        for id in &recorded {
+            assert_eq!(next_id(&mut replayed).await, *id);
+        }
+        let mut resumed = Box::pin(engine.settlement_event_stream(settlement.id, Some(recorded[1])).await.unwrap());
+        assert_eq!(next_id(&mut resumed).await, recorded[2]);
+        assert_eq!(engine.open_event_streams(), 2);
+        
+        let live = engine
+            .record_event(settlement.id, AuditEventType::Accepted, "system", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(next_id(&mut replayed).await, live.id);
+        assert_eq!(next_id(&mut resumed).await, live.id);
+        
+        drop(replayed);
+        drop(resumed);
+        assert_eq!(engine.open_event_streams(), 0);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn a_waived_fee_charges_nothing_but_shows_the_standard_fee() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let request = |fee_policy| CreateSettlementRequest {
+            user_id,
+            creditor_id,
+            debt_id: Some(debt_id),
+            violations: vec![],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+            locale: Default::default(),
+            fee_policy,
+            auto_accept_below: None,
+            ai_mode: None,
+        };
+        
+        assert!(matches!(
+            engine.set_user_fee_policy(user_id, &FeePolicy::Reduced { rate: dec("2") }, "admin:alice").await,
+            Err(SettlementError::Validation(_))
+        ));
+        let granted = engine.set_user_fee_policy(user_id, &FeePolicy::Waived, "admin:alice").await.unwrap();
+        assert_eq!(granted.set_by, "admin:alice");
+        
+        let waived = engine.create_settlement_proposal(&request(None)).await.unwrap();
+        let fees = &waived.fee_breakdown;
+        assert_eq!(waived.settlement.platform_fee, dec("0"));
+        assert_eq!(waived.settlement.fee_policy, FeePolicy::Waived);
+        assert!(fees.standard_total > dec("0"));
+        assert_eq!(fees.policy_discount, fees.standard_total);
+        let events = engine.get_settlement_events(waived.settlement.id).await.unwrap();
+        let applied = events
+            .iter()
+            .find(|event| event.event_type == AuditEventType::FeePolicyApplied)
+            .expect("the waiver is on the audit trail");
+        assert_eq!(applied.metadata["granted_by"], "user");
+        assert_eq!(applied.metadata["fee_policy"]["kind"], "waived");
+        
+        // Accepting a different amount is charged under the same policy.
+        let accepted = engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: waived.settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: Some(&waived.settlement.settled_amount - dec("100")),
+            })
+            .await
+            .unwrap();
+        assert_eq!(accepted.platform_fee, dec("0"));
+        
+        // A request's own policy wins over the user's.
+        let reduced = engine
+            .create_settlement_proposal(&request(Some(FeePolicy::Reduced { rate: dec("0.5") })))
+            .await
+            .unwrap();
+        let fees = &reduced.fee_breakdown;
+        assert_eq!(reduced.settlement.platform_fee, round_to_minor_units(&fees.standard_total / dec("2"), &usd()));
+        let standard = engine.create_settlement_proposal(&request(Some(FeePolicy::Standard))).await.unwrap();
+        assert_eq!(standard.settlement.platform_fee, standard.fee_breakdown.standard_total);
+        assert!(engine
+            .get_settlement_events(standard.settlement.id)
+            .await
+            .unwrap()
+            .iter()
+            .all(|event| event.event_type != AuditEventType::FeePolicyApplied));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn a_violation_lists_the_settlements_citing_it() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        // Two calls within the dedup window count once, and another
+        // creditor's violation not at all.
+        let mut violations = Vec::new();
+        for (violation_type, committed_by) in [
+            ("HarassmentCalls", creditor_id),
+            ("HarassmentCalls", creditor_id),
+            ("FalseRepresentation", Uuid::new_v4()),
+        ] {
+            let id = Uuid::new_v4();
+            sqlx::query(
+                "INSERT INTO violations (id, creditor_id, type, severity, confidence, legal_reference, estimated_damage, created_at)
+                 VALUES ($1, $2, $3, 'high', 0.9, '15 U.S.C. 1692d', 500, NOW() + $4 * INTERVAL '1 minute')",
+            )
+            .bind(id)
+            .bind(committed_by)
+            .bind(violation_type)
+            .bind(violations.len() as i32)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            violations.push(id);
+        }
+        
+        let proposal = engine
+            .create_settlement_proposal(&CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: violations.clone(),
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            })
+            .await
+            .unwrap();
+        
+        let first = engine
+            .get_violation_settlements(violations[0], &Caller::User(user_id))
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].settlement.id, proposal.settlement.id);
+        assert_eq!(first[0].violation_use, ViolationUse::Counted);
+        let factor = proposal
+            .leverage_analysis
+            .explanation
+            .factors
+            .iter()
+            .find(|factor| factor.violation_id == Some(violations[0]))
+            .unwrap();
+        assert_eq!(first[0].contribution, factor.contribution);
+        let repeat = engine
+            .get_violation_settlements(violations[1], &Caller::Creditor(creditor_id))
+            .await
+            .unwrap();
+        assert_eq!(repeat[0].violation_use, ViolationUse::Repeat);
+        assert_eq!(repeat[0].contribution, 0.0);
+        let admin = Caller::Admin("admin:alice".to_string());
+        assert!(engine.get_violation_settlements(violations[2], &admin).await.unwrap().is_empty());
+        
+        assert!(engine
+            .get_violation_settlements(violations[0], &Caller::User(Uuid::new_v4()))
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            engine.get_violation_settlements(violations[0], &Caller::Creditor(Uuid::new_v4())).await,
+            Err(SettlementError::Forbidden(_))
+        ));
+        assert!(matches!(
+            engine.get_violation_settlements(Uuid::new_v4(), &admin).await,
+            Err(SettlementError::NotFound("violation", _))
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn each_deadline_reminder_is_sent_once() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let negotiation = |proposed_hours_ago, expires_in_hours| Settlement {
+            status: SettlementStatus::Negotiating,
+            proposed_at: Utc::now() - Duration::hours(proposed_hours_ago),
+            expires_at: Utc::now() + Duration::hours(expires_in_hours),
+            ..proposed_settlement()
+        };
+        // Past the 72h threshold, and the 24h one since the last sweep.
+        let missed = db.insert_settlement(&negotiation(300, 20)).await.unwrap();
+        // Proposed with less than 72h to go, and not yet within 24h.
+        let short = db.insert_settlement(&negotiation(10, 50)).await.unwrap();
+        let proposed = db
+            .insert_settlement(&Settlement {
+                status: SettlementStatus::Proposed,
+                ..negotiation(300, 20)
+            })
+            .await
+            .unwrap();
+        let reminders = |settlement_id| {
+            let engine = engine.clone();
+            async move {
+                engine
+                    .get_settlement_events(settlement_id)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .filter(|event| event.event_type == AuditEventType::DeadlineReminder)
+                    .map(|event| event.metadata["threshold_hours"].clone())
+                    .collect::<Vec<_>>()
+            }
+        };
+        
+        engine.send_deadline_reminders().await.unwrap();
+        engine.send_deadline_reminders().await.unwrap();
+        
+        assert_eq!(reminders(missed.id).await, [json!(24)]);
+        assert!(reminders(short.id).await.is_empty());
+        assert!(reminders(proposed.id).await.is_empty());
+        assert!(!db.claim_reminder(missed.id, 24).await.unwrap());
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn creditor_counters_within_the_threshold_are_accepted_for_the_user() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let settlement = db
+            .insert_settlement(&Settlement { auto_accept_below: Some(dec("750")), ..proposed_settlement() })
+            .await
+            .unwrap();
+        let counter = |amount: &str, from, auto_accept_below: Option<&str>| CounterOffer {
+            amount: dec(amount),
+            from,
+            currency: None,
+            locale: Locale::default(),
+            auto_accept_below: auto_accept_below.map(dec),
+        };
+        
+        assert!(matches!(
+            engine.counter_offer(settlement.id, &counter("800", Party::Creditor, Some("700"))).await,
+            Err(SettlementError::Validation(_))
+        ));
+        assert!(matches!(
+            engine.counter_offer(settlement.id, &counter("650", Party::Debtor, Some("700.001"))).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        let above = engine.counter_offer(settlement.id, &counter("800", Party::Creditor, None)).await.unwrap();
+        assert!(above.auto_accepted.is_none());
+        
+        // The user lowers the bar; a counter between the two old bars is
+        // then no longer good enough.
+        engine.counter_offer(settlement.id, &counter("650", Party::Debtor, Some("700"))).await.unwrap();
+        let between = engine.counter_offer(settlement.id, &counter("720", Party::Creditor, None)).await.unwrap();
+        assert!(between.auto_accepted.is_none());
+        
+        let within = engine.counter_offer(settlement.id, &counter("700", Party::Creditor, None)).await.unwrap();
+        assert_eq!(within.decision.action, NegotiationAction::Accept);
+        let accepted = within.auto_accepted.expect("the counter is within the threshold");
+        assert_eq!(accepted.settled_amount, dec("700.00"));
+        assert!(accepted.transaction_hash.is_some());
+        
+        let events = db.get_settlement_events(settlement.id).await.unwrap();
+        let auto_accepted = events
+            .iter()
+            .find(|event| event.event_type == AuditEventType::AutoAccepted)
+            .expect("the auto-acceptance is audited");
+        assert_eq!(auto_accepted.actor, SYSTEM_ACTOR);
+        assert_eq!(auto_accepted.metadata["auto_accept_below"], json!("700"));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn deliveries_out_of_attempts_are_dead_lettered_until_redelivered() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        // Down for the first two requests, up from then on.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let served = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while let Ok(n @ 1..) = stream.read(&mut buf).await {
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+                let status = if served.fetch_add(1, Ordering::SeqCst) < 2 { "503 Service Unavailable" } else { "200 OK" };
+                let _ = stream
+                    .write_all(format!("HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status).as_bytes())
+                    .await;
+            }
+        });
+        
+        let db = test_database().await;
+        let mut engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        engine.webhooks = engine.webhooks.clone().with_retries(2, StdDuration::ZERO);
+        let settlement = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let creditor_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(settlement.debt_id)
+        .bind(settlement.user_id)
+        .bind(creditor_id)
+        .bind(&settlement.original_amount)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let webhook = db
+            .insert_webhook(&Webhook {
+                id: Uuid::new_v4(),
+                creditor_id,
+                url: url.clone(),
+                secret: WebhookDispatcher::generate_secret(),
+                events: vec![SettlementStatus::Accepted],
+                active: true,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        
+        engine
+            .accept_settlement(&AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: None,
+                envelope_id: None,
+                debtor_id: None,
+                accepted_amount: None,
+            })
+            .await
+            .unwrap();
+        let open = ListDeadLettersQuery { include_redelivered: false, limit: Some(MAX_PAGE_SIZE) };
+        let mut letter = None;
+        for _ in 0..100 {
+            letter = engine
+                .webhook_dead_letters(&open)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|letter| letter.settlement_id == settlement.id);
+            if letter.is_some() {
+                break;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+        let letter = letter.expect("the delivery is dead-lettered after its last attempt");
+        assert_eq!(letter.webhook_id, webhook.id);
+        assert_eq!(letter.url, url);
+        assert_eq!(letter.attempts, 2);
+        assert_eq!(letter.payload["event"], "settlement.status_changed");
+        assert!(letter.last_error.contains("503"), "{}", letter.last_error);
+        
+        let redelivered = engine.redeliver_webhook_dead_letter(letter.id, "ops").await.unwrap();
+        assert!(redelivered.redelivered_at.is_some());
+        assert_eq!(redelivered.attempts, 3);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(matches!(
+            engine.redeliver_webhook_dead_letter(letter.id, "ops").await,
+            Err(SettlementError::Conflict(_))
+        ));
+        assert!(engine
+            .webhook_dead_letters(&open)
+            .await
+            .unwrap()
+            .iter()
+            .all(|letter| letter.settlement_id != settlement.id));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn leaderboards_rank_creditors_and_refresh_on_new_snapshots() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let user_id = Uuid::new_v4();
+        let scored = |score: f64| LeverageAnalysis {
+            total_leverage_score: score,
+            ..prediction_request().leverage_analysis
+        };
+        let (weak, strong) = (Uuid::new_v4(), Uuid::new_v4());
+        for (creditor_id, score) in [(weak, 20.0), (strong, 70.0)] {
+            let settlement = db.insert_settlement(&Settlement { user_id, ..proposed_settlement() }).await.unwrap();
+            db.insert_leverage_snapshot(creditor_id, Some(settlement.id), &scored(score)).await.unwrap();
+        }
+        
+        let leaderboard = engine.leverage_leaderboard(user_id).await.unwrap();
+        let ranked: Vec<Uuid> = leaderboard.creditors.iter().map(|entry| entry.creditor_id).collect();
+        assert_eq!(ranked, vec![strong, weak]);
+        assert_eq!(leaderboard.creditors[0].total_leverage_score, 70.0);
+        
+        // Written behind the engine's back, so the cached ranking stands.
+        let settlement = db.insert_settlement(&Settlement { user_id, ..proposed_settlement() }).await.unwrap();
+        db.insert_leverage_snapshot(weak, Some(settlement.id), &scored(90.0)).await.unwrap();
+        let cached = engine.leverage_leaderboard(user_id).await.unwrap();
+        assert_eq!(cached.cached_at, leaderboard.cached_at);
+        
+        engine.snapshot_leverage(&settlement, weak, &scored(95.0)).await;
+        let refreshed = engine.leverage_leaderboard(user_id).await.unwrap();
+        assert!(refreshed.cached_at > leaderboard.cached_at);
+        assert_eq!(refreshed.creditors[0].creditor_id, weak);
+        assert_eq!(refreshed.creditors[0].total_leverage_score, 95.0);
+        assert_eq!(refreshed.creditors.len(), 2);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn submissions_interrupted_before_recording_are_recovered_at_startup() {
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::never_confirming());
+        let engine = test_engine(db.clone(), chain.clone());
+        let accept = |settlement_id| AcceptSettlementRequest {
+            settlement_id,
+            user_signature: None,
+            envelope_id: None,
+            debtor_id: None,
+            accepted_amount: None,
+        };
+        let sent = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let unsent = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        engine.accept_settlement(&accept(sent.id)).await.unwrap();
+        engine.accept_settlement(&accept(unsent.id)).await.unwrap();
+        
+        // Both crashed after writing their intent; only one got to the node.
+        let sent = db.get_settlement(sent.id).await.unwrap().unwrap();
+        let sent = db.set_submission_intent(sent.id, sent.version, Some(Uuid::new_v4())).await.unwrap().unwrap();
+        let submitted = chain.submit(&sent).await.unwrap();
+        let unsent = db.get_settlement(unsent.id).await.unwrap().unwrap();
+        db.set_submission_intent(unsent.id, unsent.version, Some(Uuid::new_v4())).await.unwrap().unwrap();
+        
+        assert!(engine.recover_submissions().await.unwrap() >= 2);
+        
+        let recovered = db.get_settlement(sent.id).await.unwrap().unwrap();
+        assert_eq!(recovered.transaction_hash, Some(submitted.tx_hash.clone()));
+        assert_eq!(recovered.terms_hash, Some(submitted.terms_hash));
+        let events = db.get_settlement_events(sent.id).await.unwrap();
+        let event = events.last().unwrap();
+        assert_eq!(event.event_type, AuditEventType::SubmissionRecovered);
+        assert_eq!(event.metadata["tx_hash"], submitted.tx_hash);
+        
+        let cleared = db.get_settlement(unsent.id).await.unwrap().unwrap();
+        assert_eq!(cleared.submission_intent, None);
+        assert_eq!(cleared.transaction_hash, None);
+        
+        // Neither is paid again: one executes as already submitted, the
+        // other goes out for the first time.
+        let replayed = engine.execute_settlement(sent.id).await.unwrap();
+        assert_eq!(replayed.transaction_hash, Some(submitted.tx_hash));
+        assert_eq!(chain.submissions(), 1);
+        assert!(engine.execute_settlement(unsent.id).await.unwrap().transaction_hash.is_some());
+        assert_eq!(chain.submissions(), 2);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn violation_imports_keep_good_rows_and_recompute_open_settlements() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let proposal = engine
+            .create_settlement_proposal(&CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations: vec![],
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            })
+            .await
+            .unwrap();
+        
+        let row = |violation_type: &str| {
+            json!({
+                "violation_type": violation_type,
+                "severity": "high",
+                "confidence": 0.9,
+                "legal_reference": "15 U.S.C. 1692e",
+                "estimated_damage": 500.0,
+                "occurred_at": Utc::now() - Duration::days(30),
+            })
+        };
+        let mut unbacked = row("HarassmentCalls");
+        unbacked["evidence"] = json!([
+            { "evidence_type": "CallRecording", "sha256": "abc", "content_type": "audio/mpeg" },
+        ]);
+        let mut unknown = row("HarassmentCalls");
+        unknown["severty"] = json!("high");
+        let request = BulkViolationImport {
+            violations: vec![row("FalseRepresentation"), unbacked, unknown, row("HarassmentCalls")],
+            recompute: Some(ImportRecompute {
+                jurisdiction: "CA".to_string(),
+                locale: Default::default(),
+            }),
+        };
+        
+        let stranger = Caller::User(Uuid::new_v4());
+        assert!(matches!(
+            engine.import_violations(debt_id, &request, &stranger).await,
+            Err(SettlementError::Forbidden(_))
+        ));
+        
+        let import = engine.import_violations(debt_id, &request, &Caller::User(user_id)).await.unwrap();
+        let imported: Vec<Uuid> = import
+            .rows
+            .iter()
+            .filter_map(|row| row.as_ref().ok().map(|imported| imported.violation_id))
+            .collect();
+        assert_eq!(imported.len(), 2);
+        assert!(import.rows[0].is_ok() && import.rows[3].is_ok());
+        assert_eq!(import.rows[1].as_ref().unwrap_err()[0].field, "evidence");
+        assert_eq!(import.rows[2].as_ref().unwrap_err()[0].field, "row");
+        let stored = db.get_violations(&imported).await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored.iter().all(|violation| violation.creditor_id == creditor_id));
+        
+        // Only the debtor backs an imported violation; this store has no
+        // bucket, so their attempt gets as far as presigning.
+        let recording = AttachEvidenceRequest {
+            evidence_type: EvidenceType::CallRecording,
+            sha256: "ab".repeat(32),
+            content_type: "audio/mpeg".to_string(),
+        };
+        for caller in [stranger, Caller::Creditor(creditor_id)] {
+            let refused = engine.attach_evidence(imported[0], &recording, &caller).await.unwrap_err();
+            assert!(matches!(refused, SettlementError::Forbidden(_)), "{}", refused);
+        }
+        let unstored = engine
+            .attach_evidence(imported[0], &recording, &Caller::User(user_id))
+            .await
+            .unwrap_err();
+        assert!(matches!(unstored, SettlementError::EvidenceStore(_)), "{}", unstored);
+        
+        let [(settlement_id, recomputed)] = import.recomputed.as_slice() else {
+            panic!("expected one recomputed settlement, got {}", import.recomputed.len());
+        };
+        assert_eq!(*settlement_id, proposal.settlement.id);
+        let recomputed = recomputed.as_ref().unwrap();
+        assert!(recomputed.settlement.settled_amount < proposal.settlement.settled_amount);
+        assert_eq!(recomputed.leverage_analysis.violation_count, 2);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn the_database_refuses_terms_that_dont_add_up() {
+        let db = test_database().await;
+        let settled_in_full = Settlement {
+            settled_amount: dec("1000"),
+            saved_amount: dec("0"),
+            ..proposed_settlement()
+        };
+        assert!(db.insert_settlement(&settled_in_full).await.is_ok());
+        
+        let overpaid = Settlement {
+            settled_amount: dec("1200"),
+            saved_amount: dec("-200"),
+            ..proposed_settlement()
+        };
+        let error = db.insert_settlement(&overpaid).await.unwrap_err();
+        assert!(error.to_string().contains("settlements_amounts_add_up"), "{}", error);
+        assert!(db.get_settlement(overpaid.id).await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn creditor_emails_negotiate_or_wait_for_review() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        // Stands in for the AI service: reads the reply from a keyword in the
+        // email and fails every other call, which the rules fall back from.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while let Ok(n @ 1..) = stream.read(&mut buf).await {
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+                let text = String::from_utf8_lossy(&request);
+                let reply = if !text.starts_with("POST /api/v1/emails/parse ") {
+                    None
+                } else if text.contains("settle for 700") {
+                    Some(json!({ "intent": "Counter", "amount": "700.00", "confidence": 0.9 }))
+                } else if text.contains("not interested") {
+                    Some(json!({ "intent": "Reject", "confidence": 0.95 }))
+                } else {
+                    Some(json!({ "intent": "Counter", "confidence": 0.4 }))
+                };
+                let response = match reply {
+                    Some(reply) => format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+                         connection: close\r\n\r\n{}",
+                        reply.to_string().len(),
+                        reply
+                    ),
+                    None => {
+                        "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string()
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        let db = test_database().await;
+        let mut engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        engine.ai_client = AiClient::at(PromptTemplates::default(), url);
+        let debt_id = Uuid::new_v4();
+        let creditor_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount) VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(Uuid::new_v4())
+        .bind(creditor_id)
+        .bind(dec("1000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let contact = CreditorContactRequest {
+            email: Some("billing@acme.example".to_string()),
+            mailing_address: None,
+            preferred_channel: ContactChannel::Email,
+        };
+        engine.set_creditor_contact(creditor_id, &contact).await.unwrap();
+        let countered = db.insert_settlement(&Settlement { debt_id, ..proposed_settlement() }).await.unwrap();
+        let declined = db.insert_settlement(&Settlement { debt_id, ..proposed_settlement() }).await.unwrap();
+        let email = |from: &str, settlement: &Settlement, text: &str| InboundEmail {
+            message_id: format!("<{}@acme.example>", Uuid::new_v4()),
+            from: from.to_string(),
+            subject: format!("RE: Settlement offer {}", settlement.reference_number.as_deref().unwrap()),
+            text: text.to_string(),
+        };
+        
+        let counter = email("Acme Billing <Billing@Acme.example>", &countered, "We could settle for 700.");
+        let received = engine.receive_creditor_email(&counter).await.unwrap();
+        assert!(received.created);
+        assert_eq!(received.email.status, CreditorEmailStatus::Processed);
+        assert_eq!(received.email.settlement_id, Some(countered.id));
+        assert_eq!(received.email.intent, Some(ReplyIntent::Counter));
+        let rounds = engine.get_negotiation_rounds(countered.id).await.unwrap();
+        assert_eq!(rounds.len(), 1);
+        assert_eq!((rounds[0].from_party, rounds[0].amount.clone()), (Party::Creditor, dec("700")));
+        
+        let redelivered = engine.receive_creditor_email(&counter).await.unwrap();
+        assert!(!redelivered.created);
+        assert_eq!(redelivered.email.id, received.email.id);
+        assert_eq!(engine.get_negotiation_rounds(countered.id).await.unwrap().len(), 1);
+        
+        let rejected = email("billing@acme.example", &declined, "We are not interested.");
+        let rejected = engine.receive_creditor_email(&rejected).await.unwrap();
+        assert_eq!(rejected.email.status, CreditorEmailStatus::Processed);
+        let declined = db.get_settlement(declined.id).await.unwrap().unwrap();
+        assert_eq!(declined.status, SettlementStatus::Rejected);
+        assert_eq!(declined.rejection_note.as_deref(), Some(EMAIL_REJECTION_NOTE));
+        
+        let stranger = engine
+            .receive_creditor_email(&email("someone@elsewhere.example", &countered, "We could settle for 700."))
+            .await
+            .unwrap();
+        let unsure = engine
+            .receive_creditor_email(&email("billing@acme.example", &countered, "Let us talk."))
+            .await
+            .unwrap();
+        let unknown = engine
+            .receive_creditor_email(&InboundEmail {
+                subject: "RE: DMC-1999-999999".to_string(),
+                ..email("billing@acme.example", &countered, "We could settle for 700.")
+            })
+            .await
+            .unwrap();
+        for (review, reason) in [
+            (&stranger, "is not the creditor's contact address"),
+            (&unsure, "with confidence 0.4"),
+            (&unknown, "DMC-1999-999999 not found"),
+        ] {
+            assert_eq!(review.email.status, CreditorEmailStatus::NeedsReview);
+            let review_reason = review.email.review_reason.as_deref().unwrap();
+            assert!(review_reason.contains(reason), "{}", review_reason);
+        }
+        assert_eq!(engine.get_negotiation_rounds(countered.id).await.unwrap().len(), 1);
+        
+        let query = ListCreditorEmailsQuery { status: None, limit: Some(MAX_PAGE_SIZE) };
+        let waiting: Vec<Uuid> = engine.creditor_emails(&query).await.unwrap().iter().map(|email| email.id).collect();
+        assert!(waiting.contains(&stranger.email.id) && waiting.contains(&unknown.email.id));
+        assert!(!waiting.contains(&received.email.id));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn escrowed_payments_wait_for_release_or_the_refund_deadline() {
+        async fn escrowed(engine: &SettlementEngine, db: &Database, creditor_id: Uuid) -> Settlement {
+            let settlement = db
+                .insert_settlement(&Settlement {
+                    execution_mode: ExecutionMode::Escrow,
+                    ..proposed_settlement()
+                })
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+                 VALUES ($1, $2, $3, $4, $4)",
+            )
+            .bind(settlement.debt_id)
+            .bind(settlement.user_id)
+            .bind(creditor_id)
+            .bind(&settlement.original_amount)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            engine
+                .accept_settlement(&AcceptSettlementRequest {
+                    settlement_id: settlement.id,
+                    user_signature: None,
+                    envelope_id: None,
+                    debtor_id: None,
+                    accepted_amount: None,
+                })
+                .await
+                .unwrap();
+            
+            let executed = engine.execute_settlement(settlement.id).await.unwrap();
+            let refund_after = executed.escrow_refund_after.unwrap();
+            assert!(refund_after > Utc::now() + Duration::hours(719));
+            for _ in 0..50 {
+                let current = db.get_settlement(settlement.id).await.unwrap().unwrap();
+                if current.status == SettlementStatus::Escrowed {
+                    return current;
+                }
+                tokio::time::sleep(StdDuration::from_millis(20)).await;
+            }
+            panic!("settlement {} was never escrowed", settlement.id);
+        }
+        
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(1));
+        let engine = test_engine(db.clone(), chain.clone());
+        let creditor_id = Uuid::new_v4();
+        let mode = CreditorExecutionMode {
+            execution_mode: ExecutionMode::Escrow,
+        };
+        engine.set_execution_mode(creditor_id, &mode).await.unwrap();
+        assert_eq!(db.get_execution_mode(creditor_id).await.unwrap(), Some(ExecutionMode::Escrow));
+        
+        let released = escrowed(&engine, &db, creditor_id).await;
+        assert!(released.transaction_hash.as_deref().unwrap().starts_with("mockescrow"));
+        let user = Caller::User(released.user_id);
+        let refused = engine.release_escrow(released.id, &user).await.unwrap_err();
+        assert!(matches!(refused, SettlementError::Forbidden(_)), "{}", refused);
+        let early = engine.refund_escrow(released.id, &user).await.unwrap_err();
+        assert!(matches!(early, SettlementError::Conflict(_)), "{}", early);
+        
+        let completed = engine.release_escrow(released.id, &Caller::Creditor(creditor_id)).await.unwrap();
+        assert_eq!(completed.status, SettlementStatus::Completed);
+        let events = db.get_settlement_events(released.id).await.unwrap();
+        let release = events
+            .iter()
+            .find(|event| event.event_type == AuditEventType::EscrowReleased)
+            .unwrap();
+        assert_eq!(release.actor, creditor_actor(creditor_id));
+        assert!(events.iter().any(|event| event.event_type == AuditEventType::Escrowed));
+        
+        // Once the deadline has passed, the debtor can take the payment back.
+        let lapsed = escrowed(&engine, &db, creditor_id).await;
+        sqlx::query("UPDATE settlements SET escrow_refund_after = NOW() - INTERVAL '1 hour' WHERE id = $1")
+            .bind(lapsed.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let refunded = engine.refund_escrow(lapsed.id, &Caller::User(lapsed.user_id)).await.unwrap();
+        assert_eq!(refunded.status, SettlementStatus::Refunded);
+        let late = engine.release_escrow(lapsed.id, &Caller::Creditor(creditor_id)).await.unwrap_err();
+        assert!(matches!(late, SettlementError::InvalidStatus { .. }), "{}", late);
+        assert_eq!(chain.submissions(), 2);
+        
+        // Support can only escrow a settlement with no lock by forcing it,
+        // and refunding that is refused rather than guessed at.
+        let unlocked = db.insert_settlement(&proposed_settlement()).await.unwrap();
+        let force = |force_unsafe| ForceStatusRequest {
+            status: SettlementStatus::Escrowed,
+            reason: "creditor asked to hold the payment".to_string(),
+            force_unsafe,
+        };
+        let refused = engine.force_status(unlocked.id, &force(false), "admin:alice").await.unwrap_err();
+        assert!(matches!(refused, SettlementError::Conflict(_)), "{}", refused);
+        engine.force_status(unlocked.id, &force(true), "admin:alice").await.unwrap();
+        let refund = engine.refund_escrow(unlocked.id, &Caller::User(unlocked.user_id)).await.unwrap_err();
+        assert!(matches!(refund, SettlementError::Conflict(_)), "{}", refund);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn leverage_counts_only_the_statutes_asked_for() {
+        let db = test_database().await;
+        let mut engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        engine.ai_client = AiClient::simulated(PromptTemplates::default(), SimulationConfig { seed: 7 });
+        let creditor_id = Uuid::new_v4();
+        let mut violations = Vec::new();
+        for (violation_type, statute) in [
+            ("FalseRepresentation", "fdcpa"),
+            ("Robocall", "tcpa"),
+            ("InaccurateReporting", "fcra"),
+        ] {
+            let id = Uuid::new_v4();
+            sqlx::query(
+                "INSERT INTO violations
+                     (id, creditor_id, type, severity, confidence, legal_reference, estimated_damage, statute)
+                 VALUES ($1, $2, $3, 'high', 0.9, '15 U.S.C. 1692e', 500, $4::violation_statute)",
+            )
+            .bind(id)
+            .bind(creditor_id)
+            .bind(violation_type)
+            .bind(statute)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            violations.push(id);
+        }
+        let score = |statutes: Option<Vec<Statute>>| {
+            let request = LeverageRequest {
+                creditor_id,
+                violations: violations.clone(),
+                jurisdiction: "CA".to_string(),
+                statutes,
+                ai_mode: None,
+            };
+            let engine = &engine;
+            async move { engine.calculate_leverage(&request).await.unwrap() }
+        };
+        
+        let all = score(None).await;
+        assert_eq!(all.violation_count, 3);
+        assert!(all.excluded_violations.is_empty());
+        assert_eq!(score(Some(Vec::new())).await.total_leverage_score, all.total_leverage_score);
+        
+        let tcpa = score(Some(vec![Statute::Tcpa])).await;
+        assert_eq!(tcpa.violation_count, 1);
+        assert_eq!(tcpa.excluded_violations, [violations[0], violations[2]]);
+        assert!(tcpa.total_leverage_score < all.total_leverage_score);
+        assert_eq!(tcpa.legal_strength, crate::services::leverage::legal_strength(tcpa.total_leverage_score));
+        let statutes: Vec<_> = tcpa.exposure_by_statute.iter().map(|exposure| exposure.statute).collect();
+        assert_eq!(statutes, [Statute::Tcpa]);
+        
+        let narrower = score(Some(vec![Statute::Fdcpa, Statute::Fcra])).await;
+        assert_eq!(narrower.violation_count, 2);
+        assert_eq!(narrower.excluded_violations, [violations[1]]);
+        let together = narrower.total_leverage_score + tcpa.total_leverage_score;
+        assert!((together - all.total_leverage_score).abs() < 1e-9);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn registering_a_webhook_again_returns_the_one_registered() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        // Echoes every challenge, counting them.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let challenges = Arc::new(AtomicUsize::new(0));
+        let counted = challenges.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let challenge = loop {
+                    match stream.read(&mut buf).await {
+                        Ok(n @ 1..) => request.extend_from_slice(&buf[..n]),
+                        _ => break None,
+                    }
+                    let text = String::from_utf8_lossy(&request);
+                    let body = text.split_once("\r\n\r\n").map(|(_, body)| body);
+                    if let Some(body) = body.and_then(|body| serde_json::from_str::<serde_json::Value>(body).ok()) {
+                        break body["challenge"].as_str().map(str::to_string);
+                    }
+                };
+                counted.fetch_add(1, Ordering::SeqCst);
+                let reply = json!({ "challenge": challenge }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+                     connection: close\r\n\r\n{}",
+                    reply.len(),
+                    reply
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(MockBlockchainClient::new(1)));
+        let creditor_id = Uuid::new_v4();
+        let register = |url: &str, events: &[SettlementStatus]| RegisterWebhookRequest {
+            creditor_id,
+            url: url.to_string(),
+            events: events.to_vec(),
+        };
+        let hook = format!("{}/hook", base);
+        let listed = ListWebhooksQuery { creditor_id };
+        
+        let first = engine.register_webhook(&register(&hook, &[SettlementStatus::Accepted])).await.unwrap();
+        assert!(first.created);
+        assert!(first.secret.is_some());
+        assert_eq!(challenges.load(Ordering::SeqCst), 1);
+        
+        // The same registration again is the same webhook, unchallenged and
+        // without its secret.
+        let again = engine.register_webhook(&register(&hook, &[SettlementStatus::Accepted])).await.unwrap();
+        assert!(!again.created);
+        assert_eq!(again.webhook.id, first.webhook.id);
+        assert!(again.secret.is_none());
+        assert_eq!(challenges.load(Ordering::SeqCst), 1);
+        
+        // New events for the URL join the existing webhook.
+        let more = engine
+            .register_webhook(&register(&hook, &[SettlementStatus::Completed, SettlementStatus::Accepted]))
+            .await
+            .unwrap();
+        assert!(!more.created);
+        assert_eq!(more.webhook.id, first.webhook.id);
+        assert_eq!(more.webhook.secret, first.webhook.secret);
+        assert_eq!(more.webhook.events, vec![SettlementStatus::Accepted, SettlementStatus::Completed]);
+        assert_eq!(challenges.load(Ordering::SeqCst), 1);
+        assert_eq!(engine.list_webhooks(&listed).await.unwrap().len(), 1);
+        
+        let other = engine
+            .register_webhook(&register(&format!("{}/other", base), &[SettlementStatus::Accepted]))
+            .await
+            .unwrap();
+        assert!(other.created);
+        assert_eq!(challenges.load(Ordering::SeqCst), 2);
+        let ids: Vec<Uuid> = engine.list_webhooks(&listed).await.unwrap().iter().map(|w| w.id).collect();
+        assert_eq!(ids, vec![first.webhook.id, other.webhook.id]);
+        
+        // Once deleted, the URL is a new registration, challenged again.
+        let deleted = engine.delete_webhook(first.webhook.id).await.unwrap();
+        assert!(!deleted.active);
+        let ids: Vec<Uuid> = engine.list_webhooks(&listed).await.unwrap().iter().map(|w| w.id).collect();
+        assert_eq!(ids, vec![other.webhook.id]);
+        assert!(db.get_subscribed_webhooks(creditor_id, SettlementStatus::Completed).await.unwrap().is_empty());
+        
+        let renewed = engine.register_webhook(&register(&hook, &[SettlementStatus::Accepted])).await.unwrap();
+        assert!(renewed.created);
+        assert_ne!(renewed.webhook.id, first.webhook.id);
+        assert_eq!(challenges.load(Ordering::SeqCst), 3);
+        
+        assert!(matches!(
+            engine.delete_webhook(Uuid::new_v4()).await,
+            Err(SettlementError::NotFound("webhook", _))
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn drafts_become_one_proposal_once_complete() {
+        use crate::handlers::{json, ApiError};
+        
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("1000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let fields = |value: serde_json::Value| value.as_object().unwrap().clone();
+        
+        let draft = engine
+            .create_draft(&CreateDraftRequest {
+                user_id,
+                kind: DraftKind::Settlement,
+                fields: fields(json!({ "creditor_id": creditor_id, "debt_id": "not yet" })),
+            })
+            .await
+            .unwrap();
+        assert_eq!(engine.list_drafts(&ListDraftsQuery { user_id }).await.unwrap().len(), 1);
+        
+        // Unfinished, the draft fails as the same request posted would.
+        let error = json::parse::<CreateSettlementRequest>(&draft.request_body()).unwrap_err();
+        assert!(
+            matches!(&error, ApiError::Validation(message) if message.starts_with("invalid request body at `debt_id`")),
+            "{:?}",
+            error
+        );
+        
+        let save = |value| UpdateDraftRequest { fields: fields(value) };
+        let cleared = save(json!({ "debt_id": null, "jurisdiction": "CA", "violations": [] }));
+        engine.update_draft(draft.id, &cleared).await.unwrap();
+        let draft = engine.get_draft(draft.id).await.unwrap();
+        let request = json::parse::<CreateSettlementRequest>(&draft.request_body()).unwrap();
+        let errors = request.validate().unwrap_err();
+        assert_eq!(errors[0].field, "violations");
+        
+        let draft = engine.update_draft(draft.id, &save(json!({ "debt_id": debt_id }))).await.unwrap();
+        assert_eq!(draft.version, 2);
+        assert_eq!(
+            draft.fields,
+            json!({ "creditor_id": creditor_id, "debt_id": debt_id, "jurisdiction": "CA", "violations": [] })
+        );
+        
+        // A save landing after the draft was read is not what gets proposed.
+        let request = json::parse::<CreateSettlementRequest>(&draft.request_body()).unwrap();
+        request.validate().unwrap();
+        let stale = draft.clone();
+        engine.update_draft(draft.id, &save(json!({ "strategy": "Aggressive" }))).await.unwrap();
+        assert!(matches!(
+            engine.finalize_settlement_draft(&stale, &request).await,
+            Err(SettlementError::Conflict(_))
+        ));
+        
+        let draft = engine.get_draft(draft.id).await.unwrap();
+        let request = json::parse::<CreateSettlementRequest>(&draft.request_body()).unwrap();
+        let proposal = engine.finalize_settlement_draft(&draft, &request).await.unwrap();
+        assert_eq!(proposal.settlement.debt_id, debt_id);
+        assert_eq!(proposal.settlement.user_id, user_id);
+        
+        let finalized = engine.get_draft(draft.id).await.unwrap();
+        assert_eq!(finalized.settlement_id, Some(proposal.settlement.id));
+        assert!(engine.list_drafts(&ListDraftsQuery { user_id }).await.unwrap().is_empty());
+        assert!(matches!(
+            engine.finalize_settlement_draft(&finalized, &request).await,
+            Err(SettlementError::Conflict(_))
+        ));
+        assert!(matches!(
+            engine.update_draft(draft.id, &save(json!({ "jurisdiction": "NY" }))).await,
+            Err(SettlementError::Conflict(_))
+        ));
+        
+        // Expired drafts are gone, and the sweep deletes them.
+        sqlx::query("UPDATE settlement_drafts SET expires_at = NOW() - INTERVAL '1 hour' WHERE id = $1")
+            .bind(draft.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.get_draft(draft.id).await,
+            Err(SettlementError::NotFound("draft", _))
+        ));
+        assert!(db.delete_expired_drafts(Utc::now()).await.unwrap() >= 1);
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn joint_debts_wait_for_every_debtor_to_accept() {
+        use ed25519_dalek::{Signer, SigningKey};
+        
+        let db = test_database().await;
+        let chain = Arc::new(MockBlockchainClient::new(1_000));
+        let engine = test_engine(db.clone(), chain.clone());
+        let (debt_id, user_id, spouse_id, creditor_id) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, debtors, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $5, $5)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(vec![user_id, spouse_id])
+        .bind(creditor_id)
+        .bind(dec("1000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let keys = [user_id, spouse_id].map(|debtor_id| (debtor_id, SigningKey::from_bytes(&rand::random())));
+        for (debtor_id, key) in &keys {
+            sqlx::query("INSERT INTO user_signing_keys (user_id, public_key) VALUES ($1, $2)")
+                .bind(debtor_id)
+                .bind(hex::encode(key.verifying_key().to_bytes()))
+                .execute(db.pool())
+                .await
+                .unwrap();
+        }
+        let settlement = db
+            .insert_settlement(&Settlement { user_id, debt_id, ..proposed_settlement() })
+            .await
+            .unwrap();
+        let countered_terms = engine.accepted_terms(&settlement, &dec("550")).await.unwrap();
+        let accept = |debtor_id: Uuid, amount: Option<&str>| {
+            let terms = if amount.is_some() { &countered_terms } else { &settlement };
+            let signature = keys.iter().find(|(id, _)| *id == debtor_id).map(|(_, key)| {
+                hex::encode(key.sign(acceptance_terms(terms, creditor_id, None).as_bytes()).to_bytes())
+            });
+            AcceptSettlementRequest {
+                settlement_id: settlement.id,
+                user_signature: signature,
+                envelope_id: None,
+                debtor_id: Some(debtor_id),
+                accepted_amount: amount.map(dec),
+            }
+        };
+        
+        assert!(engine.authorize_settlement(settlement.id, &Caller::User(spouse_id)).await.is_ok());
+        let stranger = Uuid::new_v4();
+        assert!(matches!(
+            engine.authorize_settlement(settlement.id, &Caller::User(stranger)).await,
+            Err(SettlementError::Forbidden(_))
+        ));
+        assert!(matches!(
+            engine.accept_settlement(&accept(stranger, None)).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        // Each debtor signs, and signs for themselves alone.
+        let unsigned = AcceptSettlementRequest {
+            user_signature: None,
+            ..accept(user_id, None)
+        };
+        assert!(matches!(
+            engine.accept_settlement(&unsigned).await,
+            Err(SettlementError::SignatureRequired(_))
+        ));
+        let requested = engine
+            .request_signature(
+                settlement.id,
+                &SignatureRequest {
+                    signer_name: "Test User".to_string(),
+                    signer_email: "user@example.com".to_string(),
+                    return_url: "https://app.example.com/signed".to_string(),
+                    debtor_id: Some(user_id),
+                },
+            )
+            .await
+            .unwrap();
+        let callback = serde_json::to_vec(&json!({
+            "envelope_id": requested.envelope.envelope_id,
+            "status": EnvelopeStatus::Completed,
+        }))
+        .unwrap();
+        engine
+            .handle_signature_callback(settlement.id, &callback, MOCK_CALLBACK_SIGNATURE)
+            .await
+            .unwrap();
+        let borrowed = AcceptSettlementRequest {
+            user_signature: None,
+            envelope_id: Some(requested.envelope.envelope_id.clone()),
+            ..accept(spouse_id, None)
+        };
+        assert!(matches!(
+            engine.accept_settlement(&borrowed).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        // One debtor's acceptance leaves the settlement open, and unpaid.
+        let waiting = engine.accept_settlement(&accept(user_id, None)).await.unwrap();
+        assert_eq!(waiting.status, SettlementStatus::Proposed);
+        let acceptances = engine.settlement_acceptances(&waiting).await.unwrap();
+        assert!(!acceptances.complete);
+        assert_eq!(acceptances.debtors[0].debtor_id, user_id);
+        assert_eq!(acceptances.pending(), [spouse_id]);
+        assert!(matches!(
+            engine.execute_settlement(settlement.id).await,
+            Err(SettlementError::InvalidStatus { .. })
+        ));
+        
+        // Accepting at another amount is no acceptance of the first terms,
+        // nor they of it.
+        let countered = engine.accept_settlement(&accept(spouse_id, Some("550"))).await.unwrap();
+        assert_eq!(countered.status, SettlementStatus::Proposed);
+        assert_eq!(engine.settlement_acceptances(&countered).await.unwrap().pending(), [spouse_id]);
+        
+        let accepted = engine.accept_settlement(&accept(user_id, Some("550"))).await.unwrap();
+        assert_eq!(accepted.status, SettlementStatus::Accepted);
+        assert_eq!(accepted.settled_amount, dec("550"));
+        assert!(engine.settlement_acceptances(&accepted).await.unwrap().complete);
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        let waited = events
+            .iter()
+            .filter(|event| event.event_type == AuditEventType::DebtorAccepted)
+            .count();
+        assert_eq!(waited, 2);
+        
+        // A debtor added since holds the payment up.
+        let added = Uuid::new_v4();
+        sqlx::query("UPDATE debts SET debtors = debtors || $2 WHERE id = $1")
+            .bind(debt_id)
+            .bind(added)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let refused = engine.execute_settlement(settlement.id).await;
+        assert!(
+            matches!(&refused, Err(SettlementError::Conflict(message)) if message.contains(&added.to_string())),
+            "{:?}",
+            refused
+        );
+        assert_eq!(chain.submissions(), 0);
+        
+        // Once rescinded, everyone accepts again.
+        let support = Caller::Admin("admin:alice".to_string());
+        let rescinded = engine.rescind_settlement(settlement.id, &support).await.unwrap();
+        assert_eq!(engine.settlement_acceptances(&rescinded).await.unwrap().pending().len(), 3);
+        let events = engine.get_settlement_events(settlement.id).await.unwrap();
+        let rescission = events.iter().find(|event| event.event_type == AuditEventType::Rescinded).unwrap();
+        assert_eq!(rescission.actor, "admin:alice");
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn demand_letters_cite_what_the_proposal_counted() {
+        let db = test_database().await;
+        let engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("10000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let mut violations = Vec::new();
+        for (violation_type, statute, legal_reference) in [
+            ("FalseRepresentation", Statute::Fdcpa, "15 U.S.C. 1692e"),
+            ("Robocall", Statute::Tcpa, "47 U.S.C. 227(b)"),
+        ] {
+            let id = Uuid::new_v4();
+            sqlx::query(
+                "INSERT INTO violations
+                     (id, creditor_id, type, statute, severity, confidence, legal_reference, estimated_damage)
+                 VALUES ($1, $2, $3, $4, 'high', 0.9, $5, 500)",
+            )
+            .bind(id)
+            .bind(creditor_id)
+            .bind(violation_type)
+            .bind(statute)
+            .bind(legal_reference)
+            .execute(db.pool())
+            .await
+            .unwrap();
+            violations.push(id);
+        }
+        let proposal = engine
+            .create_settlement_proposal(&CreateSettlementRequest {
+                user_id,
+                creditor_id,
+                debt_id: Some(debt_id),
+                violations,
+                jurisdiction: "CA".to_string(),
+                strategy: NegotiationStrategy::default(),
+                locale: Default::default(),
+                fee_policy: None,
+                auto_accept_below: None,
+                ai_mode: None,
+            })
+            .await
+            .unwrap();
+        let settlement = proposal.settlement;
+        
+        let letter = engine.demand_letter(&settlement, Some(14)).await.unwrap();
+        let respond_by = (Utc::now() + Duration::days(14)).date_naive();
+        for expected in [
+            format!("Reference: {}", settlement.reference_number.as_deref().unwrap()),
+            format!("To: Creditor {}", creditor_id),
+            "FalseRepresentation, contrary to the Fair Debt Collection Practices Act (15 U.S.C. 1692e)".to_string(),
+            "Robocall, contrary to the Telephone Consumer Protection Act (47 U.S.C. 227(b))".to_string(),
+            format!("settle the debt in full for {} USD", settlement.settled_amount.with_scale(2)),
+            format!("answer in writing by {}", respond_by),
+        ] {
+            assert!(letter.contains(&expected), "missing {:?} in:\n{}", expected, letter);
+        }
+        assert!(engine
+            .demand_letter_pdf(&settlement, None)
+            .await
+            .unwrap()
+            .starts_with(b"%PDF"));
+        assert!(matches!(
+            engine.demand_letter(&settlement, Some(0)).await,
+            Err(SettlementError::Validation(_))
+        ));
+        
+        let rejected = engine
+            .reject_settlement(settlement.id, RejectReason::AmountTooHigh, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.demand_letter(&rejected, None).await,
+            Err(SettlementError::InvalidStatus { action: "write a demand letter for", .. })
+        ));
+    }
+    
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn disabling_ai_proposes_by_the_rules_alone() {
+        let db = test_database().await;
+        let mut engine = test_engine(db.clone(), Arc::new(CardanoClient::new("http://127.0.0.1:9")));
+        // Any call to the unreachable AI service would now fail the request.
+        engine.ai_fallback_enabled = false;
+        let (debt_id, user_id, creditor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO debts (id, user_id, creditor_id, original_amount, current_amount)
+             VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .bind(creditor_id)
+        .bind(dec("5000"))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let violation_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO violations (id, creditor_id, type, severity, confidence, legal_reference, estimated_damage)
+             VALUES ($1, $2, 'FalseRepresentation', 'high', 0.9, '15 U.S.C. 1692e', 500)",
+        )
+        .bind(violation_id)
+        .bind(creditor_id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let request = CreateSettlementRequest {
+            user_id,
+            creditor_id,
+            debt_id: Some(debt_id),
+            violations: vec![violation_id],
+            jurisdiction: "CA".to_string(),
+            strategy: NegotiationStrategy::default(),
+            locale: Default::default(),
+            fee_policy: None,
+            auto_accept_below: None,
+            ai_mode: Some(AiMode::Disabled),
+        };
+        
+        let proposal = engine.create_settlement_proposal(&request).await.unwrap();
+        assert_eq!(proposal.source, ProposalSource::RulesOnly);
+        assert_eq!(proposal.model_version, ai_client::RULES_ONLY_MODEL_VERSION);
+        assert_eq!(proposal.confidence_score, None);
+        assert!(proposal.settlement.settled_amount < dec("5000"));
+        let settlement_id = proposal.settlement.id;
+        let events = engine.get_settlement_events(settlement_id).await.unwrap();
+        let created = events.iter().find(|e| e.event_type == AuditEventType::Created).unwrap();
+        assert_eq!(created.metadata["ai_mode"], "Disabled");
+        
+        // Recomputing a rules-only proposal leaves the AI out again.
+        let recompute = RecomputeSettlementRequest {
+            violations: vec![violation_id],
+            jurisdiction: "CA".to_string(),
+            locale: Default::default(),
+        };
+        engine.recompute_settlement(settlement_id, &recompute).await.unwrap();
+        let events = engine.get_settlement_events(settlement_id).await.unwrap();
+        let recomputed = events.iter().find(|e| e.event_type == AuditEventType::Recomputed).unwrap();
+        assert_eq!(recomputed.metadata["ai_mode"], "Disabled");
+        assert_eq!(recomputed.metadata["source"], "RulesOnly");
+        
+        let leverage = LeverageRequest {
+            creditor_id,
+            violations: vec![violation_id],
+            jurisdiction: "CA".to_string(),
+            statutes: None,
+            ai_mode: Some(AiMode::Disabled),
+        };
+        let analysis = engine.calculate_leverage(&leverage).await.unwrap();
+        assert_eq!(analysis.violation_count, 1);
+        assert!(!analysis.explanation.factors.iter().any(|factor| factor.name == "ai_refinement"));
+        
+        // The service's AI_MODE can't be overridden back on.
+        engine.ai_mode = AiMode::Disabled;
+        let leverage = LeverageRequest { ai_mode: None, ..leverage };
+        assert_eq!(engine.calculate_leverage(&leverage).await.unwrap().violation_count, 1);
+        assert!(matches!(
+            engine
+                .create_settlement_proposal(&CreateSettlementRequest { ai_mode: Some(AiMode::Enabled), ..request })
+                .await,
+            Err(SettlementError::Validation(_))
+        ));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,167 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{watch, Notify};
+
+/// How long abandoned work gets to record where it stopped.
+const ABANDON_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Running,
+    /// No new background work starts; what is running finishes.
+    Draining,
+    /// The drain deadline passed; running work stops where it stands.
+    Abandoning,
+}
+
+struct InFlight {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+/// Coordinates a graceful shutdown of the work that outlives requests:
+/// settlement executions, confirmation watchers, webhook deliveries and the
+/// queue workers. Clones share the same state.
+#[derive(Clone)]
+pub struct Shutdown {
+    phase: Arc<watch::Sender<Phase>>,
+    in_flight: Arc<InFlight>,
+}
+
+/// Keeps the drain waiting until it is dropped.
+pub struct WorkGuard(Arc<InFlight>);
+
+impl Drop for WorkGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            phase: Arc::new(watch::Sender::new(Phase::Running)),
+            in_flight: Arc::new(InFlight {
+                count: AtomicUsize::new(0),
+                idle: Notify::new(),
+            }),
+        }
+    }
+}
+
+impl Shutdown {
+    /// Registers a piece of work the drain waits for.
+    pub fn track(&self) -> WorkGuard {
+        self.in_flight.count.fetch_add(1, Ordering::AcqRel);
+        WorkGuard(Arc::clone(&self.in_flight))
+    }
+    
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.count.load(Ordering::Acquire)
+    }
+    
+    pub fn is_draining(&self) -> bool {
+        *self.phase.borrow() >= Phase::Draining
+    }
+    
+    /// Resolves once shutdown has begun.
+    pub async fn draining(&self) {
+        self.reached(Phase::Draining).await
+    }
+    
+    /// Resolves once the drain deadline has passed and running work should
+    /// stop where it stands.
+    pub async fn abandoned(&self) {
+        self.reached(Phase::Abandoning).await
+    }
+    
+    /// Stops new background work from starting. Idempotent.
+    pub fn begin(&self) {
+        self.advance(Phase::Draining);
+    }
+    
+    /// Begins shutdown if it hasn't already, waits up to `deadline` for
+    /// tracked work to finish and then tells whatever is left to stop.
+    /// Returns how much work was still running at the deadline.
+    pub async fn drain(&self, deadline: Duration) -> usize {
+        self.begin();
+        if tokio::time::timeout(deadline, self.idle()).await.is_ok() {
+            return 0;
+        }
+        
+        let abandoned = self.in_flight();
+        self.advance(Phase::Abandoning);
+        let _ = tokio::time::timeout(ABANDON_GRACE, self.idle()).await;
+        abandoned
+    }
+    
+    fn advance(&self, phase: Phase) {
+        self.phase.send_if_modified(|current| {
+            let advanced = *current < phase;
+            if advanced {
+                *current = phase;
+            }
+            advanced
+        });
+    }
+    
+    async fn reached(&self, phase: Phase) {
+        let mut receiver = self.phase.subscribe();
+        let _ = receiver.wait_for(|current| *current >= phase).await;
+    }
+    
+    async fn idle(&self) {
+        loop {
+            let notified = self.in_flight.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.in_flight() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn drain_waits_for_tracked_work() {
+        let shutdown = Shutdown::default();
+        let guard = shutdown.track();
+        assert!(!shutdown.is_draining());
+        
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+        
+        assert_eq!(shutdown.drain(Duration::from_secs(5)).await, 0);
+        assert!(shutdown.is_draining());
+        assert_eq!(shutdown.in_flight(), 0);
+        release.await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn work_past_the_deadline_is_abandoned() {
+        let shutdown = Shutdown::default();
+        let worker = {
+            let shutdown = shutdown.clone();
+            let guard = shutdown.track();
+            tokio::spawn(async move {
+                let _guard = guard;
+                shutdown.abandoned().await;
+            })
+        };
+        
+        assert_eq!(shutdown.drain(Duration::from_millis(50)).await, 1);
+        worker.await.unwrap();
+        assert_eq!(shutdown.in_flight(), 0);
+    }
+}
@@ -0,0 +1,205 @@
+use bigdecimal::{BigDecimal, FromPrimitive};
+use sha2::{Digest, Sha256};
+
+use crate::i18n::Locale;
+use crate::models::{
+    AcceptanceAssessment, AcceptancePredictionRequest, CounterOffer, CreditorReply, Debt, LeverageAnalysis,
+    NegotiationDecision, NegotiationRound, NegotiationStrategy, OptimalSettlement, ReplyIntent, Settlement,
+    Simulation,
+};
+use crate::money::round_to_minor_units;
+use crate::services::ai_client;
+
+/// `model_version` recorded for proposals made in simulation mode.
+pub const SIMULATION_MODEL_VERSION: &str = "simulation";
+
+/// Stands in for the AI service in tests and demos: every answer is a pure
+/// function of the seed and the inputs, so the same request always yields the
+/// same amount and confidence. Ids and timestamps are not inputs, since tests
+/// create fresh ones every run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    pub seed: u64,
+}
+
+impl SimulationConfig {
+    /// Reads `SETTLEMENT_SIMULATION_SEED`; simulation is off unless it is set
+    /// to an integer.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("SETTLEMENT_SIMULATION_SEED")
+            .ok()
+            .and_then(|seed| seed.trim().parse().ok())
+            .map(|seed| Self { seed })
+    }
+    
+    pub fn info(&self) -> Simulation {
+        Simulation { seed: self.seed }
+    }
+    
+    /// The rules-based analysis as is; there is no model to refine it.
+    pub fn score_leverage(&self, baseline: &LeverageAnalysis) -> LeverageAnalysis {
+        baseline.clone()
+    }
+    
+    /// Reduces the balance by the strategy's target reduction, moved up to
+    /// 10% either way by the seed, with a confidence between 0.5 and 0.9.
+    pub fn optimal_settlement(
+        &self,
+        debt: &Debt,
+        leverage: &LeverageAnalysis,
+        strategy: NegotiationStrategy,
+        locale: &Locale,
+    ) -> OptimalSettlement {
+        let inputs = format!(
+            "{}|{}|{}|{}|{:?}|{:?}",
+            debt.original_amount,
+            debt.current_amount,
+            debt.currency,
+            leverage.total_leverage_score,
+            leverage.statute_of_limitations,
+            strategy
+        );
+        let target = ai_client::target_reduction_percentage(leverage, strategy);
+        let reduction_percentage =
+            (target * (0.9 + 0.2 * self.unit("reduction", &inputs))).clamp(0.0, 95.0);
+        let reduction = BigDecimal::from_f64(reduction_percentage).unwrap_or_default();
+        let amount = round_to_minor_units(
+            &debt.current_amount * (BigDecimal::from(100) - reduction) / BigDecimal::from(100),
+            &debt.currency,
+        );
+        
+        OptimalSettlement {
+            amount,
+            reduction_percentage,
+            confidence: Some(0.5 + 0.4 * self.unit("confidence", &inputs)),
+            reasoning: vec![locale.message(
+                "simulation.proposal",
+                &[
+                    ("seed", self.seed.to_string()),
+                    ("reduction_percentage", format!("{:.1}", reduction_percentage)),
+                    ("target_percentage", format!("{:.1}", target)),
+                ],
+            )],
+            model_version: SIMULATION_MODEL_VERSION.to_string(),
+            prompt_hash: self.hash("prompt", &inputs)[..16].to_string(),
+            prompt_version: None,
+            currency: Some(debt.currency.clone()),
+            floor_applied: false,
+        }
+    }
+    
+    /// The fallback rules' decision, which depends on nothing but its inputs.
+    pub fn evaluate_counter_offer(
+        &self,
+        settlement: &Settlement,
+        offer: &CounterOffer,
+        history: &[NegotiationRound],
+    ) -> NegotiationDecision {
+        let mut decision = ai_client::counter_fallback(settlement, offer, history);
+        decision.reasoning[0] = offer
+            .locale
+            .message("simulation.decision", &[("seed", self.seed.to_string())]);
+        decision
+    }
+    
+    /// No reading at all: without a model, every creditor email is left for
+    /// someone to read.
+    pub fn parse_creditor_reply(&self) -> CreditorReply {
+        CreditorReply {
+            intent: ReplyIntent::Unclear,
+            amount: None,
+            confidence: 0.0,
+        }
+    }
+    
+    /// The fallback rules' prediction, which depends on nothing but its
+    /// inputs.
+    pub fn predict_acceptance(&self, request: &AcceptancePredictionRequest) -> AcceptanceAssessment {
+        let mut assessment = ai_client::acceptance_fallback(request);
+        assessment.reasoning[0] = request
+            .locale
+            .message("simulation.prediction", &[("seed", self.seed.to_string())]);
+        assessment
+    }
+    
+    fn digest(&self, purpose: &str, inputs: &str) -> [u8; 32] {
+        Sha256::digest(format!("{}|{}|{}", self.seed, purpose, inputs)).into()
+    }
+    
+    fn hash(&self, purpose: &str, inputs: &str) -> String {
+        hex::encode(self.digest(purpose, inputs))
+    }
+    
+    /// A number in `[0, 1)` fixed by the seed, `purpose` and `inputs`.
+    fn unit(&self, purpose: &str, inputs: &str) -> f64 {
+        let digest = self.digest(purpose, inputs);
+        let bits = u64::from_be_bytes(digest[..8].try_into().expect("a SHA-256 digest is 32 bytes"));
+        // The top 53 bits, exactly representable as an f64.
+        (bits >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use uuid::Uuid;
+    
+    use super::*;
+    use crate::models::{LeverageExplanation, SolStatus, VerificationStatus};
+    
+    fn debt() -> Debt {
+        Debt {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            debtors: Vec::new(),
+            creditor_id: Uuid::new_v4(),
+            original_amount: BigDecimal::from(10_000),
+            current_amount: BigDecimal::from(8_000),
+            currency: Default::default(),
+            status: "active".to_string(),
+            last_payment_date: None,
+            verification_status: VerificationStatus::Unverified,
+            created_at: chrono::Utc::now(),
+        }
+    }
+    
+    fn leverage() -> LeverageAnalysis {
+        LeverageAnalysis {
+            violation_count: 3,
+            total_leverage_score: 45.0,
+            estimated_reduction_percentage: 22.5,
+            legal_strength: "strong".to_string(),
+            key_violations: Vec::new(),
+            unsubstantiated_violations: Vec::new(),
+            expired_violations: Vec::new(),
+            excluded_violations: Vec::new(),
+            statute_of_limitations: SolStatus::WithinPeriod,
+            explanation: LeverageExplanation::default(),
+            exposure_by_statute: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn the_same_seed_and_inputs_give_the_same_proposal() {
+        let simulation = SimulationConfig { seed: 42 };
+        // Fresh ids each time, as in the integration tests.
+        let first = simulation.optimal_settlement(&debt(), &leverage(), NegotiationStrategy::Balanced, &Locale::default());
+        let second = simulation.optimal_settlement(&debt(), &leverage(), NegotiationStrategy::Balanced, &Locale::default());
+        
+        assert_eq!(first.amount, second.amount);
+        assert_eq!(first.confidence, second.confidence);
+        assert_eq!(first.prompt_hash, second.prompt_hash);
+        assert!((0.5..0.9).contains(&first.confidence.unwrap()));
+        assert!(first.amount < debt().current_amount);
+        assert_eq!(first.model_version, SIMULATION_MODEL_VERSION);
+    }
+    
+    #[test]
+    fn another_seed_gives_another_proposal() {
+        let proposal = |seed| {
+            SimulationConfig { seed }.optimal_settlement(&debt(), &leverage(), NegotiationStrategy::Balanced, &Locale::default())
+        };
+        
+        assert_ne!(proposal(1).confidence, proposal(2).confidence);
+    }
+}
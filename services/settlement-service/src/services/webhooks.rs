@@ -0,0 +1,344 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{error, info, instrument, warn, Instrument};
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::models::{ContactChannel, DeliveryStatus, Settlement, Webhook, WebhookDeadLetter, WebhookDelivery};
+use crate::services::env_or;
+use crate::services::shutdown::Shutdown;
+
+/// `sha256=<hex HMAC of the raw body>`, keyed with the webhook's secret.
+pub const SIGNATURE_HEADER: &str = "X-Damocles-Signature";
+pub const DELIVERY_HEADER: &str = "X-Damocles-Delivery";
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF_SECS: u64 = 2;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+struct ChallengeResponse {
+    challenge: String,
+}
+
+/// What a delivery tells the creditor about its settlement.
+#[derive(Debug, Clone, Copy)]
+enum WebhookEvent {
+    StatusChanged,
+    /// The negotiation expires within `threshold_hours`.
+    DeadlineApproaching { threshold_hours: i32 },
+}
+
+impl WebhookEvent {
+    fn payload(self, delivery: &WebhookDelivery, settlement: &Settlement) -> serde_json::Value {
+        let mut payload = json!({
+            "delivery_id": delivery.id,
+            "event": "settlement.status_changed",
+            "status": settlement.status,
+            "settlement": settlement,
+            "occurred_at": delivery.created_at,
+        });
+        if let Self::DeadlineApproaching { threshold_hours } = self {
+            payload["event"] = json!("settlement.deadline_approaching");
+            payload["threshold_hours"] = json!(threshold_hours);
+            payload["expires_at"] = json!(settlement.expires_at);
+        }
+        payload
+    }
+}
+
+/// Delivers settlement status changes to creditor webhooks in the background,
+/// retrying with exponential backoff and recording every delivery. One that
+/// fails every attempt becomes a dead letter, for an admin to replay.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    db: Database,
+    http: reqwest::Client,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    shutdown: Shutdown,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: Database, shutdown: Shutdown) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build webhook HTTP client");
+        
+        Self {
+            db,
+            http,
+            max_attempts: env_or("WEBHOOK_MAX_ATTEMPTS", DEFAULT_MAX_ATTEMPTS).max(1),
+            initial_backoff: Duration::from_secs(env_or(
+                "WEBHOOK_INITIAL_BACKOFF_SECS",
+                DEFAULT_INITIAL_BACKOFF_SECS,
+            )),
+            shutdown,
+        }
+    }
+    
+    #[cfg(test)]
+    pub fn with_retries(self, max_attempts: u32, initial_backoff: Duration) -> Self {
+        Self { max_attempts, initial_backoff, ..self }
+    }
+    
+    /// Posts a random challenge to `url` and requires it to be echoed back as
+    /// `{ "challenge": ... }`, proving the receiver is ours to deliver to.
+    pub async fn verify_endpoint(&self, url: &str) -> anyhow::Result<()> {
+        let challenge = random_hex(16);
+        let response = self
+            .http
+            .post(url)
+            .json(&json!({ "type": "url_verification", "challenge": challenge }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ChallengeResponse>()
+            .await
+            .context("challenge response was not valid JSON")?;
+        
+        if response.challenge != challenge {
+            bail!("endpoint echoed the wrong challenge");
+        }
+        Ok(())
+    }
+    
+    pub fn generate_secret() -> String {
+        random_hex(32)
+    }
+    
+    /// Queues delivery of `settlement`'s current status to every subscribed
+    /// webhook of its creditor. Never blocks or fails the caller; deliveries
+    /// run in the caller's span, and a shutdown waits for them.
+    pub fn notify(&self, settlement: Settlement) {
+        self.dispatch(settlement, WebhookEvent::StatusChanged);
+    }
+    
+    /// Queues a reminder that `settlement`'s negotiation expires within
+    /// `threshold_hours` to the webhooks subscribed to its status, as
+    /// [`Self::notify`] does.
+    pub fn deadline_approaching(&self, settlement: Settlement, threshold_hours: i32) {
+        self.dispatch(settlement, WebhookEvent::DeadlineApproaching { threshold_hours });
+    }
+    
+    fn dispatch(&self, settlement: Settlement, event: WebhookEvent) {
+        let dispatcher = self.clone();
+        let guard = self.shutdown.track();
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                if let Err(e) = dispatcher.fan_out(&settlement, event).await {
+                    error!("Webhook fan-out for settlement {} failed: {}", settlement.id, e);
+                }
+            }
+            .in_current_span(),
+        );
+    }
+    
+    async fn fan_out(&self, settlement: &Settlement, event: WebhookEvent) -> Result<(), sqlx::Error> {
+        let Some(debt) = self.db.get_debt(settlement.debt_id).await? else {
+            return Ok(());
+        };
+        
+        let webhooks = self
+            .db
+            .get_subscribed_webhooks(debt.creditor_id, settlement.status)
+            .await?;
+        
+        // Subscribed webhooks are always delivered to; the contact on file
+        // says whether the creditor expects to hear some other way as well.
+        match self.db.get_creditor_contact(debt.creditor_id).await? {
+            Some(contact) if contact.preferred_channel != ContactChannel::Webhook => {
+                let address = match contact.preferred_channel {
+                    ContactChannel::Email => contact.email,
+                    _ => contact.mailing_address,
+                };
+                info!(
+                    "Settlement {} is now {:?}; creditor {} prefers {:?} at {}",
+                    settlement.id,
+                    settlement.status,
+                    debt.creditor_id,
+                    contact.preferred_channel,
+                    address.unwrap_or_default()
+                );
+            }
+            contact if webhooks.is_empty() => {
+                warn!(
+                    "Settlement {} is now {:?} but creditor {} cannot be notified: {}",
+                    settlement.id,
+                    settlement.status,
+                    debt.creditor_id,
+                    if contact.is_some() { "no webhook is subscribed" } else { "no contact is on file" }
+                );
+            }
+            _ => {}
+        }
+        
+        for webhook in webhooks {
+            let delivery = self
+                .db
+                .insert_webhook_delivery(&WebhookDelivery {
+                    id: Uuid::new_v4(),
+                    webhook_id: webhook.id,
+                    settlement_id: settlement.id,
+                    settlement_status: settlement.status,
+                    status: DeliveryStatus::Pending,
+                    attempts: 0,
+                    last_error: None,
+                    created_at: Utc::now(),
+                    delivered_at: None,
+                })
+                .await?;
+            
+            let dispatcher = self.clone();
+            let settlement = settlement.clone();
+            let guard = self.shutdown.track();
+            tokio::spawn(
+                async move {
+                    let _guard = guard;
+                    if let Err(e) = dispatcher.deliver(&webhook, &delivery, &settlement, event).await {
+                        error!("Recording webhook delivery {} failed: {}", delivery.id, e);
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+        
+        Ok(())
+    }
+    
+    /// Once shutdown begins the remaining attempts are made without backoff;
+    /// past the drain deadline the delivery stops and stays `Pending`.
+    #[instrument(skip_all, fields(delivery_id = %delivery.id, webhook_id = %webhook.id))]
+    async fn deliver(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+        settlement: &Settlement,
+        event: WebhookEvent,
+    ) -> Result<(), sqlx::Error> {
+        let payload = event.payload(delivery, settlement);
+        let body = serde_json::to_vec(&payload).expect("webhook payload serializes");
+        
+        let mut backoff = self.initial_backoff;
+        for attempt in 1..=self.max_attempts {
+            let outcome = tokio::select! {
+                outcome = self.post(&webhook.url, &webhook.secret, delivery.id, &body) => outcome,
+                _ = self.shutdown.abandoned() => {
+                    warn!(
+                        "Shutting down before webhook delivery {} to {} succeeded; leaving it pending",
+                        delivery.id, webhook.url
+                    );
+                    return Ok(());
+                }
+            };
+            
+            match outcome {
+                Ok(_) => {
+                    info!(
+                        "Webhook delivery {} to {} succeeded on attempt {}",
+                        delivery.id, webhook.url, attempt
+                    );
+                    return self
+                        .db
+                        .record_webhook_attempt(delivery.id, DeliveryStatus::Delivered, attempt as i32, None)
+                        .await;
+                }
+                Err(e) => {
+                    warn!(
+                        "Webhook delivery {} to {} failed on attempt {}/{}: {}",
+                        delivery.id, webhook.url, attempt, self.max_attempts, e
+                    );
+                    if attempt == self.max_attempts {
+                        error!(
+                            "Webhook delivery {} to {} is out of attempts; dead-lettering it",
+                            delivery.id, webhook.url
+                        );
+                        return self
+                            .db
+                            .dead_letter_webhook_delivery(&WebhookDeadLetter {
+                                id: Uuid::new_v4(),
+                                delivery_id: delivery.id,
+                                webhook_id: webhook.id,
+                                settlement_id: settlement.id,
+                                url: webhook.url.clone(),
+                                payload,
+                                attempts: attempt as i32,
+                                last_error: e.to_string(),
+                                created_at: Utc::now(),
+                                redelivered_at: None,
+                            })
+                            .await;
+                    }
+                    self.db
+                        .record_webhook_attempt(delivery.id, DeliveryStatus::Pending, attempt as i32, Some(&e.to_string()))
+                        .await?;
+                    
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = self.shutdown.draining() => {}
+                    }
+                    backoff = backoff.saturating_mul(2);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Posts `letter`'s payload to its URL once more, signed with the
+    /// webhook's secret and under the original delivery id, so a receiver
+    /// that got it after all can tell. `None` if the letter was already
+    /// redelivered.
+    #[instrument(skip_all, fields(dead_letter_id = %letter.id, webhook_id = %webhook.id))]
+    pub async fn redeliver(
+        &self,
+        webhook: &Webhook,
+        letter: &WebhookDeadLetter,
+    ) -> Result<Option<WebhookDeadLetter>, sqlx::Error> {
+        let body = serde_json::to_vec(&letter.payload).expect("webhook payload serializes");
+        let error = match self.post(&letter.url, &webhook.secret, letter.delivery_id, &body).await {
+            Ok(_) => {
+                info!("Redelivered dead-lettered webhook delivery {} to {}", letter.delivery_id, letter.url);
+                None
+            }
+            Err(e) => {
+                warn!("Redelivering webhook delivery {} to {} failed: {}", letter.delivery_id, letter.url, e);
+                Some(e.to_string())
+            }
+        };
+        self.db.record_dead_letter_redelivery(letter.id, error.as_deref()).await
+    }
+    
+    async fn post(&self, url: &str, secret: &str, delivery_id: Uuid, body: &[u8]) -> reqwest::Result<reqwest::Response> {
+        self.http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, body)))
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(body.to_vec())
+            .send()
+            .await?
+            .error_for_status()
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    hex::encode(buf)
+}
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::blockchain::BlockchainClient;
+use crate::database::{Database, PoolConfig};
+
+/// Pause after the first failed attempt; it doubles up to [`MAX_BACKOFF`].
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// How long startup waits for the service's dependencies before binding,
+/// and which of them it can't start without.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupConfig {
+    pub deadline: Duration,
+    /// Whether the Cardano node must answer too; the database always must.
+    pub require_cardano: bool,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(60),
+            require_cardano: true,
+        }
+    }
+}
+
+/// Connects to the database and pings the Cardano node, retrying each with
+/// backoff until it answers or the deadline passes. Without the database,
+/// or without the node when it is required, this fails; a node that isn't
+/// required is only warned about, and the circuit breaker takes it from there.
+pub async fn await_dependencies(
+    config: StartupConfig,
+    database_url: &str,
+    pool: PoolConfig,
+    cardano: &dyn BlockchainClient,
+) -> anyhow::Result<Database> {
+    let deadline = Instant::now() + config.deadline;
+    let (db, node) = tokio::join!(
+        retry_until("database", deadline, || Database::connect_with(database_url, pool)),
+        retry_until("cardano_node", deadline, || cardano.tip_height()),
+    );
+    
+    match node {
+        Ok(height) => info!("Cardano node is up at block {}", height),
+        Err(e) if config.require_cardano => return Err(e),
+        Err(e) => warn!("Starting without the Cardano node: {}", e),
+    }
+    db
+}
+
+/// Calls `attempt` until it succeeds, waiting longer after each failure,
+/// and gives up with the last error once `deadline` passes. An attempt
+/// still running at the deadline is abandoned.
+async fn retry_until<T, E, F, Fut>(name: &str, deadline: Instant, mut attempt: F) -> anyhow::Result<T>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match tokio::time::timeout_at(deadline, attempt()).await {
+            Ok(Ok(value)) => {
+                if attempts > 1 {
+                    info!("{} is up after {} attempts", name, attempts);
+                }
+                return Ok(value);
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => "no answer".to_string(),
+        };
+        
+        let now = Instant::now();
+        if now >= deadline {
+            anyhow::bail!("{} did not come up after {} attempts: {}", name, attempts, error);
+        }
+        let pause = backoff.min(deadline - now);
+        info!("Waiting for {} (attempt {}: {}); retrying in {:?}", name, attempts, error, pause);
+        tokio::time::sleep(pause).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    
+    use super::*;
+    
+    #[tokio::test]
+    async fn retries_until_the_dependency_answers() {
+        let calls = AtomicU32::new(0);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        
+        let answer = retry_until("flaky", deadline, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err("connection refused"),
+                _ => Ok(42),
+            }
+        })
+        .await
+        .unwrap();
+        
+        assert_eq!(answer, 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+    
+    #[tokio::test]
+    async fn gives_up_with_the_last_error_at_the_deadline() {
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(600);
+        
+        let error = retry_until("down", deadline, || async { Err::<(), _>("connection refused") })
+            .await
+            .unwrap_err();
+        
+        assert!(error.to_string().starts_with("down did not come up after"), "{}", error);
+        assert!(error.to_string().ends_with("connection refused"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}